@@ -21,7 +21,7 @@ public class MidenWallet {
     
     // MARK: - Properties
     
-    private var handle: OpaquePointer?
+    private var handle: MidenHandle?
     private let keystorePath: String
     private let storePath: String
 
@@ -52,12 +52,16 @@ public class MidenWallet {
     /// - Parameters:
     ///   - keystorePath: Keystore storage directory path (optional, defaults to Documents/miden_keystore)
     ///   - storePath: SQLite database file path (optional, defaults to Documents/miden_store.sqlite3)
-    ///   - rpcEndpoint: RPC endpoint URL (optional, nil uses testnet)
+    ///   - rpcEndpoint: RPC endpoint URL or network alias (`testnet`, `devnet`, `localhost`,
+    ///     `localhost:<port>`) (optional, nil uses testnet)
+    ///   - tenantId: Profile to open (optional); tenants share the store, each with its own
+    ///     accounts, notes and keystore
     /// - Throws: If initialization fails
     public init(
         keystorePath: String? = nil,
         storePath: String? = nil,
-        rpcEndpoint: String? = nil
+        rpcEndpoint: String? = nil,
+        tenantId: String? = nil
     ) throws {
         // Get Documents directory
         let documentsPath = FileManager.default.urls(
@@ -82,31 +86,84 @@ public class MidenWallet {
             attributes: nil
         )
         
-        // Create client
-        var handlePtr: OpaquePointer?
-        let result = self.keystorePath.withCString { ks in
-            self.storePath.withCString { store in
-                if let endpoint = rpcEndpoint {
-                    return endpoint.withCString { ep in
-                        wc_miden_create(ks, store, ep, &handlePtr)
+        // Create client (a tenant's through the config, see `MidenClientConfig.tenantId`)
+        var handleValue: MidenHandle = 0
+        let result: Int32
+        if let tenantId = tenantId {
+            let config = MidenClientConfig(
+                keystorePath: self.keystorePath,
+                storePath: self.storePath,
+                rpcEndpoint: rpcEndpoint,
+                tenantId: tenantId
+            )
+            guard let json = try? JSONEncoder().encode(config) else {
+                throw MidenError.invalidJSON
+            }
+            result = String(decoding: json, as: UTF8.self).withCString { configPtr in
+                wc_miden_create_with_config(configPtr, &handleValue)
+            }
+        } else {
+            result = self.keystorePath.withCString { ks in
+                self.storePath.withCString { store in
+                    if let endpoint = rpcEndpoint {
+                        return endpoint.withCString { ep in
+                            wc_miden_create(ks, store, ep, &handleValue)
+                        }
+                    } else {
+                        return wc_miden_create(ks, store, nil, &handleValue)
                     }
-                } else {
-                    return wc_miden_create(ks, store, nil, &handlePtr)
                 }
             }
         }
         
-        guard result == 0, let h = handlePtr else {
+        guard result == 0, handleValue != 0 else {
+            throw MidenError.initializationFailed(code: result)
+        }
+        
+        self.handle = handleValue
+    }
+    
+    /// Initialize Miden Wallet with explicit client settings
+    ///
+    /// - Parameter config: Paths, node and RPC/runtime settings
+    /// - Throws: If the config is invalid or initialization fails
+    public init(config: MidenClientConfig) throws {
+        self.keystorePath = config.keystorePath
+        self.storePath = config.storePath
+        
+        if config.inMemory != true {
+            try? FileManager.default.createDirectory(
+                at: URL(fileURLWithPath: config.keystorePath),
+                withIntermediateDirectories: true,
+                attributes: nil
+            )
+        }
+        
+        guard let json = try? JSONEncoder().encode(config) else {
+            throw MidenError.invalidJSON
+        }
+        
+        var handleValue: MidenHandle = 0
+        let result = String(decoding: json, as: UTF8.self).withCString { configPtr in
+            wc_miden_create_with_config(configPtr, &handleValue)
+        }
+        
+        guard result == 0, handleValue != 0 else {
             throw MidenError.initializationFailed(code: result)
         }
         
-        self.handle = h
+        self.handle = handleValue
     }
     
     deinit {
-        // wc_miden_destroy takes a pointer to handle and sets it to NULL
-        // This prevents double-free if deinit is called multiple times
-        wc_miden_destroy(&handle)
+        // wc_miden_destroy_async takes a pointer to the handle and sets it to 0; it never
+        // blocks, so releasing the wallet on the main thread cannot stall on a running sync
+        // Destroying an already destroyed handle is a no-op
+        guard var h = handle else {
+            return
+        }
+        wc_miden_destroy_async(&h, nil, nil)
+        handle = nil
     }
     
     // MARK: - Public Methods
@@ -132,10 +189,17 @@ public class MidenWallet {
     
     /// Create a new wallet account
     ///
-    /// - Parameter seed: 32-byte seed (optional, nil auto-generates)
+    /// - Parameters:
+    ///   - seed: 32-byte seed (optional, nil auto-generates)
+    ///   - keyBackend: Where the account key is kept (`WcKeyBackend_Filesystem` or
+    ///     `WcKeyBackend_Memory`; memory keys are lost when the wallet is closed)
+    ///   - storageMode: `WcStorageMode_Public` or `WcStorageMode_Private` (only the state
+    ///     commitment goes on chain; the state lives in this store, so back it up)
     /// - Returns: Account ID (hex string)
-    /// - Throws: If creation fails
-    public func createWallet(seed: [UInt8]? = nil) throws -> String {
+    /// - Throws: `MidenError.accountAlreadyExists` if the seed's account already exists,
+    ///   `MidenError.notSynced` before the first sync with `requireSyncForCreate`, or if
+    ///   creation fails
+    public func createWallet(seed: [UInt8]? = nil, keyBackend: WcKeyBackend = WcKeyBackend_Filesystem, storageMode: WcStorageMode = WcStorageMode_Public) throws -> String {
         guard let h = handle else {
             throw MidenError.invalidHandle
         }
@@ -159,16 +223,18 @@ public class MidenWallet {
         var accountIdBuffer = [UInt8](repeating: 0, count: 64)
         var accountIdLen: Int = 64
         
-        let result = wc_miden_create_wallet(
+        let result = wc_miden_create_wallet_with_key_backend(
             h,
             seedPtr,
             UInt(seedLen),
+            storageMode.rawValue,
+            keyBackend.rawValue,
             &accountIdBuffer,
             &accountIdLen
         )
         
         guard result == 0 else {
-            throw MidenError.createWalletFailed(code: result)
+            throw MidenError.createWallet(code: result)
         }
         
         guard let accountIdString = String(
@@ -181,257 +247,2532 @@ public class MidenWallet {
         return accountIdString
     }
     
-    /// Get all accounts list
+    /// Create a wallet account whose key is held by a registered external signer
     ///
-    /// - Returns: Array of account IDs
-    /// - Throws: If retrieval fails
-    public func getAccounts() throws -> [String] {
+    /// The account is bound to `schemeId` (see `MidenWallet.registerSigner`): every
+    /// signature it needs, for transactions and `signMessage` alike, is requested from the
+    /// signer. Register the signer again after each app launch, before the account signs.
+    /// Do NOT call from the main thread.
+    ///
+    /// - Parameters:
+    ///   - schemeId: Scheme the signer was registered under
+    ///   - keyIndex: Index of the signer's key to use
+    ///   - seed: 32-byte account seed (optional, nil auto-generates)
+    ///   - storageMode: `WcStorageMode_Public` or `WcStorageMode_Private`
+    /// - Returns: Account ID (hex string)
+    /// - Throws: If the scheme is not registered, the signer fails or creation fails
+    public func createWalletWithSigner(schemeId: UInt32, keyIndex: UInt32 = 0, seed: [UInt8]? = nil, storageMode: WcStorageMode = WcStorageMode_Public) throws -> String {
         guard let h = handle else {
             throw MidenError.invalidHandle
         }
+        if let seed = seed, seed.count != 32 {
+            throw MidenError.invalidSeedLength
+        }
         
-        var jsonBuffer = [UInt8](repeating: 0, count: 4096)
-        var jsonLen: Int = 4096
+        var accountIdBuffer = [UInt8](repeating: 0, count: 64)
+        var accountIdLen: Int = 64
         
-        let result = wc_miden_get_accounts(h, &jsonBuffer, &jsonLen)
+        let result = (seed ?? []).withUnsafeBytes { seedBytes in
+            wc_miden_create_wallet_with_signer(
+                h,
+                seed == nil ? nil : seedBytes.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                UInt(seed?.count ?? 0),
+                storageMode.rawValue,
+                schemeId,
+                keyIndex,
+                &accountIdBuffer,
+                &accountIdLen
+            )
+        }
         
         guard result == 0 else {
-            throw MidenError.getAccountsFailed(code: result)
+            throw MidenError.createWallet(code: result)
         }
         
-        guard let jsonString = String(
-            bytes: jsonBuffer.prefix(jsonLen),
-            encoding: .utf8
-        ) else {
-            throw MidenError.invalidJSON
+        guard let accountIdString = String(bytes: accountIdBuffer.prefix(accountIdLen), encoding: .utf8) else {
+            throw MidenError.invalidAccountId
+        }
+        return accountIdString
+    }
+    
+    /// Create a wallet account whose auth key can be rotated with `rotateAuthKey`
+    ///
+    /// The account has updatable code, so its ID differs from the wallet `createWallet`
+    /// makes for the same seed. Do NOT call from the main thread.
+    ///
+    /// - Parameters:
+    ///   - seed: 32-byte seed (optional, nil auto-generates)
+    ///   - keyBackend: `WcKeyBackend_Filesystem` or `WcKeyBackend_Memory`
+    ///   - storageMode: `WcStorageMode_Public` or `WcStorageMode_Private`
+    /// - Returns: Account ID (hex string)
+    /// - Throws: `MidenError.accountAlreadyExists`, `MidenError.notSynced`, or if creation
+    ///   fails
+    public func createRotatableWallet(seed: [UInt8]? = nil, keyBackend: WcKeyBackend = WcKeyBackend_Filesystem, storageMode: WcStorageMode = WcStorageMode_Public) throws -> String {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        if let seed = seed, seed.count != 32 {
+            throw MidenError.invalidSeedLength
         }
         
-        guard let data = jsonString.data(using: .utf8) else {
-            throw MidenError.invalidJSON
+        var accountIdBuffer = [UInt8](repeating: 0, count: 64)
+        var accountIdLen: Int = 64
+        
+        let result = (seed ?? []).withUnsafeBytes { seedBytes in
+            wc_miden_create_rotatable_wallet(
+                h,
+                seed == nil ? nil : seedBytes.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                UInt(seed?.count ?? 0),
+                storageMode.rawValue,
+                keyBackend.rawValue,
+                &accountIdBuffer,
+                &accountIdLen
+            )
         }
         
-        do {
-            let accountIds = try JSONDecoder().decode([String].self, from: data)
-            return accountIds
-        } catch {
-            throw MidenError.jsonDecodeFailed(error: error)
+        guard result == 0 else {
+            throw MidenError.createWallet(code: result)
+        }
+        
+        guard let accountIdString = String(bytes: accountIdBuffer.prefix(accountIdLen), encoding: .utf8) else {
+            throw MidenError.invalidAccountId
         }
+        return accountIdString
     }
     
-    /// Get account balance
+    /// Create (or restore) the wallet at an index of a master seed
     ///
-    /// - Parameter accountId: Account ID (hex string)
-    /// - Returns: Account balance information
-    /// - Throws: If retrieval fails
-    public func getBalance(accountId: String) throws -> AccountBalance {
+    /// The same master seed and index always give the same wallet, so wallets can be
+    /// recovered by walking the indices from 0: a public wallet the node already knows is
+    /// restored into this store (`restored` is true), and the first index that creates a
+    /// new wallet ends the walk. Private wallets cannot be restored from the master seed;
+    /// restore their store from a backup. Do NOT call from the main thread.
+    ///
+    /// - Parameters:
+    ///   - masterSeed: Master seed (16-64 bytes, e.g. the seed of a mnemonic)
+    ///   - index: Wallet index
+    ///   - storageMode: `WcStorageMode_Public` or `WcStorageMode_Private`
+    /// - Returns: Account ID and whether the wallet was restored
+    /// - Throws: `MidenError.accountAlreadyExists` if the wallet is already in the store,
+    ///   `MidenError.notSynced` before the first sync with `requireSyncForCreate`, or if
+    ///   creation fails
+    public func createWalletDerived(masterSeed: [UInt8], index: UInt32, storageMode: WcStorageMode = WcStorageMode_Public) throws -> DerivedWallet {
         guard let h = handle else {
             throw MidenError.invalidHandle
         }
         
-        var jsonBuffer = [UInt8](repeating: 0, count: 8192)
-        var jsonLen: Int = 8192
-        
-        let result = accountId.withCString { accountIdPtr in
-            wc_miden_get_balance(h, accountIdPtr, &jsonBuffer, &jsonLen)
+        guard (16...64).contains(masterSeed.count) else {
+            throw MidenError.invalidSeedLength
         }
         
-        switch result {
-        case 0:
-            break
-        case -3:
-            throw MidenError.invalidAccountId
-        case -4:
-            throw MidenError.accountNotFound(accountId: accountId)
-        default:
-            throw MidenError.getBalanceFailed(code: result)
-        }
+        var jsonBuffer = [UInt8](repeating: 0, count: 256)
+        var jsonLen: Int = 256
         
-        guard let jsonString = String(
-            bytes: jsonBuffer.prefix(jsonLen),
-            encoding: .utf8
-        ) else {
-            throw MidenError.invalidJSON
+        let result = masterSeed.withUnsafeBufferPointer { seed in
+            wc_miden_create_wallet_derived(
+                h,
+                seed.baseAddress,
+                UInt(seed.count),
+                index,
+                storageMode.rawValue,
+                &jsonBuffer,
+                &jsonLen
+            )
         }
         
-        guard let data = jsonString.data(using: .utf8) else {
-            throw MidenError.invalidJSON
+        guard result == 0 else {
+            throw MidenError.createWallet(code: result)
         }
         
         do {
-            let balance = try JSONDecoder().decode(AccountBalance.self, from: data)
-            return balance
+            return try JSONDecoder().decode(DerivedWallet.self, from: Data(jsonBuffer.prefix(jsonLen)))
         } catch {
             throw MidenError.jsonDecodeFailed(error: error)
         }
     }
     
-    /// Test network connection
+    #if !MIDEN_NO_FAUCETS
+    /// Create a fungible faucet account for issuing a token
     ///
-    /// - Returns: Whether connection succeeded
-    /// - Throws: If test fails
-    public func testConnection() throws -> Bool {
+    /// - Parameters:
+    ///   - symbol: Token symbol (1-6 uppercase letters)
+    ///   - decimals: Token decimals (at most 12)
+    ///   - maxSupply: Maximum supply in base units
+    ///   - seed: Optional 32-byte seed, random if nil
+    /// - Returns: Faucet account ID
+    /// - Throws: If creation fails
+    public func createFaucet(symbol: String, decimals: UInt8, maxSupply: UInt64, seed: [UInt8]? = nil) throws -> String {
         guard let h = handle else {
             throw MidenError.invalidHandle
         }
         
-        let result = wc_miden_test_connection(h)
-        
-        guard result == 0 else {
-            throw MidenError.connectionTestFailed(code: result)
+        if let seed = seed, seed.count != 32 {
+            throw MidenError.invalidSeedLength
         }
         
-        return true
-    }
-    
-    /// Convert account ID bytes to hex string
-    ///
-    /// - Parameter accountIdBytes: Account ID byte array
-    /// - Returns: Hex string
-    /// - Throws: If conversion fails
-    public static func accountIdToHex(_ accountIdBytes: [UInt8]) throws -> String {
-        var hexBuffer = [UInt8](repeating: 0, count: 128)
-        var hexLen: Int = 128
+        var accountIdBuffer = [UInt8](repeating: 0, count: 64)
+        var accountIdLen: Int = 64
         
-        let result = accountIdBytes.withUnsafeBytes { bytes in
-            guard let baseAddress = bytes.baseAddress?.assumingMemoryBound(to: UInt8.self) else {
-                return -1
+        let result = symbol.withCString { symbolPtr in
+            if let seed = seed {
+                return seed.withUnsafeBytes { seedBytes in
+                    wc_miden_create_faucet(
+                        h,
+                        seedBytes.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        UInt(seed.count),
+                        symbolPtr,
+                        decimals,
+                        maxSupply,
+                        &accountIdBuffer,
+                        &accountIdLen
+                    )
+                }
             }
-            return Int(wc_miden_account_id_to_hex(
-                baseAddress,
-                UInt(accountIdBytes.count),
-                &hexBuffer,
-                &hexLen
-            ))
+            return wc_miden_create_faucet(h, nil, 0, symbolPtr, decimals, maxSupply, &accountIdBuffer, &accountIdLen)
         }
         
         guard result == 0 else {
-            throw MidenError.hexConversionFailed(code: Int32(result))
+            throw MidenError.createFaucetFailed(code: result)
         }
         
-        guard let hexString = String(
-            bytes: hexBuffer.prefix(hexLen),
+        guard let accountIdString = String(
+            bytes: accountIdBuffer.prefix(accountIdLen),
             encoding: .utf8
         ) else {
-            throw MidenError.invalidHexString
+            throw MidenError.invalidAccountId
         }
         
-        return hexString
+        return accountIdString
     }
+    #endif
     
-    /// Get consumable Input Notes
+    /// Get all accounts list
     ///
-    /// - Parameter accountId: Account ID (optional, nil gets notes for all accounts)
-    /// - Returns: List of consumable notes
+    /// - Returns: Array of account IDs
     /// - Throws: If retrieval fails
-    public func getInputNotes(accountId: String? = nil) throws -> InputNotesResult {
+    public func getAccounts() throws -> [String] {
         guard let h = handle else {
             throw MidenError.invalidHandle
         }
         
-        var jsonBuffer = [UInt8](repeating: 0, count: 16384)
-        var jsonLen: Int = 16384
+        var dataPtr: UnsafeMutablePointer<UInt8>?
+        var dataLen: Int = 0
         
-        let result: Int32
-        if let accountId = accountId {
-            result = accountId.withCString { accountIdPtr in
-                wc_miden_get_input_notes(h, accountIdPtr, &jsonBuffer, &jsonLen)
-            }
-        } else {
-            result = wc_miden_get_input_notes(h, nil, &jsonBuffer, &jsonLen)
+        let result = wc_miden_get_accounts_alloc(h, &dataPtr, &dataLen)
+        
+        guard result == 0, let dataPtr = dataPtr else {
+            throw MidenError.getAccountsFailed(code: result)
         }
         
-        switch result {
-        case 0:
-            break
-        case -3:
-            throw MidenError.invalidAccountId
-        default:
-            throw MidenError.getInputNotesFailed(code: result)
+        // Copy out, then free the Rust-allocated buffer
+        let data = Data(bytes: dataPtr, count: dataLen)
+        wc_bytes_free(dataPtr, dataLen)
+        
+        do {
+            let accountIds = try JSONDecoder().decode([String].self, from: data)
+            return accountIds
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Get one page of the account list
+    ///
+    /// - Parameters:
+    ///   - offset: Index of the first account
+    ///   - limit: Maximum number of accounts (> 0)
+    /// - Returns: Account IDs of the page with the total count
+    /// - Throws: If retrieval fails
+    public func getAccounts(offset: Int, limit: Int) throws -> AccountsPage {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
         }
         
-        guard let jsonString = String(
-            bytes: jsonBuffer.prefix(jsonLen),
-            encoding: .utf8
-        ) else {
-            throw MidenError.invalidJSON
+        var jsonBuffer = [UInt8](repeating: 0, count: 16384)
+        var jsonLen: Int = jsonBuffer.count
+        var result = wc_miden_get_accounts_page(h, UInt32(clamping: offset), UInt32(clamping: limit), &jsonBuffer, &jsonLen)
+        
+        if result == -7 {
+            jsonBuffer = [UInt8](repeating: 0, count: jsonLen)
+            result = wc_miden_get_accounts_page(h, UInt32(clamping: offset), UInt32(clamping: limit), &jsonBuffer, &jsonLen)
         }
         
-        guard let data = jsonString.data(using: .utf8) else {
-            throw MidenError.invalidJSON
+        guard result == 0 else {
+            throw MidenError.getAccountsFailed(code: result)
         }
         
         do {
-            let notesResult = try JSONDecoder().decode(InputNotesResult.self, from: data)
-            return notesResult
+            return try JSONDecoder().decode(AccountsPage.self, from: Data(jsonBuffer.prefix(jsonLen)))
         } catch {
             throw MidenError.jsonDecodeFailed(error: error)
         }
     }
     
-    /// Consume Notes
-    ///
-    /// Create and submit a transaction to consume specified notes.
+    /// Get the type, storage mode, status and key backend of an account
     ///
-    /// - Parameters:
-    ///   - accountId: Account ID to execute transaction
-    ///   - noteIds: Array of note IDs to consume
-    /// - Returns: Transaction ID
-    /// - Throws: If consumption fails
-    public func consumeNotes(accountId: String, noteIds: [String]) throws -> String {
+    /// - Parameter accountId: Account ID (hex string)
+    /// - Returns: Account details
+    /// - Throws: If the account is unknown or retrieval fails
+    public func getAccountDetails(accountId: String) throws -> AccountDetails {
         guard let h = handle else {
             throw MidenError.invalidHandle
         }
         
-        guard !noteIds.isEmpty else {
-            throw MidenError.emptyNoteIds
+        var jsonBuffer = [UInt8](repeating: 0, count: 512)
+        var jsonLen: Int = 512
+        
+        let result = accountId.withCString { accountIdPtr in
+            wc_miden_get_account_details(h, accountIdPtr, &jsonBuffer, &jsonLen)
         }
         
-        // Build JSON array
-        let noteIdsJson = "[" + noteIds.map { "\"\($0)\"" }.joined(separator: ",") + "]"
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        default:
+            throw MidenError.getAccountsFailed(code: result)
+        }
         
-        var txIdBuffer = [UInt8](repeating: 0, count: 128)
-        var txIdLen: Int = 128
+        do {
+            return try JSONDecoder().decode(AccountDetails.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Import a public account from the node and track it (watch-only: no key is imported)
+    ///
+    /// - Parameter accountId: Public account ID
+    /// - Returns: Details of the imported account
+    /// - Throws: If the ID is invalid or private, or the node does not know the account
+    public func importAccount(accountId: String) throws -> AccountDetails {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 512)
+        var jsonLen: Int = 512
         
         let result = accountId.withCString { accountIdPtr in
-            noteIdsJson.withCString { noteIdsPtr in
-                wc_miden_consume_notes(h, accountIdPtr, noteIdsPtr, &txIdBuffer, &txIdLen)
-            }
+            wc_miden_import_account_by_id(h, accountIdPtr, &jsonBuffer, &jsonLen)
         }
         
         switch result {
         case 0:
             break
-        case -3:
+        case -1, -3:
             throw MidenError.invalidAccountId
-        case -4:
-            throw MidenError.invalidNoteId
-        case -5:
-            throw MidenError.consumeNotesFailed(code: result, message: "Transaction creation failed")
-        case -6:
-            throw MidenError.consumeNotesFailed(code: result, message: "Transaction submission failed")
         default:
-            throw MidenError.consumeNotesFailed(code: result, message: nil)
+            throw MidenError.importAccountFailed(code: result)
         }
         
-        guard let txIdString = String(
-            bytes: txIdBuffer.prefix(txIdLen),
-            encoding: .utf8
-        ) else {
-            throw MidenError.invalidJSON
+        do {
+            return try JSONDecoder().decode(AccountDetails.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
         }
-        
-        return txIdString
     }
-}
-
-// MARK: - Error Types
-
+    
+    /// Import a public account from the node, with its key held by a registered signer
+    ///
+    /// Unlike `importAccount(accountId:)`, the account can sign: its auth key must be key
+    /// `keyIndex` of the signer registered under `schemeId`. Do NOT call from the main thread.
+    ///
+    /// - Parameters:
+    ///   - accountId: Public account ID
+    ///   - schemeId: Scheme the signer was registered under
+    ///   - keyIndex: Index of the signer's key the account authenticates with
+    /// - Returns: Details of the imported account
+    /// - Throws: If the ID is invalid or private, the scheme is not registered, the node
+    ///   does not know the account or its key does not match the signer's
+    public func importAccount(accountId: String, schemeId: UInt32, keyIndex: UInt32 = 0) throws -> AccountDetails {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 512)
+        var jsonLen: Int = 512
+        
+        let result = accountId.withCString { accountIdPtr in
+            wc_miden_import_account_with_signer(h, accountIdPtr, schemeId, keyIndex, &jsonBuffer, &jsonLen)
+        }
+        
+        guard result == 0 else {
+            throw MidenError.importAccountFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(AccountDetails.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Sync and refresh one account
+    ///
+    /// Runs one sync round but defers transaction work for other accounts (ephemeral
+    /// sweeps, stream payouts) to the next `sync()`, so opening one wallet stays fast.
+    ///
+    /// - Parameter accountId: Account ID
+    /// - Returns: Block number, nonce and consumable note count of the account
+    /// - Throws: If the account is unknown or the sync fails
+    public func syncAccount(accountId: String) throws -> AccountSyncResult {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 256)
+        var jsonLen: Int = 256
+        
+        let result = accountId.withCString { accountIdPtr in
+            wc_miden_sync_account(h, accountIdPtr, &jsonBuffer, &jsonLen)
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        case -5:
+            throw MidenError.accountNotFound(accountId: accountId)
+        default:
+            throw MidenError.syncFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(AccountSyncResult.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Compare an account's local state with the node's
+    ///
+    /// Reports a stale account state (`stale_account`) and notes the store still offers
+    /// although they were consumed elsewhere (`missing_consumption`).
+    ///
+    /// - Parameters:
+    ///   - accountId: Account ID
+    ///   - repair: Sync and audit again when discrepancies are found
+    /// - Returns: The audit report; `consistent` tells whether the account is in order
+    public func auditState(accountId: String, repair: Bool = false) throws -> StateAuditReport {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 16384)
+        var jsonLen: Int = jsonBuffer.count
+        var result = accountId.withCString { accountIdPtr in
+            wc_miden_audit_state(h, accountIdPtr, repair, &jsonBuffer, &jsonLen)
+        }
+        
+        // Many discrepancies: audit again (without repeating a repair) into a larger buffer
+        if result == -7 {
+            jsonBuffer = [UInt8](repeating: 0, count: jsonLen)
+            result = accountId.withCString { accountIdPtr in
+                wc_miden_audit_state(h, accountIdPtr, false, &jsonBuffer, &jsonLen)
+            }
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        case -5:
+            throw MidenError.accountNotFound(accountId: accountId)
+        default:
+            throw MidenError.stateAuditFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(StateAuditReport.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Archive or unarchive an account
+    ///
+    /// An archived account keeps its data and keys but is skipped by post-sync work,
+    /// left out of `getInputNotes()` without an account, and of the widget totals.
+    ///
+    /// - Parameters:
+    ///   - accountId: Account ID
+    ///   - archived: true to archive, false to unarchive
+    /// - Throws: If the account is unknown
+    public func setAccountArchived(accountId: String, archived: Bool) throws {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        let result = accountId.withCString { accountIdPtr in
+            wc_miden_set_account_archived(h, accountIdPtr, archived)
+        }
+        
+        switch result {
+        case 0:
+            return
+        case -3:
+            throw MidenError.invalidAccountId
+        case -5:
+            throw MidenError.accountNotFound(accountId: accountId)
+        default:
+            throw MidenError.getAccountsFailed(code: result)
+        }
+    }
+    
+    /// Export a sent note as a NoteFile to share out of band (AirDrop, QR code)
+    ///
+    /// - Parameters:
+    ///   - noteId: Output note ID
+    ///   - type: `WcNoteExportType_Id` (public notes), `WcNoteExportType_Full` (committed
+    ///     notes) or `WcNoteExportType_Partial`
+    /// - Returns: Serialized NoteFile, for `importNote(_:)` on the receiving side
+    /// - Throws: If the note is unknown or cannot be exported with that type
+    public func exportNote(noteId: String, type: WcNoteExportType = WcNoteExportType_Partial) throws -> Data {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        // First call reports the size of the file
+        var len: Int = 0
+        var result = noteId.withCString { noteIdPtr in
+            wc_miden_export_note(h, noteIdPtr, type.rawValue, nil, &len)
+        }
+        
+        var buffer = [UInt8](repeating: 0, count: len)
+        if result == 0 {
+            result = noteId.withCString { noteIdPtr in
+                wc_miden_export_note(h, noteIdPtr, type.rawValue, &buffer, &len)
+            }
+        }
+        
+        switch result {
+        case 0:
+            return Data(buffer.prefix(len))
+        default:
+            throw MidenError.noteExportFailed(code: result)
+        }
+    }
+    
+    /// Import a NoteFile received out of band as an input note
+    ///
+    /// - Parameter noteFile: Serialized NoteFile (e.g. from `exportNote(noteId:type:)`)
+    /// - Returns: ID of the imported note, for `consumeNotes`
+    /// - Throws: If the data is not a NoteFile or the note cannot be imported
+    public func importNote(_ noteFile: Data) throws -> String {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var buffer = [UInt8](repeating: 0, count: 128)
+        var len: Int = 128
+        
+        let bytes = [UInt8](noteFile)
+        let result = wc_miden_import_note(h, bytes, bytes.count, &buffer, &len)
+        guard result == 0 else {
+            throw MidenError.noteImportFailed(code: result)
+        }
+        
+        return String(decoding: buffer.prefix(len), as: UTF8.self)
+    }
+    
+    /// Estimate when a block is produced, e.g. to show "reclaimable in about 3 hours"
+    ///
+    /// Extrapolates from the newest block seen during sync with the network's average
+    /// block time. Requires at least one completed sync.
+    ///
+    /// - Parameter blockNum: Block number (past or future)
+    /// - Returns: The estimate; use `relativeDescription(locale:)` for display
+    public func estimateTime(forBlock blockNum: UInt32) throws -> BlockTimeEstimate {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 512)
+        var jsonLen: Int = 512
+        
+        let result = wc_miden_estimate_time_for_block(h, blockNum, &jsonBuffer, &jsonLen)
+        guard result == 0 else {
+            throw MidenError.blockTimeEstimateFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(BlockTimeEstimate.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Convert a token amount such as "12.5" into base units with the faucet's decimals
+    ///
+    /// - Parameters:
+    ///   - amount: Amount in token units (digits with an optional fraction, no sign)
+    ///   - faucetId: Faucet account ID
+    /// - Returns: Amount in base units
+    /// - Throws: If the amount has more fraction digits than the token or the faucet is unknown
+    public func parseAmount(_ amount: String, faucetId: String) throws -> UInt64 {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var baseUnits: UInt64 = 0
+        let result = faucetId.withCString { faucetIdPtr in
+            amount.withCString { amountPtr in
+                wc_miden_parse_faucet_amount(h, faucetIdPtr, amountPtr, &baseUnits)
+            }
+        }
+        
+        switch result {
+        case 0:
+            return baseUnits
+        case -3:
+            throw MidenError.invalidAccountId
+        default:
+            throw MidenError.amountConversionFailed(code: result)
+        }
+    }
+    
+    /// Convert base units into a token amount such as "12.5" with the faucet's decimals
+    ///
+    /// - Parameters:
+    ///   - amount: Amount in base units
+    ///   - faucetId: Faucet account ID
+    /// - Returns: Amount in token units, without grouping or trailing zeros
+    /// - Throws: If the faucet is unknown
+    public func formatAmount(_ amount: UInt64, faucetId: String) throws -> String {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var buffer = [UInt8](repeating: 0, count: 32)
+        var len: Int = 32
+        
+        let result = faucetId.withCString { faucetIdPtr in
+            wc_miden_format_faucet_amount(h, faucetIdPtr, amount, &buffer, &len)
+        }
+        
+        switch result {
+        case 0:
+            return String(decoding: buffer.prefix(len), as: UTF8.self)
+        case -3:
+            throw MidenError.invalidAccountId
+        default:
+            throw MidenError.amountConversionFailed(code: result)
+        }
+    }
+    
+    /// Token metadata of a fungible faucet
+    ///
+    /// Faucets this client does not track are fetched from the node (public faucets only).
+    ///
+    /// - Parameter faucetId: Faucet account ID
+    /// - Returns: Symbol, decimals and max supply
+    /// - Throws: If the ID is not a faucet ID or the faucet is unknown
+    public func faucetMetadata(faucetId: String) throws -> FaucetMetadata {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 512)
+        var jsonLen: Int = jsonBuffer.count
+        
+        let result = faucetId.withCString { faucetIdPtr in
+            wc_miden_get_faucet_metadata(h, faucetIdPtr, &jsonBuffer, &jsonLen)
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        default:
+            throw MidenError.faucetMetadataFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(FaucetMetadata.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Get account balance
+    ///
+    /// - Parameter accountId: Account ID (hex string)
+    /// - Returns: Account balance information
+    /// - Throws: If retrieval fails
+    public func getBalance(accountId: String) throws -> AccountBalance {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var dataPtr: UnsafeMutablePointer<UInt8>?
+        var dataLen: Int = 0
+        
+        let result = accountId.withCString { accountIdPtr in
+            wc_miden_get_balance_alloc(h, accountIdPtr, &dataPtr, &dataLen)
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        case -4:
+            throw MidenError.accountNotFound(accountId: accountId)
+        default:
+            throw MidenError.getBalanceFailed(code: result)
+        }
+        
+        guard let dataPtr = dataPtr else {
+            throw MidenError.invalidJSON
+        }
+        
+        // Copy out, then free the Rust-allocated buffer
+        let data = Data(bytes: dataPtr, count: dataLen)
+        wc_bytes_free(dataPtr, dataLen)
+        
+        do {
+            let balance = try JSONDecoder().decode(AccountBalance.self, from: data)
+            return balance
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Check that the node is on the network this wallet's store belongs to
+    ///
+    /// Run it after opening the wallet, before syncing. The first successful check pins the
+    /// node's genesis block for the store; later checks against another network fail.
+    /// Do NOT call from the main thread.
+    ///
+    /// - Returns: The node's network and genesis block
+    /// - Throws: `MidenError.networkMismatch` if the node is on another network than the
+    ///   store (e.g. a testnet store opened with the `devnet` alias)
+    public func checkNetwork() throws -> NetworkCheck {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 512)
+        var jsonLen: Int = jsonBuffer.count
+        
+        let result = wc_miden_check_network(h, &jsonBuffer, &jsonLen)
+        
+        switch result {
+        case 0:
+            break
+        case -104:
+            throw MidenError.networkMismatch(details: lastErrorMessage)
+        default:
+            throw MidenError.networkCheckFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(NetworkCheck.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Test network connection
+    ///
+    /// - Returns: Whether connection succeeded
+    /// - Throws: If test fails
+    public func testConnection() throws -> Bool {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        let result = wc_miden_test_connection(h)
+        
+        guard result == 0 else {
+            throw MidenError.connectionTestFailed(code: result)
+        }
+        
+        return true
+    }
+    
+    /// Get how far the local store is behind the node
+    ///
+    /// Returns immediately, even while a sync is running, so it is safe to call from the main thread.
+    ///
+    /// - Returns: Local height, node tip and the estimated time to catch up
+    /// - Throws: If the query fails
+    public func syncLag() throws -> SyncLag {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 512)
+        var jsonLen: Int = 512
+        
+        let result = wc_miden_get_sync_lag(h, &jsonBuffer, &jsonLen)
+        
+        guard result == 0 else {
+            throw MidenError.syncFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(SyncLag.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Get the health of the wallet's storage
+    ///
+    /// Returns immediately, so it is safe to call from the main thread. While `degraded`
+    /// (disk full or read-only store), reads keep working and calls that write to the store
+    /// fail with error code -107 until space is freed.
+    ///
+    /// - Returns: Whether the storage is degraded, and why
+    /// - Throws: If the query fails
+    public func healthCheck() throws -> StorageHealth {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 1024)
+        var jsonLen: Int = 1024
+        
+        let result = wc_miden_health_check(h, &jsonBuffer, &jsonLen)
+        
+        guard result == 0 else {
+            throw MidenError.healthCheckFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(StorageHealth.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Sync within a time budget, for `BGAppRefreshTask` handlers
+    ///
+    /// Running out of budget is not an error: completed sync rounds are kept and the result
+    /// reports whether more work remains. Do NOT call from the main thread.
+    ///
+    /// - Parameter maxDuration: Time budget in seconds (at most 30); leave a margin below
+    ///   the time the system grants the task
+    /// - Returns: Synced height and remaining work
+    /// - Throws: If the first sync round fails
+    public func backgroundSync(maxDuration: TimeInterval = 25) throws -> BackgroundSyncResult {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 512)
+        var jsonLen: Int = 512
+        
+        let result = wc_miden_background_sync(h, UInt32(maxDuration * 1000), &jsonBuffer, &jsonLen)
+        
+        guard result == 0 else {
+            throw MidenError.syncFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(BackgroundSyncResult.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Get the tracking status of an account in the local store
+    ///
+    /// - Parameter accountId: Account ID (hex string)
+    /// - Returns: Account status
+    /// - Throws: If the account is unknown or the lookup fails
+    public func accountStatus(accountId: String) throws -> WcAccountStatus {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var status = WcAccountStatus_New
+        let result = accountId.withCString { accountIdPtr in
+            wc_miden_get_account_status(h, accountIdPtr, &status)
+        }
+        
+        switch result {
+        case 0:
+            return status
+        case -5:
+            throw MidenError.accountNotFound(accountId: accountId)
+        default:
+            throw MidenError.getAccountsFailed(code: result)
+        }
+    }
+    
+    #if !MIDEN_NO_SCRIPTS
+    /// Resolve a registered name to an account ID
+    ///
+    /// Requires a registry configured with `wc_miden_set_name_registry`.
+    ///
+    /// - Parameter name: Name to resolve (case-insensitive)
+    /// - Returns: Account ID (hex string), or nil if the name is not registered
+    /// - Throws: If the lookup fails
+    public func resolveName(_ name: String) throws -> String? {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var idBuffer = [UInt8](repeating: 0, count: 128)
+        var idLen: Int = 128
+        
+        let result = name.withCString { namePtr in
+            wc_miden_resolve_name(h, namePtr, &idBuffer, &idLen)
+        }
+        
+        switch result {
+        case 0:
+            return String(decoding: idBuffer.prefix(idLen), as: UTF8.self)
+        case -5:
+            return nil
+        default:
+            throw MidenError.nameResolutionFailed(code: result)
+        }
+    }
+    #endif
+    
+    /// Convert account ID bytes to hex string
+    ///
+    /// - Parameter accountIdBytes: Account ID byte array
+    /// - Returns: Hex string
+    /// - Throws: If conversion fails
+    public static func accountIdToHex(_ accountIdBytes: [UInt8]) throws -> String {
+        var hexBuffer = [UInt8](repeating: 0, count: 128)
+        var hexLen: Int = 128
+        
+        let result = accountIdBytes.withUnsafeBytes { bytes in
+            guard let baseAddress = bytes.baseAddress?.assumingMemoryBound(to: UInt8.self) else {
+                return -1
+            }
+            return Int(wc_miden_account_id_to_hex(
+                baseAddress,
+                UInt(accountIdBytes.count),
+                &hexBuffer,
+                &hexLen
+            ))
+        }
+        
+        guard result == 0 else {
+            throw MidenError.hexConversionFailed(code: Int32(result))
+        }
+        
+        guard let hexString = String(
+            bytes: hexBuffer.prefix(hexLen),
+            encoding: .utf8
+        ) else {
+            throw MidenError.invalidHexString
+        }
+        
+        return hexString
+    }
+    
+    /// Get a short, localized message for an error code, suitable for alerts
+    ///
+    /// - Parameters:
+    ///   - code: Error code returned by the Rust library
+    ///   - locale: Locale to use (defaults to the current locale; falls back to English)
+    /// - Returns: User-presentable message
+    public static func userMessage(for code: Int32, locale: Locale = .current) -> String {
+        var buffer = [UInt8](repeating: 0, count: 512)
+        var len: Int = 512
+        
+        let result = locale.identifier.withCString { localePtr in
+            wc_error_user_message(code, localePtr, &buffer, &len)
+        }
+        
+        guard result == 0 else {
+            return "Error \(code)"
+        }
+        return String(decoding: buffer.prefix(len), as: UTF8.self)
+    }
+    
+    /// Query which optional features the linked framework build supports
+    ///
+    /// - Returns: Build version and capability flags
+    /// - Throws: If the query fails
+    public static func capabilities() throws -> BuildCapabilities {
+        var jsonBuffer = [UInt8](repeating: 0, count: 1024)
+        var jsonLen: Int = 1024
+        
+        let result = wc_miden_capabilities(&jsonBuffer, &jsonLen)
+        
+        guard result == 0 else {
+            throw MidenError.capabilitiesQueryFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(BuildCapabilities.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Report Rust-allocated buffers not yet released with `wc_bytes_free`
+    ///
+    /// Only tracks buffers in builds with the `memory-debug` Cargo feature (check
+    /// `enabled`); use it during development to find leaked callback results.
+    ///
+    /// - Returns: Outstanding buffers, oldest first, with the source location that produced them
+    /// - Throws: If the query fails
+    public static func memoryReport() throws -> MemoryReport {
+        var jsonBuffer = [UInt8](repeating: 0, count: 16384)
+        var jsonLen: Int = jsonBuffer.count
+        
+        var result = wc_memory_report(&jsonBuffer, &jsonLen)
+        
+        if result == -7 {
+            jsonBuffer = [UInt8](repeating: 0, count: jsonLen)
+            result = wc_memory_report(&jsonBuffer, &jsonLen)
+        }
+        
+        guard result == 0 else {
+            throw MidenError.memoryReportFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(MemoryReport.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Check an export file against its detached signature (`<path>.sig`)
+    ///
+    /// Needs no client; safe to call from any thread.
+    ///
+    /// - Parameters:
+    ///   - path: Signed file
+    ///   - pubKey: Expected public key commitment (hex), e.g. as registered with a server
+    /// - Returns: Whether the file is unchanged and signed by `pubKey`
+    /// - Throws: If the file or its signature file cannot be read
+    public static func verifyExport(path: String, pubKey: String) throws -> Bool {
+        let result = path.withCString { pathPtr in
+            pubKey.withCString { pubKeyPtr in
+                wc_verify_export(pathPtr, pubKeyPtr)
+            }
+        }
+        
+        switch result {
+        case 0:
+            return true
+        case -3:
+            return false
+        default:
+            throw MidenError.exportSigningFailed(code: result)
+        }
+    }
+    
+    /// Verify a message signature made by `signMessage` (by this or another wallet)
+    ///
+    /// Needs no client; safe to call from any thread.
+    ///
+    /// - Parameters:
+    ///   - pubKey: Signer's public key commitment (hex), e.g. from `getPublicKey`
+    ///   - message: Signed message (1 byte to 64 KB)
+    ///   - signature: Serialized signature (hex), e.g. `SignedMessage.signature`
+    /// - Returns: Whether the signature is valid for the message and key
+    /// - Throws: If the key or signature is malformed or the message length is invalid
+    public static func verifySignature(pubKey: String, message: Data, signature: String) throws -> Bool {
+        let result = pubKey.withCString { pubKeyPtr in
+            message.withUnsafeBytes { messageBytes in
+                signature.withCString { signaturePtr in
+                    wc_miden_verify_signature(
+                        pubKeyPtr,
+                        messageBytes.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        UInt(message.count),
+                        signaturePtr
+                    )
+                }
+            }
+        }
+        
+        switch result {
+        case 0:
+            return true
+        case -3:
+            return false
+        default:
+            throw MidenError.verifySignatureFailed(code: result)
+        }
+    }
+    
+    #if !MIDEN_NO_MNEMONIC
+    /// Generate a new English BIP-39 recovery phrase
+    ///
+    /// Needs no client; safe to call from any thread. Nothing is stored: keep the phrase in
+    /// the Keychain or show it to the user to write down.
+    ///
+    /// - Parameter wordCount: 12, 15, 18, 21 or 24
+    /// - Returns: Space-separated phrase
+    /// - Throws: If the word count is not supported
+    public static func generateMnemonic(wordCount: UInt32 = 24) throws -> String {
+        var phraseBuffer = [UInt8](repeating: 0, count: 256)
+        var phraseLen: Int = 256
+        
+        let result = wc_mnemonic_generate(wordCount, &phraseBuffer, &phraseLen)
+        guard result == 0 else {
+            throw MidenError.mnemonicFailed(code: result)
+        }
+        
+        guard let phrase = String(bytes: phraseBuffer.prefix(phraseLen), encoding: .utf8) else {
+            throw MidenError.mnemonicFailed(code: -1)
+        }
+        return phrase
+    }
+    
+    /// Derive the 64-byte master seed of a recovery phrase, for `createWalletDerived`
+    ///
+    /// Needs no client; safe to call from any thread.
+    ///
+    /// - Parameters:
+    ///   - phrase: English BIP-39 phrase
+    ///   - passphrase: Optional BIP-39 passphrase (a different passphrase gives different wallets)
+    /// - Returns: Master seed
+    /// - Throws: If the phrase has an unknown word or a wrong checksum
+    public static func mnemonicToSeed(_ phrase: String, passphrase: String? = nil) throws -> [UInt8] {
+        var seedBuffer = [UInt8](repeating: 0, count: 64)
+        var seedLen: Int = 64
+        
+        let result = phrase.withCString { phrasePtr in
+            if let passphrase = passphrase {
+                return passphrase.withCString { passphrasePtr in
+                    wc_mnemonic_to_seed(phrasePtr, passphrasePtr, &seedBuffer, &seedLen)
+                }
+            }
+            return wc_mnemonic_to_seed(phrasePtr, nil, &seedBuffer, &seedLen)
+        }
+        
+        guard result == 0 else {
+            throw MidenError.mnemonicFailed(code: result)
+        }
+        return Array(seedBuffer.prefix(seedLen))
+    }
+    #endif
+    
+    /// Register (or replace) an external signer under `schemeId`
+    ///
+    /// Accounts created with `createWalletWithSigner(schemeId:)` route their signing
+    /// requests to `signer` instead of the filesystem keystore. Registrations are
+    /// process-wide and not persisted: register at every launch before any wallet signs.
+    /// The signer is kept alive until it is unregistered.
+    ///
+    /// - Parameters:
+    ///   - signer: Key holder (Secure Enclave-protected Keychain item, hardware wallet)
+    ///   - schemeId: Scheme ID (> 0; 0 is the filesystem keystore)
+    /// - Throws: If `schemeId` is 0
+    public static func registerSigner(_ signer: MidenSigner, schemeId: UInt32) throws {
+        let box = SignerBox(signer: signer)
+        let callbacks = WcSignerCallbacks(
+            user_data: Unmanaged.passUnretained(box).toOpaque(),
+            get_public_key_commitment: { userData, keyIndex, out, outLen in
+                guard let userData = userData, let out = out, let outLen = outLen else {
+                    return -1
+                }
+                let box = Unmanaged<SignerBox>.fromOpaque(userData).takeUnretainedValue()
+                guard let commitment = try? box.signer.publicKeyCommitment(keyIndex: keyIndex),
+                      commitment.count <= Int(outLen.pointee) else {
+                    return -3
+                }
+                commitment.copyBytes(to: out, count: commitment.count)
+                outLen.pointee = UInt(commitment.count)
+                return 0
+            },
+            sign: { userData, pubKey, message, sigOut, sigOutLen in
+                guard let userData = userData, let pubKey = pubKey, let message = message,
+                      let sigOut = sigOut, let sigOutLen = sigOutLen else {
+                    return -1
+                }
+                let box = Unmanaged<SignerBox>.fromOpaque(userData).takeUnretainedValue()
+                guard let signature = try? box.signer.sign(
+                          pubKey: Data(bytes: pubKey, count: 32),
+                          message: Data(bytes: message, count: 32)
+                      ),
+                      signature.count <= Int(sigOutLen.pointee) else {
+                    return -3
+                }
+                signature.copyBytes(to: sigOut, count: signature.count)
+                sigOutLen.pointee = UInt(signature.count)
+                return 0
+            }
+        )
+        
+        SignerBox.lock.lock()
+        defer { SignerBox.lock.unlock() }
+        let result = wc_miden_register_signer(schemeId, callbacks)
+        guard result == 0 else {
+            throw MidenError.signerRegistrationFailed(code: result)
+        }
+        SignerBox.registered[schemeId] = box
+    }
+    
+    /// Remove an external signer registration
+    ///
+    /// Accounts bound to the scheme fail to sign until it is registered again. Do not
+    /// unregister while one of them may be signing.
+    ///
+    /// - Parameter schemeId: Scheme ID passed to `registerSigner`
+    public static func unregisterSigner(schemeId: UInt32) {
+        SignerBox.lock.lock()
+        defer { SignerBox.lock.unlock() }
+        _ = wc_miden_unregister_signer(schemeId)
+        SignerBox.registered[schemeId] = nil
+    }
+    
+    /// Convert base units into a token amount such as "12.5" with known decimals
+    ///
+    /// Needs no client; use it when the decimals are already known (e.g. from
+    /// `faucetMetadata(faucetId:)`).
+    ///
+    /// - Parameters:
+    ///   - amount: Amount in base units
+    ///   - decimals: Token decimals
+    ///   - fractionDigits: Maximum fraction digits, rounded half up (nil = exact)
+    /// - Returns: Amount in token units, without grouping or trailing zeros
+    public static func formatAmount(_ amount: UInt64, decimals: UInt8, fractionDigits: UInt8? = nil) -> String {
+        var buffer = [UInt8](repeating: 0, count: 64)
+        var len: Int = buffer.count
+        
+        let result = wc_miden_format_amount(amount, decimals, fractionDigits ?? decimals, &buffer, &len)
+        guard result == 0 else {
+            return String(amount)
+        }
+        return String(decoding: buffer.prefix(len), as: UTF8.self)
+    }
+    
+    /// Convert a token amount such as "12.5" into base units with known decimals
+    ///
+    /// Needs no client. Never rounds: amounts finer than one base unit are rejected.
+    ///
+    /// - Parameters:
+    ///   - amount: Amount in token units (digits with an optional fraction, no sign)
+    ///   - decimals: Token decimals
+    /// - Returns: Amount in base units
+    /// - Throws: If the amount is malformed, too precise or overflows
+    public static func parseAmount(_ amount: String, decimals: UInt8) throws -> UInt64 {
+        var baseUnits: UInt64 = 0
+        let result = amount.withCString { amountPtr in
+            wc_miden_parse_amount(amountPtr, decimals, &baseUnits)
+        }
+        
+        guard result == 0 else {
+            throw MidenError.amountConversionFailed(code: result)
+        }
+        return baseUnits
+    }
+    
+    /// Check user input as an account ID before using it
+    ///
+    /// Needs no client and does no I/O; cheap enough to run on every keystroke.
+    ///
+    /// - Parameter accountId: Account ID as entered
+    /// - Returns: `WcIdValidation_Valid`, or why the input is not an account ID (missing
+    ///   `0x`, wrong length, bad bech32 checksum, ...)
+    public static func validateAccountId(_ accountId: String) -> WcIdValidation {
+        var reason = WcIdValidation_InvalidCharacter
+        _ = accountId.withCString { accountIdPtr in
+            wc_miden_validate_account_id(accountIdPtr, &reason)
+        }
+        return reason
+    }
+    
+    /// Check user input as a note ID before using it
+    ///
+    /// Needs no client and does no I/O.
+    ///
+    /// - Parameter noteId: Note ID as entered
+    /// - Returns: `WcIdValidation_Valid`, or why the input is not a note ID
+    public static func validateNoteId(_ noteId: String) -> WcIdValidation {
+        var reason = WcIdValidation_InvalidCharacter
+        _ = noteId.withCString { noteIdPtr in
+            wc_miden_validate_note_id(noteIdPtr, &reason)
+        }
+        return reason
+    }
+    
+    /// Write amounts in all JSON output as base-unit decimal strings (or numbers)
+    ///
+    /// Process-wide and off by default. The `Codable` models of this file expect numbers:
+    /// only enable it for bridges (e.g. JavaScriptCore) that decode the JSON themselves.
+    public static func setAmountEncoding(_ encoding: WcAmountEncoding) {
+        _ = wc_miden_set_amount_encoding(encoding.rawValue)
+    }
+    
+    /// Emit all JSON in canonical form (sorted keys, no whitespace, fixed number format)
+    ///
+    /// Process-wide and off by default. Use it to hash or sign outputs, or to keep
+    /// snapshot tests stable.
+    public static func setCanonicalJSON(_ enabled: Bool) {
+        _ = wc_miden_set_canonical_json(enabled)
+    }
+    
+    /// Message of the last failed request of this client
+    ///
+    /// Carries the underlying miden-client error (e.g. why a transaction was rejected)
+    /// behind the integer code of a thrown `MidenError`. Nil if no request has failed yet.
+    public var lastErrorMessage: String? {
+        guard let h = handle else {
+            return nil
+        }
+        
+        var buffer = [UInt8](repeating: 0, count: 4096)
+        var length: Int = 4096
+        
+        guard wc_miden_last_error_message(h, &buffer, &length) == 0 else {
+            return nil
+        }
+        return String(bytes: buffer.prefix(length), encoding: .utf8)
+    }
+    
+    /// Get consumable Input Notes
+    ///
+    /// - Parameter accountId: Account ID (optional, nil gets notes for all accounts)
+    /// - Returns: List of consumable notes
+    /// - Throws: If retrieval fails
+    public func getInputNotes(accountId: String? = nil) throws -> InputNotesResult {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var dataPtr: UnsafeMutablePointer<UInt8>?
+        var dataLen: Int = 0
+        
+        let result: Int32
+        if let accountId = accountId {
+            result = accountId.withCString { accountIdPtr in
+                wc_miden_get_input_notes_alloc(h, accountIdPtr, &dataPtr, &dataLen)
+            }
+        } else {
+            result = wc_miden_get_input_notes_alloc(h, nil, &dataPtr, &dataLen)
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        default:
+            throw MidenError.getInputNotesFailed(code: result)
+        }
+        
+        guard let dataPtr = dataPtr else {
+            throw MidenError.invalidJSON
+        }
+        
+        // Copy out, then free the Rust-allocated buffer
+        let data = Data(bytes: dataPtr, count: dataLen)
+        wc_bytes_free(dataPtr, dataLen)
+        
+        do {
+            let notesResult = try JSONDecoder().decode(InputNotesResult.self, from: data)
+            return notesResult
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Get one page of the consumable Input Notes
+    ///
+    /// - Parameters:
+    ///   - accountId: Account ID (optional, nil gets notes for all accounts)
+    ///   - offset: Index of the first note
+    ///   - limit: Maximum number of notes (> 0)
+    /// - Returns: Notes of the page with the total count
+    /// - Throws: If retrieval fails
+    public func getInputNotes(accountId: String? = nil, offset: Int, limit: Int) throws -> InputNotesPage {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        func query(_ buffer: inout [UInt8], _ length: inout Int) -> Int32 {
+            let offset = UInt32(clamping: offset)
+            let limit = UInt32(clamping: limit)
+            if let accountId = accountId {
+                return accountId.withCString { accountIdPtr in
+                    wc_miden_get_input_notes_page(h, accountIdPtr, offset, limit, &buffer, &length)
+                }
+            }
+            return wc_miden_get_input_notes_page(h, nil, offset, limit, &buffer, &length)
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 16384)
+        var jsonLen: Int = jsonBuffer.count
+        var result = query(&jsonBuffer, &jsonLen)
+        
+        if result == -7 {
+            jsonBuffer = [UInt8](repeating: 0, count: jsonLen)
+            result = query(&jsonBuffer, &jsonLen)
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        default:
+            throw MidenError.getInputNotesFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(InputNotesPage.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Get Input Notes by state
+    ///
+    /// Unlike `getInputNotes(accountId:)`, can list notes that are not consumable yet
+    /// (e.g. an incoming payment whose block has not been synced). Each note's `state`
+    /// is set, except with `WcNoteFilter_Consumable`.
+    ///
+    /// - Parameters:
+    ///   - accountId: Only notes for this account (nil: all non-archived accounts)
+    ///   - filter: Store state to select
+    /// - Returns: List of notes
+    /// - Throws: If retrieval fails
+    public func getInputNotes(accountId: String? = nil, filter: WcNoteFilter) throws -> InputNotesResult {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        func query(_ buffer: inout [UInt8], _ length: inout Int) -> Int32 {
+            if let accountId = accountId {
+                return accountId.withCString { accountIdPtr in
+                    wc_miden_get_input_notes_filtered(h, accountIdPtr, filter.rawValue, &buffer, &length)
+                }
+            }
+            return wc_miden_get_input_notes_filtered(h, nil, filter.rawValue, &buffer, &length)
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 16384)
+        var jsonLen: Int = jsonBuffer.count
+        var result = query(&jsonBuffer, &jsonLen)
+        
+        if result == -7 {
+            jsonBuffer = [UInt8](repeating: 0, count: jsonLen)
+            result = query(&jsonBuffer, &jsonLen)
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        default:
+            throw MidenError.getInputNotesFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(InputNotesResult.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Get Output Notes
+    ///
+    /// Notes created by this wallet's transactions, with their state as of the last sync.
+    ///
+    /// - Parameter accountId: Only notes sent by this account (nil: all non-archived accounts)
+    /// - Returns: List of sent notes
+    /// - Throws: If retrieval fails
+    public func getOutputNotes(accountId: String? = nil) throws -> OutputNotesResult {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        func query(_ buffer: inout [UInt8], _ length: inout Int) -> Int32 {
+            if let accountId = accountId {
+                return accountId.withCString { accountIdPtr in
+                    wc_miden_get_output_notes(h, accountIdPtr, &buffer, &length)
+                }
+            }
+            return wc_miden_get_output_notes(h, nil, &buffer, &length)
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 16384)
+        var jsonLen: Int = jsonBuffer.count
+        var result = query(&jsonBuffer, &jsonLen)
+        
+        if result == -7 {
+            jsonBuffer = [UInt8](repeating: 0, count: jsonLen)
+            result = query(&jsonBuffer, &jsonLen)
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        default:
+            throw MidenError.getOutputNotesFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(OutputNotesResult.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Get transaction history
+    ///
+    /// Transactions in the local store, newest first. Sync first to see pending
+    /// transactions become committed.
+    ///
+    /// - Parameters:
+    ///   - accountId: Only this account's transactions (nil: all non-archived accounts)
+    ///   - status: Only transactions in this status
+    ///   - limit: Maximum number of transactions
+    ///   - offset: Number of (newest) transactions to skip, for paging
+    /// - Returns: Transaction records
+    /// - Throws: If the query fails
+    public func getTransactions(accountId: String? = nil, status: TransactionInfo.Status? = nil, limit: Int? = nil, offset: Int? = nil) throws -> [TransactionInfo] {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var filter: [String: Any] = [:]
+        filter["account_id"] = accountId
+        filter["status"] = status?.rawValue
+        filter["limit"] = limit
+        filter["offset"] = offset
+        let filterJson = String(data: try JSONSerialization.data(withJSONObject: filter), encoding: .utf8) ?? "{}"
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 16384)
+        var jsonLen: Int = jsonBuffer.count
+        var result = filterJson.withCString { filterPtr in
+            wc_miden_get_transactions(h, filterPtr, &jsonBuffer, &jsonLen)
+        }
+        
+        if result == -7 {
+            jsonBuffer = [UInt8](repeating: 0, count: jsonLen)
+            result = filterJson.withCString { filterPtr in
+                wc_miden_get_transactions(h, filterPtr, &jsonBuffer, &jsonLen)
+            }
+        }
+        
+        switch result {
+        case 0:
+            break
+        default:
+            throw MidenError.getTransactionsFailed(code: result)
+        }
+        
+        struct Response: Codable {
+            let transactions: [TransactionInfo]
+        }
+        do {
+            return try JSONDecoder().decode(Response.self, from: Data(jsonBuffer.prefix(jsonLen))).transactions
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Transaction Status
+    ///
+    /// Look up one transaction as of the last sync. Use `waitForTransaction(txId:timeout:)`
+    /// to sync until it is committed.
+    ///
+    /// - Parameter txId: Transaction ID (hex)
+    /// - Returns: Transaction record
+    /// - Throws: If the ID is invalid or the transaction is not in the store
+    public func transactionStatus(txId: String) throws -> TransactionInfo {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 1024)
+        var jsonLen: Int = jsonBuffer.count
+        var result = txId.withCString { txIdPtr in
+            wc_miden_get_transaction_status(h, txIdPtr, &jsonBuffer, &jsonLen)
+        }
+        
+        if result == -7 {
+            jsonBuffer = [UInt8](repeating: 0, count: jsonLen)
+            result = txId.withCString { txIdPtr in
+                wc_miden_get_transaction_status(h, txIdPtr, &jsonBuffer, &jsonLen)
+            }
+        }
+        
+        switch result {
+        case 0:
+            break
+        default:
+            throw MidenError.transactionStatusFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(TransactionInfo.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Open a snapshot of an account
+    ///
+    /// The returned object reads its fields without going through the worker queue, so its
+    /// accessors are cheap and safe on the main thread. Opening is blocking; the snapshot does
+    /// not follow later syncs (open it again to refresh).
+    ///
+    /// - Parameter accountId: Account ID (hex)
+    /// - Returns: Account object, released when it is deinitialized
+    /// - Throws: If the ID is invalid or the account is not in the store
+    public func openAccount(accountId: String) throws -> AccountObject {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var obj: WcObject = 0
+        let result = accountId.withCString { accountIdPtr in
+            wc_account_open(h, accountIdPtr, &obj)
+        }
+        
+        switch result {
+        case 0:
+            return AccountObject(obj)
+        case -3:
+            throw MidenError.invalidAccountId
+        case -5:
+            throw MidenError.accountNotFound(accountId: accountId)
+        default:
+            throw MidenError.objectOpenFailed(code: result)
+        }
+    }
+    
+    /// Open a snapshot of a note, received or sent
+    ///
+    /// Same rules as `openAccount(accountId:)`.
+    ///
+    /// - Parameter noteId: Note ID (hex)
+    /// - Returns: Note object, released when it is deinitialized
+    /// - Throws: If the ID is invalid or the note is not in the store
+    public func openNote(noteId: String) throws -> NoteObject {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var obj: WcObject = 0
+        let result = noteId.withCString { noteIdPtr in
+            wc_note_open(h, noteIdPtr, &obj)
+        }
+        
+        switch result {
+        case 0:
+            return NoteObject(obj)
+        default:
+            throw MidenError.objectOpenFailed(code: result)
+        }
+    }
+    
+    /// Prove control of an account to a server
+    ///
+    /// Signs the server's challenge with the account's key; send the returned statement
+    /// (JSON-encoded) back for verification. External signers may ask the user to confirm,
+    /// so do NOT call from the main thread.
+    ///
+    /// - Parameters:
+    ///   - accountId: Account ID (hex); its key must be held by this client
+    ///   - challenge: Fresh server nonce (16-1024 bytes)
+    /// - Returns: Signed ownership statement
+    /// - Throws: If the challenge length is invalid, the account is unknown or signing fails
+    public func proveOwnership(accountId: String, challenge: Data) throws -> OwnershipStatement {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 4096)
+        var jsonLen: Int = jsonBuffer.count
+        
+        func prove(_ buffer: inout [UInt8], _ length: inout Int) -> Int32 {
+            accountId.withCString { accountIdPtr in
+                challenge.withUnsafeBytes { challengeBytes in
+                    wc_miden_prove_ownership(
+                        h,
+                        accountIdPtr,
+                        challengeBytes.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        UInt(challenge.count),
+                        &buffer,
+                        &length
+                    )
+                }
+            }
+        }
+        
+        var result = prove(&jsonBuffer, &jsonLen)
+        if result == -7 {
+            jsonBuffer = [UInt8](repeating: 0, count: jsonLen)
+            result = prove(&jsonBuffer, &jsonLen)
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        case -5:
+            throw MidenError.accountNotFound(accountId: accountId)
+        default:
+            throw MidenError.proveOwnershipFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(OwnershipStatement.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Export an account's secret key as a passphrase-encrypted backup
+    ///
+    /// The envelope can be stored outside the keystore (e.g. iCloud Keychain) and restored
+    /// with `importKey(envelope:passphrase:)`. Deriving the encryption key takes a moment by
+    /// design, so do NOT call from the main thread.
+    ///
+    /// - Parameters:
+    ///   - accountId: Account ID (hex); its key must be held by this client
+    ///   - passphrase: Passphrase protecting the backup
+    /// - Returns: Envelope (JSON)
+    /// - Throws: If the account is unknown or its key is held by an external signer
+    public func exportKey(accountId: String, passphrase: String) throws -> Data {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var envelopeBuffer = [UInt8](repeating: 0, count: 8192)
+        var envelopeLen: Int = envelopeBuffer.count
+        
+        let result = accountId.withCString { accountIdPtr in
+            passphrase.withCString { passphrasePtr in
+                wc_miden_export_key(h, accountIdPtr, passphrasePtr, &envelopeBuffer, &envelopeLen)
+            }
+        }
+        
+        switch result {
+        case 0:
+            return Data(envelopeBuffer.prefix(envelopeLen))
+        case -5:
+            throw MidenError.accountNotFound(accountId: accountId)
+        default:
+            throw MidenError.keyExportFailed(code: result)
+        }
+    }
+    
+    /// Import a secret key from a backup made by `exportKey(accountId:passphrase:)`
+    ///
+    /// The key is saved to the filesystem keystore; the account does not need to be in the
+    /// store yet. Do NOT call from the main thread.
+    ///
+    /// - Parameters:
+    ///   - envelope: Envelope from `exportKey`
+    ///   - passphrase: Passphrase the backup was made with
+    /// - Returns: Account ID (hex) the key belongs to
+    /// - Throws: `MidenError.wrongPassphrase`, or if the envelope is malformed
+    public func importKey(envelope: Data, passphrase: String) throws -> String {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var accountIdBuffer = [UInt8](repeating: 0, count: 64)
+        var accountIdLen: Int = 64
+        
+        let result = envelope.withUnsafeBytes { envelopeBytes in
+            passphrase.withCString { passphrasePtr in
+                wc_miden_import_key(
+                    h,
+                    envelopeBytes.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                    UInt(envelope.count),
+                    passphrasePtr,
+                    &accountIdBuffer,
+                    &accountIdLen
+                )
+            }
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -101:
+            throw MidenError.wrongPassphrase
+        default:
+            throw MidenError.keyImportFailed(code: result)
+        }
+        
+        guard let accountIdString = String(bytes: accountIdBuffer.prefix(accountIdLen), encoding: .utf8) else {
+            throw MidenError.invalidAccountId
+        }
+        return accountIdString
+    }
+    
+    /// Change the passphrase of the keystore
+    ///
+    /// Re-encrypts every key of the filesystem keystore. nil means no passphrase, so this also
+    /// encrypts an unencrypted keystore or decrypts an encrypted one. Close other wallets on
+    /// the same keystore first, and open it with `keystorePassphrase` set to the new
+    /// passphrase afterwards. Do NOT call from the main thread.
+    ///
+    /// - Parameters:
+    ///   - oldPassphrase: Current passphrase (nil for an unencrypted keystore)
+    ///   - newPassphrase: New passphrase (nil to store the keys unencrypted)
+    /// - Throws: `MidenError.wrongPassphrase` if `oldPassphrase` is not the keystore's
+    public func rekeyKeystore(oldPassphrase: String?, newPassphrase: String?) throws {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        // An empty string means no passphrase
+        let result = (oldPassphrase ?? "").withCString { oldPtr in
+            (newPassphrase ?? "").withCString { newPtr in
+                wc_miden_rekey_keystore(h, oldPtr, newPtr)
+            }
+        }
+        
+        switch result {
+        case 0:
+            return
+        case -101:
+            throw MidenError.wrongPassphrase
+        default:
+            throw MidenError.keystoreRekeyFailed(code: result)
+        }
+    }
+    
+    /// Change the key of an encrypted store (`MidenClientConfig.storeKey`)
+    ///
+    /// Re-encrypts the store and reopens it with the new key. Close other wallets on the same
+    /// store first, and open it with `storeKey` set to the new key afterwards. Do NOT call
+    /// from the main thread.
+    ///
+    /// - Parameters:
+    ///   - oldKey: Current store key
+    ///   - newKey: New store key (not empty)
+    /// - Throws: `MidenError.wrongPassphrase` if `oldKey` is not the store's key
+    public func changeStoreKey(oldKey: String, newKey: String) throws {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        let result = oldKey.withCString { oldPtr in
+            newKey.withCString { newPtr in
+                wc_miden_change_store_key(h, oldPtr, newPtr)
+            }
+        }
+        
+        switch result {
+        case 0:
+            return
+        case -101:
+            throw MidenError.wrongPassphrase
+        default:
+            throw MidenError.storeKeyChangeFailed(code: result)
+        }
+    }
+    
+    /// Back up the store and keystore to a directory
+    ///
+    /// Writes a consistent copy of the store, a copy of the keystore and a manifest with
+    /// their hashes. Keys of the memory backend and external signers are not included. Do
+    /// NOT call from the main thread.
+    ///
+    /// - Parameter destinationPath: Directory to write to; created if missing, must be empty
+    public func backupStore(to destinationPath: String) throws {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        let result = destinationPath.withCString { pathPtr in
+            wc_miden_backup_store(h, pathPtr)
+        }
+        
+        guard result == 0 else {
+            throw MidenError.storeBackupFailed(code: result)
+        }
+    }
+    
+    /// Restore the store and keystore from a backup made by `backupStore(to:)`
+    ///
+    /// The wallet must have been opened with the same `storeKey` and `keystorePassphrase` as
+    /// when the backup was made. Accounts and notes added after the backup are gone from the
+    /// store; sync afterwards. Do NOT call from the main thread.
+    ///
+    /// - Parameter sourcePath: Backup directory
+    /// - Throws: `MidenError.wrongPassphrase` if the backup's store key or keystore
+    ///   passphrase differs, or if the backup is damaged
+    public func restoreStore(from sourcePath: String) throws {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        let result = sourcePath.withCString { pathPtr in
+            wc_miden_restore_store(h, pathPtr)
+        }
+        
+        switch result {
+        case 0:
+            return
+        case -101:
+            throw MidenError.wrongPassphrase
+        default:
+            throw MidenError.storeRestoreFailed(code: result)
+        }
+    }
+    
+    /// Get the public key of an account, e.g. to register the wallet with a server
+    ///
+    /// - Parameter accountId: Account ID (hex)
+    /// - Returns: Public key commitment, and the full public key when held by this client
+    /// - Throws: If the account is unknown or has no auth key
+    public func getPublicKey(accountId: String) throws -> AccountPublicKey {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 4096)
+        var jsonLen: Int = jsonBuffer.count
+        
+        let result = accountId.withCString { accountIdPtr in
+            wc_miden_get_public_key(h, accountIdPtr, &jsonBuffer, &jsonLen)
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        case -5:
+            throw MidenError.accountNotFound(accountId: accountId)
+        default:
+            throw MidenError.publicKeyFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(AccountPublicKey.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Move a wallet created with `createRotatableWallet` to a new auth key
+    ///
+    /// Submits a transaction, signed with the current key, that switches the account to a
+    /// new key in the same backend. The old key is retired and no longer signs. If the
+    /// transaction is discarded, rotate again. Do NOT call from the main thread.
+    ///
+    /// - Parameter accountId: Account ID (hex)
+    /// - Returns: Rotation transaction and the new and retired key commitments
+    /// - Throws: If the account cannot rotate its key (immutable code or external signer)
+    ///   or the transaction fails
+    public func rotateAuthKey(accountId: String) throws -> AuthKeyRotation {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 1024)
+        var jsonLen: Int = jsonBuffer.count
+        
+        let result = accountId.withCString { accountIdPtr in
+            wc_miden_rotate_auth_key(h, accountIdPtr, &jsonBuffer, &jsonLen)
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -5:
+            throw MidenError.accountNotFound(accountId: accountId)
+        default:
+            throw MidenError.keyRotationFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(AuthKeyRotation.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Sign a message with an account's key (login, challenge-response)
+    ///
+    /// The message is hashed with a `miden-message:v1:` prefix before signing, so the
+    /// signature cannot authorize a transaction. External signers may ask the user to
+    /// confirm, so do NOT call from the main thread.
+    ///
+    /// - Parameters:
+    ///   - accountId: Account ID (hex); its key must be held by this client
+    ///   - message: Message to sign (1 byte to 64 KB)
+    /// - Returns: Signed message
+    /// - Throws: If the message length is invalid, the account is unknown or signing fails
+    public func signMessage(accountId: String, message: Data) throws -> SignedMessage {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 4096)
+        var jsonLen: Int = jsonBuffer.count
+        
+        func sign(_ buffer: inout [UInt8], _ length: inout Int) -> Int32 {
+            accountId.withCString { accountIdPtr in
+                message.withUnsafeBytes { messageBytes in
+                    wc_miden_sign_message(
+                        h,
+                        accountIdPtr,
+                        messageBytes.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        UInt(message.count),
+                        &buffer,
+                        &length
+                    )
+                }
+            }
+        }
+        
+        var result = sign(&jsonBuffer, &jsonLen)
+        if result == -7 {
+            jsonBuffer = [UInt8](repeating: 0, count: jsonLen)
+            result = sign(&jsonBuffer, &jsonLen)
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        case -5:
+            throw MidenError.accountNotFound(accountId: accountId)
+        default:
+            throw MidenError.signMessageFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(SignedMessage.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Sign an export file (e.g. a proof-of-reserves report) with an account's key
+    ///
+    /// Writes the detached signature to `<path>.sig`; check it with
+    /// `MidenWallet.verifyExport(path:pubKey:)`. Do NOT call from the main thread.
+    ///
+    /// - Parameters:
+    ///   - accountId: Signing account; its key must be held by this client
+    ///   - path: File to sign
+    /// - Throws: If the file cannot be read, the account is unknown or signing fails
+    public func signExport(accountId: String, path: String) throws {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        let result = accountId.withCString { accountIdPtr in
+            path.withCString { pathPtr in
+                wc_miden_sign_export(h, accountIdPtr, pathPtr)
+            }
+        }
+        
+        switch result {
+        case 0:
+            return
+        case -5:
+            throw MidenError.accountNotFound(accountId: accountId)
+        default:
+            throw MidenError.exportSigningFailed(code: result)
+        }
+    }
+    
+    /// Consume Notes
+    ///
+    /// Create and submit a transaction to consume specified notes.
+    ///
+    /// - Parameters:
+    ///   - accountId: Account ID to execute transaction
+    ///   - noteIds: Array of note IDs to consume
+    /// - Returns: Transaction ID
+    /// - Throws: If consumption fails
+    public func consumeNotes(accountId: String, noteIds: [String]) throws -> String {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        guard !noteIds.isEmpty else {
+            throw MidenError.emptyNoteIds
+        }
+        
+        // Build JSON array
+        let noteIdsJson = "[" + noteIds.map { "\"\($0)\"" }.joined(separator: ",") + "]"
+        
+        var txIdBuffer = [UInt8](repeating: 0, count: 128)
+        var txIdLen: Int = 128
+        
+        let result = accountId.withCString { accountIdPtr in
+            noteIdsJson.withCString { noteIdsPtr in
+                wc_miden_consume_notes(h, accountIdPtr, noteIdsPtr, &txIdBuffer, &txIdLen)
+            }
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        case -4:
+            throw MidenError.invalidNoteId
+        case -5:
+            throw MidenError.consumeNotesFailed(code: result, message: "Transaction creation failed")
+        case -6:
+            throw MidenError.consumeNotesFailed(code: result, message: "Transaction submission failed")
+        default:
+            throw MidenError.consumeNotesFailed(code: result, message: nil)
+        }
+        
+        guard let txIdString = String(
+            bytes: txIdBuffer.prefix(txIdLen),
+            encoding: .utf8
+        ) else {
+            throw MidenError.invalidJSON
+        }
+        
+        return txIdString
+    }
+    
+    /// Send
+    ///
+    /// Pay another account with a public P2ID note.
+    ///
+    /// - Parameters:
+    ///   - accountId: Sending account ID
+    ///   - targetAccountId: Receiving account ID
+    ///   - faucetId: Faucet of the sent asset
+    ///   - amount: Amount in base units
+    /// - Returns: Transaction ID and created note ID
+    /// - Throws: If the send fails
+    public func send(accountId: String, targetAccountId: String, faucetId: String, amount: UInt64) throws -> SendResult {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 512)
+        var jsonLen: Int = 512
+        
+        let result = accountId.withCString { accountIdPtr in
+            targetAccountId.withCString { targetPtr in
+                faucetId.withCString { faucetPtr in
+                    wc_miden_send(h, accountIdPtr, targetPtr, faucetPtr, amount, &jsonBuffer, &jsonLen)
+                }
+            }
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        case -6:
+            throw MidenError.sendFailed(code: result, message: "Transaction submission failed")
+        default:
+            throw MidenError.sendFailed(code: result, message: nil)
+        }
+        
+        do {
+            return try JSONDecoder().decode(SendResult.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Send recallably
+    ///
+    /// Pay another account with a note the sender can take back from `reclaimBlock` on,
+    /// for payments to accounts that may never come online. Use `estimateTime(forBlock:)`
+    /// to show when.
+    ///
+    /// - Parameters:
+    ///   - accountId: Sending account ID
+    ///   - targetAccountId: Receiving account ID
+    ///   - faucetId: Faucet of the sent asset
+    ///   - amount: Amount in base units
+    ///   - reclaimBlock: Block from which the note can be recalled (after the current height)
+    ///   - noteType: `WcNoteType_Public` or `WcNoteType_Private` (the result then carries
+    ///     the NoteFile for the recipient)
+    /// - Returns: Transaction ID and created note ID
+    /// - Throws: If the send fails
+    public func sendRecallable(accountId: String, targetAccountId: String, faucetId: String, amount: UInt64, reclaimBlock: UInt32, noteType: WcNoteType = WcNoteType_Public) throws -> SendResult {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 4096)
+        var jsonLen: Int = 4096
+        
+        let result = accountId.withCString { accountIdPtr in
+            targetAccountId.withCString { targetPtr in
+                faucetId.withCString { faucetPtr in
+                    wc_miden_send_recallable(h, accountIdPtr, targetPtr, faucetPtr, amount, reclaimBlock, noteType.rawValue, &jsonBuffer, &jsonLen)
+                }
+            }
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        case -6:
+            throw MidenError.sendFailed(code: result, message: "Transaction submission failed")
+        default:
+            throw MidenError.sendFailed(code: result, message: nil)
+        }
+        
+        do {
+            return try JSONDecoder().decode(SendResult.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Reclaimable notes
+    ///
+    /// Notes sent recallably from `accountId` whose reclaim block has been reached and that
+    /// the target has not consumed, as of the last sync (sync first).
+    ///
+    /// - Parameter accountId: Sending account ID
+    /// - Returns: Current sync height and the reclaimable notes
+    /// - Throws: If the query fails
+    public func reclaimableNotes(accountId: String) throws -> ReclaimableNotes {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 16384)
+        var jsonLen: Int = jsonBuffer.count
+        var result = accountId.withCString { accountIdPtr in
+            wc_miden_get_reclaimable_notes(h, accountIdPtr, &jsonBuffer, &jsonLen)
+        }
+        
+        if result == -7 {
+            jsonBuffer = [UInt8](repeating: 0, count: jsonLen)
+            result = accountId.withCString { accountIdPtr in
+                wc_miden_get_reclaimable_notes(h, accountIdPtr, &jsonBuffer, &jsonLen)
+            }
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        default:
+            throw MidenError.reclaimFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(ReclaimableNotes.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Reclaim notes
+    ///
+    /// Consume notes listed by `reclaimableNotes(accountId:)` back into the sending account.
+    /// Fails if the target consumed one of them since the last sync.
+    ///
+    /// - Parameters:
+    ///   - accountId: Sending account ID
+    ///   - noteIds: IDs of reclaimable notes
+    /// - Returns: Transaction ID
+    /// - Throws: If a note is not reclaimable or the transaction fails
+    public func reclaimNotes(accountId: String, noteIds: [String]) throws -> String {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        guard !noteIds.isEmpty else {
+            throw MidenError.emptyNoteIds
+        }
+        
+        let noteIdsJson = "[" + noteIds.map { "\"\($0)\"" }.joined(separator: ",") + "]"
+        
+        var txIdBuffer = [UInt8](repeating: 0, count: 128)
+        var txIdLen: Int = 128
+        
+        let result = accountId.withCString { accountIdPtr in
+            noteIdsJson.withCString { noteIdsPtr in
+                wc_miden_reclaim_notes(h, accountIdPtr, noteIdsPtr, &txIdBuffer, &txIdLen)
+            }
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        case -4:
+            throw MidenError.invalidNoteId
+        default:
+            throw MidenError.reclaimFailed(code: result)
+        }
+        
+        guard let txIdString = String(bytes: txIdBuffer.prefix(txIdLen), encoding: .utf8) else {
+            throw MidenError.invalidJSON
+        }
+        return txIdString
+    }
+    
+    /// Send privately
+    ///
+    /// Pay another account with a private P2ID note. Only the note's commitment goes on
+    /// chain: give the recipient `noteFileData` (AirDrop, QR code) to pass to `importNote(_:)`.
+    ///
+    /// - Parameters:
+    ///   - accountId: Sending account ID
+    ///   - targetAccountId: Receiving account ID
+    ///   - faucetId: Faucet of the sent asset
+    ///   - amount: Amount in base units
+    /// - Returns: Transaction ID, created note ID and the NoteFile for the recipient
+    /// - Throws: If the send fails
+    public func sendPrivate(accountId: String, targetAccountId: String, faucetId: String, amount: UInt64) throws -> SendResult {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        // The note file is only sized once the transaction is submitted (see wc_miden_send_private)
+        var jsonBuffer = [UInt8](repeating: 0, count: 65536)
+        var jsonLen: Int = 65536
+        
+        let result = accountId.withCString { accountIdPtr in
+            targetAccountId.withCString { targetPtr in
+                faucetId.withCString { faucetPtr in
+                    wc_miden_send_private(h, accountIdPtr, targetPtr, faucetPtr, amount, &jsonBuffer, &jsonLen)
+                }
+            }
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        case -6:
+            throw MidenError.sendFailed(code: result, message: "Transaction submission failed")
+        default:
+            throw MidenError.sendFailed(code: result, message: nil)
+        }
+        
+        do {
+            return try JSONDecoder().decode(SendResult.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    #if !MIDEN_NO_FAUCETS
+    /// Mint tokens from a faucet created by this client
+    ///
+    /// - Parameters:
+    ///   - faucetId: Faucet account ID
+    ///   - targetAccountId: Receiving account ID
+    ///   - amount: Amount in base units
+    ///   - noteType: Note type of the minted note (`WcNoteType_Public` or `WcNoteType_Private`)
+    /// - Returns: Transaction ID
+    /// - Throws: If minting fails
+    public func mint(faucetId: String, targetAccountId: String, amount: UInt64, noteType: WcNoteType = WcNoteType_Public) throws -> String {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var txIdBuffer = [UInt8](repeating: 0, count: 128)
+        var txIdLen: Int = 128
+        
+        let result = faucetId.withCString { faucetPtr in
+            targetAccountId.withCString { targetPtr in
+                wc_miden_mint(h, faucetPtr, targetPtr, amount, noteType.rawValue, &txIdBuffer, &txIdLen)
+            }
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        default:
+            throw MidenError.mintFailed(code: result)
+        }
+        
+        guard let txId = String(bytes: txIdBuffer.prefix(txIdLen), encoding: .utf8) else {
+            throw MidenError.invalidHexString
+        }
+        return txId
+    }
+    #endif
+    
+    #if !MIDEN_NO_SWAPS
+    /// Offer one fungible asset for another in a SWAP note
+    ///
+    /// Whoever consumes the note receives the offered asset and pays the requested one back
+    /// in a payback note, which a later sync delivers to this account. Accounts with a
+    /// withdrawal whitelist cannot create swaps.
+    ///
+    /// - Parameters:
+    ///   - accountId: Offering account ID
+    ///   - offeredFaucetId: Faucet of the offered asset
+    ///   - offeredAmount: Offered amount in base units
+    ///   - requestedFaucetId: Faucet of the requested asset
+    ///   - requestedAmount: Requested amount in base units
+    ///   - noteType: Note type of the SWAP and payback notes (`WcNoteType_Public` or `WcNoteType_Private`)
+    /// - Returns: Transaction ID
+    /// - Throws: If the swap cannot be created or submitted
+    public func createSwap(
+        accountId: String,
+        offeredFaucetId: String,
+        offeredAmount: UInt64,
+        requestedFaucetId: String,
+        requestedAmount: UInt64,
+        noteType: WcNoteType = WcNoteType_Public
+    ) throws -> String {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        var txIdBuffer = [UInt8](repeating: 0, count: 128)
+        var txIdLen: Int = 128
+        
+        let result = accountId.withCString { accountPtr in
+            offeredFaucetId.withCString { offeredPtr in
+                requestedFaucetId.withCString { requestedPtr in
+                    wc_miden_create_swap(
+                        h,
+                        accountPtr,
+                        offeredPtr,
+                        offeredAmount,
+                        requestedPtr,
+                        requestedAmount,
+                        noteType.rawValue,
+                        &txIdBuffer,
+                        &txIdLen
+                    )
+                }
+            }
+        }
+        
+        switch result {
+        case 0:
+            break
+        case -3:
+            throw MidenError.invalidAccountId
+        default:
+            throw MidenError.swapFailed(code: result)
+        }
+        
+        guard let txId = String(bytes: txIdBuffer.prefix(txIdLen), encoding: .utf8) else {
+            throw MidenError.invalidHexString
+        }
+        return txId
+    }
+    #endif
+    
+    /// Register this device with a push relay for incoming notes
+    ///
+    /// Register again after creating accounts or tracking new tags.
+    ///
+    /// - Parameters:
+    ///   - relayURL: Registration endpoint of the relay service
+    ///   - deviceToken: APNs device token from `didRegisterForRemoteNotificationsWithDeviceToken`
+    ///   - tags: Note tags to watch, or nil for every tag the client tracks
+    /// - Returns: The registered note tags
+    /// - Throws: If the relay cannot be reached or refuses the registration
+    @discardableResult
+    public func registerPush(relayURL: String, deviceToken: Data, tags: [UInt32]? = nil) throws -> [UInt32] {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        let tokenHex = deviceToken.map { String(format: "%02x", $0) }.joined()
+        let tagsJSON = try tags.map { String(decoding: try JSONEncoder().encode($0), as: UTF8.self) }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 16384)
+        var jsonLen: Int = 16384
+        
+        let result = relayURL.withCString { urlPtr in
+            tokenHex.withCString { tokenPtr in
+                if let tagsJSON = tagsJSON {
+                    return tagsJSON.withCString { tagsPtr in
+                        wc_miden_register_push(h, urlPtr, tokenPtr, tagsPtr, &jsonBuffer, &jsonLen)
+                    }
+                }
+                return wc_miden_register_push(h, urlPtr, tokenPtr, nil, &jsonBuffer, &jsonLen)
+            }
+        }
+        
+        guard result == 0 else {
+            throw MidenError.pushFailed(code: result)
+        }
+        
+        struct Registration: Decodable {
+            let registeredTags: [UInt32]
+            
+            enum CodingKeys: String, CodingKey {
+                case registeredTags = "registered_tags"
+            }
+        }
+        do {
+            return try JSONDecoder().decode(Registration.self, from: Data(jsonBuffer.prefix(jsonLen))).registeredTags
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+    
+    /// Fetch the note referenced by a push notification
+    ///
+    /// Quick enough for a notification service extension. Do NOT call from the main thread.
+    ///
+    /// - Parameter userInfo: The notification's `userInfo` dictionary
+    /// - Returns: Whether the note was imported or a regular sync is needed
+    /// - Throws: If the payload does not reference a note or the store fails
+    public func handlePush(userInfo: [AnyHashable: Any]) throws -> PushNoteResult {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        guard let miden = userInfo["miden"],
+              JSONSerialization.isValidJSONObject(miden),
+              let payload = try? JSONSerialization.data(withJSONObject: miden) else {
+            throw MidenError.invalidJSON
+        }
+        
+        var jsonBuffer = [UInt8](repeating: 0, count: 512)
+        var jsonLen: Int = 512
+        
+        let result = String(decoding: payload, as: UTF8.self).withCString { payloadPtr in
+            wc_miden_handle_push(h, payloadPtr, &jsonBuffer, &jsonLen)
+        }
+        
+        guard result == 0 else {
+            throw MidenError.pushFailed(code: result)
+        }
+        
+        do {
+            return try JSONDecoder().decode(PushNoteResult.self, from: Data(jsonBuffer.prefix(jsonLen)))
+        } catch {
+            throw MidenError.jsonDecodeFailed(error: error)
+        }
+    }
+}
+
+// MARK: - Error Types
+
 /// Miden Wallet error type
 public enum MidenError: LocalizedError {
     case initializationFailed(code: Int32)
     case invalidHandle
     case syncFailed(code: Int32)
     case createWalletFailed(code: Int32)
+    case createFaucetFailed(code: Int32)
     case getAccountsFailed(code: Int32)
     case getBalanceFailed(code: Int32)
     case getInputNotesFailed(code: Int32)
@@ -446,6 +2787,61 @@ public enum MidenError: LocalizedError {
     case connectionTestFailed(code: Int32)
     case hexConversionFailed(code: Int32)
     case invalidHexString
+    case capabilitiesQueryFailed(code: Int32)
+    case nameResolutionFailed(code: Int32)
+    case sendFailed(code: Int32, message: String?)
+    case mintFailed(code: Int32)
+    case swapFailed(code: Int32)
+    case mnemonicFailed(code: Int32)
+    case pushFailed(code: Int32)
+    case amountConversionFailed(code: Int32)
+    case importAccountFailed(code: Int32)
+    case noteExportFailed(code: Int32)
+    case noteImportFailed(code: Int32)
+    case stateAuditFailed(code: Int32)
+    case blockTimeEstimateFailed(code: Int32)
+    case reclaimFailed(code: Int32)
+    case getTransactionsFailed(code: Int32)
+    case transactionStatusFailed(code: Int32)
+    case getOutputNotesFailed(code: Int32)
+    case memoryReportFailed(code: Int32)
+    case objectOpenFailed(code: Int32)
+    case proveOwnershipFailed(code: Int32)
+    case faucetMetadataFailed(code: Int32)
+    case exportSigningFailed(code: Int32)
+    case networkMismatch(details: String?)
+    case networkCheckFailed(code: Int32)
+    /// The derived account already exists in the store or on chain (import it instead)
+    case accountAlreadyExists
+    /// Wallet creation requires a sync first (`requireSyncForCreate`)
+    case notSynced
+    case healthCheckFailed(code: Int32)
+    case deployFailed(code: Int32)
+    case keyExportFailed(code: Int32)
+    case keyImportFailed(code: Int32)
+    /// The passphrase or key does not open the key backup, keystore or store
+    case wrongPassphrase
+    case publicKeyFailed(code: Int32)
+    case keyRotationFailed(code: Int32)
+    case signMessageFailed(code: Int32)
+    case verifySignatureFailed(code: Int32)
+    case signerRegistrationFailed(code: Int32)
+    case keystoreRekeyFailed(code: Int32)
+    case storeKeyChangeFailed(code: Int32)
+    case storeBackupFailed(code: Int32)
+    case storeRestoreFailed(code: Int32)
+    
+    /// Error of a failed wallet creation
+    static func createWallet(code: Int32) -> MidenError {
+        switch code {
+        case -105:
+            return .accountAlreadyExists
+        case -106:
+            return .notSynced
+        default:
+            return .createWalletFailed(code: code)
+        }
+    }
     
     public var errorDescription: String? {
         switch self {
@@ -457,6 +2853,8 @@ public enum MidenError: LocalizedError {
             return "Sync failed (error code: \(code))"
         case .createWalletFailed(let code):
             return "Wallet creation failed (error code: \(code))"
+        case .createFaucetFailed(let code):
+            return "Faucet creation failed (error code: \(code))"
         case .getAccountsFailed(let code):
             return "Get accounts failed (error code: \(code))"
         case .getBalanceFailed(let code):
@@ -471,7 +2869,7 @@ public enum MidenError: LocalizedError {
         case .accountNotFound(let accountId):
             return "Account not found: \(accountId)"
         case .invalidSeedLength:
-            return "Seed length must be 32 bytes"
+            return "Seed length must be 32 bytes (master seeds: 16-64 bytes)"
         case .invalidAccountId:
             return "Invalid account ID"
         case .invalidNoteId:
@@ -488,99 +2886,974 @@ public enum MidenError: LocalizedError {
             return "Hex conversion failed (error code: \(code))"
         case .invalidHexString:
             return "Invalid hex string"
+        case .capabilitiesQueryFailed(let code):
+            return "Capabilities query failed (error code: \(code))"
+        case .nameResolutionFailed(let code):
+            return "Name resolution failed (error code: \(code))"
+        case .sendFailed(let code, let message):
+            if let msg = message {
+                return "Send failed: \(msg) (error code: \(code))"
+            }
+            return "Send failed (error code: \(code))"
+        case .mintFailed(let code):
+            return "Mint failed (error code: \(code))"
+        case .swapFailed(let code):
+            return "Swap failed (error code: \(code))"
+        case .mnemonicFailed(let code):
+            return "Recovery phrase operation failed (error code: \(code))"
+        case .pushFailed(let code):
+            return "Push registration failed (error code: \(code))"
+        case .amountConversionFailed(let code):
+            return "Amount conversion failed (error code: \(code))"
+        case .importAccountFailed(let code):
+            return "Account import failed (error code: \(code))"
+        case .noteExportFailed(let code):
+            return "Note export failed (error code: \(code))"
+        case .noteImportFailed(let code):
+            return "Note import failed (error code: \(code))"
+        case .stateAuditFailed(let code):
+            return "State audit failed (error code: \(code))"
+        case .blockTimeEstimateFailed(let code):
+            return "Block time estimate failed (error code: \(code))"
+        case .reclaimFailed(let code):
+            return "Note reclaim failed (error code: \(code))"
+        case .getTransactionsFailed(let code):
+            return "Transaction history query failed (error code: \(code))"
+        case .transactionStatusFailed(let code):
+            return "Transaction status lookup failed (error code: \(code))"
+        case .getOutputNotesFailed(let code):
+            return "Get output notes failed (error code: \(code))"
+        case .memoryReportFailed(let code):
+            return "Memory report failed (error code: \(code))"
+        case .objectOpenFailed(let code):
+            return "Open object failed (error code: \(code))"
+        case .proveOwnershipFailed(let code):
+            return "Ownership proof failed (error code: \(code))"
+        case .faucetMetadataFailed(let code):
+            return "Faucet metadata lookup failed (error code: \(code))"
+        case .exportSigningFailed(let code):
+            return "Export signing failed (error code: \(code))"
+        case .networkMismatch(let details):
+            return "Node is on another network than the store" + (details.map { ": \($0)" } ?? "")
+        case .networkCheckFailed(let code):
+            return "Network check failed (error code: \(code))"
+        case .accountAlreadyExists:
+            return "Account already exists; import it instead"
+        case .notSynced:
+            return "Sync before creating a wallet"
+        case .healthCheckFailed(let code):
+            return "Health check failed (error code: \(code))"
+        case .deployFailed(let code):
+            return "Account deployment failed (error code: \(code))"
+        case .keyExportFailed(let code):
+            return "Key export failed (error code: \(code))"
+        case .keyImportFailed(let code):
+            return "Key import failed (error code: \(code))"
+        case .wrongPassphrase:
+            return "Wrong passphrase"
+        case .publicKeyFailed(let code):
+            return "Failed to get public key (error code: \(code))"
+        case .keyRotationFailed(let code):
+            return "Auth key rotation failed (error code: \(code))"
+        case .signMessageFailed(let code):
+            return "Message signing failed (error code: \(code))"
+        case .verifySignatureFailed(let code):
+            return "Signature verification failed (error code: \(code))"
+        case .signerRegistrationFailed(let code):
+            return "Signer registration failed (error code: \(code))"
+        case .keystoreRekeyFailed(let code):
+            return "Keystore rekey failed (error code: \(code))"
+        case .storeKeyChangeFailed(let code):
+            return "Store key change failed (error code: \(code))"
+        case .storeBackupFailed(let code):
+            return "Store backup failed (error code: \(code))"
+        case .storeRestoreFailed(let code):
+            return "Store restore failed (error code: \(code))"
+        }
+    }
+}
+
+// MARK: - Data Models
+
+/// Fungible asset information
+public struct FungibleAsset: Codable {
+    /// Faucet ID (issuer account ID)
+    public let faucetId: String
+    /// Asset amount
+    public let amount: UInt64
+    
+    enum CodingKeys: String, CodingKey {
+        case faucetId = "faucet_id"
+        case amount
+    }
+}
+
+/// Account balance information
+public struct AccountBalance: Codable {
+    /// Account ID
+    public let accountId: String
+    /// List of fungible assets
+    public let fungibleAssets: [FungibleAsset]
+    /// Total count of fungible assets
+    public let totalFungibleCount: Int
+    /// Total count of non-fungible assets
+    public let totalNonFungibleCount: Int
+    
+    enum CodingKeys: String, CodingKey {
+        case accountId = "account_id"
+        case fungibleAssets = "fungible_assets"
+        case totalFungibleCount = "total_fungible_count"
+        case totalNonFungibleCount = "total_non_fungible_count"
+    }
+    
+    /// Check if account has any assets
+    public var hasAssets: Bool {
+        totalFungibleCount > 0 || totalNonFungibleCount > 0
+    }
+    
+    /// Get balance for specific faucet
+    public func balance(for faucetId: String) -> UInt64 {
+        fungibleAssets.first { $0.faucetId == faucetId }?.amount ?? 0
+    }
+}
+
+/// Token metadata of a fungible faucet
+public struct FaucetMetadata: Codable {
+    public let faucetId: String
+    /// Token symbol, e.g. "MID"
+    public let symbol: String
+    public let decimals: UInt8
+    /// Maximum supply in base units
+    public let maxSupply: UInt64
+    
+    enum CodingKeys: String, CodingKey {
+        case faucetId = "faucet_id"
+        case symbol
+        case decimals
+        case maxSupply = "max_supply"
+    }
+}
+
+/// Result of `MidenWallet.checkNetwork()`
+public struct NetworkCheck: Codable {
+    /// `testnet`, `devnet`, `localhost` or `custom`
+    public let network: String
+    public let endpoint: String
+    public let genesisCommitment: String
+    /// Unix time of the genesis block
+    public let genesisTimestamp: UInt64
+    /// Whether this check pinned the network for the store
+    public let newlyPinned: Bool
+    
+    enum CodingKeys: String, CodingKey {
+        case network
+        case endpoint
+        case genesisCommitment = "genesis_commitment"
+        case genesisTimestamp = "genesis_timestamp"
+        case newlyPinned = "newly_pinned"
+    }
+}
+
+/// Input Note information
+public struct InputNoteInfo: Codable {
+    /// Note ID
+    public let noteId: String
+    /// List of assets
+    public let assets: [FungibleAsset]
+    /// Whether authenticated
+    public let isAuthenticated: Bool
+    /// ISO-8601 time of the block the note was included in (nil until known)
+    public let timestamp: String?
+    /// "expected", "committed", "processing" or "consumed" (only in filtered listings)
+    public let state: String?
+    
+    enum CodingKeys: String, CodingKey {
+        case noteId = "note_id"
+        case assets
+        case isAuthenticated = "is_authenticated"
+        case timestamp
+        case state
+    }
+    
+    /// Inclusion time as a `Date` (nil until known)
+    public var date: Date? {
+        timestamp.flatMap { ISO8601DateFormatter().date(from: $0) }
+    }
+    
+    /// Get total asset value in Note (aggregated by faucet)
+    public var totalAssets: [String: UInt64] {
+        var totals: [String: UInt64] = [:]
+        for asset in assets {
+            totals[asset.faucetId, default: 0] += asset.amount
+        }
+        return totals
+    }
+}
+
+/// Result of a send
+public struct SendResult: Codable {
+    /// Transaction ID
+    public let txId: String
+    /// ID of the created P2ID note
+    public let noteId: String
+    /// Serialized NoteFile of a private note (hex), for the recipient to import
+    public let noteFile: String?
+    
+    enum CodingKeys: String, CodingKey {
+        case txId = "tx_id"
+        case noteId = "note_id"
+        case noteFile = "note_file"
+    }
+    
+    /// The NoteFile of a private note, for `importNote(_:)` on the recipient's side
+    public var noteFileData: Data? {
+        guard let hex = noteFile, hex.count % 2 == 0 else { return nil }
+        var data = Data(capacity: hex.count / 2)
+        var index = hex.startIndex
+        while index < hex.endIndex {
+            let next = hex.index(index, offsetBy: 2)
+            guard let byte = UInt8(hex[index..<next], radix: 16) else { return nil }
+            data.append(byte)
+            index = next
+        }
+        return data
+    }
+}
+
+/// Transaction record returned by `getTransactions(accountId:status:limit:)`,
+/// `transactionStatus(txId:)` and `waitForTransaction(txId:timeout:)`
+public struct TransactionInfo: Codable {
+    public enum Status: String, Codable {
+        case pending
+        case committed
+        case discarded
+    }
+    
+    public let txId: String
+    public let accountId: String
+    public let status: Status
+    /// Block the transaction was committed in (nil until committed)
+    public let blockNum: UInt32?
+    /// ISO-8601 time of that block (nil until known)
+    public let timestamp: String?
+    public let inputNoteCount: Int
+    public let outputNoteCount: Int
+    
+    enum CodingKeys: String, CodingKey {
+        case txId = "tx_id"
+        case accountId = "account_id"
+        case status
+        case blockNum = "block_num"
+        case timestamp
+        case inputNoteCount = "input_note_count"
+        case outputNoteCount = "output_note_count"
+    }
+    
+    /// Commit time as a `Date` (nil until known)
+    public var date: Date? {
+        timestamp.flatMap { ISO8601DateFormatter().date(from: $0) }
+    }
+}
+
+/// Input Notes query result
+public struct InputNotesResult: Codable {
+    /// List of notes
+    public let notes: [InputNoteInfo]
+    /// Total count
+    public let totalCount: Int
+    
+    enum CodingKeys: String, CodingKey {
+        case notes
+        case totalCount = "total_count"
+    }
+    
+    /// Get all consumable note IDs
+    public var consumableNoteIds: [String] {
+        notes.map { $0.noteId }
+    }
+    
+    /// Whether there are consumable notes
+    public var hasConsumableNotes: Bool {
+        !notes.isEmpty
+    }
+}
+
+/// Note created by one of this wallet's transactions
+public struct OutputNoteInfo: Codable {
+    public enum State: String, Codable {
+        case expected
+        case committed
+        case consumed
+    }
+    
+    /// Recipient of the note (target only known for P2ID/P2IDE notes created here)
+    public struct Recipient: Codable {
+        public let digest: String
+        public let targetAccountId: String?
+        /// Block from which the sender can reclaim the note (P2IDE notes)
+        public let reclaimBlock: UInt64?
+        
+        enum CodingKeys: String, CodingKey {
+            case digest
+            case targetAccountId = "target_account_id"
+            case reclaimBlock = "reclaim_block"
         }
     }
+    
+    public let noteId: String
+    public let sender: String
+    public let state: State
+    /// "public" or "private"
+    public let noteType: String
+    public let assets: [FungibleAsset]
+    public let recipient: Recipient
+    public let expectedHeight: UInt32
+    /// Block the note was included in (nil until committed)
+    public let blockNum: UInt32?
+    /// ISO-8601 time of that block (nil until known)
+    public let timestamp: String?
+    
+    enum CodingKeys: String, CodingKey {
+        case noteId = "note_id"
+        case sender
+        case state
+        case noteType = "note_type"
+        case assets
+        case recipient
+        case expectedHeight = "expected_height"
+        case blockNum = "block_num"
+        case timestamp
+    }
+    
+    /// Inclusion time as a `Date` (nil until known)
+    public var date: Date? {
+        timestamp.flatMap { ISO8601DateFormatter().date(from: $0) }
+    }
+}
+
+/// Output Notes query result
+public struct OutputNotesResult: Codable {
+    public let notes: [OutputNoteInfo]
+    public let totalCount: Int
+    
+    enum CodingKeys: String, CodingKey {
+        case notes
+        case totalCount = "total_count"
+    }
 }
 
-// MARK: - Data Models
+/// One page of `getAccounts(offset:limit:)`
+public struct AccountsPage: Codable {
+    public let accounts: [String]
+    /// Accounts on all pages
+    public let totalCount: Int
+    public let offset: Int
+    public let hasMore: Bool
+    
+    enum CodingKeys: String, CodingKey {
+        case accounts
+        case totalCount = "total_count"
+        case offset
+        case hasMore = "has_more"
+    }
+}
 
-/// Fungible asset information
-public struct FungibleAsset: Codable {
-    /// Faucet ID (issuer account ID)
-    public let faucetId: String
-    /// Asset amount
-    public let amount: UInt64
+/// One page of `getInputNotes(accountId:offset:limit:)`
+public struct InputNotesPage: Codable {
+    public let notes: [InputNoteInfo]
+    /// Notes on all pages
+    public let totalCount: Int
+    public let offset: Int
+    public let hasMore: Bool
     
     enum CodingKeys: String, CodingKey {
-        case faucetId = "faucet_id"
-        case amount
+        case notes
+        case totalCount = "total_count"
+        case offset
+        case hasMore = "has_more"
     }
 }
 
-/// Account balance information
-public struct AccountBalance: Codable {
-    /// Account ID
+/// Signed statement returned by `proveOwnership(accountId:challenge:)` (hex fields)
+public struct OwnershipStatement: Codable {
+    public let version: Int
     public let accountId: String
-    /// List of fungible assets
-    public let fungibleAssets: [FungibleAsset]
-    /// Total count of fungible assets
-    public let totalFungibleCount: Int
-    /// Total count of non-fungible assets
-    public let totalNonFungibleCount: Int
+    public let challenge: String
+    /// RPO hash of `miden-ownership:v1:<account_id>:<challenge>`
+    public let digest: String
+    /// Public key commitment of the account's auth key
+    public let pubKey: String
+    /// Serialized RPO Falcon512 signature over `digest`
+    public let signature: String
     
     enum CodingKeys: String, CodingKey {
+        case version
         case accountId = "account_id"
-        case fungibleAssets = "fungible_assets"
-        case totalFungibleCount = "total_fungible_count"
-        case totalNonFungibleCount = "total_non_fungible_count"
+        case challenge
+        case digest
+        case pubKey = "pub_key"
+        case signature
     }
+}
+
+/// Public key of an account's auth key
+public struct AccountPublicKey: Codable {
+    public let accountId: String
+    /// Signature scheme (`rpo_falcon512`)
+    public let scheme: String
+    /// Public key commitment the account authenticates with (hex)
+    public let pubKey: String
+    /// Serialized public key (hex); nil when an external signer holds the key
+    public let publicKey: String?
+    /// Key backend: `filesystem`, `callback` or `memory`
+    public let keyBackend: String
     
-    /// Check if account has any assets
-    public var hasAssets: Bool {
-        totalFungibleCount > 0 || totalNonFungibleCount > 0
+    enum CodingKeys: String, CodingKey {
+        case accountId = "account_id"
+        case scheme
+        case pubKey = "pub_key"
+        case publicKey = "public_key"
+        case keyBackend = "key_backend"
     }
+}
+
+/// Auth key rotation submitted by `rotateAuthKey`
+public struct AuthKeyRotation: Codable {
+    public let txId: String
+    public let accountId: String
+    /// Public key commitment of the new key (hex)
+    public let pubKey: String
+    /// Public key commitment of the retired key (hex)
+    public let retiredPubKey: String
     
-    /// Get balance for specific faucet
+    enum CodingKeys: String, CodingKey {
+        case txId = "tx_id"
+        case accountId = "account_id"
+        case pubKey = "pub_key"
+        case retiredPubKey = "retired_pub_key"
+    }
+}
+
+/// Message signed with an account's auth key
+public struct SignedMessage: Codable {
+    public let version: Int
+    public let accountId: String
+    /// RPO hash of `miden-message:v1:` followed by the message
+    public let digest: String
+    /// Public key commitment of the account's auth key
+    public let pubKey: String
+    /// Serialized RPO Falcon512 signature over `digest`
+    public let signature: String
+    
+    enum CodingKeys: String, CodingKey {
+        case version
+        case accountId = "account_id"
+        case digest
+        case pubKey = "pub_key"
+        case signature
+    }
+}
+
+/// Read a string accessor of an object handle
+private func objectString(_ read: (UnsafeMutablePointer<UInt8>?, UnsafeMutablePointer<Int>?) -> Int32) -> String? {
+    var buffer = [UInt8](repeating: 0, count: 128)
+    var length: Int = buffer.count
+    guard read(&buffer, &length) == 0 else {
+        return nil
+    }
+    return String(decoding: buffer.prefix(length), as: UTF8.self)
+}
+
+/// Snapshot of an account opened with `openAccount(accountId:)`
+public final class AccountObject {
+    private let obj: WcObject
+    
+    fileprivate init(_ obj: WcObject) {
+        self.obj = obj
+    }
+    
+    deinit {
+        wc_object_free(obj)
+    }
+    
+    /// Account ID (hex)
+    public var id: String {
+        objectString { wc_account_id(obj, $0, $1) } ?? ""
+    }
+    
+    /// Account nonce
+    public var nonce: UInt64 {
+        var nonce: UInt64 = 0
+        _ = wc_account_nonce(obj, &nonce)
+        return nonce
+    }
+    
+    /// State commitment (hex)
+    public var commitment: String {
+        objectString { wc_account_commitment(obj, $0, $1) } ?? ""
+    }
+    
+    /// Balance of one fungible asset (0 when the vault has none or the faucet ID is invalid)
     public func balance(for faucetId: String) -> UInt64 {
-        fungibleAssets.first { $0.faucetId == faucetId }?.amount ?? 0
+        var balance: UInt64 = 0
+        _ = faucetId.withCString { wc_account_balance(obj, $0, &balance) }
+        return balance
     }
 }
 
-/// Input Note information
-public struct InputNoteInfo: Codable {
-    /// Note ID
-    public let noteId: String
-    /// List of assets
-    public let assets: [FungibleAsset]
-    /// Whether authenticated
-    public let isAuthenticated: Bool
+/// Snapshot of a note opened with `openNote(noteId:)`
+public final class NoteObject {
+    private let obj: WcObject
+    
+    fileprivate init(_ obj: WcObject) {
+        self.obj = obj
+    }
+    
+    deinit {
+        wc_object_free(obj)
+    }
+    
+    /// Note ID (hex)
+    public var id: String {
+        objectString { wc_note_id(obj, $0, $1) } ?? ""
+    }
+    
+    /// Sender account ID (nil while a received note's metadata is unknown)
+    public var sender: String? {
+        objectString { wc_note_sender(obj, $0, $1) }
+    }
+    
+    /// `expected`, `committed`, `processing` or `consumed`
+    public var state: String {
+        objectString { wc_note_state(obj, $0, $1) } ?? ""
+    }
+    
+    /// Inclusion block (nil until committed)
+    public var blockNum: UInt32? {
+        var blockNum: UInt32 = 0
+        return wc_note_block_num(obj, &blockNum) == 0 ? blockNum : nil
+    }
+    
+    /// Amount of one fungible asset (0 when the note carries none or the faucet ID is invalid)
+    public func amount(for faucetId: String) -> UInt64 {
+        var amount: UInt64 = 0
+        _ = faucetId.withCString { wc_note_amount(obj, $0, &amount) }
+        return amount
+    }
+}
+
+/// Local sync height compared to the node tip
+public struct SyncLag: Codable {
+    public let localHeight: UInt32
+    public let nodeTip: UInt32
+    public let blocksBehind: UInt32
+    public let syncing: Bool
+    /// Estimated time to catch up, nil until a sync has measured the sync rate
+    public let estimatedSecondsRemaining: UInt64?
+    /// Unix time the node tip was last refreshed
+    public let tipUpdatedAt: UInt64
+    /// Node traffic of the last sync round in bytes (0 before the first)
+    public let lastSyncBytesSent: UInt64
+    public let lastSyncBytesReceived: UInt64
     
     enum CodingKeys: String, CodingKey {
-        case noteId = "note_id"
-        case assets
-        case isAuthenticated = "is_authenticated"
+        case localHeight = "local_height"
+        case nodeTip = "node_tip"
+        case blocksBehind = "blocks_behind"
+        case syncing
+        case estimatedSecondsRemaining = "estimated_seconds_remaining"
+        case tipUpdatedAt = "tip_updated_at"
+        case lastSyncBytesSent = "last_sync_bytes_sent"
+        case lastSyncBytesReceived = "last_sync_bytes_received"
     }
+}
+
+/// Wallet from `MidenWallet.createWalletDerived(masterSeed:index:storageMode:)`
+public struct DerivedWallet: Codable {
+    /// Account ID (hex)
+    public let accountId: String
+    /// Index of the wallet under the master seed
+    public let index: UInt32
+    /// The wallet already existed on chain and was restored into this store
+    public let restored: Bool
     
-    /// Get total asset value in Note (aggregated by faucet)
-    public var totalAssets: [String: UInt64] {
-        var totals: [String: UInt64] = [:]
-        for asset in assets {
-            totals[asset.faucetId, default: 0] += asset.amount
+    enum CodingKeys: String, CodingKey {
+        case accountId = "account_id"
+        case index
+        case restored
+    }
+}
+
+/// Storage health from `MidenWallet.healthCheck()`
+public struct StorageHealth: Codable {
+    /// Writes fail (disk full or read-only store); reads keep working
+    public let degraded: Bool
+    /// `ok`, `full` or `read_only`
+    public let storage: String
+    /// Error that put the storage in degraded mode
+    public let reason: String?
+    /// Unix time degraded mode started
+    public let degradedSince: UInt64?
+    
+    enum CodingKeys: String, CodingKey {
+        case degraded
+        case storage
+        case reason
+        case degradedSince = "degraded_since"
+    }
+}
+
+/// Client settings for `MidenWallet.init(config:)`
+public struct MidenClientConfig: Encodable {
+    public var keystorePath: String
+    public var storePath: String
+    /// Node URL, e.g. `https://rpc.testnet.miden.io:443`, or a `networkId` alias (nil uses
+    /// `networkId`)
+    public var rpcEndpoint: String?
+    /// `testnet`, `devnet`, `localhost` or `localhost:<port>` (nil means testnet)
+    public var networkId: String?
+    /// RPC timeout in milliseconds (nil means 10000)
+    public var timeoutMs: UInt64?
+    public var debug: Bool?
+    /// Extra runtime threads for network I/O (nil or 0 means none)
+    public var workerThreads: UInt32?
+    /// Refuse wallet creation until the store has synced once (nil means false)
+    public var requireSyncForCreate: Bool?
+    /// Memory in MB a proof may need; transactions fail with error -108 instead of proving
+    /// when less is available (nil means no check)
+    public var provingMemoryCeilingMb: UInt64?
+    /// Keep the store and keys in memory and write no files; the paths are ignored and
+    /// everything is gone when the wallet is closed (tests, demo sessions)
+    public var inMemory: Bool?
+    /// Encrypt the keys of the filesystem keystore with this passphrase; required for a
+    /// keystore created with one (nil means unencrypted, see `rekeyKeystore`)
+    public var keystorePassphrase: String?
+    /// Encrypt the store with SQLCipher using this key; needs a framework built with the
+    /// `sqlcipher` feature (nil means unencrypted, see `changeStoreKey`)
+    public var storeKey: String?
+    /// Compression of node calls: `gzip` where the node supports it, or `none` (nil means
+    /// gzip)
+    public var compression: String?
+    /// Largest node response message in bytes, from 1024 up to 4 MiB (nil means 4 MiB)
+    public var maxMessageBytes: UInt64?
+    /// Profile of a store shared by several, 1-64 characters of `[A-Za-z0-9_-]`; the wallet
+    /// sees only the tenant's accounts, notes and keys (nil opens the whole store)
+    public var tenantId: String?
+    
+    public init(
+        keystorePath: String,
+        storePath: String,
+        rpcEndpoint: String? = nil,
+        networkId: String? = nil,
+        timeoutMs: UInt64? = nil,
+        debug: Bool? = nil,
+        workerThreads: UInt32? = nil,
+        requireSyncForCreate: Bool? = nil,
+        provingMemoryCeilingMb: UInt64? = nil,
+        inMemory: Bool? = nil,
+        keystorePassphrase: String? = nil,
+        storeKey: String? = nil,
+        compression: String? = nil,
+        maxMessageBytes: UInt64? = nil,
+        tenantId: String? = nil
+    ) {
+        self.keystorePath = keystorePath
+        self.storePath = storePath
+        self.rpcEndpoint = rpcEndpoint
+        self.networkId = networkId
+        self.timeoutMs = timeoutMs
+        self.debug = debug
+        self.workerThreads = workerThreads
+        self.requireSyncForCreate = requireSyncForCreate
+        self.provingMemoryCeilingMb = provingMemoryCeilingMb
+        self.inMemory = inMemory
+        self.keystorePassphrase = keystorePassphrase
+        self.storeKey = storeKey
+        self.compression = compression
+        self.maxMessageBytes = maxMessageBytes
+        self.tenantId = tenantId
+    }
+    
+    /// Settings of an in-memory wallet that writes no files
+    ///
+    /// - Parameters:
+    ///   - networkId: `testnet`, `devnet`, `localhost` or `localhost:<port>` (nil means testnet)
+    ///   - rpcEndpoint: Node URL, overriding `networkId`
+    public static func inMemory(networkId: String? = nil, rpcEndpoint: String? = nil) -> MidenClientConfig {
+        MidenClientConfig(
+            keystorePath: "",
+            storePath: "",
+            rpcEndpoint: rpcEndpoint,
+            networkId: networkId,
+            inMemory: true
+        )
+    }
+    
+    enum CodingKeys: String, CodingKey {
+        case keystorePath = "keystore_path"
+        case storePath = "store_path"
+        case rpcEndpoint = "rpc_endpoint"
+        case networkId = "network_id"
+        case timeoutMs = "timeout_ms"
+        case debug
+        case workerThreads = "worker_threads"
+        case requireSyncForCreate = "require_sync_for_create"
+        case provingMemoryCeilingMb = "proving_memory_ceiling_mb"
+        case inMemory = "in_memory"
+        case keystorePassphrase = "keystore_passphrase"
+        case storeKey = "store_key"
+        case compression
+        case maxMessageBytes = "max_message_bytes"
+        case tenantId = "tenant_id"
+    }
+}
+
+/// Result of `syncAccount`
+public struct AccountSyncResult: Codable {
+    public let accountId: String
+    public let blockNum: UInt32
+    public let nonce: UInt64
+    public let consumableNotes: Int
+    /// Node traffic of the sync in bytes
+    public let bytesSent: UInt64
+    public let bytesReceived: UInt64
+    
+    enum CodingKeys: String, CodingKey {
+        case accountId = "account_id"
+        case blockNum = "block_num"
+        case nonce
+        case consumableNotes = "consumable_notes"
+        case bytesSent = "bytes_sent"
+        case bytesReceived = "bytes_received"
+    }
+}
+
+/// Result of `auditState(accountId:repair:)`
+public struct StateAuditReport: Codable {
+    /// Difference between the local store and the node
+    public struct Discrepancy: Codable {
+        /// `stale_account` or `missing_consumption`
+        public let kind: String
+        public let localCommitment: String?
+        public let nodeCommitment: String?
+        public let noteId: String?
+        public let nullifier: String?
+        
+        enum CodingKeys: String, CodingKey {
+            case kind
+            case localCommitment = "local_commitment"
+            case nodeCommitment = "node_commitment"
+            case noteId = "note_id"
+            case nullifier
         }
-        return totals
+    }
+    
+    public let accountId: String
+    public let checkedNotes: Int
+    /// Uncommitted transactions of the account (a stale account state is expected then)
+    public let pendingTransactions: Int
+    public let consistent: Bool
+    public let discrepancies: [Discrepancy]
+    public let repairAttempted: Bool
+    /// Discrepancies found before the repair (only when one was attempted)
+    public let repairedFrom: [Discrepancy]?
+    
+    enum CodingKeys: String, CodingKey {
+        case accountId = "account_id"
+        case checkedNotes = "checked_notes"
+        case pendingTransactions = "pending_transactions"
+        case consistent
+        case discrepancies
+        case repairAttempted = "repair_attempted"
+        case repairedFrom = "repaired_from"
     }
 }
 
-/// Input Notes query result
-public struct InputNotesResult: Codable {
-    /// List of notes
-    public let notes: [InputNoteInfo]
-    /// Total count
-    public let totalCount: Int
+/// Result of `reclaimableNotes(accountId:)`
+public struct ReclaimableNotes: Codable {
+    public struct Note: Codable {
+        public let noteId: String
+        public let targetAccountId: String?
+        public let assets: [FungibleAsset]
+        public let reclaimBlock: UInt64
+        
+        enum CodingKeys: String, CodingKey {
+            case noteId = "note_id"
+            case targetAccountId = "target_account_id"
+            case assets
+            case reclaimBlock = "reclaim_block"
+        }
+    }
+    
+    /// Sync height the notes were checked against
+    public let currentBlock: UInt32
+    public let notes: [Note]
     
     enum CodingKeys: String, CodingKey {
+        case currentBlock = "current_block"
         case notes
-        case totalCount = "total_count"
+    }
+}
+
+/// Result of `estimateTime(forBlock:)`
+public struct BlockTimeEstimate: Codable {
+    public let blockNum: UInt32
+    /// Node tip as of the last sync
+    public let tip: UInt32
+    public let blocksRemaining: UInt32
+    public let averageBlockTimeMs: UInt64
+    /// False while too few blocks have been seen and a default block time is used
+    public let blockTimeMeasured: Bool
+    /// ISO-8601 time of the block
+    public let estimatedAt: String
+    /// Negative for past blocks
+    public let secondsFromNow: Int64
+    /// False when the block's actual timestamp is known
+    public let isEstimate: Bool
+    
+    enum CodingKeys: String, CodingKey {
+        case blockNum = "block_num"
+        case tip
+        case blocksRemaining = "blocks_remaining"
+        case averageBlockTimeMs = "average_block_time_ms"
+        case blockTimeMeasured = "block_time_measured"
+        case estimatedAt = "estimated_at"
+        case secondsFromNow = "seconds_from_now"
+        case isEstimate = "is_estimate"
     }
     
-    /// Get all consumable note IDs
-    public var consumableNoteIds: [String] {
-        notes.map { $0.noteId }
+    /// Time of the block
+    public var date: Date {
+        ISO8601DateFormatter().date(from: estimatedAt) ?? Date(timeIntervalSinceNow: TimeInterval(secondsFromNow))
     }
     
-    /// Whether there are consumable notes
-    public var hasConsumableNotes: Bool {
-        !notes.isEmpty
+    /// Localized relative time, e.g. "in 3 hours" or "vor 2 Tagen"
+    public func relativeDescription(locale: Locale = .current) -> String {
+        let formatter = RelativeDateTimeFormatter()
+        formatter.locale = locale
+        formatter.unitsStyle = .full
+        return formatter.localizedString(fromTimeInterval: TimeInterval(secondsFromNow))
+    }
+}
+
+/// Account details
+public struct AccountDetails: Codable {
+    public let accountId: String
+    /// `regular_immutable_code`, `regular_updatable_code`, `fungible_faucet` or `non_fungible_faucet`
+    public let accountType: String
+    /// `public`, `private` or `network`
+    public let storageMode: String
+    /// `new`, `tracked` or `locked`
+    public let status: String
+    /// Where the signing key is kept: `filesystem`, `callback` or `memory`
+    public let keyBackend: String
+    /// External signer scheme, for `callback` keys
+    public let signerSchemeId: UInt32?
+    /// Whether the account is archived (see `setAccountArchived`)
+    public let archived: Bool
+    
+    enum CodingKeys: String, CodingKey {
+        case accountId = "account_id"
+        case accountType = "account_type"
+        case storageMode = "storage_mode"
+        case status
+        case keyBackend = "key_backend"
+        case signerSchemeId = "signer_scheme_id"
+        case archived
+    }
+}
+
+/// Outcome of handling a push notification
+public struct PushNoteResult: Codable {
+    public let noteId: String
+    /// Whether the note was fetched and stored
+    public let imported: Bool
+    /// Whether a regular sync is needed to pick up the note (e.g. private notes)
+    public let requiresSync: Bool
+    
+    enum CodingKeys: String, CodingKey {
+        case noteId = "note_id"
+        case imported
+        case requiresSync = "requires_sync"
+    }
+}
+
+/// Outcome of a time-boxed background sync
+public struct BackgroundSyncResult: Codable {
+    /// Height reached, nil when no sync round completed within the budget
+    public let syncedToBlock: UInt32?
+    public let nodeTip: UInt32
+    public let blocksBehind: UInt32
+    /// Whether the app should sync again (e.g. schedule a processing task)
+    public let moreWorkRemaining: Bool
+    public let rounds: UInt32
+    public let elapsedMs: UInt64
+    /// Node traffic of all rounds in bytes
+    public let bytesSent: UInt64
+    public let bytesReceived: UInt64
+    
+    enum CodingKeys: String, CodingKey {
+        case syncedToBlock = "synced_to_block"
+        case nodeTip = "node_tip"
+        case blocksBehind = "blocks_behind"
+        case moreWorkRemaining = "more_work_remaining"
+        case rounds
+        case elapsedMs = "elapsed_ms"
+        case bytesSent = "bytes_sent"
+        case bytesReceived = "bytes_received"
+    }
+}
+
+/// Optional features supported by the linked framework build
+public struct BuildCapabilities: Codable {
+    /// Crate version of the Rust library
+    public let version: String
+    /// Capability flags keyed by feature name (e.g. "sqlcipher", "mnemonic")
+    public let capabilities: [String: Bool]
+    
+    /// Check whether a named capability is available
+    public func supports(_ capability: String) -> Bool {
+        capabilities[capability] ?? false
+    }
+}
+
+/// Outstanding Rust-allocated buffers (`memory-debug` builds)
+public struct MemoryReport: Codable {
+    public struct Allocation: Codable {
+        /// Buffer address (hex)
+        public let ptr: String
+        public let len: Int
+        /// Rust source location that produced the buffer (file:line:column)
+        public let site: String
+        /// Milliseconds since the buffer was handed out
+        public let ageMs: UInt64
+        
+        enum CodingKeys: String, CodingKey {
+            case ptr
+            case len
+            case site
+            case ageMs = "age_ms"
+        }
+    }
+    
+    /// False when the framework was built without `memory-debug` (other fields are nil)
+    public let enabled: Bool
+    public let outstandingCount: Int?
+    public let outstandingBytes: Int?
+    public let totalAllocated: UInt64?
+    public let totalFreed: UInt64?
+    /// `wc_bytes_free` calls with an unknown pointer (double free) or a wrong length
+    public let invalidFrees: UInt64?
+    public let allocations: [Allocation]?
+    
+    enum CodingKeys: String, CodingKey {
+        case enabled
+        case outstandingCount = "outstanding_count"
+        case outstandingBytes = "outstanding_bytes"
+        case totalAllocated = "total_allocated"
+        case totalFreed = "total_freed"
+        case invalidFrees = "invalid_frees"
+        case allocations
     }
 }
 
@@ -597,35 +3870,45 @@ extension MidenWallet {
             throw MidenError.invalidHandle
         }
         
-        return try await withCheckedThrowingContinuation { continuation in
-            let continuationPtr = Unmanaged.passRetained(
-                ContinuationBox(continuation: continuation)
-            ).toOpaque()
+        let operation = OperationHandle()
+        return try await withTaskCancellationHandler {
+            try await withCheckedThrowingContinuation { continuation in
+                let continuationPtr = Unmanaged.passRetained(
+                    ContinuationBox(continuation: continuation)
+                ).toOpaque()
             
-            let result = wc_miden_sync_async(h, { userData, errorCode, blockNum in
-                guard let userData = userData else { return }
-                let box = Unmanaged<ContinuationBox<UInt32>>.fromOpaque(userData).takeRetainedValue()
+                let op = wc_miden_sync_async(h, { userData, errorCode, blockNum in
+                    guard let userData = userData else { return }
+                    let box = Unmanaged<ContinuationBox<UInt32>>.fromOpaque(userData).takeRetainedValue()
                 
-                if errorCode == 0 {
-                    box.continuation.resume(returning: blockNum)
+                    if errorCode == 0 {
+                        box.continuation.resume(returning: blockNum)
+                    } else {
+                        box.continuation.resume(throwing: MidenError.syncFailed(code: errorCode))
+                    }
+                }, continuationPtr)
+            
+                if op < 0 {
+                    let result = Int32(op)
+                    let box = Unmanaged<ContinuationBox<UInt32>>.fromOpaque(continuationPtr).takeRetainedValue()
+                    box.continuation.resume(throwing: MidenError.syncFailed(code: result))
                 } else {
-                    box.continuation.resume(throwing: MidenError.syncFailed(code: errorCode))
+                    operation.set(op)
                 }
-            }, continuationPtr)
-            
-            if result != 0 {
-                let box = Unmanaged<ContinuationBox<UInt32>>.fromOpaque(continuationPtr).takeRetainedValue()
-                box.continuation.resume(throwing: MidenError.syncFailed(code: result))
             }
+        } onCancel: {
+            operation.cancel()
         }
     }
     
     /// Async version of createWallet - create a new wallet account
     ///
-    /// - Parameter seed: 32-byte seed (optional, nil auto-generates)
+    /// - Parameters:
+    ///   - seed: 32-byte seed (optional, nil auto-generates)
+    ///   - storageMode: `WcStorageMode_Public` or `WcStorageMode_Private`
     /// - Returns: Account ID (hex string)
     /// - Throws: If creation fails
-    public func createWalletAsync(seed: [UInt8]? = nil) async throws -> String {
+    public func createWalletAsync(seed: [UInt8]? = nil, storageMode: WcStorageMode = WcStorageMode_Public) async throws -> String {
         guard let h = handle else {
             throw MidenError.invalidHandle
         }
@@ -635,22 +3918,51 @@ extension MidenWallet {
             throw MidenError.invalidSeedLength
         }
         
-        return try await withCheckedThrowingContinuation { continuation in
-            let continuationPtr = Unmanaged.passRetained(
-                ContinuationBox(continuation: continuation)
-            ).toOpaque()
+        let operation = OperationHandle()
+        return try await withTaskCancellationHandler {
+            try await withCheckedThrowingContinuation { continuation in
+                let continuationPtr = Unmanaged.passRetained(
+                    ContinuationBox(continuation: continuation)
+                ).toOpaque()
             
-            let result: Int32
-            if let seed = seed {
-                result = seed.withUnsafeBytes { seedBytes in
-                    wc_miden_create_wallet_async(
+                let op: Int64
+                if let seed = seed {
+                    op = seed.withUnsafeBytes { seedBytes in
+                        wc_miden_create_wallet_async(
+                            h,
+                            seedBytes.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                            UInt(seed.count),
+                            storageMode.rawValue,
+                            { userData, errorCode, dataPtr, dataLen in
+                                guard let userData = userData else { return }
+                                let box = Unmanaged<ContinuationBox<String>>.fromOpaque(userData).takeRetainedValue()
+                            
+                                if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
+                                    let data = Data(bytes: dataPtr, count: Int(dataLen))
+                                    // Free Rust-allocated memory
+                                    wc_bytes_free(dataPtr, dataLen)
+                                    if let str = String(data: data, encoding: .utf8) {
+                                        box.continuation.resume(returning: str)
+                                    } else {
+                                        box.continuation.resume(throwing: MidenError.invalidAccountId)
+                                    }
+                                } else {
+                                    box.continuation.resume(throwing: MidenError.createWallet(code: errorCode))
+                                }
+                            },
+                            continuationPtr
+                        )
+                    }
+                } else {
+                    op = wc_miden_create_wallet_async(
                         h,
-                        seedBytes.baseAddress?.assumingMemoryBound(to: UInt8.self),
-                        UInt(seed.count),
+                        nil,
+                        0,
+                        storageMode.rawValue,
                         { userData, errorCode, dataPtr, dataLen in
                             guard let userData = userData else { return }
                             let box = Unmanaged<ContinuationBox<String>>.fromOpaque(userData).takeRetainedValue()
-                            
+                        
                             if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
                                 let data = Data(bytes: dataPtr, count: Int(dataLen))
                                 // Free Rust-allocated memory
@@ -661,42 +3973,23 @@ extension MidenWallet {
                                     box.continuation.resume(throwing: MidenError.invalidAccountId)
                                 }
                             } else {
-                                box.continuation.resume(throwing: MidenError.createWalletFailed(code: errorCode))
+                                box.continuation.resume(throwing: MidenError.createWallet(code: errorCode))
                             }
                         },
                         continuationPtr
                     )
                 }
-            } else {
-                result = wc_miden_create_wallet_async(
-                    h,
-                    nil,
-                    0,
-                    { userData, errorCode, dataPtr, dataLen in
-                        guard let userData = userData else { return }
-                        let box = Unmanaged<ContinuationBox<String>>.fromOpaque(userData).takeRetainedValue()
-                        
-                        if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
-                            let data = Data(bytes: dataPtr, count: Int(dataLen))
-                            // Free Rust-allocated memory
-                            wc_bytes_free(dataPtr, dataLen)
-                            if let str = String(data: data, encoding: .utf8) {
-                                box.continuation.resume(returning: str)
-                            } else {
-                                box.continuation.resume(throwing: MidenError.invalidAccountId)
-                            }
-                        } else {
-                            box.continuation.resume(throwing: MidenError.createWalletFailed(code: errorCode))
-                        }
-                    },
-                    continuationPtr
-                )
-            }
             
-            if result != 0 {
-                let box = Unmanaged<ContinuationBox<String>>.fromOpaque(continuationPtr).takeRetainedValue()
-                box.continuation.resume(throwing: MidenError.createWalletFailed(code: result))
+                if op < 0 {
+                    let result = Int32(op)
+                    let box = Unmanaged<ContinuationBox<String>>.fromOpaque(continuationPtr).takeRetainedValue()
+                    box.continuation.resume(throwing: MidenError.createWallet(code: result))
+                } else {
+                    operation.set(op)
+                }
             }
+        } onCancel: {
+            operation.cancel()
         }
     }
     
@@ -709,39 +4002,47 @@ extension MidenWallet {
             throw MidenError.invalidHandle
         }
         
-        return try await withCheckedThrowingContinuation { continuation in
-            let continuationPtr = Unmanaged.passRetained(
-                ContinuationBox(continuation: continuation)
-            ).toOpaque()
+        let operation = OperationHandle()
+        return try await withTaskCancellationHandler {
+            try await withCheckedThrowingContinuation { continuation in
+                let continuationPtr = Unmanaged.passRetained(
+                    ContinuationBox(continuation: continuation)
+                ).toOpaque()
             
-            let result = wc_miden_get_accounts_async(h, { userData, errorCode, dataPtr, dataLen in
-                guard let userData = userData else { return }
-                let box = Unmanaged<ContinuationBox<[String]>>.fromOpaque(userData).takeRetainedValue()
+                let op = wc_miden_get_accounts_async(h, { userData, errorCode, dataPtr, dataLen in
+                    guard let userData = userData else { return }
+                    let box = Unmanaged<ContinuationBox<[String]>>.fromOpaque(userData).takeRetainedValue()
                 
-                if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
-                    let data = Data(bytes: dataPtr, count: Int(dataLen))
-                    // Free Rust-allocated memory
-                    wc_bytes_free(dataPtr, dataLen)
-                    if let jsonString = String(data: data, encoding: .utf8),
-                       let jsonData = jsonString.data(using: .utf8) {
-                        do {
-                            let accounts = try JSONDecoder().decode([String].self, from: jsonData)
-                            box.continuation.resume(returning: accounts)
-                        } catch {
-                            box.continuation.resume(throwing: MidenError.jsonDecodeFailed(error: error))
+                    if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
+                        let data = Data(bytes: dataPtr, count: Int(dataLen))
+                        // Free Rust-allocated memory
+                        wc_bytes_free(dataPtr, dataLen)
+                        if let jsonString = String(data: data, encoding: .utf8),
+                           let jsonData = jsonString.data(using: .utf8) {
+                            do {
+                                let accounts = try JSONDecoder().decode([String].self, from: jsonData)
+                                box.continuation.resume(returning: accounts)
+                            } catch {
+                                box.continuation.resume(throwing: MidenError.jsonDecodeFailed(error: error))
+                            }
+                        } else {
+                            box.continuation.resume(throwing: MidenError.invalidJSON)
                         }
                     } else {
-                        box.continuation.resume(throwing: MidenError.invalidJSON)
+                        box.continuation.resume(throwing: MidenError.getAccountsFailed(code: errorCode))
                     }
+                }, continuationPtr)
+            
+                if op < 0 {
+                    let result = Int32(op)
+                    let box = Unmanaged<ContinuationBox<[String]>>.fromOpaque(continuationPtr).takeRetainedValue()
+                    box.continuation.resume(throwing: MidenError.getAccountsFailed(code: result))
                 } else {
-                    box.continuation.resume(throwing: MidenError.getAccountsFailed(code: errorCode))
+                    operation.set(op)
                 }
-            }, continuationPtr)
-            
-            if result != 0 {
-                let box = Unmanaged<ContinuationBox<[String]>>.fromOpaque(continuationPtr).takeRetainedValue()
-                box.continuation.resume(throwing: MidenError.getAccountsFailed(code: result))
             }
+        } onCancel: {
+            operation.cancel()
         }
     }
     
@@ -755,47 +4056,55 @@ extension MidenWallet {
             throw MidenError.invalidHandle
         }
         
-        return try await withCheckedThrowingContinuation { continuation in
-            let continuationPtr = Unmanaged.passRetained(
-                ContextContinuationBox(continuation: continuation, context: accountId)
-            ).toOpaque()
+        let operation = OperationHandle()
+        return try await withTaskCancellationHandler {
+            try await withCheckedThrowingContinuation { continuation in
+                let continuationPtr = Unmanaged.passRetained(
+                    ContextContinuationBox(continuation: continuation, context: accountId)
+                ).toOpaque()
             
-            let result = accountId.withCString { accountIdPtr in
-                wc_miden_get_balance_async(h, accountIdPtr, { userData, errorCode, dataPtr, dataLen in
-                    guard let userData = userData else { return }
-                    let box = Unmanaged<ContextContinuationBox<AccountBalance, String>>.fromOpaque(userData).takeRetainedValue()
+                let op = accountId.withCString { accountIdPtr in
+                    wc_miden_get_balance_async(h, accountIdPtr, { userData, errorCode, dataPtr, dataLen in
+                        guard let userData = userData else { return }
+                        let box = Unmanaged<ContextContinuationBox<AccountBalance, String>>.fromOpaque(userData).takeRetainedValue()
                     
-                    if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
-                        let data = Data(bytes: dataPtr, count: Int(dataLen))
-                        // Free Rust-allocated memory
-                        wc_bytes_free(dataPtr, dataLen)
-                        if let jsonString = String(data: data, encoding: .utf8),
-                           let jsonData = jsonString.data(using: .utf8) {
-                            do {
-                                let balance = try JSONDecoder().decode(AccountBalance.self, from: jsonData)
-                                box.continuation.resume(returning: balance)
-                            } catch {
-                                box.continuation.resume(throwing: MidenError.jsonDecodeFailed(error: error))
+                        if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
+                            let data = Data(bytes: dataPtr, count: Int(dataLen))
+                            // Free Rust-allocated memory
+                            wc_bytes_free(dataPtr, dataLen)
+                            if let jsonString = String(data: data, encoding: .utf8),
+                               let jsonData = jsonString.data(using: .utf8) {
+                                do {
+                                    let balance = try JSONDecoder().decode(AccountBalance.self, from: jsonData)
+                                    box.continuation.resume(returning: balance)
+                                } catch {
+                                    box.continuation.resume(throwing: MidenError.jsonDecodeFailed(error: error))
+                                }
+                            } else {
+                                box.continuation.resume(throwing: MidenError.invalidJSON)
                             }
+                        } else if errorCode == -4 {
+                            box.continuation.resume(throwing: MidenError.accountNotFound(accountId: box.context))
                         } else {
-                            box.continuation.resume(throwing: MidenError.invalidJSON)
+                            box.continuation.resume(throwing: MidenError.getBalanceFailed(code: errorCode))
                         }
-                    } else if errorCode == -4 {
-                        box.continuation.resume(throwing: MidenError.accountNotFound(accountId: box.context))
+                    }, continuationPtr)
+                }
+            
+                if op < 0 {
+                    let result = Int32(op)
+                    let box = Unmanaged<ContextContinuationBox<AccountBalance, String>>.fromOpaque(continuationPtr).takeRetainedValue()
+                    if result == -3 {
+                        box.continuation.resume(throwing: MidenError.invalidAccountId)
                     } else {
-                        box.continuation.resume(throwing: MidenError.getBalanceFailed(code: errorCode))
+                        box.continuation.resume(throwing: MidenError.getBalanceFailed(code: result))
                     }
-                }, continuationPtr)
-            }
-            
-            if result != 0 {
-                let box = Unmanaged<ContextContinuationBox<AccountBalance, String>>.fromOpaque(continuationPtr).takeRetainedValue()
-                if result == -3 {
-                    box.continuation.resume(throwing: MidenError.invalidAccountId)
                 } else {
-                    box.continuation.resume(throwing: MidenError.getBalanceFailed(code: result))
+                    operation.set(op)
                 }
             }
+        } onCancel: {
+            operation.cancel()
         }
     }
     
@@ -808,26 +4117,34 @@ extension MidenWallet {
             throw MidenError.invalidHandle
         }
         
-        return try await withCheckedThrowingContinuation { continuation in
-            let continuationPtr = Unmanaged.passRetained(
-                ContinuationBox(continuation: continuation)
-            ).toOpaque()
+        let operation = OperationHandle()
+        return try await withTaskCancellationHandler {
+            try await withCheckedThrowingContinuation { continuation in
+                let continuationPtr = Unmanaged.passRetained(
+                    ContinuationBox(continuation: continuation)
+                ).toOpaque()
             
-            let result = wc_miden_test_connection_async(h, { userData, errorCode in
-                guard let userData = userData else { return }
-                let box = Unmanaged<ContinuationBox<Bool>>.fromOpaque(userData).takeRetainedValue()
+                let op = wc_miden_test_connection_async(h, { userData, errorCode in
+                    guard let userData = userData else { return }
+                    let box = Unmanaged<ContinuationBox<Bool>>.fromOpaque(userData).takeRetainedValue()
                 
-                if errorCode == 0 {
-                    box.continuation.resume(returning: true)
+                    if errorCode == 0 {
+                        box.continuation.resume(returning: true)
+                    } else {
+                        box.continuation.resume(throwing: MidenError.connectionTestFailed(code: errorCode))
+                    }
+                }, continuationPtr)
+            
+                if op < 0 {
+                    let result = Int32(op)
+                    let box = Unmanaged<ContinuationBox<Bool>>.fromOpaque(continuationPtr).takeRetainedValue()
+                    box.continuation.resume(throwing: MidenError.connectionTestFailed(code: result))
                 } else {
-                    box.continuation.resume(throwing: MidenError.connectionTestFailed(code: errorCode))
+                    operation.set(op)
                 }
-            }, continuationPtr)
-            
-            if result != 0 {
-                let box = Unmanaged<ContinuationBox<Bool>>.fromOpaque(continuationPtr).takeRetainedValue()
-                box.continuation.resume(throwing: MidenError.connectionTestFailed(code: result))
             }
+        } onCancel: {
+            operation.cancel()
         }
     }
     
@@ -841,18 +4158,45 @@ extension MidenWallet {
             throw MidenError.invalidHandle
         }
         
-        return try await withCheckedThrowingContinuation { continuation in
-            let continuationPtr = Unmanaged.passRetained(
-                ContinuationBox(continuation: continuation)
-            ).toOpaque()
+        let operation = OperationHandle()
+        return try await withTaskCancellationHandler {
+            try await withCheckedThrowingContinuation { continuation in
+                let continuationPtr = Unmanaged.passRetained(
+                    ContinuationBox(continuation: continuation)
+                ).toOpaque()
             
-            let result: Int32
-            if let accountId = accountId {
-                result = accountId.withCString { accountIdPtr in
-                    wc_miden_get_input_notes_async(h, accountIdPtr, { userData, errorCode, dataPtr, dataLen in
+                let op: Int64
+                if let accountId = accountId {
+                    op = accountId.withCString { accountIdPtr in
+                        wc_miden_get_input_notes_async(h, accountIdPtr, { userData, errorCode, dataPtr, dataLen in
+                            guard let userData = userData else { return }
+                            let box = Unmanaged<ContinuationBox<InputNotesResult>>.fromOpaque(userData).takeRetainedValue()
+                        
+                            if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
+                                let data = Data(bytes: dataPtr, count: Int(dataLen))
+                                // Free Rust-allocated memory
+                                wc_bytes_free(dataPtr, dataLen)
+                                if let jsonString = String(data: data, encoding: .utf8),
+                                   let jsonData = jsonString.data(using: .utf8) {
+                                    do {
+                                        let notes = try JSONDecoder().decode(InputNotesResult.self, from: jsonData)
+                                        box.continuation.resume(returning: notes)
+                                    } catch {
+                                        box.continuation.resume(throwing: MidenError.jsonDecodeFailed(error: error))
+                                    }
+                                } else {
+                                    box.continuation.resume(throwing: MidenError.invalidJSON)
+                                }
+                            } else {
+                                box.continuation.resume(throwing: MidenError.getInputNotesFailed(code: errorCode))
+                            }
+                        }, continuationPtr)
+                    }
+                } else {
+                    op = wc_miden_get_input_notes_async(h, nil, { userData, errorCode, dataPtr, dataLen in
                         guard let userData = userData else { return }
                         let box = Unmanaged<ContinuationBox<InputNotesResult>>.fromOpaque(userData).takeRetainedValue()
-                        
+                    
                         if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
                             let data = Data(bytes: dataPtr, count: Int(dataLen))
                             // Free Rust-allocated memory
@@ -873,40 +4217,21 @@ extension MidenWallet {
                         }
                     }, continuationPtr)
                 }
-            } else {
-                result = wc_miden_get_input_notes_async(h, nil, { userData, errorCode, dataPtr, dataLen in
-                    guard let userData = userData else { return }
-                    let box = Unmanaged<ContinuationBox<InputNotesResult>>.fromOpaque(userData).takeRetainedValue()
-                    
-                    if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
-                        let data = Data(bytes: dataPtr, count: Int(dataLen))
-                        // Free Rust-allocated memory
-                        wc_bytes_free(dataPtr, dataLen)
-                        if let jsonString = String(data: data, encoding: .utf8),
-                           let jsonData = jsonString.data(using: .utf8) {
-                            do {
-                                let notes = try JSONDecoder().decode(InputNotesResult.self, from: jsonData)
-                                box.continuation.resume(returning: notes)
-                            } catch {
-                                box.continuation.resume(throwing: MidenError.jsonDecodeFailed(error: error))
-                            }
-                        } else {
-                            box.continuation.resume(throwing: MidenError.invalidJSON)
-                        }
+            
+                if op < 0 {
+                    let result = Int32(op)
+                    let box = Unmanaged<ContinuationBox<InputNotesResult>>.fromOpaque(continuationPtr).takeRetainedValue()
+                    if result == -3 {
+                        box.continuation.resume(throwing: MidenError.invalidAccountId)
                     } else {
-                        box.continuation.resume(throwing: MidenError.getInputNotesFailed(code: errorCode))
+                        box.continuation.resume(throwing: MidenError.getInputNotesFailed(code: result))
                     }
-                }, continuationPtr)
-            }
-            
-            if result != 0 {
-                let box = Unmanaged<ContinuationBox<InputNotesResult>>.fromOpaque(continuationPtr).takeRetainedValue()
-                if result == -3 {
-                    box.continuation.resume(throwing: MidenError.invalidAccountId)
                 } else {
-                    box.continuation.resume(throwing: MidenError.getInputNotesFailed(code: result))
+                    operation.set(op)
                 }
             }
+        } onCancel: {
+            operation.cancel()
         }
     }
     
@@ -928,51 +4253,407 @@ extension MidenWallet {
         
         let noteIdsJson = "[" + noteIds.map { "\"\($0)\"" }.joined(separator: ",") + "]"
         
-        return try await withCheckedThrowingContinuation { continuation in
-            let continuationPtr = Unmanaged.passRetained(
-                ContinuationBox(continuation: continuation)
-            ).toOpaque()
+        let operation = OperationHandle()
+        return try await withTaskCancellationHandler {
+            try await withCheckedThrowingContinuation { continuation in
+                let continuationPtr = Unmanaged.passRetained(
+                    ContinuationBox(continuation: continuation)
+                ).toOpaque()
+            
+                let op = accountId.withCString { accountIdPtr in
+                    noteIdsJson.withCString { noteIdsPtr in
+                        wc_miden_consume_notes_async(h, accountIdPtr, noteIdsPtr, { userData, errorCode, dataPtr, dataLen in
+                            guard let userData = userData else { return }
+                            let box = Unmanaged<ContinuationBox<String>>.fromOpaque(userData).takeRetainedValue()
+                        
+                            if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
+                                let data = Data(bytes: dataPtr, count: Int(dataLen))
+                                // Free Rust-allocated memory
+                                wc_bytes_free(dataPtr, dataLen)
+                                if let str = String(data: data, encoding: .utf8) {
+                                    box.continuation.resume(returning: str)
+                                } else {
+                                    box.continuation.resume(throwing: MidenError.invalidJSON)
+                                }
+                            } else if errorCode == -5 {
+                                box.continuation.resume(throwing: MidenError.consumeNotesFailed(code: errorCode, message: "Transaction creation failed"))
+                            } else if errorCode == -6 {
+                                box.continuation.resume(throwing: MidenError.consumeNotesFailed(code: errorCode, message: "Transaction submission failed"))
+                            } else {
+                                box.continuation.resume(throwing: MidenError.consumeNotesFailed(code: errorCode, message: nil))
+                            }
+                        }, continuationPtr)
+                    }
+                }
+            
+                if op < 0 {
+                    let result = Int32(op)
+                    let box = Unmanaged<ContinuationBox<String>>.fromOpaque(continuationPtr).takeRetainedValue()
+                    if result == -3 {
+                        box.continuation.resume(throwing: MidenError.invalidAccountId)
+                    } else if result == -4 {
+                        box.continuation.resume(throwing: MidenError.invalidNoteId)
+                    } else {
+                        box.continuation.resume(throwing: MidenError.consumeNotesFailed(code: result, message: nil))
+                    }
+                } else {
+                    operation.set(op)
+                }
+            }
+        } onCancel: {
+            operation.cancel()
+        }
+    }
+    
+    /// Async version of send - pay another account with a public P2ID note
+    ///
+    /// - Parameters:
+    ///   - accountId: Sending account ID
+    ///   - targetAccountId: Receiving account ID
+    ///   - faucetId: Faucet of the sent asset
+    ///   - amount: Amount in base units
+    /// - Returns: Transaction ID and created note ID
+    /// - Throws: If the send fails
+    public func sendAsync(accountId: String, targetAccountId: String, faucetId: String, amount: UInt64) async throws -> SendResult {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        let operation = OperationHandle()
+        return try await withTaskCancellationHandler {
+            try await withCheckedThrowingContinuation { continuation in
+                let continuationPtr = Unmanaged.passRetained(
+                    ContinuationBox(continuation: continuation)
+                ).toOpaque()
+            
+                let op = accountId.withCString { accountIdPtr in
+                    targetAccountId.withCString { targetPtr in
+                        faucetId.withCString { faucetPtr in
+                            wc_miden_send_async(h, accountIdPtr, targetPtr, faucetPtr, amount, { userData, errorCode, dataPtr, dataLen in
+                                guard let userData = userData else { return }
+                                let box = Unmanaged<ContinuationBox<SendResult>>.fromOpaque(userData).takeRetainedValue()
+                            
+                                if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
+                                    let data = Data(bytes: dataPtr, count: Int(dataLen))
+                                    // Free Rust-allocated memory
+                                    wc_bytes_free(dataPtr, dataLen)
+                                    do {
+                                        box.continuation.resume(returning: try JSONDecoder().decode(SendResult.self, from: data))
+                                    } catch {
+                                        box.continuation.resume(throwing: MidenError.jsonDecodeFailed(error: error))
+                                    }
+                                } else if errorCode == -6 {
+                                    box.continuation.resume(throwing: MidenError.sendFailed(code: errorCode, message: "Transaction submission failed"))
+                                } else {
+                                    box.continuation.resume(throwing: MidenError.sendFailed(code: errorCode, message: nil))
+                                }
+                            }, continuationPtr)
+                        }
+                    }
+                }
             
-            let result = accountId.withCString { accountIdPtr in
-                noteIdsJson.withCString { noteIdsPtr in
-                    wc_miden_consume_notes_async(h, accountIdPtr, noteIdsPtr, { userData, errorCode, dataPtr, dataLen in
+                if op < 0 {
+                    let result = Int32(op)
+                    let box = Unmanaged<ContinuationBox<SendResult>>.fromOpaque(continuationPtr).takeRetainedValue()
+                    if result == -3 {
+                        box.continuation.resume(throwing: MidenError.invalidAccountId)
+                    } else {
+                        box.continuation.resume(throwing: MidenError.sendFailed(code: result, message: nil))
+                    }
+                } else {
+                    operation.set(op)
+                }
+            }
+        } onCancel: {
+            operation.cancel()
+        }
+    }
+    
+    /// Async version of sendPrivate - pay another account with a private P2ID note
+    ///
+    /// - Parameters:
+    ///   - accountId: Sending account ID
+    ///   - targetAccountId: Receiving account ID
+    ///   - faucetId: Faucet of the sent asset
+    ///   - amount: Amount in base units
+    /// - Returns: Transaction ID, created note ID and the NoteFile for the recipient
+    /// - Throws: If the send fails
+    public func sendPrivateAsync(accountId: String, targetAccountId: String, faucetId: String, amount: UInt64) async throws -> SendResult {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        let operation = OperationHandle()
+        return try await withTaskCancellationHandler {
+            try await withCheckedThrowingContinuation { continuation in
+                let continuationPtr = Unmanaged.passRetained(
+                    ContinuationBox(continuation: continuation)
+                ).toOpaque()
+            
+                let op = accountId.withCString { accountIdPtr in
+                    targetAccountId.withCString { targetPtr in
+                        faucetId.withCString { faucetPtr in
+                            wc_miden_send_private_async(h, accountIdPtr, targetPtr, faucetPtr, amount, { userData, errorCode, dataPtr, dataLen in
+                                guard let userData = userData else { return }
+                                let box = Unmanaged<ContinuationBox<SendResult>>.fromOpaque(userData).takeRetainedValue()
+                            
+                                if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
+                                    let data = Data(bytes: dataPtr, count: Int(dataLen))
+                                    // Free Rust-allocated memory
+                                    wc_bytes_free(dataPtr, dataLen)
+                                    do {
+                                        box.continuation.resume(returning: try JSONDecoder().decode(SendResult.self, from: data))
+                                    } catch {
+                                        box.continuation.resume(throwing: MidenError.jsonDecodeFailed(error: error))
+                                    }
+                                } else if errorCode == -6 {
+                                    box.continuation.resume(throwing: MidenError.sendFailed(code: errorCode, message: "Transaction submission failed"))
+                                } else {
+                                    box.continuation.resume(throwing: MidenError.sendFailed(code: errorCode, message: nil))
+                                }
+                            }, continuationPtr)
+                        }
+                    }
+                }
+            
+                if op < 0 {
+                    let result = Int32(op)
+                    let box = Unmanaged<ContinuationBox<SendResult>>.fromOpaque(continuationPtr).takeRetainedValue()
+                    if result == -3 {
+                        box.continuation.resume(throwing: MidenError.invalidAccountId)
+                    } else {
+                        box.continuation.resume(throwing: MidenError.sendFailed(code: result, message: nil))
+                    }
+                } else {
+                    operation.set(op)
+                }
+            }
+        } onCancel: {
+            operation.cancel()
+        }
+    }
+    
+@@PA@@    /// Import a public account from the node and track it (async)
+    ///
+    /// - Parameter accountId: Public account ID
+    /// - Returns: Details of the imported account
+    /// - Throws: If the ID is invalid or private, or the node does not know the account
+    public func importAccountAsync(accountId: String) async throws -> AccountDetails {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        let operation = OperationHandle()
+        return try await withTaskCancellationHandler {
+            try await withCheckedThrowingContinuation { continuation in
+                let continuationPtr = Unmanaged.passRetained(
+                    ContinuationBox(continuation: continuation)
+                ).toOpaque()
+            
+                let op = accountId.withCString { accountIdPtr in
+                    wc_miden_import_account_by_id_async(h, accountIdPtr, { userData, errorCode, dataPtr, dataLen in
+                        guard let userData = userData else { return }
+                        let box = Unmanaged<ContinuationBox<AccountDetails>>.fromOpaque(userData).takeRetainedValue()
+                    
+                        if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
+                            let data = Data(bytes: dataPtr, count: Int(dataLen))
+                            // Free Rust-allocated memory
+                            wc_bytes_free(dataPtr, dataLen)
+                            do {
+                                box.continuation.resume(returning: try JSONDecoder().decode(AccountDetails.self, from: data))
+                            } catch {
+                                box.continuation.resume(throwing: MidenError.jsonDecodeFailed(error: error))
+                            }
+                        } else {
+                            box.continuation.resume(throwing: MidenError.importAccountFailed(code: errorCode))
+                        }
+                    }, continuationPtr)
+                }
+            
+                if op < 0 {
+                    let result = Int32(op)
+                    let box = Unmanaged<ContinuationBox<AccountDetails>>.fromOpaque(continuationPtr).takeRetainedValue()
+                    if result == -1 || result == -3 {
+                        box.continuation.resume(throwing: MidenError.invalidAccountId)
+                    } else {
+                        box.continuation.resume(throwing: MidenError.importAccountFailed(code: result))
+                    }
+                } else {
+                    operation.set(op)
+                }
+            }
+        } onCancel: {
+            operation.cancel()
+        }
+    }
+    
+    /// Deploy a newly created account on chain
+    ///
+    /// Submits the account's first transaction so the node knows it before it sends or
+    /// receives anything. Only accounts with the `new` status and a key in this store can
+    /// be deployed.
+    ///
+    /// - Parameter accountId: Account ID (hex) of a wallet created in this store
+    /// - Returns: Transaction ID (hex); track it with `waitForTransaction(txId:)`
+    /// - Throws: `MidenError.deployFailed` if the account is already deployed, unknown or
+    ///   the transaction fails
+    public func deployAccount(accountId: String) async throws -> String {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        let operation = OperationHandle()
+        return try await withTaskCancellationHandler {
+            try await withCheckedThrowingContinuation { continuation in
+                let continuationPtr = Unmanaged.passRetained(
+                    ContinuationBox(continuation: continuation)
+                ).toOpaque()
+            
+                let op = accountId.withCString { accountIdPtr in
+                    wc_miden_deploy_account(h, accountIdPtr, { userData, errorCode, dataPtr, dataLen in
                         guard let userData = userData else { return }
                         let box = Unmanaged<ContinuationBox<String>>.fromOpaque(userData).takeRetainedValue()
-                        
+                    
                         if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
                             let data = Data(bytes: dataPtr, count: Int(dataLen))
                             // Free Rust-allocated memory
                             wc_bytes_free(dataPtr, dataLen)
-                            if let str = String(data: data, encoding: .utf8) {
-                                box.continuation.resume(returning: str)
+                            if let txId = String(data: data, encoding: .utf8) {
+                                box.continuation.resume(returning: txId)
                             } else {
                                 box.continuation.resume(throwing: MidenError.invalidJSON)
                             }
-                        } else if errorCode == -5 {
-                            box.continuation.resume(throwing: MidenError.consumeNotesFailed(code: errorCode, message: "Transaction creation failed"))
-                        } else if errorCode == -6 {
-                            box.continuation.resume(throwing: MidenError.consumeNotesFailed(code: errorCode, message: "Transaction submission failed"))
                         } else {
-                            box.continuation.resume(throwing: MidenError.consumeNotesFailed(code: errorCode, message: nil))
+                            box.continuation.resume(throwing: MidenError.deployFailed(code: errorCode))
                         }
                     }, continuationPtr)
                 }
+            
+                if op < 0 {
+                    let result = Int32(op)
+                    let box = Unmanaged<ContinuationBox<String>>.fromOpaque(continuationPtr).takeRetainedValue()
+                    if result == -1 || result == -3 {
+                        box.continuation.resume(throwing: MidenError.invalidAccountId)
+                    } else {
+                        box.continuation.resume(throwing: MidenError.deployFailed(code: result))
+                    }
+                } else {
+                    operation.set(op)
+                }
             }
+        } onCancel: {
+            operation.cancel()
+        }
+    }
+    
+    /// Wait until a transaction is committed or discarded
+    ///
+    /// Syncs every few seconds until the transaction leaves `pending`. Cancelling the task
+    /// stops the wait (the transaction itself is unaffected).
+    ///
+    /// - Parameters:
+    ///   - txId: Transaction ID (hex) submitted through this wallet
+    ///   - timeout: Longest wait in seconds (at most 600)
+    /// - Returns: Transaction record; still `.pending` if the timeout elapsed first
+    /// - Throws: If the transaction is not in the store or the wait is cancelled
+    public func waitForTransaction(txId: String, timeout: TimeInterval = 60) async throws -> TransactionInfo {
+        guard let h = handle else {
+            throw MidenError.invalidHandle
+        }
+        
+        let timeoutMs = UInt64(max(timeout, 0) * 1000)
+        let operation = OperationHandle()
+        return try await withTaskCancellationHandler {
+            try await withCheckedThrowingContinuation { continuation in
+                let continuationPtr = Unmanaged.passRetained(
+                    ContinuationBox(continuation: continuation)
+                ).toOpaque()
+            
+                let op = txId.withCString { txIdPtr in
+                    wc_miden_wait_for_transaction(h, txIdPtr, timeoutMs, { userData, errorCode, dataPtr, dataLen in
+                        guard let userData = userData else { return }
+                        let box = Unmanaged<ContinuationBox<TransactionInfo>>.fromOpaque(userData).takeRetainedValue()
+                    
+                        if errorCode == 0, let dataPtr = dataPtr, dataLen > 0 {
+                            let data = Data(bytes: dataPtr, count: Int(dataLen))
+                            // Free Rust-allocated memory
+                            wc_bytes_free(dataPtr, dataLen)
+                            do {
+                                box.continuation.resume(returning: try JSONDecoder().decode(TransactionInfo.self, from: data))
+                            } catch {
+                                box.continuation.resume(throwing: MidenError.jsonDecodeFailed(error: error))
+                            }
+                        } else {
+                            box.continuation.resume(throwing: MidenError.transactionStatusFailed(code: errorCode))
+                        }
+                    }, continuationPtr)
+                }
             
-            if result != 0 {
-                let box = Unmanaged<ContinuationBox<String>>.fromOpaque(continuationPtr).takeRetainedValue()
-                if result == -3 {
-                    box.continuation.resume(throwing: MidenError.invalidAccountId)
-                } else if result == -4 {
-                    box.continuation.resume(throwing: MidenError.invalidNoteId)
+                if op < 0 {
+                    let result = Int32(op)
+                    let box = Unmanaged<ContinuationBox<TransactionInfo>>.fromOpaque(continuationPtr).takeRetainedValue()
+                    box.continuation.resume(throwing: MidenError.transactionStatusFailed(code: result))
                 } else {
-                    box.continuation.resume(throwing: MidenError.consumeNotesFailed(code: result, message: nil))
+                    operation.set(op)
                 }
             }
+        } onCancel: {
+            operation.cancel()
+        }
+    }
+    
+    /// Close the wallet without blocking
+    ///
+    /// The wallet is unusable from this call on (methods throw `invalidHandle`); requests
+    /// already queued still finish. Returns once the store is closed, e.g. before opening
+    /// the same store again. Closing a closed wallet returns immediately.
+    public func close() async {
+        guard var h = handle else {
+            return
+        }
+        handle = nil
+        
+        _ = try? await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+            let continuationPtr = Unmanaged.passRetained(
+                ContinuationBox(continuation: continuation)
+            ).toOpaque()
+            wc_miden_destroy_async(&h, { userData in
+                guard let userData = userData else { return }
+                let box = Unmanaged<ContinuationBox<Void>>.fromOpaque(userData).takeRetainedValue()
+                box.continuation.resume()
+            }, continuationPtr)
         }
     }
 }
 
+// MARK: - External Signers
+
+/// Holder of account keys outside this library
+///
+/// Called on the wallet's worker thread; implementations may block, e.g. while the user
+/// confirms with Face ID or on a hardware device. Throwing fails the signing request.
+public protocol MidenSigner: AnyObject {
+    /// 32-byte public key commitment of the RPO Falcon512 key at `keyIndex`
+    func publicKeyCommitment(keyIndex: UInt32) throws -> Data
+    
+    /// Serialized RPO Falcon512 signature over the 32-byte `message` with the key whose
+    /// commitment is `pubKey`
+    func sign(pubKey: Data, message: Data) throws -> Data
+}
+
+/// Registered signer, referenced by the C callbacks through `user_data`
+private final class SignerBox {
+    static let lock = NSLock()
+    /// Keeps each registered signer alive while Rust may call it
+    static var registered: [UInt32: SignerBox] = [:]
+    
+    let signer: MidenSigner
+    
+    init(signer: MidenSigner) {
+        self.signer = signer
+    }
+}
+
 // MARK: - Helper Types for Async
 
 /// Box type to hold continuation for passing through C callback
@@ -984,6 +4665,34 @@ private final class ContinuationBox<T> {
     }
 }
 
+/// Operation handle of an async call, cancelled when the calling task is cancelled
+///
+/// The task can be cancelled before the C call has returned the handle; the cancellation
+/// is then applied as soon as the handle is set.
+private final class OperationHandle: @unchecked Sendable {
+    private let lock = NSLock()
+    private var op: Int64 = 0
+    private var cancelled = false
+    
+    func set(_ op: Int64) {
+        lock.lock()
+        defer { lock.unlock() }
+        self.op = op
+        if cancelled {
+            _ = wc_miden_cancel(UInt64(op))
+        }
+    }
+    
+    func cancel() {
+        lock.lock()
+        defer { lock.unlock() }
+        cancelled = true
+        if op > 0 {
+            _ = wc_miden_cancel(UInt64(op))
+        }
+    }
+}
+
 /// Box type with additional context for C callbacks that need extra data
 private final class ContextContinuationBox<T, C> {
     let continuation: CheckedContinuation<T, Error>
@@ -44,6 +44,10 @@ rustflags = [
 rustflags = [
     "-C", "link-arg=-mios-simulator-version-min=$IOS_DEPLOYMENT_TARGET",
 ]
+
+[alias]
+# cargo xtask build-xcframework (see xtask/src/main.rs)
+xtask = "run --package xtask --"
 EOF
 echo "   ✅ Cargo config updated"
 
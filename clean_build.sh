@@ -6,11 +6,13 @@ PROJECT_DIR="$(cd "$(dirname "$0")" && pwd)"
 echo "🧹 Cleaning build cache..."
 echo ""
 
-# Clean iOS-related builds in target directory
-echo "Removing iOS build targets..."
+# Clean Apple-target builds in target directory
+echo "Removing Apple build targets..."
 rm -rf "$PROJECT_DIR/target/aarch64-apple-ios"
 rm -rf "$PROJECT_DIR/target/aarch64-apple-ios-sim"
 rm -rf "$PROJECT_DIR/target/x86_64-apple-ios"
+rm -rf "$PROJECT_DIR/target/aarch64-apple-darwin"
+rm -rf "$PROJECT_DIR/target/x86_64-apple-darwin"
 
 # Clean build directory
 echo "Removing build directory..."
@@ -46,25 +46,563 @@
  */
 #define ERR_TX_SUBMIT -6
 
+/**
+ * Error: output buffer too small (the required length is written to `*out_len`)
+ */
+#define ERR_BUFFER_TOO_SMALL -7
+
 /**
  * Error: worker queue is full
  */
 #define ERR_QUEUE_FULL -8
 
+/**
+ * Error: operation cancelled with `wc_miden_cancel`
+ */
+#define ERR_CANCELLED -9
+
+/**
+ * Error: a file outside the store (export, bundle) could not be written
+ */
+#define ERR_FILE_WRITE -10
+
 /**
  * Error: operation timed out
  */
 #define ERR_TIMEOUT -99
 
 /**
- * Handle structure containing sender to worker thread
+ * Error: request rejected by the user / approval callback
+ */
+#define ERR_REJECTED -100
+
+/**
+ * Error: caller lacks the permission required for the request
+ */
+#define ERR_PERMISSION_DENIED -101
+
+/**
+ * Error: transaction blocked by the screening callback
+ */
+#define ERR_SCREENING_DENIED -102
+
+/**
+ * Error: push relay or watch service unreachable, or request refused
+ */
+#define ERR_RELAY -103
+
+/**
+ * Error: the node is on another network than the store
+ */
+#define ERR_NETWORK_MISMATCH -104
+
+/**
+ * Error: an account with the derived ID already exists in the store or on chain
+ */
+#define ERR_ACCOUNT_EXISTS -105
+
+/**
+ * Error: the store must have synced at least once
+ */
+#define ERR_NOT_SYNCED -106
+
+/**
+ * Error: the store cannot be written (disk full or read-only); see `wc_miden_health_check`
+ */
+#define ERR_STORAGE_FULL -107
+
+/**
+ * Error: less memory available than `proving_memory_ceiling_mb`; prove remotely or retry
+ */
+#define ERR_PROVING_MEMORY -108
+
+/**
+ * Block headers requested per sync to backfill timestamps
+ */
+#define BLOCK_TIME_BACKFILL_LIMIT 32
+
+/**
+ * Block time assumed until the recorded timestamps span [`BLOCK_TIME_MIN_SAMPLE_BLOCKS`]
+ */
+#define BLOCK_TIME_DEFAULT_MS 3000
+
+/**
+ * Blocks the recorded timestamps must span to measure the average block time
+ * (timestamps have a resolution of one second)
+ */
+#define BLOCK_TIME_MIN_SAMPLE_BLOCKS 100
+
+/**
+ * Version tag of the unsigned transaction blob format
+ */
+#define UNSIGNED_TX_BLOB_VERSION 1
+
+/**
+ * RPC timeout of clients created without a config
+ */
+#define DEFAULT_RPC_TIMEOUT_MS 10000
+
+/**
+ * Upper bound of `timeout_ms`
+ */
+#define MAX_RPC_TIMEOUT_MS 600000
+
+/**
+ * Upper bound of `worker_threads`
+ */
+#define MAX_WORKER_THREADS 16
+
+/**
+ * Page size used when the caller passes `limit = 0`
+ */
+#define DEPOSIT_SCAN_DEFAULT_LIMIT 100
+
+/**
+ * Maximum page size for a single deposit scan
+ */
+#define DEPOSIT_SCAN_MAX_LIMIT 1000
+
+/**
+ * Shortest accepted master seed, in bytes
+ */
+#define HD_MASTER_SEED_MIN_LEN 16
+
+/**
+ * Longest accepted master seed, in bytes
+ */
+#define HD_MASTER_SEED_MAX_LEN 64
+
+/**
+ * Number of log lines kept in memory
+ */
+#define DIAGNOSTICS_LOG_CAPACITY 500
+
+/**
+ * Number of error records kept in memory
+ */
+#define DIAGNOSTICS_ERROR_CAPACITY 100
+
+/**
+ * Redaction: include everything except key material
+ */
+#define REDACT_NONE 0
+
+/**
+ * Redaction: replace account IDs, note IDs and other hex identifiers
+ */
+#define REDACT_IDENTIFIERS 1
+
+/**
+ * Redaction: identifiers redacted and log lines omitted entirely
+ */
+#define REDACT_STRICT 2
+
+/**
+ * Version tag of `keystore.json`
+ */
+#define KEYSTORE_FORMAT_VERSION 2
+
+/**
+ * Version tag of the detached signature format
+ */
+#define EXPORT_SIGNATURE_VERSION 1
+
+/**
+ * Version tag of the inclusion proof package format
+ */
+#define NOTE_PROOF_PACKAGE_VERSION 1
+
+/**
+ * Version tag of the key envelope format
+ */
+#define KEY_ENVELOPE_VERSION 2
+
+/**
+ * Blocks after the commit block before a transaction is reported as finalized
+ */
+#define LIVE_TX_FINALITY_BLOCKS 10
+
+/**
+ * Version tag of the signed message format
+ */
+#define SIGNED_MESSAGE_VERSION 1
+
+/**
+ * Maximum message length
+ */
+#define SIGNED_MESSAGE_MAX_BYTES (64 * 1024)
+
+/**
+ * Maximum number of participants in one session
+ */
+#define MPC_MAX_PARTICIPANTS 16
+
+/**
+ * Maximum number of rounds in one session
+ */
+#define MPC_MAX_ROUNDS 32
+
+/**
+ * Seconds after which an open session expires
+ */
+#define MPC_SESSION_TTL_SECS 600
+
+/**
+ * Maximum number of open sessions per handle
+ */
+#define MPC_MAX_SESSIONS 32
+
+/**
+ * Maximum size of one contribution
+ */
+#define MPC_MAX_PAYLOAD_BYTES (64 * 1024)
+
+/**
+ * Maximum size of all contributions to one session
+ */
+#define MPC_MAX_SESSION_BYTES (1024 * 1024)
+
+/**
+ * Minimum name length (after normalization)
+ */
+#define NAME_MIN_LEN 3
+
+/**
+ * Maximum name length (after normalization)
+ */
+#define NAME_MAX_LEN 32
+
+/**
+ * Largest response message `GrpcClient` accepts (tonic's default decoding limit)
+ */
+#define DEFAULT_MAX_MESSAGE_BYTES ((4 * 1024) * 1024)
+
+/**
+ * Smallest `max_message_bytes` accepted in the client config
+ */
+#define MIN_MAX_MESSAGE_BYTES 1024
+
+/**
+ * Maximum number of notes in one batch
+ */
+#define NOTE_IMPORT_MAX_BATCH 256
+
+/**
+ * Observe account inserts and updates
+ */
+#define OBSERVE_ACCOUNTS (1 << 0)
+
+/**
+ * Observe input note inserts, state changes and removals
+ */
+#define OBSERVE_INPUT_NOTES (1 << 1)
+
+/**
+ * Observe output note inserts, state changes and removals
+ */
+#define OBSERVE_OUTPUT_NOTES (1 << 2)
+
+/**
+ * Observe transaction inserts, status changes and removals
+ */
+#define OBSERVE_TRANSACTIONS (1 << 3)
+
+/**
+ * Observe every domain
+ */
+#define OBSERVE_ALL (((OBSERVE_ACCOUNTS | OBSERVE_INPUT_NOTES) | OBSERVE_OUTPUT_NOTES) | OBSERVE_TRANSACTIONS)
+
+/**
+ * Version tag of the ownership statement format
+ */
+#define OWNERSHIP_STATEMENT_VERSION 1
+
+/**
+ * Minimum challenge length (a server nonce shorter than this is guessable)
+ */
+#define OWNERSHIP_MIN_CHALLENGE_BYTES 16
+
+/**
+ * Maximum challenge length
+ */
+#define OWNERSHIP_MAX_CHALLENGE_BYTES 1024
+
+/**
+ * Most wallets created by one `wc_miden_create_wallets_bulk` call
+ */
+#define BULK_MAX_WALLETS 100
+
+/**
+ * Upper bound on the tags of one registration
+ */
+#define PUSH_MAX_TAGS 1024
+
+/**
+ * Consecutive transport failures that trigger a reconnect
+ */
+#define RECONNECT_AFTER_FAILURES 3
+
+/**
+ * Version tag of the report format
+ */
+#define POR_REPORT_VERSION 1
+
+/**
+ * Maximum number of accounts in one report
+ */
+#define POR_MAX_ACCOUNTS 100
+
+/**
+ * Scheme ID reserved for the built-in filesystem keystore
+ */
+#define SIGNER_SCHEME_KEYSTORE 0
+
+/**
+ * Size of a serialized word (public key commitment or signed message)
+ */
+#define WORD_BYTES 32
+
+/**
+ * Upper bound for a serialized RPO Falcon512 signature returned by a signer
+ */
+#define MAX_SIGNATURE_BYTES 4096
+
+/**
+ * Maximum number of beneficiaries in one policy
+ */
+#define SPLIT_MAX_BENEFICIARIES 16
+
+/**
+ * Least time between two probes while degraded
+ */
+#define STORAGE_PROBE_INTERVAL_SECS 10
+
+/**
+ * Version tag of `manifest.json`
  */
-typedef struct MidenWorkerHandle MidenWorkerHandle;
+#define STORE_BACKUP_VERSION 1
 
 /**
- * Opaque handle type for FFI
+ * Minimum time between two payouts of a stream
  */
-typedef struct MidenWorkerHandle *MidenHandle;
+#define STREAM_PAYOUT_INTERVAL_SECS (60 * 60)
+
+/**
+ * Blocks after a payout at which the sender can reclaim it if unclaimed
+ */
+#define STREAM_RECLAIM_BLOCKS 100000
+
+/**
+ * Maximum tenant ID length
+ */
+#define TENANT_ID_MAX_LEN 64
+
+/**
+ * Version tag of the envelope format
+ */
+#define TRAVEL_RULE_ENVELOPE_VERSION 1
+
+/**
+ * Upper bound on the notes of one delegation
+ */
+#define WATCH_MAX_NOTES 256
+
+/**
+ * Version tag of the snapshot format
+ */
+#define WIDGET_SNAPSHOT_VERSION 1
+
+/**
+ * Tracking status of an account in the local store
+ */
+typedef enum WcAccountStatus {
+  /**
+   * Created locally, not yet seen on chain
+   */
+  WcAccountStatus_New = 0,
+  /**
+   * Known on chain and tracked
+   */
+  WcAccountStatus_Tracked = 1,
+  /**
+   * Local state diverged from the chain; the account must be re-imported
+   */
+  WcAccountStatus_Locked = 2,
+} WcAccountStatus;
+
+/**
+ * Account type
+ */
+typedef enum WcAccountType {
+  WcAccountType_FungibleFaucet = 0,
+  WcAccountType_NonFungibleFaucet = 1,
+  WcAccountType_RegularImmutableCode = 2,
+  WcAccountType_RegularUpdatableCode = 3,
+} WcAccountType;
+
+/**
+ * Account storage mode
+ */
+typedef enum WcStorageMode {
+  /**
+   * Full account state is public on chain
+   */
+  WcStorageMode_Public = 0,
+  /**
+   * Only the state commitment is on chain
+   */
+  WcStorageMode_Private = 1,
+  /**
+   * Public account executed by the network
+   */
+  WcStorageMode_Network = 2,
+} WcStorageMode;
+
+/**
+ * Result of checking a user-entered account or note ID
+ */
+typedef enum WcIdValidation {
+  /**
+   * Accepted by the FFI as is
+   */
+  WcIdValidation_Valid = 0,
+  /**
+   * Empty string
+   */
+  WcIdValidation_Empty = 1,
+  /**
+   * Hex digits without the `0x` prefix
+   */
+  WcIdValidation_MissingPrefix = 2,
+  /**
+   * `0x` followed by the wrong number of hex digits
+   */
+  WcIdValidation_WrongLength = 3,
+  /**
+   * Characters that are neither hex digits nor a bech32 address
+   */
+  WcIdValidation_InvalidCharacter = 4,
+  /**
+   * Bech32 address with a checksum that does not match (mistyped or truncated)
+   */
+  WcIdValidation_BadChecksum = 5,
+  /**
+   * Well-formed, but not an ID (reserved bits set or values out of field range)
+   */
+  WcIdValidation_InvalidId = 6,
+  /**
+   * Intact bech32 address; the FFI takes the `0x` hex form
+   */
+  WcIdValidation_Bech32Address = 7,
+} WcIdValidation;
+
+/**
+ * Direction of the screened transfer
+ */
+typedef enum WcScreeningDirection {
+  WcScreeningDirection_Outgoing = 0,
+  WcScreeningDirection_Incoming = 1,
+} WcScreeningDirection;
+
+/**
+ * Note visibility
+ */
+typedef enum WcNoteType {
+  /**
+   * Note details are published on chain
+   */
+  WcNoteType_Public = 0,
+  /**
+   * Only the note commitment is published; details travel off chain
+   */
+  WcNoteType_Private = 1,
+} WcNoteType;
+
+/**
+ * Backend holding an account's signing key
+ */
+typedef enum WcKeyBackend {
+  /**
+   * Key file in the keystore directory
+   */
+  WcKeyBackend_Filesystem = 0,
+  /**
+   * External signer callbacks (Secure Enclave, hardware wallet, MPC)
+   */
+  WcKeyBackend_Callback = 1,
+  /**
+   * Process memory only; lost when the handle is destroyed
+   */
+  WcKeyBackend_Memory = 2,
+} WcKeyBackend;
+
+/**
+ * Encoding of amounts in JSON output
+ */
+typedef enum WcAmountEncoding {
+  /**
+   * JSON numbers (base units)
+   */
+  WcAmountEncoding_Number = 0,
+  /**
+   * Decimal strings (base units), for bridges limited to 53-bit integers
+   */
+  WcAmountEncoding_String = 1,
+} WcAmountEncoding;
+
+/**
+ * Content of an exported note file
+ */
+typedef enum WcNoteExportType {
+  /**
+   * Note ID only; the recipient fetches the note from the node (public notes)
+   */
+  WcNoteExportType_Id = 0,
+  /**
+   * Full note with its inclusion proof (committed notes)
+   */
+  WcNoteExportType_Full = 1,
+  /**
+   * Note details with the tag and the block to look from; the recipient finds the
+   * inclusion proof by syncing
+   */
+  WcNoteExportType_Partial = 2,
+} WcNoteExportType;
+
+/**
+ * Input notes selected by `wc_miden_get_input_notes_filtered`
+ */
+typedef enum WcNoteFilter {
+  /**
+   * Notes an account can consume now (the `wc_miden_get_input_notes` result)
+   */
+  WcNoteFilter_Consumable = 0,
+  /**
+   * Every input note in the store
+   */
+  WcNoteFilter_All = 1,
+  /**
+   * Announced (e.g. imported or pushed) but not yet seen in a block
+   */
+  WcNoteFilter_Expected = 2,
+  /**
+   * Included in a block and not consumed
+   */
+  WcNoteFilter_Committed = 3,
+  /**
+   * Consumed by a local or external transaction
+   */
+  WcNoteFilter_Consumed = 4,
+  /**
+   * Being consumed by a submitted local transaction
+   */
+  WcNoteFilter_Processing = 5,
+} WcNoteFilter;
+
+/**
+ * Opaque handle type for FFI (0 is never valid, see handles.rs)
+ */
+typedef uint64_t MidenHandle;
 
 /**
  * Callback for sync operation: (user_data, error_code, block_num)
@@ -79,247 +617,3640 @@ typedef void (*CreateWalletCallback)(void*, int32_t, uint8_t*, uintptr_t);
 /**
  * Callback for get accounts operation: (user_data, error_code, json_ptr, json_len)
  */
-typedef void (*GetAccountsCallback)(void*, int32_t, uint8_t*, uintptr_t);
+typedef void (*GetAccountsCallback)(void*, int32_t, uint8_t*, uintptr_t);
+
+/**
+ * Callback for get balance operation: (user_data, error_code, json_ptr, json_len)
+ */
+typedef void (*GetBalanceCallback)(void*, int32_t, uint8_t*, uintptr_t);
+
+/**
+ * Callback for test connection operation: (user_data, error_code)
+ */
+typedef void (*TestConnectionCallback)(void*, int32_t);
+
+/**
+ * Callback for get input notes operation: (user_data, error_code, json_ptr, json_len)
+ */
+typedef void (*GetInputNotesCallback)(void*, int32_t, uint8_t*, uintptr_t);
+
+/**
+ * Callback for consume notes operation: (user_data, error_code, tx_id_ptr, tx_id_len)
+ */
+typedef void (*ConsumeNotesCallback)(void*, int32_t, uint8_t*, uintptr_t);
+
+/**
+ * Generic callback for operations returning JSON or binary data: (user_data, error_code, data_ptr, data_len)
+ */
+typedef void (*BytesCallback)(void*, int32_t, uint8_t*, uintptr_t);
+
+/**
+ * Approval callback: (user_data, request_json_ptr, request_json_len) -> approved
+ *
+ * Invoked synchronously on the thread calling into the dApp API; Swift typically blocks
+ * on a UI confirmation here, so never call the dApp API from the main thread.
+ */
+typedef bool (*DappApprovalCallback)(void*, const uint8_t*, uintptr_t);
+
+/**
+ * Completion callback of an asynchronous destroy: (user_data)
+ */
+typedef void (*WcDestroyCallback)(void*);
+
+/**
+ * Event callback: (user_data, event_json_ptr, event_json_len); NULL clears the registration
+ */
+typedef void (*WcEventCallback)(void*, const uint8_t*, uintptr_t);
+
+/**
+ * Opaque object handle (0 is never valid)
+ */
+typedef uint64_t WcObject;
+
+/**
+ * Screening callback: (user_data, account_id, counterparty, faucet_id, amount, direction)
+ * -> allowed; NULL clears the registration
+ *
+ * Strings are hex account IDs valid only during the call; `counterparty` and `faucet_id`
+ * may be NULL. Invoked on the worker thread, so the callback must not call back into
+ * the same handle.
+ */
+typedef bool (*WcScreeningCallback)(void*,
+                                    const char*,
+                                    const char*,
+                                    const char*,
+                                    uint64_t,
+                                    enum WcScreeningDirection);
+
+/**
+ * Callbacks implemented by an external signer
+ *
+ * Both functions return 0 on success and a negative code on failure. They are invoked
+ * on the worker thread and may block (e.g. while the user confirms on a device).
+ */
+typedef struct WcSignerCallbacks {
+  /**
+   * Opaque pointer passed back to every callback
+   */
+  void *user_data;
+  /**
+   * Write the 32-byte public key commitment of key `key_index` into `out` (capacity in `out_len`)
+   */
+  int32_t (*get_public_key_commitment)(void*, uint32_t, uint8_t*, uintptr_t*);
+  /**
+   * Sign the 32-byte `message` with the key whose commitment is `pub_key`, writing the
+   * serialized RPO Falcon512 signature into `sig_out` (capacity in `sig_out_len`)
+   */
+  int32_t (*sign)(void*, const uint8_t*, const uint8_t*, uint8_t*, uintptr_t*);
+} WcSignerCallbacks;
+
+/**
+ * Free bytes allocated by Rust (for async callback results)
+ *
+ * MUST be called to release memory returned by async callbacks.
+ * The (ptr, len) pair must match exactly what was returned by the callback.
+ */
+void wc_bytes_free(uint8_t *ptr, uintptr_t len);
+
+/**
+ * Create and initialize Miden Client
+ *
+ * This starts a dedicated worker thread that owns the MidenClient.
+ * All operations are sent to this worker thread via channels.
+ * Runs with the default settings; use `wc_miden_create_with_config` to change them.
+ *
+ * # Parameters
+ * - `keystore_path`: Keystore storage directory path (C string)
+ * - `store_path`: SQLite database file path (C string)
+ * - `rpc_endpoint`: RPC endpoint URL or network alias (`testnet`, `devnet`, `localhost`,
+ *   `localhost:<port>`) (C string, can be NULL to use testnet)
+ * - `handle_out`: Output client handle
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters (including an endpoint that is neither a URL nor an alias)
+ * - -2: Initialization failed
+ */
+int32_t wc_miden_create(const char *keystore_path,
+                        const char *store_path,
+                        const char *rpc_endpoint,
+                        MidenHandle *handle_out);
+
+/**
+ * Destroy client and release resources
+ *
+ * Sends shutdown signal to worker thread and waits for it to finish.
+ * Safe to call multiple times - the handle is set to 0 after destruction, and any other
+ * copy of it becomes invalid (calls return -2).
+ *
+ * # Parameters
+ * - `handle_ptr`: Pointer to the handle (will be set to 0 after destruction)
+ *
+ * # Example (Swift)
+ * ```swift
+ * var handle: MidenHandle = ...
+ * wc_miden_destroy(&handle)  // handle is now 0
+ * ```
+ *
+ * # Shutdown Semantics
+ * This performs a **fast shutdown**: pending requests in the queue will be dropped.
+ * Callbacks for in-flight async operations may not be invoked.
+ * Blocks until the running request finishes; from the main thread, or for a graceful
+ * shutdown (process all pending requests), use `wc_miden_destroy_async`.
+ */
+void wc_miden_destroy(MidenHandle *handle_ptr);
+
+/**
+ * Sync state (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ * Use wc_miden_sync_async for non-blocking operation.
+ *
+ * NOTE: Timeout (-99) only abandons waiting; the operation may still complete in background.
+ *
+ * # Returns
+ * - 0: Success
+ * - -2: Invalid handle or worker closed
+ * - -8: Queue full
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_sync(MidenHandle handle, uint32_t *block_num_out);
+
+/**
+ * Create a new wallet account (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * `storage_mode` is a `WcStorageMode` value: 0 = public (state on chain), 1 = private
+ * (only the state commitment on chain; the state stays in this store, so back it up).
+ *
+ * Fails with -105 when the seed's account already exists in the store or on chain, and
+ * with -106 before the first sync when `require_sync_for_create` is set. The output
+ * buffer must hold 32 bytes; a smaller one fails with -7 before the account is created.
+ */
+int32_t wc_miden_create_wallet(MidenHandle handle,
+                               const uint8_t *seed_ptr,
+                               uintptr_t seed_len,
+                               uint32_t storage_mode,
+                               uint8_t *account_id_out,
+                               uintptr_t *account_id_out_len);
+
+/**
+ * Get all accounts (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ * Pass a NULL `accounts_json_out` to get the required length; returns -7 (with the
+ * required length) when the buffer is too small.
+ */
+int32_t wc_miden_get_accounts(MidenHandle handle,
+                              uint8_t *accounts_json_out,
+                              uintptr_t *accounts_json_out_len);
+
+/**
+ * Get account balance (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ * Pass a NULL `balance_json_out` to get the required length; returns -7 (with the
+ * required length) when the buffer is too small.
+ */
+int32_t wc_miden_get_balance(MidenHandle handle,
+                             const char *account_id_hex,
+                             uint8_t *balance_json_out,
+                             uintptr_t *balance_json_out_len);
+
+/**
+ * Test connection (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ */
+int32_t wc_miden_test_connection(MidenHandle handle);
+
+/**
+ * Get consumable input notes (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ * Pass a NULL `notes_json_out` to get the required length; returns -7 (with the
+ * required length) when the buffer is too small.
+ */
+int32_t wc_miden_get_input_notes(MidenHandle handle,
+                                 const char *account_id_hex,
+                                 uint8_t *notes_json_out,
+                                 uintptr_t *notes_json_out_len);
+
+/**
+ * Get all accounts into a Rust-allocated buffer (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ */
+int32_t wc_miden_get_accounts_alloc(MidenHandle handle, uint8_t **out_ptr, uintptr_t *out_len);
+
+/**
+ * Get an account balance into a Rust-allocated buffer (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ */
+int32_t wc_miden_get_balance_alloc(MidenHandle handle,
+                                   const char *account_id_hex,
+                                   uint8_t **out_ptr,
+                                   uintptr_t *out_len);
+
+/**
+ * Get consumable input notes into a Rust-allocated buffer (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ */
+int32_t wc_miden_get_input_notes_alloc(MidenHandle handle,
+                                       const char *account_id_hex,
+                                       uint8_t **out_ptr,
+                                       uintptr_t *out_len);
+
+/**
+ * Consume notes (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ * NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+ * Fails with -102 if the screening callback denies a note's sender. The output buffer
+ * must hold 66 bytes; a smaller one fails with -7 before anything is submitted.
+ */
+int32_t wc_miden_consume_notes(MidenHandle handle,
+                               const char *account_id_hex,
+                               const char *note_ids_json,
+                               uint8_t *tx_id_out,
+                               uintptr_t *tx_id_out_len);
+
+/**
+ * Send fungible assets to another account in a public P2ID note (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ * NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+ *
+ * # Parameters
+ * - `account_id_hex`: Sending account ID (C string)
+ * - `target_account_id_hex`: Receiving account ID (C string)
+ * - `faucet_id_hex`: Faucet of the sent asset (C string)
+ * - `amount`: Amount in base units (must be > 0)
+ * - `json_out` / `json_out_len`: Output buffer for `{"tx_id":"0x..","note_id":"0x.."}`
+ *   (157 bytes)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account or faucet ID
+ * - -4: Note construction failed
+ * - -6: Transaction submission failed (e.g. insufficient balance)
+ * - -7: Output buffer too small, checked before anything is submitted
+ * - -8: Queue full
+ * - -99: Operation timed out
+ * - -101: Recipient is not on the account's withdrawal whitelist, or the whitelist
+ *   could not be read
+ * - -102: Blocked by the screening callback
+ */
+int32_t wc_miden_send(MidenHandle handle,
+                      const char *account_id_hex,
+                      const char *target_account_id_hex,
+                      const char *faucet_id_hex,
+                      uint64_t amount,
+                      uint8_t *json_out,
+                      uintptr_t *json_out_len);
+
+/**
+ * Send fungible assets to another account in a private P2ID note (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ * NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+ *
+ * Only the note's commitment goes on chain, so the recipient cannot discover the note:
+ * hand them `note_file` (out of band: AirDrop, QR code, a messaging channel) to import
+ * with `wc_miden_import_note`. The note can also be exported again later with
+ * `wc_miden_export_note`.
+ *
+ * # Parameters
+ * - `account_id_hex`: Sending account ID (C string)
+ * - `target_account_id_hex`: Receiving account ID (C string)
+ * - `faucet_id_hex`: Faucet of the sent asset (C string)
+ * - `amount`: Amount in base units (must be > 0)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"tx_id":"0x..","note_id":"0x..","note_file":"<hex NoteFile>"}`
+ *
+ * # Returns
+ * Same codes as `wc_miden_send`. The length of `note_file` is only known once the note
+ * exists, so only the rest of the result is checked up front: a -7 with `*json_out_len`
+ * above 157 means the transaction was submitted. Do not retry it; find the note in the
+ * sender's output notes and export it with `wc_miden_export_note`. Size the buffer
+ * generously (the Swift wrapper passes 64 KiB).
+ */
+int32_t wc_miden_send_private(MidenHandle handle,
+                              const char *account_id_hex,
+                              const char *target_account_id_hex,
+                              const char *faucet_id_hex,
+                              uint64_t amount,
+                              uint8_t *json_out,
+                              uintptr_t *json_out_len);
+
+/**
+ * Get the tracking status of an account in the local store
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID or account lookup failed
+ * - -5: Account not found
+ */
+int32_t wc_miden_get_account_status(MidenHandle handle,
+                                    const char *account_id_hex,
+                                    enum WcAccountStatus *status_out);
+
+/**
+ * Get the type, storage mode, status and key backend of an account (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `account_id_hex`: Account ID (C string, hex)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"account_id","account_type","storage_mode","status","key_backend","signer_scheme_id"}`
+ *   (`key_backend` is `filesystem`, `callback` or `memory`; `signer_scheme_id` is set for
+ *   `callback` only)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID or account lookup failed
+ * - -5: Account not found
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_get_account_details(MidenHandle handle,
+                                     const char *account_id_hex,
+                                     uint8_t *json_out,
+                                     uintptr_t *json_out_len);
+
+/**
+ * Sync state (async)
+ *
+ * NOTE: Callback is invoked on worker thread, NOT main thread.
+ * Swift callers should dispatch to main queue if updating UI.
+ *
+ * Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
+ */
+int64_t wc_miden_sync_async(MidenHandle handle, SyncCallback callback, void *user_data);
+
+/**
+ * Create wallet (async)
+ *
+ * NOTE: Callback is invoked on worker thread, NOT main thread.
+ *
+ * `storage_mode` as in `wc_miden_create_wallet` (0 = public, 1 = private).
+ *
+ * Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
+ */
+int64_t wc_miden_create_wallet_async(MidenHandle handle,
+                                     const uint8_t *seed_ptr,
+                                     uintptr_t seed_len,
+                                     uint32_t storage_mode,
+                                     CreateWalletCallback callback,
+                                     void *user_data);
+
+/**
+ * Get accounts (async)
+ *
+ * NOTE: Callback is invoked on worker thread, NOT main thread.
+ *
+ * Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
+ */
+int64_t wc_miden_get_accounts_async(MidenHandle handle,
+                                    GetAccountsCallback callback,
+                                    void *user_data);
+
+/**
+ * Get balance (async)
+ *
+ * NOTE: Callback is invoked on worker thread, NOT main thread.
+ *
+ * Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
+ */
+int64_t wc_miden_get_balance_async(MidenHandle handle,
+                                   const char *account_id_hex,
+                                   GetBalanceCallback callback,
+                                   void *user_data);
+
+/**
+ * Test connection (async)
+ *
+ * NOTE: Callback is invoked on worker thread, NOT main thread.
+ *
+ * Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
+ */
+int64_t wc_miden_test_connection_async(MidenHandle handle,
+                                       TestConnectionCallback callback,
+                                       void *user_data);
+
+/**
+ * Get input notes (async)
+ *
+ * NOTE: Callback is invoked on worker thread, NOT main thread.
+ *
+ * Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
+ */
+int64_t wc_miden_get_input_notes_async(MidenHandle handle,
+                                       const char *account_id_hex,
+                                       GetInputNotesCallback callback,
+                                       void *user_data);
+
+/**
+ * Consume notes (async)
+ *
+ * NOTE: Callback is invoked on worker thread, NOT main thread.
+ * Fails with -102 if the screening callback denies a note's sender.
+ *
+ * Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
+ */
+int64_t wc_miden_consume_notes_async(MidenHandle handle,
+                                     const char *account_id_hex,
+                                     const char *note_ids_json,
+                                     ConsumeNotesCallback callback,
+                                     void *user_data);
+
+/**
+ * Send fungible assets to another account in a public P2ID note (async)
+ *
+ * NOTE: Callback is invoked on worker thread, NOT main thread.
+ * The callback receives `{"tx_id":"0x..","note_id":"0x.."}`; error codes match `wc_miden_send`.
+ *
+ * Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
+ */
+int64_t wc_miden_send_async(MidenHandle handle,
+                            const char *account_id_hex,
+                            const char *target_account_id_hex,
+                            const char *faucet_id_hex,
+                            uint64_t amount,
+                            BytesCallback callback,
+                            void *user_data);
+
+/**
+ * Send fungible assets to another account in a private P2ID note (async)
+ *
+ * NOTE: Callback is invoked on worker thread, NOT main thread.
+ * The callback receives `{"tx_id":"0x..","note_id":"0x..","note_file":"<hex NoteFile>"}`;
+ * error codes match `wc_miden_send`.
+ *
+ * Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
+ */
+int64_t wc_miden_send_private_async(MidenHandle handle,
+                                    const char *account_id_hex,
+                                    const char *target_account_id_hex,
+                                    const char *faucet_id_hex,
+                                    uint64_t amount,
+                                    BytesCallback callback,
+                                    void *user_data);
+
+/**
+ * Keccak256 hash function
+ *
+ * # Parameters
+ * - `data_ptr`: Input data pointer
+ * - `data_len`: Input data length
+ * - `out_ptr`: Output buffer pointer (must be at least 32 bytes)
+ * - `out_len`: Input: buffer capacity; Output: actual length (32)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -7: Output buffer too small
+ */
+int32_t wc_keccak256(const uint8_t *data_ptr,
+                     uintptr_t data_len,
+                     uint8_t *out_ptr,
+                     uintptr_t *out_len);
+
+/**
+ * Query which optional features this build supports
+ *
+ * Lets a single Swift SDK adapt to differently configured framework builds at runtime.
+ *
+ * # Output JSON
+ * `{"version":"0.1.0","capabilities":{"sqlcipher":false,"uniffi":false,"mock_node":false,
+ *   "remote_prover":false,"mnemonic":true,"external_signers":true,...}}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_capabilities(uint8_t *json_out, uintptr_t *json_out_len);
+
+/**
+ * Decode the account type and storage mode encoded in an account ID
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -3: Invalid account ID
+ */
+int32_t wc_miden_account_id_info(const char *account_id_hex,
+                                 enum WcAccountType *account_type_out,
+                                 enum WcStorageMode *storage_mode_out);
+
+/**
+ * Convert account ID bytes to hex string
+ */
+int32_t wc_miden_account_id_to_hex(const uint8_t *account_id_ptr,
+                                   uintptr_t account_id_len,
+                                   uint8_t *hex_out,
+                                   uintptr_t *hex_out_len);
+
+/**
+ * Import a public account from the node and track it (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `account_id_hex`: Account ID (C string)
+ * - `json_out` / `json_out_len`: Output buffer for the account details (same shape as
+ *   `wc_miden_get_account_details`)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or a private account
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID, or the node has no such account
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_import_account_by_id(MidenHandle handle,
+                                      const char *account_id_hex,
+                                      uint8_t *json_out,
+                                      uintptr_t *json_out_len);
+
+/**
+ * Import a public account from the node and track it (async)
+ *
+ * NOTE: Callback is invoked on worker thread, NOT main thread.
+ *
+ * Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
+ */
+int64_t wc_miden_import_account_by_id_async(MidenHandle handle,
+                                            const char *account_id_hex,
+                                            BytesCallback callback,
+                                            void *user_data);
+
+/**
+ * Sync and refresh one account, deferring other accounts' post-sync work (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `account_id_hex`: Account ID (C string)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"account_id","block_num","nonce","consumable_notes","bytes_sent","bytes_received"}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle, worker closed or sync failed
+ * - -3: Invalid account ID
+ * - -4: Note query failed
+ * - -5: Account not in the store
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_sync_account(MidenHandle handle,
+                              const char *account_id_hex,
+                              uint8_t *json_out,
+                              uintptr_t *json_out_len);
+
+/**
+ * Choose how amounts are written in JSON output (process-wide)
+ *
+ * # Parameters
+ * - `encoding`: `WcAmountEncoding` value (0 = numbers, the default; 1 = decimal strings)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Unknown encoding
+ */
+int32_t wc_miden_set_amount_encoding(uint32_t encoding);
+
+/**
+ * Convert a token-unit decimal string into base units with the faucet's decimals (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `faucet_id_hex`: Faucet account ID (C string)
+ * - `amount_str`: Amount in token units, e.g. `"12.5"` (C string, no sign or exponent)
+ * - `amount_out`: Amount in base units
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, more fraction digits than the token has, or overflow
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid faucet ID
+ * - -5: Faucet unknown or not a fungible faucet
+ */
+int32_t wc_miden_parse_faucet_amount(MidenHandle handle,
+                                     const char *faucet_id_hex,
+                                     const char *amount_str,
+                                     uint64_t *amount_out);
+
+/**
+ * Convert base units into a token-unit decimal string with the faucet's decimals (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `faucet_id_hex`: Faucet account ID (C string)
+ * - `amount`: Amount in base units
+ * - `out` / `out_len`: Output buffer for the decimal string (no grouping, no trailing zeros)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid faucet ID
+ * - -5: Faucet unknown or not a fungible faucet
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_format_faucet_amount(MidenHandle handle,
+                                      const char *faucet_id_hex,
+                                      uint64_t amount,
+                                      uint8_t *out,
+                                      uintptr_t *out_len);
+
+/**
+ * Get the token metadata of a fungible faucet (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Untracked public faucets are fetched from the node.
+ *
+ * # Parameters
+ * - `faucet_id_hex`: Faucet account ID (C string)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"faucet_id","symbol","decimals","max_supply"}` (`max_supply` in base units)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid faucet ID (including IDs of non-faucet accounts)
+ * - -5: Faucet unknown or not a fungible faucet
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_get_faucet_metadata(MidenHandle handle,
+                                     const char *faucet_id_hex,
+                                     uint8_t *json_out,
+                                     uintptr_t *json_out_len);
+
+/**
+ * Convert base units into a token-unit decimal string with known decimals
+ *
+ * Safe to call from any thread; needs no handle. Use `wc_miden_format_faucet_amount` to
+ * read the decimals from the faucet instead.
+ *
+ * # Parameters
+ * - `amount`: Amount in base units
+ * - `decimals`: Token decimals
+ * - `fraction_digits`: Maximum fraction digits, rounded half up (`decimals` or more = exact)
+ * - `out` / `out_len`: Output buffer for the decimal string (no grouping, no trailing zeros)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_format_amount(uint64_t amount,
+                               uint8_t decimals,
+                               uint8_t fraction_digits,
+                               uint8_t *out,
+                               uintptr_t *out_len);
+
+/**
+ * Convert a token-unit decimal string into base units with known decimals
+ *
+ * Safe to call from any thread; needs no handle. Never rounds. Use
+ * `wc_miden_parse_faucet_amount` to read the decimals from the faucet instead.
+ *
+ * # Parameters
+ * - `amount_str`: Amount in token units, e.g. `"12.5"` (C string, no sign or exponent)
+ * - `decimals`: Token decimals
+ * - `amount_out`: Amount in base units
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, more fraction digits than `decimals`, or overflow
+ */
+int32_t wc_miden_parse_amount(const char *amount_str, uint8_t decimals, uint64_t *amount_out);
+
+/**
+ * Archive or unarchive an account (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * An archived account keeps its data and keys but is left out of post-sync work,
+ * cross-account note scans and portfolio totals (see the module docs).
+ *
+ * # Parameters
+ * - `account_id_hex`: Account ID (C string)
+ * - `archived`: true to archive, false to unarchive (no-op if already in that state)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID, or the archive file could not be written
+ * - -5: Account not in the store
+ */
+int32_t wc_miden_set_account_archived(MidenHandle handle,
+                                      const char *account_id_hex,
+                                      bool archived);
+
+/**
+ * Read the audit log (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `since_unix`: Only return entries at or after this unix time (0 = all)
+ * - `json_out` / `json_out_len`: Output buffer for the JSON array of entries (oldest first)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_get_audit_log(MidenHandle handle,
+                               uint64_t since_unix,
+                               uint8_t *json_out,
+                               uintptr_t *json_out_len);
+
+/**
+ * Sync for at most `max_duration_ms`, for `BGAppRefreshTask` handlers (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Returns once the store reaches the node tip or the budget runs out. Running out of
+ * budget is not an error: progress of completed sync rounds is kept and the output
+ * reports what is left. Call `setTaskCompleted(success:)` with 0 as success.
+ *
+ * # Parameters
+ * - `max_duration_ms`: Time budget in milliseconds, from 1 up to 30000 (the blocking
+ *   call timeout); leave a margin below the time the system grants the task
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"synced_to_block","node_tip","blocks_behind","more_work_remaining","rounds",
+ *   "elapsed_ms","bytes_sent","bytes_received"}` (`synced_to_block` is null when no round
+ *   completed in time; the byte counts are the node traffic of all rounds, see
+ *   node_proxy.rs)
+ *
+ * # Returns
+ * - 0: Success (check `more_work_remaining`)
+ * - -1: Invalid parameters
+ * - -2: Invalid handle, worker closed or the first sync round failed
+ * - -7: Output buffer too small
+ * - -8: Worker queue full
+ */
+int32_t wc_miden_background_sync(MidenHandle handle,
+                                 uint32_t max_duration_ms,
+                                 uint8_t *json_out,
+                                 uintptr_t *json_out_len);
+
+/**
+ * Estimate the wall-clock time of a block, e.g. a timelock expiry (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * The estimate extrapolates from the newest block recorded during sync using the
+ * network's average block time (see the module docs); blocks whose timestamp is recorded
+ * are reported exactly. Format `seconds_from_now` or `estimated_at` for display with the
+ * user's locale (e.g. `RelativeDateTimeFormatter`).
+ *
+ * # Parameters
+ * - `block_num`: Block number (past or future)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"block_num","tip","blocks_remaining","average_block_time_ms","block_time_measured",
+ *   "estimated_at","seconds_from_now","is_estimate"}` (`estimated_at` in ISO-8601,
+ *   `seconds_from_now` negative for past blocks)
+ *
+ * # Returns
+ * - 0: Success
+ * - -2: Invalid handle or worker closed
+ * - -5: No block recorded yet (sync first)
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_estimate_time_for_block(MidenHandle handle,
+                                         uint32_t block_num,
+                                         uint8_t *json_out,
+                                         uintptr_t *json_out_len);
+
+/**
+ * Configure the bridge operator used by a handle
+ *
+ * # Parameters
+ * - `config_json`: `{"operator_account_id":"0x..","deposit_script":"<MASM>",
+ *   "supported_chains":[1,10]}` (`supported_chains` optional), or NULL to remove the
+ *   configuration
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid configuration (including a script that does not compile)
+ * - -2: Invalid handle
+ */
+int32_t wc_miden_set_bridge_operator(MidenHandle handle, const char *config_json);
+
+/**
+ * Send assets to the bridge operator for payout on another chain (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `account_id_hex`: Sending account (C string)
+ * - `target_chain`: EVM chain ID of the destination network
+ * - `target_address`: `0x`-prefixed 20-byte destination address (C string)
+ * - `faucet_id_hex`: Faucet of the bridged asset (C string)
+ * - `amount`: Amount in base units (must be > 0)
+ * - `json_out` / `json_out_len`: Output buffer for `{"request_id":"0x..","tx_id":"0x.."}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, no operator configured or unsupported chain
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID
+ * - -4: Note construction failed
+ * - -6: Transaction submission failed
+ * - -7: Output buffer too small
+ * - -101: Operator is not on the account's withdrawal whitelist
+ * - -102: Blocked by the screening callback
+ */
+int32_t wc_miden_create_bridge_request(MidenHandle handle,
+                                       const char *account_id_hex,
+                                       uint64_t target_chain,
+                                       const char *target_address,
+                                       const char *faucet_id_hex,
+                                       uint64_t amount,
+                                       uint8_t *json_out,
+                                       uintptr_t *json_out_len);
+
+/**
+ * Mark a bridge request as paid out on the target chain (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `request_id_hex`: Bridge request ID (C string)
+ * - `target_tx_hash`: Payout transaction hash on the target chain (C string)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or request already fulfilled
+ * - -2: Invalid handle or worker closed
+ * - -4: Invalid request ID
+ * - -5: Request not found
+ */
+int32_t wc_miden_confirm_bridge_fulfillment(MidenHandle handle,
+                                            const char *request_id_hex,
+                                            const char *target_tx_hash);
+
+/**
+ * List bridge requests with their lifecycle state (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `json_out` / `json_out_len`: Output buffer for a JSON array of `{"request_id","state",
+ *   "account_id","operator_account_id","target_chain","target_address","faucet_id",
+ *   "amount","tx_id","created_at","target_tx_hash"}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_list_bridge_requests(MidenHandle handle,
+                                      uint8_t *json_out,
+                                      uintptr_t *json_out_len);
+
+/**
+ * Cancel an async operation
+ *
+ * Safe to call from any thread, including the main thread. See the module docs for what
+ * can be interrupted; the operation's callback still runs exactly once.
+ *
+ * # Parameters
+ * - `op_handle`: Operation handle returned by a `*_async` function
+ *
+ * # Returns
+ * - 0: Cancellation requested
+ * - -5: Unknown operation, or its callback already ran
+ */
+int32_t wc_miden_cancel(uint64_t op_handle);
+
+#if defined(MIDEN_TESTING)
+/**
+ * Freeze the time and/or block-height source (testing builds only)
+ *
+ * # Parameters
+ * - `unix_secs`: Frozen unix time in seconds, or 0 to use the system clock
+ * - `block_height`: Frozen block height, or 0 to use the client's sync height
+ *
+ * # Returns
+ * - 0: Success
+ */
+int32_t wc_test_set_time(uint64_t unix_secs, uint32_t block_height);
+#endif
+
+#if defined(MIDEN_TESTING)
+/**
+ * Move a frozen clock forward (testing builds only)
+ *
+ * Only sources frozen with `wc_test_set_time` are advanced.
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Neither source is frozen
+ */
+int32_t wc_test_advance_time(uint64_t secs, uint32_t blocks);
+#endif
+
+/**
+ * Build an unsigned transaction for signing on an air-gapped device (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `account_id_hex`: Executing account ID (C string)
+ * - `request_json`: Transaction description (C string), one of
+ *   `{"type":"p2id","target_account_id","faucet_id","amount","note_type":"public"|"private",
+ *   "reclaim_block"}` (`reclaim_block` optional) or
+ *   `{"type":"consume","note_ids":[...]}`
+ * - `blob_out` / `blob_out_len`: Output buffer for the unsigned transaction blob (JSON).
+ *   The blob's `message` field (hex-serialized word) is what the offline device must sign
+ *   with the key whose commitment is `pub_key`. `travel_rule_envelope` holds the sealed
+ *   travel-rule metadata to deliver with the payment (null without a `travel_rule` section).
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID
+ * - -4: Invalid note ID / note construction failed
+ * - -6: Transaction execution failed before a signature was requested
+ * - -7: Output buffer too small
+ * - -101: Recipient is not on the account's withdrawal whitelist, or the whitelist
+ *   could not be read
+ * - -102: Blocked by the screening callback
+ */
+int32_t wc_miden_build_unsigned_tx(MidenHandle handle,
+                                   const char *account_id_hex,
+                                   const char *request_json,
+                                   uint8_t *blob_out,
+                                   uintptr_t *blob_out_len);
+
+/**
+ * Attach an externally produced signature to an unsigned transaction blob and submit it (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ * NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+ *
+ * # Parameters
+ * - `blob_ptr` / `blob_len`: Blob returned by `wc_miden_build_unsigned_tx`
+ * - `signature_ptr` / `signature_len`: Serialized RPO Falcon512 signature over the blob's `message`
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"tx_id":"0x..","travel_rule_envelope":{...}|null}`, the envelope being the one
+ *   exported in the blob
+ *
+ * The account must be in the state the blob was built against (see the module docs);
+ * otherwise the signature does not verify and the call fails with -6.
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or malformed blob/signature
+ * - -2: Invalid handle or worker closed
+ * - -4: Note deserialization failed
+ * - -6: Transaction submission failed (including a signature that does not verify)
+ * - -7: Output buffer too small
+ * - -101: Recipient is not on the account's withdrawal whitelist, or the whitelist
+ *   could not be read
+ * - -102: Blocked by the screening callback
+ */
+int32_t wc_miden_attach_signature_and_submit(MidenHandle handle,
+                                             const uint8_t *blob_ptr,
+                                             uintptr_t blob_len,
+                                             const uint8_t *signature_ptr,
+                                             uintptr_t signature_len,
+                                             uint8_t *json_out,
+                                             uintptr_t *json_out_len);
+
+/**
+ * Create and initialize a Miden Client from a JSON config
+ *
+ * Like `wc_miden_create`, with the RPC timeout, debug mode, runtime threads and node
+ * call compression configurable, and optionally scoped to one tenant of the store (see
+ * the module docs for the config object).
+ *
+ * # Parameters
+ * - `config_json`: Config object (C string); `keystore_path` and `store_path` are required
+ *   unless `in_memory` is true
+ * - `handle_out`: Output client handle
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters (malformed config, unknown network, out-of-range setting), or
+ *   another tenant of the store is open
+ * - -2: Initialization failed
+ */
+int32_t wc_miden_create_with_config(const char *config_json, MidenHandle *handle_out);
+
+/**
+ * Set the callback that approves dApp connections and requests
+ *
+ * Pass the callback again to replace it. Without a callback every connection and
+ * request is rejected.
+ */
+int32_t wc_dapp_set_approval_callback(DappApprovalCallback callback, void *user_data);
+
+/**
+ * Open a dApp session
+ *
+ * # Parameters
+ * - `payload_json`: Connection payload `{"dapp_id","name","url","requested_permissions":[...]}`
+ *   where permissions are `view_accounts`, `send_transaction` and `sign_message`
+ * - `accounts_json`: JSON array of account IDs the user chose to expose to the dApp
+ * - `session_id_out`: Receives the session ID, valid on this handle only
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid payload, unknown permission or invalid account ID
+ * - -2: Invalid handle
+ * - -100: Rejected by the approval callback (or no callback set)
+ */
+int32_t wc_dapp_connect(MidenHandle handle,
+                        const char *payload_json,
+                        const char *accounts_json,
+                        uint64_t *session_id_out);
+
+/**
+ * Handle a request from a connected dApp (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Request JSON
+ * - `{"method":"get_accounts"}` → `["0x..", ...]`
+ * - `{"method":"send_transaction","account_id":"0x..","transaction":{...}}` →
+ *   `{"tx_id":"0x..","travel_rule_envelope":{...}|null}`
+ *   where `transaction` uses the `wc_miden_build_unsigned_tx` request format
+ * - `{"method":"sign_message","account_id":"0x..","message":"..."}` →
+ *   `{"version":1,"account_id","digest","pub_key","signature"}` (the UTF-8 message, 1 byte
+ *   to 64 KB, signed as by `wc_miden_sign_message`)
+ *
+ * Transactions and signatures are executed only after the approval callback accepts them.
+ * For `send_transaction` the response buffer is checked first: a buffer smaller than the
+ * result fails with -7 (and the required length) before the user is asked to approve.
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Unknown session (or one opened on another handle) or malformed/unsupported request
+ * - -2: Invalid handle or worker closed
+ * - -3: The account has no key this client can sign with
+ * - -5: Account not in the store
+ * - -6: Transaction submission failed
+ * - -7: Output buffer too small
+ * - -100: Rejected by the approval callback
+ * - -101: Session lacks the permission, the account is not exposed to the dApp, or the
+ *   recipient is not on the account's withdrawal whitelist
+ * - -102: Blocked by the screening callback
+ */
+int32_t wc_dapp_handle_request(MidenHandle handle,
+                               uint64_t session_id,
+                               const char *request_json,
+                               uint8_t *response_out,
+                               uintptr_t *response_out_len);
+
+/**
+ * Close a dApp session of a handle
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Unknown session
+ * - -2: Invalid handle
+ */
+int32_t wc_dapp_disconnect(MidenHandle handle, uint64_t session_id);
+
+/**
+ * List the open dApp sessions of a handle
+ *
+ * # Output JSON
+ * `[{"session_id","dapp":{"dapp_id","name","url"},"permissions":[...],"accounts":[...]}]`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle
+ * - -7: Output buffer too small
+ */
+int32_t wc_dapp_list_sessions(MidenHandle handle, uint8_t *json_out, uintptr_t *json_out_len);
+
+/**
+ * Deploy a new account on chain (async)
+ *
+ * NOTE: Callback is invoked on worker thread, NOT main thread.
+ *
+ * Submits the account's first transaction (see the module docs). The callback receives
+ * the transaction ID (hex string, not NUL-terminated; free with `wc_bytes_free`) once the
+ * transaction is submitted, or an error code:
+ * - -3: The account is not `new` (already deployed, or locked)
+ * - -5: The account is not in the store
+ * - -6: Proving or submission failed, e.g. no key for the account in this store
+ *
+ * Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code
+ * (-1 invalid parameters, -2 invalid handle, -3 invalid account ID, -8 queue full).
+ */
+int64_t wc_miden_deploy_account(MidenHandle handle,
+                                const char *account_id,
+                                BytesCallback callback,
+                                void *user_data);
+
+/**
+ * Scan committed incoming notes whose tag falls inside a namespace (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Reads the local store only; call `wc_miden_sync` first to pick up new deposits.
+ *
+ * # Parameters
+ * - `tag_prefix`: Tag whose top `prefix_bits` bits define the namespace
+ * - `prefix_bits`: Number of significant prefix bits (0-32, 0 matches every tag)
+ * - `since_block`: Ignore notes included before this block
+ * - `cursor`: `next_cursor` from the previous page (C string, NULL or empty for the first page)
+ * - `limit`: Page size (0 uses the default of 100, max 1000)
+ * - `json_out` / `json_out_len`: Output buffer; capacity in, written length out
+ *
+ * # Output JSON
+ * `{"deposits":[{"note_id","tag","block_num","timestamp","sender","assets","is_consumed"}],"count","has_more","next_cursor"}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or malformed cursor
+ * - -2: Invalid handle or worker closed
+ * - -4: Note store query failed
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_scan_deposits(MidenHandle handle,
+                               uint32_t tag_prefix,
+                               uint8_t prefix_bits,
+                               uint32_t since_block,
+                               const char *cursor,
+                               uint32_t limit,
+                               uint8_t *json_out,
+                               uintptr_t *json_out_len);
+
+/**
+ * Scan committed incoming notes whose tag falls inside a namespace (async)
+ *
+ * NOTE: Callback is invoked on worker thread, NOT main thread.
+ *
+ * Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
+ */
+int64_t wc_miden_scan_deposits_async(MidenHandle handle,
+                                     uint32_t tag_prefix,
+                                     uint8_t prefix_bits,
+                                     uint32_t since_block,
+                                     const char *cursor,
+                                     uint32_t limit,
+                                     BytesCallback callback,
+                                     void *user_data);
+
+/**
+ * Create (or restore) the wallet at an index of a master seed (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Derives the wallet's account seed and key from the master seed and index (see the
+ * module docs). A public wallet that already exists on chain is restored into this
+ * store; `restored` tells the two cases apart, so a recovery loop stops at the first
+ * index that returns `restored: false`.
+ *
+ * # Parameters
+ * - `master_seed_ptr` / `master_seed_len`: Master seed (16-64 bytes)
+ * - `index`: Wallet index
+ * - `storage_mode`: `WcStorageMode` value (0 = public, 1 = private)
+ * - `json_out` / `json_out_len`: Output buffer for `{"account_id","index","restored"}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or `WcStorageMode_Network`
+ * - -2: Invalid handle or worker closed
+ * - -3: Account creation or restore failed
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ * - -105: The wallet is already in the store, or is a private wallet known on chain
+ * - -106: The store has not synced yet and `require_sync_for_create` is set
+ */
+int32_t wc_miden_create_wallet_derived(MidenHandle handle,
+                                       const uint8_t *master_seed_ptr,
+                                       uintptr_t master_seed_len,
+                                       uint32_t index,
+                                       uint32_t storage_mode,
+                                       uint8_t *json_out,
+                                       uintptr_t *json_out_len);
+
+/**
+ * Write a diagnostics bundle (zip) for support tickets (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * The bundle contains recent logs, per-operation metrics, error history, the store
+ * schema and version, and the sync height. Keys are never included.
+ *
+ * # Parameters
+ * - `out_path`: Destination file path of the zip (overwritten if present)
+ * - `redact_level`: 0 = no redaction, 1 = redact identifiers, 2 = redact identifiers and omit logs
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -5: Account lookup failed
+ * - -10: The file could not be written
+ */
+int32_t wc_miden_export_diagnostics(MidenHandle handle,
+                                    const char *out_path,
+                                    uint32_t redact_level);
+
+/**
+ * Destroy client without blocking (async)
+ *
+ * Safe to call from any thread, including the main thread. The handle is set to 0 and
+ * becomes invalid immediately; requests already queued run to completion (their
+ * callbacks are invoked) before the store is closed. See the module docs.
+ *
+ * NOTE: The completion callback is invoked on the disposal thread, NOT main thread. It
+ * runs exactly once, even when the handle was already destroyed.
+ *
+ * # Parameters
+ * - `handle_ptr`: Pointer to the handle (will be set to 0)
+ * - `callback`: Called once the store is closed (NULL = no notification)
+ * - `user_data`: Passed to the callback
+ */
+void wc_miden_destroy_async(MidenHandle *handle_ptr, WcDestroyCallback callback, void *user_data);
+
+/**
+ * Change the keystore passphrase (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread. Every key is
+ * rewritten, and each passphrase goes through a deliberately slow key derivation.
+ *
+ * Re-encrypts the keys of the filesystem backend under the new passphrase and swaps the
+ * keystore directory (see the module docs). NULL or an empty string means no passphrase,
+ * so this also encrypts a plain keystore or decrypts an encrypted one. Close other
+ * handles on the same keystore first, and create later clients with the new passphrase.
+ *
+ * # Parameters
+ * - `old_passphrase`: Current passphrase (C string, NULL for a plain keystore)
+ * - `new_passphrase`: New passphrase (C string, NULL to store the keys unencrypted)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, or an in-memory client (no keystore)
+ * - -2: Invalid handle or worker closed
+ * - -3: Reading or writing the keys failed; the keystore is unchanged
+ * - -99: Operation timed out
+ * - -101: `old_passphrase` is not the keystore's passphrase
+ */
+int32_t wc_miden_rekey_keystore(MidenHandle handle,
+                                const char *old_passphrase,
+                                const char *new_passphrase);
+
+/**
+ * Change the key of an encrypted store (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread. Every page of the
+ * store is re-encrypted.
+ *
+ * The client is reopened with the new key (see the module docs); create later clients on
+ * this store with the new `store_key`. Close other handles on the same store first.
+ *
+ * # Parameters
+ * - `old_key`: Current store key (C string)
+ * - `new_key`: New store key (C string, not empty)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, or the store is not encrypted
+ * - -2: Invalid handle or worker closed
+ * - -3: Rekeying or reopening the store failed; the store keeps its old key if the rekey
+ *   itself failed
+ * - -99: Operation timed out
+ * - -101: `old_key` is not the store's key
+ */
+int32_t wc_miden_change_store_key(MidenHandle handle, const char *old_key, const char *new_key);
+
+/**
+ * Create a private ephemeral account that is swept back to a main account (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `main_account_id_hex`: Local account that receives the residual funds (C string)
+ * - `ttl_blocks`: Lifetime in blocks from the current sync height (must be > 0)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"account_id":"0x..","main_account_id":"0x..","expires_at_block":123}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID, or the account could not be created
+ * - -5: Main account not found
+ * - -7: Output buffer too small
+ * - -105: An account with the derived ID already exists in the store or on chain
+ * - -106: The store has not synced yet and `require_sync_for_create` is set
+ */
+int32_t wc_miden_create_ephemeral_account(MidenHandle handle,
+                                          const char *main_account_id_hex,
+                                          uint32_t ttl_blocks,
+                                          uint8_t *json_out,
+                                          uintptr_t *json_out_len);
+
+/**
+ * Get a short, user-presentable message for an error code
+ *
+ * # Parameters
+ * - `code`: Error code returned by any `wc_*` function
+ * - `locale`: Locale identifier such as `en`, `de_CH` or `zh-Hans` (NULL or unknown uses English)
+ * - `out` / `out_len`: Output buffer for the UTF-8 message
+ *
+ * Unknown codes map to a generic message.
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -7: Output buffer too small
+ */
+int32_t wc_error_user_message(int32_t code, const char *locale, uint8_t *out, uintptr_t *out_len);
+
+/**
+ * Lock funds in an escrow note for a payee (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `payer_account_id_hex` / `payee_account_id_hex` / `faucet_id_hex`: Account IDs (C strings)
+ * - `amount`: Escrowed amount (base units)
+ * - `reclaim_after_blocks`: Blocks from the current sync height after which the payer
+ *   can refund (must be > 0)
+ * - `json_out` / `json_out_len`: Output buffer for `{"escrow_id","tx_id","reclaim_block"}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID
+ * - -4: Note creation failed
+ * - -5: Sync height unavailable
+ * - -6: Transaction submission failed
+ * - -7: Output buffer too small
+ * - -101: Blocked by the payer's withdrawal whitelist
+ * - -102: Blocked by compliance screening
+ */
+int32_t wc_miden_create_escrow(MidenHandle handle,
+                               const char *payer_account_id_hex,
+                               const char *payee_account_id_hex,
+                               const char *faucet_id_hex,
+                               uint64_t amount,
+                               uint32_t reclaim_after_blocks,
+                               uint8_t *json_out,
+                               uintptr_t *json_out_len);
+
+/**
+ * Release an escrow to its payee (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Writes the serialized NoteFile of the escrow note; deliver it to the payee, who
+ * imports it (`wc_miden_import_notes_batch`) and consumes the note before the reclaim
+ * block. Releasing again returns the same NoteFile.
+ *
+ * # Parameters
+ * - `escrow_id_hex`: Escrow ID (C string)
+ * - `bytes_out` / `bytes_out_len`: Output buffer for the NoteFile
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or escrow already refunded
+ * - -2: Invalid handle or worker closed
+ * - -4: Invalid escrow ID or state could not be saved
+ * - -5: Escrow not found
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_release_escrow(MidenHandle handle,
+                                const char *escrow_id_hex,
+                                uint8_t *bytes_out,
+                                uintptr_t *bytes_out_len);
+
+/**
+ * Reclaim an escrow after its reclaim block (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `escrow_id_hex`: Escrow ID (C string)
+ * - `tx_id_out` / `tx_id_out_len`: Output buffer for the transaction ID (hex)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or escrow already refunded
+ * - -2: Invalid handle or worker closed
+ * - -4: Invalid escrow ID, or the reclaim block has not been reached
+ * - -5: Escrow not found
+ * - -6: Transaction submission failed (e.g. the payee already consumed the note)
+ * - -7: Output buffer too small
+ * - -102: Blocked by the screening callback
+ */
+int32_t wc_miden_refund_escrow(MidenHandle handle,
+                               const char *escrow_id_hex,
+                               uint8_t *tx_id_out,
+                               uintptr_t *tx_id_out_len);
+
+/**
+ * List the escrows created from this store (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `json_out` / `json_out_len`: Output buffer for a JSON array of `{"escrow_id","payer",
+ *   "payee","faucet_id","amount","created_at_block","reclaim_block","state"}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_list_escrows(MidenHandle handle, uint8_t *json_out, uintptr_t *json_out_len);
+
+/**
+ * Set (or clear, with a NULL callback) the event callback of a handle
+ *
+ * # Returns
+ * - 0: Success
+ * - -2: Invalid handle
+ */
+int32_t wc_miden_set_event_callback(MidenHandle handle, WcEventCallback callback, void *user_data);
+
+/**
+ * Derive the EVM address of a secp256k1 public key
+ *
+ * # Parameters
+ * - `pubkey_ptr` / `pubkey_len`: Uncompressed public key, 65 bytes (`0x04 || X || Y`) or
+ *   64 bytes (`X || Y`)
+ * - `address_out` / `address_out_len`: Output buffer for the EIP-55 checksummed address
+ *   (`0x` + 40 hex chars, 42 bytes, not NUL-terminated)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters (including compressed keys)
+ * - -7: Output buffer too small
+ */
+int32_t wc_evm_address_from_pubkey(const uint8_t *pubkey_ptr,
+                                   uintptr_t pubkey_len,
+                                   uint8_t *address_out,
+                                   uintptr_t *address_out_len);
+
+/**
+ * Encode an EVM address with the EIP-55 mixed-case checksum
+ *
+ * The input case is ignored, so this both normalizes and validates user-entered
+ * addresses: compare the result with the input to detect a checksum mismatch.
+ *
+ * # Parameters
+ * - `address`: 20-byte hex address, `0x` prefix optional (C string)
+ * - `address_out` / `address_out_len`: Output buffer for the checksummed address
+ *   (`0x` + 40 hex chars, 42 bytes, not NUL-terminated)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid address
+ * - -7: Output buffer too small
+ */
+int32_t wc_eip55_checksum(const char *address, uint8_t *address_out, uintptr_t *address_out_len);
+
+/**
+ * Hash a message as EVM `personal_sign` does (EIP-191 version 0x45)
+ *
+ * Computes `keccak256("\x19Ethereum Signed Message:\n" || len(message) || message)`,
+ * with the length in decimal ASCII.
+ *
+ * # Parameters
+ * - `message_ptr` / `message_len`: Raw message bytes (may be empty)
+ * - `out_ptr` / `out_len`: Output buffer (at least 32 bytes); receives the 32-byte hash
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -7: Output buffer too small
+ */
+int32_t wc_evm_personal_sign_hash(const uint8_t *message_ptr,
+                                  uintptr_t message_len,
+                                  uint8_t *out_ptr,
+                                  uintptr_t *out_len);
+
+/**
+ * Sign an export file with an account's key (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Writes the detached signature to `<path>.sig` (overwritten if present):
+ * `{"version":1,"account_id","pub_key","digest","signature"}` (hex fields; `signature`:
+ * serialized signature over `digest`, see the module docs).
+ *
+ * # Parameters
+ * - `account_id_hex`: Signing account (C string); its key must be held by this client
+ * - `path`: File to sign, e.g. a `wc_miden_generate_por_report` report (C string)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, or the file could not be read or the signature written
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID, or the account has no key this client can sign with
+ * - -5: Account not in the store
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_sign_export(MidenHandle handle, const char *account_id_hex, const char *path);
+
+/**
+ * Verify an export file against its detached signature (`<path>.sig`)
+ *
+ * Needs no client handle; safe to call from any thread.
+ *
+ * # Parameters
+ * - `path`: Signed file (C string)
+ * - `pub_key_hex`: Expected public key commitment, 32 bytes hex (C string), e.g. the
+ *   account's auth key commitment as registered with the server
+ *
+ * # Returns
+ * - 0: The file is unchanged and signed by `pub_key_hex`
+ * - -1: Invalid parameters, unreadable file or malformed signature file
+ * - -3: Signature invalid, made by another key, or the file changed since signing
+ */
+int32_t wc_verify_export(const char *path, const char *pub_key_hex);
+
+/**
+ * Create a fungible faucet account (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * The faucet is a public `BasicFungibleFaucet` account whose Falcon key is stored in the
+ * keystore, so this client can mint its token.
+ *
+ * # Parameters
+ * - `seed_ptr` / `seed_len`: Optional 32-byte account seed (NULL for a random seed)
+ * - `symbol`: Token symbol, 1-6 uppercase letters (C string)
+ * - `decimals`: Token decimals (at most 12)
+ * - `max_supply`: Maximum supply in base units (must be > 0 and fit a fungible asset)
+ * - `account_id_out` / `account_id_out_len`: Output buffer for the faucet account ID (hex)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Account creation failed
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_create_faucet(MidenHandle handle,
+                               const uint8_t *seed_ptr,
+                               uintptr_t seed_len,
+                               const char *symbol,
+                               uint8_t decimals,
+                               uint64_t max_supply,
+                               uint8_t *account_id_out,
+                               uintptr_t *account_id_out_len);
+
+/**
+ * Mint tokens from a local faucet to an account (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ * NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+ *
+ * The minted amount is delivered in a P2ID note that the target consumes like any
+ * other received payment.
+ *
+ * # Parameters
+ * - `faucet_id_hex`: Faucet account created by this client (C string)
+ * - `target_account_id_hex`: Receiving account ID (C string)
+ * - `amount`: Amount in base units (must be > 0)
+ * - `note_type`: `WcNoteType` value (0 = public, 1 = private)
+ * - `tx_id_out` / `tx_id_out_len`: Output buffer for the transaction ID (hex, 66 bytes)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters (including an unknown note type)
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID, or the faucet ID is not a faucet
+ * - -4: Mint request construction failed
+ * - -6: Transaction submission failed (e.g. max supply exceeded, key not in keystore)
+ * - -7: Output buffer too small, checked before anything is submitted
+ * - -8: Queue full
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_mint(MidenHandle handle,
+                      const char *faucet_id_hex,
+                      const char *target_account_id_hex,
+                      uint64_t amount,
+                      uint32_t note_type,
+                      uint8_t *tx_id_out,
+                      uintptr_t *tx_id_out_len);
+
+/**
+ * Check that a string is an account ID the FFI accepts
+ *
+ * Needs no client handle and does no I/O; safe to call from the main thread, e.g. on
+ * every keystroke. A valid ID may still be unknown to the node.
+ *
+ * # Parameters
+ * - `account_id`: Account ID to check (C string)
+ * - `reason_out`: Result of the check (`WcIdValidation_Valid` or why it was rejected);
+ *   may be NULL
+ *
+ * # Returns
+ * - 0: Valid
+ * - -1: Invalid parameters (NULL string)
+ * - -3: Not a valid account ID; see `reason_out`
+ */
+int32_t wc_miden_validate_account_id(const char *account_id, enum WcIdValidation *reason_out);
+
+/**
+ * Check that a string is a note ID the FFI accepts
+ *
+ * Needs no client handle and does no I/O; safe to call from the main thread. A valid ID
+ * may still be unknown to the store and the node.
+ *
+ * # Parameters
+ * - `note_id`: Note ID to check (C string)
+ * - `reason_out`: Result of the check (`WcIdValidation_Valid` or why it was rejected);
+ *   may be NULL
+ *
+ * # Returns
+ * - 0: Valid
+ * - -1: Invalid parameters (NULL string)
+ * - -4: Not a valid note ID; see `reason_out`
+ */
+int32_t wc_miden_validate_note_id(const char *note_id, enum WcIdValidation *reason_out);
+
+/**
+ * Export a note's inclusion proof together with its block header (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `note_id_hex`: Input note ID (C string)
+ * - `bytes_out` / `bytes_out_len`: Output buffer for the package
+ *
+ * # Output
+ * JSON `{"version":1,"note_id","block_num","metadata","inclusion_proof","block_header"}`
+ * with the last three fields hex-encoded in the miden serialization format.
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -4: Invalid note ID, or the note is not committed yet
+ * - -5: Note or block header not found
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_export_note_inclusion_proof(MidenHandle handle,
+                                             const char *note_id_hex,
+                                             uint8_t *bytes_out,
+                                             uintptr_t *bytes_out_len);
+
+/**
+ * Get input notes selected by store state (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `account_id_hex`: Only notes for this account (C string, NULL = all active accounts)
+ * - `filter`: `WcNoteFilter` value (0 = consumable, as `wc_miden_get_input_notes`)
+ * - `json_out` / `json_out_len`: Output buffer for the `wc_miden_get_input_notes` JSON;
+ *   each note also carries `"state"`: `"expected"`, `"committed"`, `"processing"` or
+ *   `"consumed"` (except with the consumable filter)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters (including an unknown filter)
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID
+ * - -4: Note query failed
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_get_input_notes_filtered(MidenHandle handle,
+                                          const char *account_id_hex,
+                                          uint32_t filter,
+                                          uint8_t *json_out,
+                                          uintptr_t *json_out_len);
+
+/**
+ * Turn canonical JSON output on or off (process-wide)
+ *
+ * See the module docs for the canonical form. Off by default.
+ *
+ * # Returns
+ * - 0: Success
+ */
+int32_t wc_miden_set_canonical_json(bool enabled);
+
+/**
+ * Export an account's secret key in a passphrase-encrypted envelope (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread. Deriving the
+ * encryption key from the passphrase takes a noticeable moment by design.
+ *
+ * # Parameters
+ * - `account_id_hex`: Account ID (C string); its key must be held by this client
+ * - `passphrase`: Passphrase protecting the envelope (C string, not empty)
+ * - `envelope_out` / `envelope_out_len`: Output buffer for the envelope JSON (see the
+ *   module docs; about 5 KB)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID, or the key is held by an external signer or missing
+ * - -5: Account not in the store
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_export_key(MidenHandle handle,
+                            const char *account_id_hex,
+                            const char *passphrase,
+                            uint8_t *envelope_out,
+                            uintptr_t *envelope_out_len);
+
+/**
+ * Import a secret key from an envelope made by `wc_miden_export_key` (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * The key is saved to the filesystem keystore and recorded for the envelope's account,
+ * which does not need to be in the store yet (import it afterwards to sign with it).
+ *
+ * # Parameters
+ * - `envelope_ptr` / `envelope_len`: Envelope JSON
+ * - `passphrase`: Passphrase the envelope was exported with (C string)
+ * - `account_id_out` / `account_id_out_len`: Output buffer for the account ID (hex)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or a malformed envelope
+ * - -2: Invalid handle or worker closed
+ * - -3: The key could not be saved
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ * - -101: Wrong passphrase (or a tampered envelope)
+ */
+int32_t wc_miden_import_key(MidenHandle handle,
+                            const uint8_t *envelope_ptr,
+                            uintptr_t envelope_len,
+                            const char *passphrase,
+                            uint8_t *account_id_out,
+                            uintptr_t *account_id_out_len);
+
+/**
+ * Create a wallet account whose auth key can be rotated (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Like `wc_miden_create_wallet_with_key_backend`, but the account has updatable code and
+ * an auth component that `wc_miden_rotate_auth_key` can move to a new key. The account ID
+ * differs from the one a plain wallet gets for the same seed.
+ *
+ * # Parameters
+ * - `seed_ptr` / `seed_len`: 32-byte account seed (NULL generates a random seed)
+ * - `storage_mode`: `WcStorageMode` value (0 = public, 1 = private)
+ * - `key_backend`: `WcKeyBackend` value (0 = filesystem, 2 = memory)
+ * - `account_id_out` / `account_id_out_len`: Output buffer for the account ID (hex)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, `WcStorageMode_Network` or `WcKeyBackend_Callback`
+ * - -2: Invalid handle or worker closed
+ * - -3: Account creation failed
+ * - -7: Output buffer too small
+ * - -105: An account with the derived ID already exists in the store or on chain
+ * - -106: The store has not synced yet and `require_sync_for_create` is set
+ */
+int32_t wc_miden_create_rotatable_wallet(MidenHandle handle,
+                                         const uint8_t *seed_ptr,
+                                         uintptr_t seed_len,
+                                         uint32_t storage_mode,
+                                         uint32_t key_backend,
+                                         uint8_t *account_id_out,
+                                         uintptr_t *account_id_out_len);
+
+/**
+ * Rotate the auth key of a wallet created with `wc_miden_create_rotatable_wallet`
+ * (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Generates a new Falcon key in the account's key backend and submits a transaction,
+ * signed with the current key, that makes the new key the account's auth key. The old
+ * key is then retired: it stays in the keystore but no longer signs. If the transaction
+ * is discarded, rotate again (see the module docs).
+ *
+ * # Parameters
+ * - `account_id_hex`: Account ID (C string)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"tx_id","account_id","pub_key","retired_pub_key"}` (public key commitments, hex)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, or the key is held by an external signer
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID, or the account has immutable code or no rotatable auth
+ *   component
+ * - -5: Account not in the store
+ * - -6: Transaction failed
+ * - -7: Output buffer too small (checked before the transaction is submitted)
+ * - -99: Operation timed out
+ * - -108: Less memory available than `proving_memory_ceiling_mb`
+ */
+int32_t wc_miden_rotate_auth_key(MidenHandle handle,
+                                 const char *account_id_hex,
+                                 uint8_t *json_out,
+                                 uintptr_t *json_out_len);
+
+/**
+ * Create a wallet account with its key in the given backend (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Keys held by an external signer are created with `wc_miden_create_wallet_with_signer`.
+ *
+ * # Parameters
+ * - `seed_ptr` / `seed_len`: 32-byte account seed (NULL generates a random seed)
+ * - `storage_mode`: `WcStorageMode` value (0 = public, 1 = private)
+ * - `key_backend`: `WcKeyBackend` value (0 = filesystem, 2 = memory)
+ * - `account_id_out` / `account_id_out_len`: Output buffer for the account ID (hex)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, `WcStorageMode_Network` or `WcKeyBackend_Callback`
+ * - -2: Invalid handle or worker closed
+ * - -3: Account creation failed
+ * - -7: Output buffer too small
+ * - -105: An account with the derived ID already exists in the store or on chain
+ * - -106: The store has not synced yet and `require_sync_for_create` is set
+ */
+int32_t wc_miden_create_wallet_with_key_backend(MidenHandle handle,
+                                                const uint8_t *seed_ptr,
+                                                uintptr_t seed_len,
+                                                uint32_t storage_mode,
+                                                uint32_t key_backend,
+                                                uint8_t *account_id_out,
+                                                uintptr_t *account_id_out_len);
+
+/**
+ * Get the message of the last failed request of a handle
+ *
+ * Safe to call from any thread, including while the worker is busy.
+ *
+ * # Parameters
+ * - `buf` / `buf_len`: Output buffer for the UTF-8 message (not NUL-terminated), e.g.
+ *   `send: transaction executor error: ... (code -6)`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle
+ * - -5: No request of this handle has failed yet
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_last_error_message(MidenHandle handle, uint8_t *buf, uintptr_t *buf_len);
+
+/**
+ * Track a submitted transaction until it is finalized or discarded (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * The callback receives
+ * `{"type":"tx_live_update","tx_id","state","block_num","timestamp"}` right away with the
+ * current state, then on every transition detected after a sync (see the module docs for
+ * the states; `timestamp` is the commit block's ISO-8601 time). It runs on the worker
+ * thread; the JSON buffer is only valid during the call. Tracking persists across
+ * relaunches, reporting through the event callback until the ID is tracked again.
+ *
+ * # Parameters
+ * - `tx_id_hex`: Transaction ID returned by a submit call (C string)
+ * - `callback`: Update callback, or NULL to rely on the event callback only
+ * - `user_data`: Passed back to the callback
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -5: Transaction not found in the store
+ */
+int32_t wc_miden_track_tx_live(MidenHandle handle,
+                               const char *tx_id_hex,
+                               WcEventCallback callback,
+                               void *user_data);
+
+/**
+ * Report the Rust-allocated buffers not yet released with `wc_bytes_free`
+ *
+ * Safe to call from any thread. Requires the `memory-debug` Cargo feature; otherwise the
+ * report is `{"enabled":false}`.
+ *
+ * # Parameters
+ * - `json_out` / `json_out_len`: Output buffer for `{"enabled","outstanding_count",
+ *   "outstanding_bytes","total_allocated","total_freed","invalid_frees","allocations":
+ *   [{"ptr","len","site","age_ms"}]}` (`site`: source location that produced the buffer;
+ *   allocations oldest first)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -7: Output buffer too small
+ */
+int32_t wc_memory_report(uint8_t *json_out, uintptr_t *json_out_len);
+
+/**
+ * Sign a message with an account's key (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `account_id_hex`: Account ID (C string); its key must be held by this client
+ * - `message_ptr` / `message_len`: Message (1 byte to 64 KB)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"version":1,"account_id","digest","pub_key","signature"}` (hex fields; `signature`:
+ *   serialized RPO Falcon512 signature over `digest`, see the module docs)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters (including an empty or oversized message)
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID, or the account has no key this client can sign with
+ * - -5: Account not in the store
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_sign_message(MidenHandle handle,
+                              const char *account_id_hex,
+                              const uint8_t *message_ptr,
+                              uintptr_t message_len,
+                              uint8_t *json_out,
+                              uintptr_t *json_out_len);
+
+/**
+ * Verify a message signature made by `wc_miden_sign_message`
+ *
+ * Needs no client handle; safe to call from any thread.
+ *
+ * # Parameters
+ * - `pub_key_hex`: Signer's public key commitment, 32 bytes hex (C string), e.g. the
+ *   `pub_key` reported by `wc_miden_get_public_key`
+ * - `message_ptr` / `message_len`: Signed message (1 byte to 64 KB)
+ * - `signature_hex`: Serialized RPO Falcon512 signature, hex (C string)
+ *
+ * # Returns
+ * - 0: The signature is valid for the message and key
+ * - -1: Invalid parameters or a malformed signature
+ * - -3: Signature invalid, made by another key, or over another message
+ */
+int32_t wc_miden_verify_signature(const char *pub_key_hex,
+                                  const uint8_t *message_ptr,
+                                  uintptr_t message_len,
+                                  const char *signature_hex);
+
+/**
+ * Generate a random English BIP-39 mnemonic
+ *
+ * # Parameters
+ * - `word_count`: 12, 15, 18, 21 or 24 (128 to 256 bits of entropy)
+ * - `phrase_out` / `phrase_out_len`: Output buffer for the space-separated phrase (UTF-8)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters (including an unsupported word count)
+ * - -7: Output buffer too small; `*phrase_out_len` holds the size needed, and a retry
+ *   generates a different phrase
+ */
+int32_t wc_mnemonic_generate(uint32_t word_count, uint8_t *phrase_out, uintptr_t *phrase_out_len);
+
+/**
+ * Derive the 64-byte BIP-39 seed of a mnemonic
+ *
+ * The phrase is checked against the English word list and its checksum, so a mistyped
+ * word is rejected instead of silently giving a different wallet.
+ *
+ * # Parameters
+ * - `phrase`: Space-separated English mnemonic (C string)
+ * - `passphrase`: Optional BIP-39 passphrase (C string, NULL or empty for none)
+ * - `seed_out` / `seed_out_len`: Output buffer (at least 64 bytes); receives the seed
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters (including an invalid phrase or checksum)
+ * - -7: Output buffer too small
+ */
+int32_t wc_mnemonic_to_seed(const char *phrase,
+                            const char *passphrase,
+                            uint8_t *seed_out,
+                            uintptr_t *seed_out_len);
+
+/**
+ * Open a signing session for `message` under the key committed to by `pub_key_commitment`
+ *
+ * The session belongs to `handle` and expires after `MPC_SESSION_TTL_SECS` seconds.
+ *
+ * # Parameters
+ * - `pub_key_commitment`: 32-byte serialized public key commitment
+ * - `message`: 32-byte serialized message word
+ * - `participants`: Number of parties that must contribute each round (2-16)
+ * - `session_id_out`: Receives the session ID
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, or `MPC_MAX_SESSIONS` sessions already open on the handle
+ * - -2: Invalid handle
+ */
+int32_t wc_mpc_session_start(MidenHandle handle,
+                             const uint8_t *pub_key_commitment,
+                             const uint8_t *message,
+                             uint32_t participants,
+                             uint64_t *session_id_out);
+
+/**
+ * Post a participant's payload for the current round
+ *
+ * Rounds advance automatically once every participant has contributed; posting to any
+ * round other than the current one is rejected so that parties stay in lockstep.
+ * A payload may hold up to `MPC_MAX_PAYLOAD_BYTES` bytes, and all payloads of a session
+ * up to `MPC_MAX_SESSION_BYTES`.
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Unknown or expired session, wrong round, participant out of range, duplicate
+ *   contribution, payload over the size limits or round limit reached
+ * - -2: Invalid handle
+ */
+int32_t wc_mpc_session_contribute(MidenHandle handle,
+                                  uint64_t session_id,
+                                  uint32_t participant,
+                                  uint32_t round,
+                                  const uint8_t *payload_ptr,
+                                  uintptr_t payload_len);
+
+/**
+ * Get the payloads posted for a round
+ *
+ * # Output JSON
+ * `{"session_id","round","current_round","complete","contributions":[{"participant","payload":"<hex>"}]}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Unknown or expired session, unknown round, or invalid parameters
+ * - -2: Invalid handle
+ * - -7: Output buffer too small
+ */
+int32_t wc_mpc_session_round_payloads(MidenHandle handle,
+                                      uint64_t session_id,
+                                      uint32_t round,
+                                      uint8_t *json_out,
+                                      uintptr_t *json_out_len);
+
+/**
+ * Close a session with the combined signature
+ *
+ * The signature (serialized RPO Falcon512) must verify against the session's public key
+ * commitment and message. The session is removed only when verification succeeds, so a
+ * failed combination can be retried.
+ *
+ * # Returns
+ * - 0: Signature verified, session closed
+ * - -1: Unknown or expired session, or malformed signature
+ * - -2: Invalid handle
+ * - -3: Signature does not verify
+ */
+int32_t wc_mpc_session_finish(MidenHandle handle,
+                              uint64_t session_id,
+                              const uint8_t *signature_ptr,
+                              uintptr_t signature_len);
+
+/**
+ * Abort a session and discard its contributions
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Unknown or expired session
+ * - -2: Invalid handle
+ */
+int32_t wc_mpc_session_abort(MidenHandle handle, uint64_t session_id);
+
+/**
+ * Configure the name registry used by a handle
+ *
+ * # Parameters
+ * - `config_json`: `{"account_id":"0x..","storage_slot":1,"register_script":"<MASM>"}`,
+ *   or NULL to remove the configuration. `register_script` is the registry's
+ *   registration note script and is only needed for `wc_miden_register_name`.
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid configuration (including a script that does not compile)
+ * - -2: Invalid handle
+ */
+int32_t wc_miden_set_name_registry(MidenHandle handle, const char *config_json);
+
+/**
+ * Resolve a registered name to an account ID (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Names are case-insensitive. The first lookup imports the registry account; later
+ * lookups read the state of the last sync.
+ *
+ * # Parameters
+ * - `name`: Name to resolve (C string, 3-32 characters of `[a-z0-9._-]`)
+ * - `account_id_out` / `account_id_out_len`: Output buffer for the account ID hex
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid name or no registry configured
+ * - -2: Invalid handle or worker closed
+ * - -3: Registry entry is not a valid account ID
+ * - -5: Name not registered or registry account not found
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_resolve_name(MidenHandle handle,
+                              const char *name,
+                              uint8_t *account_id_out,
+                              uintptr_t *account_id_out_len);
+
+/**
+ * Register a name for a local account (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Submits the registration note; the name resolves once the registry has consumed it.
+ * Check availability with `wc_miden_resolve_name` first (-5 means free).
+ *
+ * # Parameters
+ * - `account_id_hex`: Account that sends the note and is registered under `name`
+ * - `name`: Name to register (C string, 3-32 characters of `[a-z0-9._-]`)
+ * - `tx_id_out` / `tx_id_out_len`: Output buffer for the transaction ID hex
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid name, or no registry or registration script configured
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID
+ * - -4: Note construction failed
+ * - -6: Transaction failed
+ * - -7: Output buffer too small
+ * - -102: Blocked by the screening callback
+ */
+int32_t wc_miden_register_name(MidenHandle handle,
+                               const char *account_id_hex,
+                               const char *name,
+                               uint8_t *tx_id_out,
+                               uintptr_t *tx_id_out_len);
+
+/**
+ * Check that the node is on the network the store belongs to (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Run it after creating a handle, before syncing or sending. The first successful check
+ * pins the node's genesis block for the store (see the module docs).
+ *
+ * # Output JSON
+ * `{"network":"devnet","endpoint","genesis_commitment","genesis_timestamp",
+ *   "newly_pinned":true}` (`network`: `testnet`, `devnet`, `localhost` or `custom`)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle, worker closed or node unreachable
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ * - -104: The node is on another network than the store; the store is left untouched
+ *   (`wc_miden_last_error_message` names both networks)
+ */
+int32_t wc_miden_check_network(MidenHandle handle, uint8_t *json_out, uintptr_t *json_out_len);
+
+/**
+ * Export an output note as a serialized NoteFile (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `note_id_hex`: Output note ID (C string)
+ * - `export_type`: `WcNoteExportType` value (0 = id, 1 = full, 2 = partial)
+ * - `bytes_out` / `bytes_out_len`: Output buffer for the NoteFile
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or export type
+ * - -2: Invalid handle or worker closed
+ * - -4: Invalid note ID, a full export of an uncommitted note, or a note whose
+ *   recipient is unknown to this store
+ * - -5: Note not found among the notes sent from this store
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_export_note(MidenHandle handle,
+                             const char *note_id_hex,
+                             uint32_t export_type,
+                             uint8_t *bytes_out,
+                             uintptr_t *bytes_out_len);
+
+/**
+ * Import a serialized NoteFile as an input note (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `bytes` / `len`: Serialized NoteFile (e.g. from `wc_miden_export_note`)
+ * - `note_id_out` / `note_id_out_len`: Output buffer for the note ID (hex)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or not a NoteFile
+ * - -2: Invalid handle or worker closed
+ * - -4: Import failed (unknown note ID, invalid inclusion proof, store error)
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_import_note(MidenHandle handle,
+                             const uint8_t *bytes,
+                             uintptr_t len,
+                             uint8_t *note_id_out,
+                             uintptr_t *note_id_out_len);
+
+/**
+ * Import a batch of serialized NoteFiles (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `blobs` / `blob_lens` / `count`: Arrays of pointers to serialized NoteFiles and their lengths
+ * - `atomic`: Must be false; the store cannot import a batch all-or-nothing (see the
+ *   module docs)
+ * - `report_out` / `report_out_len`: Output buffer for the JSON report
+ *
+ * # Output JSON
+ * `{"imported":2,"failed":1,"results":[{"index":0,"ok":true,"note_id":"0x.."},
+ *   {"index":1,"ok":false,"error":-1}]}`
+ *
+ * # Returns
+ * - 0: Batch processed (check the report for per-item failures)
+ * - -1: Invalid parameters, batch too large or `atomic` set
+ * - -2: Invalid handle or worker closed
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_import_notes_batch(MidenHandle handle,
+                                    const uint8_t *const *blobs,
+                                    const uintptr_t *blob_lens,
+                                    uintptr_t count,
+                                    bool atomic,
+                                    uint8_t *report_out,
+                                    uintptr_t *report_out_len);
+
+/**
+ * Render a note into display-ready strings (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `note_id_hex`: Input or output note ID (C string)
+ * - `locale`: Locale identifier such as `en`, `de_CH` or `zh-Hans` (NULL or unknown uses
+ *   English)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"note_id","assets":[{"faucet_id","amount","symbol","decimals","formatted"}],
+ *   "amount_text","sender","sender_label","sender_is_own","from_text","expires_at_block",
+ *   "expiry_text"}` (`symbol`/`decimals` are null for unknown tokens; sender and expiry
+ *   fields are null when not known)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -4: Invalid note ID or store error
+ * - -5: Note not found
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_render_note_summary(MidenHandle handle,
+                                     const char *note_id_hex,
+                                     const char *locale,
+                                     uint8_t *json_out,
+                                     uintptr_t *json_out_len);
+
+/**
+ * Open a snapshot of an account as an object handle (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `account_id_hex`: Account ID (C string)
+ * - `obj_out`: Receives the object handle; release it with `wc_object_free`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID
+ * - -5: Account not in the store
+ * - -99: Operation timed out
+ */
+int32_t wc_account_open(MidenHandle handle, const char *account_id_hex, WcObject *obj_out);
+
+/**
+ * Open a snapshot of a note (received or sent) as an object handle (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `note_id_hex`: Note ID (C string)
+ * - `obj_out`: Receives the object handle; release it with `wc_object_free`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -4: Invalid note ID or note query failed
+ * - -5: Note not in the store
+ * - -99: Operation timed out
+ */
+int32_t wc_note_open(MidenHandle handle, const char *note_id_hex, WcObject *obj_out);
+
+/**
+ * Release an object handle
+ *
+ * Safe to call from any thread. Freeing 0, an unknown or an already freed handle is a no-op.
+ */
+void wc_object_free(WcObject obj);
+
+/**
+ * Account ID (hex) of an account object
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Not an open account object
+ * - -7: Output buffer too small
+ */
+int32_t wc_account_id(WcObject obj, uint8_t *out, uintptr_t *out_len);
+
+/**
+ * Nonce of an account object
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Not an open account object
+ */
+int32_t wc_account_nonce(WcObject obj, uint64_t *nonce_out);
+
+/**
+ * State commitment (hex) of an account object
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Not an open account object
+ * - -7: Output buffer too small
+ */
+int32_t wc_account_commitment(WcObject obj, uint8_t *out, uintptr_t *out_len);
+
+/**
+ * Balance of one fungible asset in an account object (0 when the vault has none)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Not an open account object
+ * - -3: Invalid faucet ID
+ */
+int32_t wc_account_balance(WcObject obj, const char *faucet_id_hex, uint64_t *balance_out);
+
+/**
+ * Note ID (hex) of a note object
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Not an open note object
+ * - -7: Output buffer too small
+ */
+int32_t wc_note_id(WcObject obj, uint8_t *out, uintptr_t *out_len);
+
+/**
+ * Sender account ID (hex) of a note object
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Not an open note object
+ * - -5: Sender unknown (a received note whose metadata has not been synced yet)
+ * - -7: Output buffer too small
+ */
+int32_t wc_note_sender(WcObject obj, uint8_t *out, uintptr_t *out_len);
+
+/**
+ * State of a note object: `expected`, `committed`, `processing` or `consumed`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Not an open note object
+ * - -7: Output buffer too small
+ */
+int32_t wc_note_state(WcObject obj, uint8_t *out, uintptr_t *out_len);
+
+/**
+ * Inclusion block of a note object
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Not an open note object
+ * - -5: Not committed (as of the snapshot)
+ */
+int32_t wc_note_block_num(WcObject obj, uint32_t *block_num_out);
+
+/**
+ * Amount of one fungible asset carried by a note object (0 when it carries none)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Not an open note object
+ * - -3: Invalid faucet ID
+ */
+int32_t wc_note_amount(WcObject obj, const char *faucet_id_hex, uint64_t *amount_out);
+
+/**
+ * Set (or clear) the store change observer of a handle (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Replaces any previous observer. The callback receives one change event per written row
+ * (see the module docs) after each request that changed the store, starting with the
+ * first write after this call. It runs on the worker thread and must return quickly;
+ * the JSON buffer is only valid during the call.
+ *
+ * # Parameters
+ * - `domain_flags`: `OBSERVE_*` flags to observe (`OBSERVE_ALL` for everything); 0 clears
+ *   the observer
+ * - `callback`: Change callback; NULL clears the observer
+ * - `user_data`: Passed back to the callback
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters (unknown flags)
+ * - -2: Invalid handle or worker closed
+ * - -3 / -4 / -5: The accounts, notes or transactions could not be read for the baseline
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_observe(MidenHandle handle,
+                         uint32_t domain_flags,
+                         WcEventCallback callback,
+                         void *user_data);
+
+/**
+ * List the notes created by this store's transactions (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * States reflect the last sync.
+ *
+ * # Parameters
+ * - `account_id_hex`: Only notes sent by this account (C string, NULL = all active accounts)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"notes":[{"note_id","sender","state":"expected"|"committed"|"consumed","note_type",
+ *   "assets":[{"faucet_id","amount"}],"recipient":{"digest","target_account_id",
+ *   "reclaim_block"},"expected_height","block_num","timestamp"}],"total_count"}`
+ *   (`target_account_id`: P2ID/P2IDE target, null for other or digest-only recipients;
+ *   `block_num` and `timestamp`: the inclusion block, null until committed)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID
+ * - -4: Note query failed
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_get_output_notes(MidenHandle handle,
+                                  const char *account_id_hex,
+                                  uint8_t *json_out,
+                                  uintptr_t *json_out_len);
+
+/**
+ * Sign a statement proving control of an account (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `account_id_hex`: Account ID (C string); its key must be held by this client
+ * - `challenge_ptr` / `challenge_len`: Server challenge (16-1024 bytes)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"version":1,"account_id","challenge","digest","pub_key","signature"}` (hex fields;
+ *   `signature`: serialized RPO Falcon512 signature over `digest`, see the module docs)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters (including a challenge outside 16-1024 bytes)
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID, or the account has no key this client can sign with
+ * - -5: Account not in the store
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_prove_ownership(MidenHandle handle,
+                                 const char *account_id_hex,
+                                 const uint8_t *challenge_ptr,
+                                 uintptr_t challenge_len,
+                                 uint8_t *json_out,
+                                 uintptr_t *json_out_len);
+
+/**
+ * Get one page of the account list (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `offset`: Index of the first account of the page
+ * - `limit`: Maximum number of accounts (must be > 0)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"accounts":["0x.."],"total_count","offset","has_more"}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Account query failed
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_get_accounts_page(MidenHandle handle,
+                                   uint32_t offset,
+                                   uint32_t limit,
+                                   uint8_t *json_out,
+                                   uintptr_t *json_out_len);
+
+/**
+ * Get one page of the consumable notes (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `account_id_hex`: Account ID (C string, NULL = all active accounts)
+ * - `offset`: Index of the first note of the page
+ * - `limit`: Maximum number of notes (must be > 0)
+ * - `json_out` / `json_out_len`: Output buffer for `{"notes":[..],"total_count","offset",
+ *   "has_more"}` (notes as in `wc_miden_get_input_notes`; `total_count` counts every page)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID
+ * - -4: Note query failed
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_get_input_notes_page(MidenHandle handle,
+                                      const char *account_id_hex,
+                                      uint32_t offset,
+                                      uint32_t limit,
+                                      uint8_t *json_out,
+                                      uintptr_t *json_out_len);
+
+/**
+ * Create a batch of wallets derived from a base (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Creates the wallets with indices `first_index..first_index + count` in order (see the
+ * module docs for the derivation). Stops at the first wallet that cannot be created and
+ * still succeeds, reporting the wallets handled so far and the failure.
+ *
+ * # Parameters
+ * - `count`: Wallets to create (1-100)
+ * - `base_ptr` / `base_len`: 32-byte derivation base (NULL generates a random base,
+ *   returned in the output; store it to resume or recreate the batch)
+ * - `first_index`: Index of the first wallet
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"derivation_base","wallets":[{"index","account_id","existing"}],"created_count",
+ *   "failed":{"index","code"}}` (`existing`: already in the store, skipped; `failed`:
+ *   null when every wallet was handled, else the error code of the wallet at `index`)
+ *
+ * # Returns
+ * - 0: Success (check `failed`)
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Key backend records could not be saved
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_create_wallets_bulk(MidenHandle handle,
+                                     uint32_t count,
+                                     const uint8_t *base_ptr,
+                                     uintptr_t base_len,
+                                     uint32_t first_index,
+                                     uint8_t *json_out,
+                                     uintptr_t *json_out_len);
+
+/**
+ * Get the public key of an account (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `account_id_hex`: Account ID (C string)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"account_id","scheme":"rpo_falcon512","pub_key","public_key","key_backend"}`
+ *   (`pub_key`: commitment, hex; `public_key`: serialized public key, hex, or null when an
+ *   external signer holds the key; see the module docs)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID, or the account has no auth key
+ * - -5: Account not in the store
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_get_public_key(MidenHandle handle,
+                                const char *account_id_hex,
+                                uint8_t *json_out,
+                                uintptr_t *json_out_len);
+
+/**
+ * Register the device with a push relay for the client's note tags (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `relay_url`: Registration endpoint of the relay service (C string, http or https)
+ * - `device_token`: APNs device token, hex encoded (C string)
+ * - `tags_json`: JSON array of note tags (u32), or NULL for every tag the client tracks
+ * - `json_out` / `json_out_len`: Output buffer for `{"registered_tags":[..]}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, no tags or more than [`PUSH_MAX_TAGS`]
+ * - -2: Invalid handle or worker closed
+ * - -4: Tracked tags could not be read
+ * - -7: Output buffer too small
+ * - -103: Relay unreachable or registration refused
+ */
+int32_t wc_miden_register_push(MidenHandle handle,
+                               const char *relay_url,
+                               const char *device_token,
+                               const char *tags_json,
+                               uint8_t *json_out,
+                               uintptr_t *json_out_len);
+
+/**
+ * Fetch the note referenced by a push payload (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Imports only the referenced note, so it is quick enough for a notification service
+ * extension. When `requires_sync` is true the note could not be fetched by ID (private
+ * note, or the node has not caught up) and a regular sync is needed.
+ *
+ * # Parameters
+ * - `payload_json`: Push payload, the APNs `userInfo` dictionary or its `miden` object (C string)
+ * - `json_out` / `json_out_len`: Output buffer for `{"note_id","imported","requires_sync"}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or payload without a valid note ID
+ * - -2: Invalid handle or worker closed
+ * - -4: Store error
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_handle_push(MidenHandle handle,
+                             const char *payload_json,
+                             uint8_t *json_out,
+                             uintptr_t *json_out_len);
+
+/**
+ * Configure the node RPC rate limit of a handle
+ *
+ * Calls beyond the budget wait for a token instead of failing.
+ *
+ * # Parameters
+ * - `calls_per_second`: Sustained rate (0 disables rate limiting)
+ * - `burst`: Number of calls allowed back-to-back after an idle period (at least 1)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Negative or non-finite rate
+ * - -2: Invalid handle
+ */
+int32_t wc_miden_set_rpc_rate_limit(MidenHandle handle, double calls_per_second, uint32_t burst);
+
+/**
+ * Get rate limiter configuration and throttle statistics
+ *
+ * # Output JSON
+ * `{"enabled":true,"calls_per_second":2.0,"burst":5,"available_tokens":3,"calls":120,
+ *   "throttled_calls":14,"total_wait_ms":6200}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_get_rpc_stats(MidenHandle handle, uint8_t *json_out, uintptr_t *json_out_len);
+
+/**
+ * Send fungible assets in a note the sender can recall from a given block (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ * NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+ *
+ * # Parameters
+ * - `account_id_hex`: Sending account ID (C string)
+ * - `target_account_id_hex`: Receiving account ID (C string)
+ * - `faucet_id_hex`: Faucet of the sent asset (C string)
+ * - `amount`: Amount in base units (must be > 0)
+ * - `reclaim_block`: Block from which the sender can recall the note (must be after the
+ *   current sync height)
+ * - `note_type`: `WcNoteType` value (0 = public, 1 = private)
+ * - `json_out` / `json_out_len`: Output buffer for `{"tx_id":"0x..","note_id":"0x.."}`,
+ *   plus `"note_file"` (hex NoteFile for the recipient) for a private note
+ *
+ * # Returns
+ * Same codes as `wc_miden_send`; -1 also for a reclaim block that is not in the future.
+ * For a private note only the result without `note_file` is checked up front, as in
+ * `wc_miden_send_private`: a -7 with `*json_out_len` above 157 means the transaction was
+ * submitted; do not retry it.
+ */
+int32_t wc_miden_send_recallable(MidenHandle handle,
+                                 const char *account_id_hex,
+                                 const char *target_account_id_hex,
+                                 const char *faucet_id_hex,
+                                 uint64_t amount,
+                                 uint32_t reclaim_block,
+                                 uint32_t note_type,
+                                 uint8_t *json_out,
+                                 uintptr_t *json_out_len);
+
+/**
+ * List the outgoing notes an account can reclaim now (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Based on the last sync: sync first so notes consumed by their target are not listed.
+ *
+ * # Parameters
+ * - `account_id_hex`: Sending account ID (C string)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"current_block","notes":[{"note_id","target_account_id","assets":[{"faucet_id",
+ *   "amount"}],"reclaim_block"}]}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID
+ * - -4: Note query failed
+ * - -5: Sync height unavailable
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_get_reclaimable_notes(MidenHandle handle,
+                                       const char *account_id_hex,
+                                       uint8_t *json_out,
+                                       uintptr_t *json_out_len);
+
+/**
+ * Consume reclaimable outgoing notes back into the sending account (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ * NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+ *
+ * # Parameters
+ * - `account_id_hex`: Sending account ID (C string)
+ * - `note_ids_json`: JSON array of note IDs, each listed by `wc_miden_get_reclaimable_notes`
+ * - `tx_id_out` / `tx_id_out_len`: Output buffer for the transaction ID (hex)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID
+ * - -4: Invalid note ID list, or a note that is not reclaimable (yet)
+ * - -5: Sync height unavailable
+ * - -6: Transaction failed (e.g. the target consumed a note since the last sync)
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ * - -102: Blocked by the screening callback
+ */
+int32_t wc_miden_reclaim_notes(MidenHandle handle,
+                               const char *account_id_hex,
+                               const char *note_ids_json,
+                               uint8_t *tx_id_out,
+                               uintptr_t *tx_id_out_len);
+
+/**
+ * Generate a signed proof-of-reserves report (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `account_ids_json`: JSON array of public account IDs (1-100), e.g. `["0x..","0x.."]`
+ * - `block_num`: Report block (0 = node tip; otherwise must equal the node tip)
+ * - `out_path`: Destination file of the report JSON (overwritten if present)
+ *
+ * # Report JSON
+ * `{"report":{"version":1,"block_num","generated_at","accounts":[{"account_id",
+ *   "account_commitment","local_state_matches","balances":[...],"merkle_proof"}],
+ *   "totals":[{"faucet_id","amount"}]},"digest":"..","signatures":[{"account_id","pub_key",
+ *   "signature"}]}`. `digest` is the RPO hash of the `report` JSON text; totals only count
+ * accounts whose local state matches the proof.
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or the file could not be written
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid or private account ID, or signing failed
+ * - -5: Account not found, proof request failed, or `block_num` is not the node tip
+ */
+int32_t wc_miden_generate_por_report(MidenHandle handle,
+                                     const char *account_ids_json,
+                                     uint32_t block_num,
+                                     const char *out_path);
+
+/**
+ * Set the store retention policy (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * The policy is applied immediately and then automatically after every successful sync.
+ * The report lists the consumed notes and transactions the policy deleted from the store.
+ * With `"dry_run": true` it lists what would be deleted and nothing is changed: no rows
+ * are deleted and the stored policy and its state are left as they were.
+ *
+ * # Policy JSON
+ * `{"consumed_note_max_age_days":90,"max_transactions":500,"dry_run":false}`
+ * (omitted or null fields disable the rule; `{}` disables the policy)
+ *
+ * # Output JSON
+ * `{"dry_run":false,"note_ids":["0x.."],"transaction_ids":["0x.."]}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -4: Note lookup or deletion failed
+ * - -5: Transaction lookup failed
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_set_retention_policy(MidenHandle handle,
+                                      const char *policy_json,
+                                      uint8_t *report_out,
+                                      uintptr_t *report_out_len);
+
+/**
+ * Get per-method node RPC metrics of a handle
+ *
+ * Readable while the worker is busy; does not queue.
+ *
+ * # Output JSON
+ * `{"methods":{"GetBlockHeaderByNumber":{"calls","errors","total_ms","buckets":[{"le_ms",
+ *   "count"}],"error_kinds":{"RpcError.ConnectionError":2}}}}` (`buckets` cumulative as in
+ *   Prometheus; the last bucket has `"le_ms":null`, i.e. +Inf)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_get_rpc_metrics(MidenHandle handle, uint8_t *json_out, uintptr_t *json_out_len);
+
+/**
+ * Write per-method node RPC metrics of a handle to a file in the Prometheus text format
+ *
+ * Does not queue. Point a node exporter's textfile collector at the file and call this
+ * periodically; the file is replaced atomically.
+ *
+ * # Parameters
+ * - `path`: Destination file (C string), e.g. `.../miden_rpc.prom`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or the file could not be written
+ * - -2: Invalid handle
+ */
+int32_t wc_miden_export_rpc_metrics(MidenHandle handle, const char *path);
+
+/**
+ * Set (or clear, with a NULL callback) the process-wide screening callback
+ *
+ * Applies to every handle. Returning false from the callback aborts the transaction
+ * with -102 and records the denial in the handle's audit log.
+ *
+ * # Returns
+ * - 0: Success
+ */
+int32_t wc_miden_set_screening_callback(WcScreeningCallback callback, void *user_data);
+
+/**
+ * Register (or replace) an external signer under `scheme_id`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: `scheme_id` is 0 (reserved for the filesystem keystore)
+ */
+int32_t wc_miden_register_signer(uint32_t scheme_id, struct WcSignerCallbacks callbacks);
+
+/**
+ * Remove an external signer registration
+ *
+ * Accounts bound to the scheme fail to sign until it is registered again.
+ */
+int32_t wc_miden_unregister_signer(uint32_t scheme_id);
+
+/**
+ * Create a wallet account whose key is held by a registered external signer (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `seed_ptr` / `seed_len`: 32-byte account seed (NULL generates a random seed)
+ * - `storage_mode`: `WcStorageMode` value (0 = public, 1 = private)
+ * - `scheme_id`: Registered signer scheme
+ * - `key_index`: Key index passed to the signer's `get_public_key_commitment`
+ * - `account_id_out` / `account_id_out_len`: Output buffer for the account ID (hex); must
+ *   hold 32 bytes, a smaller one fails with -7 before the account is created
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, unknown scheme or `WcStorageMode_Network`
+ * - -2: Invalid handle or worker closed
+ * - -3: Signer or account creation failed
+ * - -7: Output buffer too small
+ * - -105: An account with the derived ID already exists in the store or on chain
+ * - -106: The store has not synced yet and `require_sync_for_create` is set
+ */
+int32_t wc_miden_create_wallet_with_signer(MidenHandle handle,
+                                           const uint8_t *seed_ptr,
+                                           uintptr_t seed_len,
+                                           uint32_t storage_mode,
+                                           uint32_t scheme_id,
+                                           uint32_t key_index,
+                                           uint8_t *account_id_out,
+                                           uintptr_t *account_id_out_len);
+
+/**
+ * Import a public account from the node and bind it to a registered signer (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Like `wc_miden_import_account_by_id`, but the account can sign from this store: its
+ * key is key `key_index` of the signer `scheme_id` (e.g. the hardware wallet that created
+ * it on another device). The account's auth public key must match that key; otherwise it
+ * is imported without a signer and the call fails with -3.
+ *
+ * # Parameters
+ * - `account_id_hex`: Account ID (C string)
+ * - `scheme_id`: Registered signer scheme
+ * - `key_index`: Key index passed to the signer's `get_public_key_commitment`
+ * - `json_out` / `json_out_len`: Output buffer for the account details (same shape as
+ *   `wc_miden_get_account_details`)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, unknown scheme or a private account
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID, no such account on the node, or the signer key does not match
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_import_account_with_signer(MidenHandle handle,
+                                            const char *account_id_hex,
+                                            uint32_t scheme_id,
+                                            uint32_t key_index,
+                                            uint8_t *json_out,
+                                            uintptr_t *json_out_len);
+
+/**
+ * Set, replace or remove the split policy of an account (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `account_id_hex`: Receiving account (C string)
+ * - `splits_json`: JSON array `[{"account_id":"0x..","percent":12.5},...]` with 1-16
+ *   distinct beneficiaries, percentages > 0 with at most 2 decimals summing to ≤ 100;
+ *   NULL removes the policy
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, or the policy file could not be written
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID
+ */
+int32_t wc_miden_set_split_policy(MidenHandle handle,
+                                  const char *account_id_hex,
+                                  const char *splits_json);
+
+/**
+ * Consume a received note and pay out the shares of its account's split policy (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `note_id_hex`: Consumable note of an account with a split policy (C string)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"tx_id","account_id","notes":[{"note_id","account_id","assets":[...]}]}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -4: Invalid note ID or note operation failed
+ * - -5: Note not consumable, or no consuming account has a split policy
+ * - -6: Transaction submission failed
+ * - -7: Output buffer too small
+ * - -101: Blocked by the account's withdrawal whitelist
+ * - -102: Blocked by compliance screening
+ *
+ * The share notes are only known once the split ran, so only the rest of the result is
+ * checked up front: a -7 with `*json_out_len` above 137 means the transaction was
+ * submitted. Do not retry it; the notes are in the account's output notes.
+ */
+int32_t wc_miden_apply_split(MidenHandle handle,
+                             const char *note_id_hex,
+                             uint8_t *json_out,
+                             uintptr_t *json_out_len);
+
+/**
+ * Compare an account's local state with the node's, optionally repairing it (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Makes one node request per consumable note of the account.
+ *
+ * # Parameters
+ * - `account_id_hex`: Account ID (C string)
+ * - `repair`: Sync and audit again when discrepancies are found
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"account_id","checked_notes","pending_transactions","consistent",
+ *   "discrepancies":[{"kind":"stale_account","local_commitment","node_commitment"}
+ *   |{"kind":"missing_consumption","note_id","nullifier"}],"repair_attempted",
+ *   "repaired_from"}` (`repaired_from`: the discrepancies found before the repair, only
+ *   when one was attempted)
+ *
+ * # Returns
+ * - 0: Success (check `consistent`)
+ * - -1: Invalid parameters
+ * - -2: Invalid handle, worker closed or repair sync failed
+ * - -3: Invalid account ID
+ * - -4: Nullifier query failed
+ * - -5: Account not in the store, or unknown to the node
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
+ */
+int32_t wc_miden_audit_state(MidenHandle handle,
+                             const char *account_id_hex,
+                             bool repair,
+                             uint8_t *json_out,
+                             uintptr_t *json_out_len);
+
+/**
+ * Get the health of a handle's storage
+ *
+ * Does not queue; safe to call from the main thread, also while the worker is busy.
+ * While degraded, the probe is retried at most every 10 seconds, so a recovered disk is
+ * reported without another write attempt.
+ *
+ * # Output JSON
+ * `{"degraded":true,"storage":"full","reason":"database or disk is full",
+ *   "degraded_since":1700000000}` (`storage`: `ok`, `full` or `read_only`; `reason` and
+ *   `degraded_since` are null when not degraded)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle
+ * - -7: Output buffer too small
+ */
+int32_t wc_miden_health_check(MidenHandle handle, uint8_t *json_out, uintptr_t *json_out_len);
 
 /**
- * Callback for get balance operation: (user_data, error_code, json_ptr, json_len)
+ * Back up the store and keystore to a directory (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread. The whole store
+ * is copied.
+ *
+ * Writes `store.sqlite3`, `keystore/` and `manifest.json` (see the module docs). A failed
+ * backup removes what it wrote.
+ *
+ * # Parameters
+ * - `dest_path`: Directory to write the backup to (C string); created if missing, and
+ *   must be empty
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, a non-empty destination, or an in-memory client
+ * - -2: Invalid handle or worker closed
+ * - -3: Copying the store or keystore failed
+ * - -99: Operation timed out
  */
-typedef void (*GetBalanceCallback)(void*, int32_t, uint8_t*, uintptr_t);
+int32_t wc_miden_backup_store(MidenHandle handle, const char *dest_path);
 
 /**
- * Callback for test connection operation: (user_data, error_code)
+ * Restore the store and keystore from a backup (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Replaces the client's store with the backup's and restores its keystore files, then
+ * reopens the client (see the module docs). Close other handles on the same store first.
+ * Accounts and notes added after the backup are gone from the store; sync afterwards.
+ *
+ * # Parameters
+ * - `src_path`: Backup directory written by `wc_miden_backup_store` (C string)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, a backup that does not match its manifest or is damaged, an
+ *   unencrypted backup for an encrypted store (or the reverse), or an in-memory client
+ * - -2: Invalid handle or worker closed
+ * - -3: Restoring the store or keystore failed
+ * - -99: Operation timed out
+ * - -101: The backup's store key or keystore passphrase differs from the client's
  */
-typedef void (*TestConnectionCallback)(void*, int32_t);
+int32_t wc_miden_restore_store(MidenHandle handle, const char *src_path);
 
 /**
- * Callback for get input notes operation: (user_data, error_code, json_ptr, json_len)
+ * Start streaming an asset to a recipient (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `sender_account_id_hex` / `recipient_account_id_hex` / `faucet_id_hex`: Account IDs
+ * - `amount_per_hour`: Streaming rate in base units per hour (must be > 0)
+ * - `budget`: Maximum total amount paid until topped up (must be > 0)
+ * - `json_out` / `json_out_len`: Output buffer for the stream JSON (`{"stream_id",...}`)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or the stream file could not be written
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID
+ * - -5: Sender account not found
+ * - -7: Output buffer too small
  */
-typedef void (*GetInputNotesCallback)(void*, int32_t, uint8_t*, uintptr_t);
+int32_t wc_miden_start_stream(MidenHandle handle,
+                              const char *sender_account_id_hex,
+                              const char *recipient_account_id_hex,
+                              const char *faucet_id_hex,
+                              uint64_t amount_per_hour,
+                              uint64_t budget,
+                              uint8_t *json_out,
+                              uintptr_t *json_out_len);
 
 /**
- * Callback for consume notes operation: (user_data, error_code, tx_id_ptr, tx_id_len)
+ * Raise the budget of an active stream (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, stream cancelled, or the stream file could not be written
+ * - -2: Invalid handle or worker closed
+ * - -5: Stream not found
  */
-typedef void (*ConsumeNotesCallback)(void*, int32_t, uint8_t*, uintptr_t);
+int32_t wc_miden_top_up_stream(MidenHandle handle, const char *stream_id, uint64_t amount);
 
 /**
- * Free bytes allocated by Rust (for async callback results)
+ * Cancel a stream, paying out what accrued so far (blocking)
  *
- * MUST be called to release memory returned by async callbacks.
- * The (ptr, len) pair must match exactly what was returned by the callback.
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * The stream is cancelled even if the final payout fails; the payout is then retried
+ * after the next sync.
+ *
+ * # Parameters
+ * - `stream_id`: Stream ID (C string)
+ * - `json_out` / `json_out_len`: Output buffer for `{"stream":{...},"final_tx_id":"0x.."|null}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or stream already cancelled
+ * - -2: Invalid handle or worker closed
+ * - -5: Stream not found
+ * - -6: Final payout failed
+ * - -7: Output buffer too small
+ * - -101 / -102: Final payout blocked by the whitelist / screening
  */
-void wc_bytes_free(uint8_t *ptr, uintptr_t len);
+int32_t wc_miden_cancel_stream(MidenHandle handle,
+                               const char *stream_id,
+                               uint8_t *json_out,
+                               uintptr_t *json_out_len);
 
 /**
- * Create and initialize Miden Client
+ * List outgoing streams (blocking)
  *
- * This starts a dedicated worker thread that owns the MidenClient.
- * All operations are sent to this worker thread via channels.
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
  *
  * # Parameters
- * - `keystore_path`: Keystore storage directory path (C string)
- * - `store_path`: SQLite database file path (C string)
- * - `rpc_endpoint`: RPC endpoint URL (C string, can be NULL to use testnet)
- * - `handle_out`: Output client handle
+ * - `json_out` / `json_out_len`: Output buffer for a JSON array of `{"stream_id","sender",
+ *   "recipient","faucet_id","amount_per_hour","budget","paid","due","started_at",
+ *   "last_payout_at","cancelled_at"}`
  *
  * # Returns
  * - 0: Success
  * - -1: Invalid parameters
- * - -2: Initialization failed
+ * - -2: Invalid handle or worker closed
+ * - -7: Output buffer too small
  */
-int32_t wc_miden_create(const char *keystore_path,
-                        const char *store_path,
-                        const char *rpc_endpoint,
-                        MidenHandle *handle_out);
+int32_t wc_miden_list_streams(MidenHandle handle, uint8_t *json_out, uintptr_t *json_out_len);
 
 /**
- * Destroy client and release resources
+ * Claim a sender's stream notes to a local account on a block schedule (blocking)
  *
- * Sends shutdown signal to worker thread and waits for it to finish.
- * Safe to call multiple times - the handle pointer is set to NULL after destruction.
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
  *
  * # Parameters
- * - `handle_ptr`: Pointer to the handle (will be set to NULL after destruction)
- *
- * # Example (Swift)
- * ```swift
- * var handle: MidenHandle? = ...
- * wc_miden_destroy(&handle)  // handle is now nil
- * ```
+ * - `account_id_hex`: Receiving local account (C string)
+ * - `sender_account_id_hex`: Streaming sender (C string)
+ * - `interval_blocks`: Claim at most every this many blocks, after sync (0 removes the schedule)
  *
- * # Shutdown Semantics
- * This performs a **fast shutdown**: pending requests in the queue will be dropped.
- * Callbacks for in-flight async operations may not be invoked.
- * If you need graceful shutdown (process all pending requests), do not use this pattern.
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, or the stream file could not be written
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID
  */
-void wc_miden_destroy(MidenHandle *handle_ptr);
+int32_t wc_miden_schedule_stream_claims(MidenHandle handle,
+                                        const char *account_id_hex,
+                                        const char *sender_account_id_hex,
+                                        uint32_t interval_blocks);
 
 /**
- * Sync state (blocking)
+ * Offer one fungible asset for another in a SWAP note (blocking)
  *
  * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
- * Use wc_miden_sync_async for non-blocking operation.
+ * NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
  *
- * NOTE: Timeout (-99) only abandons waiting; the operation may still complete in background.
+ * The offered amount leaves the account with the SWAP note. Whoever consumes the note
+ * pays the requested amount back in a payback note of the same note type, which the
+ * next sync delivers. A private SWAP note must be shared out of band (see
+ * `wc_miden_export_note`) to be found.
+ *
+ * # Parameters
+ * - `account_id_hex`: Offering account ID (C string)
+ * - `offered_faucet_id_hex` / `offered_amount`: Asset offered, in base units (C string, > 0)
+ * - `requested_faucet_id_hex` / `requested_amount`: Asset requested in return (C string, > 0)
+ * - `note_type`: `WcNoteType` value of the SWAP and payback notes (0 = public, 1 = private)
+ * - `tx_id_out` / `tx_id_out_len`: Output buffer for the transaction ID (hex, 66 bytes)
  *
  * # Returns
  * - 0: Success
+ * - -1: Invalid parameters (including an unknown note type)
  * - -2: Invalid handle or worker closed
+ * - -3: Invalid account or faucet ID
+ * - -4: SWAP note construction failed
+ * - -6: Transaction submission failed (e.g. insufficient balance)
+ * - -7: Output buffer too small, checked before anything is submitted
  * - -8: Queue full
  * - -99: Operation timed out
+ * - -101: The account has a withdrawal whitelist
+ * - -102: Blocked by the screening callback
  */
-int32_t wc_miden_sync(MidenHandle handle, uint32_t *block_num_out);
+int32_t wc_miden_create_swap(MidenHandle handle,
+                             const char *account_id_hex,
+                             const char *offered_faucet_id_hex,
+                             uint64_t offered_amount,
+                             const char *requested_faucet_id_hex,
+                             uint64_t requested_amount,
+                             uint32_t note_type,
+                             uint8_t *tx_id_out,
+                             uintptr_t *tx_id_out_len);
 
 /**
- * Create a new wallet account (blocking)
+ * Get how far the local store is behind the node
  *
- * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ * Reads shared counters and returns immediately, even while a sync is running, so it is
+ * safe to call from the main thread. The node tip is refreshed at the start of every
+ * sync; `tip_updated_at` tells how fresh it is.
+ *
+ * # Output JSON
+ * `{"local_height":1000,"node_tip":13430,"blocks_behind":12430,"syncing":true,
+ *   "estimated_seconds_remaining":95,"tip_updated_at":1700000000,
+ *   "last_sync_bytes_sent":2210,"last_sync_bytes_received":183402}`
+ * (`estimated_seconds_remaining` is null until a sync has measured the sync rate; the
+ * byte counts are gRPC message bytes of the last sync round, 0 before the first)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle
+ * - -7: Output buffer too small
  */
-int32_t wc_miden_create_wallet(MidenHandle handle,
-                               const uint8_t *seed_ptr,
-                               uintptr_t seed_len,
-                               uint8_t *account_id_out,
-                               uintptr_t *account_id_out_len);
+int32_t wc_miden_get_sync_lag(MidenHandle handle, uint8_t *json_out, uintptr_t *json_out_len);
 
 /**
- * Get all accounts (blocking)
+ * List the tenants of the store at `store_path`
  *
- * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ * # Parameters
+ * - `store_path`: Store file path, as in the client config
+ * - `store_key`: Key of an encrypted store (can be NULL for a plain store)
+ *
+ * # Output JSON
+ * `["alice","bob"]` (sorted; empty for a store that does not exist yet)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -3: The store could not be read (wrong key)
+ * - -7: Output buffer too small
  */
-int32_t wc_miden_get_accounts(MidenHandle handle,
-                              uint8_t *accounts_json_out,
-                              uintptr_t *accounts_json_out_len);
+int32_t wc_miden_list_tenants(const char *store_path,
+                              const char *store_key,
+                              uint8_t *json_out,
+                              uintptr_t *json_out_len);
 
 /**
- * Get account balance (blocking)
+ * Delete a tenant: its rows in the store (accounts no other tenant imported, their
+ * transactions, and the notes and tags no other tenant sees), its keystore and its
+ * state files
  *
- * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ * # Parameters
+ * - `keystore_path` / `store_path`: Base keystore directory and store file path, as in
+ *   the client config
+ * - `store_key`: Key of an encrypted store (can be NULL for a plain store)
+ * - `tenant_id`: Tenant to delete
+ *
+ * # Returns
+ * - 0: Success (also when the tenant does not exist)
+ * - -1: Invalid parameters, or a tenant of the store is open
+ * - -3: The store could not be updated or the files could not be removed
  */
-int32_t wc_miden_get_balance(MidenHandle handle,
-                             const char *account_id_hex,
-                             uint8_t *balance_json_out,
-                             uintptr_t *balance_json_out_len);
+int32_t wc_miden_delete_tenant(const char *keystore_path,
+                               const char *store_path,
+                               const char *store_key,
+                               const char *tenant_id);
 
+#if defined(MIDEN_TESTING)
 /**
- * Test connection (blocking)
+ * Make the next `count` operations of `kind` fail (testing builds only)
  *
- * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ * # Parameters
+ * - `kind`: 0 = node RPC calls, 1 = client store writes, 2 = transaction proofs
+ * - `count`: Number of operations to fail; 0 clears pending failures of that kind
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Unknown kind
  */
-int32_t wc_miden_test_connection(MidenHandle handle);
+int32_t wc_test_inject_failure(uint32_t kind, uint32_t count);
+#endif
 
 /**
- * Get consumable input notes (blocking)
+ * Get (creating on first use) the travel-rule public key of a local account (blocking)
  *
  * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Counterparties pass this key as `travel_rule.recipient_key` when sending to the account.
+ *
+ * # Parameters
+ * - `account_id_hex`: Local account ID (C string)
+ * - `key_out` / `key_out_len`: Output buffer for the X25519 public key (64 hex characters)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID or key file could not be read/written
+ * - -5: Account not found
+ * - -7: Output buffer too small
  */
-int32_t wc_miden_get_input_notes(MidenHandle handle,
-                                 const char *account_id_hex,
-                                 uint8_t *notes_json_out,
-                                 uintptr_t *notes_json_out_len);
+int32_t wc_miden_travel_rule_public_key(MidenHandle handle,
+                                        const char *account_id_hex,
+                                        uint8_t *key_out,
+                                        uintptr_t *key_out_len);
 
 /**
- * Consume notes (blocking)
+ * Decrypt a travel-rule envelope received with a payment (blocking)
  *
  * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
- * NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
- */
-int32_t wc_miden_consume_notes(MidenHandle handle,
-                               const char *account_id_hex,
-                               const char *note_ids_json,
-                               uint8_t *tx_id_out,
-                               uintptr_t *tx_id_out_len);
-
-/**
- * Sync state (async)
  *
- * NOTE: Callback is invoked on worker thread, NOT main thread.
- * Swift callers should dispatch to main queue if updating UI.
- */
-int32_t wc_miden_sync_async(MidenHandle handle, SyncCallback callback, void *user_data);
-
-/**
- * Create wallet (async)
+ * # Parameters
+ * - `account_id_hex`: Receiving local account (whose travel-rule key the envelope is sealed to)
+ * - `envelope_json`: Envelope `{"version":1,"note_id","ephemeral_key","nonce","ciphertext"}`
+ * - `json_out` / `json_out_len`: Output buffer for `{"note_id":"0x..","data":{...}}`
  *
- * NOTE: Callback is invoked on worker thread, NOT main thread.
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters or malformed envelope
+ * - -2: Invalid handle or worker closed
+ * - -3: Envelope is not addressed to this account or was tampered with
+ * - -5: Account or travel-rule key not found
+ * - -7: Output buffer too small
  */
-int32_t wc_miden_create_wallet_async(MidenHandle handle,
-                                     const uint8_t *seed_ptr,
-                                     uintptr_t seed_len,
-                                     CreateWalletCallback callback,
-                                     void *user_data);
+int32_t wc_miden_decode_travel_rule(MidenHandle handle,
+                                    const char *account_id_hex,
+                                    const char *envelope_json,
+                                    uint8_t *json_out,
+                                    uintptr_t *json_out_len);
 
 /**
- * Get accounts (async)
+ * List the transactions in the store, newest first (blocking)
  *
- * NOTE: Callback is invoked on worker thread, NOT main thread.
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * # Parameters
+ * - `filter_json`: `{"account_id","status":"pending"|"committed"|"discarded","limit",
+ *   "offset"}`, every field optional (C string, NULL = all transactions of active accounts)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"transactions":[{"tx_id","account_id","status","block_num","timestamp",
+ *   "input_note_count","output_note_count"}],"total_count","offset","has_more"}`
+ *   (`block_num` and `timestamp`: the commit block, null until committed; `timestamp` also
+ *   null until the block time is known; `total_count`: matching transactions on all pages)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid filter
+ * - -2: Invalid handle or worker closed
+ * - -5: Store query failed
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
  */
-int32_t wc_miden_get_accounts_async(MidenHandle handle,
-                                    GetAccountsCallback callback,
-                                    void *user_data);
+int32_t wc_miden_get_transactions(MidenHandle handle,
+                                  const char *filter_json,
+                                  uint8_t *json_out,
+                                  uintptr_t *json_out_len);
 
 /**
- * Get balance (async)
+ * Look up the status of one transaction in the store (blocking)
  *
- * NOTE: Callback is invoked on worker thread, NOT main thread.
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Reflects the last sync; `wc_miden_wait_for_transaction` syncs until the status changes.
+ *
+ * # Parameters
+ * - `tx_id_hex`: Transaction ID (C string)
+ * - `json_out` / `json_out_len`: Output buffer for one entry as listed by
+ *   `wc_miden_get_transactions`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid transaction ID
+ * - -2: Invalid handle or worker closed
+ * - -5: Transaction not in the store, or store query failed
+ * - -7: Output buffer too small
+ * - -99: Operation timed out
  */
-int32_t wc_miden_get_balance_async(MidenHandle handle,
-                                   const char *account_id_hex,
-                                   GetBalanceCallback callback,
-                                   void *user_data);
+int32_t wc_miden_get_transaction_status(MidenHandle handle,
+                                        const char *tx_id_hex,
+                                        uint8_t *json_out,
+                                        uintptr_t *json_out_len);
 
 /**
- * Test connection (async)
+ * Sync until a transaction is committed or discarded, or the timeout elapses (async)
  *
- * NOTE: Callback is invoked on worker thread, NOT main thread.
+ * NOTE: Callback is invoked on the worker thread, NOT main thread.
+ *
+ * The callback receives the transaction's entry (as `wc_miden_get_transaction_status`)
+ * with `"timed_out"`: true when it was still pending at the deadline. Sync failures
+ * during the wait are retried; the wait is cancellable with `wc_miden_cancel`. The wait
+ * runs on the worker as one request per sync round (see the module docs).
+ *
+ * # Parameters
+ * - `tx_id_hex`: Transaction ID (C string), submitted through this store
+ * - `timeout_ms`: Longest wait in milliseconds (at most 10 minutes, must be > 0)
+ *
+ * # Returns
+ * An operation handle (> 0) for `wc_miden_cancel`, or a negative error code:
+ * - -1: Invalid transaction ID or timeout
+ * - -2: Invalid handle or worker closed
+ * - -8: Queue full
+ *
+ * Callback error codes: -2 (worker closed during the wait), -5 (transaction not in the
+ * store), -9 (cancelled).
  */
-int32_t wc_miden_test_connection_async(MidenHandle handle,
-                                       TestConnectionCallback callback,
-                                       void *user_data);
+int64_t wc_miden_wait_for_transaction(MidenHandle handle,
+                                      const char *tx_id_hex,
+                                      uint64_t timeout_ms,
+                                      BytesCallback callback,
+                                      void *user_data);
 
 /**
- * Get input notes (async)
+ * Delegate monitoring of sent P2IDE notes to a watch service (blocking)
  *
- * NOTE: Callback is invoked on worker thread, NOT main thread.
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Shares only the note ID, nullifier and reclaim height of each note (see the module
+ * docs). Delegating a note again replaces its watch.
+ *
+ * # Parameters
+ * - `watch_service_url`: Delegation endpoint of the watch service (C string, http or https)
+ * - `note_ids_json`: JSON array of output note IDs (hex), at most [`WATCH_MAX_NOTES`]
+ * - `json_out` / `json_out_len`: Output buffer for `{"delegated":["0x.."]}`
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters, or a note without a reclaim height or known recipient
+ * - -2: Invalid handle or worker closed
+ * - -4: Store error
+ * - -5: Note not found among the notes sent from this store
+ * - -7: Output buffer too small
+ * - -103: Watch service unreachable or delegation refused
  */
-int32_t wc_miden_get_input_notes_async(MidenHandle handle,
-                                       const char *account_id_hex,
-                                       GetInputNotesCallback callback,
-                                       void *user_data);
+int32_t wc_miden_delegate_watch(MidenHandle handle,
+                                const char *watch_service_url,
+                                const char *note_ids_json,
+                                uint8_t *json_out,
+                                uintptr_t *json_out_len);
 
 /**
- * Consume notes (async)
+ * Ingest an alert from a watch service into the event stream (blocking)
  *
- * NOTE: Callback is invoked on worker thread, NOT main thread.
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+ *
+ * Emits a `watch_alert` event. Alerts are not authenticated: sync before acting on one.
+ *
+ * # Parameters
+ * - `alert_json`: `{"note_id","alert":"reclaimable"|"consumed","block_num"}` (C string)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid alert, or `reclaimable` before the note's reclaim height
+ * - -2: Invalid handle or worker closed
+ * - -4: Store error
+ * - -5: Note is not watched
  */
-int32_t wc_miden_consume_notes_async(MidenHandle handle,
-                                     const char *account_id_hex,
-                                     const char *note_ids_json,
-                                     ConsumeNotesCallback callback,
-                                     void *user_data);
+int32_t wc_miden_ingest_watch_alert(MidenHandle handle, const char *alert_json);
 
 /**
- * Keccak256 hash function
+ * Set, replace or remove the withdrawal whitelist of an account (blocking)
+ *
+ * WARNING: This is a blocking call. Do NOT call from the main/UI thread.
  *
  * # Parameters
- * - `data_ptr`: Input data pointer
- * - `data_len`: Input data length
- * - `out_ptr`: Output buffer pointer (must be at least 32 bytes)
- * - `out_len`: Input: buffer capacity; Output: actual length (32)
+ * - `account_id_hex`: Account whose sends are restricted (C string)
+ * - `addresses_json`: JSON array of allowed recipient account IDs, or NULL to remove
+ *   the whitelist (an empty array blocks all sends)
+ * - `lock_passphrase`: Sets the lock when the account has no whitelist yet; must match
+ *   the existing lock otherwise (C string, not empty)
  *
  * # Returns
  * - 0: Success
- * - -1: Invalid parameters or buffer too small
+ * - -1: Invalid parameters, or the whitelist file could not be written
+ * - -2: Invalid handle or worker closed
+ * - -3: Invalid account ID
+ * - -101: Wrong lock passphrase, or the whitelist file or the account's entry could not
+ *   be read
  */
-int32_t wc_keccak256(const uint8_t *data_ptr,
-                     uintptr_t data_len,
-                     uint8_t *out_ptr,
-                     uintptr_t *out_len);
+int32_t wc_miden_set_withdrawal_whitelist(MidenHandle handle,
+                                          const char *account_id_hex,
+                                          const char *addresses_json,
+                                          const char *lock_passphrase);
 
 /**
- * Convert account ID bytes to hex string
+ * Read the wallet summary written after the last successful sync
+ *
+ * Does not need a handle and does not touch the SQLite store or the network: it only
+ * reads a small file, so it is cheap enough for a widget timeline provider.
+ *
+ * # Parameters
+ * - `store_path`: SQLite database file path, as passed to `wc_miden_create` (C string)
+ * - `json_out` / `json_out_len`: Output buffer for
+ *   `{"version":1,"updated_at","sync_height","account_count",
+ *   "balances":[{"faucet_id","amount"}],
+ *   "last_activity":{"tx_id","account_id","block_num","timestamp"}|null,
+ *   "unclaimed_note_count"}` (`updated_at` in Unix seconds, `timestamp` in ISO-8601;
+ *   balances summed over all accounts, in base units)
+ *
+ * # Returns
+ * - 0: Success
+ * - -1: Invalid parameters
+ * - -5: No snapshot yet (no sync has completed on this store)
+ * - -7: Output buffer too small
  */
-int32_t wc_miden_account_id_to_hex(const uint8_t *account_id_ptr,
-                                   uintptr_t account_id_len,
-                                   uint8_t *hex_out,
-                                   uintptr_t *hex_out_len);
+int32_t wc_miden_get_widget_snapshot(const char *store_path,
+                                     uint8_t *json_out,
+                                     uintptr_t *json_out_len);
 
 #endif  /* MIDEN_SWIFT_CLIENT_H */
@@ -0,0 +1,116 @@
+//! Import of existing public accounts by ID
+//!
+//! Wallet apps watch accounts created elsewhere: the same user's wallet on another
+//! device, a faucet, a merchant's account. Importing fetches the account's current state
+//! from the node and adds it to the store, where it is tracked like a local account
+//! (balances, notes, sync updates). No key is imported, so the account cannot sign from
+//! this store.
+//!
+//! Only public accounts can be imported: the node does not hold the state of private
+//! accounts. Importing an account that is already in the store refreshes its state.
+//! `wc_miden_import_account_with_signer` (signer.rs) also binds the account to the external
+//! signer holding its key, so it can sign.
+
+use std::{ffi::c_void, os::raw::c_char};
+
+use miden_objects::account::AccountId;
+
+use crate::{
+    cancel, get_account_details_impl, get_handle, last_error, parse_account_id,
+    request_blocking, signer::{self, SignerKey}, write_out_buffer, BytesCallback, MidenContext, MidenHandle, Reply, Request,
+    ERR_ACCOUNT_OP, ERR_INVALID_HANDLE, ERR_INVALID_PARAM,
+};
+
+pub(crate) async fn import_account_by_id_impl(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    signer: Option<SignerKey>,
+) -> Result<String, i32> {
+    context.client.import_account_by_id(account_id).await
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    if let Some(signer) = signer {
+        signer::bind_imported_account(context, account_id, signer).await?;
+    }
+
+    get_account_details_impl(context, account_id).await
+}
+
+/// Parse the account ID argument (private accounts cannot be fetched from the node)
+pub(crate) fn parse_public_account_id(account_id_hex: *const c_char) -> Result<AccountId, i32> {
+    let (account_id, _) = parse_account_id(account_id_hex)?;
+    if !account_id.is_public() {
+        return Err(ERR_INVALID_PARAM);
+    }
+    Ok(account_id)
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Import a public account from the node and track it (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_id_hex`: Account ID (C string)
+/// - `json_out` / `json_out_len`: Output buffer for the account details (same shape as
+///   `wc_miden_get_account_details`)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or a private account
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID, or the node has no such account
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_import_account_by_id(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let account_id = match parse_public_account_id(account_id_hex) {
+        Ok(account_id) => account_id,
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::ImportAccountById { account_id, signer: None, reply };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Import a public account from the node and track it (async)
+///
+/// NOTE: Callback is invoked on worker thread, NOT main thread.
+///
+/// Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_import_account_by_id_async(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    callback: BytesCallback,
+    user_data: *mut c_void,
+) -> i64 {
+    let Some(worker) = get_handle(handle) else {
+        return i64::from(ERR_INVALID_HANDLE);
+    };
+
+    let account_id = match parse_public_account_id(account_id_hex) {
+        Ok(account_id) => account_id,
+        Err(code) => return i64::from(code),
+    };
+
+    cancel::submit(&worker, Request::ImportAccountById {
+        account_id,
+        signer: None,
+        reply: Reply::Callback { callback, user_data: user_data as usize },
+    })
+}
@@ -0,0 +1,106 @@
+//! Sync scoped to one account
+//!
+//! A user opening one wallet of a client that tracks many accounts wants that wallet
+//! fresh, not every account's deferred work done first. The node round cannot be scoped:
+//! the store has a single sync height for all accounts, so a round that only asked for
+//! one account's notes and tags would still advance it and the other accounts would miss
+//! whatever happened in that block range. One node round is cheap compared to the
+//! post-sync work, though, and that is what an account sync scopes:
+//!
+//! - a single sync round, like `wc_miden_sync`
+//! - store-local post-sync work (retention, bridge and live transaction updates, the
+//!   widget snapshot), like `wc_miden_background_sync`
+//! - work that proves and submits transactions for other accounts (ephemeral account
+//!   sweeps, stream payouts) is left to the next regular sync
+//! - the result reports the account only: its nonce and consumable notes, and the node
+//!   traffic of the round
+
+use std::os::raw::c_char;
+
+use miden_objects::account::AccountId;
+
+use crate::{
+    block_times, get_handle, last_error, live_tx, parse_account_id, request_blocking, retention,
+    sync_state_impl, widget, write_out_buffer, MidenContext, MidenHandle, Request,
+    ERR_INVALID_HANDLE, ERR_LOOKUP, ERR_NOTE_OP,
+};
+
+pub(crate) async fn sync_account_impl(
+    context: &mut MidenContext,
+    account_id: AccountId,
+) -> Result<String, i32> {
+    // Checked first so an unknown account does not cost a sync round
+    context.client.get_account(account_id).await
+        .map_err(|e| last_error::detail(ERR_LOOKUP, e))?
+        .ok_or(ERR_LOOKUP)?;
+
+    let traffic = context.node_proxy.traffic().count();
+    let block_num = sync_state_impl(context).await?;
+    retention::apply_after_sync(context).await;
+    block_times::backfill_after_sync(context).await;
+    #[cfg(feature = "scripts")]
+    crate::bridge::update_after_sync(context).await;
+    live_tx::update_after_sync(context).await;
+    widget::update_after_sync(context).await;
+
+    let record = context.client.get_account(account_id).await
+        .map_err(|e| last_error::detail(ERR_LOOKUP, e))?
+        .ok_or(ERR_LOOKUP)?;
+    let consumable_notes = context.client.get_consumable_notes(Some(account_id)).await
+        .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?;
+    let traffic = context.node_proxy.traffic().since(traffic);
+
+    Ok(serde_json::json!({
+        "account_id": account_id.to_hex(),
+        "block_num": block_num,
+        "nonce": record.account().nonce().as_int(),
+        "consumable_notes": consumable_notes.len(),
+        "bytes_sent": traffic.sent,
+        "bytes_received": traffic.received,
+    })
+    .to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Sync and refresh one account, deferring other accounts' post-sync work (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_id_hex`: Account ID (C string)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"account_id","block_num","nonce","consumable_notes","bytes_sent","bytes_received"}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle, worker closed or sync failed
+/// - -3: Invalid account ID
+/// - -4: Note query failed
+/// - -5: Account not in the store
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_sync_account(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let account_id = match parse_account_id(account_id_hex) {
+        Ok((account_id, _)) => account_id,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::SyncAccount { account_id, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,18 @@
+//! Global allocator selection
+//!
+//! The system allocator is used by default. Long-running hosts that prove many
+//! transactions (servers, custodial backends) see their resident memory grow with
+//! fragmentation after large proofs; the `jemalloc` and `mimalloc` features replace the
+//! global allocator for the whole library. The features are exclusive, and neither is
+//! meant for the iOS framework, where the system allocator is the right choice.
+
+#[cfg(all(feature = "jemalloc", feature = "mimalloc"))]
+compile_error!("features `jemalloc` and `mimalloc` are mutually exclusive");
+
+#[cfg(all(feature = "jemalloc", not(feature = "mimalloc")))]
+#[global_allocator]
+static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
+#[cfg(all(feature = "mimalloc", not(feature = "jemalloc")))]
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
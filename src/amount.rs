@@ -0,0 +1,369 @@
+//! Decimal-string amounts
+//!
+//! Amounts are u64 base units, which JavaScript-style bridges (JavaScriptCore, React
+//! Native) cannot hold exactly above 2^53, and which UI code easily confuses with token
+//! units. Three additions keep the u64 API while allowing strings at the boundary:
+//!
+//! - string encoding (`wc_miden_set_amount_encoding`, process-wide): every amount in JSON
+//!   output (`amount`, `amount_per_hour`, `budget`, `max_supply`), callbacks and events
+//!   is written as a base-unit decimal string, e.g. `"1000000"`. Swift's `Codable` models
+//!   expect numbers, so this is for bridges that decode the JSON themselves
+//! - JSON inputs accept an amount as a number or as a base-unit decimal string
+//! - `wc_miden_parse_amount` / `wc_miden_format_amount` convert between base units and
+//!   token units (`"12.5"`) with decimals the caller knows, without a handle; formatting
+//!   can round to fewer fraction digits for display. Parsing never rounds: input finer than
+//!   one base unit is rejected
+//! - `wc_miden_parse_faucet_amount` / `wc_miden_format_faucet_amount` do the same with the
+//!   faucet's decimals, read from the faucet account (the local store first, then the node
+//!   for public faucets)
+//! - `wc_miden_get_faucet_metadata` reports the same faucet's symbol, decimals and max
+//!   supply, for labels such as "12.5 MID"
+
+use std::{
+    os::raw::c_char,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use miden_objects::account::AccountId;
+
+use crate::{
+    get_handle, note_summary, parse_account_id, parse_required_str, request_blocking,
+    types::WcAmountEncoding, write_out_buffer, MidenContext, MidenHandle, Request,
+    ERR_ACCOUNT_OP, ERR_INVALID_HANDLE, ERR_INVALID_PARAM, ERR_LOOKUP,
+};
+
+/// Keys of JSON output fields holding base-unit amounts
+const AMOUNT_KEYS: [&str; 4] = ["amount", "amount_per_hour", "budget", "max_supply"];
+
+/// Whether amounts are written as strings (process-wide)
+static STRING_AMOUNTS: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn string_amounts() -> bool {
+    STRING_AMOUNTS.load(Ordering::Relaxed)
+}
+
+/// Rewrite the integer amounts of a JSON output value as decimal strings
+pub(crate) fn stringify(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                match field {
+                    serde_json::Value::Number(n) if AMOUNT_KEYS.contains(&key.as_str()) => {
+                        if let Some(amount) = n.as_u64() {
+                            *field = serde_json::Value::String(amount.to_string());
+                        }
+                    }
+                    _ => stringify(field),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(stringify),
+        _ => {}
+    }
+}
+
+/// Amount of a JSON input: a number or a base-unit decimal string
+pub(crate) fn from_json(value: &serde_json::Value) -> Option<u64> {
+    match value {
+        serde_json::Value::String(text) if text.bytes().all(|b| b.is_ascii_digit()) => {
+            text.parse().ok()
+        }
+        _ => value.as_u64(),
+    }
+}
+
+/// Base units as a token-unit decimal string (`1500`, 3 decimals → `"1.5"`)
+pub(crate) fn format_units(amount: u64, decimals: u8) -> String {
+    let Some(unit) = 10u64.checked_pow(u32::from(decimals)) else {
+        return amount.to_string();
+    };
+    let fraction = format!("{:0width$}", amount % unit, width = usize::from(decimals));
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        (amount / unit).to_string()
+    } else {
+        format!("{}.{}", amount / unit, fraction)
+    }
+}
+
+/// `format_units` rounded half up to at most `fraction_digits` fraction digits
+/// (`1_234_567`, 6 decimals, 2 digits → `"1.23"`)
+pub(crate) fn format_units_rounded(amount: u64, decimals: u8, fraction_digits: u8) -> String {
+    if fraction_digits >= decimals {
+        return format_units(amount, decimals);
+    }
+    let (Some(step), Some(unit)) = (
+        10u128.checked_pow(u32::from(decimals - fraction_digits)),
+        10u128.checked_pow(u32::from(fraction_digits)),
+    ) else {
+        return format_units(amount, decimals);
+    };
+
+    // In units of 10^-fraction_digits tokens; u128 so rounding up cannot overflow
+    let rounded = (u128::from(amount) + step / 2) / step;
+    let fraction = format!("{:0width$}", rounded % unit, width = usize::from(fraction_digits));
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        (rounded / unit).to_string()
+    } else {
+        format!("{}.{}", rounded / unit, fraction)
+    }
+}
+
+/// Token-unit decimal string as base units (more fraction digits than `decimals` are rejected)
+pub(crate) fn parse_units(text: &str, decimals: u8) -> Result<u64, i32> {
+    let (integer, fraction) = text.split_once('.').unwrap_or((text, ""));
+    let digits_only = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if integer.is_empty()
+        || !digits_only(integer)
+        || !digits_only(fraction)
+        || (text.contains('.') && fraction.is_empty())
+        || fraction.len() > usize::from(decimals)
+    {
+        return Err(ERR_INVALID_PARAM);
+    }
+
+    let unit = 10u64.checked_pow(u32::from(decimals)).ok_or(ERR_INVALID_PARAM)?;
+    let scale = 10u64.pow((usize::from(decimals) - fraction.len()) as u32);
+    let integer: u64 = integer.parse().map_err(|_| ERR_INVALID_PARAM)?;
+    let fraction: u64 = match fraction {
+        "" => 0,
+        digits => digits.parse().map_err(|_| ERR_INVALID_PARAM)?,
+    };
+
+    integer
+        .checked_mul(unit)
+        .and_then(|base| base.checked_add(fraction * scale))
+        .ok_or(ERR_INVALID_PARAM)
+}
+
+/// Decimals of a fungible faucet, for conversions on the FFI thread
+pub(crate) async fn faucet_decimals_impl(
+    context: &MidenContext,
+    faucet_id: AccountId,
+) -> Result<u8, i32> {
+    note_summary::token_info(context, faucet_id).await
+        .map(|(_, decimals)| decimals)
+        .ok_or(ERR_LOOKUP)
+}
+
+/// Symbol, decimals and max supply of a fungible faucet
+pub(crate) async fn faucet_metadata_impl(
+    context: &MidenContext,
+    faucet_id: AccountId,
+) -> Result<String, i32> {
+    let faucet = note_summary::fungible_faucet(context, faucet_id).await.ok_or(ERR_LOOKUP)?;
+    let symbol = faucet.symbol().to_string().map_err(|_| ERR_LOOKUP)?;
+
+    Ok(serde_json::json!({
+        "faucet_id": faucet_id.to_hex(),
+        "symbol": symbol,
+        "decimals": faucet.decimals(),
+        "max_supply": faucet.max_supply().as_int(),
+    })
+    .to_string())
+}
+
+/// Ask the worker for a faucet's decimals
+fn faucet_decimals(handle: MidenHandle, faucet_id_hex: *const c_char) -> Result<u8, i32> {
+    let worker = get_handle(handle).ok_or(ERR_INVALID_HANDLE)?;
+    let (faucet_id, _) = parse_account_id(faucet_id_hex)?;
+
+    let bytes = request_blocking(&worker, |reply| Request::FaucetDecimals { faucet_id, reply })?;
+    bytes.first().copied().ok_or(ERR_LOOKUP)
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Choose how amounts are written in JSON output (process-wide)
+///
+/// # Parameters
+/// - `encoding`: `WcAmountEncoding` value (0 = numbers, the default; 1 = decimal strings)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Unknown encoding
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_set_amount_encoding(encoding: u32) -> i32 {
+    match WcAmountEncoding::try_from(encoding) {
+        Ok(encoding) => {
+            STRING_AMOUNTS.store(encoding == WcAmountEncoding::String, Ordering::Relaxed);
+            0
+        }
+        Err(code) => code,
+    }
+}
+
+/// Convert a token-unit decimal string into base units with the faucet's decimals (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `faucet_id_hex`: Faucet account ID (C string)
+/// - `amount_str`: Amount in token units, e.g. `"12.5"` (C string, no sign or exponent)
+/// - `amount_out`: Amount in base units
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, more fraction digits than the token has, or overflow
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid faucet ID
+/// - -5: Faucet unknown or not a fungible faucet
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_parse_faucet_amount(
+    handle: MidenHandle,
+    faucet_id_hex: *const c_char,
+    amount_str: *const c_char,
+    amount_out: *mut u64,
+) -> i32 {
+    if amount_out.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+    let text = match parse_required_str(amount_str) {
+        Ok(text) => text,
+        Err(code) => return code,
+    };
+
+    let amount = faucet_decimals(handle, faucet_id_hex)
+        .and_then(|decimals| parse_units(text, decimals));
+    match amount {
+        Ok(amount) => {
+            unsafe { *amount_out = amount };
+            0
+        }
+        Err(code) => code,
+    }
+}
+
+/// Convert base units into a token-unit decimal string with the faucet's decimals (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `faucet_id_hex`: Faucet account ID (C string)
+/// - `amount`: Amount in base units
+/// - `out` / `out_len`: Output buffer for the decimal string (no grouping, no trailing zeros)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid faucet ID
+/// - -5: Faucet unknown or not a fungible faucet
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_format_faucet_amount(
+    handle: MidenHandle,
+    faucet_id_hex: *const c_char,
+    amount: u64,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    match faucet_decimals(handle, faucet_id_hex) {
+        Ok(decimals) => write_out_buffer(format_units(amount, decimals).as_bytes(), out, out_len),
+        Err(code) => code,
+    }
+}
+
+/// Get the token metadata of a fungible faucet (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Untracked public faucets are fetched from the node.
+///
+/// # Parameters
+/// - `faucet_id_hex`: Faucet account ID (C string)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"faucet_id","symbol","decimals","max_supply"}` (`max_supply` in base units)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid faucet ID (including IDs of non-faucet accounts)
+/// - -5: Faucet unknown or not a fungible faucet
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_faucet_metadata(
+    handle: MidenHandle,
+    faucet_id_hex: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let faucet_id = match parse_account_id(faucet_id_hex) {
+        Ok((faucet_id, _)) if faucet_id.is_faucet() => faucet_id,
+        Ok(_) => return ERR_ACCOUNT_OP,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::FaucetMetadata { faucet_id, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Convert base units into a token-unit decimal string with known decimals
+///
+/// Safe to call from any thread; needs no handle. Use `wc_miden_format_faucet_amount` to
+/// read the decimals from the faucet instead.
+///
+/// # Parameters
+/// - `amount`: Amount in base units
+/// - `decimals`: Token decimals
+/// - `fraction_digits`: Maximum fraction digits, rounded half up (`decimals` or more = exact)
+/// - `out` / `out_len`: Output buffer for the decimal string (no grouping, no trailing zeros)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_format_amount(
+    amount: u64,
+    decimals: u8,
+    fraction_digits: u8,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    let text = format_units_rounded(amount, decimals, fraction_digits);
+    write_out_buffer(text.as_bytes(), out, out_len)
+}
+
+/// Convert a token-unit decimal string into base units with known decimals
+///
+/// Safe to call from any thread; needs no handle. Never rounds. Use
+/// `wc_miden_parse_faucet_amount` to read the decimals from the faucet instead.
+///
+/// # Parameters
+/// - `amount_str`: Amount in token units, e.g. `"12.5"` (C string, no sign or exponent)
+/// - `decimals`: Token decimals
+/// - `amount_out`: Amount in base units
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, more fraction digits than `decimals`, or overflow
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_parse_amount(
+    amount_str: *const c_char,
+    decimals: u8,
+    amount_out: *mut u64,
+) -> i32 {
+    if amount_out.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    match parse_required_str(amount_str).and_then(|text| parse_units(text, decimals)) {
+        Ok(amount) => {
+            unsafe { *amount_out = amount };
+            0
+        }
+        Err(code) => code,
+    }
+}
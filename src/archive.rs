@@ -0,0 +1,131 @@
+//! Account archival
+//!
+//! Users collect dormant wallets. Archiving an account keeps its data and keys in the
+//! store but leaves it out of the work done for all accounts:
+//!
+//! - post-sync work: ephemeral account sweeps and stream payouts or claims involving it
+//!   are skipped (and resume when it is unarchived)
+//! - consumable note scans across accounts (`wc_miden_get_input_notes` without an
+//!   account): notes only an archived account can consume are left out
+//! - portfolio totals: the widget snapshot's balances, account and note counts
+//!
+//! The node sync itself still covers archived accounts: miden-client keeps one sync
+//! height and tag set for the whole store, and an account cannot be untracked without
+//! deleting it. Queries naming an archived account (balance, notes, details) keep working,
+//! and `wc_miden_get_account_details` reports the flag. Archived account IDs are kept in a
+//! JSON file next to the SQLite store.
+
+use std::{
+    collections::BTreeSet,
+    os::raw::c_char,
+    path::{Path, PathBuf},
+};
+
+use miden_objects::account::AccountId;
+
+use crate::{
+    get_handle, last_error, memory_store, parse_account_id, request_blocking, MidenContext,
+    MidenHandle, Request, ERR_ACCOUNT_OP, ERR_INVALID_HANDLE, ERR_LOOKUP,
+};
+
+/// Archived accounts of the store
+#[derive(Clone, Default)]
+pub(crate) struct Archive {
+    path: PathBuf,
+    accounts: BTreeSet<AccountId>,
+}
+
+impl Archive {
+    /// Load the sidecar file for the store at `store_path` (missing or corrupt → none)
+    pub fn load(store_path: &Path) -> Self {
+        let path = store_path.with_extension("archive.json");
+        let entries: Vec<String> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        let accounts = entries.iter().filter_map(|id| AccountId::from_hex(id).ok()).collect();
+        Self { path, accounts }
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let entries: Vec<String> = self.accounts.iter().map(|id| id.to_hex()).collect();
+        memory_store::write(&self.path, serde_json::Value::from(entries).to_string())
+    }
+
+    pub fn contains(&self, account_id: AccountId) -> bool {
+        self.accounts.contains(&account_id)
+    }
+
+    /// Drop consumable notes that only archived accounts can consume
+    pub fn retain_active<N, R>(&self, notes: &mut Vec<(N, Vec<(AccountId, R)>)>) {
+        if self.accounts.is_empty() {
+            return;
+        }
+        notes.retain(|(_, consumers)| consumers.iter().any(|(id, _)| !self.contains(*id)));
+    }
+}
+
+pub(crate) async fn set_account_archived_impl(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    archived: bool,
+) -> Result<(), i32> {
+    context.client.get_account(account_id).await
+        .map_err(|e| last_error::detail(ERR_LOOKUP, e))?
+        .ok_or(ERR_LOOKUP)?;
+
+    let changed = if archived {
+        context.archive.accounts.insert(account_id)
+    } else {
+        context.archive.accounts.remove(&account_id)
+    };
+    if changed {
+        context.archive.save().map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+        context.read_path.publish_archive(&context.archive);
+    }
+    Ok(())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Archive or unarchive an account (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// An archived account keeps its data and keys but is left out of post-sync work,
+/// cross-account note scans and portfolio totals (see the module docs).
+///
+/// # Parameters
+/// - `account_id_hex`: Account ID (C string)
+/// - `archived`: true to archive, false to unarchive (no-op if already in that state)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID, or the archive file could not be written
+/// - -5: Account not in the store
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_set_account_archived(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    archived: bool,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let account_id = match parse_account_id(account_id_hex) {
+        Ok((account_id, _)) => account_id,
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::SetAccountArchived { account_id, archived, reply };
+    match request_blocking(&worker, request) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,89 @@
+//! Compliance audit log
+//!
+//! Append-only JSON-lines file next to the SQLite store recording compliance-relevant
+//! decisions (screening denials, ...). Each line is
+//! `{"ts":<unix secs>,"event":"<name>",...event fields}`. Entries are never rewritten;
+//! apps that ship the log to a backend read it with `wc_miden_get_audit_log`.
+
+use std::{
+    fs::OpenOptions,
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    clock, diagnostics, get_handle, memory_store, request_blocking, write_out_buffer, MidenContext,
+    MidenHandle, Request, ERR_INVALID_HANDLE,
+};
+
+fn log_path(store_path: &Path) -> PathBuf {
+    store_path.with_extension("audit.jsonl")
+}
+
+/// Append an entry; failures are logged but never fail the audited operation
+pub(crate) fn record(context: &MidenContext, event: &str, fields: serde_json::Value) {
+    if memory_store::is_in_memory(&context.store_path) {
+        return;
+    }
+    let mut entry = serde_json::json!({ "ts": clock::now_unix(), "event": event });
+    if let (Some(entry), serde_json::Value::Object(fields)) = (entry.as_object_mut(), fields) {
+        entry.extend(fields);
+    }
+
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path(&context.state_path))
+        .and_then(|mut file| writeln!(file, "{}", entry));
+    if let Err(e) = result {
+        diagnostics::log("audit", format!("failed to write audit entry: {}", e));
+    }
+}
+
+pub(crate) fn read_audit_log_impl(context: &MidenContext, since: u64) -> Result<String, i32> {
+    let entries: Vec<serde_json::Value> = match std::fs::File::open(log_path(&context.state_path)) {
+        Ok(file) => BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(&line).ok())
+            .filter(|entry| entry["ts"].as_u64().unwrap_or(0) >= since)
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    Ok(serde_json::Value::from(entries).to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Read the audit log (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `since_unix`: Only return entries at or after this unix time (0 = all)
+/// - `json_out` / `json_out_len`: Output buffer for the JSON array of entries (oldest first)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_audit_log(
+    handle: MidenHandle,
+    since_unix: u64,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    match request_blocking(&worker, |reply| Request::GetAuditLog { since: since_unix, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,98 @@
+//! Transaction authenticator used by the client
+//!
+//! Wraps the keystore router so that signatures can also come from outside the device:
+//! in capture mode the signing request is recorded instead of signed (cold wallet
+//! export), and signatures imported for a specific (public key, message) pair are served
+//! before the router is consulted. The router picks the key's backend (filesystem,
+//! external signer callbacks or memory), see keystore.rs.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use miden_client::{
+    auth::{PublicKeyCommitment, SigningInputs, TransactionAuthenticator},
+    AuthenticationError,
+};
+use miden_objects::{account::auth::Signature, Word};
+
+use crate::{keystore::KeystoreRouter, MidenKeyStore};
+
+/// A signature the transaction kernel asked for
+#[derive(Clone, Copy)]
+pub(crate) struct SigningRequest {
+    pub pub_key: Word,
+    pub message: Word,
+}
+
+pub(crate) struct WalletAuthenticator {
+    keystore: Arc<KeystoreRouter>,
+    /// When set, signing requests are recorded and rejected instead of signed
+    capture_only: Mutex<bool>,
+    captured: Mutex<Option<SigningRequest>>,
+    /// Externally produced signatures keyed by (public key, message)
+    imported: Mutex<HashMap<(Word, Word), Signature>>,
+}
+
+impl WalletAuthenticator {
+    pub fn new(keystore: Arc<KeystoreRouter>) -> Self {
+        Self {
+            keystore,
+            capture_only: Mutex::new(false),
+            captured: Mutex::new(None),
+            imported: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Start recording signing requests instead of signing
+    pub fn begin_capture(&self) {
+        *self.capture_only.lock().unwrap() = true;
+        *self.captured.lock().unwrap() = None;
+    }
+
+    /// Stop capture mode and return the recorded request, if any
+    pub fn end_capture(&self) -> Option<SigningRequest> {
+        *self.capture_only.lock().unwrap() = false;
+        self.captured.lock().unwrap().take()
+    }
+
+    /// Make an externally produced signature available for the next matching request
+    pub fn import_signature(&self, request: SigningRequest, signature: Signature) {
+        self.imported.lock().unwrap().insert((request.pub_key, request.message), signature);
+    }
+
+    /// Drop an imported signature that was not consumed
+    pub fn forget_signature(&self, request: SigningRequest) {
+        self.imported.lock().unwrap().remove(&(request.pub_key, request.message));
+    }
+}
+
+/// Required by the client builder, which can create a keystore-backed authenticator itself
+impl From<MidenKeyStore> for WalletAuthenticator {
+    fn from(keystore: MidenKeyStore) -> Self {
+        Self::new(Arc::new(KeystoreRouter::from_filesystem(keystore)))
+    }
+}
+
+impl TransactionAuthenticator for WalletAuthenticator {
+    async fn get_signature(
+        &self,
+        pub_key_commitment: PublicKeyCommitment,
+        signing_inputs: &SigningInputs,
+    ) -> Result<Signature, AuthenticationError> {
+        let pub_key = Word::from(pub_key_commitment);
+        let message = signing_inputs.to_commitment();
+
+        if *self.capture_only.lock().unwrap() {
+            *self.captured.lock().unwrap() = Some(SigningRequest { pub_key, message });
+            return Err(AuthenticationError::other("signing request captured for external signer"));
+        }
+
+        if let Some(signature) = self.imported.lock().unwrap().remove(&(pub_key, message)) {
+            return Ok(signature);
+        }
+
+        self.keystore.get_signature(pub_key, signing_inputs).await
+    }
+}
@@ -0,0 +1,134 @@
+//! Time-boxed sync for background refresh
+//!
+//! `BGAppRefreshTask` gives an app about 30 seconds before the system suspends it, and a
+//! task that overruns is killed (and scheduled less often afterwards). A background sync
+//! runs sync rounds until the store reaches the node tip or the budget runs out, then
+//! reports whether more work remains, so the app can schedule a processing task or sync
+//! again on the next launch.
+//!
+//! The budget starts when the call is made, so time spent queued behind other requests
+//! counts against it. miden-client applies each sync round to the store in a single
+//! write: a round cut off by the deadline is dropped and the store stays at the height
+//! of the last completed round.
+//!
+//! After a completed round the store-local post-sync work runs (retention, bridge and
+//! live transaction updates, the widget snapshot). Ephemeral account sweeps and stream
+//! payouts prove and submit transactions, which cannot be interrupted safely, so they are
+//! left to the next regular sync.
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    block_times, get_handle, live_tx, request_blocking, retention, sync_progress, sync_state_impl,
+    widget, write_out_buffer, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM, ERR_TIMEOUT, SYNC_TIMEOUT,
+};
+
+pub(crate) async fn background_sync_impl(
+    context: &mut MidenContext,
+    deadline: Instant,
+) -> Result<String, i32> {
+    let started = Instant::now();
+    let traffic = context.node_proxy.traffic().count();
+    let mut rounds = 0u32;
+    let mut synced_to_block = None;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        let round_started = Instant::now();
+        match tokio::time::timeout(remaining, sync_state_impl(context)).await {
+            Ok(Ok(block_num)) => {
+                rounds += 1;
+                synced_to_block = Some(block_num);
+                if block_num >= context.progress.node_tip() {
+                    break;
+                }
+            }
+            // Nothing was applied yet: report the failure like a regular sync
+            Ok(Err(code)) if synced_to_block.is_none() => return Err(code),
+            Ok(Err(_)) => break,
+            Err(_) => {
+                // The interrupted round never reached `end_sync`
+                sync_progress::end_sync(context, &Err(ERR_TIMEOUT), round_started.elapsed());
+                break;
+            }
+        }
+    }
+
+    if synced_to_block.is_some() && Instant::now() < deadline {
+        retention::apply_after_sync(context).await;
+        block_times::backfill_after_sync(context).await;
+        #[cfg(feature = "scripts")]
+        crate::bridge::update_after_sync(context).await;
+        live_tx::update_after_sync(context).await;
+        widget::update_after_sync(context).await;
+    }
+
+    let local_height = context.progress.local_height();
+    let node_tip = context.progress.node_tip();
+    let traffic = context.node_proxy.traffic().since(traffic);
+    Ok(serde_json::json!({
+        "synced_to_block": synced_to_block,
+        "node_tip": node_tip,
+        "blocks_behind": node_tip.saturating_sub(local_height),
+        "more_work_remaining": synced_to_block.is_none() || local_height < node_tip,
+        "rounds": rounds,
+        "elapsed_ms": started.elapsed().as_millis() as u64,
+        "bytes_sent": traffic.sent,
+        "bytes_received": traffic.received,
+    })
+    .to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Sync for at most `max_duration_ms`, for `BGAppRefreshTask` handlers (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Returns once the store reaches the node tip or the budget runs out. Running out of
+/// budget is not an error: progress of completed sync rounds is kept and the output
+/// reports what is left. Call `setTaskCompleted(success:)` with 0 as success.
+///
+/// # Parameters
+/// - `max_duration_ms`: Time budget in milliseconds, from 1 up to 30000 (the blocking
+///   call timeout); leave a margin below the time the system grants the task
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"synced_to_block","node_tip","blocks_behind","more_work_remaining","rounds",
+///   "elapsed_ms","bytes_sent","bytes_received"}` (`synced_to_block` is null when no round
+///   completed in time; the byte counts are the node traffic of all rounds, see
+///   node_proxy.rs)
+///
+/// # Returns
+/// - 0: Success (check `more_work_remaining`)
+/// - -1: Invalid parameters
+/// - -2: Invalid handle, worker closed or the first sync round failed
+/// - -7: Output buffer too small
+/// - -8: Worker queue full
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_background_sync(
+    handle: MidenHandle,
+    max_duration_ms: u32,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let budget = Duration::from_millis(u64::from(max_duration_ms));
+    if budget.is_zero() || budget > SYNC_TIMEOUT {
+        return ERR_INVALID_PARAM;
+    }
+    let deadline = Instant::now() + budget;
+
+    match request_blocking(&worker, |reply| Request::BackgroundSync { deadline, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,237 @@
+//! Block timestamps
+//!
+//! Transaction and note records only carry block numbers, while apps show dates. The
+//! worker keeps the timestamps of the blocks it has seen (from their headers), so JSON
+//! outputs can include an ISO-8601 UTC `timestamp` next to a block number:
+//!
+//! - every sync records the header of the node tip, which it requests anyway
+//! - after each successful sync, the blocks of committed transactions and notes whose
+//!   timestamp is unknown are backfilled with block-header requests, newest first and at
+//!   most [`BLOCK_TIME_BACKFILL_LIMIT`] per sync so a long history does not hold up syncs
+//!
+//! Outputs only read the recorded timestamps: a `timestamp` is null until its block has
+//! been backfilled. Timestamps are kept in a JSON file next to the SQLite store.
+//!
+//! The recorded timestamps also give the network's average block time, which
+//! `wc_miden_estimate_time_for_block` uses to tell when a future block (a timelock or
+//! reclaim height) will be produced. Formatting the estimate ("in about 3 hours") is left
+//! to the app, which knows the user's locale.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use miden_client::{
+    rpc::NodeRpcClient,
+    store::{NoteFilter, TransactionFilter},
+    transaction::TransactionStatus,
+};
+use miden_objects::block::BlockHeader;
+
+use crate::{
+    clock, diagnostics, get_handle, memory_store, request_blocking, write_out_buffer, MidenContext,
+    MidenHandle, Request, ERR_INVALID_HANDLE, ERR_LOOKUP,
+};
+
+/// Block headers requested per sync to backfill timestamps
+pub const BLOCK_TIME_BACKFILL_LIMIT: usize = 32;
+
+/// Block time assumed until the recorded timestamps span [`BLOCK_TIME_MIN_SAMPLE_BLOCKS`]
+pub const BLOCK_TIME_DEFAULT_MS: u64 = 3_000;
+
+/// Blocks the recorded timestamps must span to measure the average block time
+/// (timestamps have a resolution of one second)
+pub const BLOCK_TIME_MIN_SAMPLE_BLOCKS: u32 = 100;
+
+/// Recorded block timestamps of the store
+#[derive(Clone, Default)]
+pub(crate) struct BlockTimes {
+    path: PathBuf,
+    /// Block number → unix time in seconds
+    times: BTreeMap<u32, u64>,
+    unsaved: bool,
+}
+
+impl BlockTimes {
+    /// Load the sidecar file for the store at `store_path` (missing or corrupt → none)
+    pub fn load(store_path: &Path) -> Self {
+        let path = store_path.with_extension("block_times.json");
+        let entries: BTreeMap<String, u64> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        let times = entries
+            .into_iter()
+            .filter_map(|(block_num, time)| Some((block_num.parse().ok()?, time)))
+            .collect();
+        Self { path, times, unsaved: false }
+    }
+
+    fn save(&mut self) -> std::io::Result<()> {
+        let entries: BTreeMap<String, u64> =
+            self.times.iter().map(|(block_num, time)| (block_num.to_string(), *time)).collect();
+        let json = serde_json::to_string(&entries).map_err(std::io::Error::other)?;
+        memory_store::write(&self.path, json)?;
+        self.unsaved = false;
+        Ok(())
+    }
+
+    /// Record the timestamp of a block header seen during sync
+    pub fn record(&mut self, header: &BlockHeader) {
+        let time = u64::from(header.timestamp());
+        if self.times.insert(header.block_num().as_u32(), time) != Some(time) {
+            self.unsaved = true;
+        }
+    }
+
+    /// ISO-8601 timestamp of a block (None until it has been recorded)
+    pub fn timestamp(&self, block_num: u32) -> Option<String> {
+        self.times.get(&block_num).map(|time| clock::iso8601(*time))
+    }
+
+    /// Average block time in milliseconds over the recorded blocks (None if too few)
+    fn average_block_time_ms(&self) -> Option<u64> {
+        let (first_block, first_time) = self.times.first_key_value()?;
+        let (last_block, last_time) = self.times.last_key_value()?;
+        let blocks = last_block - first_block;
+        if blocks < BLOCK_TIME_MIN_SAMPLE_BLOCKS || last_time <= first_time {
+            return None;
+        }
+        Some((last_time - first_time) * 1_000 / u64::from(blocks))
+    }
+}
+
+/// Blocks in which transactions and input notes of the store were committed
+async fn activity_blocks(context: &MidenContext) -> Result<BTreeSet<u32>, String> {
+    let mut blocks = BTreeSet::new();
+
+    let transactions = context.client.get_transactions(TransactionFilter::All).await
+        .map_err(|e| e.to_string())?;
+    for record in transactions {
+        if let TransactionStatus::Committed { block_number, .. } = record.status {
+            blocks.insert(block_number.as_u32());
+        }
+    }
+
+    let notes = context.client.get_input_notes(NoteFilter::All).await
+        .map_err(|e| e.to_string())?;
+    for record in notes {
+        if let Some(proof) = record.inclusion_proof() {
+            blocks.insert(proof.location().block_num().as_u32());
+        }
+    }
+    Ok(blocks)
+}
+
+/// Fetch the timestamps of activity blocks not recorded yet (run after each successful sync)
+pub(crate) async fn backfill_after_sync(context: &mut MidenContext) {
+    let blocks = match activity_blocks(context).await {
+        Ok(blocks) => blocks,
+        Err(e) => {
+            diagnostics::log("block_times", format!("activity query failed: {}", e));
+            return;
+        }
+    };
+
+    let missing: Vec<u32> = blocks
+        .into_iter()
+        .rev()
+        .filter(|block_num| !context.block_times.times.contains_key(block_num))
+        .take(BLOCK_TIME_BACKFILL_LIMIT)
+        .collect();
+    for block_num in missing {
+        match context.rpc.get_block_header_by_number(Some(block_num.into()), false).await {
+            Ok((header, _)) => context.block_times.record(&header),
+            Err(e) => {
+                // Retried after the next sync
+                diagnostics::log("block_times", format!("header request failed: {:?}", e));
+                break;
+            }
+        }
+    }
+
+    if !context.block_times.unsaved {
+        return;
+    }
+    if let Err(e) = context.block_times.save() {
+        diagnostics::log("block_times", format!("failed to save timestamps: {:?}", e));
+    }
+}
+
+/// Estimate when `block_num` is (or was) produced, from the newest recorded block
+pub(crate) fn estimate_time_for_block_impl(
+    context: &MidenContext,
+    block_num: u32,
+) -> Result<String, i32> {
+    // Nothing is recorded before the first sync
+    let (&reference_block, &reference_time) =
+        context.block_times.times.last_key_value().ok_or(ERR_LOOKUP)?;
+    let measured_ms = context.block_times.average_block_time_ms();
+    let block_time_ms = measured_ms.unwrap_or(BLOCK_TIME_DEFAULT_MS);
+
+    let (estimated_at, is_estimate) = match context.block_times.times.get(&block_num) {
+        Some(time) => (*time, false),
+        None => {
+            let blocks = i64::from(block_num) - i64::from(reference_block);
+            let offset_secs = blocks * block_time_ms as i64 / 1_000;
+            (reference_time.saturating_add_signed(offset_secs), true)
+        }
+    };
+
+    let tip = context.progress.node_tip().max(reference_block);
+    Ok(serde_json::json!({
+        "block_num": block_num,
+        "tip": tip,
+        "blocks_remaining": block_num.saturating_sub(tip),
+        "average_block_time_ms": block_time_ms,
+        "block_time_measured": measured_ms.is_some(),
+        "estimated_at": clock::iso8601(estimated_at),
+        "seconds_from_now": estimated_at as i64 - clock::now_unix() as i64,
+        "is_estimate": is_estimate,
+    })
+    .to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Estimate the wall-clock time of a block, e.g. a timelock expiry (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// The estimate extrapolates from the newest block recorded during sync using the
+/// network's average block time (see the module docs); blocks whose timestamp is recorded
+/// are reported exactly. Format `seconds_from_now` or `estimated_at` for display with the
+/// user's locale (e.g. `RelativeDateTimeFormatter`).
+///
+/// # Parameters
+/// - `block_num`: Block number (past or future)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"block_num","tip","blocks_remaining","average_block_time_ms","block_time_measured",
+///   "estimated_at","seconds_from_now","is_estimate"}` (`estimated_at` in ISO-8601,
+///   `seconds_from_now` negative for past blocks)
+///
+/// # Returns
+/// - 0: Success
+/// - -2: Invalid handle or worker closed
+/// - -5: No block recorded yet (sync first)
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_estimate_time_for_block(
+    handle: MidenHandle,
+    block_num: u32,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    match request_blocking(&worker, |reply| Request::EstimateTimeForBlock { block_num, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,523 @@
+//! Cross-network bridge requests
+//!
+//! A bridge operator is a Miden account that takes custody of assets sent to it and pays
+//! out the equivalent on another chain. A bridge request is a public note to the operator
+//! built with the operator's deposit script (part of the operator configuration, like a
+//! name registry's registration script) and tagged for the operator account. Its assets
+//! are the bridged amount and its inputs are
+//!
+//! `[target_chain_id, address(5), sender_prefix, sender_suffix]`
+//!
+//! where `address` is the 20-byte EVM target address as five big-endian u32 limbs. The
+//! request ID is the note ID.
+//!
+//! Requests are tracked like invoices in a JSON file next to the SQLite store:
+//! `submitted` → `committed` (note included in a block) → `accepted` (the operator
+//! consumed the note) → `fulfilled`. The first transitions are detected after each
+//! successful sync; the payout on the target chain is not visible from Miden, so the app
+//! confirms it (from the operator's API) with `wc_miden_confirm_bridge_fulfillment`.
+//! Every transition emits a `bridge_request_updated` event.
+
+use std::{
+    os::raw::c_char,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use miden_client::transaction::TransactionRequestBuilder;
+use miden_lib::utils::ScriptBuilder;
+use miden_objects::{
+    account::AccountId,
+    asset::FungibleAsset,
+    crypto::rand::FeltRng,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteId, NoteInputs, NoteMetadata, NoteRecipient,
+        NoteScript, NoteTag, NoteType,
+    },
+    transaction::OutputNote,
+    Felt,
+};
+
+use crate::{
+    check_out_capacity, clock, diagnostics, get_handle, hex_placeholder, last_error, memory_store,
+    output_len, parse_account_id, parse_required_str, proving, request_blocking, screening,
+    whitelist, write_out_buffer, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM, ERR_LOOKUP, ERR_NOTE_OP, ERR_TX_SUBMIT, ID_HEX_LEN,
+};
+
+/// Bridge operator configured on a handle
+#[derive(Clone)]
+pub(crate) struct BridgeOperator {
+    account_id: AccountId,
+    deposit_script: NoteScript,
+    /// Accepted target chain IDs (empty = any)
+    supported_chains: Vec<u64>,
+}
+
+impl BridgeOperator {
+    /// Parse `{"operator_account_id":"0x..","deposit_script":"<MASM>","supported_chains":[1]}`
+    fn from_json(json: &str) -> Result<Self, i32> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|_| ERR_INVALID_PARAM)?;
+
+        let account_id = value["operator_account_id"].as_str()
+            .and_then(|hex| AccountId::from_hex(hex).ok())
+            .ok_or(ERR_INVALID_PARAM)?;
+        let deposit_script = value["deposit_script"].as_str()
+            .ok_or(ERR_INVALID_PARAM)
+            .and_then(|source| {
+                ScriptBuilder::new(true)
+                    .compile_note_script(source)
+                    .map_err(|_| ERR_INVALID_PARAM)
+            })?;
+        let supported_chains = match value.get("supported_chains") {
+            None | Some(serde_json::Value::Null) => Vec::new(),
+            Some(chains) => serde_json::from_value(chains.clone()).map_err(|_| ERR_INVALID_PARAM)?,
+        };
+
+        Ok(Self { account_id, deposit_script, supported_chains })
+    }
+}
+
+/// Bridge operator configuration of a handle (read by the FFI thread, never by the worker)
+#[derive(Default)]
+pub(crate) struct BridgeOperatorConfig(Mutex<Option<BridgeOperator>>);
+
+impl BridgeOperatorConfig {
+    /// Snapshot of the configured operator (-1 when none is configured)
+    fn get(&self) -> Result<BridgeOperator, i32> {
+        self.0.lock().unwrap().clone().ok_or(ERR_INVALID_PARAM)
+    }
+}
+
+/// Parse a `0x`-prefixed 20-byte EVM address into five big-endian u32 limbs
+fn parse_evm_address(address: &str) -> Result<[u32; 5], i32> {
+    let bytes = address.strip_prefix("0x")
+        .and_then(|hex_str| hex::decode(hex_str).ok())
+        .filter(|bytes| bytes.len() == 20)
+        .ok_or(ERR_INVALID_PARAM)?;
+
+    let mut limbs = [0u32; 5];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(4)) {
+        *limb = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+    Ok(limbs)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BridgeState {
+    Submitted,
+    Committed,
+    Accepted,
+    Fulfilled,
+}
+
+impl BridgeState {
+    fn as_str(self) -> &'static str {
+        match self {
+            BridgeState::Submitted => "submitted",
+            BridgeState::Committed => "committed",
+            BridgeState::Accepted => "accepted",
+            BridgeState::Fulfilled => "fulfilled",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "submitted" => Some(BridgeState::Submitted),
+            "committed" => Some(BridgeState::Committed),
+            "accepted" => Some(BridgeState::Accepted),
+            "fulfilled" => Some(BridgeState::Fulfilled),
+            _ => None,
+        }
+    }
+}
+
+struct BridgeRequest {
+    id: NoteId,
+    /// Request fields as reported to the app (account, chain, address, asset, timestamps)
+    details: serde_json::Value,
+    state: BridgeState,
+    target_tx_hash: Option<String>,
+}
+
+impl BridgeRequest {
+    fn to_json(&self) -> serde_json::Value {
+        let mut value = self.details.clone();
+        if let Some(fields) = value.as_object_mut() {
+            fields.insert("request_id".into(), self.id.to_hex().into());
+            fields.insert("state".into(), self.state.as_str().into());
+            fields.insert("target_tx_hash".into(), self.target_tx_hash.clone().into());
+        }
+        value
+    }
+}
+
+/// Bridge requests sent from this store
+pub(crate) struct BridgeRequests {
+    path: PathBuf,
+    requests: Vec<BridgeRequest>,
+}
+
+impl BridgeRequests {
+    /// Load the sidecar file for the store at `store_path` (missing or corrupt → none)
+    pub fn load(store_path: &Path) -> Self {
+        let path = store_path.with_extension("bridge.json");
+        let entries: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        let requests = entries
+            .into_iter()
+            .filter_map(|mut entry| {
+                let fields = entry.as_object_mut()?;
+                let id = NoteId::try_from_hex(fields.remove("request_id")?.as_str()?).ok()?;
+                let state = BridgeState::parse(fields.remove("state")?.as_str()?)?;
+                let target_tx_hash = fields.remove("target_tx_hash")
+                    .and_then(|hash| hash.as_str().map(str::to_string));
+                Some(BridgeRequest { id, details: entry, state, target_tx_hash })
+            })
+            .collect();
+
+        Self { path, requests }
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let entries: Vec<serde_json::Value> =
+            self.requests.iter().map(BridgeRequest::to_json).collect();
+        memory_store::write(&self.path, serde_json::Value::from(entries).to_string())
+    }
+}
+
+/// Persist a state change and announce it
+fn transition(context: &mut MidenContext, index: usize, state: BridgeState) {
+    let request = &mut context.bridge.requests[index];
+    request.state = state;
+    let request_id = request.id;
+
+    if let Err(e) = context.bridge.save() {
+        diagnostics::log("bridge", format!("failed to save state: {:?}", e));
+    }
+    context.events.emit(serde_json::json!({
+        "type": "bridge_request_updated",
+        "request_id": request_id.to_hex(),
+        "state": state.as_str(),
+    }));
+}
+
+pub(crate) async fn create_bridge_request_impl(
+    context: &mut MidenContext,
+    operator: BridgeOperator,
+    account_id: AccountId,
+    target_chain: u64,
+    target_address: String,
+    faucet_id: AccountId,
+    amount: u64,
+) -> Result<String, i32> {
+    let address = parse_evm_address(&target_address)?;
+
+    let mut inputs = vec![Felt::try_from(target_chain).map_err(|_| ERR_INVALID_PARAM)?];
+    inputs.extend(address.map(Felt::from));
+    inputs.extend([account_id.prefix().as_felt(), account_id.suffix()]);
+    let inputs = NoteInputs::new(inputs).map_err(|_| ERR_NOTE_OP)?;
+
+    let asset = FungibleAsset::new(faucet_id, amount).map_err(|_| ERR_INVALID_PARAM)?;
+    let assets = NoteAssets::new(vec![asset.into()]).map_err(|_| ERR_NOTE_OP)?;
+    let metadata = NoteMetadata::new(
+        account_id,
+        NoteType::Public,
+        NoteTag::from_account_id(operator.account_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )
+    .map_err(|_| ERR_NOTE_OP)?;
+    let serial_num = context.client.rng().draw_word();
+    let note = Note::new(
+        assets,
+        metadata,
+        NoteRecipient::new(serial_num, operator.deposit_script, inputs),
+    );
+
+    // Bridged funds leave the account like any other send
+    let output_notes = [note.clone()];
+    whitelist::check(context, account_id, &output_notes)?;
+    screening::screen_transaction(context, account_id, &output_notes, &[]).await?;
+
+    let request_id = note.id();
+    let tx_request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note)])
+        .build()
+        .map_err(|_| ERR_NOTE_OP)?;
+
+    proving::check_memory(context)?;
+    let tx_id = context.client.submit_new_transaction(account_id, tx_request).await
+        .map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))?;
+
+    context.bridge.requests.push(BridgeRequest {
+        id: request_id,
+        details: serde_json::json!({
+            "account_id": account_id.to_hex(),
+            "operator_account_id": operator.account_id.to_hex(),
+            "target_chain": target_chain,
+            "target_address": target_address.to_ascii_lowercase(),
+            "faucet_id": faucet_id.to_hex(),
+            "amount": amount,
+            "tx_id": tx_id.to_hex(),
+            "created_at": clock::now_unix(),
+        }),
+        state: BridgeState::Submitted,
+        target_tx_hash: None,
+    });
+    let index = context.bridge.requests.len() - 1;
+    transition(context, index, BridgeState::Submitted);
+
+    Ok(serde_json::json!({
+        "request_id": request_id.to_hex(),
+        "tx_id": tx_id.to_hex(),
+    })
+    .to_string())
+}
+
+/// Advance requests whose note was committed or consumed (after each successful sync)
+pub(crate) async fn update_after_sync(context: &mut MidenContext) {
+    for index in 0..context.bridge.requests.len() {
+        let request = &context.bridge.requests[index];
+        if matches!(request.state, BridgeState::Accepted | BridgeState::Fulfilled) {
+            continue;
+        }
+        let (request_id, state) = (request.id, request.state);
+
+        let record = match context.client.get_output_note(request_id).await {
+            Ok(Some(record)) => record,
+            Ok(None) => continue,
+            Err(e) => {
+                diagnostics::log("bridge", format!("output note lookup failed: {:?}", e));
+                continue;
+            }
+        };
+
+        let observed = if record.is_consumed() {
+            BridgeState::Accepted
+        } else if record.is_committed() {
+            BridgeState::Committed
+        } else {
+            continue;
+        };
+        if observed != state {
+            transition(context, index, observed);
+        }
+    }
+}
+
+pub(crate) fn confirm_bridge_fulfillment_impl(
+    context: &mut MidenContext,
+    request_id: NoteId,
+    target_tx_hash: String,
+) -> Result<String, i32> {
+    let index = context.bridge.requests.iter().position(|request| request.id == request_id)
+        .ok_or(ERR_LOOKUP)?;
+    if context.bridge.requests[index].state == BridgeState::Fulfilled {
+        return Err(ERR_INVALID_PARAM);
+    }
+
+    context.bridge.requests[index].target_tx_hash = Some(target_tx_hash);
+    transition(context, index, BridgeState::Fulfilled);
+    Ok(String::new())
+}
+
+pub(crate) fn list_bridge_requests_impl(context: &MidenContext) -> Result<String, i32> {
+    let requests: Vec<serde_json::Value> =
+        context.bridge.requests.iter().map(BridgeRequest::to_json).collect();
+    Ok(serde_json::Value::from(requests).to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Configure the bridge operator used by a handle
+///
+/// # Parameters
+/// - `config_json`: `{"operator_account_id":"0x..","deposit_script":"<MASM>",
+///   "supported_chains":[1,10]}` (`supported_chains` optional), or NULL to remove the
+///   configuration
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid configuration (including a script that does not compile)
+/// - -2: Invalid handle
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_set_bridge_operator(
+    handle: MidenHandle,
+    config_json: *const c_char,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let operator = if config_json.is_null() {
+        None
+    } else {
+        match parse_required_str(config_json).and_then(BridgeOperator::from_json) {
+            Ok(operator) => Some(operator),
+            Err(code) => return code,
+        }
+    };
+
+    *worker.bridge_operator.0.lock().unwrap() = operator;
+    0
+}
+
+/// Send assets to the bridge operator for payout on another chain (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_id_hex`: Sending account (C string)
+/// - `target_chain`: EVM chain ID of the destination network
+/// - `target_address`: `0x`-prefixed 20-byte destination address (C string)
+/// - `faucet_id_hex`: Faucet of the bridged asset (C string)
+/// - `amount`: Amount in base units (must be > 0)
+/// - `json_out` / `json_out_len`: Output buffer for `{"request_id":"0x..","tx_id":"0x.."}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, no operator configured or unsupported chain
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID
+/// - -4: Note construction failed
+/// - -6: Transaction submission failed
+/// - -7: Output buffer too small
+/// - -101: Operator is not on the account's withdrawal whitelist
+/// - -102: Blocked by the screening callback
+#[allow(clippy::too_many_arguments)]
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_create_bridge_request(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    target_chain: u64,
+    target_address: *const c_char,
+    faucet_id_hex: *const c_char,
+    amount: u64,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let operator = match worker.bridge_operator.get() {
+        Ok(operator) => operator,
+        Err(code) => return code,
+    };
+    if amount == 0
+        || (!operator.supported_chains.is_empty()
+            && !operator.supported_chains.contains(&target_chain))
+    {
+        return ERR_INVALID_PARAM;
+    }
+    let result = serde_json::json!({
+        "request_id": hex_placeholder(ID_HEX_LEN),
+        "tx_id": hex_placeholder(ID_HEX_LEN),
+    });
+    if let Err(code) = check_out_capacity(json_out, json_out_len, output_len(&result)) {
+        return code;
+    }
+    let (account_id, faucet_id) =
+        match (parse_account_id(account_id_hex), parse_account_id(faucet_id_hex)) {
+            (Ok((account_id, _)), Ok((faucet_id, _))) => (account_id, faucet_id),
+            (Err(code), _) | (_, Err(code)) => return code,
+        };
+    let target_address = match parse_required_str(target_address) {
+        Ok(address) => address.to_string(),
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::CreateBridgeRequest {
+        operator,
+        account_id,
+        target_chain,
+        target_address,
+        faucet_id,
+        amount,
+        reply,
+    };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Mark a bridge request as paid out on the target chain (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `request_id_hex`: Bridge request ID (C string)
+/// - `target_tx_hash`: Payout transaction hash on the target chain (C string)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or request already fulfilled
+/// - -2: Invalid handle or worker closed
+/// - -4: Invalid request ID
+/// - -5: Request not found
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_confirm_bridge_fulfillment(
+    handle: MidenHandle,
+    request_id_hex: *const c_char,
+    target_tx_hash: *const c_char,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let request_id = match parse_required_str(request_id_hex) {
+        Ok(s) => match NoteId::try_from_hex(s) {
+            Ok(id) => id,
+            Err(_) => return ERR_NOTE_OP,
+        },
+        Err(code) => return code,
+    };
+    let target_tx_hash = match parse_required_str(target_tx_hash) {
+        Ok(hash) if !hash.is_empty() => hash.to_string(),
+        Ok(_) => return ERR_INVALID_PARAM,
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::ConfirmBridgeFulfillment { request_id, target_tx_hash, reply };
+    match request_blocking(&worker, request) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
+
+/// List bridge requests with their lifecycle state (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `json_out` / `json_out_len`: Output buffer for a JSON array of `{"request_id","state",
+///   "account_id","operator_account_id","target_chain","target_address","faucet_id",
+///   "amount","tx_id","created_at","target_tx_hash"}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_list_bridge_requests(
+    handle: MidenHandle,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    match request_blocking(&worker, |reply| Request::ListBridgeRequests { reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
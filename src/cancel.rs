@@ -0,0 +1,151 @@
+//! Cancellation of async operations
+//!
+//! Every `*_async` entry point returns an operation handle (> 0) instead of 0. Passing it
+//! to `wc_miden_cancel` cancels the operation, and its callback receives
+//! `ERR_CANCELLED` instead of a result:
+//!
+//! - a request still waiting in the worker queue is dropped without running
+//! - a running read or sync is aborted at its next await point; a sync round is applied
+//!   to the store in one write, so an aborted sync leaves the store at its last height
+//! - a running wallet creation or transaction (consume, send) is not interrupted: the
+//!   transaction may already be with the node, so aborting would desynchronize the
+//!   store. Cancelling it once it has started has no effect
+//! - a wait for a transaction (`wc_miden_wait_for_transaction`) stops before its next poll
+//!
+//! The callback runs exactly once either way. Handles are only valid until the callback
+//! runs; cancelling a finished or unknown operation returns `ERR_LOOKUP`.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Instant,
+};
+
+use once_cell::sync::Lazy;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    diagnostics, handle_request, sync_progress, try_send_request, MidenContext, MidenWorkerHandle,
+    Request, ERR_CANCELLED, ERR_LOOKUP,
+};
+
+/// Tokens of the operations queued or running, by operation handle
+static OPERATIONS: Lazy<Mutex<HashMap<u64, CancellationToken>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Next operation handle (0 is never handed out)
+static NEXT_OPERATION: AtomicU64 = AtomicU64::new(1);
+
+/// Cancellation state of an async request, travelling with it through the worker queue
+pub(crate) struct Operation {
+    id: u64,
+    token: CancellationToken,
+}
+
+impl Operation {
+    /// Register a new operation
+    pub fn register() -> Self {
+        let id = NEXT_OPERATION.fetch_add(1, Ordering::Relaxed);
+        let token = CancellationToken::new();
+        OPERATIONS.lock().unwrap().insert(id, token.clone());
+        Self { id, token }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Whether `wc_miden_cancel` was called (for operations that check it themselves)
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Forget the operation (done, or never queued)
+    pub fn finish(self) {
+        OPERATIONS.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// Queue an async request as a cancellable operation
+///
+/// Returns the operation handle, or the error code when the request could not be queued.
+pub(crate) fn submit(worker: &MidenWorkerHandle, request: Request) -> i64 {
+    let operation = Operation::register();
+    let id = operation.id();
+    let request = Request::Cancellable { operation, request: Box::new(request) };
+
+    match try_send_request(worker, request) {
+        Ok(()) => id as i64,
+        Err(code) => {
+            // The request was dropped with its operation: forget the handle
+            forget(id);
+            i64::from(code)
+        }
+    }
+}
+
+/// Forget the operation of a request that could not be queued
+pub(crate) fn forget(id: u64) {
+    OPERATIONS.lock().unwrap().remove(&id);
+}
+
+/// Run a cancellable request on the worker
+pub(crate) async fn run(context: &mut MidenContext, operation: Operation, request: Request) {
+    let token = operation.token.clone();
+    execute(context, &token, request).await;
+    operation.finish();
+}
+
+async fn execute(context: &mut MidenContext, token: &CancellationToken, request: Request) {
+    let Some(fail) = request.failure_reply() else {
+        handle_request(context, request).await;
+        return;
+    };
+
+    if token.is_cancelled() {
+        diagnostics::record_error(ERR_CANCELLED);
+        fail(ERR_CANCELLED);
+        return;
+    }
+    if !request.is_interruptible() {
+        handle_request(context, request).await;
+        return;
+    }
+
+    let started = Instant::now();
+    if token.run_until_cancelled(handle_request(context, request)).await.is_none() {
+        // An aborted sync never reached `end_sync`
+        sync_progress::end_sync(context, &Err(ERR_CANCELLED), started.elapsed());
+        diagnostics::record_error(ERR_CANCELLED);
+        fail(ERR_CANCELLED);
+    }
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Cancel an async operation
+///
+/// Safe to call from any thread, including the main thread. See the module docs for what
+/// can be interrupted; the operation's callback still runs exactly once.
+///
+/// # Parameters
+/// - `op_handle`: Operation handle returned by a `*_async` function
+///
+/// # Returns
+/// - 0: Cancellation requested
+/// - -5: Unknown operation, or its callback already ran
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_cancel(op_handle: u64) -> i32 {
+    match OPERATIONS.lock().unwrap().get(&op_handle) {
+        Some(token) => {
+            token.cancel();
+            0
+        }
+        None => ERR_LOOKUP,
+    }
+}
@@ -0,0 +1,137 @@
+//! Time and block-height source
+//!
+//! Expiration, timelock and retention logic reads the current time and chain height
+//! through this module instead of the system clock and the client directly. With the
+//! `testing` feature enabled, `wc_test_set_time` freezes either source so note-expiry and
+//! scheduler behavior can be tested deterministically; `wc_test_advance_time` moves a
+//! frozen clock forward.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{MidenContext, ERR_LOOKUP};
+
+#[cfg(feature = "testing")]
+mod frozen {
+    use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+    /// Frozen unix time in seconds (0 = system clock)
+    pub static TIME: AtomicU64 = AtomicU64::new(0);
+    /// Frozen block height (0 = client sync height)
+    pub static BLOCK: AtomicU32 = AtomicU32::new(0);
+
+    pub fn time() -> Option<u64> {
+        Some(TIME.load(Ordering::SeqCst)).filter(|t| *t != 0)
+    }
+
+    pub fn block() -> Option<u32> {
+        Some(BLOCK.load(Ordering::SeqCst)).filter(|b| *b != 0)
+    }
+}
+
+#[cfg(not(feature = "testing"))]
+mod frozen {
+    #[inline(always)]
+    pub fn time() -> Option<u64> {
+        None
+    }
+
+    #[inline(always)]
+    pub fn block() -> Option<u32> {
+        None
+    }
+}
+
+/// Current unix time in seconds
+pub(crate) fn now_unix() -> u64 {
+    frozen::time().unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    })
+}
+
+/// ISO-8601 UTC form of a unix time, e.g. `2025-03-14T09:26:53Z`
+pub(crate) fn iso8601(unix_secs: u64) -> String {
+    let days = (unix_secs / 86_400) as i64;
+    let secs = unix_secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (H. Hinnant's `civil_from_days`)
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3_600,
+        secs % 3_600 / 60,
+        secs % 60
+    )
+}
+
+/// Current chain height as known to the client (last synced block)
+pub(crate) async fn current_block(context: &MidenContext) -> Result<u32, i32> {
+    if let Some(block) = frozen::block() {
+        return Ok(block);
+    }
+    context.client.get_sync_height().await
+        .map(|block| block.as_u32())
+        .map_err(|_| ERR_LOOKUP)
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Freeze the time and/or block-height source (testing builds only)
+///
+/// # Parameters
+/// - `unix_secs`: Frozen unix time in seconds, or 0 to use the system clock
+/// - `block_height`: Frozen block height, or 0 to use the client's sync height
+///
+/// # Returns
+/// - 0: Success
+#[cfg(feature = "testing")]
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_test_set_time(unix_secs: u64, block_height: u32) -> i32 {
+    use std::sync::atomic::Ordering;
+
+    frozen::TIME.store(unix_secs, Ordering::SeqCst);
+    frozen::BLOCK.store(block_height, Ordering::SeqCst);
+    0
+}
+
+/// Move a frozen clock forward (testing builds only)
+///
+/// Only sources frozen with `wc_test_set_time` are advanced.
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Neither source is frozen
+#[cfg(feature = "testing")]
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_test_advance_time(secs: u64, blocks: u32) -> i32 {
+    use std::sync::atomic::Ordering;
+
+    let advance_time = frozen::TIME.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |t| {
+        (t != 0).then(|| t.saturating_add(secs))
+    });
+    let advance_block = frozen::BLOCK.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |b| {
+        (b != 0).then(|| b.saturating_add(blocks))
+    });
+
+    if advance_time.is_err() && advance_block.is_err() {
+        return crate::ERR_INVALID_PARAM;
+    }
+    0
+}
@@ -0,0 +1,450 @@
+//! Cold-wallet (air-gapped signing) flow
+//!
+//! 1. The online device calls `wc_miden_build_unsigned_tx`: the transaction is executed
+//!    in capture mode, which records the message the account's auth component needs
+//!    signed, and everything required to rebuild the transaction is packed into a blob.
+//! 2. The blob is moved to the offline device, which signs `message` with the Falcon key
+//!    matching `pub_key`.
+//! 3. The online device calls `wc_miden_attach_signature_and_submit` with the blob and
+//!    the serialized signature; the transaction is rebuilt, executed with the imported
+//!    signature, proven and submitted.
+//!
+//! Output notes are serialized into the blob so that the rebuilt transaction (and hence
+//! the signed message) is identical to the one that was exported. The message commits to
+//! the account state the transaction was executed against, so the signature only verifies
+//! when the online device re-runs it on the same state: a sync that changes the account,
+//! or another transaction from it, between steps 1 and 3 makes the submission fail and the
+//! transaction must be exported again. The sealed travel-rule envelope of a payment travels
+//! in the blob too and is returned with the submission result.
+
+use std::os::raw::c_char;
+
+use miden_client::transaction::{TransactionRequest, TransactionRequestBuilder};
+use miden_objects::{
+    account::{auth::Signature, AccountId},
+    crypto::dsa::rpo_falcon512,
+    note::{Note, NoteId, NoteType},
+    transaction::{OutputNote, TransactionId},
+    utils::{Deserializable, Serializable},
+    Word,
+};
+
+use crate::{
+    amount,
+    auth::SigningRequest,
+    check_out_capacity, get_handle, last_error, parse_account_id, parse_required_str, proving,
+    request_blocking, screening,
+    travel_rule::TravelRuleRequest,
+    types::WcNoteType,
+    wallet_core::{self, CoreError},
+    whitelist, write_out_buffer, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM, ERR_NOTE_OP, ERR_TX_SUBMIT, ID_HEX_LEN,
+};
+
+/// Version tag of the unsigned transaction blob format
+pub const UNSIGNED_TX_BLOB_VERSION: u32 = 1;
+
+/// Transaction described by the caller's request JSON
+///
+/// - `{"type":"p2id","target_account_id":"0x..","faucet_id":"0x..","amount":100,"note_type":"public"}`
+///   (`note_type` may also be the numeric `WcNoteType` value; an optional `reclaim_block`
+///   makes the payment recallable from that block; an optional `travel_rule` section
+///   attaches sealed originator/beneficiary metadata, see `travel_rule`)
+/// - `{"type":"consume","note_ids":["0x..", ...]}`
+pub(crate) enum TxSpec {
+    PayToId {
+        target: AccountId,
+        faucet_id: AccountId,
+        amount: u64,
+        note_type: NoteType,
+        /// Block from which the sender can recall the note (P2IDE instead of P2ID)
+        reclaim_block: Option<u32>,
+        travel_rule: Option<TravelRuleRequest>,
+    },
+    Consume {
+        note_ids: Vec<NoteId>,
+    },
+}
+
+impl TxSpec {
+    pub fn from_json(json: &str) -> Result<Self, i32> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|_| ERR_INVALID_PARAM)?;
+        let field = |name: &str| value.get(name).ok_or(ERR_INVALID_PARAM);
+        let account_field = |name: &str| {
+            field(name)?
+                .as_str()
+                .and_then(|s| AccountId::from_hex(s).ok())
+                .ok_or(ERR_INVALID_PARAM)
+        };
+
+        match field("type")?.as_str() {
+            Some("p2id") => Ok(TxSpec::PayToId {
+                target: account_field("target_account_id")?,
+                faucet_id: account_field("faucet_id")?,
+                amount: amount::from_json(field("amount")?).ok_or(ERR_INVALID_PARAM)?,
+                note_type: WcNoteType::from_json(value.get("note_type"))?.into(),
+                reclaim_block: value
+                    .get("reclaim_block")
+                    .map(|v| v.as_u64().and_then(|b| u32::try_from(b).ok()).ok_or(ERR_INVALID_PARAM))
+                    .transpose()?,
+                travel_rule: value.get("travel_rule").map(TravelRuleRequest::from_json).transpose()?,
+            }),
+            Some("consume") => {
+                let note_ids = field("note_ids")?
+                    .as_array()
+                    .ok_or(ERR_INVALID_PARAM)?
+                    .iter()
+                    .map(|id| id.as_str().and_then(|s| NoteId::try_from_hex(s).ok()).ok_or(ERR_NOTE_OP))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if note_ids.is_empty() {
+                    return Err(ERR_NOTE_OP);
+                }
+                Ok(TxSpec::Consume { note_ids })
+            }
+            _ => Err(ERR_INVALID_PARAM),
+        }
+    }
+
+    /// Length of the result of submitting the spec (see `submission_json`), so callers can
+    /// check their buffer before anything is submitted
+    pub fn submission_len(&self) -> usize {
+        let envelope_len = match self {
+            TxSpec::PayToId { travel_rule: Some(request), .. } => Some(request.sealed_len()),
+            _ => None,
+        };
+        submission_len(envelope_len)
+    }
+}
+
+/// Everything needed to rebuild an exported transaction on the online device
+pub(crate) struct UnsignedTx {
+    account_id: AccountId,
+    output_notes: Vec<Note>,
+    consumed_note_ids: Vec<NoteId>,
+    request: SigningRequest,
+    /// Sealed travel-rule metadata for the payment note (not part of the signed data)
+    travel_rule_envelope: Option<serde_json::Value>,
+}
+
+impl UnsignedTx {
+    /// Materialize the notes of a transaction spec (P2ID notes get fresh serial numbers)
+    fn prepare(context: &mut MidenContext, account_id: AccountId, spec: TxSpec) -> Result<Self, i32> {
+        let (output_notes, consumed_note_ids, travel_rule_envelope) = match spec {
+            TxSpec::PayToId { target, faucet_id, amount, note_type, reclaim_block, travel_rule } => {
+                let rng = context.client.rng();
+                let note = wallet_core::payment_note(
+                    account_id, target, faucet_id, amount, note_type, reclaim_block, rng,
+                )
+                .map_err(CoreError::into_code)?;
+                let envelope = travel_rule.map(|request| request.seal(note.id())).transpose()?;
+                (vec![note], Vec::new(), envelope)
+            }
+            TxSpec::Consume { note_ids } => (Vec::new(), note_ids, None),
+        };
+
+        Ok(Self {
+            account_id,
+            output_notes,
+            consumed_note_ids,
+            request: SigningRequest { pub_key: Word::default(), message: Word::default() },
+            travel_rule_envelope,
+        })
+    }
+
+    /// Apply the pre-submit policies: withdrawal whitelist, then the screening callback
+    async fn screen(&self, context: &MidenContext) -> Result<(), i32> {
+        whitelist::check(context, self.account_id, &self.output_notes)?;
+        screening::screen_transaction(context, self.account_id, &self.output_notes, &self.consumed_note_ids)
+            .await
+    }
+
+    /// IDs of the notes created by the transaction
+    pub fn output_note_ids(&self) -> Vec<NoteId> {
+        self.output_notes.iter().map(Note::id).collect()
+    }
+
+    fn to_blob(&self) -> Vec<u8> {
+        let value = serde_json::json!({
+            "version": UNSIGNED_TX_BLOB_VERSION,
+            "account_id": self.account_id.to_hex(),
+            "output_notes": self.output_notes.iter().map(|n| hex::encode(n.to_bytes())).collect::<Vec<_>>(),
+            "consumed_note_ids": self.consumed_note_ids.iter().map(|id| id.to_hex()).collect::<Vec<_>>(),
+            "pub_key": hex::encode(self.request.pub_key.to_bytes()),
+            "message": hex::encode(self.request.message.to_bytes()),
+            "travel_rule_envelope": self.travel_rule_envelope,
+        });
+        value.to_string().into_bytes()
+    }
+
+    fn from_blob(blob: &[u8]) -> Result<Self, i32> {
+        let value: serde_json::Value = serde_json::from_slice(blob).map_err(|_| ERR_INVALID_PARAM)?;
+        if value.get("version").and_then(|v| v.as_u64()) != Some(UNSIGNED_TX_BLOB_VERSION as u64) {
+            return Err(ERR_INVALID_PARAM);
+        }
+
+        let hex_field = |name: &str| -> Result<Vec<u8>, i32> {
+            value.get(name)
+                .and_then(|v| v.as_str())
+                .and_then(|s| hex::decode(s).ok())
+                .ok_or(ERR_INVALID_PARAM)
+        };
+        let strings = |name: &str| -> Result<Vec<String>, i32> {
+            serde_json::from_value(value.get(name).cloned().ok_or(ERR_INVALID_PARAM)?)
+                .map_err(|_| ERR_INVALID_PARAM)
+        };
+
+        let account_id = value.get("account_id")
+            .and_then(|v| v.as_str())
+            .and_then(|s| AccountId::from_hex(s).ok())
+            .ok_or(ERR_INVALID_PARAM)?;
+
+        let output_notes = strings("output_notes")?
+            .iter()
+            .map(|s| {
+                let bytes = hex::decode(s).map_err(|_| ERR_INVALID_PARAM)?;
+                Note::read_from_bytes(&bytes).map_err(|_| ERR_NOTE_OP)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let consumed_note_ids = strings("consumed_note_ids")?
+            .iter()
+            .map(|s| NoteId::try_from_hex(s).map_err(|_| ERR_NOTE_OP))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let pub_key = Word::read_from_bytes(&hex_field("pub_key")?).map_err(|_| ERR_INVALID_PARAM)?;
+        let message = Word::read_from_bytes(&hex_field("message")?).map_err(|_| ERR_INVALID_PARAM)?;
+
+        let travel_rule_envelope = match value.get("travel_rule_envelope") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(envelope @ serde_json::Value::Object(_)) => Some(envelope.clone()),
+            Some(_) => return Err(ERR_INVALID_PARAM),
+        };
+
+        Ok(Self {
+            account_id,
+            output_notes,
+            consumed_note_ids,
+            request: SigningRequest { pub_key, message },
+            travel_rule_envelope,
+        })
+    }
+
+    fn to_request(&self) -> Result<TransactionRequest, i32> {
+        if self.consumed_note_ids.is_empty() {
+            TransactionRequestBuilder::new()
+                .own_output_notes(self.output_notes.iter().cloned().map(OutputNote::Full).collect::<Vec<_>>())
+                .build()
+                .map_err(|_| ERR_NOTE_OP)
+        } else {
+            TransactionRequestBuilder::new()
+                .build_consume_notes(self.consumed_note_ids.clone())
+                .map_err(|_| ERR_NOTE_OP)
+        }
+    }
+}
+
+pub(crate) async fn build_unsigned_tx_impl(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    spec: TxSpec,
+) -> Result<Vec<u8>, i32> {
+    let mut unsigned = UnsignedTx::prepare(context, account_id, spec)?;
+    unsigned.screen(context).await?;
+    let tx_request = unsigned.to_request()?;
+
+    // Execute in capture mode: the auth component's signature request is recorded and
+    // execution stops there, so nothing is proven or submitted.
+    context.authenticator.begin_capture();
+    let _ = context.client.execute_transaction(account_id, tx_request).await;
+    unsigned.request = context.authenticator.end_capture().ok_or(ERR_TX_SUBMIT)?;
+
+    Ok(unsigned.to_blob())
+}
+
+/// Build, execute, prove and submit a transaction spec with locally available keys
+pub(crate) async fn submit_spec(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    spec: TxSpec,
+) -> Result<(TransactionId, UnsignedTx), i32> {
+    let unsigned = UnsignedTx::prepare(context, account_id, spec)?;
+    unsigned.screen(context).await?;
+    let tx_request = unsigned.to_request()?;
+
+    proving::check_memory(context)?;
+    let tx_id = wallet_core::submit(&mut context.client, account_id, tx_request).await
+        .map_err(CoreError::into_code)?;
+
+    Ok((tx_id, unsigned))
+}
+
+/// Length of `submission_json` with an envelope of `envelope_len` bytes (None for null)
+fn submission_len(envelope_len: Option<usize>) -> usize {
+    let envelope_len = envelope_len.unwrap_or("null".len());
+    r#"{"tx_id":"","travel_rule_envelope":}"#.len() + ID_HEX_LEN + envelope_len
+}
+
+/// `{"tx_id":"0x..","travel_rule_envelope":{...}|null}` for a submitted transaction
+fn submission_json(tx_id: TransactionId, unsigned: &UnsignedTx) -> String {
+    serde_json::json!({
+        "tx_id": tx_id.to_hex(),
+        "travel_rule_envelope": unsigned.travel_rule_envelope,
+    })
+    .to_string()
+}
+
+/// Submit a transaction spec, returning `{"tx_id":"0x..","travel_rule_envelope":{...}|null}`
+pub(crate) async fn submit_tx_spec_impl(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    spec: TxSpec,
+) -> Result<String, i32> {
+    let (tx_id, unsigned) = submit_spec(context, account_id, spec).await?;
+    Ok(submission_json(tx_id, &unsigned))
+}
+
+pub(crate) async fn submit_signed_tx_impl(
+    context: &mut MidenContext,
+    unsigned: UnsignedTx,
+    signature: Signature,
+) -> Result<String, i32> {
+    unsigned.screen(context).await?;
+    let tx_request = unsigned.to_request()?;
+
+    proving::check_memory(context)?;
+    context.authenticator.import_signature(unsigned.request, signature);
+    let result = context.client.submit_new_transaction(unsigned.account_id, tx_request).await;
+    context.authenticator.forget_signature(unsigned.request);
+
+    let tx_id = result.map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))?;
+    Ok(submission_json(tx_id, &unsigned))
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Build an unsigned transaction for signing on an air-gapped device (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_id_hex`: Executing account ID (C string)
+/// - `request_json`: Transaction description (C string), one of
+///   `{"type":"p2id","target_account_id","faucet_id","amount","note_type":"public"|"private",
+///   "reclaim_block"}` (`reclaim_block` optional) or
+///   `{"type":"consume","note_ids":[...]}`
+/// - `blob_out` / `blob_out_len`: Output buffer for the unsigned transaction blob (JSON).
+///   The blob's `message` field (hex-serialized word) is what the offline device must sign
+///   with the key whose commitment is `pub_key`. `travel_rule_envelope` holds the sealed
+///   travel-rule metadata to deliver with the payment (null without a `travel_rule` section).
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID
+/// - -4: Invalid note ID / note construction failed
+/// - -6: Transaction execution failed before a signature was requested
+/// - -7: Output buffer too small
+/// - -101: Recipient is not on the account's withdrawal whitelist, or the whitelist
+///   could not be read
+/// - -102: Blocked by the screening callback
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_build_unsigned_tx(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    request_json: *const c_char,
+    blob_out: *mut u8,
+    blob_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if blob_out.is_null() || blob_out_len.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    let (account_id, _) = match parse_account_id(account_id_hex) {
+        Ok(v) => v,
+        Err(code) => return code,
+    };
+
+    let spec = match parse_required_str(request_json).and_then(TxSpec::from_json) {
+        Ok(spec) => spec,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::BuildUnsignedTx { account_id, spec, reply }) {
+        Ok(blob) => write_out_buffer(&blob, blob_out, blob_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Attach an externally produced signature to an unsigned transaction blob and submit it (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+/// NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+///
+/// # Parameters
+/// - `blob_ptr` / `blob_len`: Blob returned by `wc_miden_build_unsigned_tx`
+/// - `signature_ptr` / `signature_len`: Serialized RPO Falcon512 signature over the blob's `message`
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"tx_id":"0x..","travel_rule_envelope":{...}|null}`, the envelope being the one
+///   exported in the blob
+///
+/// The account must be in the state the blob was built against (see the module docs);
+/// otherwise the signature does not verify and the call fails with -6.
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or malformed blob/signature
+/// - -2: Invalid handle or worker closed
+/// - -4: Note deserialization failed
+/// - -6: Transaction submission failed (including a signature that does not verify)
+/// - -7: Output buffer too small
+/// - -101: Recipient is not on the account's withdrawal whitelist, or the whitelist
+///   could not be read
+/// - -102: Blocked by the screening callback
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_attach_signature_and_submit(
+    handle: MidenHandle,
+    blob_ptr: *const u8,
+    blob_len: usize,
+    signature_ptr: *const u8,
+    signature_len: usize,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if blob_ptr.is_null() || signature_ptr.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    let blob = unsafe { std::slice::from_raw_parts(blob_ptr, blob_len) };
+    let unsigned = match UnsignedTx::from_blob(blob) {
+        Ok(v) => v,
+        Err(code) => return code,
+    };
+    let envelope_len =
+        unsigned.travel_rule_envelope.as_ref().map(|envelope| envelope.to_string().len());
+    if let Err(code) = check_out_capacity(json_out, json_out_len, submission_len(envelope_len)) {
+        return code;
+    }
+
+    let signature_bytes = unsafe { std::slice::from_raw_parts(signature_ptr, signature_len) };
+    let signature = match rpo_falcon512::Signature::read_from_bytes(signature_bytes) {
+        Ok(sig) => Signature::RpoFalcon512(sig),
+        Err(_) => return ERR_INVALID_PARAM,
+    };
+
+    let request = |reply| Request::SubmitSignedTx { unsigned, signature, reply };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
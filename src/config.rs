@@ -0,0 +1,247 @@
+//! Client configuration
+//!
+//! `wc_miden_create` takes paths and an endpoint only, and runs with fixed settings: a
+//! 10s RPC timeout, debug mode off and a current-thread runtime. `wc_miden_create_with_config`
+//! takes all settings as one JSON object instead:
+//!
+//! ```json
+//! {"keystore_path":"..","store_path":"..","rpc_endpoint":"https://rpc.testnet.miden.io:443",
+//!  "network_id":"testnet","timeout_ms":10000,"debug":false,"worker_threads":0,
+//!  "require_sync_for_create":false,"proving_memory_ceiling_mb":null,"in_memory":false,
+//!  "keystore_passphrase":null,"store_key":null,"compression":"gzip",
+//!  "max_message_bytes":4194304,"tenant_id":null}
+//! ```
+//!
+//! Only the two paths are required, and not even those with `in_memory`: an in-memory
+//! client ignores them and writes no files (see memory_store.rs).
+//! `network_id` (`testnet`, `devnet`, `localhost` or `localhost:<port>`) selects the node
+//! when `rpc_endpoint` is absent; an explicit endpoint wins and may itself be one of these
+//! aliases (see network.rs).
+//! `worker_threads` > 0 runs the worker on a multi-thread runtime with that many extra
+//! threads for network I/O and timers; requests are still handled one at a time on the
+//! worker thread. `require_sync_for_create` refuses wallet creation until the store has
+//! synced once (see create_preflight.rs). `proving_memory_ceiling_mb` refuses to prove a
+//! transaction when less memory than that is available (see proving.rs).
+//! `keystore_passphrase` encrypts the keys of the filesystem backend, and must be given
+//! for a keystore created with one (see encrypted_keystore.rs). `store_key` encrypts the
+//! SQLite store with SQLCipher, in builds with the `sqlcipher` feature (see
+//! encrypted_store.rs). `compression` (`gzip` or `none`) and `max_message_bytes` (1024 up
+//! to 4 MiB) set how node calls are compressed and the largest response message accepted
+//! (see node_proxy.rs). `tenant_id` (1-64 characters of `[A-Za-z0-9_-]`) opens one tenant
+//! of a store shared by several profiles (see tenants.rs).
+
+use std::{os::raw::c_char, path::PathBuf};
+
+use miden_client::rpc::Endpoint;
+
+use crate::{
+    encrypted_store, handles, memory_store,
+    network::{self, Network},
+    node_proxy::{self, MessageCompression},
+    parse_required_str, start_worker,
+    tenants::{self, TenantLease},
+    MidenHandle, ERR_INVALID_HANDLE, ERR_INVALID_PARAM,
+};
+
+/// RPC timeout of clients created without a config
+pub const DEFAULT_RPC_TIMEOUT_MS: u64 = 10_000;
+
+/// Upper bound of `timeout_ms`
+pub const MAX_RPC_TIMEOUT_MS: u64 = 600_000;
+
+/// Upper bound of `worker_threads`
+pub const MAX_WORKER_THREADS: usize = 16;
+
+/// Settings of a client handle
+pub(crate) struct ClientConfig {
+    pub keystore_path: PathBuf,
+    pub store_path: PathBuf,
+    pub endpoint: Endpoint,
+    /// Network the endpoint was chosen for (`Custom` for a URL)
+    pub network: Network,
+    pub timeout_ms: u64,
+    /// Client debug mode; also echoes the worker's log lines to stderr (see diagnostics.rs)
+    pub debug: bool,
+    /// Runtime worker threads (0 = current-thread runtime)
+    pub worker_threads: usize,
+    /// Refuse wallet creation until the store has synced once
+    pub require_sync_for_create: bool,
+    /// Memory (MB) a proof may need; None skips the check
+    pub proving_memory_ceiling_mb: Option<u64>,
+    /// Passphrase of an encrypted keystore; None keeps keys unencrypted
+    pub keystore_passphrase: Option<String>,
+    /// SQLCipher key of an encrypted store; None keeps the store unencrypted
+    pub store_key: Option<String>,
+    /// Compression of node calls
+    pub compression: MessageCompression,
+    /// Largest node response message accepted, after decompression
+    pub max_message_bytes: usize,
+    /// Tenant of a shared store; None opens the whole store
+    pub tenant_id: Option<String>,
+}
+
+impl ClientConfig {
+    /// Default settings for the given paths and endpoint
+    pub fn new(
+        keystore_path: PathBuf,
+        store_path: PathBuf,
+        endpoint: Endpoint,
+        network: Network,
+    ) -> Self {
+        Self {
+            keystore_path,
+            store_path,
+            endpoint,
+            network,
+            timeout_ms: DEFAULT_RPC_TIMEOUT_MS,
+            debug: false,
+            worker_threads: 0,
+            require_sync_for_create: false,
+            proving_memory_ceiling_mb: None,
+            keystore_passphrase: None,
+            store_key: None,
+            compression: MessageCompression::Gzip,
+            max_message_bytes: node_proxy::DEFAULT_MAX_MESSAGE_BYTES,
+            tenant_id: None,
+        }
+    }
+
+    /// Parse a config object (see the module docs)
+    fn from_json(json: &str) -> Result<Self, i32> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|_| ERR_INVALID_PARAM)?;
+        let path = |name: &str| match value[name].as_str() {
+            Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+            _ => Err(ERR_INVALID_PARAM),
+        };
+
+        let rpc_endpoint = value["rpc_endpoint"].as_str();
+        let (endpoint, network) = match (rpc_endpoint, value["network_id"].as_str()) {
+            (Some(endpoint), _) => network::resolve_endpoint(endpoint)?,
+            (None, None) => (Endpoint::testnet(), Network::Testnet),
+            // `network_id` takes aliases only, not URLs
+            (None, Some(alias)) => match network::resolve_endpoint(alias)? {
+                (_, Network::Custom) => return Err(ERR_INVALID_PARAM),
+                resolved => resolved,
+            },
+        };
+        let in_memory = match &value["in_memory"] {
+            serde_json::Value::Null => false,
+            in_memory => in_memory.as_bool().ok_or(ERR_INVALID_PARAM)?,
+        };
+        let (keystore_path, store_path) = if in_memory {
+            memory_store::paths()
+        } else {
+            (path("keystore_path")?, path("store_path")?)
+        };
+        let mut config = Self::new(keystore_path, store_path, endpoint, network);
+
+        if !value["timeout_ms"].is_null() {
+            config.timeout_ms = value["timeout_ms"].as_u64()
+                .filter(|timeout| (1..=MAX_RPC_TIMEOUT_MS).contains(timeout))
+                .ok_or(ERR_INVALID_PARAM)?;
+        }
+        if !value["debug"].is_null() {
+            config.debug = value["debug"].as_bool().ok_or(ERR_INVALID_PARAM)?;
+        }
+        if !value["worker_threads"].is_null() {
+            config.worker_threads = value["worker_threads"].as_u64()
+                .and_then(|threads| usize::try_from(threads).ok())
+                .filter(|threads| *threads <= MAX_WORKER_THREADS)
+                .ok_or(ERR_INVALID_PARAM)?;
+        }
+        if !value["require_sync_for_create"].is_null() {
+            config.require_sync_for_create =
+                value["require_sync_for_create"].as_bool().ok_or(ERR_INVALID_PARAM)?;
+        }
+        if !value["proving_memory_ceiling_mb"].is_null() {
+            let ceiling = value["proving_memory_ceiling_mb"].as_u64().filter(|mb| *mb > 0);
+            config.proving_memory_ceiling_mb = Some(ceiling.ok_or(ERR_INVALID_PARAM)?);
+        }
+        if !value["keystore_passphrase"].is_null() {
+            let passphrase = value["keystore_passphrase"].as_str().filter(|p| !p.is_empty());
+            config.keystore_passphrase = Some(passphrase.ok_or(ERR_INVALID_PARAM)?.to_string());
+        }
+        if !value["store_key"].is_null() {
+            let key = value["store_key"].as_str().filter(|key| !key.is_empty());
+            encrypted_store::check_key(&config.store_path)?;
+            config.store_key = Some(key.ok_or(ERR_INVALID_PARAM)?.to_string());
+        }
+        if !value["compression"].is_null() {
+            config.compression = match value["compression"].as_str() {
+                Some("gzip") => MessageCompression::Gzip,
+                Some("none") => MessageCompression::None,
+                _ => return Err(ERR_INVALID_PARAM),
+            };
+        }
+        if !value["max_message_bytes"].is_null() {
+            let limits = node_proxy::MIN_MAX_MESSAGE_BYTES..=node_proxy::DEFAULT_MAX_MESSAGE_BYTES;
+            config.max_message_bytes = value["max_message_bytes"].as_u64()
+                .and_then(|bytes| usize::try_from(bytes).ok())
+                .filter(|bytes| limits.contains(bytes))
+                .ok_or(ERR_INVALID_PARAM)?;
+        }
+        if !value["tenant_id"].is_null() {
+            let tenant_id = value["tenant_id"].as_str().ok_or(ERR_INVALID_PARAM)?;
+            tenants::validate_tenant_id(tenant_id)?;
+            // An in-memory store has no other tenants to share with
+            if in_memory {
+                return Err(ERR_INVALID_PARAM);
+            }
+            config.tenant_id = Some(tenant_id.to_string());
+        }
+        Ok(config)
+    }
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Create and initialize a Miden Client from a JSON config
+///
+/// Like `wc_miden_create`, with the RPC timeout, debug mode, runtime threads and node
+/// call compression configurable, and optionally scoped to one tenant of the store (see
+/// the module docs for the config object).
+///
+/// # Parameters
+/// - `config_json`: Config object (C string); `keystore_path` and `store_path` are required
+///   unless `in_memory` is true
+/// - `handle_out`: Output client handle
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters (malformed config, unknown network, out-of-range setting), or
+///   another tenant of the store is open
+/// - -2: Initialization failed
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_create_with_config(
+    config_json: *const c_char,
+    handle_out: *mut MidenHandle,
+) -> i32 {
+    if handle_out.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    let config = match parse_required_str(config_json).and_then(ClientConfig::from_json) {
+        Ok(config) => config,
+        Err(code) => return code,
+    };
+
+    // Taken before the worker opens the store, so the tenant cannot be deleted meanwhile
+    let lease = match &config.tenant_id {
+        Some(tenant_id) => match TenantLease::acquire(&config.store_path, tenant_id) {
+            Ok(lease) => Some(lease),
+            Err(code) => return code,
+        },
+        None => None,
+    };
+
+    match start_worker(config) {
+        Ok(mut handle) => {
+            handle.tenant = lease;
+            unsafe { *handle_out = handles::insert(handle) };
+            0
+        }
+        Err(_) => ERR_INVALID_HANDLE,
+    }
+}
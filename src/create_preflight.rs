@@ -0,0 +1,60 @@
+//! Account creation preflight
+//!
+//! Wallet creation used to fail with a generic -3 whenever the store refused the new
+//! account. Before a new wallet is added to the store (and before its key is saved), its
+//! derived ID is checked:
+//!
+//! - an ID already in the store fails with `ERR_ACCOUNT_EXISTS`, e.g. a seed used twice
+//! - an ID the node already knows fails with `ERR_ACCOUNT_EXISTS` too: the account was
+//!   created elsewhere with the same seed and key, and must be imported rather than
+//!   created. The node is asked on a best-effort basis: when it cannot be reached the
+//!   check is skipped, so wallets can still be created offline
+//! - with `require_sync_for_create` set in the client config, a store that has never
+//!   synced fails with `ERR_NOT_SYNCED`, for apps that want the chain state in place
+//!   before the first account exists
+//!
+//! Both checks apply to every wallet creation path (filesystem, memory and external
+//! signer keys, ephemeral accounts).
+
+use miden_client::rpc::NodeRpcClient;
+use miden_objects::account::AccountId;
+
+use crate::{
+    diagnostics, last_error, reconnect, MidenContext, ERR_ACCOUNT_EXISTS, ERR_ACCOUNT_OP,
+    ERR_NOT_SYNCED,
+};
+
+/// Check that a wallet with `account_id` may be created
+pub(crate) async fn check(context: &MidenContext, account_id: AccountId) -> Result<(), i32> {
+    if context.require_sync_for_create {
+        let height = context.client.get_sync_height().await
+            .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+        if height.as_u32() == 0 {
+            let message = "the store has not synced yet (require_sync_for_create is set)";
+            return Err(last_error::detail(ERR_NOT_SYNCED, message));
+        }
+    }
+
+    let local = context.client.get_account(account_id).await
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    if local.is_some() {
+        let message = format!("account {} is already in the store", account_id.to_hex());
+        return Err(last_error::detail(ERR_ACCOUNT_EXISTS, message));
+    }
+
+    match context.rpc.get_account_details(account_id).await {
+        Ok(_) => {
+            let message = format!(
+                "account {} already exists on chain; import it instead",
+                account_id.to_hex()
+            );
+            Err(last_error::detail(ERR_ACCOUNT_EXISTS, message))
+        }
+        Err(e) if reconnect::is_transport_error(&e) => {
+            diagnostics::log("create_preflight", format!("on-chain check skipped: {:?}", e));
+            Ok(())
+        }
+        // Unknown to the node
+        Err(_) => Ok(()),
+    }
+}
@@ -0,0 +1,390 @@
+//! Remote dApp sessions (WalletConnect-style)
+//!
+//! The Swift layer owns the transport (QR code, deep link, relay socket) and hands the
+//! decoded messages to this module. Protocol logic lives here:
+//!
+//! - `wc_dapp_connect` takes the dApp's connection payload, asks the approval callback,
+//!   and opens a session limited to the requested permissions and the accounts the user
+//!   chose to expose.
+//! - `wc_dapp_handle_request` checks every incoming request against the session's
+//!   permissions and accounts, asks the approval callback for anything that moves funds
+//!   or signs, and executes approved requests on the client (`sign_message` signs as
+//!   `wc_miden_sign_message` does, see message_signing.rs).
+//!
+//! Approval payloads are JSON: `{"kind":"connect"|"request","session_id",...,"dapp":{...}}`.
+//! Sessions belong to the handle they were opened on and live in memory until it is
+//! destroyed: a session ID approved on one handle (one tenant's wallet) is unknown to
+//! every other handle. The approval callback is process-wide, as it stands for the app's UI.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    ffi::c_void,
+    os::raw::c_char,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use miden_objects::account::AccountId;
+use once_cell::sync::Lazy;
+
+use crate::{
+    check_out_capacity, cold_wallet::TxSpec, get_handle, json,
+    message_signing::SIGNED_MESSAGE_MAX_BYTES, parse_required_str, request_blocking,
+    write_out_buffer, MidenHandle, Request, ERR_INVALID_HANDLE, ERR_INVALID_PARAM,
+    ERR_PERMISSION_DENIED, ERR_REJECTED,
+};
+
+/// Approval callback: (user_data, request_json_ptr, request_json_len) -> approved
+///
+/// Invoked synchronously on the thread calling into the dApp API; Swift typically blocks
+/// on a UI confirmation here, so never call the dApp API from the main thread.
+pub type DappApprovalCallback = extern "C" fn(*mut c_void, *const u8, usize) -> bool;
+
+const PERMISSION_VIEW_ACCOUNTS: &str = "view_accounts";
+const PERMISSION_SEND_TRANSACTION: &str = "send_transaction";
+const PERMISSION_SIGN_MESSAGE: &str = "sign_message";
+const KNOWN_PERMISSIONS: [&str; 3] = [PERMISSION_VIEW_ACCOUNTS, PERMISSION_SEND_TRANSACTION, PERMISSION_SIGN_MESSAGE];
+
+/// What an authorized dApp request does
+enum DappAction {
+    GetAccounts,
+    SendTransaction(AccountId, TxSpec),
+    SignMessage(AccountId, Vec<u8>),
+}
+
+struct DappSession {
+    dapp_id: String,
+    name: String,
+    url: String,
+    permissions: BTreeSet<String>,
+    accounts: Vec<AccountId>,
+}
+
+impl DappSession {
+    fn to_json(&self, session_id: u64) -> serde_json::Value {
+        serde_json::json!({
+            "session_id": session_id,
+            "dapp": { "dapp_id": self.dapp_id, "name": self.name, "url": self.url },
+            "permissions": self.permissions,
+            "accounts": self.accounts.iter().map(|id| id.to_hex()).collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Approver {
+    callback: DappApprovalCallback,
+    user_data: usize,
+}
+
+impl Approver {
+    fn approve(&self, request: &serde_json::Value) -> bool {
+        let bytes = json::prepare_output(request.to_string().as_bytes()).into_owned();
+        (self.callback)(self.user_data as *mut c_void, bytes.as_ptr(), bytes.len())
+    }
+}
+
+/// dApp sessions opened on one handle
+#[derive(Default)]
+pub(crate) struct DappSessions {
+    sessions: Mutex<HashMap<u64, DappSession>>,
+}
+
+static APPROVER: Lazy<Mutex<Option<Approver>>> = Lazy::new(|| Mutex::new(None));
+/// Session IDs are unique across handles, so an ID never names another handle's session
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+
+fn approver() -> Result<Approver, i32> {
+    APPROVER.lock().unwrap().ok_or(ERR_REJECTED)
+}
+
+fn string_field(value: &serde_json::Value, name: &str) -> Result<String, i32> {
+    value.get(name)
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .ok_or(ERR_INVALID_PARAM)
+}
+
+fn parse_accounts(json: &str) -> Result<Vec<AccountId>, i32> {
+    let ids: Vec<String> = serde_json::from_str(json).map_err(|_| ERR_INVALID_PARAM)?;
+    ids.iter()
+        .map(|id| AccountId::from_hex(id).map_err(|_| ERR_INVALID_PARAM))
+        .collect()
+}
+
+fn connect(sessions: &DappSessions, payload_json: &str, accounts_json: &str) -> Result<u64, i32> {
+    let payload: serde_json::Value = serde_json::from_str(payload_json).map_err(|_| ERR_INVALID_PARAM)?;
+
+    let permissions: BTreeSet<String> = payload.get("requested_permissions")
+        .and_then(|v| v.as_array())
+        .ok_or(ERR_INVALID_PARAM)?
+        .iter()
+        .map(|p| p.as_str().filter(|p| KNOWN_PERMISSIONS.contains(p)).map(str::to_string).ok_or(ERR_INVALID_PARAM))
+        .collect::<Result<_, _>>()?;
+
+    let session = DappSession {
+        dapp_id: string_field(&payload, "dapp_id")?,
+        name: string_field(&payload, "name").unwrap_or_default(),
+        url: string_field(&payload, "url").unwrap_or_default(),
+        permissions,
+        accounts: parse_accounts(accounts_json)?,
+    };
+
+    let session_id = NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed);
+    let mut approval = session.to_json(session_id);
+    approval["kind"] = "connect".into();
+    if !approver()?.approve(&approval) {
+        return Err(ERR_REJECTED);
+    }
+
+    sessions.sessions.lock().unwrap().insert(session_id, session);
+    Ok(session_id)
+}
+
+/// Validate a dApp request against its session
+///
+/// Returns the action to run and the session's JSON (for the approval prompt).
+fn authorize(
+    sessions: &DappSessions,
+    session_id: u64,
+    request: &serde_json::Value,
+) -> Result<(DappAction, serde_json::Value), i32> {
+    let sessions = sessions.sessions.lock().unwrap();
+    let session = sessions.get(&session_id).ok_or(ERR_INVALID_PARAM)?;
+    let method = string_field(request, "method")?;
+
+    let required = match method.as_str() {
+        "get_accounts" => PERMISSION_VIEW_ACCOUNTS,
+        "send_transaction" => PERMISSION_SEND_TRANSACTION,
+        "sign_message" => PERMISSION_SIGN_MESSAGE,
+        _ => return Err(ERR_INVALID_PARAM),
+    };
+    if !session.permissions.contains(required) {
+        return Err(ERR_PERMISSION_DENIED);
+    }
+
+    let exposed_account = || {
+        let account_id = AccountId::from_hex(&string_field(request, "account_id")?)
+            .map_err(|_| ERR_INVALID_PARAM)?;
+        if !session.accounts.contains(&account_id) {
+            return Err(ERR_PERMISSION_DENIED);
+        }
+        Ok(account_id)
+    };
+
+    let action = match method.as_str() {
+        "send_transaction" => {
+            let account_id = exposed_account()?;
+            let spec = TxSpec::from_json(&request.get("transaction").ok_or(ERR_INVALID_PARAM)?.to_string())?;
+            DappAction::SendTransaction(account_id, spec)
+        }
+        "sign_message" => {
+            let account_id = exposed_account()?;
+            let message = string_field(request, "message")?.into_bytes();
+            if !(1..=SIGNED_MESSAGE_MAX_BYTES).contains(&message.len()) {
+                return Err(ERR_INVALID_PARAM);
+            }
+            DappAction::SignMessage(account_id, message)
+        }
+        _ => DappAction::GetAccounts,
+    };
+
+    Ok((action, session.to_json(session_id)))
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Set the callback that approves dApp connections and requests
+///
+/// Pass the callback again to replace it. Without a callback every connection and
+/// request is rejected.
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_dapp_set_approval_callback(callback: DappApprovalCallback, user_data: *mut c_void) -> i32 {
+    *APPROVER.lock().unwrap() = Some(Approver { callback, user_data: user_data as usize });
+    0
+}
+
+/// Open a dApp session
+///
+/// # Parameters
+/// - `payload_json`: Connection payload `{"dapp_id","name","url","requested_permissions":[...]}`
+///   where permissions are `view_accounts`, `send_transaction` and `sign_message`
+/// - `accounts_json`: JSON array of account IDs the user chose to expose to the dApp
+/// - `session_id_out`: Receives the session ID, valid on this handle only
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid payload, unknown permission or invalid account ID
+/// - -2: Invalid handle
+/// - -100: Rejected by the approval callback (or no callback set)
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_dapp_connect(
+    handle: MidenHandle,
+    payload_json: *const c_char,
+    accounts_json: *const c_char,
+    session_id_out: *mut u64,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if session_id_out.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    let result = parse_required_str(payload_json).and_then(|payload| {
+        connect(&worker.dapp_sessions, payload, parse_required_str(accounts_json)?)
+    });
+
+    match result {
+        Ok(session_id) => {
+            unsafe { *session_id_out = session_id };
+            0
+        }
+        Err(code) => code,
+    }
+}
+
+/// Handle a request from a connected dApp (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Request JSON
+/// - `{"method":"get_accounts"}` → `["0x..", ...]`
+/// - `{"method":"send_transaction","account_id":"0x..","transaction":{...}}` →
+///   `{"tx_id":"0x..","travel_rule_envelope":{...}|null}`
+///   where `transaction` uses the `wc_miden_build_unsigned_tx` request format
+/// - `{"method":"sign_message","account_id":"0x..","message":"..."}` →
+///   `{"version":1,"account_id","digest","pub_key","signature"}` (the UTF-8 message, 1 byte
+///   to 64 KB, signed as by `wc_miden_sign_message`)
+///
+/// Transactions and signatures are executed only after the approval callback accepts them.
+/// For `send_transaction` the response buffer is checked first: a buffer smaller than the
+/// result fails with -7 (and the required length) before the user is asked to approve.
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Unknown session (or one opened on another handle) or malformed/unsupported request
+/// - -2: Invalid handle or worker closed
+/// - -3: The account has no key this client can sign with
+/// - -5: Account not in the store
+/// - -6: Transaction submission failed
+/// - -7: Output buffer too small
+/// - -100: Rejected by the approval callback
+/// - -101: Session lacks the permission, the account is not exposed to the dApp, or the
+///   recipient is not on the account's withdrawal whitelist
+/// - -102: Blocked by the screening callback
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_dapp_handle_request(
+    handle: MidenHandle,
+    session_id: u64,
+    request_json: *const c_char,
+    response_out: *mut u8,
+    response_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if response_out.is_null() || response_out_len.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    let request: serde_json::Value = match parse_required_str(request_json)
+        .and_then(|json| serde_json::from_str(json).map_err(|_| ERR_INVALID_PARAM))
+    {
+        Ok(v) => v,
+        Err(code) => return code,
+    };
+
+    let (action, session_json) = match authorize(&worker.dapp_sessions, session_id, &request) {
+        Ok(v) => v,
+        Err(code) => return code,
+    };
+
+    // Checked before the approval and the submission, which cannot be repeated safely
+    if let DappAction::SendTransaction(_, spec) = &action
+        && let Err(code) =
+            check_out_capacity(response_out, response_out_len, spec.submission_len())
+    {
+        return code;
+    }
+
+    if !matches!(action, DappAction::GetAccounts) {
+        let approval = serde_json::json!({
+            "kind": "request",
+            "session_id": session_id,
+            "dapp": session_json["dapp"],
+            "request": request,
+        });
+        match approver() {
+            Ok(approver) if approver.approve(&approval) => {}
+            _ => return ERR_REJECTED,
+        }
+    }
+
+    let response = match action {
+        DappAction::GetAccounts => Ok(session_json["accounts"].to_string().into_bytes()),
+        DappAction::SendTransaction(account_id, spec) => {
+            request_blocking(&worker, |reply| Request::SubmitTxSpec { account_id, spec, reply })
+        }
+        DappAction::SignMessage(account_id, message) => {
+            request_blocking(&worker, |reply| Request::SignMessage { account_id, message, reply })
+        }
+    };
+
+    match response {
+        Ok(response) => write_out_buffer(&response, response_out, response_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Close a dApp session of a handle
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Unknown session
+/// - -2: Invalid handle
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_dapp_disconnect(handle: MidenHandle, session_id: u64) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    match worker.dapp_sessions.sessions.lock().unwrap().remove(&session_id) {
+        Some(_) => 0,
+        None => ERR_INVALID_PARAM,
+    }
+}
+
+/// List the open dApp sessions of a handle
+///
+/// # Output JSON
+/// `[{"session_id","dapp":{"dapp_id","name","url"},"permissions":[...],"accounts":[...]}]`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_dapp_list_sessions(
+    handle: MidenHandle,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let sessions = worker.dapp_sessions.sessions.lock().unwrap();
+    let mut ids: Vec<_> = sessions.keys().copied().collect();
+    ids.sort_unstable();
+
+    let list: Vec<serde_json::Value> = ids.iter().map(|id| sessions[id].to_json(*id)).collect();
+    write_out_buffer(serde_json::Value::from(list).to_string().as_bytes(), json_out, json_out_len)
+}
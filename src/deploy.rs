@@ -0,0 +1,84 @@
+//! On-chain account deployment
+//!
+//! A wallet created locally is unknown to the node until its first transaction, so a
+//! freshly created public account cannot be looked up, imported elsewhere or used as a
+//! known recipient. `wc_miden_deploy_account` submits that first transaction on its own:
+//! an empty transaction, whose authentication increments the nonce of the new account
+//! and registers it with the node (its state for a public account, its commitment for a
+//! private one).
+//!
+//! Only accounts with the `new` status and a key in this store can be deployed; the
+//! account becomes `tracked` once the transaction is committed and synced.
+
+use std::{ffi::c_void, os::raw::c_char};
+
+use miden_client::transaction::TransactionRequestBuilder;
+use miden_objects::account::AccountId;
+
+use crate::{
+    cancel, get_account_status_impl, get_handle, last_error, parse_account_id, proving,
+    types::WcAccountStatus,
+    BytesCallback, MidenContext, MidenHandle, Reply, Request, ERR_ACCOUNT_OP, ERR_INVALID_HANDLE,
+    ERR_TX_SUBMIT,
+};
+
+/// Moves no assets and has no counterparty, so screening does not apply.
+pub(crate) async fn deploy_account_impl(
+    context: &mut MidenContext,
+    account_id: AccountId,
+) -> Result<String, i32> {
+    let status = get_account_status_impl(context, account_id).await?;
+    if status != WcAccountStatus::New {
+        let message = format!("account {} is already {}", account_id.to_hex(), status.as_str());
+        return Err(last_error::detail(ERR_ACCOUNT_OP, message));
+    }
+
+    let tx_request = TransactionRequestBuilder::new()
+        .build()
+        .map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))?;
+
+    proving::check_memory(context)?;
+    let tx_id = context.client.submit_new_transaction(account_id, tx_request).await
+        .map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))?;
+
+    Ok(tx_id.to_hex())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Deploy a new account on chain (async)
+///
+/// NOTE: Callback is invoked on worker thread, NOT main thread.
+///
+/// Submits the account's first transaction (see the module docs). The callback receives
+/// the transaction ID (hex string, not NUL-terminated; free with `wc_bytes_free`) once the
+/// transaction is submitted, or an error code:
+/// - -3: The account is not `new` (already deployed, or locked)
+/// - -5: The account is not in the store
+/// - -6: Proving or submission failed, e.g. no key for the account in this store
+///
+/// Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code
+/// (-1 invalid parameters, -2 invalid handle, -3 invalid account ID, -8 queue full).
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_deploy_account(
+    handle: MidenHandle,
+    account_id: *const c_char,
+    callback: BytesCallback,
+    user_data: *mut c_void,
+) -> i64 {
+    let Some(worker) = get_handle(handle) else {
+        return i64::from(ERR_INVALID_HANDLE);
+    };
+
+    let account_id = match parse_account_id(account_id) {
+        Ok((account_id, _)) => account_id,
+        Err(code) => return i64::from(code),
+    };
+
+    cancel::submit(&worker, Request::DeployAccount {
+        account_id,
+        reply: Reply::Callback { callback, user_data: user_data as usize },
+    })
+}
@@ -0,0 +1,252 @@
+//! Exchange deposit scanning
+//!
+//! Exchanges assign each user a note tag inside a namespace (the top `prefix_bits`
+//! bits of the tag). Deposit crediting pipelines page through committed input notes
+//! in that namespace, ordered by (block number, note ID), using an opaque cursor so
+//! that new deposits never shift earlier pages.
+//!
+//! Only notes the client has synced are visible: the exchange must track the user
+//! tags (or the account IDs they derive from) for the node to return them.
+//!
+//! The store cannot filter notes by tag or block, so the first page loads the committed
+//! notes once into an index sorted by (block number, note ID). Later pages seek into it
+//! with the cursor instead of loading the store again; the worker drops the index when it
+//! takes a request that may write to the store (see `Request::is_read_only`).
+
+use std::{ffi::c_void, os::raw::c_char};
+
+use miden_client::store::NoteFilter;
+
+use crate::{
+    cancel, get_handle, json, parse_optional_str, request_blocking, write_out_buffer,
+    BytesCallback, MidenContext, MidenHandle, Reply, Request,
+    ERR_INVALID_HANDLE, ERR_INVALID_PARAM, ERR_NOTE_OP,
+};
+
+/// Page size used when the caller passes `limit = 0`
+pub const DEPOSIT_SCAN_DEFAULT_LIMIT: u32 = 100;
+
+/// Maximum page size for a single deposit scan
+pub const DEPOSIT_SCAN_MAX_LIMIT: u32 = 1000;
+
+/// Parameters of a single deposit scan page
+pub(crate) struct DepositScan {
+    tag_prefix: u32,
+    prefix_bits: u8,
+    since_block: u32,
+    cursor: Option<(u32, String)>,
+    limit: usize,
+}
+
+impl DepositScan {
+    fn new(
+        tag_prefix: u32,
+        prefix_bits: u8,
+        since_block: u32,
+        cursor: *const c_char,
+        limit: u32,
+    ) -> Result<Self, i32> {
+        if prefix_bits > 32 || limit > DEPOSIT_SCAN_MAX_LIMIT {
+            return Err(ERR_INVALID_PARAM);
+        }
+        let cursor = parse_optional_str(cursor)?.map(parse_cursor).transpose()?;
+        let limit = if limit == 0 { DEPOSIT_SCAN_DEFAULT_LIMIT } else { limit };
+
+        Ok(Self {
+            tag_prefix,
+            prefix_bits,
+            since_block,
+            cursor,
+            limit: limit as usize,
+        })
+    }
+
+    fn matches_tag(&self, tag: u32) -> bool {
+        if self.prefix_bits == 0 {
+            return true;
+        }
+        let shift = 32 - u32::from(self.prefix_bits);
+        (tag >> shift) == (self.tag_prefix >> shift)
+    }
+
+    /// Position in `index` of the first deposit the page may contain
+    fn start(&self, index: &[json::DepositJson]) -> usize {
+        let since = index.partition_point(|deposit| deposit.block_num < self.since_block);
+        let after_cursor = match &self.cursor {
+            Some((block, note_id)) => index.partition_point(|deposit| {
+                (deposit.block_num, deposit.note_id.as_str()) <= (*block, note_id.as_str())
+            }),
+            None => 0,
+        };
+        since.max(after_cursor)
+    }
+}
+
+/// Committed input notes sorted by (block number, note ID), kept between pages
+#[derive(Default)]
+pub(crate) struct DepositIndex(Option<Vec<json::DepositJson>>);
+
+impl DepositIndex {
+    /// Forget the index (the store may have changed)
+    pub fn invalidate(&mut self) {
+        self.0 = None;
+    }
+}
+
+/// Every committed input note, sorted for paging (timestamps are filled in per page)
+async fn load_index(context: &MidenContext) -> Result<Vec<json::DepositJson>, i32> {
+    let notes = context.client.get_input_notes(NoteFilter::All).await
+        .map_err(|_| ERR_NOTE_OP)?;
+
+    let mut deposits: Vec<json::DepositJson> = notes
+        .iter()
+        .filter_map(|record| {
+            let metadata = record.metadata()?;
+            Some(json::DepositJson {
+                note_id: record.id().to_hex(),
+                tag: metadata.tag().as_u32(),
+                block_num: record.inclusion_proof()?.location().block_num().as_u32(),
+                timestamp: None,
+                sender: metadata.sender().to_hex(),
+                assets: json::AssetJson::fungible(record.assets().iter()),
+                is_consumed: record.is_consumed(),
+            })
+        })
+        .collect();
+
+    deposits.sort_by(|a, b| (a.block_num, &a.note_id).cmp(&(b.block_num, &b.note_id)));
+    Ok(deposits)
+}
+
+/// Cursor format: `<block_num>:<note_id_hex>` of the last deposit on the previous page
+fn parse_cursor(cursor: &str) -> Result<(u32, String), i32> {
+    let (block, note_id) = cursor.split_once(':').ok_or(ERR_INVALID_PARAM)?;
+    let block = block.parse::<u32>().map_err(|_| ERR_INVALID_PARAM)?;
+    Ok((block, note_id.to_string()))
+}
+
+pub(crate) async fn scan_deposits_impl(
+    context: &mut MidenContext,
+    scan: DepositScan,
+) -> Result<String, i32> {
+    if context.deposit_index.0.is_none() {
+        context.deposit_index.0 = Some(load_index(context).await?);
+    }
+    let index = context.deposit_index.0.as_deref().unwrap_or_default();
+
+    // Committed notes in the namespace after the cursor, one more than the page holds
+    let mut deposits: Vec<json::DepositJson> = index[scan.start(index)..]
+        .iter()
+        .filter(|deposit| scan.matches_tag(deposit.tag))
+        .take(scan.limit + 1)
+        .map(|deposit| json::DepositJson {
+            timestamp: context.block_times.timestamp(deposit.block_num),
+            ..deposit.clone()
+        })
+        .collect();
+
+    let has_more = deposits.len() > scan.limit;
+    deposits.truncate(scan.limit);
+
+    let next_cursor = match deposits.last() {
+        Some(last) if has_more => Some(format!("{}:{}", last.block_num, last.note_id)),
+        _ => None,
+    };
+
+    Ok(json::to_string(&json::DepositsPageJson {
+        count: deposits.len(),
+        deposits,
+        has_more,
+        next_cursor,
+    }))
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Scan committed incoming notes whose tag falls inside a namespace (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Reads the local store only; call `wc_miden_sync` first to pick up new deposits.
+///
+/// # Parameters
+/// - `tag_prefix`: Tag whose top `prefix_bits` bits define the namespace
+/// - `prefix_bits`: Number of significant prefix bits (0-32, 0 matches every tag)
+/// - `since_block`: Ignore notes included before this block
+/// - `cursor`: `next_cursor` from the previous page (C string, NULL or empty for the first page)
+/// - `limit`: Page size (0 uses the default of 100, max 1000)
+/// - `json_out` / `json_out_len`: Output buffer; capacity in, written length out
+///
+/// # Output JSON
+/// `{"deposits":[{"note_id","tag","block_num","timestamp","sender","assets","is_consumed"}],"count","has_more","next_cursor"}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or malformed cursor
+/// - -2: Invalid handle or worker closed
+/// - -4: Note store query failed
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub extern "C" fn wc_miden_scan_deposits(
+    handle: MidenHandle,
+    tag_prefix: u32,
+    prefix_bits: u8,
+    since_block: u32,
+    cursor: *const c_char,
+    limit: u32,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if json_out_len.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    let scan = match DepositScan::new(tag_prefix, prefix_bits, since_block, cursor, limit) {
+        Ok(scan) => scan,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::ScanDeposits { scan, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Scan committed incoming notes whose tag falls inside a namespace (async)
+///
+/// NOTE: Callback is invoked on worker thread, NOT main thread.
+///
+/// Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub extern "C" fn wc_miden_scan_deposits_async(
+    handle: MidenHandle,
+    tag_prefix: u32,
+    prefix_bits: u8,
+    since_block: u32,
+    cursor: *const c_char,
+    limit: u32,
+    callback: BytesCallback,
+    user_data: *mut c_void,
+) -> i64 {
+    let Some(worker) = get_handle(handle) else {
+        return i64::from(ERR_INVALID_HANDLE);
+    };
+
+    let scan = match DepositScan::new(tag_prefix, prefix_bits, since_block, cursor, limit) {
+        Ok(scan) => scan,
+        Err(code) => return i64::from(code),
+    };
+
+    cancel::submit(&worker, Request::ScanDeposits {
+        scan,
+        reply: Reply::Callback { callback, user_data: user_data as usize },
+    })
+}
@@ -0,0 +1,193 @@
+//! Hierarchical deterministic wallets
+//!
+//! A user backs up one master seed (e.g. the 64-byte seed of a mnemonic) instead of one
+//! seed per wallet. `wc_miden_create_wallet_derived` derives the wallet at an index:
+//!
+//! - account seed: RPO hash of `miden-hd:v1:seed:` ‖ master seed ‖ index (u32 LE)
+//! - Falcon key: generated from an RNG seeded with the RPO hash of
+//!   `miden-hd:v1:key:` ‖ master seed ‖ index
+//!
+//! The same master seed, index and storage mode always give the same wallet. Recovery
+//! walks the indices in order from 0: a public wallet the node already knows is restored
+//! (its state imported from the node and its key saved) instead of failing with
+//! `ERR_ACCOUNT_EXISTS`, and the first index that creates a new wallet ends the walk. The
+//! node only holds the commitment of a private wallet, so those cannot be restored from
+//! the master seed alone; restore their store from a backup.
+//!
+//! The domain differs from bulk provisioning (see provisioning.rs), so a master seed used
+//! as a derivation base gives different wallets.
+
+use miden_client::auth::AuthSecretKey;
+use miden_lib::account::auth::AuthRpoFalcon512;
+use miden_objects::{
+    account::{AccountComponent, AccountId, AccountStorageMode},
+    crypto::{dsa::rpo_falcon512, hash::rpo::Rpo256},
+    utils::Serializable,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    check_out_capacity, create_preflight, get_handle, hex_placeholder,
+    keystore::KeyBackend,
+    last_error, output_len, parse_wallet_storage_mode, request_blocking,
+    wallet_core::{self, CoreError},
+    write_out_buffer, MidenContext, MidenHandle, Request, ACCOUNT_ID_HEX_LEN, ERR_ACCOUNT_EXISTS,
+    ERR_ACCOUNT_OP, ERR_INVALID_HANDLE, ERR_INVALID_PARAM,
+};
+
+/// Shortest accepted master seed, in bytes
+pub const HD_MASTER_SEED_MIN_LEN: usize = 16;
+/// Longest accepted master seed, in bytes
+pub const HD_MASTER_SEED_MAX_LEN: usize = 64;
+
+/// 32 bytes derived from the master seed for one wallet index
+fn derive(purpose: &str, master_seed: &[u8], index: u32) -> [u8; 32] {
+    let mut message = format!("miden-hd:v1:{}:", purpose).into_bytes();
+    message.extend_from_slice(master_seed);
+    message.extend_from_slice(&index.to_le_bytes());
+
+    let mut derived = [0u8; 32];
+    derived.copy_from_slice(&Rpo256::hash(&message).to_bytes());
+    derived
+}
+
+/// Import a derived wallet the node already knows and save its key
+async fn restore(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    key: &AuthSecretKey,
+) -> Result<(), i32> {
+    let local = context.client.get_account(account_id).await
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    if local.is_some() {
+        let message = format!("account {} is already in the store", account_id.to_hex());
+        return Err(last_error::detail(ERR_ACCOUNT_EXISTS, message));
+    }
+    if !account_id.is_public() {
+        let message = format!(
+            "private account {} exists on chain; restore its store from a backup",
+            account_id.to_hex()
+        );
+        return Err(last_error::detail(ERR_ACCOUNT_EXISTS, message));
+    }
+
+    context.client.import_account_by_id(account_id).await
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    context.keystore.add_key(key, KeyBackend::Filesystem)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    context.keystore.record_account(account_id, KeyBackend::Filesystem)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))
+}
+
+pub(crate) async fn create_wallet_derived_impl(
+    context: &mut MidenContext,
+    master_seed: Vec<u8>,
+    index: u32,
+    storage_mode: AccountStorageMode,
+) -> Result<String, i32> {
+    let mut rng = StdRng::from_seed(derive("key", &master_seed, index));
+    let key = AuthSecretKey::RpoFalcon512(rpo_falcon512::SecretKey::with_rng(&mut rng));
+    let auth_component: AccountComponent =
+        AuthRpoFalcon512::new(key.public_key().to_commitment()).into();
+
+    let seed = derive("seed", &master_seed, index);
+    let account = wallet_core::build_wallet_account(seed, auth_component, storage_mode)
+        .map_err(CoreError::into_code)?;
+    let account_id = account.id();
+
+    let restored = match create_preflight::check(context, account_id).await {
+        Ok(()) => false,
+        Err(ERR_ACCOUNT_EXISTS) => {
+            restore(context, account_id, &key).await?;
+            true
+        }
+        Err(code) => return Err(code),
+    };
+
+    if !restored {
+        context.keystore.add_key(&key, KeyBackend::Filesystem)
+            .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+        wallet_core::add_account(&mut context.client, &account).await
+            .map_err(CoreError::into_code)?;
+        context.keystore.record_account(account_id, KeyBackend::Filesystem)
+            .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    }
+
+    Ok(serde_json::json!({
+        "account_id": account_id.to_hex(),
+        "index": index,
+        "restored": restored,
+    })
+    .to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Create (or restore) the wallet at an index of a master seed (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Derives the wallet's account seed and key from the master seed and index (see the
+/// module docs). A public wallet that already exists on chain is restored into this
+/// store; `restored` tells the two cases apart, so a recovery loop stops at the first
+/// index that returns `restored: false`.
+///
+/// # Parameters
+/// - `master_seed_ptr` / `master_seed_len`: Master seed (16-64 bytes)
+/// - `index`: Wallet index
+/// - `storage_mode`: `WcStorageMode` value (0 = public, 1 = private)
+/// - `json_out` / `json_out_len`: Output buffer for `{"account_id","index","restored"}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or `WcStorageMode_Network`
+/// - -2: Invalid handle or worker closed
+/// - -3: Account creation or restore failed
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+/// - -105: The wallet is already in the store, or is a private wallet known on chain
+/// - -106: The store has not synced yet and `require_sync_for_create` is set
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_create_wallet_derived(
+    handle: MidenHandle,
+    master_seed_ptr: *const u8,
+    master_seed_len: usize,
+    index: u32,
+    storage_mode: u32,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if master_seed_ptr.is_null()
+        || !(HD_MASTER_SEED_MIN_LEN..=HD_MASTER_SEED_MAX_LEN).contains(&master_seed_len)
+    {
+        return ERR_INVALID_PARAM;
+    }
+    let result = serde_json::json!({
+        "account_id": hex_placeholder(ACCOUNT_ID_HEX_LEN),
+        "index": index,
+        "restored": false,
+    });
+    if let Err(code) = check_out_capacity(json_out, json_out_len, output_len(&result)) {
+        return code;
+    }
+    let master_seed =
+        unsafe { std::slice::from_raw_parts(master_seed_ptr, master_seed_len) }.to_vec();
+    let storage_mode = match parse_wallet_storage_mode(storage_mode) {
+        Ok(storage_mode) => storage_mode,
+        Err(code) => return code,
+    };
+
+    let request =
+        |reply| Request::CreateWalletDerived { master_seed, index, storage_mode, reply };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,311 @@
+//! Diagnostics: recent logs, request metrics, error history and support bundles
+//!
+//! The worker records every request it handles (count, failures, cumulative duration)
+//! and every error code it returns; log lines are kept in a bounded in-memory buffer and
+//! echoed to stderr only by the worker of a handle created with `"debug": true` (see
+//! config.rs), so release apps do not write wallet activity to the system log.
+//! `wc_miden_export_diagnostics` packs all of this,
+//! together with store schema information and the sync height, into a zip file the user
+//! can attach to a bug report.
+//!
+//! Key material is never included: the keystore directory is not read at all.
+
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, VecDeque},
+    io::Write,
+    os::raw::c_char,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    clock, get_handle, last_error, parse_required_str, request_blocking, MidenContext, MidenHandle,
+    Request, ERR_FILE_WRITE, ERR_INVALID_HANDLE, ERR_INVALID_PARAM, ERR_LOOKUP,
+};
+
+/// Number of log lines kept in memory
+pub const DIAGNOSTICS_LOG_CAPACITY: usize = 500;
+
+/// Number of error records kept in memory
+pub const DIAGNOSTICS_ERROR_CAPACITY: usize = 100;
+
+/// Redaction: include everything except key material
+pub const REDACT_NONE: u32 = 0;
+/// Redaction: replace account IDs, note IDs and other hex identifiers
+pub const REDACT_IDENTIFIERS: u32 = 1;
+/// Redaction: identifiers redacted and log lines omitted entirely
+pub const REDACT_STRICT: u32 = 2;
+
+#[derive(Default)]
+struct OperationMetrics {
+    count: u64,
+    errors: u64,
+    total_duration: Duration,
+}
+
+struct ErrorRecord {
+    timestamp: u64,
+    operation: &'static str,
+    code: i32,
+}
+
+#[derive(Default)]
+struct DiagnosticsState {
+    logs: VecDeque<String>,
+    errors: VecDeque<ErrorRecord>,
+    metrics: BTreeMap<&'static str, OperationMetrics>,
+    /// Node calls delayed by the RPC rate limiter, and the total delay
+    throttled_calls: u64,
+    throttle_wait: Duration,
+    /// Operation the worker is currently handling (used to attribute errors)
+    current_operation: &'static str,
+}
+
+static STATE: Lazy<Mutex<DiagnosticsState>> = Lazy::new(|| Mutex::new(DiagnosticsState::default()));
+
+thread_local! {
+    /// Whether log lines written on this thread also go to stderr (debug handles only)
+    static ECHO_TO_STDERR: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Set on the worker thread whether its log lines are echoed to stderr
+pub(crate) fn install(debug: bool) {
+    ECHO_TO_STDERR.with(|echo| echo.set(debug));
+}
+
+fn push_bounded<T>(queue: &mut VecDeque<T>, item: T, capacity: usize) {
+    if queue.len() == capacity {
+        queue.pop_front();
+    }
+    queue.push_back(item);
+}
+
+/// Keep a log line for diagnostics bundles (and write it to stderr in debug mode)
+pub(crate) fn log(target: &str, message: impl std::fmt::Display) {
+    let line = format!("[{}] {}", target, message);
+    if ECHO_TO_STDERR.with(Cell::get) {
+        eprintln!("{}", line);
+    }
+
+    let line = format!("{} {}", clock::now_unix(), line);
+    push_bounded(&mut STATE.lock().unwrap().logs, line, DIAGNOSTICS_LOG_CAPACITY);
+}
+
+/// Mark the start of a worker request
+pub(crate) fn begin_operation(operation: &'static str) {
+    STATE.lock().unwrap().current_operation = operation;
+}
+
+/// Mark the end of a worker request
+pub(crate) fn end_operation(operation: &'static str, elapsed: Duration) {
+    let mut state = STATE.lock().unwrap();
+    let metrics = state.metrics.entry(operation).or_default();
+    metrics.count += 1;
+    metrics.total_duration += elapsed;
+}
+
+/// Record an error code returned by the current operation
+pub(crate) fn record_error(code: i32) {
+    let mut state = STATE.lock().unwrap();
+    let operation = state.current_operation;
+    state.metrics.entry(operation).or_default().errors += 1;
+    let record = ErrorRecord { timestamp: clock::now_unix(), operation, code };
+    push_bounded(&mut state.errors, record, DIAGNOSTICS_ERROR_CAPACITY);
+    drop(state);
+    last_error::store(operation, code);
+}
+
+/// Record a node call delayed by the RPC rate limiter
+pub(crate) fn record_throttle(wait: Duration) {
+    let mut state = STATE.lock().unwrap();
+    state.throttled_calls += 1;
+    state.throttle_wait += wait;
+}
+
+/// Record the error of a result, if any
+pub(crate) fn note_result<T>(result: &Result<T, i32>) {
+    if let Err(code) = result {
+        record_error(*code);
+    }
+}
+
+/// Replace `0x`-prefixed hex identifiers with a placeholder
+fn redact_identifiers(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(pos) = rest.find("0x") {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 2..];
+        let hex_len = after.bytes().take_while(u8::is_ascii_hexdigit).count();
+        if hex_len >= 8 {
+            out.push_str("0x<redacted>");
+        } else {
+            out.push_str(&rest[pos..pos + 2 + hex_len]);
+        }
+        rest = &after[hex_len..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Table names, their SQL and the schema version of the SQLite store
+fn store_schema(store_path: &Path) -> serde_json::Value {
+    let schema = || -> rusqlite::Result<serde_json::Value> {
+        let conn = rusqlite::Connection::open_with_flags(store_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let user_version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+        let mut stmt = conn.prepare("SELECT name, sql FROM sqlite_master WHERE type = 'table' ORDER BY name")?;
+        let tables = stmt
+            .query_map([], |row| {
+                let name: String = row.get(0)?;
+                let sql: Option<String> = row.get(1)?;
+                Ok(serde_json::json!({ "name": name, "sql": sql }))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(serde_json::json!({ "user_version": user_version, "tables": tables }))
+    };
+
+    schema().unwrap_or_else(|e| serde_json::json!({ "error": e.to_string() }))
+}
+
+pub(crate) async fn export_diagnostics_impl(
+    context: &MidenContext,
+    out_path: PathBuf,
+    redact_level: u32,
+) -> Result<String, i32> {
+    let sync_height = clock::current_block(context).await.ok();
+    let accounts: Vec<String> = context.client.get_account_headers().await
+        .map_err(|_| ERR_LOOKUP)?
+        .iter()
+        .map(|(header, _status)| header.id().to_hex())
+        .collect();
+
+    let redact = |text: String| {
+        if redact_level == REDACT_NONE { text } else { redact_identifiers(&text) }
+    };
+
+    let (logs, errors, metrics) = {
+        let state = STATE.lock().unwrap();
+
+        let logs = if redact_level >= REDACT_STRICT {
+            String::new()
+        } else {
+            state.logs.iter().map(|line| redact(line.clone())).collect::<Vec<_>>().join("\n")
+        };
+        let errors: Vec<serde_json::Value> = state.errors
+            .iter()
+            .map(|e| serde_json::json!({ "timestamp": e.timestamp, "operation": e.operation, "code": e.code }))
+            .collect();
+        let mut metrics: serde_json::Map<String, serde_json::Value> = state.metrics
+            .iter()
+            .map(|(operation, m)| {
+                (operation.to_string(), serde_json::json!({
+                    "count": m.count,
+                    "errors": m.errors,
+                    "total_ms": m.total_duration.as_millis() as u64,
+                }))
+            })
+            .collect();
+        metrics.insert("rpc_throttle".to_string(), serde_json::json!({
+            "throttled_calls": state.throttled_calls,
+            "total_wait_ms": state.throttle_wait.as_millis() as u64,
+        }));
+        metrics.insert("rpc_methods".to_string(), context.rpc_metrics.snapshot());
+
+        (logs, errors, metrics)
+    };
+
+    let store = serde_json::json!({
+        "schema": store_schema(&context.database_path),
+        "sync_height": sync_height,
+        "account_count": accounts.len(),
+        "accounts": if redact_level >= REDACT_IDENTIFIERS { Vec::new() } else { accounts },
+    });
+    let manifest = serde_json::json!({
+        "library_version": env!("CARGO_PKG_VERSION"),
+        "created_at": clock::now_unix(),
+        "redact_level": redact_level,
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+    });
+
+    let files: [(&str, String); 5] = [
+        ("manifest.json", manifest.to_string()),
+        ("store.json", store.to_string()),
+        ("metrics.json", serde_json::Value::Object(metrics).to_string()),
+        ("errors.json", serde_json::Value::from(errors).to_string()),
+        ("logs.txt", logs),
+    ];
+
+    let write_zip = || -> zip::result::ZipResult<()> {
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&out_path)?);
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+        for (name, contents) in &files {
+            zip.start_file(*name, options)?;
+            zip.write_all(contents.as_bytes())?;
+        }
+        zip.finish()?;
+        Ok(())
+    };
+
+    write_zip().map_err(|e| {
+        log("diagnostics", format!("failed to write bundle: {:?}", e));
+        ERR_FILE_WRITE
+    })?;
+
+    Ok(out_path.to_string_lossy().into_owned())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Write a diagnostics bundle (zip) for support tickets (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// The bundle contains recent logs, per-operation metrics, error history, the store
+/// schema and version, and the sync height. Keys are never included.
+///
+/// # Parameters
+/// - `out_path`: Destination file path of the zip (overwritten if present)
+/// - `redact_level`: 0 = no redaction, 1 = redact identifiers, 2 = redact identifiers and omit logs
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -5: Account lookup failed
+/// - -10: The file could not be written
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_export_diagnostics(
+    handle: MidenHandle,
+    out_path: *const c_char,
+    redact_level: u32,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if redact_level > REDACT_STRICT {
+        return ERR_INVALID_PARAM;
+    }
+
+    let out_path = match parse_required_str(out_path) {
+        Ok(path) => PathBuf::from(path),
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::ExportDiagnostics { out_path, redact_level, reply }) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,110 @@
+//! Asynchronous client disposal
+//!
+//! `wc_miden_destroy` joins the worker thread, so the caller blocks until the running
+//! request finishes: a sync in flight during app teardown stalls the main thread.
+//! `wc_miden_destroy_async` unregisters the handle at once (later calls return -2) and
+//! hands the worker to a process-wide disposal thread:
+//!
+//! - the worker's queue is closed instead of being sent `Shutdown`, so the requests already
+//!   queued still run and their callbacks are invoked; new requests fail with -2
+//! - once the queue is drained the worker drops the context (store connection, keystore)
+//!   inside its runtime, and the disposal thread joins it and runs the completion callback
+//!
+//! Disposals run one after the other in the order they were requested, so a store that is
+//! reopened after its completion callback is never open twice.
+
+use std::{
+    ffi::c_void,
+    sync::{mpsc, Arc, Mutex},
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{diagnostics, handles, MidenHandle, MidenWorkerHandle};
+
+/// Completion callback of an asynchronous destroy: (user_data)
+pub type WcDestroyCallback = Option<extern "C" fn(*mut c_void)>;
+
+struct Disposal {
+    worker: Arc<MidenWorkerHandle>,
+    callback: WcDestroyCallback,
+    user_data: usize,
+}
+
+/// Queue of the disposal thread (started on first use)
+static QUEUE: Lazy<Mutex<mpsc::Sender<Disposal>>> = Lazy::new(|| {
+    let (tx, rx) = mpsc::channel::<Disposal>();
+    std::thread::Builder::new()
+        .name("miden-dispose".to_string())
+        .spawn(move || {
+            for disposal in rx {
+                dispose(disposal);
+            }
+        })
+        .expect("failed to start the disposal thread");
+    Mutex::new(tx)
+});
+
+fn dispose(disposal: Disposal) {
+    let Disposal { worker, callback, user_data } = disposal;
+
+    // Calls that resolved the handle before it was unregistered may still hold the sender
+    // lock; taking it waits for them to queue their request
+    drop(worker.sender.write().unwrap().take());
+    let worker_thread = worker.worker_thread.lock().unwrap().take();
+    if let Some(Err(_)) = worker_thread.map(|worker_thread| worker_thread.join()) {
+        diagnostics::log("dispose", "worker thread panicked during shutdown");
+    }
+    // Release this reference (and with the last one the tenant lease) before reporting completion
+    drop(worker);
+
+    if let Some(callback) = callback {
+        callback(user_data as *mut c_void);
+    }
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Destroy client without blocking (async)
+///
+/// Safe to call from any thread, including the main thread. The handle is set to 0 and
+/// becomes invalid immediately; requests already queued run to completion (their
+/// callbacks are invoked) before the store is closed. See the module docs.
+///
+/// NOTE: The completion callback is invoked on the disposal thread, NOT main thread. It
+/// runs exactly once, even when the handle was already destroyed.
+///
+/// # Parameters
+/// - `handle_ptr`: Pointer to the handle (will be set to 0)
+/// - `callback`: Called once the store is closed (NULL = no notification)
+/// - `user_data`: Passed to the callback
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_destroy_async(
+    handle_ptr: *mut MidenHandle,
+    callback: WcDestroyCallback,
+    user_data: *mut c_void,
+) {
+    let worker = if handle_ptr.is_null() {
+        None
+    } else {
+        let handle = unsafe { *handle_ptr };
+        unsafe { *handle_ptr = 0 };
+        handles::remove(handle)
+    };
+
+    let Some(worker) = worker else {
+        // Nothing to close: report completion right away
+        if let Some(callback) = callback {
+            callback(user_data);
+        }
+        return;
+    };
+
+    let disposal = Disposal { worker, callback, user_data: user_data as usize };
+    if let Err(mpsc::SendError(disposal)) = QUEUE.lock().unwrap().send(disposal) {
+        // Disposal thread gone (it never exits while the process runs): dispose here
+        dispose(disposal);
+    }
+}
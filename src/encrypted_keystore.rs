@@ -0,0 +1,503 @@
+//! Passphrase-encrypted keystore
+//!
+//! `miden-client`'s filesystem keystore keeps each secret key as a plain hex file. With
+//! `keystore_passphrase` in the client config (see config.rs) the filesystem backend
+//! encrypts them instead:
+//!
+//! - `keystore.json`: `{"version":2,"salt","kdf","check"}`, the salt and Argon2id
+//!   parameters of the passphrase (see passphrase.rs) and a value sealed with the derived
+//!   key, so a wrong passphrase fails client creation rather than the first signature.
+//!   Version 1 files (`"rounds"` of salted SHA3-256 instead of `kdf`) still open; rekeying
+//!   such a keystore, even to the same passphrase, rewrites it as version 2
+//! - `<public key commitment hex>.key` per key: nonce ‖ ChaCha20-Poly1305 ciphertext of the
+//!   serialized key (hex), with the commitment as authenticated data
+//!
+//! The encryption key is derived once, when the client is created. A keystore is either
+//! plain or encrypted: creating a client with a passphrase for a plain keystore that holds
+//! keys, or without one for an encrypted keystore, fails.
+//!
+//! `wc_miden_rekey_keystore` changes the passphrase, or migrates a keystore between plain
+//! and encrypted. All keys are written under the new passphrase to a staging directory
+//! next to the keystore (`<keystore>.rekey`), which is marked complete and swapped in with
+//! renames; signer bindings, account backends and subdirectories (tenant keystores,
+//! travel-rule keys) move over unchanged. A rekey interrupted before the marker is
+//! discarded, and one interrupted after it is completed the next time a client opens the
+//! keystore, so keys are never split between two passphrases. Only the filesystem backend
+//! is affected: memory keys and external signers are left alone, and travel-rule keys stay
+//! unencrypted.
+//!
+//! Restoring a backup (see store_backup.rs) swaps the keystore through the same staging
+//! directory.
+
+use std::{
+    fs,
+    os::raw::c_char,
+    path::{Path, PathBuf},
+};
+
+use chacha20poly1305::{
+    aead::{Aead, Payload},
+    ChaCha20Poly1305, Nonce,
+};
+use miden_client::{auth::AuthSecretKey, keystore::FilesystemKeyStore};
+use miden_objects::{
+    utils::{Deserializable, Serializable},
+    Word,
+};
+use rand::RngCore;
+
+use crate::{
+    get_handle,
+    keystore::{ACCOUNT_BACKENDS_FILE, EXTERNAL_SIGNERS_FILE, RETIRED_KEYS_FILE},
+    last_error, parse_optional_str,
+    passphrase::{Kdf, KdfParams},
+    request_blocking, MidenContext, MidenHandle, MidenKeyStore, Request, ERR_ACCOUNT_OP,
+    ERR_INVALID_HANDLE, ERR_PERMISSION_DENIED,
+};
+
+/// Version tag of `keystore.json`
+pub const KEYSTORE_FORMAT_VERSION: u32 = 2;
+
+/// Version tag of `keystore.json` files keyed with iterated SHA3-256, still opened
+const LEGACY_KEYSTORE_FORMAT_VERSION: u32 = 1;
+
+/// File in the keystore directory holding the passphrase parameters
+const KEYSTORE_FILE: &str = "keystore.json";
+
+/// Plaintext of the `check` value
+const KEYSTORE_CHECK: &[u8] = b"miden-keystore";
+
+/// Extension of encrypted key files
+const KEY_FILE_EXTENSION: &str = "key";
+
+/// Written to the staging directory once it holds every key
+const REKEY_COMPLETE_MARKER: &str = ".rekey-complete";
+
+/// Marker contents of a staging directory that already holds everything (a restored backup,
+/// see store_backup.rs): nothing is carried over from the keystore it replaces
+const REPLACE_MARKER: &[u8] = b"replace";
+
+/// Whether the keystore in `dir` is encrypted
+pub(crate) fn is_encrypted(dir: &Path) -> bool {
+    dir.join(KEYSTORE_FILE).exists()
+}
+
+/// Sibling of the keystore directory, e.g. `keys.rekey` for `keys`
+fn sibling(dir: &Path, suffix: &str) -> PathBuf {
+    let mut name = dir.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    dir.with_file_name(name)
+}
+
+fn keystore_cipher(kdf: Kdf, salt: &[u8; 16], passphrase: &str) -> ChaCha20Poly1305 {
+    let domain = match kdf {
+        Kdf::Argon2id(_) => "miden-keystore:v2:",
+        Kdf::LegacySha3 { .. } => "miden-keystore:v1:",
+    };
+    kdf.cipher(domain, salt, passphrase)
+}
+
+/// Nonce ‖ ciphertext of `plaintext`
+fn seal(cipher: &ChaCha20Poly1305, plaintext: &[u8], aad: &str) -> Result<Vec<u8>, String> {
+    let mut nonce = [0u8; 12];
+    rand::rng().fill_bytes(&mut nonce);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext, aad: aad.as_bytes() })
+        .map_err(|_| "encryption failed".to_string())?;
+    Ok([nonce.as_slice(), &ciphertext].concat())
+}
+
+fn unseal(cipher: &ChaCha20Poly1305, sealed: &[u8], aad: &str) -> Option<Vec<u8>> {
+    if sealed.len() < 12 {
+        return None;
+    }
+    let (nonce, ciphertext) = sealed.split_at(12);
+    cipher
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: aad.as_bytes() })
+        .ok()
+}
+
+/// Keys of a plain keystore directory (files holding a hex-serialized key)
+fn plain_keys(dir: &Path) -> Result<Vec<AuthSecretKey>, String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut keys = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if !path.is_file() {
+            continue;
+        }
+        let key = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| hex::decode(contents.trim()).ok())
+            .and_then(|bytes| AuthSecretKey::read_from_bytes(&bytes).ok());
+        keys.extend(key);
+    }
+    Ok(keys)
+}
+
+/// Salt, key derivation and check value of a keystore
+type Parameters = ([u8; 16], Kdf, Vec<u8>);
+
+/// Salt, key derivation and check value from `keystore.json` in `dir` (None when there is
+/// none)
+fn read_parameters(dir: &Path) -> Result<Option<Parameters>, String> {
+    let contents = match fs::read_to_string(dir.join(KEYSTORE_FILE)) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let value: serde_json::Value =
+        serde_json::from_str(&contents).map_err(|_| "malformed keystore.json".to_string())?;
+    let kdf = match value["version"].as_u64().and_then(|version| u32::try_from(version).ok()) {
+        Some(KEYSTORE_FORMAT_VERSION) => KdfParams::from_json(&value["kdf"]).map(Kdf::Argon2id),
+        Some(LEGACY_KEYSTORE_FORMAT_VERSION) => Kdf::legacy(&value["rounds"]),
+        _ => return Err("unsupported keystore.json version".into()),
+    }
+    .ok_or("malformed keystore.json")?;
+    let hex_field = |name: &str| -> Option<Vec<u8>> { hex::decode(value[name].as_str()?).ok() };
+    let salt: [u8; 16] = hex_field("salt")
+        .and_then(|salt| salt.try_into().ok())
+        .ok_or("malformed keystore.json")?;
+    let check = hex_field("check").ok_or("malformed keystore.json")?;
+    Ok(Some((salt, kdf, check)))
+}
+
+/// Filesystem backend with keys sealed under a passphrase
+pub(crate) struct EncryptedKeyStore {
+    dir: PathBuf,
+    salt: [u8; 16],
+    kdf: Kdf,
+    /// Sealed `KEYSTORE_CHECK`, as in `keystore.json`
+    check: Vec<u8>,
+    cipher: ChaCha20Poly1305,
+}
+
+impl EncryptedKeyStore {
+    /// Open the keystore in `dir`, initializing it when it has no `keystore.json` yet
+    fn open(dir: &Path, passphrase: &str) -> Result<Self, String> {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        let Some((salt, kdf, check)) = read_parameters(dir)? else {
+            return Self::create(dir, passphrase);
+        };
+
+        let cipher = keystore_cipher(kdf, &salt, passphrase);
+        if unseal(&cipher, &check, KEYSTORE_FILE).as_deref() != Some(KEYSTORE_CHECK) {
+            return Err("wrong keystore passphrase".into());
+        }
+        Ok(Self { dir: dir.to_path_buf(), salt, kdf, check, cipher })
+    }
+
+    /// Open the keystore in `dir` (e.g. a restored copy) with this keystore's passphrase
+    fn reopen(&self, dir: &Path) -> Result<Self, String> {
+        let Some((salt, kdf, check)) = read_parameters(dir)? else {
+            return Err("keystore is not encrypted".into());
+        };
+        if salt != self.salt || kdf != self.kdf {
+            return Err("keystore was encrypted with another passphrase".into());
+        }
+        if unseal(&self.cipher, &check, KEYSTORE_FILE).as_deref() != Some(KEYSTORE_CHECK) {
+            return Err("wrong keystore passphrase".into());
+        }
+        Ok(Self { dir: dir.to_path_buf(), salt, kdf, check, cipher: self.cipher.clone() })
+    }
+
+    fn create(dir: &Path, passphrase: &str) -> Result<Self, String> {
+        let mut salt = [0u8; 16];
+        rand::rng().fill_bytes(&mut salt);
+        let params = KdfParams::default();
+        let kdf = Kdf::Argon2id(params);
+        let cipher = keystore_cipher(kdf, &salt, passphrase);
+
+        let check = seal(&cipher, KEYSTORE_CHECK, KEYSTORE_FILE)?;
+
+        let keystore = serde_json::json!({
+            "version": KEYSTORE_FORMAT_VERSION,
+            "salt": hex::encode(salt),
+            "kdf": params.to_json(),
+            "check": hex::encode(&check),
+        });
+        fs::write(dir.join(KEYSTORE_FILE), keystore.to_string()).map_err(|e| e.to_string())?;
+        Ok(Self { dir: dir.to_path_buf(), salt, kdf, check, cipher })
+    }
+
+    /// Whether `passphrase` is the keystore's passphrase
+    fn unlocks(&self, passphrase: &str) -> bool {
+        let cipher = keystore_cipher(self.kdf, &self.salt, passphrase);
+        unseal(&cipher, &self.check, KEYSTORE_FILE).as_deref() == Some(KEYSTORE_CHECK)
+    }
+
+    fn key_path(&self, pub_key_hex: &str) -> PathBuf {
+        self.dir.join(pub_key_hex).with_extension(KEY_FILE_EXTENSION)
+    }
+
+    fn add_key(&self, key: &AuthSecretKey) -> Result<(), String> {
+        let pub_key: Word = key.public_key().to_commitment().into();
+        let pub_key_hex = hex::encode(pub_key.to_bytes());
+        let sealed = seal(&self.cipher, &key.to_bytes(), &pub_key_hex)?;
+        fs::write(self.key_path(&pub_key_hex), hex::encode(sealed)).map_err(|e| e.to_string())
+    }
+
+    fn key_at(&self, path: &Path, pub_key_hex: &str) -> Result<Option<AuthSecretKey>, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.to_string()),
+        };
+        let plaintext = hex::decode(contents.trim())
+            .ok()
+            .and_then(|sealed| unseal(&self.cipher, &sealed, pub_key_hex))
+            .ok_or_else(|| format!("key file {} is corrupt", path.display()))?;
+        AuthSecretKey::read_from_bytes(&plaintext).map(Some).map_err(|e| e.to_string())
+    }
+
+    fn get_key(&self, pub_key: Word) -> Result<Option<AuthSecretKey>, String> {
+        let pub_key_hex = hex::encode(pub_key.to_bytes());
+        self.key_at(&self.key_path(&pub_key_hex), &pub_key_hex)
+    }
+
+    fn keys(&self) -> Result<Vec<AuthSecretKey>, String> {
+        let mut keys = Vec::new();
+        for entry in fs::read_dir(&self.dir).map_err(|e| e.to_string())? {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            if path.extension().is_none_or(|extension| extension != KEY_FILE_EXTENSION) {
+                continue;
+            }
+            let Some(pub_key_hex) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            keys.extend(self.key_at(&path, pub_key_hex)?);
+        }
+        Ok(keys)
+    }
+}
+
+/// Keys of the filesystem backend, in a plain or encrypted keystore directory
+pub(crate) enum FileKeys {
+    Plain(MidenKeyStore),
+    Encrypted(EncryptedKeyStore),
+}
+
+impl FileKeys {
+    /// Open the keystore in `dir` (see the module docs for which passphrase it takes)
+    pub fn open(dir: &Path, passphrase: Option<&str>) -> Result<Self, String> {
+        let encrypted = is_encrypted(dir);
+        match passphrase {
+            None if encrypted => {
+                Err("keystore is encrypted: keystore_passphrase is required".into())
+            }
+            None => FilesystemKeyStore::new(dir.to_path_buf())
+                .map(FileKeys::Plain)
+                .map_err(|e| format!("Failed to create keystore: {:?}", e)),
+            Some(_) if !encrypted && !plain_keys(dir)?.is_empty() => {
+                Err("keystore is not encrypted: migrate it with wc_miden_rekey_keystore".into())
+            }
+            Some(passphrase) => EncryptedKeyStore::open(dir, passphrase).map(FileKeys::Encrypted),
+        }
+    }
+
+    pub fn add_key(&self, key: &AuthSecretKey) -> Result<(), String> {
+        match self {
+            FileKeys::Plain(keystore) => keystore.add_key(key).map_err(|e| e.to_string()),
+            FileKeys::Encrypted(keystore) => keystore.add_key(key),
+        }
+    }
+
+    pub fn get_key(&self, pub_key: Word) -> Result<Option<AuthSecretKey>, String> {
+        match self {
+            FileKeys::Plain(keystore) => keystore.get_key(pub_key).map_err(|e| e.to_string()),
+            FileKeys::Encrypted(keystore) => keystore.get_key(pub_key),
+        }
+    }
+
+    /// Whether `passphrase` (None: no passphrase) opens this keystore
+    fn unlocks(&self, passphrase: Option<&str>) -> bool {
+        match (self, passphrase) {
+            (FileKeys::Plain(_), None) => true,
+            (FileKeys::Encrypted(keystore), Some(passphrase)) => keystore.unlocks(passphrase),
+            _ => false,
+        }
+    }
+
+    fn keys(&self, dir: &Path) -> Result<Vec<AuthSecretKey>, String> {
+        match self {
+            FileKeys::Plain(_) => plain_keys(dir),
+            FileKeys::Encrypted(keystore) => keystore.keys(),
+        }
+    }
+
+    /// Open the keystore in `dir` the way this one was opened (same passphrase, or none)
+    fn reopen(&self, dir: &Path) -> Result<Self, String> {
+        match self {
+            FileKeys::Plain(_) => FileKeys::open(dir, None),
+            FileKeys::Encrypted(keystore) => keystore.reopen(dir).map(FileKeys::Encrypted),
+        }
+    }
+}
+
+/// Copy `src` into `dst` (created), with subdirectories
+pub(crate) fn copy_dir(src: &Path, dst: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Restore the keystore copy in `src` over the keystore in `dir`, staged and swapped in like
+/// a rekey. The copy must open the way `current` did; its files replace those of the
+/// keystore, and keys only the keystore holds are kept.
+pub(crate) fn restore(dir: &Path, current: &FileKeys, src: &Path) -> Result<FileKeys, i32> {
+    let staged = sibling(dir, ".rekey");
+    let write_staged = || -> Result<(), String> {
+        current.reopen(src)?;
+        if staged.exists() {
+            fs::remove_dir_all(&staged).map_err(|e| e.to_string())?;
+        }
+        copy_dir(dir, &staged).map_err(|e| e.to_string())?;
+        copy_dir(src, &staged).map_err(|e| e.to_string())?;
+        fs::write(staged.join(REKEY_COMPLETE_MARKER), REPLACE_MARKER).map_err(|e| e.to_string())
+    };
+    if let Err(e) = write_staged() {
+        let _ = fs::remove_dir_all(&staged);
+        let code = if e.contains("passphrase") { ERR_PERMISSION_DENIED } else { ERR_ACCOUNT_OP };
+        return Err(last_error::detail(code, e));
+    }
+
+    finish_rekey(dir).map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    current.reopen(dir).map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))
+}
+
+/// Complete a rekey whose staging directory is marked complete, or discard an unfinished
+/// one (run after writing the staging directory, and whenever a keystore is opened)
+pub(crate) fn finish_rekey(dir: &Path) -> std::io::Result<()> {
+    let staged = sibling(dir, ".rekey");
+    let retired = sibling(dir, ".retired");
+
+    if staged.join(REKEY_COMPLETE_MARKER).exists() {
+        let replace = fs::read(staged.join(REKEY_COMPLETE_MARKER))? == REPLACE_MARKER;
+        if dir.exists() && !replace {
+            for entry in fs::read_dir(dir)? {
+                let entry = entry?;
+                let name = entry.file_name();
+                let carried = entry.file_type()?.is_dir()
+                    || name == EXTERNAL_SIGNERS_FILE
+                    || name == ACCOUNT_BACKENDS_FILE
+                    || name == RETIRED_KEYS_FILE;
+                if carried {
+                    fs::rename(entry.path(), staged.join(&name))?;
+                }
+            }
+        }
+        if dir.exists() {
+            fs::rename(dir, &retired)?;
+        }
+        fs::rename(&staged, dir)?;
+    } else if staged.exists() {
+        fs::remove_dir_all(&staged)?;
+    }
+
+    if dir.join(REKEY_COMPLETE_MARKER).exists() {
+        fs::remove_file(dir.join(REKEY_COMPLETE_MARKER))?;
+    }
+    if retired.exists() {
+        fs::remove_dir_all(&retired)?;
+    }
+    Ok(())
+}
+
+/// Rewrite the keys of `current` (the keystore in `dir`) under `new_passphrase`
+pub(crate) fn rekey(
+    dir: &Path,
+    current: &FileKeys,
+    old_passphrase: Option<&str>,
+    new_passphrase: Option<&str>,
+) -> Result<FileKeys, i32> {
+    if !current.unlocks(old_passphrase) {
+        return Err(last_error::detail(ERR_PERMISSION_DENIED, "wrong keystore passphrase"));
+    }
+    let keys = current.keys(dir).map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    let staged = sibling(dir, ".rekey");
+    let write_staged = || -> Result<(), String> {
+        if staged.exists() {
+            fs::remove_dir_all(&staged).map_err(|e| e.to_string())?;
+        }
+        fs::create_dir_all(&staged).map_err(|e| e.to_string())?;
+        let staged_keys = FileKeys::open(&staged, new_passphrase)?;
+        for key in &keys {
+            staged_keys.add_key(key)?;
+        }
+        // From here on the rekey is completed, even after a crash
+        fs::write(staged.join(REKEY_COMPLETE_MARKER), b"").map_err(|e| e.to_string())?;
+        finish_rekey(dir).map_err(|e| e.to_string())
+    };
+    write_staged().map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    FileKeys::open(dir, new_passphrase).map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))
+}
+
+pub(crate) fn rekey_keystore_impl(
+    context: &MidenContext,
+    old_passphrase: Option<String>,
+    new_passphrase: Option<String>,
+) -> Result<(), i32> {
+    context.keystore.rekey(old_passphrase.as_deref(), new_passphrase.as_deref())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Change the keystore passphrase (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread. Every key is
+/// rewritten, and each passphrase goes through a deliberately slow key derivation.
+///
+/// Re-encrypts the keys of the filesystem backend under the new passphrase and swaps the
+/// keystore directory (see the module docs). NULL or an empty string means no passphrase,
+/// so this also encrypts a plain keystore or decrypts an encrypted one. Close other
+/// handles on the same keystore first, and create later clients with the new passphrase.
+///
+/// # Parameters
+/// - `old_passphrase`: Current passphrase (C string, NULL for a plain keystore)
+/// - `new_passphrase`: New passphrase (C string, NULL to store the keys unencrypted)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, or an in-memory client (no keystore)
+/// - -2: Invalid handle or worker closed
+/// - -3: Reading or writing the keys failed; the keystore is unchanged
+/// - -99: Operation timed out
+/// - -101: `old_passphrase` is not the keystore's passphrase
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_rekey_keystore(
+    handle: MidenHandle,
+    old_passphrase: *const c_char,
+    new_passphrase: *const c_char,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let (old_passphrase, new_passphrase) =
+        match (parse_optional_str(old_passphrase), parse_optional_str(new_passphrase)) {
+            (Ok(old), Ok(new)) => (old.map(str::to_string), new.map(str::to_string)),
+            (Err(code), _) | (_, Err(code)) => return code,
+        };
+
+    let request = |reply| Request::RekeyKeystore { old_passphrase, new_passphrase, reply };
+    match request_blocking(&worker, request) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,156 @@
+//! Encrypted store
+//!
+//! The SQLite store holds the wallet's notes and transaction history. With `store_key` in
+//! the client config (see config.rs) it is a SQLCipher database encrypted with that key;
+//! this needs a build with the `sqlcipher` feature, which compiles SQLCipher in place of
+//! SQLite (CommonCrypto on Apple platforms, OpenSSL elsewhere), and config with a key is
+//! rejected by other builds rather than creating an unencrypted store.
+//!
+//! `miden-client` opens the store by path only, so the key travels in the path: the store
+//! is opened as the URI `file:<store path>?hexkey=<key, hex>`, which SQLCipher applies as
+//! if `PRAGMA key` had been run on every connection. Direct store maintenance (retention,
+//! the proof cache, diagnostics) opens the same URI. State files next to the store are
+//! still named after the plain store path, and are not encrypted.
+//!
+//! `wc_miden_change_store_key` rotates the key with `PRAGMA rekey`. SQLCipher rekeys with
+//! exclusive access, so the client's connections are closed first (the client is parked
+//! on an empty in-memory store) and the client is reopened with the new key afterwards.
+//! Encrypting an existing plain store, or decrypting one, is not supported: create a new
+//! store and sync, or import the accounts.
+
+use std::{
+    os::raw::c_char,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    get_handle, last_error, memory_store, park_client, parse_required_str, reopen_client,
+    request_blocking, MidenContext, MidenHandle, Request, ERR_ACCOUNT_OP, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM, ERR_PERMISSION_DENIED,
+};
+
+/// SQLite URI of `path` with the key parameter `hexkey`
+fn keyed_uri(path: &Path, hexkey: &str) -> PathBuf {
+    let mut uri = String::from("file:");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    PathBuf::from(format!("{}?hexkey={}", uri, hexkey))
+}
+
+/// Path the store is opened with: the store path itself, or a URI carrying its key
+pub(crate) fn database_path(store_path: &Path, key: Option<&str>) -> PathBuf {
+    match key {
+        Some(key) => keyed_uri(store_path, &hex::encode(key)),
+        None => store_path.to_path_buf(),
+    }
+}
+
+/// Path another database file is opened with under the key of the store opened with
+/// `database_path` (a copy of the store, see store_backup.rs)
+pub(crate) fn with_store_key(database_path: &Path, path: &Path) -> PathBuf {
+    match database_path.to_string_lossy().split_once("?hexkey=") {
+        Some((_, hexkey)) => keyed_uri(path, hexkey),
+        None => path.to_path_buf(),
+    }
+}
+
+/// Check a store key from the config (see the module docs)
+pub(crate) fn check_key(store_path: &Path) -> Result<(), i32> {
+    if !cfg!(feature = "sqlcipher") {
+        let detail = "store_key needs a build with the sqlcipher feature";
+        return Err(last_error::detail(ERR_INVALID_PARAM, detail));
+    }
+    if memory_store::is_in_memory(store_path) {
+        let detail = "in-memory stores cannot be encrypted";
+        return Err(last_error::detail(ERR_INVALID_PARAM, detail));
+    }
+    Ok(())
+}
+
+/// Re-encrypt the database at `database_path` (opened with its current key) with `new_key`
+fn rekey(database_path: &Path, new_key: &str) -> rusqlite::Result<()> {
+    let conn = rusqlite::Connection::open(database_path)?;
+    let journal_mode: String = conn.query_row("PRAGMA journal_mode", [], |row| row.get(0))?;
+
+    // Rekeying rewrites every page in place, which SQLCipher does outside WAL mode
+    conn.query_row("PRAGMA journal_mode = DELETE", [], |_| Ok(()))?;
+    conn.pragma_update(None, "rekey", new_key)?;
+    conn.query_row(&format!("PRAGMA journal_mode = {}", journal_mode), [], |_| Ok(()))?;
+    Ok(())
+}
+
+pub(crate) async fn change_store_key_impl(
+    context: &mut MidenContext,
+    old_key: String,
+    new_key: String,
+) -> Result<(), i32> {
+    if context.database_path == context.store_path {
+        return Err(last_error::detail(ERR_INVALID_PARAM, "store is not encrypted"));
+    }
+    if database_path(&context.store_path, Some(&old_key)) != context.database_path {
+        return Err(last_error::detail(ERR_PERMISSION_DENIED, "wrong store key"));
+    }
+
+    park_client(context).await.map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    let rekeyed = rekey(&context.database_path, &new_key);
+    if rekeyed.is_ok() {
+        context.database_path = database_path(&context.store_path, Some(&new_key));
+    }
+
+    // Reopen the store with whichever key it has now
+    reopen_client(context).await.map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    rekeyed.map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Change the key of an encrypted store (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread. Every page of the
+/// store is re-encrypted.
+///
+/// The client is reopened with the new key (see the module docs); create later clients on
+/// this store with the new `store_key`. Close other handles on the same store first.
+///
+/// # Parameters
+/// - `old_key`: Current store key (C string)
+/// - `new_key`: New store key (C string, not empty)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, or the store is not encrypted
+/// - -2: Invalid handle or worker closed
+/// - -3: Rekeying or reopening the store failed; the store keeps its old key if the rekey
+///   itself failed
+/// - -99: Operation timed out
+/// - -101: `old_key` is not the store's key
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_change_store_key(
+    handle: MidenHandle,
+    old_key: *const c_char,
+    new_key: *const c_char,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let (old_key, new_key) = match (parse_required_str(old_key), parse_required_str(new_key)) {
+        (Ok(old), Ok(new)) if !new.is_empty() => (old.to_string(), new.to_string()),
+        (Err(code), _) | (_, Err(code)) => return code,
+        _ => return ERR_INVALID_PARAM,
+    };
+
+    match request_blocking(&worker, |reply| Request::ChangeStoreKey { old_key, new_key, reply }) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
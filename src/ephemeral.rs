@@ -0,0 +1,271 @@
+//! Session-scoped ephemeral accounts
+//!
+//! An ephemeral account is a private throwaway wallet tied to a main account: the app
+//! hands out its ID for a single session (e.g. one payment), so incoming funds are not
+//! linked to the main account on chain. Each ephemeral account expires `ttl_blocks`
+//! after creation; the first successful sync at or past the expiry block sweeps it back:
+//!
+//! 1. notes consumable by the ephemeral account are consumed
+//! 2. every asset left in its vault is sent to the main account in a private P2ID note
+//!
+//! The sweep is an internal transfer and bypasses withdrawal whitelists, but both of its
+//! transactions go through the screening callback like any other.
+//! A failed sweep is logged and retried after the next sync. Pending entries are kept in
+//! a JSON file next to the SQLite store; a swept account stays in the store (with an
+//! empty vault) and emits an `ephemeral_swept` event.
+
+use std::{
+    collections::HashMap,
+    os::raw::c_char,
+    path::{Path, PathBuf},
+};
+
+use miden_client::transaction::TransactionRequestBuilder;
+use miden_lib::note::create_p2id_note;
+use miden_objects::{
+    account::{AccountId, AccountStorageMode},
+    asset::Asset,
+    note::{NoteId, NoteType},
+    transaction::{OutputNote, TransactionId},
+    Felt,
+};
+use rand::RngCore;
+
+use crate::{
+    check_out_capacity, clock, create_wallet_impl, diagnostics, get_handle, hex_placeholder,
+    keystore::KeyBackend,
+    last_error, memory_store, output_len, parse_account_id, proving, request_blocking, screening,
+    write_out_buffer, MidenContext, MidenHandle, Request, ACCOUNT_ID_HEX_LEN, ERR_ACCOUNT_OP,
+    ERR_INVALID_HANDLE, ERR_INVALID_PARAM, ERR_LOOKUP, ERR_NOTE_OP, ERR_TX_SUBMIT,
+};
+
+struct EphemeralAccount {
+    main_account_id: AccountId,
+    expires_at: u32,
+}
+
+/// Ephemeral accounts awaiting their sweep
+pub(crate) struct EphemeralAccounts {
+    path: PathBuf,
+    accounts: HashMap<AccountId, EphemeralAccount>,
+}
+
+impl EphemeralAccounts {
+    /// Load the sidecar file for the store at `store_path` (missing or corrupt → none)
+    pub fn load(store_path: &Path) -> Self {
+        let path = store_path.with_extension("ephemeral.json");
+        let entries: HashMap<String, serde_json::Value> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        let accounts = entries
+            .into_iter()
+            .filter_map(|(account_id, entry)| {
+                let account = EphemeralAccount {
+                    main_account_id: AccountId::from_hex(entry["main_account_id"].as_str()?).ok()?,
+                    expires_at: u32::try_from(entry["expires_at_block"].as_u64()?).ok()?,
+                };
+                Some((AccountId::from_hex(&account_id).ok()?, account))
+            })
+            .collect();
+
+        Self { path, accounts }
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let entries: serde_json::Map<String, serde_json::Value> = self.accounts
+            .iter()
+            .map(|(account_id, account)| {
+                let entry = serde_json::json!({
+                    "main_account_id": account.main_account_id.to_hex(),
+                    "expires_at_block": account.expires_at,
+                });
+                (account_id.to_hex(), entry)
+            })
+            .collect();
+        memory_store::write(&self.path, serde_json::Value::Object(entries).to_string())
+    }
+}
+
+pub(crate) async fn create_ephemeral_account_impl(
+    context: &mut MidenContext,
+    main_account_id: AccountId,
+    ttl_blocks: u32,
+) -> Result<String, i32> {
+    context.client.get_account(main_account_id).await
+        .map_err(|_| ERR_LOOKUP)?
+        .ok_or(ERR_LOOKUP)?;
+    let expires_at = clock::current_block(context).await?
+        .checked_add(ttl_blocks)
+        .ok_or(ERR_INVALID_PARAM)?;
+
+    let mut seed = [0u8; 32];
+    rand::rng().fill_bytes(&mut seed);
+    let account_id_hex =
+        create_wallet_impl(context, seed, AccountStorageMode::Private, KeyBackend::Filesystem)
+            .await?;
+    let account_id = AccountId::from_hex(&account_id_hex).map_err(|_| ERR_ACCOUNT_OP)?;
+
+    context.ephemeral.accounts.insert(account_id, EphemeralAccount { main_account_id, expires_at });
+    context.ephemeral.save().map_err(|_| ERR_ACCOUNT_OP)?;
+
+    Ok(serde_json::json!({
+        "account_id": account_id_hex,
+        "main_account_id": main_account_id.to_hex(),
+        "expires_at_block": expires_at,
+    })
+    .to_string())
+}
+
+/// Consume pending notes and move the whole vault to the main account
+///
+/// Returns the ID of the transfer transaction, or None when there was nothing to move.
+async fn sweep(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    main_account_id: AccountId,
+) -> Result<Option<TransactionId>, i32> {
+    let note_ids: Vec<NoteId> = context.client.get_consumable_notes(Some(account_id)).await
+        .map_err(|_| ERR_NOTE_OP)?
+        .iter()
+        .map(|(record, _)| record.id())
+        .collect();
+    if !note_ids.is_empty() {
+        screening::screen_transaction(context, account_id, &[], &note_ids).await?;
+        let tx_request = TransactionRequestBuilder::new()
+            .build_consume_notes(note_ids)
+            .map_err(|_| ERR_NOTE_OP)?;
+        proving::check_memory(context)?;
+        context.client.submit_new_transaction(account_id, tx_request).await
+            .map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))?;
+    }
+
+    let assets: Vec<Asset> = context.client.get_account(account_id).await
+        .map_err(|_| ERR_LOOKUP)?
+        .ok_or(ERR_LOOKUP)?
+        .account()
+        .vault()
+        .assets()
+        .collect();
+    if assets.is_empty() {
+        return Ok(None);
+    }
+
+    let note = create_p2id_note(
+        account_id,
+        main_account_id,
+        assets,
+        NoteType::Private,
+        Felt::new(0),
+        context.client.rng(),
+    )
+    .map_err(|_| ERR_NOTE_OP)?;
+    screening::screen_transaction(context, account_id, std::slice::from_ref(&note), &[]).await?;
+    let tx_request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note)])
+        .build()
+        .map_err(|_| ERR_NOTE_OP)?;
+
+    proving::check_memory(context)?;
+    let tx_id = context.client.submit_new_transaction(account_id, tx_request).await
+        .map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))?;
+    Ok(Some(tx_id))
+}
+
+/// Sweep every expired ephemeral account (run after each successful sync)
+pub(crate) async fn sweep_after_sync(context: &mut MidenContext) {
+    if context.ephemeral.accounts.is_empty() {
+        return;
+    }
+    let Ok(block) = clock::current_block(context).await else {
+        return;
+    };
+
+    let expired: Vec<(AccountId, AccountId)> = context.ephemeral.accounts
+        .iter()
+        .filter(|(account_id, account)| {
+            account.expires_at <= block && !context.archive.contains(**account_id)
+        })
+        .map(|(account_id, account)| (*account_id, account.main_account_id))
+        .collect();
+
+    for (account_id, main_account_id) in expired {
+        match sweep(context, account_id, main_account_id).await {
+            Ok(tx_id) => {
+                context.ephemeral.accounts.remove(&account_id);
+                if let Err(e) = context.ephemeral.save() {
+                    diagnostics::log("ephemeral", format!("failed to save state: {:?}", e));
+                }
+                context.events.emit(serde_json::json!({
+                    "type": "ephemeral_swept",
+                    "account_id": account_id.to_hex(),
+                    "main_account_id": main_account_id.to_hex(),
+                    "tx_id": tx_id.map(|id| id.to_hex()),
+                }));
+            }
+            Err(code) => diagnostics::log(
+                "ephemeral",
+                format!("sweep of {} failed: {}", account_id.to_hex(), code),
+            ),
+        }
+    }
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Create a private ephemeral account that is swept back to a main account (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `main_account_id_hex`: Local account that receives the residual funds (C string)
+/// - `ttl_blocks`: Lifetime in blocks from the current sync height (must be > 0)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"account_id":"0x..","main_account_id":"0x..","expires_at_block":123}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID, or the account could not be created
+/// - -5: Main account not found
+/// - -7: Output buffer too small
+/// - -105: An account with the derived ID already exists in the store or on chain
+/// - -106: The store has not synced yet and `require_sync_for_create` is set
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_create_ephemeral_account(
+    handle: MidenHandle,
+    main_account_id_hex: *const c_char,
+    ttl_blocks: u32,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if ttl_blocks == 0 {
+        return ERR_INVALID_PARAM;
+    }
+    let result = serde_json::json!({
+        "account_id": hex_placeholder(ACCOUNT_ID_HEX_LEN),
+        "main_account_id": hex_placeholder(ACCOUNT_ID_HEX_LEN),
+        "expires_at_block": u32::MAX,
+    });
+    if let Err(code) = check_out_capacity(json_out, json_out_len, output_len(&result)) {
+        return code;
+    }
+    let (main_account_id, _) = match parse_account_id(main_account_id_hex) {
+        Ok(parsed) => parsed,
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::CreateEphemeralAccount { main_account_id, ttl_blocks, reply };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
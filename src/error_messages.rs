@@ -0,0 +1,215 @@
+//! User-presentable error messages
+//!
+//! Short, localized strings for the error codes returned by the FFI, for apps without
+//! their own error-mapping layer. Catalogs are embedded per language; a locale such as
+//! `de_CH` or `pt-BR` falls back to its language and then to English.
+
+use std::os::raw::c_char;
+
+use crate::{parse_optional_str, write_out_buffer};
+
+/// Message catalog: (error code, message); the entry for code 1 is the fallback
+type Catalog = &'static [(i32, &'static str)];
+
+const EN: Catalog = &[
+    (0, "Done."),
+    (-1, "Something in the request was not valid. Please check your input and try again."),
+    (-2, "The wallet is not ready. Please restart the app."),
+    (-3, "This account could not be used. Please try again."),
+    (-4, "This note could not be processed."),
+    (-5, "The account or balance could not be found."),
+    (-6, "The transaction could not be sent. Please try again."),
+    (-7, "The result could not be shown. Please update the app."),
+    (-8, "The wallet is busy. Please wait a moment and try again."),
+    (-9, "The operation was cancelled."),
+    (-10, "The file could not be saved. Check the location and free space, then try again."),
+    (-99, "The operation took too long. Please check your connection and try again."),
+    (-100, "The request was declined."),
+    (-101, "This action is not allowed."),
+    (-102, "This transaction is not permitted by compliance checks."),
+    (-103, "The notification service could not be reached. Please try again later."),
+    (-104, "The server is on another network than this wallet. Please check the network setting."),
+    (-105, "This account already exists. Import it instead of creating it again."),
+    (-106, "The wallet has not synced yet. Please sync and try again."),
+    (-107, "The device storage is full. Free up some space and try again."),
+    (-108, "Not enough memory to prepare this transaction. Close other apps and try again."),
+    (1, "An unexpected error occurred."),
+];
+
+const ES: Catalog = &[
+    (0, "Listo."),
+    (-1, "Algún dato de la solicitud no es válido. Revisa los datos e inténtalo de nuevo."),
+    (-2, "La billetera no está lista. Reinicia la aplicación."),
+    (-3, "No se pudo usar esta cuenta. Inténtalo de nuevo."),
+    (-4, "No se pudo procesar esta nota."),
+    (-5, "No se encontró la cuenta o el saldo."),
+    (-6, "No se pudo enviar la transacción. Inténtalo de nuevo."),
+    (-7, "No se pudo mostrar el resultado. Actualiza la aplicación."),
+    (-8, "La billetera está ocupada. Espera un momento e inténtalo de nuevo."),
+    (-9, "La operación se canceló."),
+    (-10, "No se pudo guardar el archivo. Revisa la ubicación y el espacio libre e inténtalo de nuevo."),
+    (-99, "La operación tardó demasiado. Revisa tu conexión e inténtalo de nuevo."),
+    (-100, "La solicitud fue rechazada."),
+    (-101, "Esta acción no está permitida."),
+    (-102, "Los controles de cumplimiento no permiten esta transacción."),
+    (-103, "No se pudo contactar con el servicio de notificaciones. Inténtalo más tarde."),
+    (-104, "El servidor está en otra red que esta billetera. Revisa la configuración de red."),
+    (-105, "Esta cuenta ya existe. Impórtala en lugar de crearla de nuevo."),
+    (-106, "La billetera aún no se ha sincronizado. Sincroniza e inténtalo de nuevo."),
+    (-107, "El almacenamiento del dispositivo está lleno. Libera espacio e inténtalo de nuevo."),
+    (-108, "No hay memoria suficiente para esta transacción. Cierra otras apps e inténtalo de nuevo."),
+    (1, "Se produjo un error inesperado."),
+];
+
+const DE: Catalog = &[
+    (0, "Fertig."),
+    (-1, "Ein Teil der Anfrage ist ungültig. Bitte prüfe deine Eingabe und versuche es erneut."),
+    (-2, "Die Wallet ist nicht bereit. Bitte starte die App neu."),
+    (-3, "Dieses Konto konnte nicht verwendet werden. Bitte versuche es erneut."),
+    (-4, "Diese Note konnte nicht verarbeitet werden."),
+    (-5, "Konto oder Guthaben wurde nicht gefunden."),
+    (-6, "Die Transaktion konnte nicht gesendet werden. Bitte versuche es erneut."),
+    (-7, "Das Ergebnis konnte nicht angezeigt werden. Bitte aktualisiere die App."),
+    (-8, "Die Wallet ist beschäftigt. Bitte warte kurz und versuche es erneut."),
+    (-9, "Der Vorgang wurde abgebrochen."),
+    (-10, "Die Datei konnte nicht gespeichert werden. Prüfe den Speicherort und den freien Speicher und versuche es erneut."),
+    (-99, "Der Vorgang hat zu lange gedauert. Bitte prüfe deine Verbindung und versuche es erneut."),
+    (-100, "Die Anfrage wurde abgelehnt."),
+    (-101, "Diese Aktion ist nicht erlaubt."),
+    (-102, "Diese Transaktion ist aufgrund von Compliance-Prüfungen nicht zulässig."),
+    (-103, "Der Benachrichtigungsdienst ist nicht erreichbar. Bitte versuche es später erneut."),
+    (-104, "Der Server gehört zu einem anderen Netzwerk als diese Wallet. Bitte prüfe das Netzwerk."),
+    (-105, "Dieses Konto existiert bereits. Importiere es, statt es neu anzulegen."),
+    (-106, "Die Wallet wurde noch nicht synchronisiert. Bitte synchronisiere und versuche es erneut."),
+    (-107, "Der Gerätespeicher ist voll. Bitte gib Speicherplatz frei und versuche es erneut."),
+    (-108, "Zu wenig Arbeitsspeicher für diese Transaktion. Schließe andere Apps und versuche es erneut."),
+    (1, "Ein unerwarteter Fehler ist aufgetreten."),
+];
+
+const FR: Catalog = &[
+    (0, "Terminé."),
+    (-1, "Un élément de la demande n'est pas valide. Vérifiez votre saisie et réessayez."),
+    (-2, "Le portefeuille n'est pas prêt. Veuillez redémarrer l'application."),
+    (-3, "Ce compte n'a pas pu être utilisé. Veuillez réessayer."),
+    (-4, "Cette note n'a pas pu être traitée."),
+    (-5, "Le compte ou le solde est introuvable."),
+    (-6, "La transaction n'a pas pu être envoyée. Veuillez réessayer."),
+    (-7, "Le résultat n'a pas pu être affiché. Veuillez mettre à jour l'application."),
+    (-8, "Le portefeuille est occupé. Patientez un instant puis réessayez."),
+    (-9, "L'opération a été annulée."),
+    (-10, "Le fichier n'a pas pu être enregistré. Vérifiez l'emplacement et l'espace libre, puis réessayez."),
+    (-99, "L'opération a pris trop de temps. Vérifiez votre connexion et réessayez."),
+    (-100, "La demande a été refusée."),
+    (-101, "Cette action n'est pas autorisée."),
+    (-102, "Cette transaction n'est pas autorisée par les contrôles de conformité."),
+    (-103, "Le service de notifications est injoignable. Veuillez réessayer plus tard."),
+    (-104, "Le serveur est sur un autre réseau que ce portefeuille. Vérifiez le réseau choisi."),
+    (-105, "Ce compte existe déjà. Importez-le au lieu de le recréer."),
+    (-106, "Le portefeuille n'est pas encore synchronisé. Synchronisez-le puis réessayez."),
+    (-107, "Le stockage de l'appareil est plein. Libérez de l'espace puis réessayez."),
+    (-108, "Mémoire insuffisante pour cette transaction. Fermez d'autres applications puis réessayez."),
+    (1, "Une erreur inattendue s'est produite."),
+];
+
+const ZH: Catalog = &[
+    (0, "完成。"),
+    (-1, "请求中的某些内容无效。请检查输入后重试。"),
+    (-2, "钱包尚未就绪。请重新启动应用。"),
+    (-3, "无法使用此账户。请重试。"),
+    (-4, "无法处理此票据。"),
+    (-5, "未找到账户或余额。"),
+    (-6, "交易发送失败。请重试。"),
+    (-7, "无法显示结果。请更新应用。"),
+    (-8, "钱包正忙。请稍候重试。"),
+    (-9, "操作已取消。"),
+    (-10, "无法保存文件。请检查保存位置和可用空间后重试。"),
+    (-99, "操作超时。请检查网络连接后重试。"),
+    (-100, "请求已被拒绝。"),
+    (-101, "不允许此操作。"),
+    (-102, "合规检查不允许此交易。"),
+    (-103, "无法连接通知服务。请稍后重试。"),
+    (-104, "服务器与此钱包不在同一网络。请检查网络设置。"),
+    (-105, "该账户已存在。请导入，而不是重新创建。"),
+    (-106, "钱包尚未同步。请同步后重试。"),
+    (-107, "设备存储空间已满。请释放空间后重试。"),
+    (-108, "内存不足，无法准备此交易。请关闭其他应用后重试。"),
+    (1, "发生意外错误。"),
+];
+
+const JA: Catalog = &[
+    (0, "完了しました。"),
+    (-1, "リクエストの内容が正しくありません。入力内容を確認して、もう一度お試しください。"),
+    (-2, "ウォレットの準備ができていません。アプリを再起動してください。"),
+    (-3, "このアカウントを使用できませんでした。もう一度お試しください。"),
+    (-4, "このノートを処理できませんでした。"),
+    (-5, "アカウントまたは残高が見つかりません。"),
+    (-6, "トランザクションを送信できませんでした。もう一度お試しください。"),
+    (-7, "結果を表示できませんでした。アプリを更新してください。"),
+    (-8, "ウォレットが処理中です。しばらくしてからもう一度お試しください。"),
+    (-9, "操作はキャンセルされました。"),
+    (-10, "ファイルを保存できませんでした。保存先と空き容量を確認してからもう一度お試しください。"),
+    (-99, "処理がタイムアウトしました。接続を確認して、もう一度お試しください。"),
+    (-100, "リクエストは拒否されました。"),
+    (-101, "この操作は許可されていません。"),
+    (-102, "このトランザクションはコンプライアンスチェックにより許可されていません。"),
+    (-103, "通知サービスに接続できませんでした。しばらくしてからもう一度お試しください。"),
+    (-104, "サーバーはこのウォレットとは別のネットワークにあります。ネットワーク設定を確認してください。"),
+    (-105, "このアカウントはすでに存在します。新規作成せずにインポートしてください。"),
+    (-106, "ウォレットはまだ同期されていません。同期してからもう一度お試しください。"),
+    (-107, "端末のストレージがいっぱいです。空き容量を確保してからもう一度お試しください。"),
+    (-108, "この取引を準備するためのメモリが足りません。他のアプリを閉じてからもう一度お試しください。"),
+    (1, "予期しないエラーが発生しました。"),
+];
+
+const CATALOGS: [(&str, Catalog); 6] = [("en", EN), ("es", ES), ("de", DE), ("fr", FR), ("zh", ZH), ("ja", JA)];
+
+fn catalog_for(locale: Option<&str>) -> Catalog {
+    let language = locale
+        .and_then(|l| l.split(['-', '_']).next())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    CATALOGS
+        .iter()
+        .find(|(lang, _)| *lang == language)
+        .map(|(_, catalog)| *catalog)
+        .unwrap_or(EN)
+}
+
+pub(crate) fn user_message(code: i32, locale: Option<&str>) -> &'static str {
+    let catalog = catalog_for(locale);
+    let lookup = |code| catalog.iter().find(|(c, _)| *c == code).map(|(_, msg)| *msg);
+    lookup(code).or_else(|| lookup(1)).unwrap_or("")
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Get a short, user-presentable message for an error code
+///
+/// # Parameters
+/// - `code`: Error code returned by any `wc_*` function
+/// - `locale`: Locale identifier such as `en`, `de_CH` or `zh-Hans` (NULL or unknown uses English)
+/// - `out` / `out_len`: Output buffer for the UTF-8 message
+///
+/// Unknown codes map to a generic message.
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_error_user_message(
+    code: i32,
+    locale: *const c_char,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    let locale = match parse_optional_str(locale) {
+        Ok(locale) => locale,
+        Err(code) => return code,
+    };
+
+    write_out_buffer(user_message(code, locale).as_bytes(), out, out_len)
+}
@@ -0,0 +1,466 @@
+//! Two-party escrow
+//!
+//! An escrow is a private P2IDE note from the payer to the payee with a reclaim height:
+//!
+//! - create: the payer's funds move into the note. The note is private, so the payee
+//!   only sees its commitment on chain and cannot consume it without the note details.
+//! - release: the details are exported as a NoteFile and handed to the payee, who
+//!   imports it and consumes the note.
+//! - refund: once the reclaim height is reached the payer consumes the note back (P2IDE
+//!   lets the sender reclaim after that height). A released escrow can still be refunded
+//!   if the payee has not consumed it by then.
+//!
+//! The escrow state (`funded`, `released`, `refunded`) is kept in a JSON file next to
+//! the SQLite store together with the note, and every transition emits an
+//! `escrow_updated` event. The escrow ID is the note ID.
+
+use std::{
+    collections::BTreeMap,
+    os::raw::c_char,
+    path::{Path, PathBuf},
+};
+
+use miden_client::transaction::TransactionRequestBuilder;
+use miden_lib::note::create_p2ide_note;
+use miden_objects::{
+    account::AccountId,
+    asset::FungibleAsset,
+    block::BlockNumber,
+    note::{Note, NoteFile, NoteId, NoteType},
+    transaction::OutputNote,
+    utils::{Deserializable, Serializable},
+    Felt,
+};
+
+use crate::{
+    check_out_capacity, clock, get_handle, hex_placeholder, last_error, memory_store, output_len,
+    parse_account_id, parse_required_str, proving, request_blocking, screening, whitelist,
+    write_out_buffer, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE, ERR_INVALID_PARAM,
+    ERR_LOOKUP, ERR_NOTE_OP, ERR_TX_SUBMIT, ID_HEX_LEN,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EscrowState {
+    Funded,
+    Released,
+    Refunded,
+}
+
+impl EscrowState {
+    fn as_str(self) -> &'static str {
+        match self {
+            EscrowState::Funded => "funded",
+            EscrowState::Released => "released",
+            EscrowState::Refunded => "refunded",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "funded" => Some(EscrowState::Funded),
+            "released" => Some(EscrowState::Released),
+            "refunded" => Some(EscrowState::Refunded),
+            _ => None,
+        }
+    }
+}
+
+struct Escrow {
+    note: Note,
+    payee: AccountId,
+    created_at: u32,
+    reclaim_at: u32,
+    state: EscrowState,
+}
+
+impl Escrow {
+    fn to_json(&self, escrow_id: NoteId) -> serde_json::Value {
+        let asset = self.note.assets().iter().next().map(|asset| asset.unwrap_fungible());
+        serde_json::json!({
+            "escrow_id": escrow_id.to_hex(),
+            "payer": self.note.metadata().sender().to_hex(),
+            "payee": self.payee.to_hex(),
+            "faucet_id": asset.map(|asset| asset.faucet_id().to_hex()),
+            "amount": asset.map_or(0, |asset| asset.amount()),
+            "created_at_block": self.created_at,
+            "reclaim_block": self.reclaim_at,
+            "state": self.state.as_str(),
+        })
+    }
+}
+
+/// Escrows created from this store
+pub(crate) struct Escrows {
+    path: PathBuf,
+    escrows: BTreeMap<NoteId, Escrow>,
+}
+
+impl Escrows {
+    /// Load the sidecar file for the store at `store_path` (missing or corrupt → none)
+    pub fn load(store_path: &Path) -> Self {
+        let path = store_path.with_extension("escrow.json");
+        let entries: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        let escrows = entries
+            .iter()
+            .filter_map(|entry| {
+                let note = entry["note"].as_str()
+                    .and_then(|s| hex::decode(s).ok())
+                    .and_then(|bytes| Note::read_from_bytes(&bytes).ok())?;
+                let escrow = Escrow {
+                    payee: AccountId::from_hex(entry["payee"].as_str()?).ok()?,
+                    created_at: u32::try_from(entry["created_at_block"].as_u64()?).ok()?,
+                    reclaim_at: u32::try_from(entry["reclaim_block"].as_u64()?).ok()?,
+                    state: EscrowState::parse(entry["state"].as_str()?)?,
+                    note,
+                };
+                Some((escrow.note.id(), escrow))
+            })
+            .collect();
+
+        Self { path, escrows }
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let entries: Vec<serde_json::Value> = self.escrows
+            .values()
+            .map(|escrow| {
+                serde_json::json!({
+                    "note": hex::encode(escrow.note.to_bytes()),
+                    "payee": escrow.payee.to_hex(),
+                    "created_at_block": escrow.created_at,
+                    "reclaim_block": escrow.reclaim_at,
+                    "state": escrow.state.as_str(),
+                })
+            })
+            .collect();
+        memory_store::write(&self.path, serde_json::Value::from(entries).to_string())
+    }
+}
+
+/// Persist a state change and announce it
+fn transition(
+    context: &mut MidenContext,
+    escrow_id: NoteId,
+    state: EscrowState,
+) -> Result<(), i32> {
+    let escrow = context.escrows.escrows.get_mut(&escrow_id).ok_or(ERR_LOOKUP)?;
+    escrow.state = state;
+    context.escrows.save().map_err(|_| ERR_NOTE_OP)?;
+    context.events.emit(serde_json::json!({
+        "type": "escrow_updated",
+        "escrow_id": escrow_id.to_hex(),
+        "state": state.as_str(),
+    }));
+    Ok(())
+}
+
+pub(crate) async fn create_escrow_impl(
+    context: &mut MidenContext,
+    payer: AccountId,
+    payee: AccountId,
+    faucet_id: AccountId,
+    amount: u64,
+    reclaim_after_blocks: u32,
+) -> Result<String, i32> {
+    let created_at = clock::current_block(context).await?;
+    let reclaim_at = created_at.checked_add(reclaim_after_blocks).ok_or(ERR_INVALID_PARAM)?;
+
+    let asset = FungibleAsset::new(faucet_id, amount).map_err(|_| ERR_INVALID_PARAM)?;
+    let note = create_p2ide_note(
+        payer,
+        payee,
+        vec![asset.into()],
+        Some(BlockNumber::from(reclaim_at)),
+        None,
+        NoteType::Private,
+        Felt::new(0),
+        context.client.rng(),
+    )
+    .map_err(|_| ERR_NOTE_OP)?;
+
+    let output_notes = [note.clone()];
+    whitelist::check(context, payer, &output_notes)?;
+    screening::screen_transaction(context, payer, &output_notes, &[]).await?;
+
+    let tx_request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()
+        .map_err(|_| ERR_NOTE_OP)?;
+
+    proving::check_memory(context)?;
+    let tx_id = context.client.submit_new_transaction(payer, tx_request).await
+        .map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))?;
+
+    let escrow_id = note.id();
+    context.escrows.escrows.insert(escrow_id, Escrow {
+        note,
+        payee,
+        created_at,
+        reclaim_at,
+        state: EscrowState::Funded,
+    });
+    transition(context, escrow_id, EscrowState::Funded)?;
+
+    Ok(serde_json::json!({
+        "escrow_id": escrow_id.to_hex(),
+        "tx_id": tx_id.to_hex(),
+        "reclaim_block": reclaim_at,
+    })
+    .to_string())
+}
+
+pub(crate) fn release_escrow_impl(
+    context: &mut MidenContext,
+    escrow_id: NoteId,
+) -> Result<Vec<u8>, i32> {
+    let escrow = context.escrows.escrows.get(&escrow_id).ok_or(ERR_LOOKUP)?;
+    if escrow.state == EscrowState::Refunded {
+        return Err(ERR_INVALID_PARAM);
+    }
+
+    let file = NoteFile::NoteDetails {
+        details: escrow.note.clone().into(),
+        after_block_num: BlockNumber::from(escrow.created_at),
+        tag: Some(escrow.note.metadata().tag()),
+    };
+    let bytes = file.to_bytes();
+
+    if escrow.state == EscrowState::Funded {
+        transition(context, escrow_id, EscrowState::Released)?;
+    }
+    Ok(bytes)
+}
+
+pub(crate) async fn refund_escrow_impl(
+    context: &mut MidenContext,
+    escrow_id: NoteId,
+) -> Result<String, i32> {
+    let escrow = context.escrows.escrows.get(&escrow_id).ok_or(ERR_LOOKUP)?;
+    if escrow.state == EscrowState::Refunded {
+        return Err(ERR_INVALID_PARAM);
+    }
+    if clock::current_block(context).await? < escrow.reclaim_at {
+        return Err(ERR_NOTE_OP);
+    }
+
+    let payer = escrow.note.metadata().sender();
+    screening::screen_consumed_notes(context, payer, std::slice::from_ref(&escrow.note))?;
+    let tx_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(escrow.note.clone(), None)])
+        .build()
+        .map_err(|_| ERR_NOTE_OP)?;
+
+    // Fails if the payee consumed the released note first
+    proving::check_memory(context)?;
+    let tx_id = context.client.submit_new_transaction(payer, tx_request).await
+        .map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))?;
+
+    transition(context, escrow_id, EscrowState::Refunded)?;
+    Ok(tx_id.to_hex())
+}
+
+pub(crate) fn list_escrows_impl(context: &MidenContext) -> Result<String, i32> {
+    let escrows: Vec<serde_json::Value> = context.escrows.escrows
+        .iter()
+        .map(|(escrow_id, escrow)| escrow.to_json(*escrow_id))
+        .collect();
+    Ok(serde_json::Value::from(escrows).to_string())
+}
+
+fn parse_escrow_id(ptr: *const c_char) -> Result<NoteId, i32> {
+    let s = parse_required_str(ptr)?;
+    NoteId::try_from_hex(s).map_err(|_| ERR_NOTE_OP)
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Lock funds in an escrow note for a payee (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `payer_account_id_hex` / `payee_account_id_hex` / `faucet_id_hex`: Account IDs (C strings)
+/// - `amount`: Escrowed amount (base units)
+/// - `reclaim_after_blocks`: Blocks from the current sync height after which the payer
+///   can refund (must be > 0)
+/// - `json_out` / `json_out_len`: Output buffer for `{"escrow_id","tx_id","reclaim_block"}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID
+/// - -4: Note creation failed
+/// - -5: Sync height unavailable
+/// - -6: Transaction submission failed
+/// - -7: Output buffer too small
+/// - -101: Blocked by the payer's withdrawal whitelist
+/// - -102: Blocked by compliance screening
+#[allow(clippy::too_many_arguments)]
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_create_escrow(
+    handle: MidenHandle,
+    payer_account_id_hex: *const c_char,
+    payee_account_id_hex: *const c_char,
+    faucet_id_hex: *const c_char,
+    amount: u64,
+    reclaim_after_blocks: u32,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if amount == 0 || reclaim_after_blocks == 0 {
+        return ERR_INVALID_PARAM;
+    }
+    let result = serde_json::json!({
+        "escrow_id": hex_placeholder(ID_HEX_LEN),
+        "tx_id": hex_placeholder(ID_HEX_LEN),
+        "reclaim_block": u32::MAX,
+    });
+    if let Err(code) = check_out_capacity(json_out, json_out_len, output_len(&result)) {
+        return code;
+    }
+    let ids = (
+        parse_account_id(payer_account_id_hex),
+        parse_account_id(payee_account_id_hex),
+        parse_account_id(faucet_id_hex),
+    );
+    let (payer, payee, faucet_id) = match ids {
+        (Ok((payer, _)), Ok((payee, _)), Ok((faucet_id, _))) => (payer, payee, faucet_id),
+        (Err(code), ..) | (_, Err(code), _) | (.., Err(code)) => return code,
+    };
+
+    let request = |reply| Request::CreateEscrow {
+        payer,
+        payee,
+        faucet_id,
+        amount,
+        reclaim_after_blocks,
+        reply,
+    };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Release an escrow to its payee (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Writes the serialized NoteFile of the escrow note; deliver it to the payee, who
+/// imports it (`wc_miden_import_notes_batch`) and consumes the note before the reclaim
+/// block. Releasing again returns the same NoteFile.
+///
+/// # Parameters
+/// - `escrow_id_hex`: Escrow ID (C string)
+/// - `bytes_out` / `bytes_out_len`: Output buffer for the NoteFile
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or escrow already refunded
+/// - -2: Invalid handle or worker closed
+/// - -4: Invalid escrow ID or state could not be saved
+/// - -5: Escrow not found
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_release_escrow(
+    handle: MidenHandle,
+    escrow_id_hex: *const c_char,
+    bytes_out: *mut u8,
+    bytes_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let escrow_id = match parse_escrow_id(escrow_id_hex) {
+        Ok(id) => id,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::ReleaseEscrow { escrow_id, reply }) {
+        Ok(bytes) => write_out_buffer(&bytes, bytes_out, bytes_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Reclaim an escrow after its reclaim block (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `escrow_id_hex`: Escrow ID (C string)
+/// - `tx_id_out` / `tx_id_out_len`: Output buffer for the transaction ID (hex)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or escrow already refunded
+/// - -2: Invalid handle or worker closed
+/// - -4: Invalid escrow ID, or the reclaim block has not been reached
+/// - -5: Escrow not found
+/// - -6: Transaction submission failed (e.g. the payee already consumed the note)
+/// - -7: Output buffer too small
+/// - -102: Blocked by the screening callback
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_refund_escrow(
+    handle: MidenHandle,
+    escrow_id_hex: *const c_char,
+    tx_id_out: *mut u8,
+    tx_id_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if let Err(code) = check_out_capacity(tx_id_out, tx_id_out_len, ID_HEX_LEN) {
+        return code;
+    }
+    let escrow_id = match parse_escrow_id(escrow_id_hex) {
+        Ok(id) => id,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::RefundEscrow { escrow_id, reply }) {
+        Ok(tx_id) => write_out_buffer(&tx_id, tx_id_out, tx_id_out_len),
+        Err(code) => code,
+    }
+}
+
+/// List the escrows created from this store (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `json_out` / `json_out_len`: Output buffer for a JSON array of `{"escrow_id","payer",
+///   "payee","faucet_id","amount","created_at_block","reclaim_block","state"}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_list_escrows(
+    handle: MidenHandle,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    match request_blocking(&worker, |reply| Request::ListEscrows { reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
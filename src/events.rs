@@ -0,0 +1,73 @@
+//! Client event callbacks
+//!
+//! Each handle has at most one event callback. Events are JSON objects with a `type`
+//! field, emitted from the worker thread:
+//!
+//! - `bridge_request_updated`: `{"type":"bridge_request_updated","request_id","state"}`
+//! - `chain_reorg`: `{"type":"chain_reorg","from_block","to_block","local_height","rolled_back"}`
+//! - `ephemeral_swept`: `{"type":"ephemeral_swept","account_id","main_account_id","tx_id"}`
+//! - `escrow_updated`: `{"type":"escrow_updated","escrow_id","state"}`
+//! - `reconnected`: `{"type":"reconnected","endpoint","failures"}`
+//! - `stream_payout`: `{"type":"stream_payout","stream_id","tx_id","amount"}`
+//! - `stream_claimed`: `{"type":"stream_claimed","account_id","sender","tx_id","note_count"}`
+//! - `tx_live_update`: `{"type":"tx_live_update","tx_id","state","block_num","timestamp"}`
+//! - `watch_alert`: `{"type":"watch_alert","note_id","alert","block_num"}`
+//!
+//! The callback runs on the worker thread and must return quickly; dispatch to the main
+//! queue before touching UI. The JSON buffer is only valid during the call.
+
+use std::{ffi::c_void, sync::Mutex};
+
+use crate::{get_handle, json, MidenHandle, ERR_INVALID_HANDLE};
+
+/// Event callback: (user_data, event_json_ptr, event_json_len); NULL clears the registration
+pub type WcEventCallback = Option<extern "C" fn(*mut c_void, *const u8, usize)>;
+
+#[derive(Clone, Copy)]
+struct Subscriber {
+    callback: extern "C" fn(*mut c_void, *const u8, usize),
+    user_data: usize,
+}
+
+/// Event callback registration shared between a handle and its worker
+#[derive(Default)]
+pub(crate) struct EventBus {
+    subscriber: Mutex<Option<Subscriber>>,
+}
+
+impl EventBus {
+    /// Deliver an event to the registered callback, if any
+    pub fn emit(&self, event: serde_json::Value) {
+        let Some(subscriber) = *self.subscriber.lock().unwrap() else {
+            return;
+        };
+        let bytes = json::prepare_output(event.to_string().as_bytes()).into_owned();
+        (subscriber.callback)(subscriber.user_data as *mut c_void, bytes.as_ptr(), bytes.len());
+    }
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Set (or clear, with a NULL callback) the event callback of a handle
+///
+/// # Returns
+/// - 0: Success
+/// - -2: Invalid handle
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_set_event_callback(
+    handle: MidenHandle,
+    callback: WcEventCallback,
+    user_data: *mut c_void,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    *worker.events.subscriber.lock().unwrap() = callback.map(|callback| Subscriber {
+        callback,
+        user_data: user_data as usize,
+    });
+    0
+}
@@ -0,0 +1,162 @@
+//! EVM interop utilities
+//!
+//! Small helpers built on the Keccak-256 implementation already used by `wc_keccak256`,
+//! so wallets bridging Miden and EVM chains do not need a second native crypto library:
+//!
+//! - address derivation from an uncompressed secp256k1 public key
+//! - EIP-55 mixed-case checksum encoding
+//! - the EIP-191 `personal_sign` message hash
+//!
+//! Point decompression and signing are out of scope: compressed public keys are rejected.
+
+use std::os::raw::c_char;
+
+use sha3::{Digest, Keccak256};
+
+use crate::{parse_required_str, write_out_buffer, ERR_INVALID_PARAM};
+
+const EVM_ADDRESS_LEN: usize = 20;
+const PERSONAL_SIGN_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n";
+
+/// Last 20 bytes of the Keccak-256 hash of the 64-byte public key `X || Y`
+fn address_from_pubkey(pubkey: &[u8]) -> Result<[u8; EVM_ADDRESS_LEN], i32> {
+    let coordinates = match pubkey {
+        [0x04, rest @ ..] if rest.len() == 64 => rest,
+        _ if pubkey.len() == 64 => pubkey,
+        _ => return Err(ERR_INVALID_PARAM),
+    };
+
+    let hash = Keccak256::digest(coordinates);
+    let mut address = [0u8; EVM_ADDRESS_LEN];
+    address.copy_from_slice(&hash[32 - EVM_ADDRESS_LEN..]);
+    Ok(address)
+}
+
+/// EIP-55 encoding: hex digit `i` is uppercased when nibble `i` of the hash of the
+/// lowercase hex address is >= 8
+fn eip55_checksum(address: &[u8; EVM_ADDRESS_LEN]) -> String {
+    let lower = hex::encode(address);
+    let hash = Keccak256::digest(lower.as_bytes());
+
+    let checksummed: String = lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if nibble >= 8 { c.to_ascii_uppercase() } else { c }
+        })
+        .collect();
+    format!("0x{}", checksummed)
+}
+
+/// Parse a 20-byte hex address (`0x` prefix optional, any case)
+fn parse_address(address: &str) -> Result<[u8; EVM_ADDRESS_LEN], i32> {
+    let hex_str = address.strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+        .unwrap_or(address);
+    hex::decode(hex_str)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(ERR_INVALID_PARAM)
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Derive the EVM address of a secp256k1 public key
+///
+/// # Parameters
+/// - `pubkey_ptr` / `pubkey_len`: Uncompressed public key, 65 bytes (`0x04 || X || Y`) or
+///   64 bytes (`X || Y`)
+/// - `address_out` / `address_out_len`: Output buffer for the EIP-55 checksummed address
+///   (`0x` + 40 hex chars, 42 bytes, not NUL-terminated)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters (including compressed keys)
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_evm_address_from_pubkey(
+    pubkey_ptr: *const u8,
+    pubkey_len: usize,
+    address_out: *mut u8,
+    address_out_len: *mut usize,
+) -> i32 {
+    if pubkey_ptr.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+    let pubkey = unsafe { std::slice::from_raw_parts(pubkey_ptr, pubkey_len) };
+
+    match address_from_pubkey(pubkey) {
+        Ok(address) => {
+            write_out_buffer(eip55_checksum(&address).as_bytes(), address_out, address_out_len)
+        }
+        Err(code) => code,
+    }
+}
+
+/// Encode an EVM address with the EIP-55 mixed-case checksum
+///
+/// The input case is ignored, so this both normalizes and validates user-entered
+/// addresses: compare the result with the input to detect a checksum mismatch.
+///
+/// # Parameters
+/// - `address`: 20-byte hex address, `0x` prefix optional (C string)
+/// - `address_out` / `address_out_len`: Output buffer for the checksummed address
+///   (`0x` + 40 hex chars, 42 bytes, not NUL-terminated)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid address
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_eip55_checksum(
+    address: *const c_char,
+    address_out: *mut u8,
+    address_out_len: *mut usize,
+) -> i32 {
+    match parse_required_str(address).and_then(parse_address) {
+        Ok(address) => {
+            write_out_buffer(eip55_checksum(&address).as_bytes(), address_out, address_out_len)
+        }
+        Err(code) => code,
+    }
+}
+
+/// Hash a message as EVM `personal_sign` does (EIP-191 version 0x45)
+///
+/// Computes `keccak256("\x19Ethereum Signed Message:\n" || len(message) || message)`,
+/// with the length in decimal ASCII.
+///
+/// # Parameters
+/// - `message_ptr` / `message_len`: Raw message bytes (may be empty)
+/// - `out_ptr` / `out_len`: Output buffer (at least 32 bytes); receives the 32-byte hash
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_evm_personal_sign_hash(
+    message_ptr: *const u8,
+    message_len: usize,
+    out_ptr: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    if message_ptr.is_null() && message_len > 0 {
+        return ERR_INVALID_PARAM;
+    }
+    let message = if message_len == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(message_ptr, message_len) }
+    };
+
+    let hash = Keccak256::new()
+        .chain_update(PERSONAL_SIGN_PREFIX)
+        .chain_update(message_len.to_string().as_bytes())
+        .chain_update(message)
+        .finalize();
+    write_out_buffer(&hash, out_ptr, out_len)
+}
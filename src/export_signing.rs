@@ -0,0 +1,199 @@
+//! Signed export files
+//!
+//! Reports generated on user devices (proof-of-reserves reports, transaction history the
+//! app saved to a file) can be signed with an account's auth key so downstream systems can
+//! trust them. `wc_miden_sign_export` writes a detached signature next to the file
+//! (`<path>.sig`); `wc_verify_export` checks a file against its signature and an expected
+//! public key commitment without a client handle, so servers linking this library can
+//! verify too.
+//!
+//! The signed message is the RPO hash of `miden-export:v1:` followed by the file's bytes,
+//! so any change to the file invalidates the signature. Signing is optional: exports are
+//! written unsigned, and the signature covers the file as it is when signed.
+
+use std::{
+    os::raw::c_char,
+    path::{Path, PathBuf},
+};
+
+use miden_client::auth::{SigningInputs, TransactionAuthenticator};
+use miden_objects::{
+    account::{auth::Signature, AccountId},
+    crypto::hash::rpo::Rpo256,
+    utils::{Deserializable, Serializable},
+    Word,
+};
+
+use crate::{
+    get_handle, parse_account_id, parse_required_str, request_blocking,
+    reserves::AUTH_PUB_KEY_SLOT, MidenContext, MidenHandle, Request, ERR_ACCOUNT_OP,
+    ERR_INVALID_HANDLE, ERR_INVALID_PARAM, ERR_LOOKUP,
+};
+
+/// Version tag of the detached signature format
+pub const EXPORT_SIGNATURE_VERSION: u32 = 1;
+
+/// Suffix appended to an export's path for its detached signature
+const SIGNATURE_SUFFIX: &str = ".sig";
+
+fn signature_path(path: &Path) -> PathBuf {
+    let mut signature_path = path.as_os_str().to_os_string();
+    signature_path.push(SIGNATURE_SUFFIX);
+    PathBuf::from(signature_path)
+}
+
+/// Message signed for a file's contents
+fn export_digest(contents: &[u8]) -> Word {
+    let mut message = format!("miden-export:v{}:", EXPORT_SIGNATURE_VERSION).into_bytes();
+    message.extend_from_slice(contents);
+    Rpo256::hash(&message)
+}
+
+pub(crate) fn hex_word(hex_str: &str) -> Result<Word, i32> {
+    let bytes = hex::decode(hex_str.trim_start_matches("0x")).map_err(|_| ERR_INVALID_PARAM)?;
+    Word::read_from_bytes(&bytes).map_err(|_| ERR_INVALID_PARAM)
+}
+
+pub(crate) async fn sign_export_impl(
+    context: &MidenContext,
+    account_id: AccountId,
+    path: PathBuf,
+) -> Result<String, i32> {
+    let contents = std::fs::read(&path).map_err(|_| ERR_INVALID_PARAM)?;
+
+    let record = context.client.get_account(account_id).await
+        .map_err(|_| ERR_LOOKUP)?
+        .ok_or(ERR_LOOKUP)?;
+    let pub_key = record.account().storage().get_item(AUTH_PUB_KEY_SLOT)
+        .map_err(|_| ERR_ACCOUNT_OP)?;
+
+    let digest = export_digest(&contents);
+    let signature = context.authenticator
+        .get_signature(pub_key.into(), &SigningInputs::Blind(digest))
+        .await
+        .map_err(|_| ERR_ACCOUNT_OP)?;
+
+    let detached = serde_json::json!({
+        "version": EXPORT_SIGNATURE_VERSION,
+        "account_id": account_id.to_hex(),
+        "pub_key": hex::encode(pub_key.to_bytes()),
+        "digest": hex::encode(digest.to_bytes()),
+        "signature": hex::encode(signature.to_bytes()),
+    });
+    let signature_path = signature_path(&path);
+    std::fs::write(&signature_path, detached.to_string()).map_err(|_| ERR_INVALID_PARAM)?;
+
+    Ok(signature_path.to_string_lossy().into_owned())
+}
+
+/// Check a file against its detached signature and the expected key
+fn verify_export(path: &Path, expected_pub_key: Word) -> Result<(), i32> {
+    let contents = std::fs::read(path).map_err(|_| ERR_INVALID_PARAM)?;
+    let detached = std::fs::read_to_string(signature_path(path)).map_err(|_| ERR_INVALID_PARAM)?;
+    let detached: serde_json::Value =
+        serde_json::from_str(&detached).map_err(|_| ERR_INVALID_PARAM)?;
+    if detached["version"].as_u64() != Some(u64::from(EXPORT_SIGNATURE_VERSION)) {
+        return Err(ERR_INVALID_PARAM);
+    }
+
+    let pub_key = hex_word(detached["pub_key"].as_str().ok_or(ERR_INVALID_PARAM)?)?;
+    let signature_bytes = detached["signature"].as_str()
+        .and_then(|signature| hex::decode(signature).ok())
+        .ok_or(ERR_INVALID_PARAM)?;
+    let signature = match Signature::read_from_bytes(&signature_bytes) {
+        Ok(Signature::RpoFalcon512(signature)) => signature,
+        #[allow(unreachable_patterns)]
+        Ok(_) => return Err(ERR_ACCOUNT_OP),
+        Err(_) => return Err(ERR_INVALID_PARAM),
+    };
+
+    // The recorded digest is informational; verify against the file as it is now. A Falcon
+    // signature carries its public key; it must be the recorded one.
+    let digest = export_digest(&contents);
+    let public_key = signature.public_key();
+    if pub_key != expected_pub_key
+        || public_key.to_commitment() != pub_key
+        || !public_key.verify(digest, &signature)
+    {
+        return Err(ERR_ACCOUNT_OP);
+    }
+    Ok(())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Sign an export file with an account's key (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Writes the detached signature to `<path>.sig` (overwritten if present):
+/// `{"version":1,"account_id","pub_key","digest","signature"}` (hex fields; `signature`:
+/// serialized signature over `digest`, see the module docs).
+///
+/// # Parameters
+/// - `account_id_hex`: Signing account (C string); its key must be held by this client
+/// - `path`: File to sign, e.g. a `wc_miden_generate_por_report` report (C string)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, or the file could not be read or the signature written
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID, or the account has no key this client can sign with
+/// - -5: Account not in the store
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_sign_export(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    path: *const c_char,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let account_id = match parse_account_id(account_id_hex) {
+        Ok((account_id, _)) => account_id,
+        Err(code) => return code,
+    };
+    let path = match parse_required_str(path) {
+        Ok(path) => PathBuf::from(path),
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::SignExport { account_id, path, reply }) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
+
+/// Verify an export file against its detached signature (`<path>.sig`)
+///
+/// Needs no client handle; safe to call from any thread.
+///
+/// # Parameters
+/// - `path`: Signed file (C string)
+/// - `pub_key_hex`: Expected public key commitment, 32 bytes hex (C string), e.g. the
+///   account's auth key commitment as registered with the server
+///
+/// # Returns
+/// - 0: The file is unchanged and signed by `pub_key_hex`
+/// - -1: Invalid parameters, unreadable file or malformed signature file
+/// - -3: Signature invalid, made by another key, or the file changed since signing
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_verify_export(path: *const c_char, pub_key_hex: *const c_char) -> i32 {
+    let path = match parse_required_str(path) {
+        Ok(path) => PathBuf::from(path),
+        Err(code) => return code,
+    };
+    let expected_pub_key = match parse_required_str(pub_key_hex).and_then(hex_word) {
+        Ok(pub_key) => pub_key,
+        Err(code) => return code,
+    };
+
+    match verify_export(&path, expected_pub_key) {
+        Ok(()) => 0,
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,204 @@
+//! Faucets
+//!
+//! Creating fungible faucet accounts and minting their tokens. Wallet-only apps can leave
+//! this out by building without the `faucets` feature; reading a faucet's metadata (amount
+//! parsing and formatting) does not depend on it.
+
+use std::os::raw::c_char;
+
+use miden_client::{auth::AuthSecretKey, transaction::TransactionRequestBuilder};
+use miden_lib::account::{auth::AuthRpoFalcon512, faucets::BasicFungibleFaucet};
+use miden_objects::{
+    account::{AccountBuilder, AccountComponent, AccountId, AccountStorageMode, AccountType},
+    asset::{FungibleAsset, TokenSymbol},
+    note::NoteType,
+    Felt,
+};
+
+use crate::{
+    check_out_capacity, get_handle, keystore::KeyBackend, last_error, parse_account_id,
+    parse_required_str, parse_seed, proving, request_blocking,
+    types::WcNoteType,
+    write_out_buffer, MidenContext, MidenHandle, Request, ERR_ACCOUNT_OP, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM, ERR_NOTE_OP, ERR_TX_SUBMIT, ID_HEX_LEN,
+};
+
+/// Create a public fungible faucet account with its own Falcon key
+pub(crate) async fn create_faucet_impl(
+    context: &mut MidenContext,
+    init_seed: [u8; 32],
+    symbol: &str,
+    decimals: u8,
+    max_supply: u64,
+) -> Result<String, i32> {
+    let symbol = TokenSymbol::new(symbol).map_err(|_| ERR_INVALID_PARAM)?;
+    let faucet_component = BasicFungibleFaucet::new(symbol, decimals, Felt::new(max_supply))
+        .map_err(|_| ERR_INVALID_PARAM)?;
+
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+    let auth_component: AccountComponent =
+        AuthRpoFalcon512::new(key_pair.public_key().to_commitment()).into();
+
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::FungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(auth_component)
+        .with_component(faucet_component)
+        .build()
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    // Save key to keystore
+    context.keystore.add_key(&key_pair, KeyBackend::Filesystem)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    // Add account to client
+    context.client.add_account(&account, false).await
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    Ok(account.id().to_hex())
+}
+
+/// Mint fungible assets from a local faucet into a P2ID note for `target`
+///
+/// Issuance is not a withdrawal, so whitelists and screening do not apply.
+pub(crate) async fn mint_impl(
+    context: &mut MidenContext,
+    faucet_id: AccountId,
+    target: AccountId,
+    amount: u64,
+    note_type: NoteType,
+) -> Result<String, i32> {
+    if !faucet_id.is_faucet() {
+        return Err(ERR_ACCOUNT_OP);
+    }
+    let asset = FungibleAsset::new(faucet_id, amount).map_err(|_| ERR_INVALID_PARAM)?;
+    let tx_request = TransactionRequestBuilder::new()
+        .build_mint_fungible_asset(asset, target, note_type, context.client.rng())
+        .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?;
+
+    proving::check_memory(context)?;
+    let tx_id = context.client.submit_new_transaction(faucet_id, tx_request).await
+        .map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))?;
+    Ok(tx_id.to_hex())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Create a fungible faucet account (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// The faucet is a public `BasicFungibleFaucet` account whose Falcon key is stored in the
+/// keystore, so this client can mint its token.
+///
+/// # Parameters
+/// - `seed_ptr` / `seed_len`: Optional 32-byte account seed (NULL for a random seed)
+/// - `symbol`: Token symbol, 1-6 uppercase letters (C string)
+/// - `decimals`: Token decimals (at most 12)
+/// - `max_supply`: Maximum supply in base units (must be > 0 and fit a fungible asset)
+/// - `account_id_out` / `account_id_out_len`: Output buffer for the faucet account ID (hex)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Account creation failed
+/// - -7: Output buffer too small
+#[allow(clippy::too_many_arguments)]
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_create_faucet(
+    handle: MidenHandle,
+    seed_ptr: *const u8,
+    seed_len: usize,
+    symbol: *const c_char,
+    decimals: u8,
+    max_supply: u64,
+    account_id_out: *mut u8,
+    account_id_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if max_supply == 0 {
+        return ERR_INVALID_PARAM;
+    }
+    let seed = match parse_seed(seed_ptr, seed_len) {
+        Ok(seed) => seed,
+        Err(code) => return code,
+    };
+    let symbol = match parse_required_str(symbol) {
+        Ok(symbol) => symbol.to_string(),
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::CreateFaucet { seed, symbol, decimals, max_supply, reply };
+    match request_blocking(&worker, request) {
+        Ok(account_id_hex) => write_out_buffer(&account_id_hex, account_id_out, account_id_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Mint tokens from a local faucet to an account (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+/// NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+///
+/// The minted amount is delivered in a P2ID note that the target consumes like any
+/// other received payment.
+///
+/// # Parameters
+/// - `faucet_id_hex`: Faucet account created by this client (C string)
+/// - `target_account_id_hex`: Receiving account ID (C string)
+/// - `amount`: Amount in base units (must be > 0)
+/// - `note_type`: `WcNoteType` value (0 = public, 1 = private)
+/// - `tx_id_out` / `tx_id_out_len`: Output buffer for the transaction ID (hex, 66 bytes)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters (including an unknown note type)
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID, or the faucet ID is not a faucet
+/// - -4: Mint request construction failed
+/// - -6: Transaction submission failed (e.g. max supply exceeded, key not in keystore)
+/// - -7: Output buffer too small, checked before anything is submitted
+/// - -8: Queue full
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_mint(
+    handle: MidenHandle,
+    faucet_id_hex: *const c_char,
+    target_account_id_hex: *const c_char,
+    amount: u64,
+    note_type: u32,
+    tx_id_out: *mut u8,
+    tx_id_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if amount == 0 {
+        return ERR_INVALID_PARAM;
+    }
+    if let Err(code) = check_out_capacity(tx_id_out, tx_id_out_len, ID_HEX_LEN) {
+        return code;
+    }
+    let note_type: NoteType = match WcNoteType::try_from(note_type) {
+        Ok(note_type) => note_type.into(),
+        Err(code) => return code,
+    };
+    let (faucet_id, target) =
+        match (parse_account_id(faucet_id_hex), parse_account_id(target_account_id_hex)) {
+            (Ok((faucet_id, _)), Ok((target, _))) => (faucet_id, target),
+            (Err(code), _) | (_, Err(code)) => return code,
+        };
+
+    let request = |reply| Request::Mint { faucet_id, target, amount, note_type, reply };
+    match request_blocking(&worker, request) {
+        Ok(tx_id) => write_out_buffer(&tx_id, tx_id_out, tx_id_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,69 @@
+//! Handle registry
+//!
+//! Client handles used to be raw pointers to a boxed `MidenWorkerHandle`: a call racing
+//! `wc_miden_destroy` on another thread, or any call with a stale copy of a destroyed
+//! handle, dereferenced freed memory. Handles are now opaque `u64` values looked up in a
+//! process-wide registry:
+//!
+//! - the low 32 bits are the slot index plus one, the high 32 bits the slot's generation
+//! - destroying a handle empties its slot and bumps the generation, so the old value (and
+//!   any copy of it) no longer resolves and calls return -2 (invalid handle). A reused
+//!   slot hands out a different value
+//! - a lookup returns an `Arc`, so a call that resolved the handle before it was
+//!   destroyed keeps the worker handle alive until it returns (its request then fails
+//!   with -2 once the worker is gone)
+//!
+//! 0 is never a valid handle and plays the role of NULL.
+
+use std::sync::{Arc, Mutex};
+
+use crate::{MidenHandle, MidenWorkerHandle};
+
+struct Slot {
+    generation: u32,
+    worker: Option<Arc<MidenWorkerHandle>>,
+}
+
+/// Slots of all handles of the process (emptied slots are reused)
+static SLOTS: Mutex<Vec<Slot>> = Mutex::new(Vec::new());
+
+fn encode(index: usize, generation: u32) -> MidenHandle {
+    (u64::from(generation) << 32) | (index as u64 + 1)
+}
+
+/// Slot index and generation of a handle (None for 0)
+fn decode(handle: MidenHandle) -> Option<(usize, u32)> {
+    let index = (handle & u64::from(u32::MAX)).checked_sub(1)?;
+    Some((index as usize, (handle >> 32) as u32))
+}
+
+/// Register a worker handle and return its opaque handle
+pub(crate) fn insert(worker: MidenWorkerHandle) -> MidenHandle {
+    let worker = Some(Arc::new(worker));
+    let mut slots = SLOTS.lock().unwrap();
+
+    if let Some(index) = slots.iter().position(|slot| slot.worker.is_none()) {
+        slots[index].worker = worker;
+        return encode(index, slots[index].generation);
+    }
+    slots.push(Slot { generation: 0, worker });
+    encode(slots.len() - 1, 0)
+}
+
+/// Worker handle of a live handle (None when unknown or destroyed)
+pub(crate) fn get(handle: MidenHandle) -> Option<Arc<MidenWorkerHandle>> {
+    let (index, generation) = decode(handle)?;
+    let slots = SLOTS.lock().unwrap();
+    let slot = slots.get(index).filter(|slot| slot.generation == generation)?;
+    slot.worker.clone()
+}
+
+/// Unregister a handle, returning its worker handle (None when already destroyed)
+pub(crate) fn remove(handle: MidenHandle) -> Option<Arc<MidenWorkerHandle>> {
+    let (index, generation) = decode(handle)?;
+    let mut slots = SLOTS.lock().unwrap();
+    let slot = slots.get_mut(index).filter(|slot| slot.generation == generation)?;
+    let worker = slot.worker.take()?;
+    slot.generation = slot.generation.wrapping_add(1);
+    Some(worker)
+}
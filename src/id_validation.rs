@@ -0,0 +1,181 @@
+//! Account and note ID validation
+//!
+//! Checks user input (pasted or scanned IDs) before it reaches a transaction, and says
+//! what is wrong with it so the app can show "missing 0x" rather than a generic error.
+//! The FFI takes IDs as `0x` hex: an account ID is 30 hex digits, a note ID 64.
+//!
+//! Hex IDs carry no checksum, so a mistyped digit is only caught when it breaks the ID's
+//! structure. Bech32 addresses (`mtst1...`) do carry one: they are recognized and their
+//! checksum verified, so a mistyped address is reported as such, and an intact address is
+//! reported as `Bech32Address` because the FFI does not take that form.
+
+use std::{ffi::CStr, os::raw::c_char};
+
+use miden_objects::{account::AccountId, note::NoteId};
+
+use crate::{types::WcIdValidation, ERR_ACCOUNT_OP, ERR_INVALID_PARAM, ERR_NOTE_OP};
+
+/// Hex digits of an account ID after the `0x` prefix
+const ACCOUNT_ID_HEX_DIGITS: usize = 30;
+
+/// Hex digits of a note ID after the `0x` prefix
+const NOTE_ID_HEX_DIGITS: usize = 64;
+
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Polymod residue of a valid bech32m string
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+
+/// Length of the bech32 checksum in characters
+const BECH32_CHECKSUM_LEN: usize = 6;
+
+fn bech32_polymod(values: impl Iterator<Item = u8>) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
+    values.fold(1u32, |chk, value| {
+        let top = chk >> 25;
+        let chk = ((chk & 0x1ff_ffff) << 5) ^ u32::from(value);
+        GENERATOR
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| (top >> i) & 1 == 1)
+            .fold(chk, |chk, (_, g)| chk ^ g)
+    })
+}
+
+/// Check a string that looks like a bech32 address (`<hrp>1<data><checksum>`)
+fn check_bech32(input: &str) -> WcIdValidation {
+    // Mixed case is never valid bech32
+    if input.chars().any(|c| c.is_ascii_uppercase())
+        && input.chars().any(|c| c.is_ascii_lowercase())
+    {
+        return WcIdValidation::InvalidCharacter;
+    }
+    let input = input.to_ascii_lowercase();
+    let Some((hrp, data)) = input.rsplit_once('1') else {
+        return WcIdValidation::InvalidCharacter;
+    };
+    if hrp.is_empty() || !hrp.bytes().all(|b| (33..=126).contains(&b)) {
+        return WcIdValidation::InvalidCharacter;
+    }
+
+    let Some(data) = data
+        .bytes()
+        .map(|b| BECH32_CHARSET.iter().position(|c| *c == b).map(|v| v as u8))
+        .collect::<Option<Vec<u8>>>()
+    else {
+        return WcIdValidation::InvalidCharacter;
+    };
+    if data.len() <= BECH32_CHECKSUM_LEN {
+        return WcIdValidation::WrongLength;
+    }
+
+    let expanded_hrp = hrp.bytes().map(|b| b >> 5)
+        .chain(std::iter::once(0))
+        .chain(hrp.bytes().map(|b| b & 0x1f));
+    if bech32_polymod(expanded_hrp.chain(data)) != BECH32M_CONST {
+        return WcIdValidation::BadChecksum;
+    }
+    WcIdValidation::Bech32Address
+}
+
+/// Check an ID string; `parse` decides whether well-formed hex is an actual ID
+fn check_id(input: &str, hex_digits: usize, parse: impl Fn(&str) -> bool) -> WcIdValidation {
+    if input.is_empty() {
+        return WcIdValidation::Empty;
+    }
+
+    if let Some(digits) = input.strip_prefix("0x").or_else(|| input.strip_prefix("0X")) {
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return WcIdValidation::InvalidCharacter;
+        }
+        if digits.len() != hex_digits {
+            return WcIdValidation::WrongLength;
+        }
+        return if parse(input) { WcIdValidation::Valid } else { WcIdValidation::InvalidId };
+    }
+
+    if input.chars().all(|c| c.is_ascii_hexdigit()) {
+        return WcIdValidation::MissingPrefix;
+    }
+    if input.contains('1') && input.is_ascii() {
+        return check_bech32(input);
+    }
+    WcIdValidation::InvalidCharacter
+}
+
+pub(crate) fn check_account_id(input: &str) -> WcIdValidation {
+    check_id(input, ACCOUNT_ID_HEX_DIGITS, |hex| AccountId::from_hex(hex).is_ok())
+}
+
+pub(crate) fn check_note_id(input: &str) -> WcIdValidation {
+    check_id(input, NOTE_ID_HEX_DIGITS, |hex| NoteId::try_from_hex(hex).is_ok())
+}
+
+/// Run a check on a C string and report it through `reason_out`
+fn validate(
+    input: *const c_char,
+    reason_out: *mut WcIdValidation,
+    check: fn(&str) -> WcIdValidation,
+    invalid_code: i32,
+) -> i32 {
+    if input.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+    let reason = match unsafe { CStr::from_ptr(input) }.to_str() {
+        Ok(input) => check(input),
+        Err(_) => WcIdValidation::InvalidCharacter,
+    };
+
+    if !reason_out.is_null() {
+        unsafe { *reason_out = reason };
+    }
+    if reason == WcIdValidation::Valid { 0 } else { invalid_code }
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Check that a string is an account ID the FFI accepts
+///
+/// Needs no client handle and does no I/O; safe to call from the main thread, e.g. on
+/// every keystroke. A valid ID may still be unknown to the node.
+///
+/// # Parameters
+/// - `account_id`: Account ID to check (C string)
+/// - `reason_out`: Result of the check (`WcIdValidation_Valid` or why it was rejected);
+///   may be NULL
+///
+/// # Returns
+/// - 0: Valid
+/// - -1: Invalid parameters (NULL string)
+/// - -3: Not a valid account ID; see `reason_out`
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_validate_account_id(
+    account_id: *const c_char,
+    reason_out: *mut WcIdValidation,
+) -> i32 {
+    validate(account_id, reason_out, check_account_id, ERR_ACCOUNT_OP)
+}
+
+/// Check that a string is a note ID the FFI accepts
+///
+/// Needs no client handle and does no I/O; safe to call from the main thread. A valid ID
+/// may still be unknown to the store and the node.
+///
+/// # Parameters
+/// - `note_id`: Note ID to check (C string)
+/// - `reason_out`: Result of the check (`WcIdValidation_Valid` or why it was rejected);
+///   may be NULL
+///
+/// # Returns
+/// - 0: Valid
+/// - -1: Invalid parameters (NULL string)
+/// - -4: Not a valid note ID; see `reason_out`
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_validate_note_id(
+    note_id: *const c_char,
+    reason_out: *mut WcIdValidation,
+) -> i32 {
+    validate(note_id, reason_out, check_note_id, ERR_NOTE_OP)
+}
@@ -0,0 +1,104 @@
+//! Note inclusion proof export
+//!
+//! A recipient can hand a third party cryptographic evidence that a payment was made at
+//! block N: the package contains the note's metadata, its inclusion proof (path in the
+//! block's note tree) and the header of that block, whose note root the path opens to.
+//! The third party checks the header against a node or explorer it trusts. Headers are
+//! fetched once per note and block (see proof_cache.rs).
+
+use std::os::raw::c_char;
+
+use miden_client::rpc::NodeRpcClient;
+use miden_objects::{note::NoteId, utils::Serializable};
+
+use crate::{
+    get_handle, parse_required_str, proof_cache, request_blocking, write_out_buffer, MidenContext,
+    MidenHandle, Request, ERR_INVALID_HANDLE, ERR_LOOKUP, ERR_NOTE_OP,
+};
+
+/// Version tag of the inclusion proof package format
+pub const NOTE_PROOF_PACKAGE_VERSION: u32 = 1;
+
+pub(crate) async fn export_note_inclusion_proof_impl(
+    context: &MidenContext,
+    note_id: NoteId,
+) -> Result<Vec<u8>, i32> {
+    let record = context.client.get_input_note(note_id).await
+        .map_err(|_| ERR_NOTE_OP)?
+        .ok_or(ERR_LOOKUP)?;
+
+    // Notes that are not committed yet have no proof
+    let proof = record.inclusion_proof().ok_or(ERR_NOTE_OP)?;
+    let metadata = record.metadata().ok_or(ERR_NOTE_OP)?;
+    let block_num = proof.location().block_num();
+
+    let cached = proof_cache::get(&context.database_path, note_id, block_num.as_u32());
+    let (proof, header) = match cached {
+        Some(cached) => cached,
+        None => {
+            let (header, _) = context.rpc.get_block_header_by_number(Some(block_num), false).await
+                .map_err(|_| ERR_LOOKUP)?;
+            proof_cache::put(&context.database_path, note_id, proof, &header);
+            (proof.clone(), header)
+        }
+    };
+
+    let package = serde_json::json!({
+        "version": NOTE_PROOF_PACKAGE_VERSION,
+        "note_id": note_id.to_hex(),
+        "block_num": block_num.as_u32(),
+        "metadata": hex::encode(metadata.to_bytes()),
+        "inclusion_proof": hex::encode(proof.to_bytes()),
+        "block_header": hex::encode(header.to_bytes()),
+    });
+
+    Ok(package.to_string().into_bytes())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Export a note's inclusion proof together with its block header (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `note_id_hex`: Input note ID (C string)
+/// - `bytes_out` / `bytes_out_len`: Output buffer for the package
+///
+/// # Output
+/// JSON `{"version":1,"note_id","block_num","metadata","inclusion_proof","block_header"}`
+/// with the last three fields hex-encoded in the miden serialization format.
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -4: Invalid note ID, or the note is not committed yet
+/// - -5: Note or block header not found
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_export_note_inclusion_proof(
+    handle: MidenHandle,
+    note_id_hex: *const c_char,
+    bytes_out: *mut u8,
+    bytes_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let note_id = match parse_required_str(note_id_hex) {
+        Ok(s) => match NoteId::try_from_hex(s) {
+            Ok(id) => id,
+            Err(_) => return ERR_NOTE_OP,
+        },
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::ExportNoteInclusionProof { note_id, reply }) {
+        Ok(package) => write_out_buffer(&package, bytes_out, bytes_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,120 @@
+//! Input notes by store state
+//!
+//! `wc_miden_get_input_notes` lists consumable notes only, so a payment the app learned
+//! about (a push, an imported note file) stays invisible until its block is synced. The
+//! filtered listing selects notes with the store's note filters instead, and reports each
+//! note's state: `expected` (announced, not yet in a block), `committed`, `processing`
+//! (being consumed by a submitted local transaction) or `consumed`.
+//!
+//! With an account, a non-consumable selection keeps the P2ID/P2IDE notes addressed to
+//! that account; without one, notes addressed to archived accounts are left out.
+
+use std::os::raw::c_char;
+
+use miden_client::store::InputNoteRecord;
+use miden_objects::account::AccountId;
+
+use crate::{
+    get_handle, get_input_notes_impl, json, note_summary, parse_optional_account_id,
+    request_blocking,
+    types::WcNoteFilter,
+    write_out_buffer, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE, ERR_NOTE_OP,
+};
+
+pub(crate) fn state_str(record: &InputNoteRecord) -> &'static str {
+    if record.is_consumed() {
+        "consumed"
+    } else if record.is_processing() {
+        "processing"
+    } else if record.inclusion_proof().is_some() {
+        "committed"
+    } else {
+        "expected"
+    }
+}
+
+pub(crate) async fn get_input_notes_filtered_impl(
+    context: &MidenContext,
+    account_id: Option<AccountId>,
+    filter: WcNoteFilter,
+) -> Result<String, i32> {
+    let Some(store_filter) = filter.store_filter() else {
+        return get_input_notes_impl(&context.read_view(), account_id).await;
+    };
+
+    let records = context.client.get_input_notes(store_filter).await
+        .map_err(|_| ERR_NOTE_OP)?;
+
+    let notes: Vec<json::InputNoteJson> = records
+        .iter()
+        .filter(|record| {
+            let target = note_summary::target_account(record.details().recipient());
+            match account_id {
+                Some(account_id) => target == Some(account_id),
+                None => !target.is_some_and(|target| context.archive.contains(target)),
+            }
+        })
+        .map(|record| json::InputNoteJson {
+            note_id: record.id().to_hex(),
+            assets: json::AssetJson::fungible(record.assets().iter()),
+            is_authenticated: record.is_authenticated(),
+            timestamp: record.inclusion_proof().and_then(|proof| {
+                context.block_times.timestamp(proof.location().block_num().as_u32())
+            }),
+            state: Some(state_str(record)),
+        })
+        .collect();
+
+    Ok(json::to_string(&json::InputNotesJson { total_count: notes.len(), notes }))
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Get input notes selected by store state (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_id_hex`: Only notes for this account (C string, NULL = all active accounts)
+/// - `filter`: `WcNoteFilter` value (0 = consumable, as `wc_miden_get_input_notes`)
+/// - `json_out` / `json_out_len`: Output buffer for the `wc_miden_get_input_notes` JSON;
+///   each note also carries `"state"`: `"expected"`, `"committed"`, `"processing"` or
+///   `"consumed"` (except with the consumable filter)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters (including an unknown filter)
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID
+/// - -4: Note query failed
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_input_notes_filtered(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    filter: u32,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let filter = match WcNoteFilter::try_from(filter) {
+        Ok(filter) => filter,
+        Err(code) => return code,
+    };
+    let account_id = match parse_optional_account_id(account_id_hex) {
+        Ok(account_id) => account_id,
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::GetInputNotesFiltered { account_id, filter, reply };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
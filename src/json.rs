@@ -0,0 +1,187 @@
+//! JSON shapes returned over the FFI
+//!
+//! Output that used to be assembled with `format!` is declared here as serde structs, so
+//! string fields are always escaped and adding a field is a one-line change. Field order
+//! (and therefore the serialized output) matches the hand-written JSON it replaces.
+//!
+//! Every JSON object or array leaving the library (output buffers, callbacks, events)
+//! passes through `prepare_output`, which applies the process-wide output modes: string
+//! amounts (see amount.rs) and canonical form. Canonical mode
+//! (`wc_miden_set_canonical_json`) writes one canonical form, so apps can hash or sign
+//! outputs and keep stable snapshots:
+//!
+//! - object keys sorted by their UTF-8 bytes, at every level
+//! - no whitespace
+//! - integers in plain decimal; floats with an integral value (e.g. `2.0`) written as
+//!   integers, other floats in their shortest round-trip form
+//!
+//! Non-JSON outputs (hex IDs, binary payloads) are passed through unchanged.
+
+use std::{
+    borrow::Cow,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use miden_objects::asset::Asset;
+use serde::Serialize;
+
+use crate::amount;
+
+/// Whether outputs are rewritten into canonical form (process-wide)
+static CANONICAL: AtomicBool = AtomicBool::new(false);
+
+/// Largest integer an f64 represents exactly (2^53)
+const MAX_EXACT_FLOAT_INT: f64 = 9_007_199_254_740_992.0;
+
+/// Fungible asset: `{"faucet_id":"0x..","amount":100}`
+#[derive(Clone, Serialize)]
+pub(crate) struct AssetJson {
+    pub faucet_id: String,
+    pub amount: u64,
+}
+
+impl AssetJson {
+    /// The fungible assets of a note or vault (non-fungible assets are skipped)
+    pub fn fungible<'a>(assets: impl Iterator<Item = &'a Asset>) -> Vec<Self> {
+        assets
+            .filter_map(|asset| match asset {
+                Asset::Fungible(fungible) => Some(AssetJson {
+                    faucet_id: fungible.faucet_id().to_hex(),
+                    amount: fungible.amount(),
+                }),
+                Asset::NonFungible(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// Result of `wc_miden_get_balance`
+#[derive(Serialize)]
+pub(crate) struct BalanceJson {
+    pub account_id: String,
+    pub fungible_assets: Vec<AssetJson>,
+    pub total_fungible_count: usize,
+    pub total_non_fungible_count: usize,
+}
+
+/// Consumable note in `wc_miden_get_input_notes`
+#[derive(Serialize)]
+pub(crate) struct InputNoteJson {
+    pub note_id: String,
+    pub assets: Vec<AssetJson>,
+    pub is_authenticated: bool,
+    /// ISO-8601 time of the inclusion block (null if not committed or not backfilled yet)
+    pub timestamp: Option<String>,
+    /// Store state, in filtered listings only (`wc_miden_get_input_notes_filtered`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<&'static str>,
+}
+
+/// Result of `wc_miden_get_input_notes`
+#[derive(Serialize)]
+pub(crate) struct InputNotesJson {
+    pub notes: Vec<InputNoteJson>,
+    pub total_count: usize,
+}
+
+/// Committed incoming note in `wc_miden_scan_deposits`
+#[derive(Clone, Serialize)]
+pub(crate) struct DepositJson {
+    pub note_id: String,
+    pub tag: u32,
+    pub block_num: u32,
+    pub timestamp: Option<String>,
+    pub sender: String,
+    pub assets: Vec<AssetJson>,
+    pub is_consumed: bool,
+}
+
+/// Result page of `wc_miden_scan_deposits`
+#[derive(Serialize)]
+pub(crate) struct DepositsPageJson {
+    pub deposits: Vec<DepositJson>,
+    pub count: usize,
+    pub has_more: bool,
+    pub next_cursor: Option<String>,
+}
+
+/// Serialize an output value (these shapes cannot fail to serialize)
+pub(crate) fn to_string(value: &impl Serialize) -> String {
+    serde_json::to_string(value).expect("FFI JSON shapes always serialize")
+}
+
+/// Bytes of an output with the output modes applied (unchanged when none is on)
+pub(crate) fn prepare_output(bytes: &[u8]) -> Cow<'_, [u8]> {
+    let canonical = CANONICAL.load(Ordering::Relaxed);
+    let string_amounts = amount::string_amounts();
+    if !(canonical || string_amounts) || !matches!(bytes.first(), Some(b'{' | b'[')) {
+        return Cow::Borrowed(bytes);
+    }
+    let Ok(mut value) = serde_json::from_slice::<serde_json::Value>(bytes) else {
+        return Cow::Borrowed(bytes);
+    };
+
+    if string_amounts {
+        amount::stringify(&mut value);
+    }
+    if !canonical {
+        return Cow::Owned(value.to_string().into_bytes());
+    }
+    let mut out = String::with_capacity(bytes.len());
+    write_canonical(&value, &mut out);
+    Cow::Owned(out.into_bytes())
+}
+
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::from(key.as_str()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        serde_json::Value::Number(number) => match number.as_f64() {
+            Some(float)
+                if number.is_f64() && float.fract() == 0.0 && float.abs() < MAX_EXACT_FLOAT_INT =>
+            {
+                out.push_str(&(float as i64).to_string());
+            }
+            _ => out.push_str(&number.to_string()),
+        },
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Turn canonical JSON output on or off (process-wide)
+///
+/// See the module docs for the canonical form. Off by default.
+///
+/// # Returns
+/// - 0: Success
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_set_canonical_json(enabled: bool) -> i32 {
+    CANONICAL.store(enabled, Ordering::Relaxed);
+    0
+}
@@ -0,0 +1,259 @@
+//! Encrypted key backups
+//!
+//! `wc_miden_export_key` wraps the Falcon secret key of an account in a passphrase
+//! protected envelope the app can keep outside the keystore (iCloud Keychain, a file the
+//! user saves); `wc_miden_import_key` unwraps it into the filesystem keystore of any store,
+//! e.g. on a new device before the account itself is imported.
+//!
+//! The envelope is JSON: `{"version":2,"account_id","pub_key","salt","kdf","nonce",
+//! "ciphertext"}` (hex fields). The encryption key is derived from the passphrase with
+//! Argon2id, with the parameters in `kdf` (see passphrase.rs), and the secret key is
+//! sealed with ChaCha20-Poly1305; the account ID and public key commitment are
+//! authenticated data, so an envelope cannot be relabeled for another account. Version 1
+//! envelopes (`"rounds"` of salted SHA3-256 instead of `kdf`) are still imported. Only keys
+//! held by this client (filesystem or memory backend) can be exported; external signers
+//! keep theirs.
+
+use std::os::raw::c_char;
+
+use chacha20poly1305::{
+    aead::{Aead, Payload},
+    ChaCha20Poly1305, Nonce,
+};
+use miden_client::auth::AuthSecretKey;
+use miden_objects::{
+    account::AccountId,
+    utils::{Deserializable, Serializable},
+    Word,
+};
+use rand::RngCore;
+
+use crate::{
+    get_handle,
+    keystore::KeyBackend,
+    last_error, parse_account_id, parse_required_str,
+    passphrase::{Kdf, KdfParams},
+    request_blocking,
+    reserves::AUTH_PUB_KEY_SLOT,
+    write_out_buffer, MidenContext, MidenHandle, Request, ERR_ACCOUNT_OP, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM, ERR_LOOKUP, ERR_PERMISSION_DENIED,
+};
+
+/// Version tag of the key envelope format
+pub const KEY_ENVELOPE_VERSION: u32 = 2;
+
+/// Version tag of envelopes keyed with iterated SHA3-256, still imported
+const LEGACY_KEY_ENVELOPE_VERSION: u32 = 1;
+
+fn cipher(kdf: Kdf, salt: &[u8; 16], passphrase: &str) -> ChaCha20Poly1305 {
+    let domain = match kdf {
+        Kdf::Argon2id(_) => "miden-key-envelope:v2:",
+        Kdf::LegacySha3 { .. } => "miden-key-envelope:v1:",
+    };
+    kdf.cipher(domain, salt, passphrase)
+}
+
+/// Authenticated data binding an envelope to its account and key
+fn envelope_aad(account_id: AccountId, pub_key: Word) -> String {
+    format!("{}:{}", account_id.to_hex(), hex::encode(pub_key.to_bytes()))
+}
+
+pub(crate) async fn export_key_impl(
+    context: &MidenContext,
+    account_id: AccountId,
+    passphrase: String,
+) -> Result<String, i32> {
+    let record = context.client.get_account(account_id).await
+        .map_err(|e| last_error::detail(ERR_LOOKUP, e))?
+        .ok_or(ERR_LOOKUP)?;
+    let pub_key = record.account().storage().get_item(AUTH_PUB_KEY_SLOT)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    let key = context.keystore.secret_key(pub_key)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?
+        .ok_or_else(|| {
+            let message = format!("no local key for account {}", account_id.to_hex());
+            last_error::detail(ERR_ACCOUNT_OP, message)
+        })?;
+
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    rand::rng().fill_bytes(&mut salt);
+    rand::rng().fill_bytes(&mut nonce);
+
+    let plaintext = key.to_bytes();
+    let aad = envelope_aad(account_id, pub_key);
+    let params = KdfParams::default();
+    let ciphertext = cipher(Kdf::Argon2id(params), &salt, &passphrase)
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: &plaintext, aad: aad.as_bytes() })
+        .map_err(|_| ERR_ACCOUNT_OP)?;
+
+    Ok(serde_json::json!({
+        "version": KEY_ENVELOPE_VERSION,
+        "account_id": account_id.to_hex(),
+        "pub_key": hex::encode(pub_key.to_bytes()),
+        "salt": hex::encode(salt),
+        "kdf": params.to_json(),
+        "nonce": hex::encode(nonce),
+        "ciphertext": hex::encode(ciphertext),
+    })
+    .to_string())
+}
+
+/// Open an envelope, returning its account and secret key
+fn open_envelope(envelope: &[u8], passphrase: &str) -> Result<(AccountId, AuthSecretKey), i32> {
+    let value: serde_json::Value = serde_json::from_slice(envelope).map_err(|_| ERR_INVALID_PARAM)?;
+    // Parameters out of bounds are rejected before any work is done
+    let kdf = match value["version"].as_u64().and_then(|version| u32::try_from(version).ok()) {
+        Some(KEY_ENVELOPE_VERSION) => KdfParams::from_json(&value["kdf"]).map(Kdf::Argon2id),
+        Some(LEGACY_KEY_ENVELOPE_VERSION) => Kdf::legacy(&value["rounds"]),
+        _ => None,
+    }
+    .ok_or(ERR_INVALID_PARAM)?;
+    let hex_field = |name: &str| -> Result<Vec<u8>, i32> {
+        let field = value[name].as_str().ok_or(ERR_INVALID_PARAM)?;
+        hex::decode(field.trim_start_matches("0x")).map_err(|_| ERR_INVALID_PARAM)
+    };
+
+    let account_id = value["account_id"].as_str()
+        .and_then(|s| AccountId::from_hex(s).ok())
+        .ok_or(ERR_INVALID_PARAM)?;
+    let pub_key = Word::read_from_bytes(&hex_field("pub_key")?).map_err(|_| ERR_INVALID_PARAM)?;
+    let salt: [u8; 16] = hex_field("salt")?.try_into().map_err(|_| ERR_INVALID_PARAM)?;
+    let nonce: [u8; 12] = hex_field("nonce")?.try_into().map_err(|_| ERR_INVALID_PARAM)?;
+    let ciphertext = hex_field("ciphertext")?;
+
+    // Authentication fails for a wrong passphrase and for a tampered envelope alike
+    let aad = envelope_aad(account_id, pub_key);
+    let plaintext = cipher(kdf, &salt, passphrase)
+        .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: aad.as_bytes() })
+        .map_err(|_| last_error::detail(ERR_PERMISSION_DENIED, "wrong passphrase"))?;
+
+    let key = AuthSecretKey::read_from_bytes(&plaintext).map_err(|_| ERR_INVALID_PARAM)?;
+    let key_commitment: Word = key.public_key().to_commitment().into();
+    if key_commitment != pub_key {
+        return Err(ERR_INVALID_PARAM);
+    }
+    Ok((account_id, key))
+}
+
+pub(crate) fn import_key_impl(
+    context: &MidenContext,
+    envelope: Vec<u8>,
+    passphrase: String,
+) -> Result<String, i32> {
+    let (account_id, key) = open_envelope(&envelope, &passphrase)?;
+
+    context.keystore.add_key(&key, KeyBackend::Filesystem)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    context.keystore.record_account(account_id, KeyBackend::Filesystem)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    Ok(account_id.to_hex())
+}
+
+/// Read the passphrase argument (must not be empty)
+fn parse_passphrase(passphrase: *const c_char) -> Result<String, i32> {
+    match parse_required_str(passphrase) {
+        Ok(passphrase) if !passphrase.is_empty() => Ok(passphrase.to_string()),
+        _ => Err(ERR_INVALID_PARAM),
+    }
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Export an account's secret key in a passphrase-encrypted envelope (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread. Deriving the
+/// encryption key from the passphrase takes a noticeable moment by design.
+///
+/// # Parameters
+/// - `account_id_hex`: Account ID (C string); its key must be held by this client
+/// - `passphrase`: Passphrase protecting the envelope (C string, not empty)
+/// - `envelope_out` / `envelope_out_len`: Output buffer for the envelope JSON (see the
+///   module docs; about 5 KB)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID, or the key is held by an external signer or missing
+/// - -5: Account not in the store
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_export_key(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    passphrase: *const c_char,
+    envelope_out: *mut u8,
+    envelope_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let account_id = match parse_account_id(account_id_hex) {
+        Ok((account_id, _)) => account_id,
+        Err(code) => return code,
+    };
+    let passphrase = match parse_passphrase(passphrase) {
+        Ok(passphrase) => passphrase,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::ExportKey { account_id, passphrase, reply }) {
+        Ok(envelope) => write_out_buffer(&envelope, envelope_out, envelope_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Import a secret key from an envelope made by `wc_miden_export_key` (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// The key is saved to the filesystem keystore and recorded for the envelope's account,
+/// which does not need to be in the store yet (import it afterwards to sign with it).
+///
+/// # Parameters
+/// - `envelope_ptr` / `envelope_len`: Envelope JSON
+/// - `passphrase`: Passphrase the envelope was exported with (C string)
+/// - `account_id_out` / `account_id_out_len`: Output buffer for the account ID (hex)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or a malformed envelope
+/// - -2: Invalid handle or worker closed
+/// - -3: The key could not be saved
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+/// - -101: Wrong passphrase (or a tampered envelope)
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_import_key(
+    handle: MidenHandle,
+    envelope_ptr: *const u8,
+    envelope_len: usize,
+    passphrase: *const c_char,
+    account_id_out: *mut u8,
+    account_id_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if envelope_ptr.is_null() || envelope_len == 0 {
+        return ERR_INVALID_PARAM;
+    }
+    let envelope = unsafe { std::slice::from_raw_parts(envelope_ptr, envelope_len) }.to_vec();
+    let passphrase = match parse_passphrase(passphrase) {
+        Ok(passphrase) => passphrase,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::ImportKey { envelope, passphrase, reply }) {
+        Ok(account_id) => write_out_buffer(&account_id, account_id_out, account_id_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,298 @@
+//! Auth key rotation
+//!
+//! The standard Falcon auth component reads the account's public key commitment from
+//! storage slot 0 but has no procedure that changes it, so the key of a wallet is fixed
+//! when the wallet is created. Wallets created with `wc_miden_create_rotatable_wallet`
+//! have updatable code (`RegularAccountUpdatableCode`) and an auth component that also
+//! exports `rotate_auth_key`, which writes a new commitment to the slot. Its auth
+//! procedure checks the signature against the key the account had when the transaction
+//! started, so a rotation is signed with the old key and later transactions with the new
+//! one.
+//!
+//! `wc_miden_rotate_auth_key` generates a new Falcon key in the account's backend, submits
+//! the rotation transaction and retires the old key in the keystore (see keystore.rs): the
+//! key stays in its backend but no longer signs. If the rotation transaction is discarded,
+//! the account is back on the retired key; rotating again reactivates it to sign the new
+//! rotation. Keys held by an external signer cannot be rotated from here.
+
+use std::os::raw::c_char;
+
+use miden_client::{auth::AuthSecretKey, transaction::TransactionScript};
+use miden_lib::{transaction::TransactionKernel, utils::ScriptBuilder};
+use miden_objects::{
+    account::{
+        AccountCode, AccountComponent, AccountId, AccountStorageMode, AccountType, StorageSlot,
+    },
+    assembly::{diagnostics::NamedSource, Library},
+    utils::Serializable,
+    AccountError, Word,
+};
+use once_cell::sync::Lazy;
+
+use crate::{
+    add_wallet_account, audit_log, check_out_capacity, clock, diagnostics, get_handle,
+    hex_placeholder,
+    keystore::{KeyBackend, RetiredKey},
+    last_error, output_len, parse_account_id, parse_seed, parse_wallet_storage_mode, proving,
+    request_blocking, reserves::AUTH_PUB_KEY_SLOT, types::WcKeyBackend, wallet_core,
+    write_out_buffer, MidenContext, MidenHandle, Request, ACCOUNT_ID_HEX_LEN, ERR_ACCOUNT_OP,
+    ERR_INVALID_HANDLE, ERR_INVALID_PARAM, ERR_LOOKUP, ID_HEX_LEN,
+};
+
+/// Library path of the rotatable auth component
+const ROTATABLE_FALCON_PATH: &str = "miden_swift_client::rotatable_falcon";
+
+const ROTATABLE_FALCON_CODE: &str = "
+    use.miden::active_account
+    use.miden::native_account
+    use.miden::auth::rpo_falcon512
+
+    # Storage slot of the public key commitment
+    const.PUBLIC_KEY_SLOT=0
+
+    #! Authenticate a transaction with the key the account had when it started
+    #!
+    #! Inputs:  [AUTH_ARGS, pad(12)]
+    #! Outputs: [pad(16)]
+    export.auth_tx_rotatable_rpo_falcon512
+        dropw
+        # => [pad(16)]
+
+        push.PUBLIC_KEY_SLOT exec.active_account::get_initial_item
+        # => [PUB_KEY, pad(16)]
+
+        exec.rpo_falcon512::authenticate_transaction
+        # => [pad(16)]
+    end
+
+    #! Replace the public key commitment
+    #!
+    #! Inputs:  [NEW_PUB_KEY, pad(12)]
+    #! Outputs: [OLD_PUB_KEY, pad(12)]
+    export.rotate_auth_key
+        push.PUBLIC_KEY_SLOT exec.native_account::set_item
+        # => [OLD_PUB_KEY, pad(12)]
+    end
+";
+
+static ROTATABLE_FALCON_LIBRARY: Lazy<Library> = Lazy::new(|| {
+    let source = NamedSource::new(ROTATABLE_FALCON_PATH, ROTATABLE_FALCON_CODE);
+    TransactionKernel::assembler()
+        .assemble_library([source])
+        .expect("rotatable auth component code should be valid")
+});
+
+/// Rotatable auth component with `pub_key` as its public key commitment
+pub(crate) fn rotatable_auth_component(pub_key: Word) -> Result<AccountComponent, AccountError> {
+    let library = ROTATABLE_FALCON_LIBRARY.clone();
+    AccountComponent::new(library, vec![StorageSlot::Value(pub_key)])
+        .map(|component| component.with_supported_type(AccountType::RegularAccountUpdatableCode))
+}
+
+/// Whether the account code has the rotatable auth component
+pub(crate) fn is_rotatable(code: &AccountCode) -> bool {
+    let name = format!("{}::rotate_auth_key", ROTATABLE_FALCON_PATH);
+    ROTATABLE_FALCON_LIBRARY
+        .get_procedure_root_by_name(name.as_str())
+        .is_some_and(|root| code.has_procedure(root))
+}
+
+/// Transaction script calling `rotate_auth_key` with `new_pub_key`
+pub(crate) fn rotation_script(new_pub_key: Word) -> Result<TransactionScript, String> {
+    let source = format!(
+        "use.{}\nbegin\n    push.{}\n    call.rotatable_falcon::rotate_auth_key\n    dropw\nend",
+        ROTATABLE_FALCON_PATH, new_pub_key
+    );
+    ScriptBuilder::new(true)
+        .with_dynamically_linked_library(&ROTATABLE_FALCON_LIBRARY)
+        .and_then(|builder| builder.compile_tx_script(source))
+        .map_err(|e| e.to_string())
+}
+
+pub(crate) async fn create_rotatable_wallet_impl(
+    context: &mut MidenContext,
+    init_seed: [u8; 32],
+    storage_mode: AccountStorageMode,
+    backend: KeyBackend,
+) -> Result<String, i32> {
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+    let pub_key: Word = key_pair.public_key().to_commitment().into();
+
+    let account = wallet_core::build_rotatable_wallet_account(init_seed, pub_key, storage_mode)
+        .map_err(wallet_core::CoreError::into_code)?;
+    add_wallet_account(context, account, backend, Some(&key_pair)).await
+}
+
+/// Moves no assets and has no counterparty, so screening does not apply.
+pub(crate) async fn rotate_auth_key_impl(
+    context: &mut MidenContext,
+    account_id: AccountId,
+) -> Result<String, i32> {
+    let backend = context.keystore.account_backend(account_id);
+    if backend.scheme_id().is_some() {
+        let detail = "the key is held by an external signer";
+        return Err(last_error::detail(ERR_INVALID_PARAM, detail));
+    }
+    let record = context.client.get_account(account_id).await
+        .map_err(|e| last_error::detail(ERR_LOOKUP, e))?
+        .ok_or(ERR_LOOKUP)?;
+    let old_pub_key = record.account().storage().get_item(AUTH_PUB_KEY_SLOT)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    let new_key = AuthSecretKey::new_rpo_falcon512();
+    let new_pub_key: Word = new_key.public_key().to_commitment().into();
+    let request = wallet_core::rotate_auth_key_request(record.account(), new_pub_key)
+        .map_err(wallet_core::CoreError::into_code)?;
+
+    // The current key is retired when an earlier rotation was discarded; it signs this one
+    if context.keystore.retired_key(&old_pub_key).is_some() {
+        context.keystore.reactivate_key(&old_pub_key)
+            .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    }
+    // Saved first so the account can never move to a key this client does not have; a
+    // failed submission leaves an unused key behind
+    context.keystore.add_key(&new_key, backend)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    proving::check_memory(context)?;
+    let tx_id = wallet_core::submit(&mut context.client, account_id, request).await
+        .map_err(wallet_core::CoreError::into_code)?;
+
+    let retired = RetiredKey { account_id, tx_id: tx_id.to_hex(), retired_at: clock::now_unix() };
+    if let Err(e) = context.keystore.retire_key(old_pub_key, retired) {
+        diagnostics::log("key_rotation", format!("failed to retire the old key: {}", e));
+    }
+
+    let result = serde_json::json!({
+        "tx_id": tx_id.to_hex(),
+        "account_id": account_id.to_hex(),
+        "pub_key": hex::encode(new_pub_key.to_bytes()),
+        "retired_pub_key": hex::encode(old_pub_key.to_bytes()),
+    });
+    audit_log::record(context, "auth_key_rotated", result.clone());
+    Ok(result.to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Create a wallet account whose auth key can be rotated (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Like `wc_miden_create_wallet_with_key_backend`, but the account has updatable code and
+/// an auth component that `wc_miden_rotate_auth_key` can move to a new key. The account ID
+/// differs from the one a plain wallet gets for the same seed.
+///
+/// # Parameters
+/// - `seed_ptr` / `seed_len`: 32-byte account seed (NULL generates a random seed)
+/// - `storage_mode`: `WcStorageMode` value (0 = public, 1 = private)
+/// - `key_backend`: `WcKeyBackend` value (0 = filesystem, 2 = memory)
+/// - `account_id_out` / `account_id_out_len`: Output buffer for the account ID (hex)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, `WcStorageMode_Network` or `WcKeyBackend_Callback`
+/// - -2: Invalid handle or worker closed
+/// - -3: Account creation failed
+/// - -7: Output buffer too small
+/// - -105: An account with the derived ID already exists in the store or on chain
+/// - -106: The store has not synced yet and `require_sync_for_create` is set
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_create_rotatable_wallet(
+    handle: MidenHandle,
+    seed_ptr: *const u8,
+    seed_len: usize,
+    storage_mode: u32,
+    key_backend: u32,
+    account_id_out: *mut u8,
+    account_id_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if let Err(code) = check_out_capacity(account_id_out, account_id_out_len, ACCOUNT_ID_HEX_LEN) {
+        return code;
+    }
+
+    let backend = match WcKeyBackend::try_from(key_backend) {
+        Ok(WcKeyBackend::Filesystem) => KeyBackend::Filesystem,
+        Ok(WcKeyBackend::Memory) => KeyBackend::Memory,
+        Ok(WcKeyBackend::Callback) | Err(_) => return ERR_INVALID_PARAM,
+    };
+
+    let seed = match parse_seed(seed_ptr, seed_len) {
+        Ok(seed) => seed,
+        Err(code) => return code,
+    };
+    let storage_mode = match parse_wallet_storage_mode(storage_mode) {
+        Ok(storage_mode) => storage_mode,
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::CreateRotatableWallet { seed, storage_mode, backend, reply };
+    match request_blocking(&worker, request) {
+        Ok(account_id) => write_out_buffer(&account_id, account_id_out, account_id_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Rotate the auth key of a wallet created with `wc_miden_create_rotatable_wallet`
+/// (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Generates a new Falcon key in the account's key backend and submits a transaction,
+/// signed with the current key, that makes the new key the account's auth key. The old
+/// key is then retired: it stays in the keystore but no longer signs. If the transaction
+/// is discarded, rotate again (see the module docs).
+///
+/// # Parameters
+/// - `account_id_hex`: Account ID (C string)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"tx_id","account_id","pub_key","retired_pub_key"}` (public key commitments, hex)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, or the key is held by an external signer
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID, or the account has immutable code or no rotatable auth
+///   component
+/// - -5: Account not in the store
+/// - -6: Transaction failed
+/// - -7: Output buffer too small (checked before the transaction is submitted)
+/// - -99: Operation timed out
+/// - -108: Less memory available than `proving_memory_ceiling_mb`
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_rotate_auth_key(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let pub_key = hex::encode(Word::default().to_bytes());
+    let result = serde_json::json!({
+        "tx_id": hex_placeholder(ID_HEX_LEN),
+        "account_id": hex_placeholder(ACCOUNT_ID_HEX_LEN),
+        "pub_key": pub_key,
+        "retired_pub_key": pub_key,
+    });
+    if let Err(code) = check_out_capacity(json_out, json_out_len, output_len(&result)) {
+        return code;
+    }
+    let account_id = match parse_account_id(account_id_hex) {
+        Ok((account_id, _)) => account_id,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::RotateAuthKey { account_id, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,516 @@
+//! Keystore router
+//!
+//! Accounts of one client can keep their signing keys in different backends:
+//!
+//! - `filesystem`: the filesystem keystore in the keystore directory (the default)
+//! - `callback`: an external signer registered with `wc_miden_register_signer` (Secure
+//!   Enclave, hardware wallet, MPC service), see signer.rs
+//! - `memory`: process memory only. The key is lost when the handle is destroyed and the
+//!   account can no longer sign afterwards, so this is meant for short-lived accounts and
+//!   tests
+//!
+//! The backend of each account is recorded in `account_backends.json` in the keystore
+//! directory and reported by `wc_miden_get_account_details`; accounts without a record
+//! predate the router and use the filesystem. Signing requests only carry a public key
+//! commitment, so they are routed by key: memory keys first, then keys bound to an
+//! external signer (`external_signers.json`), then the filesystem keystore.
+//!
+//! Keys replaced by `wc_miden_rotate_auth_key` (see key_rotation.rs) are listed in
+//! `retired_keys.json` with the account and rotation transaction. They stay in their
+//! backend, but signing requests for them are refused.
+//!
+//! In-memory clients (see memory_store.rs) have no keystore directory: filesystem-backend
+//! keys are kept with the memory keys, and the JSON files are not written.
+//!
+//! With `keystore_passphrase` in the client config the filesystem backend encrypts its keys
+//! (see encrypted_keystore.rs); `wc_miden_rekey_keystore` changes the passphrase.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Mutex, RwLock},
+};
+
+use miden_client::{
+    auth::{AuthSecretKey, SigningInputs},
+    AuthenticationError,
+};
+use miden_objects::{
+    account::{auth::Signature, AccountId},
+    utils::{Deserializable, Serializable},
+    Word,
+};
+
+use crate::{
+    check_out_capacity,
+    encrypted_keystore::{self, FileKeys},
+    get_handle, last_error, memory_store, parse_seed, parse_wallet_storage_mode, request_blocking,
+    signer, types::WcKeyBackend, write_out_buffer, MidenHandle, MidenKeyStore, Request,
+    ACCOUNT_ID_HEX_LEN, ERR_INVALID_HANDLE, ERR_INVALID_PARAM,
+};
+
+/// File in the keystore directory mapping public key commitments to signer schemes
+pub(crate) const EXTERNAL_SIGNERS_FILE: &str = "external_signers.json";
+
+/// File in the keystore directory mapping account IDs to key backends
+pub(crate) const ACCOUNT_BACKENDS_FILE: &str = "account_backends.json";
+
+/// File in the keystore directory listing retired public key commitments
+pub(crate) const RETIRED_KEYS_FILE: &str = "retired_keys.json";
+
+/// Where the key of an account lives
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum KeyBackend {
+    Filesystem,
+    /// External signer scheme holding the key
+    Callback(u32),
+    Memory,
+}
+
+impl KeyBackend {
+    pub fn kind(self) -> WcKeyBackend {
+        match self {
+            KeyBackend::Filesystem => WcKeyBackend::Filesystem,
+            KeyBackend::Callback(_) => WcKeyBackend::Callback,
+            KeyBackend::Memory => WcKeyBackend::Memory,
+        }
+    }
+
+    /// Signer scheme of a callback backend
+    pub fn scheme_id(self) -> Option<u32> {
+        match self {
+            KeyBackend::Callback(scheme_id) => Some(scheme_id),
+            _ => None,
+        }
+    }
+
+    fn to_json(self) -> serde_json::Value {
+        serde_json::json!({ "backend": self.kind().as_str(), "scheme_id": self.scheme_id() })
+    }
+
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        match value["backend"].as_str()? {
+            "filesystem" => Some(KeyBackend::Filesystem),
+            "callback" => {
+                let scheme_id = u32::try_from(value["scheme_id"].as_u64()?).ok()?;
+                Some(KeyBackend::Callback(scheme_id))
+            }
+            "memory" => Some(KeyBackend::Memory),
+            _ => None,
+        }
+    }
+}
+
+/// Key replaced by an auth key rotation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct RetiredKey {
+    pub account_id: AccountId,
+    /// Rotation transaction (hex)
+    pub tx_id: String,
+    /// Unix seconds
+    pub retired_at: u64,
+}
+
+impl RetiredKey {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "account_id": self.account_id.to_hex(),
+            "tx_id": self.tx_id,
+            "retired_at": self.retired_at,
+        })
+    }
+
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        Some(Self {
+            account_id: AccountId::from_hex(value["account_id"].as_str()?).ok()?,
+            tx_id: value["tx_id"].as_str()?.to_string(),
+            retired_at: value["retired_at"].as_u64()?,
+        })
+    }
+}
+
+/// Signing keys of all backends, shared by the worker and the authenticator
+pub(crate) struct KeystoreRouter {
+    /// None for in-memory clients; replaced when the keystore is rekeyed
+    filesystem: RwLock<Option<FileKeys>>,
+    /// None when the keystore directory is unknown (see `from_filesystem`)
+    keystore_dir: Option<PathBuf>,
+    /// Keys that never touch the disk, by public key commitment
+    memory: Mutex<HashMap<Word, AuthSecretKey>>,
+    /// Public key commitments held by external signers, with their scheme ID
+    external_keys: Mutex<HashMap<Word, u32>>,
+    external_keys_path: Option<PathBuf>,
+    accounts: Mutex<HashMap<AccountId, KeyBackend>>,
+    accounts_path: Option<PathBuf>,
+    /// Public key commitments that no longer sign
+    retired: Mutex<HashMap<Word, RetiredKey>>,
+    retired_path: Option<PathBuf>,
+}
+
+impl KeystoreRouter {
+    /// Open the keystore in `keystore_dir`, encrypted with `passphrase` if given
+    pub fn new(keystore_dir: &Path, passphrase: Option<&str>) -> Result<Self, String> {
+        let filesystem = if memory_store::is_in_memory(keystore_dir) {
+            None
+        } else {
+            encrypted_keystore::finish_rekey(keystore_dir)
+                .map_err(|e| format!("Failed to finish keystore rekey: {}", e))?;
+            Some(FileKeys::open(keystore_dir, passphrase)?)
+        };
+        let external_keys_path = keystore_dir.join(EXTERNAL_SIGNERS_FILE);
+        let accounts_path = keystore_dir.join(ACCOUNT_BACKENDS_FILE);
+        let retired_path = keystore_dir.join(RETIRED_KEYS_FILE);
+
+        Ok(Self {
+            filesystem: RwLock::new(filesystem),
+            keystore_dir: Some(keystore_dir.to_path_buf()),
+            memory: Mutex::new(HashMap::new()),
+            external_keys: Mutex::new(load_external_keys(&external_keys_path)),
+            external_keys_path: Some(external_keys_path),
+            accounts: Mutex::new(load_account_backends(&accounts_path)),
+            accounts_path: Some(accounts_path),
+            retired: Mutex::new(load_retired_keys(&retired_path)),
+            retired_path: Some(retired_path),
+        })
+    }
+
+    /// Router over a bare filesystem keystore, with no external signers or account records
+    pub fn from_filesystem(filesystem: MidenKeyStore) -> Self {
+        Self {
+            filesystem: RwLock::new(Some(FileKeys::Plain(filesystem))),
+            keystore_dir: None,
+            memory: Mutex::new(HashMap::new()),
+            external_keys: Mutex::new(HashMap::new()),
+            external_keys_path: None,
+            accounts: Mutex::new(HashMap::new()),
+            accounts_path: None,
+            retired: Mutex::new(HashMap::new()),
+            retired_path: None,
+        }
+    }
+
+    /// Store a locally generated key in the filesystem or memory backend
+    pub fn add_key(&self, key: &AuthSecretKey, backend: KeyBackend) -> Result<(), String> {
+        match (backend, &*self.filesystem.read().unwrap()) {
+            (KeyBackend::Filesystem, Some(filesystem)) => filesystem.add_key(key),
+            (KeyBackend::Filesystem, None) | (KeyBackend::Memory, _) => {
+                let pub_key: Word = key.public_key().to_commitment().into();
+                self.memory.lock().unwrap().insert(pub_key, key.clone());
+                Ok(())
+            }
+            (KeyBackend::Callback(_), _) => Err("external signer keys are not held locally".into()),
+        }
+    }
+
+    /// Secret key for `pub_key` held by the memory or filesystem backend
+    pub fn secret_key(&self, pub_key: Word) -> Result<Option<AuthSecretKey>, String> {
+        if let Some(key) = self.memory.lock().unwrap().get(&pub_key) {
+            return Ok(Some(key.clone()));
+        }
+        match &*self.filesystem.read().unwrap() {
+            Some(filesystem) => filesystem.get_key(pub_key),
+            None => Ok(None),
+        }
+    }
+
+    /// Re-encrypt the filesystem backend under `new_passphrase` (None: unencrypted)
+    pub fn rekey(
+        &self,
+        old_passphrase: Option<&str>,
+        new_passphrase: Option<&str>,
+    ) -> Result<(), i32> {
+        let mut filesystem = self.filesystem.write().unwrap();
+        let Some(current) = filesystem.as_ref() else {
+            let detail = "in-memory clients have no keystore";
+            return Err(last_error::detail(ERR_INVALID_PARAM, detail));
+        };
+        let Some(keystore_dir) = &self.keystore_dir else {
+            return Err(last_error::detail(ERR_INVALID_PARAM, "keystore directory unknown"));
+        };
+        let rekeyed =
+            encrypted_keystore::rekey(keystore_dir, current, old_passphrase, new_passphrase)?;
+        *filesystem = Some(rekeyed);
+        Ok(())
+    }
+
+    /// Restore a backup copy of the keystore directory and reload it (see store_backup.rs)
+    pub fn restore(&self, src: &Path) -> Result<(), i32> {
+        let mut filesystem = self.filesystem.write().unwrap();
+        let Some(current) = filesystem.as_ref() else {
+            let detail = "in-memory clients have no keystore";
+            return Err(last_error::detail(ERR_INVALID_PARAM, detail));
+        };
+        let Some(keystore_dir) = &self.keystore_dir else {
+            return Err(last_error::detail(ERR_INVALID_PARAM, "keystore directory unknown"));
+        };
+        let restored = encrypted_keystore::restore(keystore_dir, current, src)?;
+        *filesystem = Some(restored);
+
+        *self.external_keys.lock().unwrap() =
+            load_external_keys(&keystore_dir.join(EXTERNAL_SIGNERS_FILE));
+        *self.accounts.lock().unwrap() =
+            load_account_backends(&keystore_dir.join(ACCOUNT_BACKENDS_FILE));
+        *self.retired.lock().unwrap() = load_retired_keys(&keystore_dir.join(RETIRED_KEYS_FILE));
+        Ok(())
+    }
+
+    /// Route signing requests for `pub_key` to the external signer `scheme_id` (persisted)
+    pub fn bind_external_key(&self, pub_key: Word, scheme_id: u32) -> std::io::Result<()> {
+        let mut keys = self.external_keys.lock().unwrap();
+        keys.insert(pub_key, scheme_id);
+        save_external_keys(sidecar_path(&self.external_keys_path)?, &keys)
+    }
+
+    /// Drop the signer binding of `pub_key` (persisted)
+    pub fn unbind_external_key(&self, pub_key: &Word) -> std::io::Result<()> {
+        let mut keys = self.external_keys.lock().unwrap();
+        keys.remove(pub_key);
+        save_external_keys(sidecar_path(&self.external_keys_path)?, &keys)
+    }
+
+    /// Scheme the key is bound to, or None for keys held locally
+    pub fn external_scheme(&self, pub_key: &Word) -> Option<u32> {
+        self.external_keys.lock().unwrap().get(pub_key).copied()
+    }
+
+    /// Record the backend holding an account's key (persisted)
+    pub fn record_account(
+        &self,
+        account_id: AccountId,
+        backend: KeyBackend,
+    ) -> std::io::Result<()> {
+        let mut accounts = self.accounts.lock().unwrap();
+        accounts.insert(account_id, backend);
+        save_account_backends(sidecar_path(&self.accounts_path)?, &accounts)
+    }
+
+    /// Drop the backend record of an account that was not added after all (persisted)
+    pub fn forget_account(&self, account_id: AccountId) -> std::io::Result<()> {
+        let mut accounts = self.accounts.lock().unwrap();
+        accounts.remove(&account_id);
+        save_account_backends(sidecar_path(&self.accounts_path)?, &accounts)
+    }
+
+    /// Record the backend of several accounts' keys with a single write (persisted)
+    pub fn record_accounts(
+        &self,
+        account_ids: &[AccountId],
+        backend: KeyBackend,
+    ) -> std::io::Result<()> {
+        let mut accounts = self.accounts.lock().unwrap();
+        accounts.extend(account_ids.iter().map(|account_id| (*account_id, backend)));
+        save_account_backends(sidecar_path(&self.accounts_path)?, &accounts)
+    }
+
+    /// Backend holding an account's key (filesystem for accounts without a record)
+    pub fn account_backend(&self, account_id: AccountId) -> KeyBackend {
+        self.accounts.lock().unwrap().get(&account_id).copied().unwrap_or(KeyBackend::Filesystem)
+    }
+
+    /// Stop signing with `pub_key`, replaced by a rotation (persisted)
+    pub fn retire_key(&self, pub_key: Word, retired: RetiredKey) -> std::io::Result<()> {
+        let mut keys = self.retired.lock().unwrap();
+        keys.insert(pub_key, retired);
+        save_retired_keys(sidecar_path(&self.retired_path)?, &keys)
+    }
+
+    /// Sign with a retired key again (its rotation was discarded; persisted)
+    pub fn reactivate_key(&self, pub_key: &Word) -> std::io::Result<()> {
+        let mut keys = self.retired.lock().unwrap();
+        keys.remove(pub_key);
+        save_retired_keys(sidecar_path(&self.retired_path)?, &keys)
+    }
+
+    /// Rotation that retired `pub_key`, or None for a key in use
+    pub fn retired_key(&self, pub_key: &Word) -> Option<RetiredKey> {
+        self.retired.lock().unwrap().get(pub_key).cloned()
+    }
+
+    pub async fn get_signature(
+        &self,
+        pub_key: Word,
+        signing_inputs: &SigningInputs,
+    ) -> Result<Signature, AuthenticationError> {
+        if self.retired_key(&pub_key).is_some() {
+            return Err(AuthenticationError::other("the key was retired by a key rotation"));
+        }
+
+        let memory_key = self.memory.lock().unwrap().get(&pub_key).cloned();
+        if let Some(key) = memory_key {
+            return sign_locally(key, signing_inputs);
+        }
+
+        if let Some(scheme_id) = self.external_scheme(&pub_key) {
+            return signer::sign_with(scheme_id, pub_key, signing_inputs.to_commitment())
+                .map_err(AuthenticationError::other);
+        }
+
+        let file_key = match &*self.filesystem.read().unwrap() {
+            Some(filesystem) => filesystem.get_key(pub_key).map_err(AuthenticationError::other)?,
+            None => None,
+        };
+        match file_key {
+            Some(key) => sign_locally(key, signing_inputs),
+            None => Err(AuthenticationError::other("no key for the public key commitment")),
+        }
+    }
+}
+
+fn sidecar_path(path: &Option<PathBuf>) -> std::io::Result<&Path> {
+    path.as_deref().ok_or_else(|| std::io::Error::other("no keystore directory to record in"))
+}
+
+fn sign_locally(
+    key: AuthSecretKey,
+    signing_inputs: &SigningInputs,
+) -> Result<Signature, AuthenticationError> {
+    match key {
+        AuthSecretKey::RpoFalcon512(secret_key) => {
+            Ok(Signature::RpoFalcon512(secret_key.sign(signing_inputs.to_commitment())))
+        }
+        #[allow(unreachable_patterns)]
+        _ => Err(AuthenticationError::other("unsupported key type")),
+    }
+}
+
+fn load_external_keys(path: &Path) -> HashMap<Word, u32> {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    let entries: HashMap<String, u32> = serde_json::from_str(&contents).unwrap_or_default();
+
+    entries
+        .into_iter()
+        .filter_map(|(key_hex, scheme_id)| {
+            let bytes = hex::decode(key_hex).ok()?;
+            Some((Word::read_from_bytes(&bytes).ok()?, scheme_id))
+        })
+        .collect()
+}
+
+fn save_external_keys(path: &Path, keys: &HashMap<Word, u32>) -> std::io::Result<()> {
+    let entries: HashMap<String, u32> = keys
+        .iter()
+        .map(|(key, scheme_id)| (hex::encode(key.to_bytes()), *scheme_id))
+        .collect();
+    let json = serde_json::to_string(&entries).map_err(std::io::Error::other)?;
+    memory_store::write(path, json)
+}
+
+fn load_account_backends(path: &Path) -> HashMap<AccountId, KeyBackend> {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    let entries: HashMap<String, serde_json::Value> =
+        serde_json::from_str(&contents).unwrap_or_default();
+
+    entries
+        .iter()
+        .filter_map(|(account_id, backend)| {
+            Some((AccountId::from_hex(account_id).ok()?, KeyBackend::from_json(backend)?))
+        })
+        .collect()
+}
+
+fn save_account_backends(
+    path: &Path,
+    accounts: &HashMap<AccountId, KeyBackend>,
+) -> std::io::Result<()> {
+    let entries: HashMap<String, serde_json::Value> = accounts
+        .iter()
+        .map(|(account_id, backend)| (account_id.to_hex(), backend.to_json()))
+        .collect();
+    let json = serde_json::to_string(&entries).map_err(std::io::Error::other)?;
+    memory_store::write(path, json)
+}
+
+fn load_retired_keys(path: &Path) -> HashMap<Word, RetiredKey> {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    let entries: HashMap<String, serde_json::Value> =
+        serde_json::from_str(&contents).unwrap_or_default();
+
+    entries
+        .iter()
+        .filter_map(|(key_hex, retired)| {
+            let bytes = hex::decode(key_hex).ok()?;
+            Some((Word::read_from_bytes(&bytes).ok()?, RetiredKey::from_json(retired)?))
+        })
+        .collect()
+}
+
+fn save_retired_keys(path: &Path, keys: &HashMap<Word, RetiredKey>) -> std::io::Result<()> {
+    let entries: HashMap<String, serde_json::Value> = keys
+        .iter()
+        .map(|(key, retired)| (hex::encode(key.to_bytes()), retired.to_json()))
+        .collect();
+    let json = serde_json::to_string(&entries).map_err(std::io::Error::other)?;
+    memory_store::write(path, json)
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Create a wallet account with its key in the given backend (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Keys held by an external signer are created with `wc_miden_create_wallet_with_signer`.
+///
+/// # Parameters
+/// - `seed_ptr` / `seed_len`: 32-byte account seed (NULL generates a random seed)
+/// - `storage_mode`: `WcStorageMode` value (0 = public, 1 = private)
+/// - `key_backend`: `WcKeyBackend` value (0 = filesystem, 2 = memory)
+/// - `account_id_out` / `account_id_out_len`: Output buffer for the account ID (hex)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, `WcStorageMode_Network` or `WcKeyBackend_Callback`
+/// - -2: Invalid handle or worker closed
+/// - -3: Account creation failed
+/// - -7: Output buffer too small
+/// - -105: An account with the derived ID already exists in the store or on chain
+/// - -106: The store has not synced yet and `require_sync_for_create` is set
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_create_wallet_with_key_backend(
+    handle: MidenHandle,
+    seed_ptr: *const u8,
+    seed_len: usize,
+    storage_mode: u32,
+    key_backend: u32,
+    account_id_out: *mut u8,
+    account_id_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if let Err(code) = check_out_capacity(account_id_out, account_id_out_len, ACCOUNT_ID_HEX_LEN) {
+        return code;
+    }
+
+    let backend = match WcKeyBackend::try_from(key_backend) {
+        Ok(WcKeyBackend::Filesystem) => KeyBackend::Filesystem,
+        Ok(WcKeyBackend::Memory) => KeyBackend::Memory,
+        Ok(WcKeyBackend::Callback) | Err(_) => return ERR_INVALID_PARAM,
+    };
+
+    let seed = match parse_seed(seed_ptr, seed_len) {
+        Ok(seed) => seed,
+        Err(code) => return code,
+    };
+    let storage_mode = match parse_wallet_storage_mode(storage_mode) {
+        Ok(storage_mode) => storage_mode,
+        Err(code) => return code,
+    };
+
+    let request =
+        |reply| Request::CreateWalletWithKeyBackend { seed, storage_mode, backend, reply };
+    match request_blocking(&worker, request) {
+        Ok(account_id) => write_out_buffer(&account_id, account_id_out, account_id_out_len),
+        Err(code) => code,
+    }
+}
+
@@ -0,0 +1,105 @@
+//! Last error message of a handle
+//!
+//! The FFI returns integer codes; the underlying miden-client error is usually much more
+//! telling ("insufficient balance of faucet 0x.." rather than -6). Each handle keeps the
+//! message of the last failed worker request, which `wc_miden_last_error_message`
+//! returns as `<operation>: <message> (code <n>)`.
+//!
+//! Failure sites attach the underlying error with [`detail`]. The worker thread has its
+//! slot installed at start-up, and every error code delivered by the worker (see
+//! `diagnostics::record_error`) stores the pending detail, or the generic English message
+//! for the code, before the result reaches the caller, so the message is always in place
+//! when a blocking call returns or an async callback runs.
+//!
+//! The slot is not cleared by successful calls, like `errno`. Argument validation errors
+//! returned before a request is queued do not change it.
+
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    error_messages, get_handle, storage_health, write_out_buffer, MidenHandle,
+    ERR_INVALID_HANDLE, ERR_LOOKUP,
+};
+
+/// Last error of a handle, shared between the handle and its worker
+#[derive(Default)]
+pub(crate) struct LastError(Mutex<Option<String>>);
+
+thread_local! {
+    /// Slot of the handle whose worker runs on this thread
+    static SLOT: RefCell<Option<Arc<LastError>>> = const { RefCell::new(None) };
+    /// Underlying error of the failure being reported
+    static PENDING: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Install the handle's slot on the worker thread
+pub(crate) fn install(slot: Arc<LastError>) {
+    SLOT.with(|s| *s.borrow_mut() = Some(slot));
+}
+
+/// Remember the underlying error of a failure and return its code
+///
+/// Used as `.map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))`. A failed store write
+/// (full disk, read-only store) is reported as `ERR_STORAGE_FULL` instead of `code`.
+pub(crate) fn detail(code: i32, error: impl std::fmt::Display) -> i32 {
+    let message = error.to_string();
+    let code = storage_health::classify(code, &message);
+    PENDING.with(|pending| *pending.borrow_mut() = Some(message));
+    code
+}
+
+/// Store the message of an error code returned by `operation` (worker thread only)
+pub(crate) fn store(operation: &str, code: i32) {
+    let message = PENDING
+        .with(|pending| pending.borrow_mut().take())
+        .unwrap_or_else(|| error_messages::user_message(code, None).to_string());
+
+    SLOT.with(|slot| {
+        if let Some(slot) = slot.borrow().as_ref() {
+            *slot.0.lock().unwrap() = Some(format!("{}: {} (code {})", operation, message, code));
+        }
+    });
+}
+
+/// Drop a detail left over from an earlier request (start of each worker request)
+pub(crate) fn clear_pending() {
+    PENDING.with(|pending| pending.borrow_mut().take());
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Get the message of the last failed request of a handle
+///
+/// Safe to call from any thread, including while the worker is busy.
+///
+/// # Parameters
+/// - `buf` / `buf_len`: Output buffer for the UTF-8 message (not NUL-terminated), e.g.
+///   `send: transaction executor error: ... (code -6)`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle
+/// - -5: No request of this handle has failed yet
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_last_error_message(
+    handle: MidenHandle,
+    buf: *mut u8,
+    buf_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let message = worker.last_error.0.lock().unwrap().clone();
+    match message {
+        Some(message) => write_out_buffer(message.as_bytes(), buf, buf_len),
+        None => ERR_LOOKUP,
+    }
+}
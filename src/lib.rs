@@ -1,11 +1,15 @@
 #![allow(private_interfaces)]
+// FFI entry points take raw pointers from C callers; their safety contract is documented
+// per function rather than by marking every `extern "C"` function unsafe
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
 
 use sha3::{Digest, Keccak256};
 use std::{
+    borrow::Cow,
     ffi::CStr,
     os::raw::c_char,
-    path::PathBuf,
-    sync::Arc,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
     thread::JoinHandle,
     time::Duration,
 };
@@ -24,16 +28,29 @@ pub const WORKER_QUEUE_CAPACITY: usize = 256;
 // Standard error codes used across all FFI functions:
 //
 //   0:    Success
-//  -1:    Invalid parameter (null pointer, invalid format, buffer too small)
+//  -1:    Invalid parameter (null pointer, invalid format)
 //  -2:    Invalid handle or worker closed
 //  -3:    Account/key operation failed
 //  -4:    Note operation failed / invalid note ID
 //  -5:    Balance/account lookup failed
 //  -6:    Transaction submission failed
+//  -7:    Output buffer too small (the required length is written to the length argument)
 //  -8:    Queue full (too many pending requests)
+//  -9:    Operation cancelled (async API only)
+//  -10:   File could not be written
 //  -99:   Operation timed out (sync API only)
 //
-// Business-specific errors use -100 to -199 range (reserved for future use)
+// Business-specific errors use -100 to -199 range:
+//
+//  -100:  Request rejected by the user / approval callback
+//  -101:  Permission denied
+//  -102:  Transaction blocked by compliance screening
+//  -103:  Push relay or watch service unreachable, or request refused
+//  -104:  Node is on another network than the store
+//  -105:  Account already exists (in the store or on chain)
+//  -106:  Store has not synced yet
+//  -107:  Store cannot be written (disk full or read-only)
+//  -108:  Not enough memory to prove a transaction on this device
 //
 
 /// Error: invalid parameter
@@ -48,36 +65,156 @@ pub const ERR_NOTE_OP: i32 = -4;
 pub const ERR_LOOKUP: i32 = -5;
 /// Error: transaction submission failed
 pub const ERR_TX_SUBMIT: i32 = -6;
+/// Error: output buffer too small (the required length is written to `*out_len`)
+pub const ERR_BUFFER_TOO_SMALL: i32 = -7;
 /// Error: worker queue is full
 pub const ERR_QUEUE_FULL: i32 = -8;
+/// Error: operation cancelled with `wc_miden_cancel`
+pub const ERR_CANCELLED: i32 = -9;
+/// Error: a file outside the store (export, bundle) could not be written
+pub const ERR_FILE_WRITE: i32 = -10;
 /// Error: operation timed out
 pub const ERR_TIMEOUT: i32 = -99;
+/// Error: request rejected by the user / approval callback
+pub const ERR_REJECTED: i32 = -100;
+/// Error: caller lacks the permission required for the request
+pub const ERR_PERMISSION_DENIED: i32 = -101;
+/// Error: transaction blocked by the screening callback
+pub const ERR_SCREENING_DENIED: i32 = -102;
+/// Error: push relay or watch service unreachable, or request refused
+pub const ERR_RELAY: i32 = -103;
+/// Error: the node is on another network than the store
+pub const ERR_NETWORK_MISMATCH: i32 = -104;
+/// Error: an account with the derived ID already exists in the store or on chain
+pub const ERR_ACCOUNT_EXISTS: i32 = -105;
+/// Error: the store must have synced at least once
+pub const ERR_NOT_SYNCED: i32 = -106;
+/// Error: the store cannot be written (disk full or read-only); see `wc_miden_health_check`
+pub const ERR_STORAGE_FULL: i32 = -107;
+/// Error: less memory available than `proving_memory_ceiling_mb`; prove remotely or retry
+pub const ERR_PROVING_MEMORY: i32 = -108;
 
 use rand::{rngs::StdRng, RngCore, SeedableRng};
 use tokio::sync::mpsc;
 
 use miden_client::{
-    account::component::BasicWallet,
     auth::AuthSecretKey,
     builder::ClientBuilder,
     keystore::FilesystemKeyStore,
     rpc::{Endpoint, GrpcClient},
-    transaction::TransactionRequestBuilder,
     Client,
 };
-use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_client_sqlite_store::SqliteStore;
 use miden_lib::account::auth::AuthRpoFalcon512;
-use miden_objects::account::{
-    AccountBuilder, AccountComponent, AccountId, AccountStorageMode, AccountType,
-};
-use miden_objects::note::NoteId;
+use miden_objects::account::{Account, AccountComponent, AccountId, AccountStorageMode};
+use miden_objects::note::{NoteId, NoteType};
+use miden_objects::transaction::TransactionId;
+
+mod account_import;
+mod account_sync;
+mod allocator;
+mod amount;
+mod archive;
+mod audit_log;
+mod auth;
+mod background_sync;
+mod block_times;
+#[cfg(feature = "scripts")]
+mod bridge;
+mod cancel;
+mod clock;
+mod cold_wallet;
+mod config;
+mod create_preflight;
+mod dapp_session;
+mod deploy;
+mod deposits;
+mod derivation;
+mod diagnostics;
+mod dispose;
+mod encrypted_keystore;
+mod encrypted_store;
+mod ephemeral;
+mod error_messages;
+mod escrow;
+mod events;
+mod evm;
+mod export_signing;
+#[cfg(feature = "faucets")]
+mod faucet;
+mod handles;
+mod id_validation;
+mod inclusion_proof;
+mod input_notes;
+mod json;
+mod key_backup;
+mod key_rotation;
+mod keystore;
+mod last_error;
+mod live_tx;
+mod memory;
+mod memory_store;
+mod message_signing;
+#[cfg(feature = "mnemonic")]
+mod mnemonic;
+mod mpc;
+#[cfg(feature = "scripts")]
+mod name_registry;
+mod network;
+mod node_proxy;
+mod note_export;
+mod note_import;
+mod note_summary;
+mod objects;
+mod observer;
+mod output_notes;
+mod ownership;
+mod paging;
+mod passphrase;
+mod proof_cache;
+mod proving;
+mod provisioning;
+mod public_key;
+mod push;
+mod rate_limit;
+mod read_path;
+mod recallable;
+mod reconnect;
+mod reorg;
+mod reserves;
+mod retention;
+mod rpc_metrics;
+mod screening;
+mod signer;
+mod split;
+mod state_audit;
+mod storage_health;
+mod store_backup;
+mod streams;
+#[cfg(feature = "swaps")]
+mod swap;
+mod sync_progress;
+mod tenants;
+mod testing;
+mod travel_rule;
+mod tx_history;
+pub mod types;
+pub mod wallet_core;
+mod watch;
+mod whitelist;
+mod widget;
+
+use auth::WalletAuthenticator;
+use keystore::KeyBackend;
+use read_path::ReadQuery;
+use types::{WcAccountStatus, WcAccountType, WcStorageMode};
 
 // ================================================================================================
 // Type Aliases
 // ================================================================================================
 
 type MidenKeyStore = FilesystemKeyStore<StdRng>;
-type MidenClient = Client<MidenKeyStore>;
+type MidenClient = Client<WalletAuthenticator>;
 
 // ================================================================================================
 // Async Callback Types
@@ -104,11 +241,15 @@ pub type ConsumeNotesCallback = extern "C" fn(*mut std::ffi::c_void, i32, *mut u
 /// Callback for test connection operation: (user_data, error_code)
 pub type TestConnectionCallback = extern "C" fn(*mut std::ffi::c_void, i32);
 
+/// Generic callback for operations returning JSON or binary data: (user_data, error_code, data_ptr, data_len)
+pub type BytesCallback = extern "C" fn(*mut std::ffi::c_void, i32, *mut u8, usize);
+
 // ================================================================================================
 // Worker Thread Architecture
 // ================================================================================================
 
 /// Request types sent to the worker thread
+#[allow(clippy::enum_variant_names)]
 enum Request {
     // Sync operations (blocking, return result via oneshot channel)
     SyncSync {
@@ -116,6 +257,7 @@ enum Request {
     },
     CreateWalletSync {
         seed: [u8; 32],
+        storage_mode: AccountStorageMode,
         response_tx: std::sync::mpsc::Sender<CreateWalletResult>,
     },
     GetAccountsSync {
@@ -146,6 +288,7 @@ enum Request {
     },
     CreateWalletAsync {
         seed: [u8; 32],
+        storage_mode: AccountStorageMode,
         callback: CreateWalletCallback,
         user_data: usize,
     },
@@ -174,11 +317,624 @@ enum Request {
         callback: TestConnectionCallback,
         user_data: usize,
     },
+
+    // Operations answering through a `Reply` (usable from both blocking and async entry points)
+    ScanDeposits {
+        scan: deposits::DepositScan,
+        reply: Reply,
+    },
+    BuildUnsignedTx {
+        account_id: AccountId,
+        spec: cold_wallet::TxSpec,
+        reply: Reply,
+    },
+    SubmitSignedTx {
+        unsigned: cold_wallet::UnsignedTx,
+        signature: miden_objects::account::auth::Signature,
+        reply: Reply,
+    },
+    CreateWalletWithSigner {
+        seed: [u8; 32],
+        storage_mode: AccountStorageMode,
+        signer: signer::SignerKey,
+        reply: Reply,
+    },
+    CreateWalletWithKeyBackend {
+        seed: [u8; 32],
+        storage_mode: AccountStorageMode,
+        backend: KeyBackend,
+        reply: Reply,
+    },
+    CreateRotatableWallet {
+        seed: [u8; 32],
+        storage_mode: AccountStorageMode,
+        backend: KeyBackend,
+        reply: Reply,
+    },
+    RotateAuthKey {
+        account_id: AccountId,
+        reply: Reply,
+    },
+    SubmitTxSpec {
+        account_id: AccountId,
+        spec: cold_wallet::TxSpec,
+        reply: Reply,
+    },
+    SetRetentionPolicy {
+        policy: retention::RetentionPolicy,
+        dry_run: bool,
+        reply: Reply,
+    },
+    ExportDiagnostics {
+        out_path: PathBuf,
+        redact_level: u32,
+        reply: Reply,
+    },
+    GetAccountStatus {
+        account_id: AccountId,
+        reply: Reply,
+    },
+    GetAccountDetails {
+        account_id: AccountId,
+        reply: Reply,
+    },
+    FaucetDecimals {
+        faucet_id: AccountId,
+        reply: Reply,
+    },
+    FaucetMetadata {
+        faucet_id: AccountId,
+        reply: Reply,
+    },
+    ImportAccountById {
+        account_id: AccountId,
+        /// External signer holding the account's key (see signer.rs)
+        signer: Option<signer::SignerKey>,
+        reply: Reply,
+    },
+    DeployAccount {
+        account_id: AccountId,
+        reply: Reply,
+    },
+    SetAccountArchived {
+        account_id: AccountId,
+        archived: bool,
+        reply: Reply,
+    },
+    ExportNoteInclusionProof {
+        note_id: NoteId,
+        reply: Reply,
+    },
+    ExportNote {
+        note_id: NoteId,
+        export_type: types::WcNoteExportType,
+        reply: Reply,
+    },
+    EstimateTimeForBlock {
+        block_num: u32,
+        reply: Reply,
+    },
+    AuditState {
+        account_id: AccountId,
+        repair: bool,
+        reply: Reply,
+    },
+    GetReclaimableNotes {
+        account_id: AccountId,
+        reply: Reply,
+    },
+    ReclaimNotes {
+        account_id: AccountId,
+        note_ids: Vec<NoteId>,
+        reply: Reply,
+    },
+    GetTransactions {
+        filter: tx_history::TxHistoryFilter,
+        reply: Reply,
+    },
+    GetTransactionStatus {
+        tx_id: TransactionId,
+        reply: Reply,
+    },
+    GetOutputNotes {
+        account_id: Option<AccountId>,
+        reply: Reply,
+    },
+    GetInputNotesFiltered {
+        account_id: Option<AccountId>,
+        filter: types::WcNoteFilter,
+        reply: Reply,
+    },
+    GetAccountsPage {
+        page: paging::Page,
+        reply: Reply,
+    },
+    GetInputNotesPage {
+        account_id: Option<AccountId>,
+        page: paging::Page,
+        reply: Reply,
+    },
+    OpenAccountObject {
+        account_id: AccountId,
+        response_tx: std::sync::mpsc::Sender<Result<objects::Object, i32>>,
+    },
+    OpenNoteObject {
+        note_id: NoteId,
+        response_tx: std::sync::mpsc::Sender<Result<objects::Object, i32>>,
+    },
+    ProveOwnership {
+        account_id: AccountId,
+        challenge: Vec<u8>,
+        reply: Reply,
+    },
+    ImportNote {
+        file: miden_objects::note::NoteFile,
+        reply: Reply,
+    },
+    ImportNotesBatch {
+        files: Vec<Result<miden_objects::note::NoteFile, i32>>,
+        reply: Reply,
+    },
+    GetAuditLog {
+        since: u64,
+        reply: Reply,
+    },
+    TravelRulePublicKey {
+        account_id: AccountId,
+        reply: Reply,
+    },
+    DecodeTravelRule {
+        account_id: AccountId,
+        envelope: serde_json::Value,
+        reply: Reply,
+    },
+    GeneratePorReport {
+        account_ids: Vec<AccountId>,
+        block_num: u32,
+        out_path: PathBuf,
+        reply: Reply,
+    },
+    SignExport {
+        account_id: AccountId,
+        path: PathBuf,
+        reply: Reply,
+    },
+    CheckNetwork {
+        reply: Reply,
+    },
+    CreateWalletsBulk {
+        base: [u8; 32],
+        first_index: u32,
+        count: u32,
+        reply: Reply,
+    },
+    CreateWalletDerived {
+        master_seed: Vec<u8>,
+        index: u32,
+        storage_mode: AccountStorageMode,
+        reply: Reply,
+    },
+    SetWithdrawalWhitelist {
+        account_id: AccountId,
+        addresses: Option<std::collections::BTreeSet<AccountId>>,
+        passphrase: String,
+        reply: Reply,
+    },
+    #[cfg(feature = "scripts")]
+    ResolveName {
+        registry: name_registry::NameRegistry,
+        key: miden_objects::Word,
+        reply: Reply,
+    },
+    #[cfg(feature = "scripts")]
+    RegisterName {
+        registry: name_registry::NameRegistry,
+        account_id: AccountId,
+        key: miden_objects::Word,
+        reply: Reply,
+    },
+    CreateEphemeralAccount {
+        main_account_id: AccountId,
+        ttl_blocks: u32,
+        reply: Reply,
+    },
+    SetSplitPolicy {
+        account_id: AccountId,
+        policy: Option<split::SplitPolicy>,
+        reply: Reply,
+    },
+    ApplySplit {
+        note_id: NoteId,
+        reply: Reply,
+    },
+    CreateEscrow {
+        payer: AccountId,
+        payee: AccountId,
+        faucet_id: AccountId,
+        amount: u64,
+        reclaim_after_blocks: u32,
+        reply: Reply,
+    },
+    ReleaseEscrow {
+        escrow_id: NoteId,
+        reply: Reply,
+    },
+    RefundEscrow {
+        escrow_id: NoteId,
+        reply: Reply,
+    },
+    ListEscrows {
+        reply: Reply,
+    },
+    Send {
+        account_id: AccountId,
+        target: AccountId,
+        faucet_id: AccountId,
+        amount: u64,
+        note_type: NoteType,
+        reclaim_block: Option<u32>,
+        reply: Reply,
+    },
+    StartStream {
+        sender: AccountId,
+        recipient: AccountId,
+        faucet_id: AccountId,
+        amount_per_hour: u64,
+        budget: u64,
+        reply: Reply,
+    },
+    TopUpStream {
+        stream_id: String,
+        amount: u64,
+        reply: Reply,
+    },
+    CancelStream {
+        stream_id: String,
+        reply: Reply,
+    },
+    ListStreams {
+        reply: Reply,
+    },
+    ScheduleStreamClaims {
+        account_id: AccountId,
+        sender: AccountId,
+        interval_blocks: u32,
+        reply: Reply,
+    },
+    #[cfg(feature = "scripts")]
+    CreateBridgeRequest {
+        operator: bridge::BridgeOperator,
+        account_id: AccountId,
+        target_chain: u64,
+        target_address: String,
+        faucet_id: AccountId,
+        amount: u64,
+        reply: Reply,
+    },
+    #[cfg(feature = "scripts")]
+    ConfirmBridgeFulfillment {
+        request_id: NoteId,
+        target_tx_hash: String,
+        reply: Reply,
+    },
+    #[cfg(feature = "scripts")]
+    ListBridgeRequests {
+        reply: Reply,
+    },
+    #[cfg(feature = "faucets")]
+    CreateFaucet {
+        seed: [u8; 32],
+        symbol: String,
+        decimals: u8,
+        max_supply: u64,
+        reply: Reply,
+    },
+    #[cfg(feature = "faucets")]
+    Mint {
+        faucet_id: AccountId,
+        target: AccountId,
+        amount: u64,
+        note_type: NoteType,
+        reply: Reply,
+    },
+    #[cfg(feature = "swaps")]
+    CreateSwap {
+        account_id: AccountId,
+        offered: miden_objects::asset::FungibleAsset,
+        requested: miden_objects::asset::FungibleAsset,
+        note_type: NoteType,
+        reply: Reply,
+    },
+    RenderNoteSummary {
+        note_id: NoteId,
+        locale: Option<String>,
+        reply: Reply,
+    },
+    TrackTxLive {
+        tx_id: TransactionId,
+        subscriber: Option<live_tx::LiveSubscriber>,
+        reply: Reply,
+    },
+    BackgroundSync {
+        deadline: std::time::Instant,
+        reply: Reply,
+    },
+    SyncAccount {
+        account_id: AccountId,
+        reply: Reply,
+    },
+    RegisterPush {
+        relay_url: String,
+        device_token: String,
+        tags: Option<Vec<u32>>,
+        reply: Reply,
+    },
+    HandlePush {
+        note_id: NoteId,
+        reply: Reply,
+    },
+    DelegateWatch {
+        service_url: String,
+        note_ids: Vec<NoteId>,
+        reply: Reply,
+    },
+    IngestWatchAlert {
+        alert: watch::WatchAlert,
+        reply: Reply,
+    },
+    Observe {
+        observer: Option<observer::Observer>,
+        reply: Reply,
+    },
+    ExportKey {
+        account_id: AccountId,
+        passphrase: String,
+        reply: Reply,
+    },
+    ImportKey {
+        envelope: Vec<u8>,
+        passphrase: String,
+        reply: Reply,
+    },
+    GetPublicKey {
+        account_id: AccountId,
+        reply: Reply,
+    },
+    SignMessage {
+        account_id: AccountId,
+        message: Vec<u8>,
+        reply: Reply,
+    },
+    RekeyKeystore {
+        old_passphrase: Option<String>,
+        new_passphrase: Option<String>,
+        reply: Reply,
+    },
+    ChangeStoreKey {
+        old_key: String,
+        new_key: String,
+        reply: Reply,
+    },
+    BackupStore {
+        dest: PathBuf,
+        reply: Reply,
+    },
+    RestoreStore {
+        src: PathBuf,
+        reply: Reply,
+    },
+    /// One round of `wc_miden_wait_for_transaction`, queued again until the wait ends
+    WaitForTransaction {
+        tx_id: TransactionId,
+        operation: cancel::Operation,
+        deadline: std::time::Instant,
+        reply: Reply,
+    },
     
+    // Async request registered for cancellation (see cancel.rs)
+    Cancellable {
+        operation: cancel::Operation,
+        request: Box<Request>,
+    },
+
     // Control
     Shutdown,
 }
 
+impl Request {
+    /// Operation name used for metrics and error history
+    fn name(&self) -> &'static str {
+        match self {
+            Request::SyncSync { .. } | Request::SyncAsync { .. } => "sync",
+            Request::CreateWalletSync { .. } | Request::CreateWalletAsync { .. } => "create_wallet",
+            Request::GetAccountsSync { .. } | Request::GetAccountsAsync { .. } => "get_accounts",
+            Request::GetBalanceSync { .. } | Request::GetBalanceAsync { .. } => "get_balance",
+            Request::GetInputNotesSync { .. } | Request::GetInputNotesAsync { .. } => "get_input_notes",
+            Request::ConsumeNotesSync { .. } | Request::ConsumeNotesAsync { .. } => "consume_notes",
+            Request::TestConnectionSync { .. } | Request::TestConnectionAsync { .. } => "test_connection",
+            Request::ScanDeposits { .. } => "scan_deposits",
+            Request::BuildUnsignedTx { .. } => "build_unsigned_tx",
+            Request::SubmitSignedTx { .. } => "submit_signed_tx",
+            Request::CreateWalletWithSigner { .. } => "create_wallet_with_signer",
+            Request::CreateWalletWithKeyBackend { .. } => "create_wallet_with_key_backend",
+            Request::CreateRotatableWallet { .. } => "create_rotatable_wallet",
+            Request::RotateAuthKey { .. } => "rotate_auth_key",
+            Request::SubmitTxSpec { .. } => "submit_tx_spec",
+            Request::SetRetentionPolicy { .. } => "set_retention_policy",
+            Request::ExportDiagnostics { .. } => "export_diagnostics",
+            Request::GetAccountStatus { .. } => "get_account_status",
+            Request::GetAccountDetails { .. } => "get_account_details",
+            Request::FaucetDecimals { .. } => "faucet_decimals",
+            Request::FaucetMetadata { .. } => "faucet_metadata",
+            Request::ImportAccountById { .. } => "import_account_by_id",
+            Request::DeployAccount { .. } => "deploy_account",
+            Request::SetAccountArchived { .. } => "set_account_archived",
+            Request::ExportNoteInclusionProof { .. } => "export_note_inclusion_proof",
+            Request::ExportNote { .. } => "export_note",
+            Request::EstimateTimeForBlock { .. } => "estimate_time_for_block",
+            Request::AuditState { .. } => "audit_state",
+            Request::GetReclaimableNotes { .. } => "get_reclaimable_notes",
+            Request::ReclaimNotes { .. } => "reclaim_notes",
+            Request::GetTransactions { .. } => "get_transactions",
+            Request::GetTransactionStatus { .. } => "get_transaction_status",
+            Request::GetOutputNotes { .. } => "get_output_notes",
+            Request::GetInputNotesFiltered { .. } => "get_input_notes_filtered",
+            Request::GetAccountsPage { .. } => "get_accounts_page",
+            Request::GetInputNotesPage { .. } => "get_input_notes_page",
+            Request::OpenAccountObject { .. } => "open_account_object",
+            Request::OpenNoteObject { .. } => "open_note_object",
+            Request::ProveOwnership { .. } => "prove_ownership",
+            Request::ImportNote { .. } => "import_note",
+            Request::ImportNotesBatch { .. } => "import_notes_batch",
+            Request::GetAuditLog { .. } => "get_audit_log",
+            Request::TravelRulePublicKey { .. } => "travel_rule_public_key",
+            Request::DecodeTravelRule { .. } => "decode_travel_rule",
+            Request::GeneratePorReport { .. } => "generate_por_report",
+            Request::SignExport { .. } => "sign_export",
+            Request::CheckNetwork { .. } => "check_network",
+            Request::CreateWalletsBulk { .. } => "create_wallets_bulk",
+            Request::CreateWalletDerived { .. } => "create_wallet_derived",
+            Request::SetWithdrawalWhitelist { .. } => "set_withdrawal_whitelist",
+            #[cfg(feature = "scripts")]
+            Request::ResolveName { .. } => "resolve_name",
+            #[cfg(feature = "scripts")]
+            Request::RegisterName { .. } => "register_name",
+            Request::CreateEphemeralAccount { .. } => "create_ephemeral_account",
+            Request::SetSplitPolicy { .. } => "set_split_policy",
+            Request::ApplySplit { .. } => "apply_split",
+            Request::CreateEscrow { .. } => "create_escrow",
+            Request::ReleaseEscrow { .. } => "release_escrow",
+            Request::RefundEscrow { .. } => "refund_escrow",
+            Request::ListEscrows { .. } => "list_escrows",
+            Request::Send { .. } => "send",
+            Request::StartStream { .. } => "start_stream",
+            Request::TopUpStream { .. } => "top_up_stream",
+            Request::CancelStream { .. } => "cancel_stream",
+            Request::ListStreams { .. } => "list_streams",
+            Request::ScheduleStreamClaims { .. } => "schedule_stream_claims",
+            #[cfg(feature = "scripts")]
+            Request::CreateBridgeRequest { .. } => "create_bridge_request",
+            #[cfg(feature = "scripts")]
+            Request::ConfirmBridgeFulfillment { .. } => "confirm_bridge_fulfillment",
+            #[cfg(feature = "scripts")]
+            Request::ListBridgeRequests { .. } => "list_bridge_requests",
+            #[cfg(feature = "faucets")]
+            Request::CreateFaucet { .. } => "create_faucet",
+            #[cfg(feature = "faucets")]
+            Request::Mint { .. } => "mint",
+            #[cfg(feature = "swaps")]
+            Request::CreateSwap { .. } => "create_swap",
+            Request::RenderNoteSummary { .. } => "render_note_summary",
+            Request::TrackTxLive { .. } => "track_tx_live",
+            Request::BackgroundSync { .. } => "background_sync",
+            Request::SyncAccount { .. } => "sync_account",
+            Request::RegisterPush { .. } => "register_push",
+            Request::HandlePush { .. } => "handle_push",
+            Request::DelegateWatch { .. } => "delegate_watch",
+            Request::IngestWatchAlert { .. } => "ingest_watch_alert",
+            Request::Observe { .. } => "observe",
+            Request::ExportKey { .. } => "export_key",
+            Request::ImportKey { .. } => "import_key",
+            Request::GetPublicKey { .. } => "get_public_key",
+            Request::SignMessage { .. } => "sign_message",
+            Request::RekeyKeystore { .. } => "rekey_keystore",
+            Request::ChangeStoreKey { .. } => "change_store_key",
+            Request::BackupStore { .. } => "backup_store",
+            Request::RestoreStore { .. } => "restore_store",
+            Request::WaitForTransaction { .. } => "wait_for_transaction",
+            Request::Cancellable { request, .. } => request.name(),
+            Request::Shutdown => "shutdown",
+        }
+    }
+
+    /// Deliver an error code to the callback of an async request without running it
+    ///
+    /// Returns None for requests answered through a blocking channel.
+    fn failure_reply(&self) -> Option<Box<dyn FnOnce(i32)>> {
+        let null = std::ptr::null_mut();
+        let reply: Box<dyn FnOnce(i32)> = match *self {
+            Request::SyncAsync { callback, user_data } => {
+                Box::new(move |code| callback(user_data as *mut std::ffi::c_void, code, 0))
+            }
+            Request::TestConnectionAsync { callback, user_data } => {
+                Box::new(move |code| callback(user_data as *mut std::ffi::c_void, code))
+            }
+            Request::CreateWalletAsync { callback, user_data, .. }
+            | Request::GetAccountsAsync { callback, user_data }
+            | Request::GetBalanceAsync { callback, user_data, .. }
+            | Request::GetInputNotesAsync { callback, user_data, .. }
+            | Request::ConsumeNotesAsync { callback, user_data, .. } => {
+                Box::new(move |code| callback(user_data as *mut std::ffi::c_void, code, null, 0))
+            }
+            Request::Send { reply: Reply::Callback { callback, user_data }, .. }
+            | Request::ScanDeposits { reply: Reply::Callback { callback, user_data }, .. }
+            | Request::ImportAccountById {
+                reply: Reply::Callback { callback, user_data }, ..
+            }
+            | Request::DeployAccount { reply: Reply::Callback { callback, user_data }, .. } => {
+                Box::new(move |code| callback(user_data as *mut std::ffi::c_void, code, null, 0))
+            }
+            _ => return None,
+        };
+        Some(reply)
+    }
+
+    /// Whether the request only reads the store (see observer.rs and deposits.rs)
+    fn is_read_only(&self) -> bool {
+        match self {
+            Request::Cancellable { request, .. } => request.is_read_only(),
+            _ => matches!(
+                self,
+                Request::GetAccountsSync { .. }
+                    | Request::GetAccountsAsync { .. }
+                    | Request::GetBalanceSync { .. }
+                    | Request::GetBalanceAsync { .. }
+                    | Request::GetInputNotesSync { .. }
+                    | Request::GetInputNotesAsync { .. }
+                    | Request::ScanDeposits { .. }
+                    | Request::GetAccountStatus { .. }
+                    | Request::GetAccountDetails { .. }
+                    | Request::FaucetDecimals { .. }
+                    | Request::FaucetMetadata { .. }
+                    | Request::ExportNote { .. }
+                    | Request::EstimateTimeForBlock { .. }
+                    | Request::GetReclaimableNotes { .. }
+                    | Request::GetTransactions { .. }
+                    | Request::GetTransactionStatus { .. }
+                    | Request::GetOutputNotes { .. }
+                    | Request::GetInputNotesFiltered { .. }
+                    | Request::GetAccountsPage { .. }
+                    | Request::GetInputNotesPage { .. }
+                    | Request::OpenAccountObject { .. }
+                    | Request::OpenNoteObject { .. }
+                    | Request::ProveOwnership { .. }
+                    | Request::CheckNetwork { .. }
+                    | Request::Observe { .. }
+                    | Request::ExportKey { .. }
+                    | Request::GetPublicKey { .. }
+                    | Request::SignMessage { .. }
+                    | Request::BackupStore { .. }
+            ),
+        }
+    }
+
+    /// Whether the request can be aborted while running (reads and syncs)
+    ///
+    /// Wallet creation and transactions run to completion once started: dropping them
+    /// halfway could leave the store behind the node.
+    fn is_interruptible(&self) -> bool {
+        matches!(
+            self,
+            Request::SyncAsync { .. }
+                | Request::TestConnectionAsync { .. }
+                | Request::GetAccountsAsync { .. }
+                | Request::GetBalanceAsync { .. }
+                | Request::GetInputNotesAsync { .. }
+                | Request::ScanDeposits { .. }
+        )
+    }
+}
+
 // Result types for sync operations
 type SyncResult = Result<u32, i32>;
 type CreateWalletResult = Result<String, i32>;
@@ -188,22 +944,128 @@ type GetInputNotesResult = Result<String, i32>;
 type ConsumeNotesResult = Result<String, i32>;
 type TestConnectionResult = Result<(), i32>;
 
+/// Destination of a worker result: a blocking FFI caller or an async C callback
+enum Reply {
+    Blocking(std::sync::mpsc::Sender<Result<Vec<u8>, i32>>),
+    Callback {
+        callback: BytesCallback,
+        user_data: usize,
+    },
+}
+
+impl Reply {
+    /// Deliver the result (callback data is leaked and must be freed with wc_bytes_free)
+    #[track_caller]
+    fn send(self, result: Result<Vec<u8>, i32>) {
+        diagnostics::note_result(&result);
+        match self {
+            Reply::Blocking(tx) => {
+                let _ = tx.send(result);
+            }
+            Reply::Callback { callback, user_data } => {
+                let user_data_ptr = user_data as *mut std::ffi::c_void;
+                match result {
+                    Ok(bytes) => {
+                        let (ptr, len) = leak_bytes(bytes);
+                        callback(user_data_ptr, 0, ptr, len);
+                    }
+                    Err(code) => callback(user_data_ptr, code, std::ptr::null_mut(), 0),
+                }
+            }
+        }
+    }
+
+    /// Deliver a JSON/string result
+    #[track_caller]
+    fn send_string(self, result: Result<String, i32>) {
+        self.send(result.map(String::into_bytes));
+    }
+}
+
 /// Client context (lives entirely in worker thread)
 struct MidenContext {
     client: MidenClient,
-    keystore: Arc<MidenKeyStore>,
+    keystore: Arc<keystore::KeystoreRouter>,
+    authenticator: Arc<WalletAuthenticator>,
+    keystore_path: PathBuf,
+    store_path: PathBuf,
+    /// Path the SQLite store is opened with (a URI with the key of an encrypted store)
+    database_path: PathBuf,
+    /// Path the state files are named after: the store path, or the tenant's (see tenants.rs)
+    state_path: PathBuf,
+    /// Tenant the client is scoped to (see tenants.rs)
+    tenant: Option<Arc<tenants::TenantScope>>,
+    retention: retention::Retention,
+    whitelists: whitelist::Whitelists,
+    ephemeral: ephemeral::EphemeralAccounts,
+    splits: split::SplitPolicies,
+    escrows: escrow::Escrows,
+    streams: streams::Streams,
+    #[cfg(feature = "scripts")]
+    bridge: bridge::BridgeRequests,
+    live_txs: live_tx::LiveTransactions,
+    watches: watch::Watches,
+    archive: archive::Archive,
+    block_times: block_times::BlockTimes,
+    /// Node RPC client, rate limited per request (see rate_limit.rs)
+    rpc: Arc<rate_limit::RateLimitedRpc>,
+    /// Proxy the RPC client connects through (see node_proxy.rs)
+    node_proxy: node_proxy::NodeProxy,
+    network: network::NetworkGuard,
+    /// Refuse wallet creation until the store has synced (see create_preflight.rs)
+    require_sync_for_create: bool,
+    /// Memory (MB) a proof may need (see proving.rs)
+    proving_memory_ceiling_mb: Option<u64>,
+    connection: reconnect::Connection,
+    progress: Arc<sync_progress::SyncProgress>,
+    events: Arc<events::EventBus>,
+    read_path: Arc<read_path::ReadPath>,
+    deposit_index: deposits::DepositIndex,
+    reorg: reorg::ReorgGuard,
+    rpc_metrics: Arc<rpc_metrics::RpcMetrics>,
+    observer: observer::StoreObserver,
+    /// The worker's own queue, for requests that queue themselves again (see tx_history.rs);
+    /// weak, so destroying the handle still closes it
+    requests: mpsc::WeakSender<Request>,
 }
 
 /// Handle structure containing sender to worker thread
 pub struct MidenWorkerHandle {
-    /// Sender to worker thread (Option to allow taking/dropping in destroy)
-    sender: Option<mpsc::Sender<Request>>,
+    /// Sender to worker thread (Option to allow taking/dropping in destroy, which may race
+    /// calls still holding the handle)
+    sender: RwLock<Option<mpsc::Sender<Request>>>,
+    worker_thread: Mutex<Option<JoinHandle<()>>>,
+    /// Sync counters shared with the worker (readable while the worker is busy)
+    progress: Arc<sync_progress::SyncProgress>,
+    /// Event callback registration shared with the worker
+    events: Arc<events::EventBus>,
+    /// Reader thread answering blocking queries while the worker is busy (see read_path.rs)
+    read_path: Arc<read_path::ReadPath>,
+    /// Node RPC rate limiter shared with the worker
+    rpc_limiter: Arc<rate_limit::RateLimiter>,
+    /// Per-method node RPC metrics shared with the worker
+    rpc_metrics: Arc<rpc_metrics::RpcMetrics>,
+    /// Storage state shared with the worker (degraded when the store cannot be written)
+    storage: Arc<storage_health::StorageHealth>,
+    /// Name registry used by name resolution and registration
+    #[cfg(feature = "scripts")]
+    name_registry: name_registry::NameRegistryConfig,
+    /// Bridge operator used by bridge requests
+    #[cfg(feature = "scripts")]
+    bridge_operator: bridge::BridgeOperatorConfig,
+    /// Message of the last failed request, written by the worker
+    last_error: Arc<last_error::LastError>,
+    /// MPC signing sessions opened on this handle (see mpc.rs)
+    mpc_sessions: mpc::MpcSessions,
+    /// dApp sessions opened on this handle (see dapp_session.rs)
+    dapp_sessions: dapp_session::DappSessions,
+    /// Keeps the tenant marked as open (handles created with a `tenant_id`)
     #[allow(dead_code)]
-    worker_thread: Option<JoinHandle<()>>,
+    tenant: Option<tenants::TenantLease>,
 }
 
-/// Opaque handle type for FFI
-pub type MidenHandle = *mut MidenWorkerHandle;
+/// Opaque handle type for FFI (0 is never valid, see handles.rs)
+pub type MidenHandle = u64;
 
 // ================================================================================================
 // Memory Management for FFI
@@ -218,6 +1080,10 @@ pub extern "C" fn wc_bytes_free(ptr: *mut u8, len: usize) {
     if ptr.is_null() {
         return;
     }
+    // With `memory-debug`, a buffer this library did not hand out is reported, not freed
+    if !memory::release(ptr, len) {
+        return;
+    }
     // Reconstruct Vec from raw parts and drop it
     // This matches the allocation in leak_bytes()
     unsafe { drop(Vec::from_raw_parts(ptr, len, len)); }
@@ -226,39 +1092,100 @@ pub extern "C" fn wc_bytes_free(ptr: *mut u8, len: usize) {
 /// Leak a Vec<u8> for FFI, returning (ptr, len)
 /// 
 /// The caller is responsible for calling wc_bytes_free(ptr, len) to release.
-fn leak_bytes(mut v: Vec<u8>) -> (*mut u8, usize) {
-    let ptr = v.as_mut_ptr();
-    let len = v.len();
-    std::mem::forget(v);
+/// The capacity is trimmed to the length first, as `wc_bytes_free` rebuilds the Vec
+/// with `capacity == len`. JSON is rewritten in canonical mode (see json.rs). The buffer
+/// is registered with the caller's location for `wc_memory_report` (see memory.rs).
+#[track_caller]
+fn leak_bytes(v: Vec<u8>) -> (*mut u8, usize) {
+    let canonical = match json::prepare_output(&v) {
+        Cow::Owned(canonical) => Some(canonical),
+        Cow::Borrowed(_) => None,
+    };
+    let v = canonical.unwrap_or(v);
+    let boxed = v.into_boxed_slice();
+    let len = boxed.len();
+    let ptr = Box::into_raw(boxed) as *mut u8;
+    memory::track(ptr, len, std::panic::Location::caller());
     (ptr, len)
 }
 
+/// Hand a blocking result to the caller in a Rust-allocated buffer (`_alloc` variants)
+#[track_caller]
+fn write_out_alloc(result: Result<String, i32>, out_ptr: *mut *mut u8, out_len: *mut usize) -> i32 {
+    let (ptr, len, code) = match result {
+        Ok(json) => {
+            let (ptr, len) = leak_bytes(json.into_bytes());
+            (ptr, len, 0)
+        }
+        Err(code) => (std::ptr::null_mut(), 0, code),
+    };
+    unsafe {
+        *out_ptr = ptr;
+        *out_len = len;
+    }
+    code
+}
+
 // ================================================================================================
 // Worker Thread Implementation
 // ================================================================================================
 
-/// Start worker thread with single-threaded Tokio runtime
-fn start_worker(
-    keystore_path: PathBuf,
-    store_path: PathBuf,
-    endpoint: Endpoint,
-) -> Result<MidenWorkerHandle, String> {
+/// Start worker thread with its Tokio runtime (single-threaded unless configured otherwise)
+fn start_worker(config: config::ClientConfig) -> Result<MidenWorkerHandle, String> {
     let (tx, rx) = mpsc::channel::<Request>(WORKER_QUEUE_CAPACITY);
+    let progress = Arc::new(sync_progress::SyncProgress::default());
+    let events = Arc::new(events::EventBus::default());
+    let rpc_limiter = Arc::new(rate_limit::RateLimiter::default());
+    let rpc_metrics = Arc::new(rpc_metrics::RpcMetrics::default());
+    let last_error = Arc::new(last_error::LastError::default());
+    let read_path = Arc::new(read_path::ReadPath::new(last_error.clone()));
+    let storage = Arc::new(storage_health::StorageHealth::new(&config.store_path));
+    let worker_progress = progress.clone();
+    let worker_events = events.clone();
+    let worker_read_path = read_path.clone();
+    let worker_limiter = rpc_limiter.clone();
+    let worker_metrics = rpc_metrics.clone();
+    let worker_last_error = last_error.clone();
+    let worker_storage = storage.clone();
+    let worker_requests = tx.downgrade();
     
     // Use std channel for init result
     let (init_tx, init_rx) = std::sync::mpsc::channel::<Result<(), String>>();
     
     let worker_thread = std::thread::spawn(move || {
-        // Create single-threaded Tokio runtime
-        let rt = tokio::runtime::Builder::new_current_thread()
+        last_error::install(worker_last_error);
+        storage_health::install(worker_storage.clone());
+        diagnostics::install(config.debug);
+
+        // Create Tokio runtime (the event loop always runs on this thread)
+        let mut builder = if config.worker_threads == 0 {
+            tokio::runtime::Builder::new_current_thread()
+        } else {
+            let mut builder = tokio::runtime::Builder::new_multi_thread();
+            builder.worker_threads(config.worker_threads);
+            builder
+        };
+        let rt = builder
             .enable_all()
             .build()
             .expect("Failed to create Tokio runtime");
         
         rt.block_on(async move {
             // Initialize context
-            let context = match create_context_async(keystore_path, store_path, endpoint).await {
+            let context = match create_context_async(
+                config,
+                worker_progress,
+                worker_events,
+                worker_read_path,
+                worker_limiter,
+                worker_metrics,
+                worker_requests,
+            )
+            .await
+            {
                 Ok(ctx) => {
+                    // The store exists now: start degraded if it cannot be written
+                    worker_storage.check_now();
                     let _ = init_tx.send(Ok(()));
                     ctx
                 }
@@ -276,8 +1203,22 @@ fn start_worker(
     // Wait for initialization result
     match init_rx.recv() {
         Ok(Ok(())) => Ok(MidenWorkerHandle {
-            sender: Some(tx),
-            worker_thread: Some(worker_thread),
+            sender: RwLock::new(Some(tx)),
+            worker_thread: Mutex::new(Some(worker_thread)),
+            progress,
+            events,
+            read_path,
+            rpc_limiter,
+            rpc_metrics,
+            storage,
+            #[cfg(feature = "scripts")]
+            name_registry: name_registry::NameRegistryConfig::default(),
+            #[cfg(feature = "scripts")]
+            bridge_operator: bridge::BridgeOperatorConfig::default(),
+            last_error,
+            mpc_sessions: mpc::MpcSessions::default(),
+            dapp_sessions: dapp_session::DappSessions::default(),
+            tenant: None,
         }),
         Ok(Err(e)) => Err(e),
         Err(_) => Err("Worker thread initialization failed".to_string()),
@@ -286,169 +1227,801 @@ fn start_worker(
 
 /// Asynchronously create MidenContext
 async fn create_context_async(
-    keystore_path: PathBuf,
-    store_path: PathBuf,
-    endpoint: Endpoint,
+    config: config::ClientConfig,
+    progress: Arc<sync_progress::SyncProgress>,
+    events: Arc<events::EventBus>,
+    read_path: Arc<read_path::ReadPath>,
+    rpc_limiter: Arc<rate_limit::RateLimiter>,
+    rpc_metrics: Arc<rpc_metrics::RpcMetrics>,
+    requests: mpsc::WeakSender<Request>,
 ) -> Result<MidenContext, String> {
-    // Create directories if they don't exist
-    if let Some(parent) = keystore_path.parent() {
-        std::fs::create_dir_all(parent).ok();
+    let config::ClientConfig {
+        keystore_path,
+        store_path,
+        endpoint,
+        network,
+        timeout_ms,
+        debug,
+        require_sync_for_create,
+        proving_memory_ceiling_mb,
+        keystore_passphrase,
+        store_key,
+        compression,
+        max_message_bytes,
+        tenant_id,
+        ..
+    } = config;
+
+    // A tenant has its own keystore and state files, next to the shared ones
+    let (keystore_path, state_path) = match &tenant_id {
+        Some(tenant_id) => (
+            tenants::tenant_keystore_path(&keystore_path, tenant_id),
+            tenants::tenant_state_path(&store_path, tenant_id),
+        ),
+        None => (keystore_path, store_path.clone()),
+    };
+    let tenant = tenant_id.map(|tenant_id| Arc::new(tenants::TenantScope::new(tenant_id)));
+
+    // Create directories if they don't exist (an in-memory client has none)
+    if !memory_store::is_in_memory(&keystore_path) {
+        if let Some(parent) = keystore_path.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+        std::fs::create_dir_all(&keystore_path).ok();
+        if let Some(state_dir) = state_path.parent() {
+            std::fs::create_dir_all(state_dir).ok();
+        }
     }
-    std::fs::create_dir_all(&keystore_path).ok();
 
     // Initialize keystore
-    let keystore = Arc::new(
-        FilesystemKeyStore::<StdRng>::new(keystore_path)
-            .map_err(|e| format!("Failed to create keystore: {:?}", e))?
-    );
-
-    // Create RPC client
-    let timeout_ms = 10_000;
-    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+    let keystore = keystore::KeystoreRouter::new(&keystore_path, keystore_passphrase.as_deref())?;
+    let keystore = Arc::new(keystore);
+
+    let authenticator = Arc::new(WalletAuthenticator::new(keystore.clone()));
+    let retention = retention::Retention::load(&state_path);
+    let whitelists = whitelist::Whitelists::load(&state_path);
+    let ephemeral = ephemeral::EphemeralAccounts::load(&state_path);
+    let splits = split::SplitPolicies::load(&state_path);
+    let escrows = escrow::Escrows::load(&state_path);
+    let streams = streams::Streams::load(&state_path);
+    #[cfg(feature = "scripts")]
+    let bridge = bridge::BridgeRequests::load(&state_path);
+    let live_txs = live_tx::LiveTransactions::load(&state_path);
+    let watches = watch::Watches::load(&state_path);
+    let archive = archive::Archive::load(&state_path);
+    let block_times = block_times::BlockTimes::load(&store_path);
+    let network = network::NetworkGuard::load(&store_path, network);
+
+    // Create RPC client, connected through the node proxy
+    let node_proxy =
+        node_proxy::NodeProxy::start(endpoint.clone(), timeout_ms, compression, max_message_bytes);
+    let grpc = GrpcClient::new(node_proxy.endpoint(), timeout_ms);
+    let rpc_client = Arc::new(rate_limit::RateLimitedRpc::new(
+        grpc,
+        &endpoint,
+        rpc_limiter,
+        rpc_metrics.clone(),
+    ));
 
     // Build Client
-    let client = ClientBuilder::new()
-        .rpc(rpc_client)
-        .sqlite_store(store_path)
-        .authenticator(keystore.clone())
-        .in_debug_mode(false.into())
+    let database_path = encrypted_store::database_path(&store_path, store_key.as_deref());
+    let client = build_client(
+        rpc_client.clone(),
+        database_path.clone(),
+        tenant.clone(),
+        authenticator.clone(),
+        debug,
+    )
+    .await?;
+
+    if let Ok(height) = client.get_sync_height().await {
+        progress.set_local_height(height.as_u32());
+    }
+
+    let context = MidenContext {
+        client,
+        keystore,
+        authenticator,
+        keystore_path,
+        store_path,
+        database_path,
+        state_path,
+        tenant,
+        retention,
+        whitelists,
+        ephemeral,
+        splits,
+        escrows,
+        streams,
+        #[cfg(feature = "scripts")]
+        bridge,
+        live_txs,
+        watches,
+        archive,
+        block_times,
+        rpc: rpc_client,
+        node_proxy,
+        network,
+        require_sync_for_create,
+        proving_memory_ceiling_mb,
+        connection: reconnect::Connection::new(endpoint, timeout_ms, debug),
+        progress,
+        events,
+        read_path,
+        deposit_index: deposits::DepositIndex::default(),
+        reorg: reorg::ReorgGuard::default(),
+        rpc_metrics,
+        observer: observer::StoreObserver::default(),
+        requests,
+    };
+    context.read_path.start(&context);
+    Ok(context)
+}
+
+/// Build the `miden-client` instance of a context (also used to reconnect)
+///
+/// With a tenant, the store is scoped to it (see tenants.rs).
+async fn build_client(
+    rpc: Arc<rate_limit::RateLimitedRpc>,
+    store_path: PathBuf,
+    tenant: Option<Arc<tenants::TenantScope>>,
+    authenticator: Arc<WalletAuthenticator>,
+    debug: bool,
+) -> Result<MidenClient, String> {
+    let builder = ClientBuilder::new()
+        .rpc(rpc)
+        .authenticator(authenticator)
+        .in_debug_mode(debug.into());
+    let store = open_store(&store_path).await?;
+    #[cfg(not(feature = "testing"))]
+    let store: Arc<dyn miden_client::store::Store> = Arc::new(store);
+    // Store and prover behind the injected-failure points (see testing.rs)
+    #[cfg(feature = "testing")]
+    let (builder, store) = testing::with_fail_points(builder, store);
+    finish_client(builder, store, &store_path, tenant).await
+}
+
+/// Build the client of the read path: like `build_client`, but without the injected-failure
+/// points, which belong to the worker's requests
+async fn build_reader_client(
+    rpc: Arc<rate_limit::RateLimitedRpc>,
+    store_path: PathBuf,
+    tenant: Option<Arc<tenants::TenantScope>>,
+    authenticator: Arc<WalletAuthenticator>,
+    debug: bool,
+) -> Result<MidenClient, String> {
+    let builder = ClientBuilder::new()
+        .rpc(rpc)
+        .authenticator(authenticator)
+        .in_debug_mode(debug.into());
+    let store = Arc::new(open_store(&store_path).await?);
+    finish_client(builder, store, &store_path, tenant).await
+}
+
+async fn open_store(store_path: &Path) -> Result<SqliteStore, String> {
+    SqliteStore::new(store_path.to_path_buf())
+        .await
+        .map_err(|e| format!("Failed to open store: {:?}", e))
+}
+
+async fn finish_client(
+    builder: ClientBuilder<WalletAuthenticator>,
+    store: Arc<dyn miden_client::store::Store>,
+    store_path: &Path,
+    tenant: Option<Arc<tenants::TenantScope>>,
+) -> Result<MidenClient, String> {
+    let store = match tenant {
+        Some(scope) => Arc::new(tenants::TenantStore::open(store, store_path, scope)?),
+        None => store,
+    };
+
+    builder
+        .store(store)
         .build()
         .await
-        .map_err(|e| format!("Failed to build client: {:?}", e))?;
+        .map_err(|e| format!("Failed to build client: {:?}", e))
+}
 
-    Ok(MidenContext { client, keystore })
+/// Close the store's connections by moving the client onto an empty in-memory store, for
+/// maintenance that needs exclusive access (rekey, restore); see `reopen_client`
+async fn park_client(context: &mut MidenContext) -> Result<(), String> {
+    context.read_path.stop();
+    let (_, parking_path) = memory_store::paths();
+    let debug = context.connection.debug;
+    let parked =
+        build_client(context.rpc.clone(), parking_path, None, context.authenticator.clone(), debug)
+            .await?;
+    drop(std::mem::replace(&mut context.client, parked));
+    Ok(())
+}
+
+/// Reopen the client on the store after `park_client`
+async fn reopen_client(context: &mut MidenContext) -> Result<(), String> {
+    let path = context.database_path.clone();
+    let debug = context.connection.debug;
+    let tenant = context.tenant.clone();
+    context.client =
+        build_client(context.rpc.clone(), path, tenant, context.authenticator.clone(), debug)
+            .await?;
+    context.read_path.start(context);
+    Ok(())
 }
 
 /// Worker event loop - processes requests sequentially
 async fn worker_event_loop(mut context: MidenContext, mut rx: mpsc::Receiver<Request>) {
     while let Some(request) = rx.recv().await {
+        let operation = request.name();
+        let read_only = request.is_read_only();
+        let started = std::time::Instant::now();
+        diagnostics::begin_operation(operation);
+        last_error::clear_pending();
+
+        if !read_only {
+            context.deposit_index.invalidate();
+        }
+
         match request {
             Request::Shutdown => break,
-            
-            // Sync operations
-            Request::SyncSync { response_tx } => {
-                let result = context.client.sync_state().await;
-                let _ = response_tx.send(match result {
-                    Ok(summary) => Ok(summary.block_num.as_u32()),
-                    Err(e) => {
-                        eprintln!("[wc_miden_sync] sync_state failed: {:?}", e);
-                        Err(ERR_INVALID_HANDLE)
-                    }
-                });
-            }
-            
-            Request::CreateWalletSync { seed, response_tx } => {
-                let result = create_wallet_impl(&mut context, seed).await;
-                let _ = response_tx.send(result);
-            }
-            
-            Request::GetAccountsSync { response_tx } => {
-                let result = get_accounts_impl(&context).await;
-                let _ = response_tx.send(result);
-            }
-            
-            Request::GetBalanceSync { account_id, account_id_str, response_tx } => {
-                let result = get_balance_impl(&context, account_id, &account_id_str).await;
-                let _ = response_tx.send(result);
-            }
-            
-            Request::GetInputNotesSync { account_id, response_tx } => {
-                let result = get_input_notes_impl(&context, account_id).await;
-                let _ = response_tx.send(result);
+            Request::Cancellable { operation: op, request } => {
+                cancel::run(&mut context, op, *request).await;
             }
-            
-            Request::ConsumeNotesSync { account_id, note_ids, response_tx } => {
-                let result = consume_notes_impl(&mut context, account_id, note_ids).await;
-                let _ = response_tx.send(result);
+            request => handle_request(&mut context, request).await,
+        }
+        if !read_only {
+            observer::publish_changes(&mut context).await;
+            context.read_path.publish_block_times(&context.block_times);
+        }
+
+        diagnostics::end_operation(operation, started.elapsed());
+        reconnect::reconnect_if_needed(&mut context).await;
+    }
+    context.read_path.stop();
+}
+
+/// Store-local work after a successful sync (retention, sweeps, payouts, status updates)
+async fn run_after_sync(context: &mut MidenContext) {
+    retention::apply_after_sync(context).await;
+    block_times::backfill_after_sync(context).await;
+    ephemeral::sweep_after_sync(context).await;
+    streams::run_after_sync(context).await;
+    #[cfg(feature = "scripts")]
+    bridge::update_after_sync(context).await;
+    live_tx::update_after_sync(context).await;
+    widget::update_after_sync(context).await;
+}
+
+/// Process one request on the worker
+async fn handle_request(context: &mut MidenContext, request: Request) {
+    match request {
+        // Handled by the event loop
+        Request::Shutdown | Request::Cancellable { .. } => {}
+        
+        // Sync operations
+        Request::SyncSync { response_tx } => {
+            let result = sync_state_impl(context).await;
+            diagnostics::note_result(&result);
+            if result.is_ok() {
+                run_after_sync(context).await;
             }
-            
-            Request::TestConnectionSync { response_tx } => {
-                let result = context.client.sync_state().await;
-                let _ = response_tx.send(match result {
-                    Ok(_) => Ok(()),
-                    Err(_) => Err(ERR_INVALID_HANDLE),
-                });
+            let _ = response_tx.send(result);
+        }
+        
+        Request::CreateWalletSync { seed, storage_mode, response_tx } => {
+            let result =
+                create_wallet_impl(context, seed, storage_mode, KeyBackend::Filesystem).await;
+            diagnostics::note_result(&result);
+            let _ = response_tx.send(result);
+        }
+        
+        Request::GetAccountsSync { response_tx } => {
+            let result = get_accounts_impl(&context.read_view()).await;
+            diagnostics::note_result(&result);
+            let _ = response_tx.send(result);
+        }
+        
+        Request::GetBalanceSync { account_id, account_id_str, response_tx } => {
+            let result = get_balance_impl(&context.read_view(), account_id, &account_id_str).await;
+            diagnostics::note_result(&result);
+            let _ = response_tx.send(result);
+        }
+        
+        Request::GetInputNotesSync { account_id, response_tx } => {
+            let result = get_input_notes_impl(&context.read_view(), account_id).await;
+            diagnostics::note_result(&result);
+            let _ = response_tx.send(result);
+        }
+        
+        Request::ConsumeNotesSync { account_id, note_ids, response_tx } => {
+            let result = consume_notes_impl(context, account_id, note_ids).await;
+            diagnostics::note_result(&result);
+            let _ = response_tx.send(result);
+        }
+        
+        Request::TestConnectionSync { response_tx } => {
+            let result = sync_state_impl(context).await.map(|_| ());
+            diagnostics::note_result(&result);
+            let _ = response_tx.send(result);
+        }
+        
+        // Async operations
+        Request::SyncAsync { callback, user_data } => {
+            let result = sync_state_impl(context).await;
+            diagnostics::note_result(&result);
+            if result.is_ok() {
+                run_after_sync(context).await;
             }
-            
-            // Async operations
-            Request::SyncAsync { callback, user_data } => {
-                let result = context.client.sync_state().await;
-                let user_data_ptr = user_data as *mut std::ffi::c_void;
-                match result {
-                    Ok(summary) => callback(user_data_ptr, 0, summary.block_num.as_u32()),
-                    Err(_) => callback(user_data_ptr, ERR_INVALID_HANDLE, 0),
-                }
+            let user_data_ptr = user_data as *mut std::ffi::c_void;
+            match result {
+                Ok(block_num) => callback(user_data_ptr, 0, block_num),
+                Err(code) => callback(user_data_ptr, code, 0),
             }
-            
-            Request::CreateWalletAsync { seed, callback, user_data } => {
-                let result = create_wallet_impl(&mut context, seed).await;
-                let user_data_ptr = user_data as *mut std::ffi::c_void;
-                match result {
-                    Ok(account_id_hex) => {
-                        let (ptr, len) = leak_bytes(account_id_hex.into_bytes());
-                        callback(user_data_ptr, 0, ptr, len);
-                    }
-                    Err(code) => callback(user_data_ptr, code, std::ptr::null_mut(), 0),
+        }
+        
+        Request::CreateWalletAsync { seed, storage_mode, callback, user_data } => {
+            let result =
+                create_wallet_impl(context, seed, storage_mode, KeyBackend::Filesystem).await;
+            diagnostics::note_result(&result);
+            let user_data_ptr = user_data as *mut std::ffi::c_void;
+            match result {
+                Ok(account_id_hex) => {
+                    let (ptr, len) = leak_bytes(account_id_hex.into_bytes());
+                    callback(user_data_ptr, 0, ptr, len);
                 }
+                Err(code) => callback(user_data_ptr, code, std::ptr::null_mut(), 0),
             }
-            
-            Request::GetAccountsAsync { callback, user_data } => {
-                let result = get_accounts_impl(&context).await;
-                let user_data_ptr = user_data as *mut std::ffi::c_void;
-                match result {
-                    Ok(json) => {
-                        let (ptr, len) = leak_bytes(json.into_bytes());
-                        callback(user_data_ptr, 0, ptr, len);
-                    }
-                    Err(code) => callback(user_data_ptr, code, std::ptr::null_mut(), 0),
+        }
+        
+        Request::GetAccountsAsync { callback, user_data } => {
+            let result = get_accounts_impl(&context.read_view()).await;
+            diagnostics::note_result(&result);
+            let user_data_ptr = user_data as *mut std::ffi::c_void;
+            match result {
+                Ok(json) => {
+                    let (ptr, len) = leak_bytes(json.into_bytes());
+                    callback(user_data_ptr, 0, ptr, len);
                 }
+                Err(code) => callback(user_data_ptr, code, std::ptr::null_mut(), 0),
             }
-            
-            Request::GetBalanceAsync { account_id, account_id_str, callback, user_data } => {
-                let result = get_balance_impl(&context, account_id, &account_id_str).await;
-                let user_data_ptr = user_data as *mut std::ffi::c_void;
-                match result {
-                    Ok(json) => {
-                        let (ptr, len) = leak_bytes(json.into_bytes());
-                        callback(user_data_ptr, 0, ptr, len);
-                    }
-                    Err(code) => callback(user_data_ptr, code, std::ptr::null_mut(), 0),
+        }
+        
+        Request::GetBalanceAsync { account_id, account_id_str, callback, user_data } => {
+            let result = get_balance_impl(&context.read_view(), account_id, &account_id_str).await;
+            diagnostics::note_result(&result);
+            let user_data_ptr = user_data as *mut std::ffi::c_void;
+            match result {
+                Ok(json) => {
+                    let (ptr, len) = leak_bytes(json.into_bytes());
+                    callback(user_data_ptr, 0, ptr, len);
                 }
+                Err(code) => callback(user_data_ptr, code, std::ptr::null_mut(), 0),
             }
-            
-            Request::GetInputNotesAsync { account_id, callback, user_data } => {
-                let result = get_input_notes_impl(&context, account_id).await;
-                let user_data_ptr = user_data as *mut std::ffi::c_void;
-                match result {
-                    Ok(json) => {
-                        let (ptr, len) = leak_bytes(json.into_bytes());
-                        callback(user_data_ptr, 0, ptr, len);
-                    }
-                    Err(code) => callback(user_data_ptr, code, std::ptr::null_mut(), 0),
+        }
+        
+        Request::GetInputNotesAsync { account_id, callback, user_data } => {
+            let result = get_input_notes_impl(&context.read_view(), account_id).await;
+            diagnostics::note_result(&result);
+            let user_data_ptr = user_data as *mut std::ffi::c_void;
+            match result {
+                Ok(json) => {
+                    let (ptr, len) = leak_bytes(json.into_bytes());
+                    callback(user_data_ptr, 0, ptr, len);
                 }
+                Err(code) => callback(user_data_ptr, code, std::ptr::null_mut(), 0),
             }
-            
-            Request::ConsumeNotesAsync { account_id, note_ids, callback, user_data } => {
-                let result = consume_notes_impl(&mut context, account_id, note_ids).await;
-                let user_data_ptr = user_data as *mut std::ffi::c_void;
-                match result {
-                    Ok(tx_id_hex) => {
-                        let (ptr, len) = leak_bytes(tx_id_hex.into_bytes());
-                        callback(user_data_ptr, 0, ptr, len);
-                    }
-                    Err(code) => callback(user_data_ptr, code, std::ptr::null_mut(), 0),
+        }
+        
+        Request::ConsumeNotesAsync { account_id, note_ids, callback, user_data } => {
+            let result = consume_notes_impl(context, account_id, note_ids).await;
+            diagnostics::note_result(&result);
+            let user_data_ptr = user_data as *mut std::ffi::c_void;
+            match result {
+                Ok(tx_id_hex) => {
+                    let (ptr, len) = leak_bytes(tx_id_hex.into_bytes());
+                    callback(user_data_ptr, 0, ptr, len);
                 }
+                Err(code) => callback(user_data_ptr, code, std::ptr::null_mut(), 0),
             }
-            
-            Request::TestConnectionAsync { callback, user_data } => {
-                let result = context.client.sync_state().await;
-                let user_data_ptr = user_data as *mut std::ffi::c_void;
-                match result {
-                    Ok(_) => callback(user_data_ptr, 0),
-                    Err(_) => callback(user_data_ptr, ERR_INVALID_HANDLE),
-                }
+        }
+        
+        Request::TestConnectionAsync { callback, user_data } => {
+            let result = sync_state_impl(context).await;
+            diagnostics::note_result(&result);
+            let user_data_ptr = user_data as *mut std::ffi::c_void;
+            match result {
+                Ok(_) => callback(user_data_ptr, 0),
+                Err(code) => callback(user_data_ptr, code),
             }
         }
+
+        Request::ScanDeposits { scan, reply } => {
+            reply.send_string(deposits::scan_deposits_impl(context, scan).await);
+        }
+
+        Request::BuildUnsignedTx { account_id, spec, reply } => {
+            reply.send(cold_wallet::build_unsigned_tx_impl(context, account_id, spec).await);
+        }
+
+        Request::SubmitSignedTx { unsigned, signature, reply } => {
+            reply.send_string(cold_wallet::submit_signed_tx_impl(context, unsigned, signature).await);
+        }
+
+        Request::CreateWalletWithSigner { seed, storage_mode, signer, reply } => {
+            let result =
+                signer::create_wallet_with_signer_impl(context, seed, storage_mode, signer).await;
+            reply.send_string(result);
+        }
+
+        Request::CreateWalletWithKeyBackend { seed, storage_mode, backend, reply } => {
+            let result = create_wallet_impl(context, seed, storage_mode, backend).await;
+            reply.send_string(result);
+        }
+
+        Request::CreateRotatableWallet { seed, storage_mode, backend, reply } => {
+            let result =
+                key_rotation::create_rotatable_wallet_impl(context, seed, storage_mode, backend)
+                    .await;
+            reply.send_string(result);
+        }
+
+        Request::RotateAuthKey { account_id, reply } => {
+            reply.send_string(key_rotation::rotate_auth_key_impl(context, account_id).await);
+        }
+
+        Request::SubmitTxSpec { account_id, spec, reply } => {
+            reply.send_string(cold_wallet::submit_tx_spec_impl(context, account_id, spec).await);
+        }
+
+        Request::SetRetentionPolicy { policy, dry_run, reply } => {
+            reply.send_string(retention::set_retention_policy_impl(context, policy, dry_run).await);
+        }
+
+        Request::ExportDiagnostics { out_path, redact_level, reply } => {
+            reply.send_string(diagnostics::export_diagnostics_impl(context, out_path, redact_level).await);
+        }
+
+        Request::GetAccountStatus { account_id, reply } => {
+            reply.send(get_account_status_impl(context, account_id).await.map(|status| vec![status as u8]));
+        }
+
+        Request::GetAccountDetails { account_id, reply } => {
+            reply.send_string(get_account_details_impl(context, account_id).await);
+        }
+
+        Request::ImportAccountById { account_id, signer, reply } => {
+            let result = account_import::import_account_by_id_impl(context, account_id, signer).await;
+            reply.send_string(result);
+        }
+
+        Request::DeployAccount { account_id, reply } => {
+            reply.send_string(deploy::deploy_account_impl(context, account_id).await);
+        }
+
+        Request::SetAccountArchived { account_id, archived, reply } => {
+            let result = archive::set_account_archived_impl(context, account_id, archived).await;
+            reply.send(result.map(|()| Vec::new()));
+        }
+
+        Request::FaucetDecimals { faucet_id, reply } => {
+            let result = amount::faucet_decimals_impl(context, faucet_id).await;
+            reply.send(result.map(|decimals| vec![decimals]));
+        }
+
+        Request::FaucetMetadata { faucet_id, reply } => {
+            reply.send_string(amount::faucet_metadata_impl(context, faucet_id).await);
+        }
+
+        Request::ExportNoteInclusionProof { note_id, reply } => {
+            reply.send(inclusion_proof::export_note_inclusion_proof_impl(context, note_id).await);
+        }
+
+        Request::ExportNote { note_id, export_type, reply } => {
+            reply.send(note_export::export_note_impl(context, note_id, export_type).await);
+        }
+
+        Request::EstimateTimeForBlock { block_num, reply } => {
+            reply.send_string(block_times::estimate_time_for_block_impl(context, block_num));
+        }
+
+        Request::AuditState { account_id, repair, reply } => {
+            reply.send_string(state_audit::audit_state_impl(context, account_id, repair).await);
+        }
+
+        Request::GetReclaimableNotes { account_id, reply } => {
+            reply.send_string(recallable::get_reclaimable_notes_impl(context, account_id).await);
+        }
+
+        Request::ReclaimNotes { account_id, note_ids, reply } => {
+            reply.send_string(recallable::reclaim_notes_impl(context, account_id, note_ids).await);
+        }
+
+        Request::GetTransactions { filter, reply } => {
+            reply.send_string(tx_history::get_transactions_impl(context, filter).await);
+        }
+
+        Request::GetTransactionStatus { tx_id, reply } => {
+            reply.send_string(tx_history::get_transaction_status_impl(context, tx_id).await);
+        }
+
+        Request::GetOutputNotes { account_id, reply } => {
+            reply.send_string(output_notes::get_output_notes_impl(context, account_id).await);
+        }
+
+        Request::GetInputNotesFiltered { account_id, filter, reply } => {
+            let result = input_notes::get_input_notes_filtered_impl(context, account_id, filter);
+            reply.send_string(result.await);
+        }
+
+        Request::GetAccountsPage { page, reply } => {
+            reply.send_string(paging::get_accounts_page_impl(context, page).await);
+        }
+
+        Request::GetInputNotesPage { account_id, page, reply } => {
+            let result = paging::get_input_notes_page_impl(context, account_id, page);
+            reply.send_string(result.await);
+        }
+
+        Request::OpenAccountObject { account_id, response_tx } => {
+            let result = objects::open_account_impl(context, account_id).await;
+            diagnostics::note_result(&result);
+            let _ = response_tx.send(result);
+        }
+
+        Request::OpenNoteObject { note_id, response_tx } => {
+            let result = objects::open_note_impl(context, note_id).await;
+            diagnostics::note_result(&result);
+            let _ = response_tx.send(result);
+        }
+
+        Request::ProveOwnership { account_id, challenge, reply } => {
+            let result = ownership::prove_ownership_impl(context, account_id, challenge).await;
+            reply.send_string(result);
+        }
+
+        Request::ImportNote { file, reply } => {
+            reply.send_string(note_import::import_note_impl(context, file).await);
+        }
+
+        Request::ImportNotesBatch { files, reply } => {
+            reply.send_string(note_import::import_notes_batch_impl(context, files).await);
+        }
+
+        Request::GetAuditLog { since, reply } => {
+            reply.send_string(audit_log::read_audit_log_impl(context, since));
+        }
+
+        Request::TravelRulePublicKey { account_id, reply } => {
+            reply.send_string(travel_rule::travel_rule_public_key_impl(context, account_id).await);
+        }
+
+        Request::DecodeTravelRule { account_id, envelope, reply } => {
+            reply.send_string(travel_rule::decode_travel_rule_impl(context, account_id, envelope).await);
+        }
+
+        Request::GeneratePorReport { account_ids, block_num, out_path, reply } => {
+            let result = reserves::generate_por_report_impl(context, account_ids, block_num, out_path).await;
+            reply.send_string(result);
+        }
+
+        Request::SignExport { account_id, path, reply } => {
+            reply.send_string(export_signing::sign_export_impl(context, account_id, path).await);
+        }
+
+        Request::CheckNetwork { reply } => {
+            reply.send_string(network::check_network_impl(context).await);
+        }
+
+        Request::CreateWalletsBulk { base, first_index, count, reply } => {
+            let result =
+                provisioning::create_wallets_bulk_impl(context, base, first_index, count).await;
+            reply.send_string(result);
+        }
+
+        Request::CreateWalletDerived { master_seed, index, storage_mode, reply } => {
+            let result =
+                derivation::create_wallet_derived_impl(context, master_seed, index, storage_mode)
+                    .await;
+            reply.send_string(result);
+        }
+
+        Request::SetWithdrawalWhitelist { account_id, addresses, passphrase, reply } => {
+            let result = whitelist::set_withdrawal_whitelist_impl(context, account_id, addresses, passphrase);
+            reply.send_string(result);
+        }
+
+        #[cfg(feature = "scripts")]
+        Request::ResolveName { registry, key, reply } => {
+            reply.send_string(name_registry::resolve_name_impl(context, registry, key).await);
+        }
+
+        #[cfg(feature = "scripts")]
+        Request::RegisterName { registry, account_id, key, reply } => {
+            let result = name_registry::register_name_impl(context, registry, account_id, key).await;
+            reply.send_string(result);
+        }
+
+        Request::CreateEphemeralAccount { main_account_id, ttl_blocks, reply } => {
+            let result = ephemeral::create_ephemeral_account_impl(context, main_account_id, ttl_blocks).await;
+            reply.send_string(result);
+        }
+
+        Request::SetSplitPolicy { account_id, policy, reply } => {
+            reply.send_string(split::set_split_policy_impl(context, account_id, policy));
+        }
+
+        Request::ApplySplit { note_id, reply } => {
+            reply.send_string(split::apply_split_impl(context, note_id).await);
+        }
+
+        Request::CreateEscrow { payer, payee, faucet_id, amount, reclaim_after_blocks, reply } => {
+            let result = escrow::create_escrow_impl(
+                context, payer, payee, faucet_id, amount, reclaim_after_blocks,
+            ).await;
+            reply.send_string(result);
+        }
+
+        Request::ReleaseEscrow { escrow_id, reply } => {
+            reply.send(escrow::release_escrow_impl(context, escrow_id));
+        }
+
+        Request::RefundEscrow { escrow_id, reply } => {
+            reply.send_string(escrow::refund_escrow_impl(context, escrow_id).await);
+        }
+
+        Request::ListEscrows { reply } => {
+            reply.send_string(escrow::list_escrows_impl(context));
+        }
+
+        Request::Send { account_id, target, faucet_id, amount, note_type, reclaim_block, reply } => {
+            let result = send_impl(
+                context, account_id, target, faucet_id, amount, note_type, reclaim_block,
+            ).await;
+            reply.send_string(result);
+        }
+
+        Request::StartStream { sender, recipient, faucet_id, amount_per_hour, budget, reply } => {
+            let result = streams::start_stream_impl(
+                context, sender, recipient, faucet_id, amount_per_hour, budget,
+            ).await;
+            reply.send_string(result);
+        }
+
+        Request::TopUpStream { stream_id, amount, reply } => {
+            reply.send_string(streams::top_up_stream_impl(context, stream_id, amount));
+        }
+
+        Request::CancelStream { stream_id, reply } => {
+            reply.send_string(streams::cancel_stream_impl(context, stream_id).await);
+        }
+
+        Request::ListStreams { reply } => {
+            reply.send_string(streams::list_streams_impl(context));
+        }
+
+        Request::ScheduleStreamClaims { account_id, sender, interval_blocks, reply } => {
+            let result = streams::schedule_stream_claims_impl(context, account_id, sender, interval_blocks);
+            reply.send_string(result);
+        }
+
+        #[cfg(feature = "scripts")]
+        Request::CreateBridgeRequest {
+            operator, account_id, target_chain, target_address, faucet_id, amount, reply,
+        } => {
+            let result = bridge::create_bridge_request_impl(
+                context, operator, account_id, target_chain, target_address, faucet_id,
+                amount,
+            ).await;
+            reply.send_string(result);
+        }
+
+        #[cfg(feature = "scripts")]
+        Request::ConfirmBridgeFulfillment { request_id, target_tx_hash, reply } => {
+            let result = bridge::confirm_bridge_fulfillment_impl(context, request_id, target_tx_hash);
+            reply.send_string(result);
+        }
+
+        #[cfg(feature = "scripts")]
+        Request::ListBridgeRequests { reply } => {
+            reply.send_string(bridge::list_bridge_requests_impl(context));
+        }
+
+        #[cfg(feature = "faucets")]
+        Request::CreateFaucet { seed, symbol, decimals, max_supply, reply } => {
+            let result = faucet::create_faucet_impl(context, seed, &symbol, decimals, max_supply).await;
+            reply.send_string(result);
+        }
+
+        #[cfg(feature = "faucets")]
+        Request::Mint { faucet_id, target, amount, note_type, reply } => {
+            reply.send_string(faucet::mint_impl(context, faucet_id, target, amount, note_type).await);
+        }
+
+        #[cfg(feature = "swaps")]
+        Request::CreateSwap { account_id, offered, requested, note_type, reply } => {
+            let result =
+                swap::create_swap_impl(context, account_id, offered, requested, note_type).await;
+            reply.send_string(result);
+        }
+
+        Request::RenderNoteSummary { note_id, locale, reply } => {
+            reply.send_string(note_summary::render_note_summary_impl(context, note_id, locale).await);
+        }
+
+        Request::TrackTxLive { tx_id, subscriber, reply } => {
+            reply.send_string(live_tx::track_tx_live_impl(context, tx_id, subscriber).await);
+        }
+
+        Request::BackgroundSync { deadline, reply } => {
+            let result = background_sync::background_sync_impl(context, deadline).await;
+            reply.send_string(result);
+        }
+
+        Request::SyncAccount { account_id, reply } => {
+            reply.send_string(account_sync::sync_account_impl(context, account_id).await);
+        }
+
+        Request::RegisterPush { relay_url, device_token, tags, reply } => {
+            let result = push::register_push_impl(context, relay_url, device_token, tags).await;
+            reply.send_string(result);
+        }
+
+        Request::HandlePush { note_id, reply } => {
+            reply.send_string(push::handle_push_impl(context, note_id).await);
+        }
+
+        Request::DelegateWatch { service_url, note_ids, reply } => {
+            let result = watch::delegate_watch_impl(context, service_url, note_ids).await;
+            reply.send_string(result);
+        }
+
+        Request::IngestWatchAlert { alert, reply } => {
+            let result = watch::ingest_watch_alert_impl(context, alert);
+            reply.send(result.map(|()| Vec::new()));
+        }
+
+        Request::Observe { observer, reply } => {
+            reply.send_string(observer::observe_impl(context, observer).await);
+        }
+
+        Request::ExportKey { account_id, passphrase, reply } => {
+            reply.send_string(key_backup::export_key_impl(context, account_id, passphrase).await);
+        }
+
+        Request::ImportKey { envelope, passphrase, reply } => {
+            reply.send_string(key_backup::import_key_impl(context, envelope, passphrase));
+        }
+
+        Request::GetPublicKey { account_id, reply } => {
+            reply.send_string(public_key::get_public_key_impl(context, account_id).await);
+        }
+
+        Request::SignMessage { account_id, message, reply } => {
+            let result = message_signing::sign_message_impl(context, account_id, message).await;
+            reply.send_string(result);
+        }
+
+        Request::RekeyKeystore { old_passphrase, new_passphrase, reply } => {
+            let result =
+                encrypted_keystore::rekey_keystore_impl(context, old_passphrase, new_passphrase);
+            reply.send(result.map(|()| Vec::new()));
+        }
+
+        Request::ChangeStoreKey { old_key, new_key, reply } => {
+            let result = encrypted_store::change_store_key_impl(context, old_key, new_key).await;
+            reply.send(result.map(|()| Vec::new()));
+        }
+
+        Request::BackupStore { dest, reply } => {
+            reply.send(store_backup::backup_store_impl(context, dest).map(|()| Vec::new()));
+        }
+
+        Request::RestoreStore { src, reply } => {
+            let result = store_backup::restore_store_impl(context, src).await;
+            reply.send(result.map(|()| Vec::new()));
+        }
+
+        Request::WaitForTransaction { tx_id, operation, deadline, reply } => {
+            tx_history::wait_round(context, tx_id, operation, deadline, reply).await;
+        }
     }
 }
 
@@ -456,155 +2029,263 @@ async fn worker_event_loop(mut context: MidenContext, mut rx: mpsc::Receiver<Req
 // Business Logic Implementations
 // ================================================================================================
 
-async fn create_wallet_impl(context: &mut MidenContext, init_seed: [u8; 32]) -> Result<String, i32> {
+/// Sync with the node, returning the synced block number
+async fn sync_state_impl(context: &mut MidenContext) -> Result<u32, i32> {
+    let traffic = context.node_proxy.traffic().count();
+    reorg::check(context).await;
+    if let Some(tip) = sync_progress::begin_sync(context).await {
+        reorg::set_checkpoint(context, &tip);
+        context.block_times.record(&tip);
+    }
+    let started = std::time::Instant::now();
+
+    // The store has one sync height, so a tenant's sync covers every tenant (see tenants.rs)
+    let store_wide = context.tenant.as_ref().map(|scope| scope.store_wide());
+    let synced = wallet_core::sync(&mut context.client).await;
+    drop(store_wide);
+
+    let result = match synced {
+        Ok(block_num) => {
+            reconnect::note_success(context);
+            Ok(block_num)
+        }
+        Err(e) => {
+            diagnostics::log("wc_miden_sync", format!("sync_state failed: {}", e));
+            reconnect::note_failure(context, &e);
+            Err(e.into_code())
+        }
+    };
+
+    sync_progress::record_traffic(context, context.node_proxy.traffic().since(traffic));
+    sync_progress::end_sync(context, &result, started.elapsed());
+    result
+}
+
+async fn create_wallet_impl(
+    context: &mut MidenContext,
+    init_seed: [u8; 32],
+    storage_mode: AccountStorageMode,
+    backend: KeyBackend,
+) -> Result<String, i32> {
     // Create key pair
     let key_pair = AuthSecretKey::new_rpo_falcon512();
     let auth_component: AccountComponent =
         AuthRpoFalcon512::new(key_pair.public_key().to_commitment()).into();
 
-    // Save key to keystore
-    context.keystore.add_key(&key_pair)
-        .map_err(|_| ERR_ACCOUNT_OP)?;
+    let account = wallet_core::build_wallet_account(init_seed, auth_component, storage_mode)
+        .map_err(wallet_core::CoreError::into_code)?;
+    add_wallet_account(context, account, backend, Some(&key_pair)).await
+}
 
-    // Build account
-    let account = AccountBuilder::new(init_seed)
-        .account_type(AccountType::RegularAccountImmutableCode)
-        .storage_mode(AccountStorageMode::Public)
-        .with_auth_component(auth_component)
-        .with_component(BasicWallet)
-        .build()
-        .map_err(|_| ERR_ACCOUNT_OP)?;
+/// Track a new wallet account in the client
+///
+/// `key` is saved to `backend` once the creation preflight has passed (None when the key
+/// is held elsewhere, e.g. by an external signer). The backend is recorded before the
+/// account is added and the record dropped if that fails, so an error means the account
+/// is not in the store.
+async fn add_wallet_account(
+    context: &mut MidenContext,
+    account: Account,
+    backend: KeyBackend,
+    key: Option<&AuthSecretKey>,
+) -> Result<String, i32> {
+    create_preflight::check(context, account.id()).await?;
+
+    // Save key to the filesystem or memory backend
+    if let Some(key) = key {
+        context.keystore.add_key(key, backend)
+            .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    }
 
-    // Add account to client
-    context.client.add_account(&account, false).await
-        .map_err(|_| ERR_ACCOUNT_OP)?;
+    // Record the backend, then add account to client
+    context.keystore.record_account(account.id(), backend)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    let added = wallet_core::add_account(&mut context.client, &account).await
+        .map_err(wallet_core::CoreError::into_code);
+    if let Err(code) = added {
+        if let Err(e) = context.keystore.forget_account(account.id()) {
+            diagnostics::log("create_wallet", format!("failed to drop key backend record: {}", e));
+        }
+        return Err(code);
+    }
+
+    Ok(account.id().to_hex())
+}
+
+/// IDs (hex) of the accounts in the store, in store order
+async fn account_ids(client: &MidenClient) -> Result<Vec<String>, i32> {
+    let accounts = wallet_core::account_ids(client).await
+        .map_err(wallet_core::CoreError::into_code)?;
+
+    Ok(accounts.into_iter().map(AccountId::to_hex).collect())
+}
+
+/// What the account, balance and input note queries read: the worker's context, or the
+/// read path's client and published state (see read_path.rs)
+struct ReadView<'a> {
+    client: &'a MidenClient,
+    archive: &'a archive::Archive,
+    block_times: &'a block_times::BlockTimes,
+}
+
+impl MidenContext {
+    fn read_view(&self) -> ReadView<'_> {
+        ReadView { client: &self.client, archive: &self.archive, block_times: &self.block_times }
+    }
+}
 
-    Ok(account.id().to_hex())
+async fn get_accounts_impl(view: &ReadView<'_>) -> Result<String, i32> {
+    Ok(json::to_string(&account_ids(view.client).await?))
 }
 
-async fn get_accounts_impl(context: &MidenContext) -> Result<String, i32> {
+async fn get_account_status_impl(context: &MidenContext, account_id: AccountId) -> Result<WcAccountStatus, i32> {
     let accounts = context.client.get_account_headers().await
-        .map_err(|_| ERR_ACCOUNT_OP)?;
-    
-    let account_ids: Vec<String> = accounts
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    accounts
         .iter()
-        .map(|(header, _status)| header.id().to_hex())
-        .collect();
-    
-    let json = format!("[{}]", 
-        account_ids.iter()
-            .map(|id| format!("\"{}\"", id))
-            .collect::<Vec<_>>()
-            .join(",")
-    );
-    
-    Ok(json)
+        .find(|(header, _status)| header.id() == account_id)
+        .map(|(_header, status)| WcAccountStatus::from(status))
+        .ok_or(ERR_LOOKUP)
 }
 
-async fn get_balance_impl(context: &MidenContext, account_id: AccountId, account_id_str: &str) -> Result<String, i32> {
-    let account_record = context.client.get_account(account_id).await
-        .map_err(|_| ERR_LOOKUP)?
-        .ok_or(ERR_LOOKUP)?;  // Account not found
-    
-    let account = account_record.account();
-    let vault = account.vault();
-
-    let mut fungible_assets = Vec::new();
-    let mut non_fungible_count = 0u32;
-
-    for asset in vault.assets() {
-        if asset.is_fungible() {
-            let fungible = asset.unwrap_fungible();
-            fungible_assets.push(format!(
-                r#"{{"faucet_id":"{}","amount":{}}}"#,
-                fungible.faucet_id().to_hex(),
-                fungible.amount()
-            ));
-        } else {
-            non_fungible_count += 1;
-        }
-    }
+async fn get_account_details_impl(
+    context: &MidenContext,
+    account_id: AccountId,
+) -> Result<String, i32> {
+    let status = get_account_status_impl(context, account_id).await?;
+    let backend = context.keystore.account_backend(account_id);
+
+    Ok(serde_json::json!({
+        "account_id": account_id.to_hex(),
+        "account_type": WcAccountType::from(account_id.account_type()).as_str(),
+        "storage_mode": WcStorageMode::from(account_id.storage_mode()).as_str(),
+        "status": status.as_str(),
+        "key_backend": backend.kind().as_str(),
+        "signer_scheme_id": backend.scheme_id(),
+        "archived": context.archive.contains(account_id),
+    })
+    .to_string())
+}
 
-    let json = format!(
-        r#"{{"account_id":"{}","fungible_assets":[{}],"total_fungible_count":{},"total_non_fungible_count":{}}}"#,
-        account_id_str,
-        fungible_assets.join(","),
-        fungible_assets.len(),
-        non_fungible_count
-    );
+async fn get_balance_impl(view: &ReadView<'_>, account_id: AccountId, account_id_str: &str) -> Result<String, i32> {
+    let assets = wallet_core::vault_assets(view.client, account_id).await
+        .map_err(wallet_core::CoreError::into_code)?;
+    let fungible_assets = json::AssetJson::fungible(assets.iter());
+
+    let balance = json::BalanceJson {
+        account_id: account_id_str.to_string(),
+        total_fungible_count: fungible_assets.len(),
+        total_non_fungible_count: assets.len() - fungible_assets.len(),
+        fungible_assets,
+    };
 
-    Ok(json)
+    Ok(json::to_string(&balance))
 }
 
-async fn get_input_notes_impl(context: &MidenContext, account_id: Option<AccountId>) -> Result<String, i32> {
-    let consumable_notes = context.client.get_consumable_notes(account_id).await
-        .map_err(|_| ERR_NOTE_OP)?;
+/// Consumable notes of `account_id` (of all active accounts when None)
+async fn consumable_note_entries(
+    view: &ReadView<'_>,
+    account_id: Option<AccountId>,
+) -> Result<Vec<json::InputNoteJson>, i32> {
+    let mut consumable_notes = wallet_core::consumable_notes(view.client, account_id).await
+        .map_err(wallet_core::CoreError::into_code)?;
+    if account_id.is_none() {
+        view.archive.retain_active(&mut consumable_notes);
+    }
     
-    let notes_json: Vec<String> = consumable_notes
+    let notes: Vec<json::InputNoteJson> = consumable_notes
         .iter()
-        .map(|(note_record, _consumability)| {
-            let assets_json: Vec<String> = note_record
-                .assets()
-                .iter()
-                .filter_map(|asset| {
-                    if asset.is_fungible() {
-                        let fungible = asset.unwrap_fungible();
-                        Some(format!(
-                            r#"{{"faucet_id":"{}","amount":{}}}"#,
-                            fungible.faucet_id().to_hex(),
-                            fungible.amount()
-                        ))
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-
-            format!(
-                r#"{{"note_id":"{}","assets":[{}],"is_authenticated":{}}}"#,
-                note_record.id().to_hex(),
-                assets_json.join(","),
-                note_record.is_authenticated()
-            )
+        .map(|(note_record, _consumability)| json::InputNoteJson {
+            note_id: note_record.id().to_hex(),
+            assets: json::AssetJson::fungible(note_record.assets().iter()),
+            is_authenticated: note_record.is_authenticated(),
+            timestamp: note_record.inclusion_proof().and_then(|proof| {
+                view.block_times.timestamp(proof.location().block_num().as_u32())
+            }),
+            state: None,
         })
         .collect();
 
-    let json = format!(
-        r#"{{"notes":[{}],"total_count":{}}}"#,
-        notes_json.join(","),
-        consumable_notes.len()
-    );
+    Ok(notes)
+}
 
-    Ok(json)
+async fn get_input_notes_impl(view: &ReadView<'_>, account_id: Option<AccountId>) -> Result<String, i32> {
+    let notes = consumable_note_entries(view, account_id).await?;
+    Ok(json::to_string(&json::InputNotesJson { total_count: notes.len(), notes }))
 }
 
 async fn consume_notes_impl(context: &mut MidenContext, account_id: AccountId, note_ids: Vec<NoteId>) -> Result<String, i32> {
-    let tx_request = TransactionRequestBuilder::new()
-        .build_consume_notes(note_ids)
-        .map_err(|_| ERR_NOTE_OP)?;
+    screening::screen_transaction(context, account_id, &[], &note_ids).await?;
 
-    let tx_id = context.client
-        .submit_new_transaction(account_id, tx_request)
-        .await
-        .map_err(|_| ERR_TX_SUBMIT)?;
+    let tx_request = wallet_core::consume_notes_request(note_ids)
+        .map_err(wallet_core::CoreError::into_code)?;
+
+    proving::check_memory(context)?;
+    let tx_id = wallet_core::submit(&mut context.client, account_id, tx_request).await
+        .map_err(wallet_core::CoreError::into_code)?;
 
     Ok(tx_id.to_hex())
 }
 
+/// Send a P2ID note, returning `{"tx_id":"0x..","note_id":"0x.."}`
+///
+/// A private note is only known to this store, so the result also carries it as a
+/// NoteFile (`"note_file"`, hex) for the recipient to import. With a `reclaim_block` the
+/// note is a P2IDE note the sender can recall from that block.
+#[allow(clippy::too_many_arguments)]
+async fn send_impl(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    target: AccountId,
+    faucet_id: AccountId,
+    amount: u64,
+    note_type: NoteType,
+    reclaim_block: Option<u32>,
+) -> Result<String, i32> {
+    // A note recallable right away would be a payment the sender can take back at will
+    let reclaim_block_passed = match reclaim_block {
+        Some(block) => block <= clock::current_block(context).await?,
+        None => false,
+    };
+    if reclaim_block_passed {
+        return Err(ERR_INVALID_PARAM);
+    }
+
+    let spec = cold_wallet::TxSpec::PayToId {
+        target,
+        faucet_id,
+        amount,
+        note_type,
+        reclaim_block,
+        travel_rule: None,
+    };
+    let (tx_id, unsigned) = cold_wallet::submit_spec(context, account_id, spec).await?;
+    let note_id = unsigned.output_note_ids().first().copied().ok_or(ERR_NOTE_OP)?;
+
+    let mut result = serde_json::json!({
+        "tx_id": tx_id.to_hex(),
+        "note_id": note_id.to_hex(),
+    });
+    if note_type == NoteType::Private {
+        // Not committed yet: the recipient finds the inclusion proof when it syncs
+        let export_type = types::WcNoteExportType::Partial;
+        let note_file = note_export::export_note_impl(context, note_id, export_type).await?;
+        result["note_file"] = hex::encode(note_file).into();
+    }
+    Ok(result.to_string())
+}
+
 // ================================================================================================
 // FFI Helper Functions
 // ================================================================================================
 
-/// Get a reference to the worker handle from a raw pointer
-/// 
-/// # Safety
-/// The returned reference is only valid for the duration of the current function call.
-/// Do NOT store this reference or pass it across function boundaries.
-fn get_handle<'a>(handle: MidenHandle) -> Option<&'a MidenWorkerHandle> {
-    if handle.is_null() {
-        return None;
-    }
-    Some(unsafe { &*handle })
+/// Look up the worker handle of an FFI handle (None when unknown or destroyed)
+///
+/// The worker handle stays alive while the returned `Arc` is held, even if the handle is
+/// destroyed meanwhile.
+fn get_handle(handle: MidenHandle) -> Option<Arc<MidenWorkerHandle>> {
+    handles::get(handle)
 }
 
 /// Try to send a request to the worker queue
@@ -613,7 +2294,13 @@ fn get_handle<'a>(handle: MidenHandle) -> Option<&'a MidenWorkerHandle> {
 /// - Ok(()) if sent successfully
 /// - Err(ERR_QUEUE_FULL) if queue is full
 /// - Err(ERR_INVALID_HANDLE) if channel is closed or sender is None
-fn try_send_request(sender: &Option<mpsc::Sender<Request>>, request: Request) -> Result<(), i32> {
+fn try_send_request(worker: &MidenWorkerHandle, request: Request) -> Result<(), i32> {
+    // Degraded mode (see storage_health.rs): only reads are queued
+    if !request.is_read_only() {
+        worker.storage.check_writable()?;
+    }
+
+    let sender = worker.sender.read().unwrap();
     let sender = sender.as_ref().ok_or(ERR_INVALID_HANDLE)?;
     match sender.try_send(request) {
         Ok(()) => Ok(()),
@@ -622,6 +2309,137 @@ fn try_send_request(sender: &Option<mpsc::Sender<Request>>, request: Request) ->
     }
 }
 
+/// Send a request answered through a `Reply` and block until the result arrives
+fn request_blocking(
+    worker: &MidenWorkerHandle,
+    build: impl FnOnce(Reply) -> Request,
+) -> Result<Vec<u8>, i32> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    try_send_request(worker, build(Reply::Blocking(tx)))?;
+
+    match rx.recv_timeout(SYNC_TIMEOUT) {
+        Ok(result) => result,
+        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => Err(ERR_TIMEOUT),
+        Err(_) => Err(ERR_INVALID_HANDLE),
+    }
+}
+
+/// Copy bytes into a caller-provided output buffer
+///
+/// `out_len` holds the buffer capacity on input and the written length on output. When
+/// `out` is NULL, or the buffer is too small, the required length is written instead
+/// (returning 0 and `ERR_BUFFER_TOO_SMALL` respectively), so callers can size the buffer
+/// with a first call.
+fn write_out_buffer(bytes: &[u8], out: *mut u8, out_len: *mut usize) -> i32 {
+    let bytes: &[u8] = &json::prepare_output(bytes);
+    if out_len.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+    if out.is_null() {
+        unsafe { *out_len = bytes.len() };
+        return 0;
+    }
+    let out_capacity = unsafe { *out_len };
+    unsafe { *out_len = bytes.len() };
+    if bytes.len() > out_capacity {
+        return ERR_BUFFER_TOO_SMALL;
+    }
+    let slice = unsafe { std::slice::from_raw_parts_mut(out, bytes.len()) };
+    slice.copy_from_slice(bytes);
+    0
+}
+
+/// Length of a transaction or note ID in hex (`0x` and 64 digits)
+const ID_HEX_LEN: usize = 66;
+
+/// Length of an account ID in hex (`0x` and 30 digits)
+const ACCOUNT_ID_HEX_LEN: usize = 32;
+
+/// Length of `{"tx_id":"0x..","note_id":"0x.."}`
+const SEND_RESULT_LEN: usize = r#"{"tx_id":"","note_id":""}"#.len() + 2 * ID_HEX_LEN;
+
+/// Check an output buffer against the length of the result before the request is queued
+///
+/// For operations that submit a transaction or create an account: a `ERR_BUFFER_TOO_SMALL`
+/// after the fact would make a caller that retries with a bigger buffer submit twice. As
+/// with `write_out_buffer`, the required length is written to `out_len` when `out` is NULL
+/// (`Err(0)`: the sizing call returns 0 without queuing anything) or too small.
+fn check_out_capacity(out: *mut u8, out_len: *mut usize, required: usize) -> Result<(), i32> {
+    if out_len.is_null() {
+        return Err(ERR_INVALID_PARAM);
+    }
+    if out.is_null() {
+        unsafe { *out_len = required };
+        return Err(0);
+    }
+    if unsafe { *out_len } < required {
+        unsafe { *out_len = required };
+        return Err(ERR_BUFFER_TOO_SMALL);
+    }
+    Ok(())
+}
+
+/// Length of a result shaped like `placeholder` once the output modes are applied
+///
+/// For `check_out_capacity` when the result is only known after the request ran: fields
+/// not known yet hold the widest value they can take (see `hex_placeholder`).
+fn output_len(placeholder: &serde_json::Value) -> usize {
+    json::prepare_output(placeholder.to_string().as_bytes()).len()
+}
+
+/// `0x`-prefixed hex placeholder of `len` characters (an ID length above)
+fn hex_placeholder(len: usize) -> String {
+    format!("0x{}", "0".repeat(len - 2))
+}
+
+/// Read a required C string argument
+fn parse_required_str<'a>(ptr: *const c_char) -> Result<&'a str, i32> {
+    if ptr.is_null() {
+        return Err(ERR_INVALID_PARAM);
+    }
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|_| ERR_INVALID_PARAM)
+}
+
+/// Read an optional C string argument (NULL or empty means None)
+fn parse_optional_str<'a>(ptr: *const c_char) -> Result<Option<&'a str>, i32> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    let s = unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|_| ERR_INVALID_PARAM)?;
+    Ok(if s.is_empty() { None } else { Some(s) })
+}
+
+/// Read a 32-byte account seed, generating a random one when `seed_ptr` is NULL
+fn parse_seed(seed_ptr: *const u8, seed_len: usize) -> Result<[u8; 32], i32> {
+    if seed_ptr.is_null() {
+        let mut s = [0u8; 32];
+        let mut rng = StdRng::from_os_rng();
+        rng.fill_bytes(&mut s);
+        return Ok(s);
+    }
+    if seed_len != 32 {
+        return Err(ERR_INVALID_PARAM);
+    }
+    let slice = unsafe { std::slice::from_raw_parts(seed_ptr, 32) };
+    let mut arr = [0u8; 32];
+    arr.copy_from_slice(slice);
+    Ok(arr)
+}
+
+/// Read the storage mode argument of the wallet creation functions (public or private)
+fn parse_wallet_storage_mode(storage_mode: u32) -> Result<AccountStorageMode, i32> {
+    match WcStorageMode::try_from(storage_mode)? {
+        WcStorageMode::Public => Ok(AccountStorageMode::Public),
+        WcStorageMode::Private => Ok(AccountStorageMode::Private),
+        // Network accounts are executed by the node and need network-enabled components
+        WcStorageMode::Network => Err(ERR_INVALID_PARAM),
+    }
+}
+
 fn parse_account_id(account_id_hex: *const c_char) -> Result<(AccountId, String), i32> {
     if account_id_hex.is_null() {
         return Err(ERR_INVALID_PARAM);
@@ -684,16 +2502,18 @@ fn parse_note_ids_json(json: &str) -> Result<Vec<NoteId>, i32> {
 /// 
 /// This starts a dedicated worker thread that owns the MidenClient.
 /// All operations are sent to this worker thread via channels.
+/// Runs with the default settings; use `wc_miden_create_with_config` to change them.
 /// 
 /// # Parameters
 /// - `keystore_path`: Keystore storage directory path (C string)
 /// - `store_path`: SQLite database file path (C string)
-/// - `rpc_endpoint`: RPC endpoint URL (C string, can be NULL to use testnet)
+/// - `rpc_endpoint`: RPC endpoint URL or network alias (`testnet`, `devnet`, `localhost`,
+///   `localhost:<port>`) (C string, can be NULL to use testnet)
 /// - `handle_out`: Output client handle
 /// 
 /// # Returns
 /// - 0: Success
-/// - -1: Invalid parameters
+/// - -1: Invalid parameters (including an endpoint that is neither a URL nor an alias)
 /// - -2: Initialization failed
 #[unsafe(no_mangle)]
 pub extern "C" fn wc_miden_create(
@@ -716,44 +2536,49 @@ pub extern "C" fn wc_miden_create(
         Err(_) => return -1,
     };
 
-    let endpoint = if rpc_endpoint.is_null() {
-        Endpoint::testnet()
-    } else {
-        match unsafe { CStr::from_ptr(rpc_endpoint) }.to_str() {
-            Ok(s) if s.is_empty() || s == "testnet" => Endpoint::testnet(),
-            Ok(_) => Endpoint::testnet(), // TODO: support custom endpoints
-            Err(_) => Endpoint::testnet(),
-        }
+    let (endpoint, network) = match parse_endpoint(rpc_endpoint) {
+        Ok(endpoint) => endpoint,
+        Err(code) => return code,
     };
-
-    match start_worker(keystore_path, store_path, endpoint) {
+    let config = config::ClientConfig::new(keystore_path, store_path, endpoint, network);
+    match start_worker(config) {
         Ok(handle) => {
-            let boxed = Box::new(handle);
-            unsafe { *handle_out = Box::into_raw(boxed) };
+            unsafe { *handle_out = handles::insert(handle) };
             0
         }
         Err(_) => -2,
     }
 }
 
+/// Resolve the RPC endpoint argument of the create functions: a URL or a network alias
+/// (NULL or empty means testnet)
+fn parse_endpoint(rpc_endpoint: *const c_char) -> Result<(Endpoint, network::Network), i32> {
+    match parse_optional_str(rpc_endpoint)? {
+        None => Ok((Endpoint::testnet(), network::Network::Testnet)),
+        Some(value) => network::resolve_endpoint(value),
+    }
+}
+
 /// Destroy client and release resources
 /// 
 /// Sends shutdown signal to worker thread and waits for it to finish.
-/// Safe to call multiple times - the handle pointer is set to NULL after destruction.
+/// Safe to call multiple times - the handle is set to 0 after destruction, and any other
+/// copy of it becomes invalid (calls return -2).
 /// 
 /// # Parameters
-/// - `handle_ptr`: Pointer to the handle (will be set to NULL after destruction)
+/// - `handle_ptr`: Pointer to the handle (will be set to 0 after destruction)
 /// 
 /// # Example (Swift)
 /// ```swift
-/// var handle: MidenHandle? = ...
-/// wc_miden_destroy(&handle)  // handle is now nil
+/// var handle: MidenHandle = ...
+/// wc_miden_destroy(&handle)  // handle is now 0
 /// ```
 /// 
 /// # Shutdown Semantics
 /// This performs a **fast shutdown**: pending requests in the queue will be dropped.
 /// Callbacks for in-flight async operations may not be invoked.
-/// If you need graceful shutdown (process all pending requests), do not use this pattern.
+/// Blocks until the running request finishes; from the main thread, or for a graceful
+/// shutdown (process all pending requests), use `wc_miden_destroy_async`.
 #[unsafe(no_mangle)]
 pub extern "C" fn wc_miden_destroy(handle_ptr: *mut MidenHandle) {
     if handle_ptr.is_null() {
@@ -761,17 +2586,16 @@ pub extern "C" fn wc_miden_destroy(handle_ptr: *mut MidenHandle) {
     }
     
     let handle = unsafe { *handle_ptr };
-    if handle.is_null() {
-        return;
-    }
-    
-    // Set to null BEFORE cleanup to prevent concurrent access
-    unsafe { *handle_ptr = std::ptr::null_mut() };
+    unsafe { *handle_ptr = 0 };
     
-    let mut worker_handle = unsafe { Box::from_raw(handle) };
+    // Unregister BEFORE cleanup so that concurrent and later calls see an invalid handle
+    let Some(worker_handle) = handles::remove(handle) else {
+        return;
+    };
     
     // Best-effort shutdown signal (may fail if queue is full)
-    if let Some(sender) = worker_handle.sender.take() {
+    let sender = worker_handle.sender.write().unwrap().take();
+    if let Some(sender) = sender {
         let _ = sender.try_send(Request::Shutdown);
         // Drop sender to close channel - guarantees worker will exit
         // even if queue was full and Shutdown wasn't received
@@ -779,7 +2603,8 @@ pub extern "C" fn wc_miden_destroy(handle_ptr: *mut MidenHandle) {
     }
     
     // Wait for worker thread to finish
-    if let Some(jh) = worker_handle.worker_thread.take() {
+    let worker_thread = worker_handle.worker_thread.lock().unwrap().take();
+    if let Some(jh) = worker_thread {
         let _ = jh.join();
     }
 }
@@ -808,7 +2633,7 @@ pub extern "C" fn wc_miden_sync(handle: MidenHandle, block_num_out: *mut u32) ->
     
     let (tx, rx) = std::sync::mpsc::channel();
     
-    if let Err(code) = try_send_request(&worker.sender, Request::SyncSync { response_tx: tx }) {
+    if let Err(code) = try_send_request(&worker, Request::SyncSync { response_tx: tx }) {
         return code;
     }
     
@@ -828,11 +2653,19 @@ pub extern "C" fn wc_miden_sync(handle: MidenHandle, block_num_out: *mut u32) ->
 /// Create a new wallet account (blocking)
 /// 
 /// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// `storage_mode` is a `WcStorageMode` value: 0 = public (state on chain), 1 = private
+/// (only the state commitment on chain; the state stays in this store, so back it up).
+///
+/// Fails with -105 when the seed's account already exists in the store or on chain, and
+/// with -106 before the first sync when `require_sync_for_create` is set. The output
+/// buffer must hold 32 bytes; a smaller one fails with -7 before the account is created.
 #[unsafe(no_mangle)]
 pub extern "C" fn wc_miden_create_wallet(
     handle: MidenHandle,
     seed_ptr: *const u8,
     seed_len: usize,
+    storage_mode: u32,
     account_id_out: *mut u8,
     account_id_out_len: *mut usize,
 ) -> i32 {
@@ -840,51 +2673,101 @@ pub extern "C" fn wc_miden_create_wallet(
         return ERR_INVALID_HANDLE;
     };
     
-    if account_id_out.is_null() || account_id_out_len.is_null() {
-        return ERR_INVALID_PARAM;
+    if let Err(code) = check_out_capacity(account_id_out, account_id_out_len, ACCOUNT_ID_HEX_LEN) {
+        return code;
     }
 
-    let seed: [u8; 32] = if seed_ptr.is_null() {
-        let mut s = [0u8; 32];
-        let mut rng = StdRng::from_os_rng();
-        rng.fill_bytes(&mut s);
-        s
-    } else {
-        if seed_len != 32 {
-            return ERR_INVALID_PARAM;
-        }
-        let slice = unsafe { std::slice::from_raw_parts(seed_ptr, 32) };
-        let mut arr = [0u8; 32];
-        arr.copy_from_slice(slice);
-        arr
+    let seed = match parse_seed(seed_ptr, seed_len) {
+        Ok(seed) => seed,
+        Err(code) => return code,
+    };
+    let storage_mode = match parse_wallet_storage_mode(storage_mode) {
+        Ok(storage_mode) => storage_mode,
+        Err(code) => return code,
     };
 
     let (tx, rx) = std::sync::mpsc::channel();
     
-    if let Err(code) = try_send_request(&worker.sender, Request::CreateWalletSync { seed, response_tx: tx }) {
+    let request = Request::CreateWalletSync { seed, storage_mode, response_tx: tx };
+    if let Err(code) = try_send_request(&worker, request) {
         return code;
     }
     
     match rx.recv_timeout(SYNC_TIMEOUT) {
-        Ok(Ok(account_id_hex)) => {
-            let out_capacity = unsafe { *account_id_out_len };
-            if account_id_hex.len() > out_capacity {
-                return ERR_INVALID_PARAM;
-            }
-            let out = unsafe { std::slice::from_raw_parts_mut(account_id_out, account_id_hex.len()) };
-            out.copy_from_slice(account_id_hex.as_bytes());
-            unsafe { *account_id_out_len = account_id_hex.len() };
-            0
-        }
+        Ok(Ok(account_id_hex)) => write_out_buffer(account_id_hex.as_bytes(), account_id_out, account_id_out_len),
         Ok(Err(code)) => code,
         Err(std::sync::mpsc::RecvTimeoutError::Timeout) => ERR_TIMEOUT,
         Err(_) => ERR_INVALID_HANDLE,
     }
 }
 
+/// Query all accounts on the read path or through the worker (shared by the buffer and
+/// `_alloc` variants)
+fn get_accounts_blocking(worker: &MidenWorkerHandle) -> GetAccountsResult {
+    if let Some(result) = worker.read_path.query(ReadQuery::Accounts) {
+        return result;
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    try_send_request(worker, Request::GetAccountsSync { response_tx: tx })?;
+
+    match rx.recv_timeout(SYNC_TIMEOUT) {
+        Ok(result) => result,
+        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => Err(ERR_TIMEOUT),
+        Err(_) => Err(ERR_INVALID_HANDLE),
+    }
+}
+
+/// Query an account balance on the read path or through the worker
+fn get_balance_blocking(
+    worker: &MidenWorkerHandle,
+    account_id_hex: *const c_char,
+) -> GetBalanceResult {
+    let (account_id, account_id_str) = parse_account_id(account_id_hex)?;
+    let query = ReadQuery::Balance(account_id, account_id_str.clone());
+    if let Some(result) = worker.read_path.query(query) {
+        return result;
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    try_send_request(worker, Request::GetBalanceSync { 
+        account_id, 
+        account_id_str, 
+        response_tx: tx 
+    })?;
+
+    match rx.recv_timeout(SYNC_TIMEOUT) {
+        Ok(result) => result,
+        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => Err(ERR_TIMEOUT),
+        Err(_) => Err(ERR_INVALID_HANDLE),
+    }
+}
+
+/// Query consumable input notes on the read path or through the worker
+fn get_input_notes_blocking(
+    worker: &MidenWorkerHandle,
+    account_id_hex: *const c_char,
+) -> GetInputNotesResult {
+    let account_id = parse_optional_account_id(account_id_hex)?;
+    if let Some(result) = worker.read_path.query(ReadQuery::InputNotes(account_id)) {
+        return result;
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    try_send_request(worker, Request::GetInputNotesSync { account_id, response_tx: tx })?;
+
+    match rx.recv_timeout(SYNC_TIMEOUT) {
+        Ok(result) => result,
+        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => Err(ERR_TIMEOUT),
+        Err(_) => Err(ERR_INVALID_HANDLE),
+    }
+}
+
 /// Get all accounts (blocking)
 /// 
 /// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+/// Pass a NULL `accounts_json_out` to get the required length; returns -7 (with the
+/// required length) when the buffer is too small.
 #[unsafe(no_mangle)]
 pub extern "C" fn wc_miden_get_accounts(
     handle: MidenHandle,
@@ -895,211 +2778,433 @@ pub extern "C" fn wc_miden_get_accounts(
         return ERR_INVALID_HANDLE;
     };
     
-    if accounts_json_out.is_null() || accounts_json_out_len.is_null() {
+    if accounts_json_out_len.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    match get_accounts_blocking(&worker) {
+        Ok(json) => write_out_buffer(json.as_bytes(), accounts_json_out, accounts_json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Get account balance (blocking)
+/// 
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+/// Pass a NULL `balance_json_out` to get the required length; returns -7 (with the
+/// required length) when the buffer is too small.
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_balance(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    balance_json_out: *mut u8,
+    balance_json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+    
+    if balance_json_out_len.is_null() {
         return ERR_INVALID_PARAM;
     }
 
+    match get_balance_blocking(&worker, account_id_hex) {
+        Ok(json) => write_out_buffer(json.as_bytes(), balance_json_out, balance_json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Test connection (blocking)
+/// 
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_test_connection(handle: MidenHandle) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+    
     let (tx, rx) = std::sync::mpsc::channel();
     
-    if let Err(code) = try_send_request(&worker.sender, Request::GetAccountsSync { response_tx: tx }) {
+    if let Err(code) = try_send_request(&worker, Request::TestConnectionSync { response_tx: tx }) {
         return code;
     }
     
     match rx.recv_timeout(SYNC_TIMEOUT) {
-        Ok(Ok(json)) => {
-            let out_capacity = unsafe { *accounts_json_out_len };
-            if json.len() > out_capacity {
-                return ERR_INVALID_PARAM;
-            }
-            let out = unsafe { std::slice::from_raw_parts_mut(accounts_json_out, json.len()) };
-            out.copy_from_slice(json.as_bytes());
-            unsafe { *accounts_json_out_len = json.len() };
-            0
-        }
+        Ok(Ok(())) => 0,
         Ok(Err(code)) => code,
         Err(std::sync::mpsc::RecvTimeoutError::Timeout) => ERR_TIMEOUT,
         Err(_) => ERR_INVALID_HANDLE,
     }
 }
 
-/// Get account balance (blocking)
+/// Get consumable input notes (blocking)
 /// 
 /// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+/// Pass a NULL `notes_json_out` to get the required length; returns -7 (with the
+/// required length) when the buffer is too small.
 #[unsafe(no_mangle)]
-pub extern "C" fn wc_miden_get_balance(
+pub extern "C" fn wc_miden_get_input_notes(
     handle: MidenHandle,
     account_id_hex: *const c_char,
-    balance_json_out: *mut u8,
-    balance_json_out_len: *mut usize,
+    notes_json_out: *mut u8,
+    notes_json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+    
+    if notes_json_out_len.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    match get_input_notes_blocking(&worker, account_id_hex) {
+        Ok(json) => write_out_buffer(json.as_bytes(), notes_json_out, notes_json_out_len),
+        Err(code) => code,
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Rust-allocated output variants
+// ------------------------------------------------------------------------------------------------
+//
+// Same results as the buffer variants, returned in a buffer allocated by Rust: no size to
+// guess and nothing to retry. On success `*out_ptr` / `*out_len` receive the JSON (UTF-8,
+// not NUL-terminated), which MUST be released with `wc_bytes_free(ptr, len)`. On error
+// `*out_ptr` is set to NULL and `*out_len` to 0.
+
+/// Get all accounts into a Rust-allocated buffer (blocking)
+/// 
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_accounts_alloc(
+    handle: MidenHandle,
+    out_ptr: *mut *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+    if out_ptr.is_null() || out_len.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    write_out_alloc(get_accounts_blocking(&worker), out_ptr, out_len)
+}
+
+/// Get an account balance into a Rust-allocated buffer (blocking)
+/// 
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_balance_alloc(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    out_ptr: *mut *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+    if out_ptr.is_null() || out_len.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    write_out_alloc(get_balance_blocking(&worker, account_id_hex), out_ptr, out_len)
+}
+
+/// Get consumable input notes into a Rust-allocated buffer (blocking)
+/// 
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_input_notes_alloc(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    out_ptr: *mut *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+    if out_ptr.is_null() || out_len.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    write_out_alloc(get_input_notes_blocking(&worker, account_id_hex), out_ptr, out_len)
+}
+
+/// Consume notes (blocking)
+/// 
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+/// NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+/// Fails with -102 if the screening callback denies a note's sender. The output buffer
+/// must hold 66 bytes; a smaller one fails with -7 before anything is submitted.
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_consume_notes(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    note_ids_json: *const c_char,
+    tx_id_out: *mut u8,
+    tx_id_out_len: *mut usize,
 ) -> i32 {
     let Some(worker) = get_handle(handle) else {
         return ERR_INVALID_HANDLE;
     };
     
-    if balance_json_out.is_null() || balance_json_out_len.is_null() {
+    if note_ids_json.is_null() {
         return ERR_INVALID_PARAM;
     }
+    if let Err(code) = check_out_capacity(tx_id_out, tx_id_out_len, ID_HEX_LEN) {
+        return code;
+    }
 
-    let (account_id, account_id_str) = match parse_account_id(account_id_hex) {
+    let (account_id, _) = match parse_account_id(account_id_hex) {
         Ok(v) => v,
         Err(code) => return code,
     };
 
+    let note_ids_str = match unsafe { CStr::from_ptr(note_ids_json) }.to_str() {
+        Ok(s) => s,
+        Err(_) => return ERR_INVALID_PARAM,
+    };
+
+    let note_ids = match parse_note_ids_json(note_ids_str) {
+        Ok(ids) if !ids.is_empty() => ids,
+        _ => return ERR_NOTE_OP,
+    };
+
     let (tx, rx) = std::sync::mpsc::channel();
     
-    if let Err(code) = try_send_request(&worker.sender, Request::GetBalanceSync { 
+    if let Err(code) = try_send_request(&worker, Request::ConsumeNotesSync { 
         account_id, 
-        account_id_str, 
+        note_ids, 
         response_tx: tx 
     }) {
         return code;
     }
     
     match rx.recv_timeout(SYNC_TIMEOUT) {
-        Ok(Ok(json)) => {
-            let out_capacity = unsafe { *balance_json_out_len };
-            if json.len() > out_capacity {
-                return ERR_INVALID_PARAM;
-            }
-            let out = unsafe { std::slice::from_raw_parts_mut(balance_json_out, json.len()) };
-            out.copy_from_slice(json.as_bytes());
-            unsafe { *balance_json_out_len = json.len() };
-            0
-        }
+        Ok(Ok(tx_id_hex)) => write_out_buffer(tx_id_hex.as_bytes(), tx_id_out, tx_id_out_len),
         Ok(Err(code)) => code,
         Err(std::sync::mpsc::RecvTimeoutError::Timeout) => ERR_TIMEOUT,
         Err(_) => ERR_INVALID_HANDLE,
     }
 }
 
-/// Test connection (blocking)
-/// 
+/// Parse the sender, target and faucet IDs of a send
+fn parse_send_ids(
+    account_id_hex: *const c_char,
+    target_account_id_hex: *const c_char,
+    faucet_id_hex: *const c_char,
+) -> Result<(AccountId, AccountId, AccountId), i32> {
+    let (account_id, _) = parse_account_id(account_id_hex)?;
+    let (target, _) = parse_account_id(target_account_id_hex)?;
+    let (faucet_id, _) = parse_account_id(faucet_id_hex)?;
+    Ok((account_id, target, faucet_id))
+}
+
+/// Send fungible assets to another account in a public P2ID note (blocking)
+///
 /// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+/// NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+///
+/// # Parameters
+/// - `account_id_hex`: Sending account ID (C string)
+/// - `target_account_id_hex`: Receiving account ID (C string)
+/// - `faucet_id_hex`: Faucet of the sent asset (C string)
+/// - `amount`: Amount in base units (must be > 0)
+/// - `json_out` / `json_out_len`: Output buffer for `{"tx_id":"0x..","note_id":"0x.."}`
+///   (157 bytes)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account or faucet ID
+/// - -4: Note construction failed
+/// - -6: Transaction submission failed (e.g. insufficient balance)
+/// - -7: Output buffer too small, checked before anything is submitted
+/// - -8: Queue full
+/// - -99: Operation timed out
+/// - -101: Recipient is not on the account's withdrawal whitelist, or the whitelist
+///   could not be read
+/// - -102: Blocked by the screening callback
 #[unsafe(no_mangle)]
-pub extern "C" fn wc_miden_test_connection(handle: MidenHandle) -> i32 {
+pub extern "C" fn wc_miden_send(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    target_account_id_hex: *const c_char,
+    faucet_id_hex: *const c_char,
+    amount: u64,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
     let Some(worker) = get_handle(handle) else {
         return ERR_INVALID_HANDLE;
     };
-    
-    let (tx, rx) = std::sync::mpsc::channel();
-    
-    if let Err(code) = try_send_request(&worker.sender, Request::TestConnectionSync { response_tx: tx }) {
+
+    if amount == 0 {
+        return ERR_INVALID_PARAM;
+    }
+    if let Err(code) = check_out_capacity(json_out, json_out_len, SEND_RESULT_LEN) {
         return code;
     }
-    
-    match rx.recv_timeout(SYNC_TIMEOUT) {
-        Ok(Ok(())) => 0,
-        Ok(Err(code)) => code,
-        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => ERR_TIMEOUT,
-        Err(_) => ERR_INVALID_HANDLE,
+
+    let (account_id, target, faucet_id) =
+        match parse_send_ids(account_id_hex, target_account_id_hex, faucet_id_hex) {
+            Ok(ids) => ids,
+            Err(code) => return code,
+        };
+
+    let note_type = NoteType::Public;
+    let request = |reply| Request::Send {
+        account_id, target, faucet_id, amount, note_type, reclaim_block: None, reply,
+    };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
     }
 }
 
-/// Get consumable input notes (blocking)
-/// 
+/// Send fungible assets to another account in a private P2ID note (blocking)
+///
 /// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+/// NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+///
+/// Only the note's commitment goes on chain, so the recipient cannot discover the note:
+/// hand them `note_file` (out of band: AirDrop, QR code, a messaging channel) to import
+/// with `wc_miden_import_note`. The note can also be exported again later with
+/// `wc_miden_export_note`.
+///
+/// # Parameters
+/// - `account_id_hex`: Sending account ID (C string)
+/// - `target_account_id_hex`: Receiving account ID (C string)
+/// - `faucet_id_hex`: Faucet of the sent asset (C string)
+/// - `amount`: Amount in base units (must be > 0)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"tx_id":"0x..","note_id":"0x..","note_file":"<hex NoteFile>"}`
+///
+/// # Returns
+/// Same codes as `wc_miden_send`. The length of `note_file` is only known once the note
+/// exists, so only the rest of the result is checked up front: a -7 with `*json_out_len`
+/// above 157 means the transaction was submitted. Do not retry it; find the note in the
+/// sender's output notes and export it with `wc_miden_export_note`. Size the buffer
+/// generously (the Swift wrapper passes 64 KiB).
 #[unsafe(no_mangle)]
-pub extern "C" fn wc_miden_get_input_notes(
+pub extern "C" fn wc_miden_send_private(
     handle: MidenHandle,
     account_id_hex: *const c_char,
-    notes_json_out: *mut u8,
-    notes_json_out_len: *mut usize,
+    target_account_id_hex: *const c_char,
+    faucet_id_hex: *const c_char,
+    amount: u64,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
 ) -> i32 {
     let Some(worker) = get_handle(handle) else {
         return ERR_INVALID_HANDLE;
     };
-    
-    if notes_json_out.is_null() || notes_json_out_len.is_null() {
+
+    if amount == 0 {
         return ERR_INVALID_PARAM;
     }
-
-    let account_id = match parse_optional_account_id(account_id_hex) {
-        Ok(v) => v,
-        Err(code) => return code,
-    };
-
-    let (tx, rx) = std::sync::mpsc::channel();
-    
-    if let Err(code) = try_send_request(&worker.sender, Request::GetInputNotesSync { account_id, response_tx: tx }) {
+    if let Err(code) = check_out_capacity(json_out, json_out_len, SEND_RESULT_LEN) {
         return code;
     }
-    
-    match rx.recv_timeout(SYNC_TIMEOUT) {
-        Ok(Ok(json)) => {
-            let out_capacity = unsafe { *notes_json_out_len };
-            if json.len() > out_capacity {
-                return ERR_INVALID_PARAM;
-            }
-            let out = unsafe { std::slice::from_raw_parts_mut(notes_json_out, json.len()) };
-            out.copy_from_slice(json.as_bytes());
-            unsafe { *notes_json_out_len = json.len() };
-            0
-        }
-        Ok(Err(code)) => code,
-        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => ERR_TIMEOUT,
-        Err(_) => ERR_INVALID_HANDLE,
+
+    let (account_id, target, faucet_id) =
+        match parse_send_ids(account_id_hex, target_account_id_hex, faucet_id_hex) {
+            Ok(ids) => ids,
+            Err(code) => return code,
+        };
+
+    let note_type = NoteType::Private;
+    let request = |reply| Request::Send {
+        account_id, target, faucet_id, amount, note_type, reclaim_block: None, reply,
+    };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
     }
 }
 
-/// Consume notes (blocking)
-/// 
+/// Get the tracking status of an account in the local store
+///
 /// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
-/// NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID or account lookup failed
+/// - -5: Account not found
 #[unsafe(no_mangle)]
-pub extern "C" fn wc_miden_consume_notes(
+pub extern "C" fn wc_miden_get_account_status(
     handle: MidenHandle,
     account_id_hex: *const c_char,
-    note_ids_json: *const c_char,
-    tx_id_out: *mut u8,
-    tx_id_out_len: *mut usize,
+    status_out: *mut WcAccountStatus,
 ) -> i32 {
     let Some(worker) = get_handle(handle) else {
         return ERR_INVALID_HANDLE;
     };
-    
-    if note_ids_json.is_null() || tx_id_out.is_null() || tx_id_out_len.is_null() {
+
+    if status_out.is_null() {
         return ERR_INVALID_PARAM;
     }
 
-    let (account_id, _) = match parse_account_id(account_id_hex) {
-        Ok(v) => v,
+    let account_id = match parse_account_id(account_id_hex) {
+        Ok((id, _)) => id,
         Err(code) => return code,
     };
 
-    let note_ids_str = match unsafe { CStr::from_ptr(note_ids_json) }.to_str() {
-        Ok(s) => s,
-        Err(_) => return ERR_INVALID_PARAM,
+    let status = match request_blocking(&worker, |reply| Request::GetAccountStatus { account_id, reply }) {
+        Ok(bytes) => match bytes.first() {
+            Some(0) => WcAccountStatus::New,
+            Some(1) => WcAccountStatus::Tracked,
+            Some(2) => WcAccountStatus::Locked,
+            _ => return ERR_ACCOUNT_OP,
+        },
+        Err(code) => return code,
     };
 
-    let note_ids = match parse_note_ids_json(note_ids_str) {
-        Ok(ids) if !ids.is_empty() => ids,
-        _ => return ERR_NOTE_OP,
+    unsafe { *status_out = status };
+    0
+}
+
+/// Get the type, storage mode, status and key backend of an account (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_id_hex`: Account ID (C string, hex)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"account_id","account_type","storage_mode","status","key_backend","signer_scheme_id"}`
+///   (`key_backend` is `filesystem`, `callback` or `memory`; `signer_scheme_id` is set for
+///   `callback` only)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID or account lookup failed
+/// - -5: Account not found
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_account_details(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
     };
 
-    let (tx, rx) = std::sync::mpsc::channel();
-    
-    if let Err(code) = try_send_request(&worker.sender, Request::ConsumeNotesSync { 
-        account_id, 
-        note_ids, 
-        response_tx: tx 
-    }) {
-        return code;
-    }
-    
-    match rx.recv_timeout(SYNC_TIMEOUT) {
-        Ok(Ok(tx_id_hex)) => {
-            let out_capacity = unsafe { *tx_id_out_len };
-            if tx_id_hex.len() > out_capacity {
-                return ERR_INVALID_PARAM;
-            }
-            let out = unsafe { std::slice::from_raw_parts_mut(tx_id_out, tx_id_hex.len()) };
-            out.copy_from_slice(tx_id_hex.as_bytes());
-            unsafe { *tx_id_out_len = tx_id_hex.len() };
-            0
-        }
-        Ok(Err(code)) => code,
-        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => ERR_TIMEOUT,
-        Err(_) => ERR_INVALID_HANDLE,
+    let account_id = match parse_account_id(account_id_hex) {
+        Ok((id, _)) => id,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::GetAccountDetails { account_id, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
     }
 }
 
@@ -1111,177 +3216,166 @@ pub extern "C" fn wc_miden_consume_notes(
 /// 
 /// NOTE: Callback is invoked on worker thread, NOT main thread.
 /// Swift callers should dispatch to main queue if updating UI.
+///
+/// Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
 #[unsafe(no_mangle)]
 pub extern "C" fn wc_miden_sync_async(
     handle: MidenHandle,
     callback: SyncCallback,
     user_data: *mut std::ffi::c_void,
-) -> i32 {
+) -> i64 {
     let Some(worker) = get_handle(handle) else {
-        return ERR_INVALID_HANDLE;
+        return i64::from(ERR_INVALID_HANDLE);
     };
     
-    if let Err(code) = try_send_request(&worker.sender, Request::SyncAsync { 
+    cancel::submit(&worker, Request::SyncAsync { 
         callback, 
         user_data: user_data as usize 
-    }) {
-        return code;
-    }
-    
-    0
+    })
 }
 
 /// Create wallet (async)
 /// 
 /// NOTE: Callback is invoked on worker thread, NOT main thread.
+///
+/// `storage_mode` as in `wc_miden_create_wallet` (0 = public, 1 = private).
+///
+/// Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
 #[unsafe(no_mangle)]
 pub extern "C" fn wc_miden_create_wallet_async(
     handle: MidenHandle,
     seed_ptr: *const u8,
     seed_len: usize,
+    storage_mode: u32,
     callback: CreateWalletCallback,
     user_data: *mut std::ffi::c_void,
-) -> i32 {
+) -> i64 {
     let Some(worker) = get_handle(handle) else {
-        return ERR_INVALID_HANDLE;
+        return i64::from(ERR_INVALID_HANDLE);
     };
 
-    let seed: [u8; 32] = if seed_ptr.is_null() {
-        let mut s = [0u8; 32];
-        let mut rng = StdRng::from_os_rng();
-        rng.fill_bytes(&mut s);
-        s
-    } else {
-        if seed_len != 32 {
-            return ERR_INVALID_PARAM;
-        }
-        let slice = unsafe { std::slice::from_raw_parts(seed_ptr, 32) };
-        let mut arr = [0u8; 32];
-        arr.copy_from_slice(slice);
-        arr
+    let seed = match parse_seed(seed_ptr, seed_len) {
+        Ok(seed) => seed,
+        Err(code) => return i64::from(code),
+    };
+    let storage_mode = match parse_wallet_storage_mode(storage_mode) {
+        Ok(storage_mode) => storage_mode,
+        Err(code) => return i64::from(code),
     };
 
-    if let Err(code) = try_send_request(&worker.sender, Request::CreateWalletAsync { 
+    cancel::submit(&worker, Request::CreateWalletAsync { 
         seed, 
+        storage_mode,
         callback, 
         user_data: user_data as usize 
-    }) {
-        return code;
-    }
-    
-    0
+    })
 }
 
 /// Get accounts (async)
 /// 
 /// NOTE: Callback is invoked on worker thread, NOT main thread.
+///
+/// Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
 #[unsafe(no_mangle)]
 pub extern "C" fn wc_miden_get_accounts_async(
     handle: MidenHandle,
     callback: GetAccountsCallback,
     user_data: *mut std::ffi::c_void,
-) -> i32 {
+) -> i64 {
     let Some(worker) = get_handle(handle) else {
-        return ERR_INVALID_HANDLE;
+        return i64::from(ERR_INVALID_HANDLE);
     };
     
-    if let Err(code) = try_send_request(&worker.sender, Request::GetAccountsAsync { 
+    cancel::submit(&worker, Request::GetAccountsAsync { 
         callback, 
         user_data: user_data as usize 
-    }) {
-        return code;
-    }
-    
-    0
+    })
 }
 
 /// Get balance (async)
 /// 
 /// NOTE: Callback is invoked on worker thread, NOT main thread.
+///
+/// Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
 #[unsafe(no_mangle)]
 pub extern "C" fn wc_miden_get_balance_async(
     handle: MidenHandle,
     account_id_hex: *const c_char,
     callback: GetBalanceCallback,
     user_data: *mut std::ffi::c_void,
-) -> i32 {
+) -> i64 {
     let Some(worker) = get_handle(handle) else {
-        return ERR_INVALID_HANDLE;
+        return i64::from(ERR_INVALID_HANDLE);
     };
 
     let (account_id, account_id_str) = match parse_account_id(account_id_hex) {
         Ok(v) => v,
-        Err(code) => return code,
+        Err(code) => return i64::from(code),
     };
 
-    if let Err(code) = try_send_request(&worker.sender, Request::GetBalanceAsync { 
+    cancel::submit(&worker, Request::GetBalanceAsync { 
         account_id, 
         account_id_str, 
         callback, 
         user_data: user_data as usize 
-    }) {
-        return code;
-    }
-    
-    0
+    })
 }
 
 /// Test connection (async)
 /// 
 /// NOTE: Callback is invoked on worker thread, NOT main thread.
+///
+/// Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
 #[unsafe(no_mangle)]
 pub extern "C" fn wc_miden_test_connection_async(
     handle: MidenHandle,
     callback: TestConnectionCallback,
     user_data: *mut std::ffi::c_void,
-) -> i32 {
+) -> i64 {
     let Some(worker) = get_handle(handle) else {
-        return ERR_INVALID_HANDLE;
+        return i64::from(ERR_INVALID_HANDLE);
     };
     
-    if let Err(code) = try_send_request(&worker.sender, Request::TestConnectionAsync { 
+    cancel::submit(&worker, Request::TestConnectionAsync { 
         callback, 
         user_data: user_data as usize 
-    }) {
-        return code;
-    }
-    
-    0
+    })
 }
 
 /// Get input notes (async)
 /// 
 /// NOTE: Callback is invoked on worker thread, NOT main thread.
+///
+/// Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
 #[unsafe(no_mangle)]
 pub extern "C" fn wc_miden_get_input_notes_async(
     handle: MidenHandle,
     account_id_hex: *const c_char,
     callback: GetInputNotesCallback,
     user_data: *mut std::ffi::c_void,
-) -> i32 {
+) -> i64 {
     let Some(worker) = get_handle(handle) else {
-        return ERR_INVALID_HANDLE;
+        return i64::from(ERR_INVALID_HANDLE);
     };
 
     let account_id = match parse_optional_account_id(account_id_hex) {
         Ok(v) => v,
-        Err(code) => return code,
+        Err(code) => return i64::from(code),
     };
 
-    if let Err(code) = try_send_request(&worker.sender, Request::GetInputNotesAsync { 
+    cancel::submit(&worker, Request::GetInputNotesAsync { 
         account_id, 
         callback, 
         user_data: user_data as usize 
-    }) {
-        return code;
-    }
-    
-    0
+    })
 }
 
 /// Consume notes (async)
 /// 
 /// NOTE: Callback is invoked on worker thread, NOT main thread.
+/// Fails with -102 if the screening callback denies a note's sender.
+///
+/// Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
 #[unsafe(no_mangle)]
 pub extern "C" fn wc_miden_consume_notes_async(
     handle: MidenHandle,
@@ -1289,40 +3383,119 @@ pub extern "C" fn wc_miden_consume_notes_async(
     note_ids_json: *const c_char,
     callback: ConsumeNotesCallback,
     user_data: *mut std::ffi::c_void,
-) -> i32 {
+) -> i64 {
     let Some(worker) = get_handle(handle) else {
-        return ERR_INVALID_HANDLE;
+        return i64::from(ERR_INVALID_HANDLE);
     };
 
     if note_ids_json.is_null() {
-        return ERR_INVALID_PARAM;
+        return i64::from(ERR_INVALID_PARAM);
     }
 
     let (account_id, _) = match parse_account_id(account_id_hex) {
         Ok(v) => v,
-        Err(code) => return code,
+        Err(code) => return i64::from(code),
     };
 
     let note_ids_str = match unsafe { CStr::from_ptr(note_ids_json) }.to_str() {
         Ok(s) => s,
-        Err(_) => return ERR_INVALID_PARAM,
+        Err(_) => return i64::from(ERR_INVALID_PARAM),
     };
 
     let note_ids = match parse_note_ids_json(note_ids_str) {
         Ok(ids) if !ids.is_empty() => ids,
-        _ => return ERR_NOTE_OP,
+        _ => return i64::from(ERR_NOTE_OP),
     };
 
-    if let Err(code) = try_send_request(&worker.sender, Request::ConsumeNotesAsync { 
+    cancel::submit(&worker, Request::ConsumeNotesAsync { 
         account_id, 
         note_ids, 
         callback, 
         user_data: user_data as usize 
-    }) {
-        return code;
+    })
+}
+
+/// Send fungible assets to another account in a public P2ID note (async)
+///
+/// NOTE: Callback is invoked on worker thread, NOT main thread.
+/// The callback receives `{"tx_id":"0x..","note_id":"0x.."}`; error codes match `wc_miden_send`.
+///
+/// Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_send_async(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    target_account_id_hex: *const c_char,
+    faucet_id_hex: *const c_char,
+    amount: u64,
+    callback: BytesCallback,
+    user_data: *mut std::ffi::c_void,
+) -> i64 {
+    let Some(worker) = get_handle(handle) else {
+        return i64::from(ERR_INVALID_HANDLE);
+    };
+
+    if amount == 0 {
+        return i64::from(ERR_INVALID_PARAM);
     }
-    
-    0
+
+    let (account_id, target, faucet_id) =
+        match parse_send_ids(account_id_hex, target_account_id_hex, faucet_id_hex) {
+            Ok(ids) => ids,
+            Err(code) => return i64::from(code),
+        };
+
+    cancel::submit(&worker, Request::Send {
+        account_id,
+        target,
+        faucet_id,
+        amount,
+        note_type: NoteType::Public,
+        reclaim_block: None,
+        reply: Reply::Callback { callback, user_data: user_data as usize },
+    })
+}
+
+/// Send fungible assets to another account in a private P2ID note (async)
+///
+/// NOTE: Callback is invoked on worker thread, NOT main thread.
+/// The callback receives `{"tx_id":"0x..","note_id":"0x..","note_file":"<hex NoteFile>"}`;
+/// error codes match `wc_miden_send`.
+///
+/// Returns an operation handle (> 0) for `wc_miden_cancel`, or a negative error code.
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_send_private_async(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    target_account_id_hex: *const c_char,
+    faucet_id_hex: *const c_char,
+    amount: u64,
+    callback: BytesCallback,
+    user_data: *mut std::ffi::c_void,
+) -> i64 {
+    let Some(worker) = get_handle(handle) else {
+        return i64::from(ERR_INVALID_HANDLE);
+    };
+
+    if amount == 0 {
+        return i64::from(ERR_INVALID_PARAM);
+    }
+
+    let (account_id, target, faucet_id) =
+        match parse_send_ids(account_id_hex, target_account_id_hex, faucet_id_hex) {
+            Ok(ids) => ids,
+            Err(code) => return i64::from(code),
+        };
+
+    cancel::submit(&worker, Request::Send {
+        account_id,
+        target,
+        faucet_id,
+        amount,
+        note_type: NoteType::Private,
+        reclaim_block: None,
+        reply: Reply::Callback { callback, user_data: user_data as usize },
+    })
 }
 
 // ================================================================================================
@@ -1339,7 +3512,8 @@ pub extern "C" fn wc_miden_consume_notes_async(
 /// 
 /// # Returns
 /// - 0: Success
-/// - -1: Invalid parameters or buffer too small
+/// - -1: Invalid parameters
+/// - -7: Output buffer too small
 #[unsafe(no_mangle)]
 pub extern "C" fn wc_keccak256(
     data_ptr: *const u8,
@@ -1351,21 +3525,93 @@ pub extern "C" fn wc_keccak256(
         return ERR_INVALID_PARAM;
     }
     
-    // Check buffer capacity
-    let capacity = unsafe { *out_len };
-    if capacity < 32 {
-        return ERR_INVALID_PARAM;
-    }
-    
     let data = unsafe { std::slice::from_raw_parts(data_ptr, data_len) };
 
     let mut hasher = Keccak256::new();
     hasher.update(data);
     let result = hasher.finalize();
 
-    let out = unsafe { std::slice::from_raw_parts_mut(out_ptr, 32) };
-    out.copy_from_slice(&result[..]);
-    unsafe { *out_len = 32 };
+    write_out_buffer(&result[..], out_ptr, out_len)
+}
+
+/// Query which optional features this build supports
+///
+/// Lets a single Swift SDK adapt to differently configured framework builds at runtime.
+///
+/// # Output JSON
+/// `{"version":"0.1.0","capabilities":{"sqlcipher":false,"uniffi":false,"mock_node":false,
+///   "remote_prover":false,"mnemonic":true,"external_signers":true,...}}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_capabilities(json_out: *mut u8, json_out_len: *mut usize) -> i32 {
+    let capabilities = [
+        // Optional subsystems not compiled into this build
+        ("uniffi", false),
+        ("mock_node", cfg!(feature = "mock-node")),
+        ("remote_prover", false),
+        // Always available
+        ("external_signers", true),
+        ("cold_wallet", true),
+        ("mpc_sessions", true),
+        ("dapp_sessions", true),
+        ("deposit_scanning", true),
+        ("evm_interop", true),
+        ("push_relay", true),
+        // Cargo features (see Cargo.toml)
+        ("sqlcipher", cfg!(feature = "sqlcipher")),
+        ("faucets", cfg!(feature = "faucets")),
+        ("swaps", cfg!(feature = "swaps")),
+        ("mnemonic", cfg!(feature = "mnemonic")),
+        ("evm_bridge", cfg!(feature = "scripts")),
+        ("name_registry", cfg!(feature = "scripts")),
+        ("testing_hooks", cfg!(feature = "testing")),
+        ("memory_debug", cfg!(feature = "memory-debug")),
+        ("jemalloc", cfg!(feature = "jemalloc")),
+        ("mimalloc", cfg!(feature = "mimalloc")),
+    ];
+
+    let json = format!(
+        r#"{{"version":"{}","capabilities":{{{}}}}}"#,
+        env!("CARGO_PKG_VERSION"),
+        capabilities
+            .iter()
+            .map(|(name, enabled)| format!(r#""{}":{}"#, name, enabled))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+
+    write_out_buffer(json.as_bytes(), json_out, json_out_len)
+}
+
+/// Decode the account type and storage mode encoded in an account ID
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -3: Invalid account ID
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_account_id_info(
+    account_id_hex: *const c_char,
+    account_type_out: *mut WcAccountType,
+    storage_mode_out: *mut WcStorageMode,
+) -> i32 {
+    if account_type_out.is_null() || storage_mode_out.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    let account_id = match parse_account_id(account_id_hex) {
+        Ok((id, _)) => id,
+        Err(code) => return code,
+    };
+
+    unsafe {
+        *account_type_out = account_id.account_type().into();
+        *storage_mode_out = account_id.storage_mode().into();
+    }
     0
 }
 
@@ -1383,15 +3629,6 @@ pub extern "C" fn wc_miden_account_id_to_hex(
 
     let account_id_bytes = unsafe { std::slice::from_raw_parts(account_id_ptr, account_id_len) };
     let hex_string = hex::encode(account_id_bytes);
-    
-    let out_capacity = unsafe { *hex_out_len };
-    if hex_string.len() > out_capacity {
-        return ERR_INVALID_PARAM;
-    }
 
-    let out = unsafe { std::slice::from_raw_parts_mut(hex_out, hex_string.len()) };
-    out.copy_from_slice(hex_string.as_bytes());
-    unsafe { *hex_out_len = hex_string.len() };
-
-    0
+    write_out_buffer(hex_string.as_bytes(), hex_out, hex_out_len)
 }
@@ -0,0 +1,263 @@
+//! Live transaction tracking (iOS Live Activities)
+//!
+//! A tracked transaction reports each state transition as JSON
+//! `{"type":"tx_live_update","tx_id","state","block_num","timestamp"}`:
+//!
+//! `submitted` → `in_block` (committed in block `block_num`) → `finalized` (the sync height
+//! is [`LIVE_TX_FINALITY_BLOCKS`] past the commit block), or `discarded` at any point.
+//!
+//! Proving happens inside the submit calls, before a transaction ID exists, so apps show
+//! "proving" while the submit call is in flight and start tracking with the returned ID.
+//! Transitions are detected after each successful sync.
+//!
+//! Tracked IDs and their last reported state are kept in a JSON file next to the SQLite
+//! store, so tracking continues after a relaunch. Callbacks cannot outlive the process:
+//! every transition is also emitted as a `tx_live_update` event, and tracking the same
+//! ID again attaches a new callback (which immediately receives the current state).
+//! Finalized and discarded transactions are no longer tracked.
+
+use std::{
+    collections::HashMap,
+    ffi::c_void,
+    os::raw::c_char,
+    path::{Path, PathBuf},
+};
+
+use miden_client::{store::TransactionFilter, transaction::TransactionStatus};
+use miden_objects::{transaction::TransactionId, Word};
+
+use crate::{
+    clock, diagnostics, events::WcEventCallback, get_handle, json, memory_store,
+    parse_required_str, request_blocking, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM, ERR_LOOKUP,
+};
+
+/// Blocks after the commit block before a transaction is reported as finalized
+pub const LIVE_TX_FINALITY_BLOCKS: u32 = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LiveState {
+    Submitted,
+    InBlock(u32),
+    Finalized(u32),
+    Discarded,
+}
+
+impl LiveState {
+    fn as_str(self) -> &'static str {
+        match self {
+            LiveState::Submitted => "submitted",
+            LiveState::InBlock(_) => "in_block",
+            LiveState::Finalized(_) => "finalized",
+            LiveState::Discarded => "discarded",
+        }
+    }
+
+    fn block_num(self) -> Option<u32> {
+        match self {
+            LiveState::InBlock(block) | LiveState::Finalized(block) => Some(block),
+            LiveState::Submitted | LiveState::Discarded => None,
+        }
+    }
+
+    fn is_terminal(self) -> bool {
+        matches!(self, LiveState::Finalized(_) | LiveState::Discarded)
+    }
+}
+
+/// Callback registered by `wc_miden_track_tx_live` (current process only)
+#[derive(Clone, Copy)]
+pub(crate) struct LiveSubscriber {
+    callback: extern "C" fn(*mut c_void, *const u8, usize),
+    user_data: usize,
+}
+
+struct Tracked {
+    state: LiveState,
+    subscriber: Option<LiveSubscriber>,
+}
+
+/// Transactions tracked for Live Activities
+pub(crate) struct LiveTransactions {
+    path: PathBuf,
+    tracked: HashMap<TransactionId, Tracked>,
+}
+
+pub(crate) fn parse_tx_id(hex: &str) -> Option<TransactionId> {
+    Word::try_from(hex).ok().map(TransactionId::from)
+}
+
+impl LiveTransactions {
+    /// Load the sidecar file for the store at `store_path` (missing or corrupt → none)
+    pub fn load(store_path: &Path) -> Self {
+        let path = store_path.with_extension("live.json");
+        let entries: HashMap<String, (String, Option<u32>)> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        let tracked = entries
+            .into_iter()
+            .filter_map(|(tx_id, (state, block_num))| {
+                let state = match (state.as_str(), block_num) {
+                    ("submitted", _) => LiveState::Submitted,
+                    ("in_block", Some(block)) => LiveState::InBlock(block),
+                    _ => return None,
+                };
+                Some((parse_tx_id(&tx_id)?, Tracked { state, subscriber: None }))
+            })
+            .collect();
+
+        Self { path, tracked }
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let entries: HashMap<String, (&str, Option<u32>)> = self.tracked
+            .iter()
+            .map(|(tx_id, tracked)| {
+                (tx_id.to_hex(), (tracked.state.as_str(), tracked.state.block_num()))
+            })
+            .collect();
+        let json = serde_json::to_string(&entries).map_err(std::io::Error::other)?;
+        memory_store::write(&self.path, json)
+    }
+}
+
+/// Current state of a transaction in the store (None when unknown)
+async fn observe(context: &MidenContext, tx_id: TransactionId) -> Result<Option<LiveState>, i32> {
+    let record = context.client.get_transactions(TransactionFilter::Ids(vec![tx_id])).await
+        .map_err(|_| ERR_LOOKUP)?
+        .into_iter()
+        .next();
+    let Some(record) = record else {
+        return Ok(None);
+    };
+
+    let state = match record.status {
+        TransactionStatus::Pending => LiveState::Submitted,
+        TransactionStatus::Committed { block_number, .. } => {
+            let block = block_number.as_u32();
+            let height = clock::current_block(context).await?;
+            if height >= block.saturating_add(LIVE_TX_FINALITY_BLOCKS) {
+                LiveState::Finalized(block)
+            } else {
+                LiveState::InBlock(block)
+            }
+        }
+        TransactionStatus::Discarded(_) => LiveState::Discarded,
+    };
+    Ok(Some(state))
+}
+
+/// Report a state to the transaction's callback (if any) and as an event
+fn report(context: &MidenContext, tx_id: TransactionId, state: LiveState) {
+    let update = serde_json::json!({
+        "type": "tx_live_update",
+        "tx_id": tx_id.to_hex(),
+        "state": state.as_str(),
+        "block_num": state.block_num(),
+        "timestamp": state.block_num().and_then(|block| context.block_times.timestamp(block)),
+    });
+
+    if let Some(subscriber) = context.live_txs.tracked.get(&tx_id).and_then(|t| t.subscriber) {
+        let bytes = json::prepare_output(update.to_string().as_bytes()).into_owned();
+        (subscriber.callback)(subscriber.user_data as *mut c_void, bytes.as_ptr(), bytes.len());
+    }
+    context.events.emit(update);
+}
+
+/// Record a state, report it and drop the transaction once the state is terminal
+fn transition(context: &mut MidenContext, tx_id: TransactionId, state: LiveState) {
+    if let Some(tracked) = context.live_txs.tracked.get_mut(&tx_id) {
+        tracked.state = state;
+    }
+    report(context, tx_id, state);
+    if state.is_terminal() {
+        context.live_txs.tracked.remove(&tx_id);
+    }
+    if let Err(e) = context.live_txs.save() {
+        diagnostics::log("live_tx", format!("failed to save state: {:?}", e));
+    }
+}
+
+pub(crate) async fn track_tx_live_impl(
+    context: &mut MidenContext,
+    tx_id: TransactionId,
+    subscriber: Option<LiveSubscriber>,
+) -> Result<String, i32> {
+    let state = observe(context, tx_id).await?.ok_or(ERR_LOOKUP)?;
+
+    context.live_txs.tracked.insert(tx_id, Tracked { state, subscriber });
+    transition(context, tx_id, state);
+    Ok(String::new())
+}
+
+/// Report the transitions of tracked transactions (run after each successful sync)
+pub(crate) async fn update_after_sync(context: &mut MidenContext) {
+    let tracked: Vec<(TransactionId, LiveState)> = context.live_txs.tracked
+        .iter()
+        .map(|(tx_id, tracked)| (*tx_id, tracked.state))
+        .collect();
+
+    for (tx_id, previous) in tracked {
+        match observe(context, tx_id).await {
+            Ok(Some(state)) if state != previous => transition(context, tx_id, state),
+            Ok(Some(_)) => {}
+            // Gone from the store (e.g. after a restore): nothing left to track
+            Ok(None) => transition(context, tx_id, LiveState::Discarded),
+            Err(code) => diagnostics::log("live_tx", format!("status lookup failed: {}", code)),
+        }
+    }
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Track a submitted transaction until it is finalized or discarded (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// The callback receives
+/// `{"type":"tx_live_update","tx_id","state","block_num","timestamp"}` right away with the
+/// current state, then on every transition detected after a sync (see the module docs for
+/// the states; `timestamp` is the commit block's ISO-8601 time). It runs on the worker
+/// thread; the JSON buffer is only valid during the call. Tracking persists across
+/// relaunches, reporting through the event callback until the ID is tracked again.
+///
+/// # Parameters
+/// - `tx_id_hex`: Transaction ID returned by a submit call (C string)
+/// - `callback`: Update callback, or NULL to rely on the event callback only
+/// - `user_data`: Passed back to the callback
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -5: Transaction not found in the store
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_track_tx_live(
+    handle: MidenHandle,
+    tx_id_hex: *const c_char,
+    callback: WcEventCallback,
+    user_data: *mut c_void,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let tx_id = match parse_required_str(tx_id_hex).map(parse_tx_id) {
+        Ok(Some(tx_id)) => tx_id,
+        Ok(None) => return ERR_INVALID_PARAM,
+        Err(code) => return code,
+    };
+    let subscriber = callback.map(|callback| LiveSubscriber {
+        callback,
+        user_data: user_data as usize,
+    });
+
+    match request_blocking(&worker, |reply| Request::TrackTxLive { tx_id, subscriber, reply }) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,146 @@
+//! Accounting of Rust-allocated buffers
+//!
+//! Callback results and `_alloc` outputs are buffers the caller must release with
+//! `wc_bytes_free`; a forgotten free on the Swift side leaks silently. With the
+//! `memory-debug` feature every buffer handed out is registered with its size, the source
+//! location that produced it and its allocation time, and `wc_memory_report` lists the
+//! buffers still outstanding, so integrators can find leaks during development.
+//!
+//! The registry is strict: freeing a pointer it does not know (a double free, or a pointer
+//! that never came from this library) or freeing with a different length is logged and
+//! counted, and the pointer is left alone instead of corrupting the heap.
+//!
+//! Without the feature, registration compiles to nothing and the report only says that
+//! accounting is off.
+
+use crate::write_out_buffer;
+
+#[cfg(feature = "memory-debug")]
+mod registry {
+    use std::{collections::HashMap, panic::Location, sync::Mutex, time::Instant};
+
+    use once_cell::sync::Lazy;
+
+    use crate::diagnostics;
+
+    struct Allocation {
+        len: usize,
+        site: &'static Location<'static>,
+        allocated_at: Instant,
+    }
+
+    #[derive(Default)]
+    struct Registry {
+        /// Outstanding buffers by address
+        outstanding: HashMap<usize, Allocation>,
+        total_allocated: u64,
+        total_freed: u64,
+        invalid_frees: u64,
+    }
+
+    static REGISTRY: Lazy<Mutex<Registry>> = Lazy::new(|| Mutex::new(Registry::default()));
+
+    pub fn track(ptr: *mut u8, len: usize, site: &'static Location<'static>) {
+        let mut registry = REGISTRY.lock().unwrap();
+        registry.total_allocated += 1;
+        registry.outstanding.insert(ptr as usize, Allocation {
+            len,
+            site,
+            allocated_at: Instant::now(),
+        });
+    }
+
+    pub fn release(ptr: *mut u8, len: usize) -> bool {
+        let mut registry = REGISTRY.lock().unwrap();
+        let message = match registry.outstanding.get(&(ptr as usize)) {
+            Some(allocation) if allocation.len == len => {
+                registry.outstanding.remove(&(ptr as usize));
+                registry.total_freed += 1;
+                return true;
+            }
+            Some(allocation) => format!(
+                "wc_bytes_free({:p}, {}) with the wrong length ({} bytes from {})",
+                ptr, len, allocation.len, allocation.site
+            ),
+            None => {
+                format!("wc_bytes_free({:p}, {}) of an unknown buffer (double free?)", ptr, len)
+            }
+        };
+        registry.invalid_frees += 1;
+        drop(registry);
+        diagnostics::log("memory", message);
+        false
+    }
+
+    pub fn report() -> serde_json::Value {
+        let registry = REGISTRY.lock().unwrap();
+        let mut allocations: Vec<(&usize, &Allocation)> = registry.outstanding.iter().collect();
+        // Oldest first: long-lived buffers are the likely leaks
+        allocations.sort_by_key(|(_, allocation)| allocation.allocated_at);
+
+        let outstanding_bytes: usize = allocations.iter().map(|(_, a)| a.len).sum();
+        let allocations: Vec<serde_json::Value> = allocations
+            .into_iter()
+            .map(|(ptr, allocation)| {
+                serde_json::json!({
+                    "ptr": format!("{:#x}", ptr),
+                    "len": allocation.len,
+                    "site": allocation.site.to_string(),
+                    "age_ms": allocation.allocated_at.elapsed().as_millis() as u64,
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "enabled": true,
+            "outstanding_count": allocations.len(),
+            "outstanding_bytes": outstanding_bytes,
+            "total_allocated": registry.total_allocated,
+            "total_freed": registry.total_freed,
+            "invalid_frees": registry.invalid_frees,
+            "allocations": allocations,
+        })
+    }
+}
+
+#[cfg(feature = "memory-debug")]
+pub(crate) use registry::{release, track};
+
+/// Register a buffer handed to the caller (no-op without `memory-debug`)
+#[cfg(not(feature = "memory-debug"))]
+pub(crate) fn track(_ptr: *mut u8, _len: usize, _site: &'static std::panic::Location<'static>) {}
+
+/// Unregister a buffer the caller frees; false when it must not be freed
+#[cfg(not(feature = "memory-debug"))]
+pub(crate) fn release(_ptr: *mut u8, _len: usize) -> bool {
+    true
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Report the Rust-allocated buffers not yet released with `wc_bytes_free`
+///
+/// Safe to call from any thread. Requires the `memory-debug` Cargo feature; otherwise the
+/// report is `{"enabled":false}`.
+///
+/// # Parameters
+/// - `json_out` / `json_out_len`: Output buffer for `{"enabled","outstanding_count",
+///   "outstanding_bytes","total_allocated","total_freed","invalid_frees","allocations":
+///   [{"ptr","len","site","age_ms"}]}` (`site`: source location that produced the buffer;
+///   allocations oldest first)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_memory_report(json_out: *mut u8, json_out_len: *mut usize) -> i32 {
+    #[cfg(feature = "memory-debug")]
+    let report = registry::report();
+    #[cfg(not(feature = "memory-debug"))]
+    let report = serde_json::json!({ "enabled": false });
+
+    write_out_buffer(report.to_string().as_bytes(), json_out, json_out_len)
+}
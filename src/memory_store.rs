@@ -0,0 +1,47 @@
+//! In-memory clients
+//!
+//! Unit tests and demo sessions should not leave wallets on disk. With `"in_memory": true`
+//! in the client config (see config.rs) a client writes no files:
+//!
+//! - the SQLite store is a shared-cache in-memory database named after the client
+//!   (`file:miden-memory-<n>?mode=memory&cache=shared`), alive while the client is
+//! - keys of the filesystem backend are kept in process memory (see keystore.rs)
+//! - state other modules keep in files next to the store (archive, retention policy,
+//!   watches, the network pin, ...) is only held in memory: `write` skips paths derived
+//!   from an in-memory store, and loading them finds nothing
+//!
+//! Everything is gone when the handle is destroyed; there is nothing to back up or clean
+//! up. Features that produce files on request (diagnostics bundles, exports to a path)
+//! still write where they are asked to.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Prefix of the store and keystore paths of in-memory clients
+const MEMORY_PATH_PREFIX: &str = "file:miden-memory-";
+
+static NEXT_CLIENT: AtomicU64 = AtomicU64::new(1);
+
+/// Store and keystore paths of a new in-memory client
+pub(crate) fn paths() -> (PathBuf, PathBuf) {
+    let client = NEXT_CLIENT.fetch_add(1, Ordering::Relaxed);
+    let name = format!("{}{}-{}", MEMORY_PATH_PREFIX, std::process::id(), client);
+    let keystore_path = PathBuf::from(format!("{}-keys", name));
+    let store_path = PathBuf::from(format!("{}?mode=memory&cache=shared", name));
+    (keystore_path, store_path)
+}
+
+/// Whether `path` is (or is derived from) the store or keystore of an in-memory client
+pub(crate) fn is_in_memory(path: &Path) -> bool {
+    path.to_str().is_some_and(|path| path.starts_with(MEMORY_PATH_PREFIX))
+}
+
+/// Write a state file, or nothing for an in-memory client
+pub(crate) fn write(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    if is_in_memory(path) {
+        return Ok(());
+    }
+    std::fs::write(path, contents)
+}
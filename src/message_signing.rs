@@ -0,0 +1,184 @@
+//! Message signing
+//!
+//! Login and challenge-response flows of apps and dapps: `wc_miden_sign_message` signs an
+//! arbitrary message with an account's auth key.
+//!
+//! The signed digest is the RPO hash of `miden-message:v1:` followed by the message bytes.
+//! The prefix keeps a message signature from ever being valid as a transaction, ownership
+//! statement or export signature, so apps can pass untrusted messages from a server
+//! through. `wc_miden_verify_signature` checks a signature against a message and a public
+//! key commitment (see `wc_miden_get_public_key`) without a client handle, so counterparty
+//! signatures can be verified without a separate Falcon implementation.
+//!
+//! Signing goes through the account's key backend, so an external signer may block on a
+//! user confirmation.
+
+use std::os::raw::c_char;
+
+use miden_client::auth::{SigningInputs, TransactionAuthenticator};
+use miden_objects::{
+    account::{auth::Signature, AccountId},
+    crypto::hash::rpo::Rpo256,
+    utils::{Deserializable, Serializable},
+    Word,
+};
+
+use crate::{
+    export_signing::hex_word, get_handle, last_error, parse_account_id, parse_required_str,
+    request_blocking, reserves::AUTH_PUB_KEY_SLOT, write_out_buffer, MidenContext, MidenHandle,
+    Request, ERR_ACCOUNT_OP, ERR_INVALID_HANDLE, ERR_INVALID_PARAM, ERR_LOOKUP,
+};
+
+/// Version tag of the signed message format
+pub const SIGNED_MESSAGE_VERSION: u32 = 1;
+
+/// Maximum message length
+pub const SIGNED_MESSAGE_MAX_BYTES: usize = 64 * 1024;
+
+/// Digest signed for `message`
+fn message_digest(message: &[u8]) -> Word {
+    let mut prefixed = format!("miden-message:v{}:", SIGNED_MESSAGE_VERSION).into_bytes();
+    prefixed.extend_from_slice(message);
+    Rpo256::hash(&prefixed)
+}
+
+pub(crate) async fn sign_message_impl(
+    context: &MidenContext,
+    account_id: AccountId,
+    message: Vec<u8>,
+) -> Result<String, i32> {
+    let record = context.client.get_account(account_id).await
+        .map_err(|e| last_error::detail(ERR_LOOKUP, e))?
+        .ok_or(ERR_LOOKUP)?;
+    let pub_key = record.account().storage().get_item(AUTH_PUB_KEY_SLOT)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    let digest = message_digest(&message);
+    let signature = context.authenticator
+        .get_signature(pub_key.into(), &SigningInputs::Blind(digest))
+        .await
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    Ok(serde_json::json!({
+        "version": SIGNED_MESSAGE_VERSION,
+        "account_id": account_id.to_hex(),
+        "digest": hex::encode(digest.to_bytes()),
+        "pub_key": hex::encode(pub_key.to_bytes()),
+        "signature": hex::encode(signature.to_bytes()),
+    })
+    .to_string())
+}
+
+/// Check `signature` (serialized, hex) over `message` against `pub_key`
+fn verify_message(pub_key: Word, message: &[u8], signature_hex: &str) -> Result<(), i32> {
+    let signature_bytes =
+        hex::decode(signature_hex.trim_start_matches("0x")).map_err(|_| ERR_INVALID_PARAM)?;
+    let signature = match Signature::read_from_bytes(&signature_bytes) {
+        Ok(Signature::RpoFalcon512(signature)) => signature,
+        #[allow(unreachable_patterns)]
+        Ok(_) => return Err(ERR_ACCOUNT_OP),
+        Err(_) => return Err(ERR_INVALID_PARAM),
+    };
+
+    // A Falcon signature carries its public key; it must be the committed one
+    let public_key = signature.public_key();
+    if public_key.to_commitment() != pub_key
+        || !public_key.verify(message_digest(message), &signature)
+    {
+        return Err(ERR_ACCOUNT_OP);
+    }
+    Ok(())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Sign a message with an account's key (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_id_hex`: Account ID (C string); its key must be held by this client
+/// - `message_ptr` / `message_len`: Message (1 byte to 64 KB)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"version":1,"account_id","digest","pub_key","signature"}` (hex fields; `signature`:
+///   serialized RPO Falcon512 signature over `digest`, see the module docs)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters (including an empty or oversized message)
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID, or the account has no key this client can sign with
+/// - -5: Account not in the store
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_sign_message(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    message_ptr: *const u8,
+    message_len: usize,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if message_ptr.is_null() || !(1..=SIGNED_MESSAGE_MAX_BYTES).contains(&message_len) {
+        return ERR_INVALID_PARAM;
+    }
+    let message = unsafe { std::slice::from_raw_parts(message_ptr, message_len) }.to_vec();
+
+    let account_id = match parse_account_id(account_id_hex) {
+        Ok((account_id, _)) => account_id,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::SignMessage { account_id, message, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Verify a message signature made by `wc_miden_sign_message`
+///
+/// Needs no client handle; safe to call from any thread.
+///
+/// # Parameters
+/// - `pub_key_hex`: Signer's public key commitment, 32 bytes hex (C string), e.g. the
+///   `pub_key` reported by `wc_miden_get_public_key`
+/// - `message_ptr` / `message_len`: Signed message (1 byte to 64 KB)
+/// - `signature_hex`: Serialized RPO Falcon512 signature, hex (C string)
+///
+/// # Returns
+/// - 0: The signature is valid for the message and key
+/// - -1: Invalid parameters or a malformed signature
+/// - -3: Signature invalid, made by another key, or over another message
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_verify_signature(
+    pub_key_hex: *const c_char,
+    message_ptr: *const u8,
+    message_len: usize,
+    signature_hex: *const c_char,
+) -> i32 {
+    if message_ptr.is_null() || !(1..=SIGNED_MESSAGE_MAX_BYTES).contains(&message_len) {
+        return ERR_INVALID_PARAM;
+    }
+    let message = unsafe { std::slice::from_raw_parts(message_ptr, message_len) };
+
+    let pub_key = match parse_required_str(pub_key_hex).and_then(hex_word) {
+        Ok(pub_key) => pub_key,
+        Err(code) => return code,
+    };
+    let signature_hex = match parse_required_str(signature_hex) {
+        Ok(signature_hex) => signature_hex,
+        Err(code) => return code,
+    };
+
+    match verify_message(pub_key, message, signature_hex) {
+        Ok(()) => 0,
+        Err(code) => code,
+    }
+}
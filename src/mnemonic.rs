@@ -0,0 +1,84 @@
+//! BIP-39 mnemonics
+//!
+//! Recovery phrases for hierarchical deterministic wallets: `wc_mnemonic_generate` draws a
+//! new English phrase and `wc_mnemonic_to_seed` turns a phrase (and optional BIP-39
+//! passphrase) into the 64-byte master seed that `wc_miden_create_wallet_derived` takes
+//! (see derivation.rs). Neither needs a client handle, and nothing is stored: the app
+//! shows the phrase to the user and keeps it in the Keychain if at all.
+//!
+//! Apps that manage master seeds another way can leave this out by building without the
+//! `mnemonic` feature.
+
+use std::os::raw::c_char;
+
+use bip39::Mnemonic;
+use rand::RngCore;
+
+use crate::{parse_optional_str, parse_required_str, write_out_buffer, ERR_INVALID_PARAM};
+
+/// Length of the seed derived from a phrase
+const SEED_LEN: usize = 64;
+
+/// Generate a random English BIP-39 mnemonic
+///
+/// # Parameters
+/// - `word_count`: 12, 15, 18, 21 or 24 (128 to 256 bits of entropy)
+/// - `phrase_out` / `phrase_out_len`: Output buffer for the space-separated phrase (UTF-8)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters (including an unsupported word count)
+/// - -7: Output buffer too small; `*phrase_out_len` holds the size needed, and a retry
+///   generates a different phrase
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_mnemonic_generate(
+    word_count: u32,
+    phrase_out: *mut u8,
+    phrase_out_len: *mut usize,
+) -> i32 {
+    if !matches!(word_count, 12 | 15 | 18 | 21 | 24) {
+        return ERR_INVALID_PARAM;
+    }
+
+    // 32 bits of entropy per 3 words
+    let mut entropy = [0u8; 32];
+    let entropy = &mut entropy[..word_count as usize * 4 / 3];
+    rand::rng().fill_bytes(entropy);
+    let Ok(mnemonic) = Mnemonic::from_entropy(entropy) else {
+        return ERR_INVALID_PARAM;
+    };
+    write_out_buffer(mnemonic.to_string().as_bytes(), phrase_out, phrase_out_len)
+}
+
+/// Derive the 64-byte BIP-39 seed of a mnemonic
+///
+/// The phrase is checked against the English word list and its checksum, so a mistyped
+/// word is rejected instead of silently giving a different wallet.
+///
+/// # Parameters
+/// - `phrase`: Space-separated English mnemonic (C string)
+/// - `passphrase`: Optional BIP-39 passphrase (C string, NULL or empty for none)
+/// - `seed_out` / `seed_out_len`: Output buffer (at least 64 bytes); receives the seed
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters (including an invalid phrase or checksum)
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_mnemonic_to_seed(
+    phrase: *const c_char,
+    passphrase: *const c_char,
+    seed_out: *mut u8,
+    seed_out_len: *mut usize,
+) -> i32 {
+    let (phrase, passphrase) = match (parse_required_str(phrase), parse_optional_str(passphrase)) {
+        (Ok(phrase), Ok(passphrase)) => (phrase, passphrase.unwrap_or("")),
+        (Err(code), _) | (_, Err(code)) => return code,
+    };
+    let Ok(mnemonic) = Mnemonic::parse(phrase) else {
+        return ERR_INVALID_PARAM;
+    };
+
+    let seed: [u8; SEED_LEN] = mnemonic.to_seed(passphrase);
+    write_out_buffer(&seed, seed_out, seed_out_len)
+}
@@ -0,0 +1,339 @@
+//! MPC / threshold signing session scaffolding
+//!
+//! Falcon has no native threshold scheme, so the cryptographic protocol itself lives with
+//! the custodial provider. This module only coordinates it: a session is opened for one
+//! (public key commitment, message) pair, each participant posts an opaque payload per
+//! round, a round completes once every participant has contributed, and the session is
+//! closed by presenting the combined signature, which is verified before it is accepted.
+//!
+//! The pair typically comes from a cold-wallet blob (`pub_key` / `message`), and the
+//! verified signature is handed to `wc_miden_attach_signature_and_submit` on the same
+//! handle.
+//!
+//! Sessions belong to the handle that opened them and are kept in memory only: a session
+//! ID is unknown to every other handle, and destroying the handle drops its sessions. A
+//! session expires [`MPC_SESSION_TTL_SECS`] seconds after it was opened, and payloads are
+//! bounded per contribution and per session, so an abandoned or hostile exchange cannot
+//! grow the process without limit.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use miden_objects::{crypto::dsa::rpo_falcon512, utils::Deserializable, Word};
+
+use crate::{
+    clock, get_handle, signer::WORD_BYTES, write_out_buffer, MidenHandle, ERR_ACCOUNT_OP,
+    ERR_INVALID_HANDLE, ERR_INVALID_PARAM,
+};
+
+/// Maximum number of participants in one session
+pub const MPC_MAX_PARTICIPANTS: u32 = 16;
+
+/// Maximum number of rounds in one session
+pub const MPC_MAX_ROUNDS: u32 = 32;
+
+/// Seconds after which an open session expires
+pub const MPC_SESSION_TTL_SECS: u64 = 600;
+
+/// Maximum number of open sessions per handle
+pub const MPC_MAX_SESSIONS: usize = 32;
+
+/// Maximum size of one contribution
+pub const MPC_MAX_PAYLOAD_BYTES: usize = 64 * 1024;
+
+/// Maximum size of all contributions to one session
+pub const MPC_MAX_SESSION_BYTES: usize = 1024 * 1024;
+
+struct MpcSession {
+    pub_key: Word,
+    message: Word,
+    participants: u32,
+    /// Contributions per round, keyed by participant index
+    rounds: Vec<BTreeMap<u32, Vec<u8>>>,
+    /// Total size of the contributions
+    bytes: usize,
+    /// Unix time after which the session is dropped
+    expires_at: u64,
+}
+
+impl MpcSession {
+    fn current_round(&self) -> u32 {
+        (self.rounds.len() as u32).saturating_sub(1)
+    }
+
+    fn round_complete(&self, round: u32) -> bool {
+        self.rounds
+            .get(round as usize)
+            .is_some_and(|c| c.len() as u32 == self.participants)
+    }
+}
+
+/// Signing sessions of one handle
+#[derive(Default)]
+pub(crate) struct MpcSessions {
+    sessions: Mutex<HashMap<u64, MpcSession>>,
+}
+
+impl MpcSessions {
+    /// Open sessions, with the expired ones dropped
+    fn live(&self) -> std::sync::MutexGuard<'_, HashMap<u64, MpcSession>> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let now = clock::now_unix();
+        sessions.retain(|_, session| session.expires_at > now);
+        sessions
+    }
+}
+
+/// Session IDs are unique across handles, so an ID never names another handle's session
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+
+fn read_word(ptr: *const u8) -> Result<Word, i32> {
+    if ptr.is_null() {
+        return Err(ERR_INVALID_PARAM);
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(ptr, WORD_BYTES) };
+    Word::read_from_bytes(bytes).map_err(|_| ERR_INVALID_PARAM)
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Open a signing session for `message` under the key committed to by `pub_key_commitment`
+///
+/// The session belongs to `handle` and expires after `MPC_SESSION_TTL_SECS` seconds.
+///
+/// # Parameters
+/// - `pub_key_commitment`: 32-byte serialized public key commitment
+/// - `message`: 32-byte serialized message word
+/// - `participants`: Number of parties that must contribute each round (2-16)
+/// - `session_id_out`: Receives the session ID
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, or `MPC_MAX_SESSIONS` sessions already open on the handle
+/// - -2: Invalid handle
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_mpc_session_start(
+    handle: MidenHandle,
+    pub_key_commitment: *const u8,
+    message: *const u8,
+    participants: u32,
+    session_id_out: *mut u64,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if session_id_out.is_null() || !(2..=MPC_MAX_PARTICIPANTS).contains(&participants) {
+        return ERR_INVALID_PARAM;
+    }
+
+    let (pub_key, message) = match (read_word(pub_key_commitment), read_word(message)) {
+        (Ok(pk), Ok(msg)) => (pk, msg),
+        _ => return ERR_INVALID_PARAM,
+    };
+
+    let mut sessions = worker.mpc_sessions.live();
+    if sessions.len() >= MPC_MAX_SESSIONS {
+        return ERR_INVALID_PARAM;
+    }
+    let session_id = NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed);
+    sessions.insert(session_id, MpcSession {
+        pub_key,
+        message,
+        participants,
+        rounds: vec![BTreeMap::new()],
+        bytes: 0,
+        expires_at: clock::now_unix() + MPC_SESSION_TTL_SECS,
+    });
+
+    unsafe { *session_id_out = session_id };
+    0
+}
+
+/// Post a participant's payload for the current round
+///
+/// Rounds advance automatically once every participant has contributed; posting to any
+/// round other than the current one is rejected so that parties stay in lockstep.
+/// A payload may hold up to `MPC_MAX_PAYLOAD_BYTES` bytes, and all payloads of a session
+/// up to `MPC_MAX_SESSION_BYTES`.
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Unknown or expired session, wrong round, participant out of range, duplicate
+///   contribution, payload over the size limits or round limit reached
+/// - -2: Invalid handle
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_mpc_session_contribute(
+    handle: MidenHandle,
+    session_id: u64,
+    participant: u32,
+    round: u32,
+    payload_ptr: *const u8,
+    payload_len: usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if (payload_ptr.is_null() && payload_len > 0) || payload_len > MPC_MAX_PAYLOAD_BYTES {
+        return ERR_INVALID_PARAM;
+    }
+
+    let mut sessions = worker.mpc_sessions.live();
+    let Some(session) = sessions.get_mut(&session_id) else {
+        return ERR_INVALID_PARAM;
+    };
+
+    if participant >= session.participants || round != session.current_round() {
+        return ERR_INVALID_PARAM;
+    }
+    if session.bytes + payload_len > MPC_MAX_SESSION_BYTES {
+        return ERR_INVALID_PARAM;
+    }
+
+    let payload = if payload_len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(payload_ptr, payload_len) }.to_vec()
+    };
+
+    let contributions = session.rounds.last_mut().expect("session always has a round");
+    if contributions.contains_key(&participant) {
+        return ERR_INVALID_PARAM;
+    }
+    contributions.insert(participant, payload);
+    session.bytes += payload_len;
+
+    if session.round_complete(round) {
+        if session.rounds.len() as u32 >= MPC_MAX_ROUNDS {
+            return 0;
+        }
+        session.rounds.push(BTreeMap::new());
+    }
+    0
+}
+
+/// Get the payloads posted for a round
+///
+/// # Output JSON
+/// `{"session_id","round","current_round","complete","contributions":[{"participant","payload":"<hex>"}]}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Unknown or expired session, unknown round, or invalid parameters
+/// - -2: Invalid handle
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_mpc_session_round_payloads(
+    handle: MidenHandle,
+    session_id: u64,
+    round: u32,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let json = {
+        let sessions = worker.mpc_sessions.live();
+        let Some(session) = sessions.get(&session_id) else {
+            return ERR_INVALID_PARAM;
+        };
+        let Some(contributions) = session.rounds.get(round as usize) else {
+            return ERR_INVALID_PARAM;
+        };
+
+        format!(
+            r#"{{"session_id":{},"round":{},"current_round":{},"complete":{},"contributions":[{}]}}"#,
+            session_id,
+            round,
+            session.current_round(),
+            session.round_complete(round),
+            contributions
+                .iter()
+                .map(|(participant, payload)| format!(
+                    r#"{{"participant":{},"payload":"{}"}}"#,
+                    participant,
+                    hex::encode(payload)
+                ))
+                .collect::<Vec<_>>()
+                .join(",")
+        )
+    };
+
+    write_out_buffer(json.as_bytes(), json_out, json_out_len)
+}
+
+/// Close a session with the combined signature
+///
+/// The signature (serialized RPO Falcon512) must verify against the session's public key
+/// commitment and message. The session is removed only when verification succeeds, so a
+/// failed combination can be retried.
+///
+/// # Returns
+/// - 0: Signature verified, session closed
+/// - -1: Unknown or expired session, or malformed signature
+/// - -2: Invalid handle
+/// - -3: Signature does not verify
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_mpc_session_finish(
+    handle: MidenHandle,
+    session_id: u64,
+    signature_ptr: *const u8,
+    signature_len: usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if signature_ptr.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    let bytes = unsafe { std::slice::from_raw_parts(signature_ptr, signature_len) };
+    let Ok(signature) = rpo_falcon512::Signature::read_from_bytes(bytes) else {
+        return ERR_INVALID_PARAM;
+    };
+
+    let mut sessions = worker.mpc_sessions.live();
+    let Some(session) = sessions.get(&session_id) else {
+        return ERR_INVALID_PARAM;
+    };
+
+    // A Falcon signature carries its public key; it must be the committed one
+    let public_key = signature.public_key();
+    if public_key.to_commitment() != session.pub_key
+        || !public_key.verify(session.message, &signature)
+    {
+        return ERR_ACCOUNT_OP;
+    }
+
+    sessions.remove(&session_id);
+    0
+}
+
+/// Abort a session and discard its contributions
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Unknown or expired session
+/// - -2: Invalid handle
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_mpc_session_abort(handle: MidenHandle, session_id: u64) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    match worker.mpc_sessions.live().remove(&session_id) {
+        Some(_) => 0,
+        None => ERR_INVALID_PARAM,
+    }
+}
@@ -0,0 +1,304 @@
+//! On-chain name registry
+//!
+//! A registry is a public account whose storage map (at a configurable slot) maps the
+//! RPO hash of a normalized name to the registered account ID, stored as the word
+//! `[prefix, suffix, 0, 0]`. An empty word means the name is free.
+//!
+//! - Resolving reads the registry account's storage: it is imported on first use and kept
+//!   up to date by regular syncs.
+//! - Registering sends a public note to the registry. The note script is part of the
+//!   registry configuration because it is defined by the registry contract, which
+//!   consumes the note and writes the map entry; the name is taken once that transaction
+//!   is included. The note inputs are `[key(4), prefix, suffix]`.
+
+use std::{os::raw::c_char, sync::Mutex};
+
+use miden_client::transaction::TransactionRequestBuilder;
+use miden_lib::utils::ScriptBuilder;
+use miden_objects::{
+    account::AccountId,
+    crypto::{hash::rpo::Rpo256, rand::FeltRng},
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    transaction::OutputNote,
+    Felt, Word,
+};
+
+use crate::{
+    check_out_capacity, get_handle, last_error, parse_account_id, parse_required_str, proving,
+    request_blocking, screening, write_out_buffer, MidenContext, MidenHandle, Request,
+    ERR_ACCOUNT_OP, ERR_INVALID_HANDLE, ERR_INVALID_PARAM, ERR_LOOKUP, ERR_NOTE_OP, ERR_TX_SUBMIT,
+    ID_HEX_LEN,
+};
+
+/// Minimum name length (after normalization)
+pub const NAME_MIN_LEN: usize = 3;
+
+/// Maximum name length (after normalization)
+pub const NAME_MAX_LEN: usize = 32;
+
+/// Registry configured on a handle
+#[derive(Clone)]
+pub(crate) struct NameRegistry {
+    account_id: AccountId,
+    slot: u8,
+    register_script: Option<NoteScript>,
+}
+
+impl NameRegistry {
+    /// Parse `{"account_id":"0x..","storage_slot":1,"register_script":"<MASM>"}`
+    /// (`register_script` is only needed for registering)
+    fn from_json(json: &str) -> Result<Self, i32> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|_| ERR_INVALID_PARAM)?;
+
+        let account_id = value["account_id"].as_str()
+            .and_then(|hex| AccountId::from_hex(hex).ok())
+            .ok_or(ERR_INVALID_PARAM)?;
+        let slot = value["storage_slot"].as_u64()
+            .and_then(|slot| u8::try_from(slot).ok())
+            .ok_or(ERR_INVALID_PARAM)?;
+        let register_script = match value["register_script"].as_str() {
+            Some(source) => Some(
+                ScriptBuilder::new(true)
+                    .compile_note_script(source)
+                    .map_err(|_| ERR_INVALID_PARAM)?,
+            ),
+            None => None,
+        };
+
+        Ok(Self { account_id, slot, register_script })
+    }
+}
+
+/// Registry configuration of a handle (read by the FFI thread, never by the worker)
+#[derive(Default)]
+pub(crate) struct NameRegistryConfig(Mutex<Option<NameRegistry>>);
+
+impl NameRegistryConfig {
+    /// Snapshot of the configured registry (-1 when none is configured)
+    fn get(&self) -> Result<NameRegistry, i32> {
+        self.0.lock().unwrap().clone().ok_or(ERR_INVALID_PARAM)
+    }
+}
+
+/// Lowercase a name and check it is 3-32 characters of `[a-z0-9._-]`
+fn normalize_name(name: &str) -> Result<String, i32> {
+    let name = name.trim().to_ascii_lowercase();
+    let valid = (NAME_MIN_LEN..=NAME_MAX_LEN).contains(&name.len())
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b".-_".contains(&b));
+    if valid { Ok(name) } else { Err(ERR_INVALID_PARAM) }
+}
+
+/// Storage map key of a normalized name
+fn name_key(name: &str) -> Word {
+    Rpo256::hash(name.as_bytes())
+}
+
+pub(crate) async fn resolve_name_impl(
+    context: &mut MidenContext,
+    registry: NameRegistry,
+    key: Word,
+) -> Result<String, i32> {
+    let existing = context.client.get_account(registry.account_id).await.map_err(|_| ERR_LOOKUP)?;
+    let record = match existing {
+        Some(record) => record,
+        None => {
+            context.client.import_account_by_id(registry.account_id).await
+                .map_err(|_| ERR_LOOKUP)?;
+            context.client.get_account(registry.account_id).await
+                .map_err(|_| ERR_LOOKUP)?
+                .ok_or(ERR_LOOKUP)?
+        }
+    };
+
+    let value = record.account().storage().get_map_item(registry.slot, key)
+        .map_err(|_| ERR_ACCOUNT_OP)?;
+    if value == Word::default() {
+        return Err(ERR_LOOKUP);
+    }
+
+    AccountId::try_from([value[0], value[1]])
+        .map(|account_id| account_id.to_hex())
+        .map_err(|_| ERR_ACCOUNT_OP)
+}
+
+pub(crate) async fn register_name_impl(
+    context: &mut MidenContext,
+    registry: NameRegistry,
+    account_id: AccountId,
+    key: Word,
+) -> Result<String, i32> {
+    let script = registry.register_script.ok_or(ERR_INVALID_PARAM)?;
+
+    let mut inputs: Vec<Felt> = key.iter().copied().collect();
+    inputs.extend([account_id.prefix().as_felt(), account_id.suffix()]);
+    let inputs = NoteInputs::new(inputs).map_err(|_| ERR_NOTE_OP)?;
+
+    let serial_num = context.client.rng().draw_word();
+    let metadata = NoteMetadata::new(
+        account_id,
+        NoteType::Public,
+        NoteTag::from_account_id(registry.account_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )
+    .map_err(|_| ERR_NOTE_OP)?;
+    let note = Note::new(
+        NoteAssets::default(),
+        metadata,
+        NoteRecipient::new(serial_num, script, inputs),
+    );
+
+    screening::screen_transaction(context, account_id, std::slice::from_ref(&note), &[]).await?;
+
+    let tx_request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note)])
+        .build()
+        .map_err(|_| ERR_NOTE_OP)?;
+
+    proving::check_memory(context)?;
+    context.client.submit_new_transaction(account_id, tx_request).await
+        .map(|tx_id| tx_id.to_hex())
+        .map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Configure the name registry used by a handle
+///
+/// # Parameters
+/// - `config_json`: `{"account_id":"0x..","storage_slot":1,"register_script":"<MASM>"}`,
+///   or NULL to remove the configuration. `register_script` is the registry's
+///   registration note script and is only needed for `wc_miden_register_name`.
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid configuration (including a script that does not compile)
+/// - -2: Invalid handle
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_set_name_registry(
+    handle: MidenHandle,
+    config_json: *const c_char,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let registry = if config_json.is_null() {
+        None
+    } else {
+        match parse_required_str(config_json).and_then(NameRegistry::from_json) {
+            Ok(registry) => Some(registry),
+            Err(code) => return code,
+        }
+    };
+
+    *worker.name_registry.0.lock().unwrap() = registry;
+    0
+}
+
+/// Resolve a registered name to an account ID (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Names are case-insensitive. The first lookup imports the registry account; later
+/// lookups read the state of the last sync.
+///
+/// # Parameters
+/// - `name`: Name to resolve (C string, 3-32 characters of `[a-z0-9._-]`)
+/// - `account_id_out` / `account_id_out_len`: Output buffer for the account ID hex
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid name or no registry configured
+/// - -2: Invalid handle or worker closed
+/// - -3: Registry entry is not a valid account ID
+/// - -5: Name not registered or registry account not found
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_resolve_name(
+    handle: MidenHandle,
+    name: *const c_char,
+    account_id_out: *mut u8,
+    account_id_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let registry = match worker.name_registry.get() {
+        Ok(registry) => registry,
+        Err(code) => return code,
+    };
+    let key = match parse_required_str(name).and_then(normalize_name) {
+        Ok(name) => name_key(&name),
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::ResolveName { registry, key, reply }) {
+        Ok(account_id) => write_out_buffer(&account_id, account_id_out, account_id_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Register a name for a local account (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Submits the registration note; the name resolves once the registry has consumed it.
+/// Check availability with `wc_miden_resolve_name` first (-5 means free).
+///
+/// # Parameters
+/// - `account_id_hex`: Account that sends the note and is registered under `name`
+/// - `name`: Name to register (C string, 3-32 characters of `[a-z0-9._-]`)
+/// - `tx_id_out` / `tx_id_out_len`: Output buffer for the transaction ID hex
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid name, or no registry or registration script configured
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID
+/// - -4: Note construction failed
+/// - -6: Transaction failed
+/// - -7: Output buffer too small
+/// - -102: Blocked by the screening callback
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_register_name(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    name: *const c_char,
+    tx_id_out: *mut u8,
+    tx_id_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if let Err(code) = check_out_capacity(tx_id_out, tx_id_out_len, ID_HEX_LEN) {
+        return code;
+    }
+    let registry = match worker.name_registry.get() {
+        Ok(registry) => registry,
+        Err(code) => return code,
+    };
+    let (account_id, _) = match parse_account_id(account_id_hex) {
+        Ok(parsed) => parsed,
+        Err(code) => return code,
+    };
+    let key = match parse_required_str(name).and_then(normalize_name) {
+        Ok(name) => name_key(&name),
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::RegisterName { registry, account_id, key, reply };
+    match request_blocking(&worker, request) {
+        Ok(tx_id) => write_out_buffer(&tx_id, tx_id_out, tx_id_out_len),
+        Err(code) => code,
+    }
+}
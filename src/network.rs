@@ -0,0 +1,208 @@
+//! Network presets and network pinning
+//!
+//! Endpoints can be given as an alias instead of a URL:
+//!
+//! - `testnet` and `devnet`: the public Miden nodes
+//! - `localhost` (port 57291) or `localhost:<port>`: a node run during development
+//!
+//! Anything else must be a URL and counts as a `custom` network, except the URLs of the
+//! public nodes, which count as their alias.
+//!
+//! Nodes do not report a network name, so a network is identified by its genesis block:
+//! the first `wc_miden_check_network` on a store pins the node's genesis commitment and the
+//! network the handle was created for in a JSON file next to the store. Later checks fail
+//! with `ERR_NETWORK_MISMATCH` when the node has another genesis block (a testnet store
+//! opened against devnet, or a local node that was reset) or when a store pinned to one
+//! public network is opened with the other's alias. `localhost` and `custom` endpoints may
+//! be swapped for each other as long as the genesis block matches.
+
+use std::path::{Path, PathBuf};
+
+use miden_client::rpc::{Endpoint, NodeRpcClient};
+use miden_objects::{
+    utils::{Deserializable, Serializable},
+    Word,
+};
+
+use crate::{
+    diagnostics, get_handle, last_error, memory_store, request_blocking, write_out_buffer,
+    MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE, ERR_INVALID_PARAM,
+    ERR_NETWORK_MISMATCH,
+};
+
+/// Network a handle was created for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Network {
+    Testnet,
+    Devnet,
+    Localhost,
+    Custom,
+}
+
+impl Network {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Network::Testnet => "testnet",
+            Network::Devnet => "devnet",
+            Network::Localhost => "localhost",
+            Network::Custom => "custom",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "testnet" => Some(Network::Testnet),
+            "devnet" => Some(Network::Devnet),
+            "localhost" => Some(Network::Localhost),
+            "custom" => Some(Network::Custom),
+            _ => None,
+        }
+    }
+
+    fn is_public(self) -> bool {
+        matches!(self, Network::Testnet | Network::Devnet)
+    }
+}
+
+/// Resolve an alias (see the module docs) or a URL
+pub(crate) fn resolve_endpoint(value: &str) -> Result<(Endpoint, Network), i32> {
+    match value {
+        "testnet" => return Ok((Endpoint::testnet(), Network::Testnet)),
+        "devnet" => return Ok((Endpoint::devnet(), Network::Devnet)),
+        "localhost" => return Ok((Endpoint::localhost(), Network::Localhost)),
+        _ => {}
+    }
+
+    if let Some(port) = value.strip_prefix("localhost:") {
+        let port: u16 = port.parse().ok().filter(|port| *port > 0).ok_or(ERR_INVALID_PARAM)?;
+        let endpoint = Endpoint::try_from(format!("http://localhost:{}", port).as_str())
+            .map_err(|_| ERR_INVALID_PARAM)?;
+        return Ok((endpoint, Network::Localhost));
+    }
+
+    // The URL of a public node counts as its alias
+    let endpoint = Endpoint::try_from(value).map_err(|_| ERR_INVALID_PARAM)?;
+    let network = if endpoint.to_string() == Endpoint::testnet().to_string() {
+        Network::Testnet
+    } else if endpoint.to_string() == Endpoint::devnet().to_string() {
+        Network::Devnet
+    } else {
+        Network::Custom
+    };
+    Ok((endpoint, network))
+}
+
+/// Network of a handle and the network pinned for its store
+pub(crate) struct NetworkGuard {
+    network: Network,
+    path: PathBuf,
+    /// Pinned network and genesis commitment
+    pinned: Option<(Network, Word)>,
+}
+
+impl NetworkGuard {
+    /// Load the pin file of the store at `store_path` (missing or corrupt → not pinned)
+    pub fn load(store_path: &Path, network: Network) -> Self {
+        let path = store_path.with_extension("network.json");
+        let pinned = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+            .and_then(|value| {
+                let network = Network::from_name(value["network"].as_str()?)?;
+                let commitment = hex::decode(value["genesis_commitment"].as_str()?).ok()?;
+                Some((network, Word::read_from_bytes(&commitment).ok()?))
+            });
+        Self { network, path, pinned }
+    }
+
+    fn pin(&mut self, genesis_commitment: Word) {
+        let pin = serde_json::json!({
+            "network": self.network.as_str(),
+            "genesis_commitment": hex::encode(genesis_commitment.to_bytes()),
+        });
+        if let Err(e) = memory_store::write(&self.path, pin.to_string()) {
+            diagnostics::log("network", format!("failed to save network pin: {}", e));
+        }
+        self.pinned = Some((self.network, genesis_commitment));
+    }
+}
+
+pub(crate) async fn check_network_impl(context: &mut MidenContext) -> Result<String, i32> {
+    let genesis = match context.rpc.get_block_header_by_number(Some(0u32.into()), false).await {
+        Ok((header, _)) => header,
+        Err(e) => return Err(last_error::detail(ERR_INVALID_HANDLE, e)),
+    };
+    let genesis_commitment = genesis.commitment();
+
+    let guard = &mut context.network;
+    let newly_pinned = guard.pinned.is_none();
+    let (pinned_network, pinned_commitment) = match guard.pinned {
+        Some(pinned) => pinned,
+        None => {
+            guard.pin(genesis_commitment);
+            (guard.network, genesis_commitment)
+        }
+    };
+
+    let public_mixup = pinned_network != guard.network
+        && (pinned_network.is_public() || guard.network.is_public());
+    if pinned_commitment != genesis_commitment || public_mixup {
+        let message = format!(
+            "store belongs to {} (genesis {}), node at {} is {} (genesis {})",
+            pinned_network.as_str(),
+            pinned_commitment.to_hex(),
+            context.connection.endpoint,
+            guard.network.as_str(),
+            genesis_commitment.to_hex(),
+        );
+        return Err(last_error::detail(ERR_NETWORK_MISMATCH, message));
+    }
+
+    Ok(serde_json::json!({
+        "network": guard.network.as_str(),
+        "endpoint": context.connection.endpoint.to_string(),
+        "genesis_commitment": genesis_commitment.to_hex(),
+        "genesis_timestamp": genesis.timestamp(),
+        "newly_pinned": newly_pinned,
+    })
+    .to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Check that the node is on the network the store belongs to (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Run it after creating a handle, before syncing or sending. The first successful check
+/// pins the node's genesis block for the store (see the module docs).
+///
+/// # Output JSON
+/// `{"network":"devnet","endpoint","genesis_commitment","genesis_timestamp",
+///   "newly_pinned":true}` (`network`: `testnet`, `devnet`, `localhost` or `custom`)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle, worker closed or node unreachable
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+/// - -104: The node is on another network than the store; the store is left untouched
+///   (`wc_miden_last_error_message` names both networks)
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_check_network(
+    handle: MidenHandle,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    match request_blocking(&worker, |reply| Request::CheckNetwork { reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,734 @@
+//! Local proxy between the gRPC client and the node
+//!
+//! Syncing large accounts over cellular moves a lot of data, but `miden-client`'s
+//! `GrpcClient` builds its tonic channel internally: it takes an endpoint and a timeout,
+//! and sends and accepts uncompressed messages only. Each handle therefore points its
+//! `GrpcClient` at a proxy on the loopback interface, served by a thread of the handle,
+//! which forwards every call to the node over its own channel (TLS for `https`
+//! endpoints, like `miden-client`) and:
+//!
+//! - asks the node for gzip-compressed responses and decompresses them for the client;
+//!   a node that does not compress answers as before
+//! - gzip-compresses requests of 1 KiB and more once the node has listed gzip among the
+//!   encodings it accepts (`grpc-accept-encoding` of an earlier response)
+//! - refuses responses with a message larger than `max_message_bytes` (after
+//!   decompression) with `RESOURCE_EXHAUSTED`; `GrpcClient` refuses messages over 4 MiB
+//!   itself, so the limit can be lowered, not raised. Bodies are read frame by frame and
+//!   refused as soon as a message's length prefix or the body as a whole is over the limit
+//!   (node calls are unary: one message per body), so an oversized response is never held
+//!   in memory; request bodies are bounded the same way by tonic's 4 MiB default
+//! - counts the gRPC message bytes sent and received, as they go over the network
+//!   (compressed, with their 5-byte prefixes; HTTP/2 headers and TLS are not counted)
+//!
+//! Compression is set with `compression` in the client config (`gzip`, the default, or
+//! `none`) and the limit with `max_message_bytes` (see config.rs). When the loopback port
+//! cannot be opened, the client connects to the node directly: uncompressed, without the
+//! limit, and counting nothing.
+//!
+//! The proxy does not authenticate its callers: any process on the device that finds the
+//! port can send calls through it. It cannot require a secret, since `GrpcClient` takes
+//! only an endpoint and sends no headers of ours, and it does not need one: it holds no
+//! credentials, keys or wallet state, adds nothing to a call but compression, and answers
+//! only on the loopback interface, so a caller gets what it would get by calling the node
+//! itself. What it can do is add to the handle's traffic counts, which are a bandwidth
+//! report, not a billing or security record.
+
+use std::{
+    convert::Infallible,
+    io::{Read, Write},
+    net::{Ipv4Addr, TcpListener},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use http_body_util::BodyExt;
+use hyper::{
+    body::{Body, Bytes, Frame},
+    header::{HeaderMap, HeaderValue},
+    Request, Response,
+};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use miden_client::rpc::Endpoint;
+use tokio::sync::oneshot;
+use tonic::{
+    codegen::Service,
+    transport::{Channel, ClientTlsConfig},
+};
+
+use crate::diagnostics;
+
+/// Largest response message `GrpcClient` accepts (tonic's default decoding limit)
+pub const DEFAULT_MAX_MESSAGE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Smallest `max_message_bytes` accepted in the client config
+pub const MIN_MAX_MESSAGE_BYTES: usize = 1024;
+
+/// Requests smaller than this are sent uncompressed
+const COMPRESS_REQUESTS_FROM: usize = 1024;
+
+/// Length of the prefix of each gRPC message (compressed flag, big-endian length)
+const PREFIX_LEN: usize = 5;
+
+/// Largest request message forwarded (tonic's default encoding limit)
+const MAX_REQUEST_MESSAGE_BYTES: usize = DEFAULT_MAX_MESSAGE_BYTES;
+
+/// gRPC status codes the proxy answers with itself
+const STATUS_RESOURCE_EXHAUSTED: u32 = 8;
+const STATUS_INTERNAL: u32 = 13;
+const STATUS_UNAVAILABLE: u32 = 14;
+
+/// Message compression of node calls
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MessageCompression {
+    None,
+    Gzip,
+}
+
+/// Node traffic of a handle, in gRPC message bytes
+#[derive(Default)]
+pub(crate) struct Traffic {
+    sent: AtomicU64,
+    received: AtomicU64,
+}
+
+/// Traffic totals at one point, or between two points
+#[derive(Clone, Copy)]
+pub(crate) struct TrafficCount {
+    pub sent: u64,
+    pub received: u64,
+}
+
+impl Traffic {
+    pub fn count(&self) -> TrafficCount {
+        TrafficCount {
+            sent: self.sent.load(Ordering::Relaxed),
+            received: self.received.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Traffic since `start` (an earlier `count`)
+    pub fn since(&self, start: TrafficCount) -> TrafficCount {
+        let now = self.count();
+        TrafficCount {
+            sent: now.sent.saturating_sub(start.sent),
+            received: now.received.saturating_sub(start.received),
+        }
+    }
+}
+
+/// Forwarding state shared by the proxy's connections
+struct Forwarder {
+    node: Endpoint,
+    timeout_ms: u64,
+    compression: MessageCompression,
+    max_message_bytes: usize,
+    /// Channel to the node, created on first use and dropped by `reconnect`
+    channel: Mutex<Option<Channel>>,
+    /// The node accepts gzip-compressed requests
+    node_accepts_gzip: AtomicBool,
+    traffic: Traffic,
+}
+
+struct Server {
+    stop: oneshot::Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+/// Loopback proxy of a handle (owned by the worker's context)
+pub(crate) struct NodeProxy {
+    /// Endpoint the `GrpcClient` connects to: the proxy, or the node when it is not running
+    endpoint: Endpoint,
+    forwarder: Arc<Forwarder>,
+    server: Option<Server>,
+}
+
+impl NodeProxy {
+    /// Open the loopback port and start serving it (worker only)
+    pub fn start(
+        node: Endpoint,
+        timeout_ms: u64,
+        compression: MessageCompression,
+        max_message_bytes: usize,
+    ) -> Self {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0));
+        Self::serve(node, timeout_ms, compression, max_message_bytes, listener)
+    }
+
+    /// Serve `listener`, or leave the client to the node itself when it could not be opened
+    fn serve(
+        node: Endpoint,
+        timeout_ms: u64,
+        compression: MessageCompression,
+        max_message_bytes: usize,
+        listener: std::io::Result<TcpListener>,
+    ) -> Self {
+        let forwarder = Arc::new(Forwarder {
+            node: node.clone(),
+            timeout_ms,
+            compression,
+            max_message_bytes,
+            channel: Mutex::new(None),
+            node_accepts_gzip: AtomicBool::new(false),
+            traffic: Traffic::default(),
+        });
+
+        let listener = listener
+            .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+            .and_then(|listener| listener.local_addr().map(|address| (listener, address)));
+        let (listener, address) = match listener {
+            Ok(bound) => bound,
+            Err(e) => {
+                diagnostics::log("node_proxy", format!("connecting directly: {}", e));
+                return Self { endpoint: node, forwarder, server: None };
+            }
+        };
+
+        let (stop, stopped) = oneshot::channel();
+        let serving = forwarder.clone();
+        let thread = std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create Tokio runtime");
+
+            rt.block_on(async move {
+                let listener = match tokio::net::TcpListener::from_std(listener) {
+                    Ok(listener) => listener,
+                    Err(e) => return diagnostics::log("node_proxy", e),
+                };
+                let accepting = tokio::spawn(accept(listener, serving));
+                let _ = stopped.await;
+                accepting.abort();
+            });
+        });
+
+        let endpoint = Endpoint::new("http".into(), address.ip().to_string(), Some(address.port()));
+        Self { endpoint, forwarder, server: Some(Server { stop, thread }) }
+    }
+
+    /// Endpoint to build the `GrpcClient` with
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    /// Node traffic counters of the handle
+    pub fn traffic(&self) -> &Traffic {
+        &self.forwarder.traffic
+    }
+
+    /// Open a new channel to the node for the next calls (see reconnect.rs)
+    pub fn reconnect(&self) {
+        self.forwarder.channel.lock().unwrap().take();
+    }
+}
+
+impl Drop for NodeProxy {
+    fn drop(&mut self) {
+        if let Some(Server { stop, thread }) = self.server.take() {
+            let _ = stop.send(());
+            let _ = thread.join();
+        }
+    }
+}
+
+async fn accept(listener: tokio::net::TcpListener, forwarder: Arc<Forwarder>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                diagnostics::log("node_proxy", format!("accept failed: {}", e));
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let forwarder = forwarder.clone();
+        tokio::spawn(async move {
+            let service = hyper::service::service_fn(move |request| {
+                let forwarder = forwarder.clone();
+                async move { Ok::<_, Infallible>(forwarder.forward(request).await) }
+            });
+            let served = hyper::server::conn::http2::Builder::new(TokioExecutor::new())
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+            if let Err(e) = served {
+                diagnostics::log("node_proxy", format!("connection closed: {}", e));
+            }
+        });
+    }
+}
+
+impl Forwarder {
+    /// Channel to the node, connecting lazily on the proxy's runtime
+    fn channel(&self) -> Result<Channel, tonic::transport::Error> {
+        let mut channel = self.channel.lock().unwrap();
+        if let Some(channel) = channel.as_ref() {
+            return Ok(channel.clone());
+        }
+        let connected = tonic::transport::Endpoint::try_from(self.node.to_string())?
+            .timeout(Duration::from_millis(self.timeout_ms))
+            .tls_config(ClientTlsConfig::new().with_native_roots())?
+            .connect_lazy();
+        Ok(channel.insert(connected).clone())
+    }
+
+    /// Forward one call to the node, answering with a gRPC error when that fails
+    async fn forward<B>(&self, request: Request<B>) -> Response<Buffered>
+    where
+        B: Body<Data = Bytes> + Unpin,
+    {
+        let (mut parts, body) = request.into_parts();
+        let body = match read_body(body, MAX_REQUEST_MESSAGE_BYTES).await {
+            Ok((body, _)) => body,
+            Err(ReadError::TooLarge) => {
+                let message =
+                    format!("request message larger than {} bytes", MAX_REQUEST_MESSAGE_BYTES);
+                return status_response(STATUS_RESOURCE_EXHAUSTED, &message);
+            }
+            Err(ReadError::Interrupted) => {
+                return status_response(STATUS_INTERNAL, "request body not received");
+            }
+        };
+
+        let body = if self.node_accepts_gzip.load(Ordering::Relaxed)
+            && body.len() >= COMPRESS_REQUESTS_FROM
+        {
+            match recode(&body, true, |message, _| gzip(message).map(Some)) {
+                Ok(compressed) => {
+                    parts.headers.insert("grpc-encoding", HeaderValue::from_static("gzip"));
+                    compressed
+                }
+                Err(_) => body,
+            }
+        } else {
+            body
+        };
+        if self.compression == MessageCompression::Gzip {
+            parts.headers.insert("grpc-accept-encoding", HeaderValue::from_static("gzip"));
+        }
+        self.traffic.sent.fetch_add(body.len() as u64, Ordering::Relaxed);
+
+        let request = Request::from_parts(parts, tonic::body::Body::new(Buffered::new(body)));
+        let response = match self.channel() {
+            Ok(mut channel) => async {
+                std::future::poll_fn(|cx| channel.poll_ready(cx)).await?;
+                channel.call(request).await
+            }
+            .await,
+            Err(e) => Err(e),
+        };
+        let (mut parts, body) = match response {
+            Ok(response) => response.into_parts(),
+            Err(e) => return status_response(STATUS_UNAVAILABLE, &format!("node proxy: {}", e)),
+        };
+        let limit = self.max_message_bytes;
+        let (body, trailers) = match read_body(body, limit).await {
+            Ok(read) => read,
+            Err(ReadError::TooLarge) => return too_large(limit),
+            Err(ReadError::Interrupted) => {
+                return status_response(STATUS_UNAVAILABLE, "node proxy: response interrupted");
+            }
+        };
+        self.traffic.received.fetch_add(body.len() as u64, Ordering::Relaxed);
+
+        if header_lists(&parts.headers, "grpc-accept-encoding", "gzip") {
+            self.node_accepts_gzip.store(true, Ordering::Relaxed);
+        }
+        let gzipped = header_lists(&parts.headers, "grpc-encoding", "gzip");
+        parts.headers.remove("grpc-encoding");
+        parts.headers.remove("grpc-accept-encoding");
+
+        let body = recode(&body, false, |message, flagged| {
+            let message = match (flagged, gzipped) {
+                (false, _) => message.to_vec(),
+                (true, true) => gunzip(message, limit)?,
+                (true, false) => return Err(RecodeError::Malformed),
+            };
+            Ok((message.len() <= limit).then_some(message))
+        });
+        match body {
+            Ok(body) => {
+                let data = (!body.is_empty()).then_some(body);
+                Response::from_parts(parts, Buffered { data, trailers })
+            }
+            Err(RecodeError::TooLarge) => too_large(limit),
+            Err(RecodeError::Malformed) => {
+                status_response(STATUS_INTERNAL, "node proxy: malformed response message")
+            }
+        }
+    }
+}
+
+/// Answer for a response message over `max_message_bytes`
+fn too_large(limit: usize) -> Response<Buffered> {
+    status_response(
+        STATUS_RESOURCE_EXHAUSTED,
+        &format!("response message larger than max_message_bytes ({})", limit),
+    )
+}
+
+enum ReadError {
+    /// A message, or the body, was over the size limit
+    TooLarge,
+    /// The body ended with an error
+    Interrupted,
+}
+
+/// Read a gRPC body into memory with its trailers, refusing it as soon as a message's
+/// length prefix says it is over `limit` or the body grows past one message of `limit`
+async fn read_body<B>(mut body: B, limit: usize) -> Result<(Bytes, Option<HeaderMap>), ReadError>
+where
+    B: Body<Data = Bytes> + Unpin,
+{
+    let mut data = Vec::new();
+    let mut trailers = None;
+    // Offset of the next message's prefix in `data`
+    let mut next_prefix = 0;
+    while let Some(frame) = body.frame().await {
+        let frame = frame.map_err(|_| ReadError::Interrupted)?;
+        let chunk = match frame.into_data() {
+            Ok(chunk) => chunk,
+            Err(frame) => {
+                trailers = frame.into_trailers().ok().or(trailers);
+                continue;
+            }
+        };
+        if data.len() + chunk.len() > limit + PREFIX_LEN {
+            return Err(ReadError::TooLarge);
+        }
+        data.extend_from_slice(&chunk);
+
+        while let Some(prefix) = data.get(next_prefix..next_prefix + PREFIX_LEN) {
+            let len = u32::from_be_bytes([prefix[1], prefix[2], prefix[3], prefix[4]]) as usize;
+            if len > limit {
+                return Err(ReadError::TooLarge);
+            }
+            next_prefix += PREFIX_LEN + len;
+        }
+    }
+    Ok((Bytes::from(data), trailers))
+}
+
+enum RecodeError {
+    /// A message was over the size limit
+    TooLarge,
+    /// The body was not a sequence of gRPC messages, or did not decompress
+    Malformed,
+}
+
+impl From<std::io::Error> for RecodeError {
+    fn from(_: std::io::Error) -> Self {
+        RecodeError::Malformed
+    }
+}
+
+/// Rewrite each message of a gRPC body with `recode`, which is given the message and its
+/// compressed flag and returns None for a message over the size limit; the rewritten
+/// messages are flagged as `compressed`
+fn recode(
+    body: &[u8],
+    compressed: bool,
+    recode: impl Fn(&[u8], bool) -> Result<Option<Vec<u8>>, RecodeError>,
+) -> Result<Bytes, RecodeError> {
+    let mut out = Vec::with_capacity(body.len());
+    let mut rest = body;
+    while !rest.is_empty() {
+        if rest.len() < PREFIX_LEN {
+            return Err(RecodeError::Malformed);
+        }
+        let flagged = rest[0] == 1;
+        let len = u32::from_be_bytes([rest[1], rest[2], rest[3], rest[4]]) as usize;
+        let message = rest.get(PREFIX_LEN..PREFIX_LEN + len).ok_or(RecodeError::Malformed)?;
+        rest = &rest[PREFIX_LEN + len..];
+
+        let recoded = recode(message, flagged)?.ok_or(RecodeError::TooLarge)?;
+        let len = u32::try_from(recoded.len()).map_err(|_| RecodeError::TooLarge)?;
+        out.push(u8::from(compressed));
+        out.extend_from_slice(&len.to_be_bytes());
+        out.extend_from_slice(&recoded);
+    }
+    Ok(Bytes::from(out))
+}
+
+fn gzip(message: &[u8]) -> Result<Vec<u8>, RecodeError> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(message)?;
+    Ok(encoder.finish()?)
+}
+
+/// Decompress a message, stopping one byte past `limit` (the caller refuses it then)
+fn gunzip(message: &[u8], limit: usize) -> Result<Vec<u8>, RecodeError> {
+    let mut out = Vec::new();
+    GzDecoder::new(message).take(limit as u64 + 1).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+/// Whether a comma-separated header lists `value`
+fn header_lists(headers: &HeaderMap, name: &str, value: &str) -> bool {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|header| header.to_str().ok())
+        .flat_map(|header| header.split(','))
+        .any(|listed| listed.trim() == value)
+}
+
+/// A trailers-only gRPC response reporting an error
+fn status_response(code: u32, message: &str) -> Response<Buffered> {
+    let mut response = Response::new(Buffered::new(Bytes::new()));
+    let headers = response.headers_mut();
+    headers.insert("content-type", HeaderValue::from_static("application/grpc"));
+    headers.insert("grpc-status", HeaderValue::from(code));
+    if let Ok(message) = HeaderValue::from_str(message) {
+        headers.insert("grpc-message", message);
+    }
+    response
+}
+
+/// A body held in memory, with the trailers of a gRPC response
+struct Buffered {
+    data: Option<Bytes>,
+    trailers: Option<HeaderMap>,
+}
+
+impl Buffered {
+    fn new(data: Bytes) -> Self {
+        Self { data: (!data.is_empty()).then_some(data), trailers: None }
+    }
+}
+
+impl Body for Buffered {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        let this = self.get_mut();
+        if let Some(data) = this.data.take() {
+            return Poll::Ready(Some(Ok(Frame::data(data))));
+        }
+        Poll::Ready(this.trailers.take().map(|trailers| Ok(Frame::trailers(trailers))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.data.is_none() && self.trailers.is_none()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+
+    fn run(test: impl std::future::Future<Output = ()>) {
+        tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(test);
+    }
+
+    /// A gRPC body of one message
+    fn message(flag: u8, message: &[u8]) -> Vec<u8> {
+        let mut body = vec![flag];
+        body.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        body.extend_from_slice(message);
+        body
+    }
+
+    /// A body yielding `frames` one poll at a time
+    struct Frames(VecDeque<Result<Frame<Bytes>, ()>>);
+
+    impl Body for Frames {
+        type Data = Bytes;
+        type Error = ();
+
+        fn poll_frame(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+        ) -> Poll<Option<Result<Frame<Bytes>, ()>>> {
+            Poll::Ready(self.get_mut().0.pop_front())
+        }
+    }
+
+    /// Node answering every call with `body`, marked gzip-encoded when `gzipped`
+    async fn fake_node(body: Vec<u8>, gzipped: bool) -> Endpoint {
+        let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let body = Bytes::from(body.clone());
+                let service = hyper::service::service_fn(move |_| {
+                    let mut trailers = HeaderMap::new();
+                    trailers.insert("grpc-status", HeaderValue::from(0));
+                    let data = Some(body.clone());
+                    let mut response = Response::new(Buffered { data, trailers: Some(trailers) });
+                    let headers = response.headers_mut();
+                    headers.insert("content-type", HeaderValue::from_static("application/grpc"));
+                    if gzipped {
+                        headers.insert("grpc-encoding", HeaderValue::from_static("gzip"));
+                    }
+                    async move { Ok::<_, Infallible>(response) }
+                });
+                tokio::spawn(
+                    hyper::server::conn::http2::Builder::new(TokioExecutor::new())
+                        .serve_connection(TokioIo::new(stream), service),
+                );
+            }
+        });
+        Endpoint::new("http".into(), address.ip().to_string(), Some(address.port()))
+    }
+
+    /// Forward one call to `node` with a response limit of `limit`
+    async fn call(node: Endpoint, limit: usize) -> (Response<Buffered>, TrafficCount) {
+        let forwarder = Forwarder {
+            node,
+            timeout_ms: 5_000,
+            compression: MessageCompression::Gzip,
+            max_message_bytes: limit,
+            channel: Mutex::new(None),
+            node_accepts_gzip: AtomicBool::new(false),
+            traffic: Traffic::default(),
+        };
+        let request = Request::builder()
+            .uri("/rpc.Api/Status")
+            .header("content-type", "application/grpc")
+            .body(Buffered::new(Bytes::from(message(0, b"status"))))
+            .unwrap();
+        let response = forwarder.forward(request).await;
+        (response, forwarder.traffic.count())
+    }
+
+    fn grpc_status(response: &Response<Buffered>) -> Option<&str> {
+        response.headers().get("grpc-status").map(|status| status.to_str().unwrap())
+    }
+
+    #[test]
+    fn recode_rewrites_each_message_with_its_prefix() {
+        let mut body = message(0, b"first");
+        body.extend(message(1, b"second"));
+
+        let flags = Mutex::new(Vec::new());
+        let recoded = recode(&body, true, |message, flagged| {
+            flags.lock().unwrap().push(flagged);
+            Ok(Some(message.to_ascii_uppercase()))
+        });
+        let mut expected = message(1, b"FIRST");
+        expected.extend(message(1, b"SECOND"));
+        assert_eq!(recoded.ok().as_deref(), Some(&expected[..]));
+        assert_eq!(*flags.lock().unwrap(), [false, true]);
+    }
+
+    #[test]
+    fn recode_refuses_malformed_and_oversized_messages() {
+        let keep = |message: &[u8], _| Ok(Some(message.to_vec()));
+        let body = message(0, b"message");
+
+        // A prefix cut short, and a length past the end of the body
+        assert!(matches!(recode(&body[..3], false, keep), Err(RecodeError::Malformed)));
+        let cut = &body[..body.len() - 1];
+        assert!(matches!(recode(cut, false, keep), Err(RecodeError::Malformed)));
+        assert!(matches!(recode(&[], false, keep).as_deref(), Ok([])));
+
+        let refuse = |_: &[u8], _| Ok(None);
+        assert!(matches!(recode(&body, false, refuse), Err(RecodeError::TooLarge)));
+    }
+
+    #[test]
+    fn gunzip_stops_one_byte_past_the_limit() {
+        let original = vec![7u8; 4096];
+        let compressed = gzip(&original).ok().unwrap();
+        assert!(compressed.len() < 1024);
+
+        assert_eq!(gunzip(&compressed, 4096).ok(), Some(original));
+        assert_eq!(gunzip(&compressed, 1024).ok().map(|out| out.len()), Some(1025));
+        assert!(matches!(gunzip(b"not gzip", 1024), Err(RecodeError::Malformed)));
+    }
+
+    #[test]
+    fn read_body_keeps_messages_and_trailers_within_the_limit() {
+        run(async {
+            let body = message(0, &[1; 1024]);
+            let mut trailers = HeaderMap::new();
+            trailers.insert("grpc-status", HeaderValue::from(0));
+            let frames = Frames(VecDeque::from([
+                Ok(Frame::data(Bytes::copy_from_slice(&body[..3]))),
+                Ok(Frame::data(Bytes::copy_from_slice(&body[3..]))),
+                Ok(Frame::trailers(trailers)),
+            ]));
+
+            let Ok((read, trailers)) = read_body(frames, 1024).await else {
+                panic!("body within the limit refused");
+            };
+            assert_eq!(read, body);
+            assert!(trailers.is_some_and(|trailers| trailers.contains_key("grpc-status")));
+        });
+    }
+
+    #[test]
+    fn read_body_refuses_a_declared_length_before_reading_the_message() {
+        run(async {
+            // The error frame after the prefix is never reached
+            let prefix = Bytes::from(vec![0, 0, 0, 0x10, 0]);
+            let frames = Frames(VecDeque::from([Ok(Frame::data(prefix)), Err(())]));
+            assert!(matches!(read_body(frames, 1024).await, Err(ReadError::TooLarge)));
+
+            // Two messages within the limit each, but not together
+            let mut body = message(0, &[1; 600]);
+            body.extend(message(0, &[2; 600]));
+            let frames = Frames(VecDeque::from([Ok(Frame::data(Bytes::from(body))), Err(())]));
+            assert!(matches!(read_body(frames, 1024).await, Err(ReadError::TooLarge)));
+        });
+    }
+
+    #[test]
+    fn forward_decompresses_gzip_responses_and_counts_wire_bytes() {
+        run(async {
+            let original = vec![3u8; 2048];
+            let wire = message(1, &gzip(&original).ok().unwrap());
+            let node = fake_node(wire.clone(), true).await;
+
+            let (mut response, traffic) = call(node, DEFAULT_MAX_MESSAGE_BYTES).await;
+            assert_eq!(grpc_status(&response), None);
+            assert!(!response.headers().contains_key("grpc-encoding"));
+            assert_eq!(response.body_mut().data.take(), Some(Bytes::from(message(0, &original))));
+            assert_eq!(traffic.sent, message(0, b"status").len() as u64);
+            assert_eq!(traffic.received, wire.len() as u64);
+        });
+    }
+
+    #[test]
+    fn forward_answers_resource_exhausted_for_messages_over_the_limit() {
+        run(async {
+            // Over the limit on the wire
+            let node = fake_node(message(0, &[1; 2048]), false).await;
+            let (response, _) = call(node, 1024).await;
+            assert_eq!(grpc_status(&response), Some("8"));
+
+            // Within the limit on the wire, over it once decompressed
+            let node = fake_node(message(1, &gzip(&[1; 2048]).ok().unwrap()), true).await;
+            let (response, _) = call(node, 1024).await;
+            assert_eq!(grpc_status(&response), Some("8"));
+        });
+    }
+
+    #[test]
+    fn start_falls_back_to_the_node_when_the_port_cannot_be_opened() {
+        let node = Endpoint::new("https".into(), "node.example".into(), Some(443));
+        let refused = Err(std::io::Error::other("no port"));
+        let proxy = NodeProxy::serve(node.clone(), 5_000, MessageCompression::Gzip, 1024, refused);
+        assert_eq!(proxy.endpoint().to_string(), node.to_string());
+        assert!(proxy.server.is_none());
+
+        let proxy = NodeProxy::start(node, 5_000, MessageCompression::Gzip, 1024);
+        assert_eq!(proxy.endpoint().host(), "127.0.0.1");
+        assert!(proxy.server.is_some());
+    }
+}
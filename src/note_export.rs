@@ -0,0 +1,106 @@
+//! Note export
+//!
+//! Serializes an output note of this store as a NoteFile the app can share out of band
+//! (AirDrop, QR code), like the miden CLI's `export`. The recipient imports the file
+//! with `wc_miden_import_note`. The three export types trade size for what the
+//! recipient must do:
+//!
+//! - `id`: only the note ID; the recipient fetches the note from the node, so this only
+//!   works for public notes
+//! - `full`: the note with its inclusion proof, usable right away; the note must be
+//!   committed
+//! - `partial`: the note details, tag and creation block; the recipient finds the
+//!   inclusion proof when it syncs. Works for private notes that are not committed yet
+
+use std::os::raw::c_char;
+
+use miden_objects::{
+    note::{Note, NoteDetails, NoteFile, NoteId},
+    utils::Serializable,
+};
+
+use crate::{
+    get_handle, last_error, parse_required_str, request_blocking, types::WcNoteExportType,
+    write_out_buffer, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE, ERR_LOOKUP,
+    ERR_NOTE_OP,
+};
+
+pub(crate) async fn export_note_impl(
+    context: &MidenContext,
+    note_id: NoteId,
+    export_type: WcNoteExportType,
+) -> Result<Vec<u8>, i32> {
+    let record = context.client.get_output_note(note_id).await
+        .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?
+        .ok_or(ERR_LOOKUP)?;
+
+    let file = match export_type {
+        WcNoteExportType::Id => NoteFile::NoteId(note_id),
+        WcNoteExportType::Full => {
+            // Notes that are not committed yet have no proof
+            let proof = record.inclusion_proof().ok_or(ERR_NOTE_OP)?.clone();
+            let note = Note::try_from(record).map_err(|e| last_error::detail(ERR_NOTE_OP, e))?;
+            NoteFile::NoteWithProof(note, proof)
+        }
+        WcNoteExportType::Partial => {
+            let after_block_num = record.expected_height();
+            let tag = record.metadata().tag();
+            let details =
+                NoteDetails::try_from(record).map_err(|e| last_error::detail(ERR_NOTE_OP, e))?;
+            NoteFile::NoteDetails { details, after_block_num, tag: Some(tag) }
+        }
+    };
+    Ok(file.to_bytes())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Export an output note as a serialized NoteFile (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `note_id_hex`: Output note ID (C string)
+/// - `export_type`: `WcNoteExportType` value (0 = id, 1 = full, 2 = partial)
+/// - `bytes_out` / `bytes_out_len`: Output buffer for the NoteFile
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or export type
+/// - -2: Invalid handle or worker closed
+/// - -4: Invalid note ID, a full export of an uncommitted note, or a note whose
+///   recipient is unknown to this store
+/// - -5: Note not found among the notes sent from this store
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_export_note(
+    handle: MidenHandle,
+    note_id_hex: *const c_char,
+    export_type: u32,
+    bytes_out: *mut u8,
+    bytes_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let export_type = match WcNoteExportType::try_from(export_type) {
+        Ok(export_type) => export_type,
+        Err(code) => return code,
+    };
+    let note_id = match parse_required_str(note_id_hex) {
+        Ok(s) => match NoteId::try_from_hex(s) {
+            Ok(id) => id,
+            Err(_) => return ERR_NOTE_OP,
+        },
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::ExportNote { note_id, export_type, reply };
+    match request_blocking(&worker, request) {
+        Ok(bytes) => write_out_buffer(&bytes, bytes_out, bytes_out_len),
+        Err(code) => code,
+    }
+}
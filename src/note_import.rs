@@ -0,0 +1,176 @@
+//! Note import
+//!
+//! A NoteFile received out of band (AirDrop, QR code, see note_export.rs) is added to the
+//! store as an input note. A note with an inclusion proof is verified against the chain
+//! and can be consumed right away; one without is expected and becomes consumable once a
+//! sync finds it (or consumed unauthenticated before that).
+//!
+//! Importing dozens of NoteFiles one call at a time is slow and leaves the caller to
+//! piece together partial failures. A batch is decoded up front and each item is imported
+//! on its own. The client imports one note per store transaction, so a failure in the
+//! middle of a batch cannot be rolled back and an all-or-nothing import is not offered:
+//! the report tells exactly which items were applied.
+
+use miden_objects::{note::NoteFile, utils::Deserializable};
+
+use crate::{
+    get_handle, last_error, request_blocking, write_out_buffer, MidenContext, MidenHandle,
+    Request, ERR_INVALID_HANDLE, ERR_INVALID_PARAM, ERR_NOTE_OP,
+};
+
+/// Maximum number of notes in one batch
+pub const NOTE_IMPORT_MAX_BATCH: usize = 256;
+
+/// Decode a serialized NoteFile
+fn decode_note_file(bytes: &[u8]) -> Result<NoteFile, i32> {
+    NoteFile::read_from_bytes(bytes).map_err(|_| ERR_INVALID_PARAM)
+}
+
+pub(crate) async fn import_note_impl(
+    context: &mut MidenContext,
+    file: NoteFile,
+) -> Result<String, i32> {
+    let note_id = context.client.import_note(file).await
+        .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?;
+    Ok(note_id.to_hex())
+}
+
+pub(crate) async fn import_notes_batch_impl(
+    context: &mut MidenContext,
+    files: Vec<Result<NoteFile, i32>>,
+) -> Result<String, i32> {
+    let mut results = Vec::with_capacity(files.len());
+    let mut imported = 0usize;
+
+    for (index, file) in files.into_iter().enumerate() {
+        let result = match file {
+            Ok(file) => {
+                context.client.import_note(file).await
+                    .map_err(|_| ERR_NOTE_OP)
+            }
+            Err(code) => Err(code),
+        };
+
+        results.push(match result {
+            Ok(note_id) => {
+                imported += 1;
+                serde_json::json!({ "index": index, "ok": true, "note_id": note_id.to_hex() })
+            }
+            Err(code) => serde_json::json!({ "index": index, "ok": false, "error": code }),
+        });
+    }
+
+    Ok(serde_json::json!({
+        "imported": imported,
+        "failed": results.len() - imported,
+        "results": results,
+    })
+    .to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Import a serialized NoteFile as an input note (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `bytes` / `len`: Serialized NoteFile (e.g. from `wc_miden_export_note`)
+/// - `note_id_out` / `note_id_out_len`: Output buffer for the note ID (hex)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or not a NoteFile
+/// - -2: Invalid handle or worker closed
+/// - -4: Import failed (unknown note ID, invalid inclusion proof, store error)
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_import_note(
+    handle: MidenHandle,
+    bytes: *const u8,
+    len: usize,
+    note_id_out: *mut u8,
+    note_id_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if bytes.is_null() || len == 0 {
+        return ERR_INVALID_PARAM;
+    }
+    let file = match decode_note_file(unsafe { std::slice::from_raw_parts(bytes, len) }) {
+        Ok(file) => file,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::ImportNote { file, reply }) {
+        Ok(note_id) => write_out_buffer(&note_id, note_id_out, note_id_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Import a batch of serialized NoteFiles (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `blobs` / `blob_lens` / `count`: Arrays of pointers to serialized NoteFiles and their lengths
+/// - `atomic`: Must be false; the store cannot import a batch all-or-nothing (see the
+///   module docs)
+/// - `report_out` / `report_out_len`: Output buffer for the JSON report
+///
+/// # Output JSON
+/// `{"imported":2,"failed":1,"results":[{"index":0,"ok":true,"note_id":"0x.."},
+///   {"index":1,"ok":false,"error":-1}]}`
+///
+/// # Returns
+/// - 0: Batch processed (check the report for per-item failures)
+/// - -1: Invalid parameters, batch too large or `atomic` set
+/// - -2: Invalid handle or worker closed
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_import_notes_batch(
+    handle: MidenHandle,
+    blobs: *const *const u8,
+    blob_lens: *const usize,
+    count: usize,
+    atomic: bool,
+    report_out: *mut u8,
+    report_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    // All-or-nothing imports are not possible (see the module docs)
+    if atomic {
+        return ERR_INVALID_PARAM;
+    }
+    if blobs.is_null() || blob_lens.is_null() || count == 0 || count > NOTE_IMPORT_MAX_BATCH {
+        return ERR_INVALID_PARAM;
+    }
+    if report_out.is_null() || report_out_len.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    let ptrs = unsafe { std::slice::from_raw_parts(blobs, count) };
+    let lens = unsafe { std::slice::from_raw_parts(blob_lens, count) };
+    let files: Vec<Result<NoteFile, i32>> = ptrs
+        .iter()
+        .zip(lens)
+        .map(|(ptr, len)| {
+            if ptr.is_null() {
+                return Err(ERR_INVALID_PARAM);
+            }
+            decode_note_file(unsafe { std::slice::from_raw_parts(*ptr, *len) })
+        })
+        .collect();
+
+    match request_blocking(&worker, |reply| Request::ImportNotesBatch { files, reply }) {
+        Ok(report) => write_out_buffer(&report, report_out, report_out_len),
+        Err(code) => code,
+    }
+}
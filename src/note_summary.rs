@@ -0,0 +1,311 @@
+//! Note previews for share sheets and widgets
+//!
+//! Renders a note into display-ready strings, so share extensions and widgets do not
+//! need their own copy of the formatting rules:
+//!
+//! - each fungible asset as `<amount> <symbol>`, scaled by the token's decimals and
+//!   grouped by the locale's separators (token metadata comes from the faucet account:
+//!   the local store first, then the node for public faucets; unknown tokens show the
+//!   raw amount and a shortened faucet ID)
+//! - the sender as a shortened account ID, flagged when it is one of this store's accounts
+//! - the reclaim height of P2IDE notes (escrows, stream payouts) as the expiry
+//!
+//! Miden notes carry no memo, so none is rendered. Locales fall back to their language
+//! and then to English, like the error message catalogs.
+
+use std::os::raw::c_char;
+
+use miden_client::rpc::NodeRpcClient;
+use miden_lib::{account::faucets::BasicFungibleFaucet, note::WellKnownNote};
+use miden_objects::{
+    account::AccountId,
+    asset::Asset,
+    note::{NoteAssets, NoteId, NoteRecipient},
+};
+
+use crate::{
+    get_handle, parse_optional_str, parse_required_str, request_blocking, write_out_buffer,
+    MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE, ERR_LOOKUP, ERR_NOTE_OP,
+};
+
+/// Separators and sentence templates of one language (`{}` is the placeholder)
+struct LocaleFormat {
+    decimal: &'static str,
+    group: &'static str,
+    from: &'static str,
+    expiry: &'static str,
+}
+
+const EN: LocaleFormat = LocaleFormat {
+    decimal: ".",
+    group: ",",
+    from: "From {}",
+    expiry: "Refundable to the sender after block {}",
+};
+
+const FORMATS: [(&str, LocaleFormat); 6] = [
+    ("en", EN),
+    ("es", LocaleFormat {
+        decimal: ",",
+        group: ".",
+        from: "De {}",
+        expiry: "Reembolsable al remitente después del bloque {}",
+    }),
+    ("de", LocaleFormat {
+        decimal: ",",
+        group: ".",
+        from: "Von {}",
+        expiry: "Nach Block {} an den Absender erstattbar",
+    }),
+    ("fr", LocaleFormat {
+        decimal: ",",
+        group: "\u{202f}",
+        from: "De {}",
+        expiry: "Remboursable à l'expéditeur après le bloc {}",
+    }),
+    ("zh", LocaleFormat {
+        decimal: ".",
+        group: ",",
+        from: "来自 {}",
+        expiry: "区块 {} 之后可退还给发送方",
+    }),
+    ("ja", LocaleFormat {
+        decimal: ".",
+        group: ",",
+        from: "送信元: {}",
+        expiry: "ブロック {} 以降は送信者に返金可能",
+    }),
+];
+
+fn format_for(locale: Option<&str>) -> &'static LocaleFormat {
+    let language = locale
+        .and_then(|l| l.split(['-', '_']).next())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    FORMATS
+        .iter()
+        .find(|(lang, _)| *lang == language)
+        .map(|(_, format)| format)
+        .unwrap_or(&EN)
+}
+
+/// Integer digits with group separators every three digits
+fn group_digits(digits: &str, separator: &str) -> String {
+    let mut grouped = String::with_capacity(digits.len() * 2);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// `amount` base units scaled by `decimals`, without trailing fractional zeros
+fn format_amount(amount: u64, decimals: u8, format: &LocaleFormat) -> String {
+    let Some(unit) = 10u64.checked_pow(u32::from(decimals)) else {
+        return group_digits(&amount.to_string(), format.group);
+    };
+
+    let integer = group_digits(&(amount / unit).to_string(), format.group);
+    let fraction = format!("{:0width$}", amount % unit, width = usize::from(decimals));
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        integer
+    } else {
+        format!("{}{}{}", integer, format.decimal, fraction)
+    }
+}
+
+/// `0x1234ab…cdef`
+fn short_id(account_id: AccountId) -> String {
+    let hex = account_id.to_hex();
+    format!("{}…{}", &hex[..8], &hex[hex.len() - 4..])
+}
+
+/// Faucet component of a fungible faucet account, from the local store or else the node
+/// (None when unknown or not a basic faucet)
+pub(crate) async fn fungible_faucet(
+    context: &MidenContext,
+    faucet_id: AccountId,
+) -> Option<BasicFungibleFaucet> {
+    let account = match context.client.get_account(faucet_id).await {
+        Ok(Some(record)) => record.account().clone(),
+        _ if faucet_id.is_public() => {
+            context.rpc.get_account_details(faucet_id).await.ok()?.account()?.clone()
+        }
+        _ => return None,
+    };
+
+    BasicFungibleFaucet::try_from(&account).ok()
+}
+
+/// Symbol and decimals of a fungible faucet (None when unknown or not a basic faucet)
+pub(crate) async fn token_info(
+    context: &MidenContext,
+    faucet_id: AccountId,
+) -> Option<(String, u8)> {
+    let faucet = fungible_faucet(context, faucet_id).await?;
+    Some((faucet.symbol().to_string().ok()?, faucet.decimals()))
+}
+
+/// Target account of a P2ID or P2IDE note (inputs start with `[target suffix, target prefix]`)
+pub(crate) fn target_account(recipient: &NoteRecipient) -> Option<AccountId> {
+    let root = recipient.script().root();
+    if root != WellKnownNote::P2ID.script_root() && root != WellKnownNote::P2IDE.script_root() {
+        return None;
+    }
+    match recipient.inputs().values() {
+        [suffix, prefix, ..] => AccountId::try_from([*prefix, *suffix]).ok(),
+        _ => None,
+    }
+}
+
+/// Reclaim height of a P2IDE note (inputs `[target(2), reclaim, timelock]`, 0 = none)
+pub(crate) fn reclaim_height(recipient: &NoteRecipient) -> Option<u64> {
+    if recipient.script().root() != WellKnownNote::P2IDE.script_root() {
+        return None;
+    }
+    recipient.inputs().values().get(2)
+        .map(|height| height.as_int())
+        .filter(|height| *height > 0)
+}
+
+pub(crate) async fn render_note_summary_impl(
+    context: &MidenContext,
+    note_id: NoteId,
+    locale: Option<String>,
+) -> Result<String, i32> {
+    // Received (or imported) notes first, then notes sent from this store
+    let (assets, sender, recipient): (NoteAssets, Option<AccountId>, Option<NoteRecipient>) =
+        match context.client.get_input_note(note_id).await.map_err(|_| ERR_NOTE_OP)? {
+            Some(record) => (
+                record.assets().clone(),
+                record.metadata().map(|metadata| metadata.sender()),
+                Some(NoteRecipient::new(
+                    record.details().serial_num(),
+                    record.details().script().clone(),
+                    record.details().inputs().clone(),
+                )),
+            ),
+            None => {
+                let record = context.client.get_output_note(note_id).await
+                    .map_err(|_| ERR_NOTE_OP)?
+                    .ok_or(ERR_LOOKUP)?;
+                (
+                    record.assets().clone(),
+                    Some(record.metadata().sender()),
+                    record.recipient().cloned(),
+                )
+            }
+        };
+
+    let format = format_for(locale.as_deref());
+
+    let mut assets_json = Vec::new();
+    let mut amount_texts = Vec::new();
+    for asset in assets.iter() {
+        let Asset::Fungible(fungible) = asset else {
+            continue;
+        };
+        let faucet_id = fungible.faucet_id();
+        let info = token_info(context, faucet_id).await;
+        let formatted = match &info {
+            Some((symbol, decimals)) => {
+                format!("{} {}", format_amount(fungible.amount(), *decimals, format), symbol)
+            }
+            None => format!(
+                "{} {}",
+                group_digits(&fungible.amount().to_string(), format.group),
+                short_id(faucet_id)
+            ),
+        };
+
+        assets_json.push(serde_json::json!({
+            "faucet_id": faucet_id.to_hex(),
+            "amount": fungible.amount(),
+            "symbol": info.as_ref().map(|(symbol, _)| symbol.clone()),
+            "decimals": info.as_ref().map(|(_, decimals)| *decimals),
+            "formatted": formatted,
+        }));
+        amount_texts.push(formatted);
+    }
+
+    let sender_is_own = match sender {
+        Some(sender) => matches!(context.client.get_account(sender).await, Ok(Some(_))),
+        None => false,
+    };
+    let sender_label = sender.map(short_id);
+    let from_text = sender_label.as_ref().map(|label| format.from.replace("{}", label));
+    let expires_at_block = recipient.as_ref().and_then(reclaim_height);
+    let expiry_text = expires_at_block.map(|block| format.expiry.replace("{}", &block.to_string()));
+
+    Ok(serde_json::json!({
+        "note_id": note_id.to_hex(),
+        "assets": assets_json,
+        "amount_text": amount_texts.join(", "),
+        "sender": sender.map(|sender| sender.to_hex()),
+        "sender_label": sender_label,
+        "sender_is_own": sender_is_own,
+        "from_text": from_text,
+        "expires_at_block": expires_at_block,
+        "expiry_text": expiry_text,
+    })
+    .to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Render a note into display-ready strings (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `note_id_hex`: Input or output note ID (C string)
+/// - `locale`: Locale identifier such as `en`, `de_CH` or `zh-Hans` (NULL or unknown uses
+///   English)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"note_id","assets":[{"faucet_id","amount","symbol","decimals","formatted"}],
+///   "amount_text","sender","sender_label","sender_is_own","from_text","expires_at_block",
+///   "expiry_text"}` (`symbol`/`decimals` are null for unknown tokens; sender and expiry
+///   fields are null when not known)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -4: Invalid note ID or store error
+/// - -5: Note not found
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_render_note_summary(
+    handle: MidenHandle,
+    note_id_hex: *const c_char,
+    locale: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let note_id = match parse_required_str(note_id_hex) {
+        Ok(s) => match NoteId::try_from_hex(s) {
+            Ok(id) => id,
+            Err(_) => return ERR_NOTE_OP,
+        },
+        Err(code) => return code,
+    };
+    let locale = match parse_optional_str(locale) {
+        Ok(locale) => locale.map(str::to_string),
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::RenderNoteSummary { note_id, locale, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,409 @@
+//! Opaque object handles
+//!
+//! Detail views used to re-request and re-parse a JSON document for every field they
+//! show. `wc_account_open` / `wc_note_open` instead take one snapshot of an account or
+//! note on the worker and return an opaque object handle; the accessor functions read the
+//! snapshot on the calling thread without going through the queue, so they are cheap and
+//! safe to call from the main thread.
+//!
+//! A snapshot does not follow later syncs: open the object again to refresh it. Objects
+//! live in a process-wide table until `wc_object_free` (they outlive the client handle
+//! they were opened with). Like client handles, object handles are never 0 and a freed or
+//! unknown handle, or one of the wrong kind, is rejected with -2.
+
+use std::{
+    collections::HashMap,
+    os::raw::c_char,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use miden_objects::{
+    account::AccountId,
+    asset::Asset,
+    note::{NoteAssets, NoteId},
+};
+use once_cell::sync::Lazy;
+
+use crate::{
+    get_handle, input_notes, last_error, output_notes, parse_account_id, parse_required_str,
+    try_send_request, write_out_buffer, MidenContext, MidenHandle, MidenWorkerHandle, Request,
+    ERR_INVALID_HANDLE, ERR_INVALID_PARAM, ERR_LOOKUP, ERR_NOTE_OP, ERR_TIMEOUT, SYNC_TIMEOUT,
+};
+
+/// Opaque object handle (0 is never valid)
+pub type WcObject = u64;
+
+/// Snapshot of an account
+pub(crate) struct AccountObject {
+    id: AccountId,
+    nonce: u64,
+    commitment: String,
+    /// Fungible balances by faucet
+    balances: Vec<(AccountId, u64)>,
+}
+
+/// Snapshot of an input note (received) or output note (sent)
+pub(crate) struct NoteObject {
+    id: NoteId,
+    sender: Option<AccountId>,
+    state: &'static str,
+    block_num: Option<u32>,
+    /// Fungible amounts by faucet
+    amounts: Vec<(AccountId, u64)>,
+}
+
+pub(crate) enum Object {
+    Account(AccountObject),
+    Note(NoteObject),
+}
+
+/// Open objects by handle
+static OBJECTS: Lazy<Mutex<HashMap<WcObject, Object>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Next object handle (0 is never handed out)
+static NEXT_OBJECT: AtomicU64 = AtomicU64::new(1);
+
+fn fungible<'a>(assets: impl Iterator<Item = &'a Asset>) -> Vec<(AccountId, u64)> {
+    assets
+        .filter_map(|asset| match asset {
+            Asset::Fungible(fungible) => Some((fungible.faucet_id(), fungible.amount())),
+            Asset::NonFungible(_) => None,
+        })
+        .collect()
+}
+
+fn total_of(amounts: &[(AccountId, u64)], faucet_id: AccountId) -> u64 {
+    amounts.iter().filter(|(faucet, _)| *faucet == faucet_id).map(|(_, amount)| amount).sum()
+}
+
+pub(crate) async fn open_account_impl(
+    context: &MidenContext,
+    account_id: AccountId,
+) -> Result<Object, i32> {
+    let record = context.client.get_account(account_id).await
+        .map_err(|e| last_error::detail(ERR_LOOKUP, e))?
+        .ok_or(ERR_LOOKUP)?;
+    let account = record.account();
+
+    Ok(Object::Account(AccountObject {
+        id: account_id,
+        nonce: account.nonce().as_int(),
+        commitment: account.commitment().to_hex(),
+        balances: fungible(account.vault().assets().collect::<Vec<_>>().iter()),
+    }))
+}
+
+pub(crate) async fn open_note_impl(context: &MidenContext, note_id: NoteId) -> Result<Object, i32> {
+    let block_of = |proof: Option<&miden_objects::note::NoteInclusionProof>| {
+        proof.map(|proof| proof.location().block_num().as_u32())
+    };
+    let amounts = |assets: &NoteAssets| fungible(assets.iter());
+
+    // Received (or imported) notes first, then notes sent from this store
+    if let Some(record) = context.client.get_input_note(note_id).await
+        .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?
+    {
+        return Ok(Object::Note(NoteObject {
+            id: note_id,
+            sender: record.metadata().map(|metadata| metadata.sender()),
+            state: input_notes::state_str(&record),
+            block_num: block_of(record.inclusion_proof()),
+            amounts: amounts(record.assets()),
+        }));
+    }
+
+    let record = context.client.get_output_note(note_id).await
+        .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?
+        .ok_or(ERR_LOOKUP)?;
+    Ok(Object::Note(NoteObject {
+        id: note_id,
+        sender: Some(record.metadata().sender()),
+        state: output_notes::state_str(record.state()),
+        block_num: block_of(record.inclusion_proof()),
+        amounts: amounts(record.assets()),
+    }))
+}
+
+/// Take a snapshot on the worker and register it
+fn open_blocking(
+    worker: &MidenWorkerHandle,
+    build: impl FnOnce(std::sync::mpsc::Sender<Result<Object, i32>>) -> Request,
+    obj_out: *mut WcObject,
+) -> i32 {
+    let (tx, rx) = std::sync::mpsc::channel();
+    if let Err(code) = try_send_request(worker, build(tx)) {
+        return code;
+    }
+
+    let object = match rx.recv_timeout(SYNC_TIMEOUT) {
+        Ok(Ok(object)) => object,
+        Ok(Err(code)) => return code,
+        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => return ERR_TIMEOUT,
+        Err(_) => return ERR_INVALID_HANDLE,
+    };
+
+    let id = NEXT_OBJECT.fetch_add(1, Ordering::Relaxed);
+    OBJECTS.lock().unwrap().insert(id, object);
+    unsafe { *obj_out = id };
+    0
+}
+
+/// Run an accessor on an account object
+fn with_account<T>(obj: WcObject, read: impl FnOnce(&AccountObject) -> T) -> Result<T, i32> {
+    match OBJECTS.lock().unwrap().get(&obj) {
+        Some(Object::Account(account)) => Ok(read(account)),
+        _ => Err(ERR_INVALID_HANDLE),
+    }
+}
+
+/// Run an accessor on a note object
+fn with_note<T>(obj: WcObject, read: impl FnOnce(&NoteObject) -> T) -> Result<T, i32> {
+    match OBJECTS.lock().unwrap().get(&obj) {
+        Some(Object::Note(note)) => Ok(read(note)),
+        _ => Err(ERR_INVALID_HANDLE),
+    }
+}
+
+/// Write a scalar accessor result
+fn write_scalar<T>(result: Result<T, i32>, out: *mut T) -> i32 {
+    if out.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+    match result {
+        Ok(value) => {
+            unsafe { *out = value };
+            0
+        }
+        Err(code) => code,
+    }
+}
+
+/// Write a string accessor result
+fn write_string(result: Result<String, i32>, out: *mut u8, out_len: *mut usize) -> i32 {
+    match result {
+        Ok(value) => write_out_buffer(value.as_bytes(), out, out_len),
+        Err(code) => code,
+    }
+}
+
+fn parse_faucet_id(faucet_id_hex: *const c_char) -> Result<AccountId, i32> {
+    parse_account_id(faucet_id_hex).map(|(faucet_id, _)| faucet_id)
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Open a snapshot of an account as an object handle (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_id_hex`: Account ID (C string)
+/// - `obj_out`: Receives the object handle; release it with `wc_object_free`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID
+/// - -5: Account not in the store
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_account_open(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    obj_out: *mut WcObject,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+    if obj_out.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    let account_id = match parse_account_id(account_id_hex) {
+        Ok((account_id, _)) => account_id,
+        Err(code) => return code,
+    };
+
+    let request = |response_tx| Request::OpenAccountObject { account_id, response_tx };
+    open_blocking(&worker, request, obj_out)
+}
+
+/// Open a snapshot of a note (received or sent) as an object handle (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `note_id_hex`: Note ID (C string)
+/// - `obj_out`: Receives the object handle; release it with `wc_object_free`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -4: Invalid note ID or note query failed
+/// - -5: Note not in the store
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_note_open(
+    handle: MidenHandle,
+    note_id_hex: *const c_char,
+    obj_out: *mut WcObject,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+    if obj_out.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    let note_id = match parse_required_str(note_id_hex) {
+        Ok(s) => match NoteId::try_from_hex(s) {
+            Ok(id) => id,
+            Err(_) => return ERR_NOTE_OP,
+        },
+        Err(code) => return code,
+    };
+
+    let request = |response_tx| Request::OpenNoteObject { note_id, response_tx };
+    open_blocking(&worker, request, obj_out)
+}
+
+/// Release an object handle
+///
+/// Safe to call from any thread. Freeing 0, an unknown or an already freed handle is a no-op.
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_object_free(obj: WcObject) {
+    OBJECTS.lock().unwrap().remove(&obj);
+}
+
+/// Account ID (hex) of an account object
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Not an open account object
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_account_id(obj: WcObject, out: *mut u8, out_len: *mut usize) -> i32 {
+    write_string(with_account(obj, |account| account.id.to_hex()), out, out_len)
+}
+
+/// Nonce of an account object
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Not an open account object
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_account_nonce(obj: WcObject, nonce_out: *mut u64) -> i32 {
+    write_scalar(with_account(obj, |account| account.nonce), nonce_out)
+}
+
+/// State commitment (hex) of an account object
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Not an open account object
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_account_commitment(obj: WcObject, out: *mut u8, out_len: *mut usize) -> i32 {
+    write_string(with_account(obj, |account| account.commitment.clone()), out, out_len)
+}
+
+/// Balance of one fungible asset in an account object (0 when the vault has none)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Not an open account object
+/// - -3: Invalid faucet ID
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_account_balance(
+    obj: WcObject,
+    faucet_id_hex: *const c_char,
+    balance_out: *mut u64,
+) -> i32 {
+    let faucet_id = match parse_faucet_id(faucet_id_hex) {
+        Ok(faucet_id) => faucet_id,
+        Err(code) => return code,
+    };
+    write_scalar(with_account(obj, |account| total_of(&account.balances, faucet_id)), balance_out)
+}
+
+/// Note ID (hex) of a note object
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Not an open note object
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_note_id(obj: WcObject, out: *mut u8, out_len: *mut usize) -> i32 {
+    write_string(with_note(obj, |note| note.id.to_hex()), out, out_len)
+}
+
+/// Sender account ID (hex) of a note object
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Not an open note object
+/// - -5: Sender unknown (a received note whose metadata has not been synced yet)
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_note_sender(obj: WcObject, out: *mut u8, out_len: *mut usize) -> i32 {
+    let sender = with_note(obj, |note| note.sender).and_then(|sender| sender.ok_or(ERR_LOOKUP));
+    write_string(sender.map(|sender| sender.to_hex()), out, out_len)
+}
+
+/// State of a note object: `expected`, `committed`, `processing` or `consumed`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Not an open note object
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_note_state(obj: WcObject, out: *mut u8, out_len: *mut usize) -> i32 {
+    write_string(with_note(obj, |note| note.state.to_string()), out, out_len)
+}
+
+/// Inclusion block of a note object
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Not an open note object
+/// - -5: Not committed (as of the snapshot)
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_note_block_num(obj: WcObject, block_num_out: *mut u32) -> i32 {
+    let block_num = with_note(obj, |note| note.block_num).and_then(|block| block.ok_or(ERR_LOOKUP));
+    write_scalar(block_num, block_num_out)
+}
+
+/// Amount of one fungible asset carried by a note object (0 when it carries none)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Not an open note object
+/// - -3: Invalid faucet ID
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_note_amount(
+    obj: WcObject,
+    faucet_id_hex: *const c_char,
+    amount_out: *mut u64,
+) -> i32 {
+    let faucet_id = match parse_faucet_id(faucet_id_hex) {
+        Ok(faucet_id) => faucet_id,
+        Err(code) => return code,
+    };
+    write_scalar(with_note(obj, |note| total_of(&note.amounts, faucet_id)), amount_out)
+}
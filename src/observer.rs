@@ -0,0 +1,285 @@
+//! Store change observer
+//!
+//! Reactive UIs (e.g. Combine publishers) used to poll the JSON queries and diff the
+//! results. `wc_miden_observe` registers one callback per handle that receives a change
+//! event for every row written to the store, in the domains selected by its flags:
+//!
+//! - accounts: `{"type":"account_inserted"|"account_updated","account_id","nonce",
+//!   "commitment"}`
+//! - input / output notes: `{"type":"note_inserted"|"note_updated"|"note_removed",
+//!   "kind":"input"|"output","note_id","state"}` (`state` as in the note listings; null
+//!   when removed)
+//! - transactions: `{"type":"tx_inserted"|"tx_status_changed"|"tx_removed","tx_id",
+//!   "status","previous_status"}` (`pending`, `committed` or `discarded`)
+//!
+//! `miden-client` has no store hooks, so the worker keeps a snapshot of the observed
+//! domains (IDs and states only) and compares it with the store after each request that
+//! may write to it (see `Request::is_read_only`), emitting the differences before the next
+//! request runs. Events are therefore batched per request: a sync reports all its notes at
+//! once, after it completes. Taking the snapshot scans the observed tables, so observe only
+//! the domains the UI shows. Writes made by another handle on the same store are picked up
+//! with the next write of this handle.
+
+use std::{collections::BTreeMap, ffi::c_void};
+
+use miden_client::store::{NoteFilter, TransactionFilter};
+
+use crate::{
+    diagnostics, events::WcEventCallback, get_handle, input_notes, json, last_error,
+    output_notes, request_blocking, tx_history::TxStatusKind, MidenContext, MidenHandle,
+    Request, ERR_ACCOUNT_OP, ERR_INVALID_HANDLE, ERR_INVALID_PARAM, ERR_LOOKUP, ERR_NOTE_OP,
+};
+
+/// Observe account inserts and updates
+pub const OBSERVE_ACCOUNTS: u32 = 1 << 0;
+/// Observe input note inserts, state changes and removals
+pub const OBSERVE_INPUT_NOTES: u32 = 1 << 1;
+/// Observe output note inserts, state changes and removals
+pub const OBSERVE_OUTPUT_NOTES: u32 = 1 << 2;
+/// Observe transaction inserts, status changes and removals
+pub const OBSERVE_TRANSACTIONS: u32 = 1 << 3;
+/// Observe every domain
+pub const OBSERVE_ALL: u32 =
+    OBSERVE_ACCOUNTS | OBSERVE_INPUT_NOTES | OBSERVE_OUTPUT_NOTES | OBSERVE_TRANSACTIONS;
+
+/// Callback registered by `wc_miden_observe` and the domains it observes
+#[derive(Clone, Copy)]
+pub(crate) struct Observer {
+    domains: u32,
+    callback: extern "C" fn(*mut c_void, *const u8, usize),
+    user_data: usize,
+}
+
+/// Observed rows by hex ID; accounts map to (nonce, commitment), notes and transactions to
+/// their state
+#[derive(Default)]
+struct Snapshot {
+    accounts: BTreeMap<String, (u64, String)>,
+    input_notes: BTreeMap<String, &'static str>,
+    output_notes: BTreeMap<String, &'static str>,
+    transactions: BTreeMap<String, &'static str>,
+}
+
+/// Observer of a handle and the snapshot its events are computed from (worker only)
+#[derive(Default)]
+pub(crate) struct StoreObserver {
+    observer: Option<Observer>,
+    snapshot: Snapshot,
+}
+
+enum Change<'a, V> {
+    Inserted(&'a V),
+    Updated(&'a V, &'a V),
+    Removed(&'a V),
+}
+
+/// Rows that differ between two snapshots of a domain, in ID order
+fn diff<'a, V: PartialEq>(
+    before: &'a BTreeMap<String, V>,
+    after: &'a BTreeMap<String, V>,
+) -> Vec<(&'a str, Change<'a, V>)> {
+    let mut changes: Vec<(&str, Change<V>)> = after
+        .iter()
+        .filter_map(|(id, new)| match before.get(id) {
+            None => Some((id.as_str(), Change::Inserted(new))),
+            Some(old) if old != new => Some((id.as_str(), Change::Updated(old, new))),
+            Some(_) => None,
+        })
+        .collect();
+    changes.extend(
+        before
+            .iter()
+            .filter(|(id, _)| !after.contains_key(*id))
+            .map(|(id, old)| (id.as_str(), Change::Removed(old))),
+    );
+    changes.sort_by_key(|(a, _)| *a);
+    changes
+}
+
+async fn take_snapshot(context: &MidenContext, domains: u32) -> Result<Snapshot, i32> {
+    let mut snapshot = Snapshot::default();
+
+    if domains & OBSERVE_ACCOUNTS != 0 {
+        let headers = context.client.get_account_headers().await
+            .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+        snapshot.accounts = headers
+            .iter()
+            .map(|(header, _)| {
+                let state = (header.nonce().as_int(), header.commitment().to_hex());
+                (header.id().to_hex(), state)
+            })
+            .collect();
+    }
+    if domains & OBSERVE_INPUT_NOTES != 0 {
+        let records = context.client.get_input_notes(NoteFilter::All).await
+            .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?;
+        snapshot.input_notes = records
+            .iter()
+            .map(|record| (record.id().to_hex(), input_notes::state_str(record)))
+            .collect();
+    }
+    if domains & OBSERVE_OUTPUT_NOTES != 0 {
+        let records = context.client.get_output_notes(NoteFilter::All).await
+            .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?;
+        snapshot.output_notes = records
+            .iter()
+            .map(|record| (record.id().to_hex(), output_notes::state_str(record.state())))
+            .collect();
+    }
+    if domains & OBSERVE_TRANSACTIONS != 0 {
+        let records = context.client.get_transactions(TransactionFilter::All).await
+            .map_err(|e| last_error::detail(ERR_LOOKUP, e))?;
+        snapshot.transactions = records
+            .iter()
+            .map(|record| (record.id.to_hex(), TxStatusKind::of(&record.status).as_str()))
+            .collect();
+    }
+
+    Ok(snapshot)
+}
+
+fn note_events(
+    kind: &str,
+    before: &BTreeMap<String, &'static str>,
+    after: &BTreeMap<String, &'static str>,
+) -> Vec<serde_json::Value> {
+    diff(before, after)
+        .into_iter()
+        .map(|(note_id, change)| {
+            let (event, state) = match change {
+                Change::Inserted(state) => ("note_inserted", Some(*state)),
+                Change::Updated(_, state) => ("note_updated", Some(*state)),
+                Change::Removed(_) => ("note_removed", None),
+            };
+            serde_json::json!({ "type": event, "kind": kind, "note_id": note_id, "state": state })
+        })
+        .collect()
+}
+
+/// Change events between two snapshots, by domain
+fn change_events(before: &Snapshot, after: &Snapshot) -> Vec<serde_json::Value> {
+    let mut events: Vec<serde_json::Value> = diff(&before.accounts, &after.accounts)
+        .into_iter()
+        .filter_map(|(account_id, change)| {
+            let (event, (nonce, commitment)) = match change {
+                Change::Inserted(state) => ("account_inserted", state),
+                Change::Updated(_, state) => ("account_updated", state),
+                // Accounts are never removed from the store
+                Change::Removed(_) => return None,
+            };
+            Some(serde_json::json!({
+                "type": event,
+                "account_id": account_id,
+                "nonce": nonce,
+                "commitment": commitment,
+            }))
+        })
+        .collect();
+
+    events.extend(note_events("input", &before.input_notes, &after.input_notes));
+    events.extend(note_events("output", &before.output_notes, &after.output_notes));
+
+    events.extend(diff(&before.transactions, &after.transactions).into_iter().map(
+        |(tx_id, change)| {
+            let (event, status, previous) = match change {
+                Change::Inserted(status) => ("tx_inserted", Some(*status), None),
+                Change::Updated(old, new) => ("tx_status_changed", Some(*new), Some(*old)),
+                Change::Removed(old) => ("tx_removed", None, Some(*old)),
+            };
+            serde_json::json!({
+                "type": event,
+                "tx_id": tx_id,
+                "status": status,
+                "previous_status": previous,
+            })
+        },
+    ));
+
+    events
+}
+
+pub(crate) async fn observe_impl(
+    context: &mut MidenContext,
+    observer: Option<Observer>,
+) -> Result<String, i32> {
+    // The baseline: later events report changes made after this point
+    let snapshot = match observer {
+        Some(observer) => take_snapshot(context, observer.domains).await?,
+        None => Snapshot::default(),
+    };
+    context.observer = StoreObserver { observer, snapshot };
+    Ok(String::new())
+}
+
+/// Report the store changes made by the last request (run after each request that may
+/// write to the store)
+pub(crate) async fn publish_changes(context: &mut MidenContext) {
+    let Some(observer) = context.observer.observer else {
+        return;
+    };
+    let snapshot = match take_snapshot(context, observer.domains).await {
+        Ok(snapshot) => snapshot,
+        // Keep the old snapshot: the changes are reported after the next write
+        Err(code) => {
+            diagnostics::log("observer", format!("store snapshot failed: {}", code));
+            return;
+        }
+    };
+
+    for event in change_events(&context.observer.snapshot, &snapshot) {
+        let bytes = json::prepare_output(event.to_string().as_bytes()).into_owned();
+        (observer.callback)(observer.user_data as *mut c_void, bytes.as_ptr(), bytes.len());
+    }
+    context.observer.snapshot = snapshot;
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Set (or clear) the store change observer of a handle (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Replaces any previous observer. The callback receives one change event per written row
+/// (see the module docs) after each request that changed the store, starting with the
+/// first write after this call. It runs on the worker thread and must return quickly;
+/// the JSON buffer is only valid during the call.
+///
+/// # Parameters
+/// - `domain_flags`: `OBSERVE_*` flags to observe (`OBSERVE_ALL` for everything); 0 clears
+///   the observer
+/// - `callback`: Change callback; NULL clears the observer
+/// - `user_data`: Passed back to the callback
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters (unknown flags)
+/// - -2: Invalid handle or worker closed
+/// - -3 / -4 / -5: The accounts, notes or transactions could not be read for the baseline
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_observe(
+    handle: MidenHandle,
+    domain_flags: u32,
+    callback: WcEventCallback,
+    user_data: *mut c_void,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if domain_flags & !OBSERVE_ALL != 0 {
+        return ERR_INVALID_PARAM;
+    }
+    let observer = callback.filter(|_| domain_flags != 0).map(|callback| Observer {
+        domains: domain_flags,
+        callback,
+        user_data: user_data as usize,
+    });
+
+    match request_blocking(&worker, |reply| Request::Observe { observer, reply }) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,126 @@
+//! Output notes
+//!
+//! Lists the notes created by this store's transactions (payments sent, minted notes,
+//! escrows) for an outgoing-payments view. A note is `expected` from its transaction's
+//! submission until the sync that sees it in a block (`committed`), and `consumed` once the
+//! sync sees its nullifier. Without an account filter, notes sent by archived accounts are
+//! left out, as in `wc_miden_get_input_notes`.
+//!
+//! Recipient details are only known for notes whose recipient this store created: a note
+//! built from a recipient digest alone reports the digest without a target account.
+
+use std::os::raw::c_char;
+
+use miden_client::store::{NoteFilter, OutputNoteRecord, OutputNoteState};
+use miden_objects::{account::AccountId, note::NoteType};
+
+use crate::{
+    get_handle, json, last_error, note_summary, parse_optional_account_id, request_blocking,
+    write_out_buffer, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE, ERR_NOTE_OP,
+};
+
+pub(crate) fn state_str(state: &OutputNoteState) -> &'static str {
+    match state {
+        OutputNoteState::ExpectedPartial | OutputNoteState::ExpectedFull { .. } => "expected",
+        OutputNoteState::CommittedPartial { .. } | OutputNoteState::CommittedFull { .. } => {
+            "committed"
+        }
+        OutputNoteState::Consumed { .. } => "consumed",
+    }
+}
+
+fn note_json(context: &MidenContext, record: &OutputNoteRecord) -> serde_json::Value {
+    let block_num = record.inclusion_proof().map(|proof| proof.location().block_num().as_u32());
+    let recipient = record.recipient();
+    serde_json::json!({
+        "note_id": record.id().to_hex(),
+        "sender": record.metadata().sender().to_hex(),
+        "state": state_str(record.state()),
+        "note_type": match record.metadata().note_type() {
+            NoteType::Private => "private",
+            _ => "public",
+        },
+        "assets": json::AssetJson::fungible(record.assets().iter()),
+        "recipient": {
+            "digest": record.recipient_digest().to_hex(),
+            "target_account_id": recipient
+                .and_then(note_summary::target_account)
+                .map(|target| target.to_hex()),
+            "reclaim_block": recipient.and_then(note_summary::reclaim_height),
+        },
+        "expected_height": record.expected_height().as_u32(),
+        "block_num": block_num,
+        "timestamp": block_num.and_then(|block| context.block_times.timestamp(block)),
+    })
+}
+
+pub(crate) async fn get_output_notes_impl(
+    context: &MidenContext,
+    account_id: Option<AccountId>,
+) -> Result<String, i32> {
+    let records = context.client.get_output_notes(NoteFilter::All).await
+        .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?;
+
+    let notes: Vec<serde_json::Value> = records
+        .iter()
+        .filter(|record| {
+            let sender = record.metadata().sender();
+            match account_id {
+                Some(account_id) => sender == account_id,
+                None => !context.archive.contains(sender),
+            }
+        })
+        .map(|record| note_json(context, record))
+        .collect();
+
+    Ok(serde_json::json!({ "total_count": notes.len(), "notes": notes }).to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// List the notes created by this store's transactions (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// States reflect the last sync.
+///
+/// # Parameters
+/// - `account_id_hex`: Only notes sent by this account (C string, NULL = all active accounts)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"notes":[{"note_id","sender","state":"expected"|"committed"|"consumed","note_type",
+///   "assets":[{"faucet_id","amount"}],"recipient":{"digest","target_account_id",
+///   "reclaim_block"},"expected_height","block_num","timestamp"}],"total_count"}`
+///   (`target_account_id`: P2ID/P2IDE target, null for other or digest-only recipients;
+///   `block_num` and `timestamp`: the inclusion block, null until committed)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID
+/// - -4: Note query failed
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_output_notes(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let account_id = match parse_optional_account_id(account_id_hex) {
+        Ok(account_id) => account_id,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::GetOutputNotes { account_id, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
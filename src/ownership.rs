@@ -0,0 +1,130 @@
+//! Proof of account ownership
+//!
+//! "Link this wallet to your user account" flows: a server hands the app a random
+//! challenge, the app returns a statement signed with the account's auth key, and the
+//! server checks it without trusting the app.
+//!
+//! The signed message is the RPO hash of the text
+//! `miden-ownership:v1:<account_id>:<challenge>` (account ID as `0x..` hex, challenge as
+//! lowercase hex without prefix); binding the account ID keeps a statement for one account
+//! from being replayed for another. To verify, the server recomputes that digest, checks
+//! the Falcon signature against the public key it carries, checks that the key hashes to
+//! `pub_key`, and compares `pub_key` with the account's auth key commitment (storage slot
+//! 0, from the node for public accounts or from an earlier registration for private ones).
+//! Challenges must be fresh and single-use: the statement itself carries no expiry.
+//!
+//! Signing goes through the account's key backend, so an external signer may block on a
+//! user confirmation.
+
+use std::os::raw::c_char;
+
+use miden_client::auth::{SigningInputs, TransactionAuthenticator};
+use miden_objects::{account::AccountId, crypto::hash::rpo::Rpo256, utils::Serializable, Word};
+
+use crate::{
+    get_handle, parse_account_id, request_blocking, reserves::AUTH_PUB_KEY_SLOT,
+    write_out_buffer, MidenContext, MidenHandle, Request, ERR_ACCOUNT_OP, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM, ERR_LOOKUP,
+};
+
+/// Version tag of the ownership statement format
+pub const OWNERSHIP_STATEMENT_VERSION: u32 = 1;
+
+/// Minimum challenge length (a server nonce shorter than this is guessable)
+pub const OWNERSHIP_MIN_CHALLENGE_BYTES: usize = 16;
+
+/// Maximum challenge length
+pub const OWNERSHIP_MAX_CHALLENGE_BYTES: usize = 1024;
+
+/// Message signed for `account_id` and `challenge`
+fn statement_digest(account_id: AccountId, challenge: &[u8]) -> Word {
+    let text = format!(
+        "miden-ownership:v{}:{}:{}",
+        OWNERSHIP_STATEMENT_VERSION,
+        account_id.to_hex(),
+        hex::encode(challenge),
+    );
+    Rpo256::hash(text.as_bytes())
+}
+
+pub(crate) async fn prove_ownership_impl(
+    context: &MidenContext,
+    account_id: AccountId,
+    challenge: Vec<u8>,
+) -> Result<String, i32> {
+    let record = context.client.get_account(account_id).await
+        .map_err(|_| ERR_LOOKUP)?
+        .ok_or(ERR_LOOKUP)?;
+    let pub_key = record.account().storage().get_item(AUTH_PUB_KEY_SLOT)
+        .map_err(|_| ERR_ACCOUNT_OP)?;
+
+    let digest = statement_digest(account_id, &challenge);
+    let signature = context.authenticator
+        .get_signature(pub_key.into(), &SigningInputs::Blind(digest))
+        .await
+        .map_err(|_| ERR_ACCOUNT_OP)?;
+
+    Ok(serde_json::json!({
+        "version": OWNERSHIP_STATEMENT_VERSION,
+        "account_id": account_id.to_hex(),
+        "challenge": hex::encode(&challenge),
+        "digest": hex::encode(digest.to_bytes()),
+        "pub_key": hex::encode(pub_key.to_bytes()),
+        "signature": hex::encode(signature.to_bytes()),
+    })
+    .to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Sign a statement proving control of an account (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_id_hex`: Account ID (C string); its key must be held by this client
+/// - `challenge_ptr` / `challenge_len`: Server challenge (16-1024 bytes)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"version":1,"account_id","challenge","digest","pub_key","signature"}` (hex fields;
+///   `signature`: serialized RPO Falcon512 signature over `digest`, see the module docs)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters (including a challenge outside 16-1024 bytes)
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID, or the account has no key this client can sign with
+/// - -5: Account not in the store
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_prove_ownership(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    challenge_ptr: *const u8,
+    challenge_len: usize,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let challenge_range = OWNERSHIP_MIN_CHALLENGE_BYTES..=OWNERSHIP_MAX_CHALLENGE_BYTES;
+    if challenge_ptr.is_null() || !challenge_range.contains(&challenge_len) {
+        return ERR_INVALID_PARAM;
+    }
+    let challenge = unsafe { std::slice::from_raw_parts(challenge_ptr, challenge_len) }.to_vec();
+
+    let account_id = match parse_account_id(account_id_hex) {
+        Ok((account_id, _)) => account_id,
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::ProveOwnership { account_id, challenge, reply };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,158 @@
+//! Paged listings
+//!
+//! Accounts, consumable notes and transactions used to come back as one JSON document; on
+//! wallets with thousands of notes that is one large allocation on both sides of the FFI.
+//! The paged variants take an offset and a limit and wrap the page in an envelope with the
+//! total count: `{"<items>":[..],"total_count","offset","has_more"}`.
+//!
+//! Pages are cut from the store's order at the time of each call, so a sync between two
+//! pages can shift entries; restart from offset 0 after a sync to show an exact list.
+
+use std::os::raw::c_char;
+
+use miden_objects::account::AccountId;
+use serde::Serialize;
+
+use crate::{
+    account_ids, consumable_note_entries, get_handle, parse_optional_account_id,
+    request_blocking, write_out_buffer, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM,
+};
+
+/// Window of a listing
+#[derive(Clone, Copy)]
+pub(crate) struct Page {
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl Page {
+    /// Page from raw FFI arguments (the limit must be > 0)
+    pub fn new(offset: u32, limit: u32) -> Result<Self, i32> {
+        if limit == 0 {
+            return Err(ERR_INVALID_PARAM);
+        }
+        Ok(Self { offset: offset as usize, limit: limit as usize })
+    }
+
+    /// Cut the page out of a full listing and wrap it under `key` in the envelope
+    pub fn envelope<T: Serialize>(self, key: &str, items: Vec<T>) -> String {
+        let total_count = items.len();
+        let page: Vec<T> = items.into_iter().skip(self.offset).take(self.limit).collect();
+        let has_more = self.offset.saturating_add(page.len()) < total_count;
+
+        let mut envelope = serde_json::Map::new();
+        envelope.insert(key.to_string(), serde_json::json!(page));
+        envelope.insert("total_count".to_string(), total_count.into());
+        envelope.insert("offset".to_string(), self.offset.into());
+        envelope.insert("has_more".to_string(), has_more.into());
+        serde_json::Value::Object(envelope).to_string()
+    }
+}
+
+pub(crate) async fn get_accounts_page_impl(
+    context: &MidenContext,
+    page: Page,
+) -> Result<String, i32> {
+    Ok(page.envelope("accounts", account_ids(&context.client).await?))
+}
+
+pub(crate) async fn get_input_notes_page_impl(
+    context: &MidenContext,
+    account_id: Option<AccountId>,
+    page: Page,
+) -> Result<String, i32> {
+    Ok(page.envelope("notes", consumable_note_entries(&context.read_view(), account_id).await?))
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Get one page of the account list (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `offset`: Index of the first account of the page
+/// - `limit`: Maximum number of accounts (must be > 0)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"accounts":["0x.."],"total_count","offset","has_more"}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Account query failed
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_accounts_page(
+    handle: MidenHandle,
+    offset: u32,
+    limit: u32,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let page = match Page::new(offset, limit) {
+        Ok(page) => page,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::GetAccountsPage { page, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Get one page of the consumable notes (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_id_hex`: Account ID (C string, NULL = all active accounts)
+/// - `offset`: Index of the first note of the page
+/// - `limit`: Maximum number of notes (must be > 0)
+/// - `json_out` / `json_out_len`: Output buffer for `{"notes":[..],"total_count","offset",
+///   "has_more"}` (notes as in `wc_miden_get_input_notes`; `total_count` counts every page)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID
+/// - -4: Note query failed
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_input_notes_page(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    offset: u32,
+    limit: u32,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let page = match Page::new(offset, limit) {
+        Ok(page) => page,
+        Err(code) => return code,
+    };
+    let account_id = match parse_optional_account_id(account_id_hex) {
+        Ok(account_id) => account_id,
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::GetInputNotesPage { account_id, page, reply };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
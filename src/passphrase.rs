@@ -0,0 +1,129 @@
+//! Passphrase key derivation
+//!
+//! Key backups (key_backup.rs), the encrypted keystore (encrypted_keystore.rs) and
+//! withdrawal whitelist locks (whitelist.rs) turn user passphrases into keys with Argon2id
+//! (RFC 9106), over a random salt and a domain string per use. The cost parameters are
+//! stored with what they protect, as `"kdf":{"m_cost","t_cost","p_cost"}` (memory in KiB,
+//! passes, lanes), so the defaults can be raised without breaking existing data.
+//! Parameters read back are bounded: a crafted envelope cannot make an import allocate or
+//! compute without limit.
+//!
+//! Formats written before Argon2id derived keys by iterating salted SHA3-256 (`rounds`
+//! times). They are still read, and replaced by the current format whenever they are
+//! written again (a re-exported key, a rekeyed keystore, a changed whitelist).
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{aead::KeyInit, ChaCha20Poly1305, Key};
+use sha3::{Digest, Sha3_256};
+
+/// Argon2id memory cost of new data in KiB (19 MiB, OWASP's recommended minimum)
+const DEFAULT_M_COST: u32 = 19 * 1024;
+
+/// Argon2id passes of new data
+const DEFAULT_T_COST: u32 = 2;
+
+/// Most memory accepted from stored parameters, in KiB (64 MiB, well inside an iOS app's
+/// memory limit)
+const MAX_M_COST: u32 = 64 * 1024;
+
+/// Most passes accepted from stored parameters
+const MAX_T_COST: u32 = 16;
+
+/// Most lanes accepted from stored parameters
+const MAX_P_COST: u32 = 4;
+
+/// Most SHA3-256 rounds accepted from legacy formats
+const LEGACY_MAX_ROUNDS: u32 = 10_000_000;
+
+/// Argon2id cost parameters
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) struct KdfParams {
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+impl Default for KdfParams {
+    /// Parameters of newly written data
+    fn default() -> Self {
+        Self { m_cost: DEFAULT_M_COST, t_cost: DEFAULT_T_COST, p_cost: 1 }
+    }
+}
+
+impl KdfParams {
+    /// Parse `{"m_cost","t_cost","p_cost"}`, rejecting parameters outside the bounds
+    pub fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let field = |name: &str, max: u32| {
+            value[name].as_u64()
+                .and_then(|n| u32::try_from(n).ok())
+                .filter(|n| (1..=max).contains(n))
+        };
+        let params = Self {
+            m_cost: field("m_cost", MAX_M_COST)?,
+            t_cost: field("t_cost", MAX_T_COST)?,
+            p_cost: field("p_cost", MAX_P_COST)?,
+        };
+        params.argon2_params().map(|_| params)
+    }
+
+    pub fn to_json(self) -> serde_json::Value {
+        serde_json::json!({ "m_cost": self.m_cost, "t_cost": self.t_cost, "p_cost": self.p_cost })
+    }
+
+    fn argon2_params(self) -> Option<Params> {
+        Params::new(self.m_cost, self.t_cost, self.p_cost, Some(32)).ok()
+    }
+}
+
+/// How a stored format derives its key
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kdf {
+    Argon2id(KdfParams),
+    /// Iterated salted SHA3-256, read from formats written before Argon2id
+    LegacySha3 { rounds: u32 },
+}
+
+impl Kdf {
+    /// Legacy derivation with `rounds` as stored (None when out of bounds)
+    pub fn legacy(rounds: &serde_json::Value) -> Option<Self> {
+        rounds.as_u64()
+            .and_then(|rounds| u32::try_from(rounds).ok())
+            .filter(|rounds| (1..=LEGACY_MAX_ROUNDS).contains(rounds))
+            .map(|rounds| Kdf::LegacySha3 { rounds })
+    }
+
+    /// Key for `passphrase` under `domain` and `salt`
+    pub fn derive(self, domain: &str, salt: &[u8; 16], passphrase: &str) -> [u8; 32] {
+        match self {
+            Kdf::Argon2id(params) => {
+                let params = params.argon2_params().expect("parameters are checked on read");
+                let salt = [domain.as_bytes(), salt].concat();
+                let mut key = [0u8; 32];
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+                    .expect("salt and key lengths are valid for Argon2");
+                key
+            }
+            Kdf::LegacySha3 { rounds } => legacy_key(domain, salt, rounds, passphrase),
+        }
+    }
+
+    /// ChaCha20-Poly1305 cipher keyed by `derive`
+    pub fn cipher(self, domain: &str, salt: &[u8; 16], passphrase: &str) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&self.derive(domain, salt, passphrase)))
+    }
+}
+
+/// Key of the legacy formats: `rounds` iterations of salted SHA3-256
+fn legacy_key(domain: &str, salt: &[u8; 16], rounds: u32, passphrase: &str) -> [u8; 32] {
+    let mut digest: [u8; 32] = Sha3_256::new()
+        .chain_update(domain.as_bytes())
+        .chain_update(salt)
+        .chain_update(passphrase.as_bytes())
+        .finalize()
+        .into();
+    for _ in 1..rounds {
+        digest = Sha3_256::new().chain_update(salt).chain_update(digest).finalize().into();
+    }
+    digest
+}
@@ -0,0 +1,92 @@
+//! Note inclusion proof cache
+//!
+//! Checking that a note was included in block N takes its inclusion proof and the header
+//! of that block, and the client does not keep headers of every block: each check fetched
+//! the header from the node again, although apps check the same notes repeatedly (an
+//! export retried after a failed share, a third party asking twice). A proof is cached
+//! with its header in a table of the SQLite store keyed by (note ID, block number), so a
+//! note that a reorg moved to another block is not answered from a stale entry.
+//!
+//! Entries follow the retention policy: when retention selects a consumed note, its entries
+//! are deleted (see retention.rs).
+
+use std::path::Path;
+
+use miden_objects::{
+    block::BlockHeader,
+    note::{NoteId, NoteInclusionProof},
+    utils::{Deserializable, Serializable},
+};
+
+use crate::{clock, diagnostics};
+
+const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS wc_note_proof_cache (
+    note_id TEXT NOT NULL,
+    block_num INTEGER NOT NULL,
+    inclusion_proof BLOB NOT NULL,
+    block_header BLOB NOT NULL,
+    cached_at INTEGER NOT NULL,
+    PRIMARY KEY (note_id, block_num)
+)";
+
+fn open(store_path: &Path) -> rusqlite::Result<rusqlite::Connection> {
+    let conn = rusqlite::Connection::open(store_path)?;
+    conn.execute_batch(CREATE_TABLE)?;
+    Ok(conn)
+}
+
+/// Cached proof and block header of a note in `block_num` (None on a miss)
+pub(crate) fn get(
+    store_path: &Path,
+    note_id: NoteId,
+    block_num: u32,
+) -> Option<(NoteInclusionProof, BlockHeader)> {
+    let conn = open(store_path).ok()?;
+    let (proof, header): (Vec<u8>, Vec<u8>) = conn
+        .query_row(
+            "SELECT inclusion_proof, block_header FROM wc_note_proof_cache
+             WHERE note_id = ?1 AND block_num = ?2",
+            rusqlite::params![note_id.to_hex(), block_num],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok()?;
+
+    let proof = NoteInclusionProof::read_from_bytes(&proof).ok()?;
+    let header = BlockHeader::read_from_bytes(&header).ok()?;
+    Some((proof, header))
+}
+
+/// Cache a proof with its header (failures are logged: the cache is only an optimization)
+pub(crate) fn put(
+    store_path: &Path,
+    note_id: NoteId,
+    proof: &NoteInclusionProof,
+    header: &BlockHeader,
+) {
+    let result = open(store_path).and_then(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO wc_note_proof_cache
+             (note_id, block_num, inclusion_proof, block_header, cached_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                note_id.to_hex(),
+                header.block_num().as_u32(),
+                proof.to_bytes(),
+                header.to_bytes(),
+                clock::now_unix(),
+            ],
+        )
+    });
+    if let Err(e) = result {
+        diagnostics::log("proof_cache", format!("failed to cache proof: {:?}", e));
+    }
+}
+
+/// Delete the entries of notes selected by retention
+pub(crate) fn evict(tx: &rusqlite::Transaction, note_ids: &[String]) -> rusqlite::Result<()> {
+    tx.execute_batch(CREATE_TABLE)?;
+    for id in note_ids {
+        tx.execute("DELETE FROM wc_note_proof_cache WHERE note_id = ?1", [id])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! Memory ceiling for local proving
+//!
+//! Transactions are proven on the device, and a proof's memory use peaks at several
+//! hundred MB; on iOS an app that crosses its memory limit is killed by jetsam with no
+//! chance to clean up. With `proving_memory_ceiling_mb` set in the client config (the
+//! memory a proof may take on this device), the memory still available to the process is
+//! checked before each transaction is proven: when it is below the ceiling, the
+//! transaction fails with `ERR_PROVING_MEMORY` before proving starts, so the app can free
+//! memory or move proving off the device (a remote prover) instead of being terminated.
+//!
+//! Available memory is the jetsam headroom on iOS (`os_proc_available_memory`) and
+//! `MemAvailable` on Linux. Other targets do not report it and always pass the check.
+//!
+//! Long-lived processes (servers, custodial backends) may also build with the `jemalloc`
+//! or `mimalloc` feature to replace the system allocator, which reduces fragmentation
+//! after many proofs (see allocator.rs).
+
+use crate::{diagnostics, last_error, MidenContext, ERR_PROVING_MEMORY};
+
+#[cfg(target_os = "ios")]
+fn available_memory() -> Option<u64> {
+    unsafe extern "C" {
+        fn os_proc_available_memory() -> usize;
+    }
+    // 0 when the process has no memory limit (e.g. in the simulator)
+    Some(unsafe { os_proc_available_memory() } as u64).filter(|bytes| *bytes > 0)
+}
+
+#[cfg(target_os = "linux")]
+fn available_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo.lines().find(|line| line.starts_with("MemAvailable:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "linux")))]
+fn available_memory() -> Option<u64> {
+    None
+}
+
+/// Check that a transaction may be proven locally (call right before submitting)
+pub(crate) fn check_memory(context: &MidenContext) -> Result<(), i32> {
+    let Some(ceiling_mb) = context.proving_memory_ceiling_mb else {
+        return Ok(());
+    };
+    let Some(available) = available_memory() else {
+        return Ok(());
+    };
+
+    let available_mb = available / (1024 * 1024);
+    if available_mb < ceiling_mb {
+        let message = format!(
+            "{} MB available, proving may need {} MB; free memory or use a remote prover",
+            available_mb, ceiling_mb
+        );
+        diagnostics::log("proving", &message);
+        return Err(last_error::detail(ERR_PROVING_MEMORY, message));
+    }
+    Ok(())
+}
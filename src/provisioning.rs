@@ -0,0 +1,206 @@
+//! Bulk wallet provisioning
+//!
+//! Custodial integrators create wallets by the hundred. `wc_miden_create_wallets_bulk`
+//! creates a batch in one worker request, deriving every wallet from a 32-byte
+//! derivation base and its index:
+//!
+//! - account seed: RPO hash of `miden-bulk:v1:seed:` ‖ base ‖ index (u32 LE)
+//! - Falcon key: generated from an RNG seeded with the RPO hash of
+//!   `miden-bulk:v1:key:` ‖ base ‖ index
+//!
+//! The same base and index always give the same wallet, so provisioning is resumable:
+//! wallets already in the store are reported as `existing` and skipped, and larger sets
+//! are created in batches of at most [`BULK_MAX_WALLETS`] with increasing `first_index`.
+//! Keep the base secret: it recreates every key derived from it.
+//!
+//! Each wallet goes through the creation preflight (see create_preflight.rs). Keys are
+//! written to the filesystem keystore and the key backend records of the batch are
+//! saved once at the end; `miden-client` has no batch insert, so each account is still
+//! its own store write. Wallets are registered locally and appear on chain with their
+//! first transaction.
+
+use miden_client::auth::AuthSecretKey;
+use miden_lib::account::auth::AuthRpoFalcon512;
+use miden_objects::{
+    account::{AccountComponent, AccountId, AccountStorageMode},
+    crypto::{dsa::rpo_falcon512, hash::rpo::Rpo256},
+    utils::Serializable,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    check_out_capacity, create_preflight, get_handle, hex_placeholder,
+    keystore::KeyBackend,
+    last_error, output_len, parse_seed, request_blocking,
+    wallet_core::{self, CoreError},
+    write_out_buffer, MidenContext, MidenHandle, Request, ACCOUNT_ID_HEX_LEN, ERR_ACCOUNT_OP,
+    ERR_INVALID_HANDLE, ERR_INVALID_PARAM,
+};
+
+/// Most wallets created by one `wc_miden_create_wallets_bulk` call
+pub const BULK_MAX_WALLETS: u32 = 100;
+
+/// 32 bytes derived from the base for one wallet index
+fn derive(purpose: &str, base: &[u8; 32], index: u32) -> [u8; 32] {
+    let mut message = format!("miden-bulk:v1:{}:", purpose).into_bytes();
+    message.extend_from_slice(base);
+    message.extend_from_slice(&index.to_le_bytes());
+
+    let mut derived = [0u8; 32];
+    derived.copy_from_slice(&Rpo256::hash(&message).to_bytes());
+    derived
+}
+
+/// Create one wallet of the batch, returning whether it was created (false: existing)
+async fn provision_one(
+    context: &mut MidenContext,
+    base: &[u8; 32],
+    index: u32,
+) -> Result<(AccountId, bool), i32> {
+    let mut rng = StdRng::from_seed(derive("key", base, index));
+    let key = AuthSecretKey::RpoFalcon512(rpo_falcon512::SecretKey::with_rng(&mut rng));
+    let auth_component: AccountComponent =
+        AuthRpoFalcon512::new(key.public_key().to_commitment()).into();
+
+    let seed = derive("seed", base, index);
+    let storage_mode = AccountStorageMode::Public;
+    let account = wallet_core::build_wallet_account(seed, auth_component, storage_mode)
+        .map_err(CoreError::into_code)?;
+    let account_id = account.id();
+
+    let local = context.client.get_account(account_id).await
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    if local.is_some() {
+        return Ok((account_id, false));
+    }
+    create_preflight::check(context, account_id).await?;
+
+    context.keystore.add_key(&key, KeyBackend::Filesystem)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    wallet_core::add_account(&mut context.client, &account).await
+        .map_err(CoreError::into_code)?;
+
+    Ok((account_id, true))
+}
+
+pub(crate) async fn create_wallets_bulk_impl(
+    context: &mut MidenContext,
+    base: [u8; 32],
+    first_index: u32,
+    count: u32,
+) -> Result<String, i32> {
+    let mut wallets = Vec::new();
+    let mut created = Vec::new();
+    let mut failed = None;
+
+    for index in first_index..first_index + count {
+        match provision_one(context, &base, index).await {
+            Ok((account_id, is_new)) => {
+                if is_new {
+                    created.push(account_id);
+                }
+                wallets.push(serde_json::json!({
+                    "index": index,
+                    "account_id": account_id.to_hex(),
+                    "existing": !is_new,
+                }));
+            }
+            Err(code) => {
+                failed = Some(serde_json::json!({ "index": index, "code": code }));
+                break;
+            }
+        }
+    }
+
+    // One write for the whole batch, also after a failure so the created wallets stay usable
+    context.keystore.record_accounts(&created, KeyBackend::Filesystem)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    Ok(serde_json::json!({
+        "derivation_base": hex::encode(base),
+        "wallets": wallets,
+        "created_count": created.len(),
+        "failed": failed,
+    })
+    .to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Create a batch of wallets derived from a base (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Creates the wallets with indices `first_index..first_index + count` in order (see the
+/// module docs for the derivation). Stops at the first wallet that cannot be created and
+/// still succeeds, reporting the wallets handled so far and the failure.
+///
+/// # Parameters
+/// - `count`: Wallets to create (1-100)
+/// - `base_ptr` / `base_len`: 32-byte derivation base (NULL generates a random base,
+///   returned in the output; store it to resume or recreate the batch)
+/// - `first_index`: Index of the first wallet
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"derivation_base","wallets":[{"index","account_id","existing"}],"created_count",
+///   "failed":{"index","code"}}` (`existing`: already in the store, skipped; `failed`:
+///   null when every wallet was handled, else the error code of the wallet at `index`)
+///
+/// # Returns
+/// - 0: Success (check `failed`)
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Key backend records could not be saved
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_create_wallets_bulk(
+    handle: MidenHandle,
+    count: u32,
+    base_ptr: *const u8,
+    base_len: usize,
+    first_index: u32,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if !(1..=BULK_MAX_WALLETS).contains(&count)
+        || first_index.checked_add(count).is_none()
+    {
+        return ERR_INVALID_PARAM;
+    }
+    // Every wallet handled and a failure reported bounds both ways the batch can end
+    let last_index = first_index + count - 1;
+    let wallets: Vec<serde_json::Value> = (first_index..=last_index)
+        .map(|index| {
+            serde_json::json!({
+                "index": index,
+                "account_id": hex_placeholder(ACCOUNT_ID_HEX_LEN),
+                "existing": false,
+            })
+        })
+        .collect();
+    let result = serde_json::json!({
+        "derivation_base": "0".repeat(64),
+        "wallets": wallets,
+        "created_count": count,
+        "failed": { "index": last_index, "code": i32::MIN },
+    });
+    if let Err(code) = check_out_capacity(json_out, json_out_len, output_len(&result)) {
+        return code;
+    }
+    let base = match parse_seed(base_ptr, base_len) {
+        Ok(base) => base,
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::CreateWalletsBulk { base, first_index, count, reply };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
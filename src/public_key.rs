@@ -0,0 +1,99 @@
+//! Account public keys
+//!
+//! External services (login backends, ownership registries, co-signers) need an account's
+//! public key before they can check its signatures. `wc_miden_get_public_key` reports:
+//!
+//! - `pub_key`: the public key commitment the account authenticates with (auth storage
+//!   slot 0), the same value carried by ownership statements and signed exports
+//! - `public_key`: the serialized RPO Falcon512 public key polynomial, when the key is
+//!   held by this client (filesystem or memory backend). External signers only expose the
+//!   commitment, so it is null for them; the polynomial is also carried by every
+//!   signature, so verifiers can check it against the commitment either way.
+
+use std::os::raw::c_char;
+
+use miden_client::auth::AuthSecretKey;
+use miden_objects::{account::AccountId, utils::Serializable};
+
+use crate::{
+    get_handle, last_error, parse_account_id, request_blocking, reserves::AUTH_PUB_KEY_SLOT,
+    write_out_buffer, MidenContext, MidenHandle, Request, ERR_ACCOUNT_OP, ERR_INVALID_HANDLE,
+    ERR_LOOKUP,
+};
+
+pub(crate) async fn get_public_key_impl(
+    context: &MidenContext,
+    account_id: AccountId,
+) -> Result<String, i32> {
+    let record = context.client.get_account(account_id).await
+        .map_err(|e| last_error::detail(ERR_LOOKUP, e))?
+        .ok_or(ERR_LOOKUP)?;
+    let pub_key = record.account().storage().get_item(AUTH_PUB_KEY_SLOT)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    let backend = context.keystore.account_backend(account_id);
+    let public_key = match context.keystore.secret_key(pub_key) {
+        Ok(Some(AuthSecretKey::RpoFalcon512(secret_key))) => {
+            // Serializable is implemented for the reference only
+            let public_key = secret_key.public_key();
+            Some(hex::encode((&public_key).to_bytes()))
+        }
+        #[allow(unreachable_patterns)]
+        Ok(_) => None,
+        Err(e) => return Err(last_error::detail(ERR_ACCOUNT_OP, e)),
+    };
+
+    Ok(serde_json::json!({
+        "account_id": account_id.to_hex(),
+        "scheme": "rpo_falcon512",
+        "pub_key": hex::encode(pub_key.to_bytes()),
+        "public_key": public_key,
+        "key_backend": backend.kind().as_str(),
+    })
+    .to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Get the public key of an account (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_id_hex`: Account ID (C string)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"account_id","scheme":"rpo_falcon512","pub_key","public_key","key_backend"}`
+///   (`pub_key`: commitment, hex; `public_key`: serialized public key, hex, or null when an
+///   external signer holds the key; see the module docs)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID, or the account has no auth key
+/// - -5: Account not in the store
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_public_key(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let account_id = match parse_account_id(account_id_hex) {
+        Ok((account_id, _)) => account_id,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::GetPublicKey { account_id, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
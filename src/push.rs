@@ -0,0 +1,213 @@
+//! Push notifications for incoming notes
+//!
+//! Without push, a wallet only learns about a payment when it next syncs. A relay
+//! (watchtower) service follows the chain for the note tags of registered devices and
+//! sends an APNs push when a note with one of those tags is committed.
+//!
+//! Registration posts `{"device_token","platform":"apns","tags":[..]}` to the relay URL;
+//! the tags default to every tag the client tracks (account tags and manually added
+//! ones). Register again after creating accounts or adding tags.
+//!
+//! The push payload carries `{"miden":{"note_id","tag","block_num"}}`. Handling it
+//! imports just that note by ID instead of running a full sync, which fits in the few
+//! seconds a notification service extension gets. The node only serves the details of
+//! public notes: private notes are reported with `requires_sync`, and the next regular
+//! sync picks them up.
+
+use std::{collections::BTreeSet, os::raw::c_char, time::Duration};
+
+use miden_objects::note::{NoteFile, NoteId};
+
+use crate::{
+    diagnostics, get_handle, last_error, parse_optional_str, parse_required_str, request_blocking,
+    widget, write_out_buffer, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM, ERR_NOTE_OP, ERR_RELAY,
+};
+
+/// Timeout of the registration request
+const PUSH_RELAY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Upper bound on the tags of one registration
+pub const PUSH_MAX_TAGS: usize = 1024;
+
+pub(crate) async fn register_push_impl(
+    context: &MidenContext,
+    relay_url: String,
+    device_token: String,
+    tags: Option<Vec<u32>>,
+) -> Result<String, i32> {
+    let tags: BTreeSet<u32> = match tags {
+        Some(tags) => tags.into_iter().collect(),
+        None => context.client.get_note_tags().await
+            .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?
+            .into_iter()
+            .map(|record| record.tag.as_u32())
+            .collect(),
+    };
+    if tags.is_empty() || tags.len() > PUSH_MAX_TAGS {
+        return Err(ERR_INVALID_PARAM);
+    }
+
+    let body = serde_json::json!({
+        "device_token": device_token,
+        "platform": "apns",
+        "tags": tags,
+    });
+
+    let client = reqwest::Client::builder()
+        .timeout(PUSH_RELAY_TIMEOUT)
+        .build()
+        .map_err(|e| last_error::detail(ERR_RELAY, e))?;
+    let response = client.post(&relay_url).json(&body).send().await
+        .map_err(|e| last_error::detail(ERR_RELAY, e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        diagnostics::log("push", format!("relay refused registration: {}", status));
+        return Err(last_error::detail(ERR_RELAY, format!("relay answered {}", status)));
+    }
+
+    Ok(serde_json::json!({ "registered_tags": tags }).to_string())
+}
+
+/// Note ID of a push payload (the APNs `userInfo`, or just its `miden` object)
+fn parse_payload(payload: &str) -> Option<NoteId> {
+    let payload: serde_json::Value = serde_json::from_str(payload).ok()?;
+    let miden = payload.get("miden").unwrap_or(&payload);
+    NoteId::try_from_hex(miden.get("note_id")?.as_str()?).ok()
+}
+
+pub(crate) async fn handle_push_impl(
+    context: &mut MidenContext,
+    note_id: NoteId,
+) -> Result<String, i32> {
+    let known = context.client.get_input_note(note_id).await
+        .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?;
+    if known.as_ref().is_some_and(|record| record.is_authenticated()) {
+        return Ok(serde_json::json!({
+            "note_id": note_id.to_hex(),
+            "imported": false,
+            "requires_sync": false,
+        })
+        .to_string());
+    }
+
+    let imported = match context.client.import_note(NoteFile::NoteId(note_id)).await {
+        Ok(_) => true,
+        Err(e) => {
+            // Private notes (and nodes that have not caught up yet) need a full sync
+            diagnostics::log("push", format!("note import failed: {:?}", e));
+            false
+        }
+    };
+    if imported {
+        widget::update_after_sync(context).await;
+    }
+
+    Ok(serde_json::json!({
+        "note_id": note_id.to_hex(),
+        "imported": imported,
+        "requires_sync": !imported,
+    })
+    .to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Register the device with a push relay for the client's note tags (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `relay_url`: Registration endpoint of the relay service (C string, http or https)
+/// - `device_token`: APNs device token, hex encoded (C string)
+/// - `tags_json`: JSON array of note tags (u32), or NULL for every tag the client tracks
+/// - `json_out` / `json_out_len`: Output buffer for `{"registered_tags":[..]}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, no tags or more than [`PUSH_MAX_TAGS`]
+/// - -2: Invalid handle or worker closed
+/// - -4: Tracked tags could not be read
+/// - -7: Output buffer too small
+/// - -103: Relay unreachable or registration refused
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_register_push(
+    handle: MidenHandle,
+    relay_url: *const c_char,
+    device_token: *const c_char,
+    tags_json: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let relay_url = match parse_required_str(relay_url) {
+        Ok(url) if url.starts_with("https://") || url.starts_with("http://") => url.to_string(),
+        Ok(_) => return ERR_INVALID_PARAM,
+        Err(code) => return code,
+    };
+    let device_token = match parse_required_str(device_token) {
+        Ok(token) if !token.is_empty() && hex::decode(token).is_ok() => token.to_string(),
+        Ok(_) => return ERR_INVALID_PARAM,
+        Err(code) => return code,
+    };
+    let tags = match parse_optional_str(tags_json) {
+        Ok(Some(json)) => match serde_json::from_str::<Vec<u32>>(json) {
+            Ok(tags) => Some(tags),
+            Err(_) => return ERR_INVALID_PARAM,
+        },
+        Ok(None) => None,
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::RegisterPush { relay_url, device_token, tags, reply };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Fetch the note referenced by a push payload (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Imports only the referenced note, so it is quick enough for a notification service
+/// extension. When `requires_sync` is true the note could not be fetched by ID (private
+/// note, or the node has not caught up) and a regular sync is needed.
+///
+/// # Parameters
+/// - `payload_json`: Push payload, the APNs `userInfo` dictionary or its `miden` object (C string)
+/// - `json_out` / `json_out_len`: Output buffer for `{"note_id","imported","requires_sync"}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or payload without a valid note ID
+/// - -2: Invalid handle or worker closed
+/// - -4: Store error
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_handle_push(
+    handle: MidenHandle,
+    payload_json: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let note_id = match parse_required_str(payload_json).map(parse_payload) {
+        Ok(Some(note_id)) => note_id,
+        Ok(None) => return ERR_INVALID_PARAM,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::HandlePush { note_id, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
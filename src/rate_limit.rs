@@ -0,0 +1,378 @@
+//! Token-bucket rate limiting for node RPC calls
+//!
+//! Public nodes throttle clients that call too often. The node RPC client of a handle is
+//! wrapped in [`RateLimitedRpc`], so every node RPC takes a token from the handle's bucket,
+//! whether this crate or `miden-client` (sync, submission, note import) makes it; a sync
+//! that needs several requests takes a token for each. When the bucket is empty the call
+//! waits for the next token instead of failing. Waits are counted so apps can explain
+//! slowness ("the node is rate limiting us") and are included in diagnostics.
+//!
+//! The limiter is shared between the handle and the worker, so it can be reconfigured
+//! and queried without queueing behind a running operation. Disabled by default.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use miden_client::{
+    rpc::{
+        domain::{
+            account::{AccountProofs, FetchedAccount},
+            account_vault::AccountVaultInfo,
+            note::{FetchedNote, NoteSyncInfo},
+            nullifier::NullifierUpdate,
+            storage_map::StorageMapInfo,
+            sync::StateSyncInfo,
+            transaction::TransactionsInfo,
+        },
+        Endpoint, GrpcClient, NodeRpcClient, RpcError,
+    },
+    transaction::ForeignAccount,
+};
+use miden_objects::{
+    account::{AccountCode, AccountId},
+    address::NetworkId,
+    block::{BlockHeader, BlockNumber, ProvenBlock},
+    crypto::merkle::{MmrProof, SmtProof},
+    note::{NoteId, NoteScript, NoteTag, Nullifier},
+    transaction::{ProvenTransaction, TransactionInputs},
+    Word,
+};
+
+use crate::{
+    diagnostics, get_handle,
+    rpc_metrics::RpcMetrics,
+    testing::{self, FailureKind},
+    write_out_buffer, MidenHandle, ERR_INVALID_HANDLE, ERR_INVALID_PARAM,
+};
+
+struct Bucket {
+    /// Tokens added per second (0 = unlimited)
+    rate: f64,
+    /// Maximum number of tokens that can accumulate
+    burst: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+#[derive(Default, Clone, Copy)]
+struct Stats {
+    calls: u64,
+    throttled_calls: u64,
+    total_wait: Duration,
+}
+
+pub(crate) struct RateLimiter {
+    bucket: Mutex<Bucket>,
+    stats: Mutex<Stats>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self {
+            bucket: Mutex::new(Bucket {
+                rate: 0.0,
+                burst: 0.0,
+                tokens: 0.0,
+                last_refill: Instant::now(),
+            }),
+            stats: Mutex::new(Stats::default()),
+        }
+    }
+}
+
+impl RateLimiter {
+    fn configure(&self, rate: f64, burst: u32) {
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.rate = rate;
+        bucket.burst = f64::from(burst.max(1));
+        bucket.tokens = bucket.burst;
+        bucket.last_refill = Instant::now();
+    }
+
+    /// Reserve one token, returning how long the caller must wait for it
+    fn reserve(&self) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+        if bucket.rate <= 0.0 {
+            return Duration::ZERO;
+        }
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * bucket.rate).min(bucket.burst);
+        bucket.last_refill = now;
+
+        // Going negative queues the call behind earlier reservations
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / bucket.rate)
+        }
+    }
+
+    /// Wait until the bucket allows one more node call
+    async fn acquire(&self) {
+        let wait = self.reserve();
+
+        {
+            let mut stats = self.stats.lock().unwrap();
+            stats.calls += 1;
+            if !wait.is_zero() {
+                stats.throttled_calls += 1;
+                stats.total_wait += wait;
+            }
+        }
+
+        if !wait.is_zero() {
+            diagnostics::record_throttle(wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let (rate, burst, tokens) = {
+            let bucket = self.bucket.lock().unwrap();
+            (bucket.rate, bucket.burst, bucket.tokens)
+        };
+        let stats = *self.stats.lock().unwrap();
+
+        serde_json::json!({
+            "enabled": rate > 0.0,
+            "calls_per_second": rate,
+            "burst": burst as u64,
+            "available_tokens": tokens.max(0.0).floor() as u64,
+            "calls": stats.calls,
+            "throttled_calls": stats.throttled_calls,
+            "total_wait_ms": stats.total_wait.as_millis() as u64,
+        })
+        .to_string()
+    }
+}
+
+/// Node RPC client that takes a token from the limiter before every request and records
+/// it in the handle's RPC metrics
+pub(crate) struct RateLimitedRpc {
+    inner: GrpcClient,
+    /// Network of the node; `inner` may be connected to the node proxy (see node_proxy.rs)
+    network_id: NetworkId,
+    limiter: Arc<RateLimiter>,
+    metrics: Arc<RpcMetrics>,
+}
+
+impl RateLimitedRpc {
+    pub fn new(
+        inner: GrpcClient,
+        node: &Endpoint,
+        limiter: Arc<RateLimiter>,
+        metrics: Arc<RpcMetrics>,
+    ) -> Self {
+        Self { inner, network_id: node.to_network_id(), limiter, metrics }
+    }
+
+    /// Same node, limiter and metrics over a new connection (see reconnect.rs)
+    pub fn reconnected(&self, inner: GrpcClient) -> Self {
+        Self {
+            inner,
+            network_id: self.network_id.clone(),
+            limiter: self.limiter.clone(),
+            metrics: self.metrics.clone(),
+        }
+    }
+
+    /// Take a token, then make the node call under its gRPC method name, timed from the
+    /// RPC fail point (see testing.rs) to the response
+    async fn call<T>(
+        &self,
+        method: &'static str,
+        call: impl Future<Output = Result<T, RpcError>>,
+    ) -> Result<T, RpcError> {
+        self.limiter.acquire().await;
+        let call = async {
+            if testing::should_fail(FailureKind::Rpc) {
+                return Err(RpcError::ConnectionError("injected RPC failure".into()));
+            }
+            call.await
+        };
+        self.metrics.time(method, call).await
+    }
+}
+
+#[async_trait]
+impl NodeRpcClient for RateLimitedRpc {
+    async fn set_genesis_commitment(&self, commitment: Word) -> Result<(), RpcError> {
+        // Recorded locally, not sent to the node
+        self.inner.set_genesis_commitment(commitment).await
+    }
+
+    async fn submit_proven_transaction(
+        &self,
+        proven_transaction: ProvenTransaction,
+        transaction_inputs: TransactionInputs,
+    ) -> Result<BlockNumber, RpcError> {
+        let call = self.inner.submit_proven_transaction(proven_transaction, transaction_inputs);
+        self.call("SubmitProvenTransaction", call).await
+    }
+
+    async fn get_block_header_by_number(
+        &self,
+        block_num: Option<BlockNumber>,
+        include_mmr_proof: bool,
+    ) -> Result<(BlockHeader, Option<MmrProof>), RpcError> {
+        let call = self.inner.get_block_header_by_number(block_num, include_mmr_proof);
+        self.call("GetBlockHeaderByNumber", call).await
+    }
+
+    async fn get_block_by_number(&self, block_num: BlockNumber) -> Result<ProvenBlock, RpcError> {
+        self.call("GetBlockByNumber", self.inner.get_block_by_number(block_num)).await
+    }
+
+    async fn get_notes_by_id(&self, note_ids: &[NoteId]) -> Result<Vec<FetchedNote>, RpcError> {
+        self.call("GetNotesById", self.inner.get_notes_by_id(note_ids)).await
+    }
+
+    async fn sync_state(
+        &self,
+        block_num: BlockNumber,
+        account_ids: &[AccountId],
+        note_tags: &BTreeSet<NoteTag>,
+    ) -> Result<StateSyncInfo, RpcError> {
+        self.call("SyncState", self.inner.sync_state(block_num, account_ids, note_tags)).await
+    }
+
+    async fn get_account_details(&self, account_id: AccountId) -> Result<FetchedAccount, RpcError> {
+        self.call("GetAccountDetails", self.inner.get_account_details(account_id)).await
+    }
+
+    async fn sync_notes(
+        &self,
+        block_num: BlockNumber,
+        block_to: Option<BlockNumber>,
+        note_tags: &BTreeSet<NoteTag>,
+    ) -> Result<NoteSyncInfo, RpcError> {
+        self.call("SyncNotes", self.inner.sync_notes(block_num, block_to, note_tags)).await
+    }
+
+    async fn sync_nullifiers(
+        &self,
+        prefix: &[u16],
+        block_num: BlockNumber,
+        block_to: Option<BlockNumber>,
+    ) -> Result<Vec<NullifierUpdate>, RpcError> {
+        self.call("SyncNullifiers", self.inner.sync_nullifiers(prefix, block_num, block_to)).await
+    }
+
+    async fn check_nullifiers(&self, nullifiers: &[Nullifier]) -> Result<Vec<SmtProof>, RpcError> {
+        self.call("CheckNullifiers", self.inner.check_nullifiers(nullifiers)).await
+    }
+
+    async fn get_account_proofs(
+        &self,
+        account_requests: &BTreeSet<ForeignAccount>,
+        known_account_codes: BTreeMap<AccountId, AccountCode>,
+    ) -> Result<AccountProofs, RpcError> {
+        let call = self.inner.get_account_proofs(account_requests, known_account_codes);
+        self.call("GetAccountProof", call).await
+    }
+
+    async fn get_note_script_by_root(&self, root: Word) -> Result<NoteScript, RpcError> {
+        self.call("GetNoteScriptByRoot", self.inner.get_note_script_by_root(root)).await
+    }
+
+    async fn sync_storage_maps(
+        &self,
+        block_from: BlockNumber,
+        block_to: Option<BlockNumber>,
+        account_id: AccountId,
+    ) -> Result<StorageMapInfo, RpcError> {
+        let call = self.inner.sync_storage_maps(block_from, block_to, account_id);
+        self.call("SyncStorageMaps", call).await
+    }
+
+    async fn sync_account_vault(
+        &self,
+        block_from: BlockNumber,
+        block_to: Option<BlockNumber>,
+        account_id: AccountId,
+    ) -> Result<AccountVaultInfo, RpcError> {
+        let call = self.inner.sync_account_vault(block_from, block_to, account_id);
+        self.call("SyncAccountVault", call).await
+    }
+
+    async fn sync_transactions(
+        &self,
+        block_from: BlockNumber,
+        block_to: Option<BlockNumber>,
+        account_ids: Vec<AccountId>,
+    ) -> Result<TransactionsInfo, RpcError> {
+        let call = self.inner.sync_transactions(block_from, block_to, account_ids);
+        self.call("SyncTransactions", call).await
+    }
+
+    async fn get_network_id(&self) -> Result<NetworkId, RpcError> {
+        // Derived from the node endpoint, not sent to the node
+        Ok(self.network_id.clone())
+    }
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Configure the node RPC rate limit of a handle
+///
+/// Calls beyond the budget wait for a token instead of failing.
+///
+/// # Parameters
+/// - `calls_per_second`: Sustained rate (0 disables rate limiting)
+/// - `burst`: Number of calls allowed back-to-back after an idle period (at least 1)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Negative or non-finite rate
+/// - -2: Invalid handle
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_set_rpc_rate_limit(
+    handle: MidenHandle,
+    calls_per_second: f64,
+    burst: u32,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if !calls_per_second.is_finite() || calls_per_second < 0.0 {
+        return ERR_INVALID_PARAM;
+    }
+
+    worker.rpc_limiter.configure(calls_per_second, burst);
+    0
+}
+
+/// Get rate limiter configuration and throttle statistics
+///
+/// # Output JSON
+/// `{"enabled":true,"calls_per_second":2.0,"burst":5,"available_tokens":3,"calls":120,
+///   "throttled_calls":14,"total_wait_ms":6200}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_rpc_stats(
+    handle: MidenHandle,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    write_out_buffer(worker.rpc_limiter.to_json().as_bytes(), json_out, json_out_len)
+}
@@ -0,0 +1,342 @@
+//! Read path running next to the worker
+//!
+//! The worker handles one request at a time, so a blocking `get_accounts` used to wait
+//! behind a sync that spent most of its time on node calls. The blocking account, balance
+//! and input note queries now run on a reader thread of the handle instead, with its own
+//! client on the same SQLite store. SQLite isolates the two: a query sees the store as of
+//! the last committed write, so it runs while a sync or transaction is in progress and
+//! reports what was there before it. A write commits before the worker replies, so a
+//! query made after a write returned sees it. The reader's client has none of the
+//! injected-failure points of the `testing` feature, so reads never use up a failure meant
+//! for a worker request.
+//!
+//! The input note query also needs the archived accounts and block timestamps, which the
+//! worker keeps in memory. It publishes a copy to the read path when the archive changes
+//! (before replying) and after each request that may have recorded block times.
+//!
+//! The worker stops the read path while it needs the store to itself (rekey, restore; see
+//! `park_client`) and when it shuts down. Queries made while it is stopped go through the
+//! worker queue as before, as do all queries of in-memory clients: their shared-cache
+//! database reports a table lock to readers instead of waiting for the writer. Async
+//! queries also go through the worker, since their callbacks run on the worker thread.
+
+use std::{
+    future::Future,
+    sync::{Arc, Mutex, RwLock},
+    thread::JoinHandle,
+    time::Instant,
+};
+
+use miden_objects::account::AccountId;
+use tokio::sync::mpsc;
+
+use crate::{
+    archive::Archive, block_times::BlockTimes, build_reader_client, diagnostics,
+    get_accounts_impl, get_balance_impl, get_input_notes_impl, last_error, memory_store, tenants,
+    MidenClient, MidenContext, ReadView, ERR_TIMEOUT, SYNC_TIMEOUT,
+};
+
+/// A read-only query served by the read path
+pub(crate) enum ReadQuery {
+    Accounts,
+    Balance(AccountId, String),
+    InputNotes(Option<AccountId>),
+}
+
+impl ReadQuery {
+    fn name(&self) -> &'static str {
+        match self {
+            ReadQuery::Accounts => "get_accounts",
+            ReadQuery::Balance(..) => "get_balance",
+            ReadQuery::InputNotes(_) => "get_input_notes",
+        }
+    }
+}
+
+type Job = (ReadQuery, std::sync::mpsc::Sender<Result<String, i32>>);
+
+/// In-memory worker state the queries read, as last published by the worker
+struct Published {
+    archive: RwLock<Arc<Archive>>,
+    block_times: RwLock<Arc<BlockTimes>>,
+}
+
+struct Reader {
+    jobs: mpsc::UnboundedSender<Job>,
+    thread: JoinHandle<()>,
+}
+
+/// Reader thread of a handle, shared between the handle and its worker
+pub(crate) struct ReadPath {
+    reader: Mutex<Option<Reader>>,
+    published: Arc<Published>,
+    last_error: Arc<last_error::LastError>,
+}
+
+impl ReadPath {
+    pub fn new(last_error: Arc<last_error::LastError>) -> Self {
+        Self {
+            reader: Mutex::new(None),
+            published: Arc::new(Published {
+                archive: RwLock::new(Arc::new(Archive::default())),
+                block_times: RwLock::new(Arc::new(BlockTimes::default())),
+            }),
+            last_error,
+        }
+    }
+
+    /// Start the reader on the context's store (worker only; nothing for an in-memory store)
+    pub fn start(&self, context: &MidenContext) {
+        if memory_store::is_in_memory(&context.store_path) {
+            return;
+        }
+        self.publish_archive(&context.archive);
+        self.publish_block_times(&context.block_times);
+
+        let rpc = context.rpc.clone();
+        let database_path = context.database_path.clone();
+        // Its own scope: the worker lifts its filter during syncs (see tenants.rs)
+        let tenant = context.tenant.as_ref().map(|scope| {
+            Arc::new(tenants::TenantScope::new(scope.tenant_id().to_string()))
+        });
+        let authenticator = context.authenticator.clone();
+        let debug = context.connection.debug;
+        self.spawn(debug, move || {
+            build_reader_client(rpc, database_path, tenant, authenticator, debug)
+        });
+    }
+
+    /// Start the reader thread on the client `build` opens there
+    fn spawn<F>(&self, debug: bool, build: impl FnOnce() -> F + Send + 'static)
+    where
+        F: Future<Output = Result<MidenClient, String>>,
+    {
+        let (jobs, mut queue) = mpsc::unbounded_channel::<Job>();
+        let published = self.published.clone();
+        let slot = self.last_error.clone();
+
+        let thread = std::thread::spawn(move || {
+            last_error::install(slot);
+            diagnostics::install(debug);
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create Tokio runtime");
+
+            rt.block_on(async move {
+                // Without a client the queue closes and queries fall back to the worker
+                let client = match build().await {
+                    Ok(client) => client,
+                    Err(e) => return diagnostics::log("read_path", e),
+                };
+                while let Some((query, reply)) = queue.recv().await {
+                    let operation = query.name();
+                    let started = Instant::now();
+                    last_error::clear_pending();
+
+                    let archive = published.archive.read().unwrap().clone();
+                    let block_times = published.block_times.read().unwrap().clone();
+                    let view =
+                        ReadView { client: &client, archive: &archive, block_times: &block_times };
+                    let result = match query {
+                        ReadQuery::Accounts => get_accounts_impl(&view).await,
+                        ReadQuery::Balance(account_id, account_id_str) => {
+                            get_balance_impl(&view, account_id, &account_id_str).await
+                        }
+                        ReadQuery::InputNotes(account_id) => {
+                            get_input_notes_impl(&view, account_id).await
+                        }
+                    };
+                    if let Err(code) = result {
+                        last_error::store(operation, code);
+                    }
+                    diagnostics::end_operation(operation, started.elapsed());
+                    let _ = reply.send(result);
+                }
+            });
+        });
+        *self.reader.lock().unwrap() = Some(Reader { jobs, thread });
+    }
+
+    /// Stop the reader, waiting for its query in progress and for its store connections to
+    /// close (worker only)
+    pub fn stop(&self) {
+        let Some(Reader { jobs, thread }) = self.reader.lock().unwrap().take() else {
+            return;
+        };
+        drop(jobs);
+        let _ = thread.join();
+    }
+
+    /// Answer a query on the reader thread (None when the read path is stopped: ask the
+    /// worker instead)
+    pub fn query(&self, query: ReadQuery) -> Option<Result<String, i32>> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.reader.lock().unwrap().as_ref()?.jobs.send((query, tx)).ok()?;
+
+        match rx.recv_timeout(SYNC_TIMEOUT) {
+            Ok(result) => Some(result),
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => Some(Err(ERR_TIMEOUT)),
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => None,
+        }
+    }
+
+    /// Hand the reader the current archive (after each change, before replying)
+    pub fn publish_archive(&self, archive: &Archive) {
+        *self.published.archive.write().unwrap() = Arc::new(archive.clone());
+    }
+
+    /// Hand the reader the current block timestamps
+    pub fn publish_block_times(&self, block_times: &BlockTimes) {
+        *self.published.block_times.write().unwrap() = Arc::new(block_times.clone());
+    }
+}
+
+#[cfg(all(test, feature = "mock-node"))]
+mod tests {
+    use std::path::Path;
+
+    use miden_client::{
+        auth::AuthSecretKey, builder::ClientBuilder, keystore::FilesystemKeyStore,
+        testing::mock::MockRpcApi, transaction::TransactionRequestBuilder, Client,
+    };
+    use miden_client_sqlite_store::SqliteStore;
+    use miden_lib::account::{auth::AuthRpoFalcon512, faucets::BasicFungibleFaucet};
+    use miden_objects::{
+        account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType},
+        asset::{FungibleAsset, TokenSymbol},
+        note::NoteType,
+        Felt,
+    };
+    use rand::rngs::StdRng;
+
+    use super::*;
+    use crate::{auth::WalletAuthenticator, keystore::KeystoreRouter, wallet_core};
+
+    type TestClient = Client<FilesystemKeyStore<StdRng>>;
+
+    fn run(test: impl std::future::Future<Output = ()>) {
+        tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(test);
+    }
+
+    /// Client standing in for the worker's, on the store of `dir`
+    async fn worker_client(dir: &Path, rpc: Arc<MockRpcApi>) -> TestClient {
+        let keystore = FilesystemKeyStore::new(dir.join("keystore")).unwrap();
+        let store = SqliteStore::new(dir.join("store.sqlite3")).await.unwrap();
+        ClientBuilder::new()
+            .rpc(rpc)
+            .store(Arc::new(store))
+            .authenticator(Arc::new(keystore))
+            .in_debug_mode(true.into())
+            .build()
+            .await
+            .unwrap()
+    }
+
+    /// Read path on the store of `dir`, connected to `rpc`
+    fn start_read_path(dir: &Path, rpc: Arc<MockRpcApi>) -> ReadPath {
+        let read_path = ReadPath::new(Arc::new(last_error::LastError::default()));
+        let keystore_dir = dir.join("keystore");
+        let database_path = dir.join("store.sqlite3");
+        read_path.spawn(true, move || async move {
+            let keystore = KeystoreRouter::new(&keystore_dir, None)?;
+            let store = SqliteStore::new(database_path).await.map_err(|e| e.to_string())?;
+            ClientBuilder::new()
+                .rpc(rpc)
+                .store(Arc::new(store))
+                .authenticator(Arc::new(WalletAuthenticator::new(Arc::new(keystore))))
+                .in_debug_mode(true.into())
+                .build()
+                .await
+                .map_err(|e| e.to_string())
+        });
+        read_path
+    }
+
+    fn falcon_auth(dir: &Path) -> AccountComponent {
+        let keystore = FilesystemKeyStore::<StdRng>::new(dir.join("keystore")).unwrap();
+        let key = AuthSecretKey::new_rpo_falcon512();
+        keystore.add_key(&key).unwrap();
+        AuthRpoFalcon512::new(key.public_key().to_commitment()).into()
+    }
+
+    fn query_json(read_path: &ReadPath, query: ReadQuery) -> serde_json::Value {
+        let json = read_path.query(query).expect("read path stopped").unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn note_count(read_path: &ReadPath, account_id: AccountId) -> usize {
+        let notes = query_json(read_path, ReadQuery::InputNotes(Some(account_id)));
+        notes["notes"].as_array().unwrap().len()
+    }
+
+    #[test]
+    fn reads_run_beside_writes_and_see_finished_ones() {
+        run(async {
+            let dir = std::env::temp_dir()
+                .join(format!("miden-read-path-test-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(dir.join("keystore")).unwrap();
+            let rpc = Arc::new(MockRpcApi::default());
+
+            let mut client = worker_client(&dir, rpc.clone()).await;
+            let read_path = start_read_path(&dir, rpc.clone());
+
+            let wallet = wallet_core::build_wallet_account(
+                [1; 32],
+                falcon_auth(&dir),
+                AccountStorageMode::Public,
+            )
+            .unwrap();
+            wallet_core::add_account(&mut client, &wallet).await.unwrap();
+            let faucet = AccountBuilder::new([7; 32])
+                .account_type(AccountType::FungibleFaucet)
+                .storage_mode(AccountStorageMode::Public)
+                .with_auth_component(falcon_auth(&dir))
+                .with_component(
+                    BasicFungibleFaucet::new(TokenSymbol::new("TST").unwrap(), 8, Felt::new(1_000))
+                        .unwrap(),
+                )
+                .build()
+                .unwrap();
+            wallet_core::add_account(&mut client, &faucet).await.unwrap();
+            wallet_core::sync(&mut client).await.unwrap();
+
+            // Accounts added by the worker are seen once added
+            let accounts = query_json(&read_path, ReadQuery::Accounts);
+            assert_eq!(accounts.as_array().map(Vec::len), Some(2));
+
+            let asset = FungibleAsset::new(faucet.id(), 100).unwrap();
+            let request = TransactionRequestBuilder::new()
+                .build_mint_fungible_asset(asset, wallet.id(), NoteType::Public, client.rng())
+                .unwrap();
+            wallet_core::submit(&mut client, faucet.id(), request).await.unwrap();
+            rpc.prove_block();
+            wallet_core::sync(&mut client).await.unwrap();
+            assert_eq!(note_count(&read_path, wallet.id()), 1);
+
+            // A write in progress (the worker busy mid-request) neither blocks reads nor
+            // shows in them
+            let writer = rusqlite::Connection::open(dir.join("store.sqlite3")).unwrap();
+            writer.execute_batch("BEGIN IMMEDIATE; DELETE FROM input_notes;").unwrap();
+            assert_eq!(note_count(&read_path, wallet.id()), 1);
+            writer.execute_batch("ROLLBACK").unwrap();
+            drop(writer);
+
+            // The balance after the worker consumed the note
+            let note_ids = wallet_core::consumable_notes(&client, Some(wallet.id()))
+                .await
+                .unwrap()
+                .iter()
+                .map(|(record, _)| record.id())
+                .collect();
+            let request = wallet_core::consume_notes_request(note_ids).unwrap();
+            wallet_core::submit(&mut client, wallet.id(), request).await.unwrap();
+            let balance = query_json(&read_path, ReadQuery::Balance(wallet.id(), String::new()));
+            assert_eq!(balance["fungible_assets"][0]["amount"], 100);
+
+            read_path.stop();
+            let _ = std::fs::remove_dir_all(&dir);
+        });
+    }
+}
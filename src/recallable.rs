@@ -0,0 +1,274 @@
+//! Recallable payments
+//!
+//! Paying an account that may never come online (a typo'd but valid ID, an abandoned
+//! wallet) locks the funds in a note nobody consumes. A recallable payment is a P2IDE
+//! note with a reclaim height (the P2IDR note of earlier miden-lib versions): the target
+//! can consume it at any time, and from the reclaim block on the sender can consume it
+//! back into its own vault.
+//!
+//! Reclaimable notes are the sender's outgoing P2IDE notes whose reclaim block the sync
+//! height has reached and that nobody has consumed yet (as far as the last sync knows).
+//! Reclaiming consumes them as unauthenticated notes, so the node rejects the transaction
+//! if the target consumed one in the meantime. Escrow and stream payouts are P2IDE notes
+//! too and are listed; refund escrows with `wc_miden_refund_escrow` so their state follows.
+
+use std::{ffi::CStr, os::raw::c_char};
+
+use miden_client::{store::NoteFilter, transaction::TransactionRequestBuilder};
+use miden_objects::{
+    account::AccountId,
+    note::{Note, NoteId, NoteType},
+};
+
+use crate::{
+    check_out_capacity, clock, get_handle, json, last_error, note_summary, parse_account_id,
+    parse_note_ids_json, parse_send_ids, proving, request_blocking, screening,
+    types::WcNoteType,
+    write_out_buffer, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE, ERR_INVALID_PARAM,
+    ERR_NOTE_OP, ERR_TX_SUBMIT, ID_HEX_LEN, SEND_RESULT_LEN,
+};
+
+/// Outgoing notes of `account_id` it can reclaim now, with their reclaim blocks
+async fn reclaimable_notes(
+    context: &MidenContext,
+    account_id: AccountId,
+) -> Result<Vec<(Note, u64)>, i32> {
+    let current_block = u64::from(clock::current_block(context).await?);
+    let records = context.client.get_output_notes(NoteFilter::All).await
+        .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?;
+
+    Ok(records
+        .into_iter()
+        .filter(|record| record.metadata().sender() == account_id && !record.is_consumed())
+        .filter_map(|record| {
+            let reclaim_block = record.recipient().and_then(note_summary::reclaim_height)?;
+            if reclaim_block > current_block {
+                return None;
+            }
+            // Only notes created by this store have the full details needed to consume them
+            Some((Note::try_from(record).ok()?, reclaim_block))
+        })
+        .collect())
+}
+
+pub(crate) async fn get_reclaimable_notes_impl(
+    context: &MidenContext,
+    account_id: AccountId,
+) -> Result<String, i32> {
+    let notes: Vec<serde_json::Value> = reclaimable_notes(context, account_id).await?
+        .iter()
+        .map(|(note, reclaim_block)| {
+            serde_json::json!({
+                "note_id": note.id().to_hex(),
+                "target_account_id": note_summary::target_account(note.recipient())
+                    .map(|target| target.to_hex()),
+                "assets": json::AssetJson::fungible(note.assets().iter()),
+                "reclaim_block": reclaim_block,
+            })
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "current_block": clock::current_block(context).await?,
+        "notes": notes,
+    })
+    .to_string())
+}
+
+pub(crate) async fn reclaim_notes_impl(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    note_ids: Vec<NoteId>,
+) -> Result<String, i32> {
+    let mut reclaimable = reclaimable_notes(context, account_id).await?;
+    // Every requested note must be reclaimable now
+    let mut notes = Vec::with_capacity(note_ids.len());
+    for note_id in &note_ids {
+        let index = reclaimable.iter().position(|(note, _)| note.id() == *note_id);
+        let (note, _) = reclaimable.swap_remove(index.ok_or(ERR_NOTE_OP)?);
+        notes.push((note, None));
+    }
+    let reclaimed: Vec<Note> = notes.iter().map(|(note, _)| note.clone()).collect();
+    screening::screen_consumed_notes(context, account_id, &reclaimed)?;
+
+    let tx_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes(notes)
+        .build()
+        .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?;
+
+    proving::check_memory(context)?;
+    let tx_id = context.client.submit_new_transaction(account_id, tx_request).await
+        .map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))?;
+    Ok(tx_id.to_hex())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Send fungible assets in a note the sender can recall from a given block (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+/// NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+///
+/// # Parameters
+/// - `account_id_hex`: Sending account ID (C string)
+/// - `target_account_id_hex`: Receiving account ID (C string)
+/// - `faucet_id_hex`: Faucet of the sent asset (C string)
+/// - `amount`: Amount in base units (must be > 0)
+/// - `reclaim_block`: Block from which the sender can recall the note (must be after the
+///   current sync height)
+/// - `note_type`: `WcNoteType` value (0 = public, 1 = private)
+/// - `json_out` / `json_out_len`: Output buffer for `{"tx_id":"0x..","note_id":"0x.."}`,
+///   plus `"note_file"` (hex NoteFile for the recipient) for a private note
+///
+/// # Returns
+/// Same codes as `wc_miden_send`; -1 also for a reclaim block that is not in the future.
+/// For a private note only the result without `note_file` is checked up front, as in
+/// `wc_miden_send_private`: a -7 with `*json_out_len` above 157 means the transaction was
+/// submitted; do not retry it.
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub extern "C" fn wc_miden_send_recallable(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    target_account_id_hex: *const c_char,
+    faucet_id_hex: *const c_char,
+    amount: u64,
+    reclaim_block: u32,
+    note_type: u32,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if amount == 0 {
+        return ERR_INVALID_PARAM;
+    }
+    if let Err(code) = check_out_capacity(json_out, json_out_len, SEND_RESULT_LEN) {
+        return code;
+    }
+    let note_type: NoteType = match WcNoteType::try_from(note_type) {
+        Ok(note_type) => note_type.into(),
+        Err(code) => return code,
+    };
+
+    let (account_id, target, faucet_id) =
+        match parse_send_ids(account_id_hex, target_account_id_hex, faucet_id_hex) {
+            Ok(ids) => ids,
+            Err(code) => return code,
+        };
+
+    let reclaim_block = Some(reclaim_block);
+    let request = |reply| Request::Send {
+        account_id, target, faucet_id, amount, note_type, reclaim_block, reply,
+    };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// List the outgoing notes an account can reclaim now (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Based on the last sync: sync first so notes consumed by their target are not listed.
+///
+/// # Parameters
+/// - `account_id_hex`: Sending account ID (C string)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"current_block","notes":[{"note_id","target_account_id","assets":[{"faucet_id",
+///   "amount"}],"reclaim_block"}]}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID
+/// - -4: Note query failed
+/// - -5: Sync height unavailable
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_reclaimable_notes(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let account_id = match parse_account_id(account_id_hex) {
+        Ok((account_id, _)) => account_id,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::GetReclaimableNotes { account_id, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Consume reclaimable outgoing notes back into the sending account (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+/// NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+///
+/// # Parameters
+/// - `account_id_hex`: Sending account ID (C string)
+/// - `note_ids_json`: JSON array of note IDs, each listed by `wc_miden_get_reclaimable_notes`
+/// - `tx_id_out` / `tx_id_out_len`: Output buffer for the transaction ID (hex)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID
+/// - -4: Invalid note ID list, or a note that is not reclaimable (yet)
+/// - -5: Sync height unavailable
+/// - -6: Transaction failed (e.g. the target consumed a note since the last sync)
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+/// - -102: Blocked by the screening callback
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_reclaim_notes(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    note_ids_json: *const c_char,
+    tx_id_out: *mut u8,
+    tx_id_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if note_ids_json.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+    if let Err(code) = check_out_capacity(tx_id_out, tx_id_out_len, ID_HEX_LEN) {
+        return code;
+    }
+
+    let account_id = match parse_account_id(account_id_hex) {
+        Ok((account_id, _)) => account_id,
+        Err(code) => return code,
+    };
+
+    let note_ids = match unsafe { CStr::from_ptr(note_ids_json) }.to_str() {
+        Ok(json) => match parse_note_ids_json(json) {
+            Ok(ids) if !ids.is_empty() => ids,
+            _ => return ERR_NOTE_OP,
+        },
+        Err(_) => return ERR_INVALID_PARAM,
+    };
+
+    match request_blocking(&worker, |reply| Request::ReclaimNotes { account_id, note_ids, reply }) {
+        Ok(tx_id) => write_out_buffer(&tx_id, tx_id_out, tx_id_out_len),
+        Err(code) => code,
+    }
+}
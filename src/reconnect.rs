@@ -0,0 +1,101 @@
+//! Automatic reconnect after persistent transport failures
+//!
+//! The gRPC channel of a handle is created once. When the device changes networks
+//! (wifi → cellular) the channel can go stale, and every node call then times out until
+//! the app recreates the handle. Sync failures are classified: a failure to reach the
+//! node counts towards [`RECONNECT_AFTER_FAILURES`] consecutive transport failures, any
+//! successful sync resets the count, and errors reported by the node itself (a rejected
+//! request) neither count nor reset.
+//!
+//! Once the threshold is reached, the worker rebuilds the RPC client, the node proxy's
+//! channel (see node_proxy.rs) and the `miden-client` instance on top of the same store
+//! and keystore after the current request has been answered, and emits a `reconnected` event:
+//! `{"type":"reconnected","endpoint","failures"}`. Requests queued meanwhile use the new
+//! channel. A failed rebuild keeps the old client and is retried after the next failures.
+
+use std::sync::Arc;
+
+use miden_client::rpc::{Endpoint, GrpcClient};
+
+use crate::{build_client, diagnostics, wallet_core::CoreError, MidenContext};
+
+/// Consecutive transport failures that trigger a reconnect
+pub const RECONNECT_AFTER_FAILURES: u32 = 3;
+
+/// Parts of client errors (debug formatting) that mean the node was not reached
+const TRANSPORT_MARKERS: [&str; 5] = [
+    "ConnectionError",
+    "transport error",
+    "Unavailable",
+    "DeadlineExceeded",
+    "timed out",
+];
+
+/// Connection settings of a handle, kept to rebuild the client
+pub(crate) struct Connection {
+    pub endpoint: Endpoint,
+    pub timeout_ms: u64,
+    pub debug: bool,
+    consecutive_failures: u32,
+}
+
+impl Connection {
+    pub fn new(endpoint: Endpoint, timeout_ms: u64, debug: bool) -> Self {
+        Self { endpoint, timeout_ms, debug, consecutive_failures: 0 }
+    }
+}
+
+/// Whether a failed node call failed because the node could not be reached
+pub(crate) fn is_transport_error(error: &impl std::fmt::Debug) -> bool {
+    let text = format!("{:?}", error);
+    TRANSPORT_MARKERS.iter().any(|marker| text.contains(marker))
+}
+
+/// Count a failed node call if the node could not be reached
+pub(crate) fn note_failure(context: &mut MidenContext, error: &CoreError) {
+    if error.is_unreachable() {
+        context.connection.consecutive_failures += 1;
+    }
+}
+
+/// Reset the failure count after a successful node call
+pub(crate) fn note_success(context: &mut MidenContext) {
+    context.connection.consecutive_failures = 0;
+}
+
+/// Rebuild the client if transport failures persisted (worker event loop, between requests)
+pub(crate) async fn reconnect_if_needed(context: &mut MidenContext) {
+    let failures = context.connection.consecutive_failures;
+    if failures < RECONNECT_AFTER_FAILURES {
+        return;
+    }
+    context.connection.consecutive_failures = 0;
+
+    let connection = &context.connection;
+    context.node_proxy.reconnect();
+    let grpc = GrpcClient::new(context.node_proxy.endpoint(), connection.timeout_ms);
+    let rpc = Arc::new(context.rpc.reconnected(grpc));
+    let built = build_client(
+        rpc.clone(),
+        context.database_path.clone(),
+        context.tenant.clone(),
+        context.authenticator.clone(),
+        connection.debug,
+    )
+    .await;
+
+    match built {
+        Ok(client) => {
+            context.client = client;
+            context.rpc = rpc;
+            let message = format!("rebuilt RPC client after {} failures", failures);
+            diagnostics::log("reconnect", message);
+            context.events.emit(serde_json::json!({
+                "type": "reconnected",
+                "endpoint": context.connection.endpoint.to_string(),
+                "failures": failures,
+            }));
+        }
+        Err(e) => diagnostics::log("reconnect", format!("rebuild failed: {}", e)),
+    }
+}
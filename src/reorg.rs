@@ -0,0 +1,133 @@
+//! Chain reorganization detection
+//!
+//! Before every sync the worker records the node's tip header as a checkpoint, keeping the
+//! most recent ones. At the start of the next sync the node is asked for the header at the
+//! newest checkpoint height again; a different commitment means the node switched to a
+//! divergent chain after the last block that was verified consistent.
+//!
+//! On a mismatch the older checkpoints are checked the same way, newest first, to find
+//! the last block both chains share. The store's sync height is rewound to that block and
+//! the block headers above it are dropped, in one store transaction, so the sync that
+//! follows fetches the node's chain again from there. A `chain_reorg` event reports the
+//! affected range; `rolled_back` is false when no checkpoint still matches or the rewind
+//! failed, and the app can then re-import affected accounts or warn the user.
+//!
+//! The client has no rollback API, so the rewind writes the two sync tables of its store
+//! directly. Notes and transactions are left alone: the re-sync updates the ones the new
+//! chain still contains.
+
+use std::collections::VecDeque;
+
+use miden_client::rpc::NodeRpcClient;
+use miden_objects::{block::BlockHeader, Word};
+
+use crate::{
+    diagnostics,
+    MidenContext,
+};
+
+/// Checkpoints kept to look for the last block shared with the node's chain
+const MAX_CHECKPOINTS: usize = 16;
+
+#[derive(Default)]
+pub(crate) struct ReorgGuard {
+    /// (block number, commitment) of the node tips seen at recent syncs, oldest first
+    checkpoints: VecDeque<(u32, Word)>,
+    /// Highest checkpoint the node confirmed unchanged
+    verified_height: u32,
+}
+
+/// Whether the node's current header at `block_num` still has `commitment`
+///
+/// None when the node could not be asked.
+async fn node_matches(context: &MidenContext, block_num: u32, commitment: Word) -> Option<bool> {
+    match context.rpc.get_block_header_by_number(Some(block_num.into()), false).await {
+        Ok((header, _)) => Some(header.commitment() == commitment),
+        Err(e) => {
+            diagnostics::log("reorg", format!("checkpoint request failed: {:?}", e));
+            None
+        }
+    }
+}
+
+/// Rewind the store's sync height to `block_num`, dropping the block headers above it
+fn rewind_store(context: &MidenContext, block_num: u32) -> rusqlite::Result<()> {
+    let mut conn = rusqlite::Connection::open(&context.database_path)?;
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM block_headers WHERE block_num > ?1", [block_num])?;
+    tx.execute("UPDATE state_sync SET block_num = ?1", [block_num])?;
+    tx.commit()
+}
+
+/// Compare the node's current headers at the checkpoint heights with the recorded ones,
+/// rewinding to the last matching block on a mismatch
+pub(crate) async fn check(context: &mut MidenContext) {
+    let Some(&(block_num, commitment)) = context.reorg.checkpoints.back() else {
+        return;
+    };
+
+    match node_matches(context, block_num, commitment).await {
+        Some(true) => {
+            context.reorg.verified_height = block_num;
+            return;
+        }
+        Some(false) => {}
+        None => return,
+    }
+
+    // Newest checkpoint the node still confirms
+    context.reorg.checkpoints.pop_back();
+    let mut common = None;
+    while let Some(&(block_num, commitment)) = context.reorg.checkpoints.back() {
+        match node_matches(context, block_num, commitment).await {
+            Some(true) => {
+                common = Some(block_num);
+                break;
+            }
+            Some(false) => {
+                context.reorg.checkpoints.pop_back();
+            }
+            None => break,
+        }
+    }
+
+    let local_height = context.progress.local_height();
+    let from_block = common.unwrap_or(context.reorg.verified_height) + 1;
+    let to_block = block_num.max(local_height);
+    diagnostics::log("reorg", format!("chain reorg detected in blocks {}..={}", from_block, to_block));
+
+    let rolled_back = match common {
+        Some(common) => match rewind_store(context, common) {
+            Ok(()) => {
+                context.reorg.verified_height = common;
+                context.progress.set_local_height(common);
+                true
+            }
+            Err(e) => {
+                diagnostics::log("reorg", format!("rewind to block {} failed: {}", common, e));
+                false
+            }
+        },
+        None => false,
+    };
+    context.events.emit(serde_json::json!({
+        "type": "chain_reorg",
+        "from_block": from_block,
+        "to_block": to_block,
+        "local_height": local_height,
+        "rolled_back": rolled_back,
+    }));
+}
+
+/// Record the node tip seen before a sync as the next checkpoint
+pub(crate) fn set_checkpoint(context: &mut MidenContext, tip: &BlockHeader) {
+    let checkpoints = &mut context.reorg.checkpoints;
+    let block_num = tip.block_num().as_u32();
+    while checkpoints.back().is_some_and(|(last, _)| *last >= block_num) {
+        checkpoints.pop_back();
+    }
+    checkpoints.push_back((block_num, tip.commitment()));
+    if checkpoints.len() > MAX_CHECKPOINTS {
+        checkpoints.pop_front();
+    }
+}
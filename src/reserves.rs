@@ -0,0 +1,212 @@
+//! Proof-of-reserves reports
+//!
+//! Exchanges and custodians publish reserve attestations: for a set of public accounts,
+//! the report lists each account's fungible balances together with the node's proof that
+//! the account's state commitment is in the account tree at the report block, and a
+//! signature by each account's auth key over the report digest (proving control).
+//!
+//! The node serves account proofs only at its chain tip, so reports are generated at the
+//! tip; a requested block number must match it. Balances come from the local store and
+//! are only attested when the local state commitment equals the proven one
+//! (`local_state_matches`); sync before generating a report. Private accounts have no
+//! public state to check balances against and are rejected.
+//!
+//! The report file as a whole can additionally be signed with `wc_miden_sign_export`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    os::raw::c_char,
+    path::PathBuf,
+};
+
+use miden_client::{
+    auth::{SigningInputs, TransactionAuthenticator},
+    rpc::{domain::account::AccountStorageRequirements, NodeRpcClient},
+    transaction::ForeignAccount,
+};
+use miden_objects::{
+    account::{AccountId, AccountStorageMode},
+    crypto::hash::rpo::Rpo256,
+    utils::Serializable,
+    Word,
+};
+
+use crate::{
+    clock, get_handle, parse_required_str, request_blocking, MidenContext, MidenHandle, Request,
+    ERR_ACCOUNT_OP, ERR_INVALID_HANDLE, ERR_INVALID_PARAM, ERR_LOOKUP,
+};
+
+/// Version tag of the report format
+pub const POR_REPORT_VERSION: u32 = 1;
+
+/// Maximum number of accounts in one report
+pub const POR_MAX_ACCOUNTS: usize = 100;
+
+/// Storage slot of the RPO Falcon512 auth component's public key commitment
+pub(crate) const AUTH_PUB_KEY_SLOT: u8 = 0;
+
+fn parse_account_ids(json: &str) -> Result<Vec<AccountId>, i32> {
+    let ids: Vec<String> = serde_json::from_str(json).map_err(|_| ERR_INVALID_PARAM)?;
+    if ids.is_empty() || ids.len() > POR_MAX_ACCOUNTS {
+        return Err(ERR_INVALID_PARAM);
+    }
+
+    let mut unique = BTreeSet::new();
+    ids.iter()
+        .map(|id| AccountId::from_hex(id).map_err(|_| ERR_ACCOUNT_OP))
+        .filter(|id| id.as_ref().map_or(true, |id| unique.insert(*id)))
+        .collect()
+}
+
+pub(crate) async fn generate_por_report_impl(
+    context: &mut MidenContext,
+    account_ids: Vec<AccountId>,
+    block_num: u32,
+    out_path: PathBuf,
+) -> Result<String, i32> {
+    let requests = account_ids
+        .iter()
+        .map(|id| {
+            if id.storage_mode() == AccountStorageMode::Private {
+                return Err(ERR_ACCOUNT_OP);
+            }
+            ForeignAccount::public(*id, AccountStorageRequirements::default())
+                .map_err(|_| ERR_ACCOUNT_OP)
+        })
+        .collect::<Result<BTreeSet<_>, _>>()?;
+
+    let (proof_block, proofs) = context.rpc.get_account_proofs(&requests, BTreeMap::new()).await
+        .map_err(|_| ERR_LOOKUP)?;
+    if block_num != 0 && proof_block.as_u32() != block_num {
+        return Err(ERR_LOOKUP);
+    }
+
+    let mut accounts = Vec::with_capacity(proofs.len());
+    let mut totals: BTreeMap<String, u64> = BTreeMap::new();
+    let mut signers = Vec::new();
+
+    for proof in &proofs {
+        let record = context.client.get_account(proof.account_id()).await
+            .map_err(|_| ERR_LOOKUP)?
+            .ok_or(ERR_LOOKUP)?;
+        let account = record.account();
+        let local_state_matches = account.commitment() == proof.account_commitment();
+
+        let balances: Vec<serde_json::Value> = account.vault().assets()
+            .filter(|asset| asset.is_fungible())
+            .map(|asset| {
+                let fungible = asset.unwrap_fungible();
+                if local_state_matches {
+                    *totals.entry(fungible.faucet_id().to_hex()).or_default() += fungible.amount();
+                }
+                serde_json::json!({
+                    "faucet_id": fungible.faucet_id().to_hex(),
+                    "amount": fungible.amount(),
+                })
+            })
+            .collect();
+
+        accounts.push(serde_json::json!({
+            "account_id": proof.account_id().to_hex(),
+            "account_commitment": hex::encode(proof.account_commitment().to_bytes()),
+            "local_state_matches": local_state_matches,
+            "balances": balances,
+            "merkle_proof": hex::encode(proof.merkle_proof().to_bytes()),
+        }));
+
+        let pub_key = account.storage().get_item(AUTH_PUB_KEY_SLOT).map_err(|_| ERR_ACCOUNT_OP)?;
+        signers.push((proof.account_id(), pub_key));
+    }
+
+    let body = serde_json::json!({
+        "version": POR_REPORT_VERSION,
+        "block_num": proof_block.as_u32(),
+        "generated_at": clock::now_unix(),
+        "accounts": accounts,
+        "totals": totals
+            .iter()
+            .map(|(faucet_id, amount)| {
+                serde_json::json!({ "faucet_id": faucet_id, "amount": amount })
+            })
+            .collect::<Vec<_>>(),
+    });
+    let body_json = body.to_string();
+    let digest: Word = Rpo256::hash(body_json.as_bytes());
+
+    let mut signatures = Vec::with_capacity(signers.len());
+    for (account_id, pub_key) in signers {
+        let signature = context.authenticator
+            .get_signature(pub_key.into(), &SigningInputs::Blind(digest))
+            .await
+            .map_err(|_| ERR_ACCOUNT_OP)?;
+        signatures.push(serde_json::json!({
+            "account_id": account_id.to_hex(),
+            "pub_key": hex::encode(pub_key.to_bytes()),
+            "signature": hex::encode(signature.to_bytes()),
+        }));
+    }
+
+    // The digest covers `report` exactly as written (compact JSON string)
+    let report = format!(
+        r#"{{"report":{},"digest":"{}","signatures":{}}}"#,
+        body_json,
+        hex::encode(digest.to_bytes()),
+        serde_json::Value::from(signatures),
+    );
+    std::fs::write(&out_path, report).map_err(|_| ERR_INVALID_PARAM)?;
+
+    Ok(out_path.to_string_lossy().into_owned())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Generate a signed proof-of-reserves report (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_ids_json`: JSON array of public account IDs (1-100), e.g. `["0x..","0x.."]`
+/// - `block_num`: Report block (0 = node tip; otherwise must equal the node tip)
+/// - `out_path`: Destination file of the report JSON (overwritten if present)
+///
+/// # Report JSON
+/// `{"report":{"version":1,"block_num","generated_at","accounts":[{"account_id",
+///   "account_commitment","local_state_matches","balances":[...],"merkle_proof"}],
+///   "totals":[{"faucet_id","amount"}]},"digest":"..","signatures":[{"account_id","pub_key",
+///   "signature"}]}`. `digest` is the RPO hash of the `report` JSON text; totals only count
+/// accounts whose local state matches the proof.
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or the file could not be written
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid or private account ID, or signing failed
+/// - -5: Account not found, proof request failed, or `block_num` is not the node tip
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_generate_por_report(
+    handle: MidenHandle,
+    account_ids_json: *const c_char,
+    block_num: u32,
+    out_path: *const c_char,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let account_ids = match parse_required_str(account_ids_json).and_then(parse_account_ids) {
+        Ok(ids) => ids,
+        Err(code) => return code,
+    };
+    let out_path = match parse_required_str(out_path) {
+        Ok(path) => PathBuf::from(path),
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::GeneratePorReport { account_ids, block_num, out_path, reply };
+    match request_blocking(&worker, request) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,298 @@
+//! Store retention policy
+//!
+//! Mobile stores grow without bound: every consumed note and every transaction record is
+//! kept forever by the client. A retention policy selects what has outlived its use:
+//!
+//! - consumed input notes once they have been consumed for longer than
+//!   `consumed_note_max_age_days` (measured from the first sync that saw them consumed)
+//! - transaction records beyond the newest `max_transactions`, never pending ones
+//!
+//! The client exposes no deletion API, so the selected rows are deleted from the SQLite
+//! store directly (as reorg.rs rewinds block headers), together with the cached inclusion
+//! proofs of the selected notes (see proof_cache.rs), in one transaction. Nothing else in
+//! the store references consumed notes or finished transactions.
+//!
+//! The policy and the consumption timestamps are kept in a JSON file next to the SQLite
+//! store and applied automatically after every successful sync. Timestamps are only kept
+//! while the note age rule is set, and dropped with the notes they date.
+
+use std::{
+    collections::HashMap,
+    os::raw::c_char,
+    path::{Path, PathBuf},
+};
+
+use miden_client::{
+    store::{NoteFilter, TransactionFilter},
+    transaction::TransactionStatus,
+};
+
+use crate::{
+    clock, diagnostics, get_handle, memory_store, parse_required_str, proof_cache,
+    request_blocking,
+    write_out_buffer, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE, ERR_INVALID_PARAM,
+    ERR_LOOKUP, ERR_NOTE_OP,
+};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Retention rules; `None` disables the rule
+#[derive(Clone, Copy, Default, PartialEq)]
+pub(crate) struct RetentionPolicy {
+    consumed_note_max_age_days: Option<u32>,
+    max_transactions: Option<u32>,
+}
+
+impl RetentionPolicy {
+    /// Parse `{"consumed_note_max_age_days":90,"max_transactions":500,"dry_run":false}`
+    ///
+    /// Returns the policy and the `dry_run` flag.
+    pub fn from_json(json: &str) -> Result<(Self, bool), i32> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|_| ERR_INVALID_PARAM)?;
+        if !value.is_object() {
+            return Err(ERR_INVALID_PARAM);
+        }
+
+        let optional_u32 = |name: &str| -> Result<Option<u32>, i32> {
+            match value.get(name) {
+                None | Some(serde_json::Value::Null) => Ok(None),
+                Some(v) => v.as_u64()
+                    .and_then(|n| u32::try_from(n).ok())
+                    .map(Some)
+                    .ok_or(ERR_INVALID_PARAM),
+            }
+        };
+
+        let policy = Self {
+            consumed_note_max_age_days: optional_u32("consumed_note_max_age_days")?,
+            max_transactions: optional_u32("max_transactions")?,
+        };
+        let dry_run = value.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        Ok((policy, dry_run))
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Policy plus the bookkeeping needed to apply it, persisted next to the store
+pub(crate) struct Retention {
+    path: PathBuf,
+    policy: RetentionPolicy,
+    /// Note ID (hex) -> unix time at which the note was first seen consumed
+    consumed_since: HashMap<String, u64>,
+}
+
+/// What a retention pass selected, and the consumption timestamps to keep after it
+struct RetentionReport {
+    note_ids: Vec<String>,
+    transaction_ids: Vec<String>,
+    consumed_since: HashMap<String, u64>,
+}
+
+impl Retention {
+    /// Load the sidecar state for the store at `store_path` (missing or corrupt → no policy)
+    pub fn load(store_path: &Path) -> Self {
+        let path = store_path.with_extension("retention.json");
+        let mut retention = Self {
+            path,
+            policy: RetentionPolicy::default(),
+            consumed_since: HashMap::new(),
+        };
+
+        let Some(value) = std::fs::read_to_string(&retention.path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+        else {
+            return retention;
+        };
+
+        if let Some(policy) = value.get("policy") {
+            retention.policy = RetentionPolicy::from_json(&policy.to_string())
+                .map(|(policy, _)| policy)
+                .unwrap_or_default();
+        }
+        if let Some(since) = value.get("consumed_since") {
+            retention.consumed_since = serde_json::from_value(since.clone()).unwrap_or_default();
+        }
+        retention
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let json = serde_json::json!({
+            "policy": {
+                "consumed_note_max_age_days": self.policy.consumed_note_max_age_days,
+                "max_transactions": self.policy.max_transactions,
+            },
+            "consumed_since": self.consumed_since,
+        });
+        memory_store::write(&self.path, json.to_string())
+    }
+}
+
+/// Work out which notes and transactions `policy` selects (the context is left unchanged)
+async fn plan(context: &MidenContext, policy: RetentionPolicy) -> Result<RetentionReport, i32> {
+    let now = clock::now_unix();
+    let mut report = RetentionReport {
+        note_ids: Vec::new(),
+        transaction_ids: Vec::new(),
+        consumed_since: HashMap::new(),
+    };
+
+    if let Some(days) = policy.consumed_note_max_age_days {
+        let consumed = context.client.get_input_notes(NoteFilter::Consumed).await
+            .map_err(|_| ERR_NOTE_OP)?;
+
+        let max_age = u64::from(days) * SECONDS_PER_DAY;
+        for id in consumed.iter().map(|record| record.id().to_hex()) {
+            let seen = context.retention.consumed_since.get(&id).copied().unwrap_or(now);
+            if now.saturating_sub(seen) >= max_age {
+                report.note_ids.push(id);
+            } else {
+                report.consumed_since.insert(id, seen);
+            }
+        }
+    }
+
+    if let Some(max) = policy.max_transactions {
+        let mut transactions = context.client.get_transactions(TransactionFilter::All).await
+            .map_err(|_| ERR_LOOKUP)?;
+        // Newest first
+        transactions.sort_by_key(|tx| std::cmp::Reverse(tx.details.block_num));
+
+        report.transaction_ids = transactions
+            .iter()
+            .skip(max as usize)
+            .filter(|record| !matches!(record.status, TransactionStatus::Pending))
+            .map(|record| record.id.to_hex())
+            .collect();
+    }
+
+    Ok(report)
+}
+
+/// Delete the selected notes (with their cached proofs) and transactions from the store
+fn delete_selected(database_path: &Path, report: &RetentionReport) -> Result<(), i32> {
+    if report.note_ids.is_empty() && report.transaction_ids.is_empty() {
+        return Ok(());
+    }
+    let delete = || -> rusqlite::Result<()> {
+        let mut conn = rusqlite::Connection::open(database_path)?;
+        let tx = conn.transaction()?;
+        for id in &report.note_ids {
+            tx.execute("DELETE FROM input_notes WHERE note_id = ?1", [id])?;
+        }
+        for id in &report.transaction_ids {
+            tx.execute("DELETE FROM transactions WHERE id = ?1", [id])?;
+        }
+        proof_cache::evict(&tx, &report.note_ids)?;
+        tx.commit()
+    };
+    delete().map_err(|_| ERR_NOTE_OP)
+}
+
+/// Select what `policy` covers and, unless `dry_run`, delete it and keep the timestamps of
+/// the remaining consumed notes
+async fn run(context: &mut MidenContext, policy: RetentionPolicy, dry_run: bool) -> Result<RetentionReport, i32> {
+    let report = plan(context, policy).await?;
+
+    if !dry_run {
+        delete_selected(&context.database_path, &report)?;
+        context.retention.consumed_since = report.consumed_since.clone();
+    }
+
+    Ok(report)
+}
+
+/// Apply the stored policy after a successful sync (failures are logged, never surfaced)
+pub(crate) async fn apply_after_sync(context: &mut MidenContext) {
+    let policy = context.retention.policy;
+    if policy.is_empty() {
+        return;
+    }
+
+    match run(context, policy, false).await {
+        Ok(_) => {
+            if let Err(e) = context.retention.save() {
+                diagnostics::log("retention", format!("failed to save state: {:?}", e));
+            }
+        }
+        Err(code) => diagnostics::log("retention", format!("post-sync pass failed: {}", code)),
+    }
+}
+
+pub(crate) async fn set_retention_policy_impl(
+    context: &mut MidenContext,
+    policy: RetentionPolicy,
+    dry_run: bool,
+) -> Result<String, i32> {
+    let report = run(context, policy, dry_run).await?;
+
+    if !dry_run {
+        context.retention.policy = policy;
+        context.retention.save().map_err(|_| ERR_NOTE_OP)?;
+    }
+
+    Ok(serde_json::json!({
+        "dry_run": dry_run,
+        "note_ids": report.note_ids,
+        "transaction_ids": report.transaction_ids,
+    })
+    .to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Set the store retention policy (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// The policy is applied immediately and then automatically after every successful sync.
+/// The report lists the consumed notes and transactions the policy deleted from the store.
+/// With `"dry_run": true` it lists what would be deleted and nothing is changed: no rows
+/// are deleted and the stored policy and its state are left as they were.
+///
+/// # Policy JSON
+/// `{"consumed_note_max_age_days":90,"max_transactions":500,"dry_run":false}`
+/// (omitted or null fields disable the rule; `{}` disables the policy)
+///
+/// # Output JSON
+/// `{"dry_run":false,"note_ids":["0x.."],"transaction_ids":["0x.."]}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -4: Note lookup or deletion failed
+/// - -5: Transaction lookup failed
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_set_retention_policy(
+    handle: MidenHandle,
+    policy_json: *const c_char,
+    report_out: *mut u8,
+    report_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if report_out.is_null() || report_out_len.is_null() {
+        return ERR_INVALID_PARAM;
+    }
+
+    let (policy, dry_run) = match parse_required_str(policy_json).and_then(RetentionPolicy::from_json) {
+        Ok(v) => v,
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::SetRetentionPolicy { policy, dry_run, reply };
+    match request_blocking(&worker, request) {
+        Ok(report) => write_out_buffer(&report, report_out, report_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,245 @@
+//! Per-method node RPC metrics
+//!
+//! Teams running their own node want to see which calls are slow or failing. Every node
+//! call made by a handle, by this crate or by `miden-client` (sync, submission, imports),
+//! is timed under its gRPC method name (`SyncState`, `SubmitProvenTransaction`,
+//! `GetBlockHeaderByNumber`, ...) and recorded in a latency histogram together with an
+//! error breakdown by kind. The handle's node client records them (see rate_limit.rs), so a
+//! sync that needs several requests records each of them. `GetAccountProof` is the one
+//! exception: miden-client asks for the proofs of several accounts one request at a time,
+//! and the batch is recorded as one call.
+//!
+//! Latency excludes waiting for the RPC rate limiter. Metrics are kept per handle from its
+//! creation, shared with the worker like the rate limiter, and can be read as JSON or
+//! written to a file in the Prometheus text format for a node exporter; diagnostics
+//! bundles include them too.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Write},
+    future::Future,
+    os::raw::c_char,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    get_handle, parse_required_str, write_out_buffer, MidenHandle, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM,
+};
+
+/// Upper bounds (ms) of the latency histogram buckets; slower calls fall in `+Inf`
+const LATENCY_BUCKETS_MS: [u64; 10] = [10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000];
+
+#[derive(Default)]
+struct MethodMetrics {
+    calls: u64,
+    errors: u64,
+    total_duration: Duration,
+    /// Calls per bucket (not cumulative), the last one being `+Inf`
+    buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+    /// Failed calls by error kind
+    error_kinds: BTreeMap<String, u64>,
+}
+
+#[derive(Default)]
+pub(crate) struct RpcMetrics {
+    methods: Mutex<BTreeMap<&'static str, MethodMetrics>>,
+}
+
+fn leading_identifier(text: &str) -> &str {
+    let end = text
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(text.len());
+    &text[..end]
+}
+
+/// Kind of an error: its variant path from the `Debug` text, e.g. `RpcError.ConnectionError`
+fn error_kind(error: &impl Debug) -> String {
+    let text = format!("{:?}", error);
+    let outer = leading_identifier(&text);
+    if outer.is_empty() {
+        return "Other".to_string();
+    }
+
+    // A tuple variant wrapping another error: add the inner variant
+    match text[outer.len()..].strip_prefix('(').map(leading_identifier) {
+        Some(inner) if !inner.is_empty() => format!("{}.{}", outer, inner),
+        _ => outer.to_string(),
+    }
+}
+
+impl RpcMetrics {
+    /// Run one node call and record it under `method`
+    pub async fn time<T, E: Debug>(
+        &self,
+        method: &'static str,
+        call: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let started = Instant::now();
+        let result = call.await;
+        self.record(method, started.elapsed(), result.as_ref().err().map(error_kind));
+        result
+    }
+
+    fn record(&self, method: &'static str, elapsed: Duration, error_kind: Option<String>) {
+        let elapsed_ms = elapsed.as_millis() as u64;
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| elapsed_ms <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+
+        let mut methods = self.methods.lock().unwrap();
+        let metrics = methods.entry(method).or_default();
+        metrics.calls += 1;
+        metrics.total_duration += elapsed;
+        metrics.buckets[bucket] += 1;
+        if let Some(kind) = error_kind {
+            metrics.errors += 1;
+            *metrics.error_kinds.entry(kind).or_default() += 1;
+        }
+    }
+
+    /// Metrics by method, as reported by `wc_miden_get_rpc_metrics` under `methods`
+    pub fn snapshot(&self) -> serde_json::Value {
+        let methods = self.methods.lock().unwrap();
+        let methods: serde_json::Map<String, serde_json::Value> = methods
+            .iter()
+            .map(|(method, m)| {
+                let mut cumulative = 0;
+                let buckets: Vec<serde_json::Value> = m.buckets
+                    .iter()
+                    .enumerate()
+                    .map(|(i, count)| {
+                        cumulative += count;
+                        let le_ms = LATENCY_BUCKETS_MS.get(i);
+                        serde_json::json!({ "le_ms": le_ms, "count": cumulative })
+                    })
+                    .collect();
+
+                (method.to_string(), serde_json::json!({
+                    "calls": m.calls,
+                    "errors": m.errors,
+                    "total_ms": m.total_duration.as_millis() as u64,
+                    "buckets": buckets,
+                    "error_kinds": m.error_kinds,
+                }))
+            })
+            .collect();
+
+        serde_json::Value::Object(methods)
+    }
+
+    /// Metrics in the Prometheus text exposition format
+    fn to_prometheus(&self) -> String {
+        let methods = self.methods.lock().unwrap();
+        let mut out = String::new();
+
+        out.push_str("# HELP miden_rpc_latency_seconds Latency of node calls by method\n");
+        out.push_str("# TYPE miden_rpc_latency_seconds histogram\n");
+        for (method, m) in methods.iter() {
+            let mut cumulative = 0;
+            for (i, count) in m.buckets.iter().enumerate() {
+                cumulative += count;
+                let le = LATENCY_BUCKETS_MS
+                    .get(i)
+                    .map_or_else(|| "+Inf".to_string(), |ms| (*ms as f64 / 1000.0).to_string());
+                let _ = writeln!(
+                    out,
+                    "miden_rpc_latency_seconds_bucket{{method=\"{}\",le=\"{}\"}} {}",
+                    method, le, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "miden_rpc_latency_seconds_sum{{method=\"{}\"}} {}",
+                method,
+                m.total_duration.as_secs_f64()
+            );
+            let _ = writeln!(
+                out,
+                "miden_rpc_latency_seconds_count{{method=\"{}\"}} {}",
+                method, m.calls
+            );
+        }
+
+        out.push_str("# HELP miden_rpc_errors_total Failed node calls by method and error kind\n");
+        out.push_str("# TYPE miden_rpc_errors_total counter\n");
+        for (method, m) in methods.iter() {
+            for (kind, count) in &m.error_kinds {
+                let _ = writeln!(
+                    out,
+                    "miden_rpc_errors_total{{method=\"{}\",kind=\"{}\"}} {}",
+                    method, kind, count
+                );
+            }
+        }
+
+        out
+    }
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Get per-method node RPC metrics of a handle
+///
+/// Readable while the worker is busy; does not queue.
+///
+/// # Output JSON
+/// `{"methods":{"GetBlockHeaderByNumber":{"calls","errors","total_ms","buckets":[{"le_ms",
+///   "count"}],"error_kinds":{"RpcError.ConnectionError":2}}}}` (`buckets` cumulative as in
+///   Prometheus; the last bucket has `"le_ms":null`, i.e. +Inf)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_rpc_metrics(
+    handle: MidenHandle,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let json = serde_json::json!({ "methods": worker.rpc_metrics.snapshot() }).to_string();
+    write_out_buffer(json.as_bytes(), json_out, json_out_len)
+}
+
+/// Write per-method node RPC metrics of a handle to a file in the Prometheus text format
+///
+/// Does not queue. Point a node exporter's textfile collector at the file and call this
+/// periodically; the file is replaced atomically.
+///
+/// # Parameters
+/// - `path`: Destination file (C string), e.g. `.../miden_rpc.prom`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or the file could not be written
+/// - -2: Invalid handle
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_export_rpc_metrics(handle: MidenHandle, path: *const c_char) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+    let path = match parse_required_str(path) {
+        Ok(path) => std::path::PathBuf::from(path),
+        Err(code) => return code,
+    };
+
+    // Write next to the target, then rename, so collectors never read a partial file
+    let mut tmp_path = path.as_os_str().to_os_string();
+    tmp_path.push(".tmp");
+    let written = std::fs::write(&tmp_path, worker.rpc_metrics.to_prometheus())
+        .and_then(|_| std::fs::rename(&tmp_path, &path));
+    match written {
+        Ok(()) => 0,
+        Err(_) => ERR_INVALID_PARAM,
+    }
+}
@@ -0,0 +1,241 @@
+//! Sanctions / deny-list screening
+//!
+//! Compliance-focused integrators register a screening callback that is asked about the
+//! counterparty and amount of every transaction right before it is submitted:
+//!
+//! - outgoing: each output note; the counterparty is the target of P2ID notes and NULL
+//!   for other note scripts
+//! - incoming: each consumed note; the counterparty is the note's sender (the account
+//!   itself for recalled notes and refunded escrows)
+//!
+//! Every submit path is screened, including ephemeral sweeps, with these exemptions:
+//! faucet mints are issuance, not transfers, and account deployment and auth key rotation
+//! move no assets and have no counterparty to ask about.
+//!
+//! A note without fungible assets is screened once with a NULL faucet and amount 0. If
+//! the callback denies any item the transaction is aborted with `ERR_SCREENING_DENIED`
+//! and a `screening_denied` entry is appended to the audit log. Without a callback
+//! everything is allowed.
+
+use std::{
+    ffi::{c_void, CString},
+    os::raw::c_char,
+    sync::Mutex,
+};
+
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::AccountId,
+    asset::Asset,
+    note::{Note, NoteId},
+};
+use once_cell::sync::Lazy;
+
+use crate::{audit_log, diagnostics, MidenContext, ERR_NOTE_OP, ERR_SCREENING_DENIED};
+
+/// Direction of the screened transfer
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WcScreeningDirection {
+    Outgoing = 0,
+    Incoming = 1,
+}
+
+impl WcScreeningDirection {
+    fn as_str(self) -> &'static str {
+        match self {
+            WcScreeningDirection::Outgoing => "outgoing",
+            WcScreeningDirection::Incoming => "incoming",
+        }
+    }
+}
+
+/// Screening callback: (user_data, account_id, counterparty, faucet_id, amount, direction)
+/// -> allowed; NULL clears the registration
+///
+/// Strings are hex account IDs valid only during the call; `counterparty` and `faucet_id`
+/// may be NULL. Invoked on the worker thread, so the callback must not call back into
+/// the same handle.
+pub type WcScreeningCallback = Option<
+    extern "C" fn(
+        *mut c_void,
+        *const c_char,
+        *const c_char,
+        *const c_char,
+        u64,
+        WcScreeningDirection,
+    ) -> bool,
+>;
+
+type ScreeningFn = extern "C" fn(
+    *mut c_void,
+    *const c_char,
+    *const c_char,
+    *const c_char,
+    u64,
+    WcScreeningDirection,
+) -> bool;
+
+#[derive(Clone, Copy)]
+struct Screener {
+    callback: ScreeningFn,
+    user_data: usize,
+}
+
+static SCREENER: Lazy<Mutex<Option<Screener>>> = Lazy::new(|| Mutex::new(None));
+
+/// One counterparty/amount pair to screen
+struct Transfer {
+    counterparty: Option<AccountId>,
+    faucet_id: Option<AccountId>,
+    amount: u64,
+    direction: WcScreeningDirection,
+}
+
+impl Screener {
+    fn allows(&self, account_id: AccountId, transfer: &Transfer) -> bool {
+        let to_cstring = |id: AccountId| CString::new(id.to_hex()).ok();
+        let account_id = to_cstring(account_id);
+        let counterparty = transfer.counterparty.and_then(to_cstring);
+        let faucet_id = transfer.faucet_id.and_then(to_cstring);
+        let as_ptr = |s: &Option<CString>| s.as_ref().map_or(std::ptr::null(), |s| s.as_ptr());
+
+        (self.callback)(
+            self.user_data as *mut c_void,
+            as_ptr(&account_id),
+            as_ptr(&counterparty),
+            as_ptr(&faucet_id),
+            transfer.amount,
+            transfer.direction,
+        )
+    }
+}
+
+/// Split a note's assets into transfers with the given counterparty
+fn transfers<'a>(
+    counterparty: Option<AccountId>,
+    assets: impl Iterator<Item = &'a Asset>,
+    direction: WcScreeningDirection,
+) -> Vec<Transfer> {
+    let mut transfers: Vec<Transfer> = assets
+        .filter(|asset| asset.is_fungible())
+        .map(|asset| {
+            let fungible = asset.unwrap_fungible();
+            Transfer {
+                counterparty,
+                faucet_id: Some(fungible.faucet_id()),
+                amount: fungible.amount(),
+                direction,
+            }
+        })
+        .collect();
+
+    if transfers.is_empty() {
+        transfers.push(Transfer { counterparty, faucet_id: None, amount: 0, direction });
+    }
+    transfers
+}
+
+/// Target account of a P2ID note (inputs are `[target suffix, target prefix]`)
+pub(crate) fn p2id_target(note: &Note) -> Option<AccountId> {
+    if note.script().root() != WellKnownNote::P2ID.script_root() {
+        return None;
+    }
+    match note.inputs().values() {
+        [suffix, prefix, ..] => AccountId::try_from([*prefix, *suffix]).ok(),
+        _ => None,
+    }
+}
+
+fn check(
+    context: &MidenContext,
+    account_id: AccountId,
+    transfers: Vec<Transfer>,
+) -> Result<(), i32> {
+    let Some(screener) = *SCREENER.lock().unwrap() else {
+        return Ok(());
+    };
+
+    for transfer in transfers {
+        if screener.allows(account_id, &transfer) {
+            continue;
+        }
+
+        diagnostics::log("screening", "transaction denied by screening callback");
+        audit_log::record(context, "screening_denied", serde_json::json!({
+            "account_id": account_id.to_hex(),
+            "counterparty": transfer.counterparty.map(|id| id.to_hex()),
+            "faucet_id": transfer.faucet_id.map(|id| id.to_hex()),
+            "amount": transfer.amount,
+            "direction": transfer.direction.as_str(),
+        }));
+        return Err(ERR_SCREENING_DENIED);
+    }
+    Ok(())
+}
+
+/// Screen a transaction's output notes and consumed notes before submission
+pub(crate) async fn screen_transaction(
+    context: &MidenContext,
+    account_id: AccountId,
+    output_notes: &[Note],
+    consumed_note_ids: &[NoteId],
+) -> Result<(), i32> {
+    if SCREENER.lock().unwrap().is_none() {
+        return Ok(());
+    }
+
+    let mut pending = Vec::new();
+    for note in output_notes {
+        let target = p2id_target(note);
+        pending.extend(transfers(target, note.assets().iter(), WcScreeningDirection::Outgoing));
+    }
+    for note_id in consumed_note_ids {
+        let record = context.client.get_input_note(*note_id).await
+            .map_err(|_| ERR_NOTE_OP)?
+            .ok_or(ERR_NOTE_OP)?;
+        let sender = record.metadata().map(|metadata| metadata.sender());
+        pending.extend(transfers(sender, record.assets().iter(), WcScreeningDirection::Incoming));
+    }
+
+    check(context, account_id, pending)
+}
+
+/// Screen notes consumed without being in the store (recalls, escrow refunds)
+pub(crate) fn screen_consumed_notes(
+    context: &MidenContext,
+    account_id: AccountId,
+    notes: &[Note],
+) -> Result<(), i32> {
+    let pending = notes
+        .iter()
+        .flat_map(|note| {
+            let sender = Some(note.metadata().sender());
+            transfers(sender, note.assets().iter(), WcScreeningDirection::Incoming)
+        })
+        .collect();
+    check(context, account_id, pending)
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Set (or clear, with a NULL callback) the process-wide screening callback
+///
+/// Applies to every handle. Returning false from the callback aborts the transaction
+/// with -102 and records the denial in the handle's audit log.
+///
+/// # Returns
+/// - 0: Success
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_set_screening_callback(
+    callback: WcScreeningCallback,
+    user_data: *mut c_void,
+) -> i32 {
+    *SCREENER.lock().unwrap() = callback.map(|callback| Screener {
+        callback,
+        user_data: user_data as usize,
+    });
+    0
+}
@@ -0,0 +1,333 @@
+//! External signer registry
+//!
+//! Hardware wallets, MPC services or other out-of-process key holders register a set of
+//! C callbacks under a `scheme_id`. An account created with `wc_miden_create_wallet_with_signer`,
+//! or imported with `wc_miden_import_account_with_signer`, is bound to that scheme: its
+//! public key commitment is recorded in the keystore directory, and whenever the
+//! transaction kernel requests a signature for it the keystore router forwards the request
+//! to the registered callbacks instead of the filesystem keystore (the account's key
+//! backend is `callback`).
+//!
+//! Registrations are process-wide and must be made before any handle needs them.
+
+use std::{collections::HashMap, ffi::c_void, os::raw::c_char, sync::Mutex};
+
+use miden_lib::account::auth::AuthRpoFalcon512;
+use miden_objects::{
+    account::{auth::Signature, AccountComponent, AccountId, AccountStorageMode},
+    crypto::dsa::rpo_falcon512,
+    utils::{Deserializable, Serializable},
+    Word,
+};
+use once_cell::sync::Lazy;
+
+use crate::{
+    account_import, add_wallet_account, check_out_capacity, diagnostics, get_handle,
+    keystore::KeyBackend, last_error, parse_seed, parse_wallet_storage_mode, request_blocking,
+    reserves::AUTH_PUB_KEY_SLOT, wallet_core, write_out_buffer, MidenContext, MidenHandle,
+    Request, ACCOUNT_ID_HEX_LEN, ERR_ACCOUNT_OP, ERR_INVALID_HANDLE, ERR_INVALID_PARAM,
+};
+
+/// Scheme ID reserved for the built-in filesystem keystore
+pub const SIGNER_SCHEME_KEYSTORE: u32 = 0;
+
+/// Size of a serialized word (public key commitment or signed message)
+pub const WORD_BYTES: usize = 32;
+
+/// Upper bound for a serialized RPO Falcon512 signature returned by a signer
+pub const MAX_SIGNATURE_BYTES: usize = 4096;
+
+/// Callbacks implemented by an external signer
+///
+/// Both functions return 0 on success and a negative code on failure. They are invoked
+/// on the worker thread and may block (e.g. while the user confirms on a device).
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct WcSignerCallbacks {
+    /// Opaque pointer passed back to every callback
+    pub user_data: *mut c_void,
+    /// Write the 32-byte public key commitment of key `key_index` into `out` (capacity in `out_len`)
+    pub get_public_key_commitment: extern "C" fn(*mut c_void, u32, *mut u8, *mut usize) -> i32,
+    /// Sign the 32-byte `message` with the key whose commitment is `pub_key`, writing the
+    /// serialized RPO Falcon512 signature into `sig_out` (capacity in `sig_out_len`)
+    pub sign: extern "C" fn(*mut c_void, *const u8, *const u8, *mut u8, *mut usize) -> i32,
+}
+
+/// Registered callbacks (user_data stored as usize so the registry can be shared across threads)
+#[derive(Clone, Copy)]
+struct SignerEntry {
+    user_data: usize,
+    get_public_key_commitment: extern "C" fn(*mut c_void, u32, *mut u8, *mut usize) -> i32,
+    sign: extern "C" fn(*mut c_void, *const u8, *const u8, *mut u8, *mut usize) -> i32,
+}
+
+static SIGNERS: Lazy<Mutex<HashMap<u32, SignerEntry>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn lookup(scheme_id: u32) -> Option<SignerEntry> {
+    SIGNERS.lock().unwrap().get(&scheme_id).copied()
+}
+
+/// A key of a registered signer, selected when an account is created or imported
+#[derive(Clone, Copy)]
+pub(crate) struct SignerKey {
+    pub scheme_id: u32,
+    pub key_index: u32,
+}
+
+impl SignerKey {
+    /// Key `key_index` of the registered scheme `scheme_id`
+    fn new(scheme_id: u32, key_index: u32) -> Result<Self, i32> {
+        if lookup(scheme_id).is_none() {
+            return Err(ERR_INVALID_PARAM);
+        }
+        Ok(Self { scheme_id, key_index })
+    }
+}
+
+/// Ask a registered signer for the public key commitment of one of its keys
+pub(crate) fn public_key_commitment(scheme_id: u32, key_index: u32) -> Result<Word, i32> {
+    let entry = lookup(scheme_id).ok_or(ERR_INVALID_PARAM)?;
+
+    let mut buf = [0u8; WORD_BYTES];
+    let mut len = WORD_BYTES;
+    let code = (entry.get_public_key_commitment)(entry.user_data as *mut c_void, key_index, buf.as_mut_ptr(), &mut len);
+    if code != 0 {
+        return Err(ERR_ACCOUNT_OP);
+    }
+
+    Word::read_from_bytes(&buf[..len.min(WORD_BYTES)]).map_err(|_| ERR_ACCOUNT_OP)
+}
+
+/// Ask a registered signer to sign `message` with the key committed to by `pub_key`
+pub(crate) fn sign_with(scheme_id: u32, pub_key: Word, message: Word) -> Result<Signature, String> {
+    let entry = lookup(scheme_id).ok_or_else(|| format!("signer scheme {} is not registered", scheme_id))?;
+
+    let pub_key_bytes = pub_key.to_bytes();
+    let message_bytes = message.to_bytes();
+    let mut sig = vec![0u8; MAX_SIGNATURE_BYTES];
+    let mut sig_len = MAX_SIGNATURE_BYTES;
+
+    let code = (entry.sign)(
+        entry.user_data as *mut c_void,
+        pub_key_bytes.as_ptr(),
+        message_bytes.as_ptr(),
+        sig.as_mut_ptr(),
+        &mut sig_len,
+    );
+    if code != 0 {
+        return Err(format!("signer scheme {} failed with code {}", scheme_id, code));
+    }
+
+    sig.truncate(sig_len.min(MAX_SIGNATURE_BYTES));
+    rpo_falcon512::Signature::read_from_bytes(&sig)
+        .map(Signature::RpoFalcon512)
+        .map_err(|e| format!("signer scheme {} returned a malformed signature: {}", scheme_id, e))
+}
+
+pub(crate) async fn create_wallet_with_signer_impl(
+    context: &mut MidenContext,
+    init_seed: [u8; 32],
+    storage_mode: AccountStorageMode,
+    signer: SignerKey,
+) -> Result<String, i32> {
+    let SignerKey { scheme_id, key_index } = signer;
+    let commitment = public_key_commitment(scheme_id, key_index)?;
+    let auth_component: AccountComponent = AuthRpoFalcon512::new(commitment.into()).into();
+    let account = wallet_core::build_wallet_account(init_seed, auth_component, storage_mode)
+        .map_err(wallet_core::CoreError::into_code)?;
+
+    // Bound before the account is added so it can never exist without a signer; a binding
+    // made here is dropped again if the account is not added (`add_wallet_account` fails
+    // only while the account is not in the store)
+    let previous = context.keystore.external_scheme(&commitment);
+    context.keystore.bind_external_key(commitment, scheme_id)
+        .map_err(|_| ERR_ACCOUNT_OP)?;
+
+    let backend = KeyBackend::Callback(scheme_id);
+    let result = add_wallet_account(context, account, backend, None).await;
+
+    if result.is_err() {
+        let restored = match previous {
+            Some(previous) => context.keystore.bind_external_key(commitment, previous),
+            None => context.keystore.unbind_external_key(&commitment),
+        };
+        if let Err(e) = restored {
+            diagnostics::log("signer", format!("failed to drop signer binding: {}", e));
+        }
+    }
+    result
+}
+
+/// Bind an imported account to the signer key it authenticates with
+///
+/// The account is already in the store; when the key does not match or the binding cannot
+/// be saved it stays there without a signer, as after a plain import.
+pub(crate) async fn bind_imported_account(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    signer: SignerKey,
+) -> Result<(), i32> {
+    let SignerKey { scheme_id, key_index } = signer;
+    let commitment = public_key_commitment(scheme_id, key_index)?;
+
+    let record = context.client.get_account(account_id).await
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?
+        .ok_or(ERR_ACCOUNT_OP)?;
+    let pub_key = record.account().storage().get_item(AUTH_PUB_KEY_SLOT)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    if pub_key != commitment {
+        return Err(last_error::detail(
+            ERR_ACCOUNT_OP,
+            format!("account {} does not authenticate with this signer key", account_id.to_hex()),
+        ));
+    }
+
+    context.keystore.bind_external_key(commitment, scheme_id)
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    context.keystore.record_account(account_id, KeyBackend::Callback(scheme_id))
+        .map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Register (or replace) an external signer under `scheme_id`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: `scheme_id` is 0 (reserved for the filesystem keystore)
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_register_signer(scheme_id: u32, callbacks: WcSignerCallbacks) -> i32 {
+    if scheme_id == SIGNER_SCHEME_KEYSTORE {
+        return ERR_INVALID_PARAM;
+    }
+
+    SIGNERS.lock().unwrap().insert(scheme_id, SignerEntry {
+        user_data: callbacks.user_data as usize,
+        get_public_key_commitment: callbacks.get_public_key_commitment,
+        sign: callbacks.sign,
+    });
+    0
+}
+
+/// Remove an external signer registration
+///
+/// Accounts bound to the scheme fail to sign until it is registered again.
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_unregister_signer(scheme_id: u32) -> i32 {
+    match SIGNERS.lock().unwrap().remove(&scheme_id) {
+        Some(_) => 0,
+        None => ERR_INVALID_PARAM,
+    }
+}
+
+/// Create a wallet account whose key is held by a registered external signer (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `seed_ptr` / `seed_len`: 32-byte account seed (NULL generates a random seed)
+/// - `storage_mode`: `WcStorageMode` value (0 = public, 1 = private)
+/// - `scheme_id`: Registered signer scheme
+/// - `key_index`: Key index passed to the signer's `get_public_key_commitment`
+/// - `account_id_out` / `account_id_out_len`: Output buffer for the account ID (hex); must
+///   hold 32 bytes, a smaller one fails with -7 before the account is created
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, unknown scheme or `WcStorageMode_Network`
+/// - -2: Invalid handle or worker closed
+/// - -3: Signer or account creation failed
+/// - -7: Output buffer too small
+/// - -105: An account with the derived ID already exists in the store or on chain
+/// - -106: The store has not synced yet and `require_sync_for_create` is set
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_create_wallet_with_signer(
+    handle: MidenHandle,
+    seed_ptr: *const u8,
+    seed_len: usize,
+    storage_mode: u32,
+    scheme_id: u32,
+    key_index: u32,
+    account_id_out: *mut u8,
+    account_id_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if let Err(code) = check_out_capacity(account_id_out, account_id_out_len, ACCOUNT_ID_HEX_LEN) {
+        return code;
+    }
+
+    let signer = match SignerKey::new(scheme_id, key_index) {
+        Ok(signer) => signer,
+        Err(code) => return code,
+    };
+    let seed = match parse_seed(seed_ptr, seed_len) {
+        Ok(seed) => seed,
+        Err(code) => return code,
+    };
+    let storage_mode = match parse_wallet_storage_mode(storage_mode) {
+        Ok(storage_mode) => storage_mode,
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::CreateWalletWithSigner { seed, storage_mode, signer, reply };
+    match request_blocking(&worker, request) {
+        Ok(account_id) => write_out_buffer(&account_id, account_id_out, account_id_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Import a public account from the node and bind it to a registered signer (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Like `wc_miden_import_account_by_id`, but the account can sign from this store: its
+/// key is key `key_index` of the signer `scheme_id` (e.g. the hardware wallet that created
+/// it on another device). The account's auth public key must match that key; otherwise it
+/// is imported without a signer and the call fails with -3.
+///
+/// # Parameters
+/// - `account_id_hex`: Account ID (C string)
+/// - `scheme_id`: Registered signer scheme
+/// - `key_index`: Key index passed to the signer's `get_public_key_commitment`
+/// - `json_out` / `json_out_len`: Output buffer for the account details (same shape as
+///   `wc_miden_get_account_details`)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, unknown scheme or a private account
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID, no such account on the node, or the signer key does not match
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_import_account_with_signer(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    scheme_id: u32,
+    key_index: u32,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let account_id = match account_import::parse_public_account_id(account_id_hex) {
+        Ok(account_id) => account_id,
+        Err(code) => return code,
+    };
+    let signer = match SignerKey::new(scheme_id, key_index) {
+        Ok(signer) => Some(signer),
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::ImportAccountById { account_id, signer, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,335 @@
+//! Payment splitting
+//!
+//! Revenue-sharing apps attach a split policy to a receiving account: a list of
+//! beneficiaries with the percentage of every incoming payment they are owed. Applying
+//! the policy to a received note consumes it and creates one P2ID note per beneficiary
+//! in the same transaction, so the split is atomic.
+//!
+//! Shares are computed per fungible asset in basis points and rounded down; the rounding
+//! dust, the part not assigned to any beneficiary (percentages may sum to less than 100)
+//! and non-fungible assets stay with the receiving account. Split notes are subject to
+//! the account's withdrawal whitelist and the screening callback like any other send.
+//! Policies are kept in a JSON file next to the SQLite store.
+
+use std::{
+    collections::HashMap,
+    os::raw::c_char,
+    path::{Path, PathBuf},
+};
+
+use miden_client::transaction::TransactionRequestBuilder;
+use miden_lib::note::create_p2id_note;
+use miden_objects::{
+    account::AccountId,
+    asset::{Asset, FungibleAsset},
+    note::{Note, NoteId, NoteType},
+    transaction::OutputNote,
+    Felt,
+};
+
+use crate::{
+    check_out_capacity, get_handle, hex_placeholder, last_error, memory_store, output_len,
+    parse_account_id, parse_optional_str, parse_required_str, proving, request_blocking,
+    screening, whitelist, write_out_buffer, MidenContext, MidenHandle, Request, ERR_ACCOUNT_OP,
+    ERR_INVALID_HANDLE, ERR_INVALID_PARAM, ERR_LOOKUP, ERR_NOTE_OP, ERR_TX_SUBMIT,
+    ACCOUNT_ID_HEX_LEN, ID_HEX_LEN,
+};
+
+/// Maximum number of beneficiaries in one policy
+pub const SPLIT_MAX_BENEFICIARIES: usize = 16;
+
+const BPS_PER_PERCENT: f64 = 100.0;
+const TOTAL_BPS: u32 = 10_000;
+
+/// Beneficiaries and their shares in basis points (sum ≤ 10000)
+pub(crate) type SplitPolicy = Vec<(AccountId, u32)>;
+
+/// Parse `[{"account_id":"0x..","percent":12.5},...]` (percent with up to 2 decimals)
+pub(crate) fn parse_split_policy(json: &str) -> Result<SplitPolicy, i32> {
+    let entries: Vec<serde_json::Value> =
+        serde_json::from_str(json).map_err(|_| ERR_INVALID_PARAM)?;
+    if entries.is_empty() || entries.len() > SPLIT_MAX_BENEFICIARIES {
+        return Err(ERR_INVALID_PARAM);
+    }
+
+    let mut policy = SplitPolicy::with_capacity(entries.len());
+    for entry in &entries {
+        let account_id = entry["account_id"].as_str()
+            .and_then(|s| AccountId::from_hex(s).ok())
+            .ok_or(ERR_ACCOUNT_OP)?;
+        let bps = entry["percent"].as_f64()
+            .map(|percent| percent * BPS_PER_PERCENT)
+            .filter(|bps| (bps - bps.round()).abs() < 1e-6)
+            .map(f64::round)
+            .filter(|bps| *bps >= 1.0 && *bps <= f64::from(TOTAL_BPS))
+            .ok_or(ERR_INVALID_PARAM)? as u32;
+        if policy.iter().any(|(id, _)| *id == account_id) {
+            return Err(ERR_INVALID_PARAM);
+        }
+        policy.push((account_id, bps));
+    }
+
+    if policy.iter().map(|(_, bps)| bps).sum::<u32>() > TOTAL_BPS {
+        return Err(ERR_INVALID_PARAM);
+    }
+    Ok(policy)
+}
+
+/// Split policies of all accounts in the store
+pub(crate) struct SplitPolicies {
+    path: PathBuf,
+    accounts: HashMap<AccountId, SplitPolicy>,
+}
+
+impl SplitPolicies {
+    /// Load the sidecar file for the store at `store_path` (missing or corrupt → no policies)
+    pub fn load(store_path: &Path) -> Self {
+        let path = store_path.with_extension("split.json");
+        let entries: HashMap<String, Vec<(String, u32)>> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        let accounts = entries
+            .into_iter()
+            .filter_map(|(account_id, beneficiaries)| {
+                let policy = beneficiaries
+                    .iter()
+                    .map(|(id, bps)| AccountId::from_hex(id).ok().map(|id| (id, *bps)))
+                    .collect::<Option<SplitPolicy>>()?;
+                Some((AccountId::from_hex(&account_id).ok()?, policy))
+            })
+            .collect();
+
+        Self { path, accounts }
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let entries: HashMap<String, Vec<(String, u32)>> = self.accounts
+            .iter()
+            .map(|(account_id, policy)| {
+                let beneficiaries = policy.iter().map(|(id, bps)| (id.to_hex(), *bps)).collect();
+                (account_id.to_hex(), beneficiaries)
+            })
+            .collect();
+        let json = serde_json::to_string(&entries).map_err(std::io::Error::other)?;
+        memory_store::write(&self.path, json)
+    }
+}
+
+pub(crate) fn set_split_policy_impl(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    policy: Option<SplitPolicy>,
+) -> Result<String, i32> {
+    match policy {
+        Some(policy) => context.splits.accounts.insert(account_id, policy),
+        None => context.splits.accounts.remove(&account_id),
+    };
+    context.splits.save().map_err(|_| ERR_INVALID_PARAM)?;
+    Ok(String::new())
+}
+
+/// Share of each fungible asset owed to a beneficiary (zero shares are skipped)
+fn shares(assets: &[FungibleAsset], bps: u32) -> Result<Vec<Asset>, i32> {
+    assets
+        .iter()
+        .filter_map(|asset| {
+            let amount = u128::from(asset.amount()) * u128::from(bps) / u128::from(TOTAL_BPS);
+            (amount > 0).then(|| {
+                FungibleAsset::new(asset.faucet_id(), amount as u64)
+                    .map(Asset::from)
+                    .map_err(|_| ERR_INVALID_PARAM)
+            })
+        })
+        .collect()
+}
+
+pub(crate) async fn apply_split_impl(
+    context: &mut MidenContext,
+    note_id: NoteId,
+) -> Result<String, i32> {
+    let (record, consumers) = context.client.get_consumable_notes(None).await
+        .map_err(|_| ERR_NOTE_OP)?
+        .into_iter()
+        .find(|(record, _)| record.id() == note_id)
+        .ok_or(ERR_LOOKUP)?;
+
+    // The consuming account is the one among the note's consumers that has a policy
+    let (account_id, policy) = consumers
+        .iter()
+        .find_map(|(id, _)| context.splits.accounts.get(id).map(|policy| (*id, policy.clone())))
+        .ok_or(ERR_LOOKUP)?;
+
+    let fungible: Vec<FungibleAsset> = record.assets()
+        .iter()
+        .filter(|asset| asset.is_fungible())
+        .map(|asset| asset.unwrap_fungible())
+        .collect();
+
+    let mut output_notes: Vec<Note> = Vec::with_capacity(policy.len());
+    let mut beneficiaries = Vec::with_capacity(policy.len());
+    for (beneficiary, bps) in &policy {
+        let assets = shares(&fungible, *bps)?;
+        if assets.is_empty() {
+            continue;
+        }
+        let note = create_p2id_note(
+            account_id,
+            *beneficiary,
+            assets,
+            NoteType::Public,
+            Felt::new(0),
+            context.client.rng(),
+        )
+        .map_err(|_| ERR_NOTE_OP)?;
+        output_notes.push(note);
+        beneficiaries.push(*beneficiary);
+    }
+
+    whitelist::check(context, account_id, &output_notes)?;
+    screening::screen_transaction(context, account_id, &output_notes, &[note_id]).await?;
+
+    let notes_json: Vec<serde_json::Value> = output_notes
+        .iter()
+        .zip(&beneficiaries)
+        .map(|(note, beneficiary)| {
+            let assets: Vec<serde_json::Value> = note.assets()
+                .iter()
+                .map(|asset| {
+                    let fungible = asset.unwrap_fungible();
+                    serde_json::json!({
+                        "faucet_id": fungible.faucet_id().to_hex(),
+                        "amount": fungible.amount(),
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "note_id": note.id().to_hex(),
+                "account_id": beneficiary.to_hex(),
+                "assets": assets,
+            })
+        })
+        .collect();
+
+    let tx_request = TransactionRequestBuilder::new()
+        .authenticated_input_notes([(note_id, None)])
+        .own_output_notes(output_notes.into_iter().map(OutputNote::Full).collect::<Vec<_>>())
+        .build()
+        .map_err(|_| ERR_NOTE_OP)?;
+
+    proving::check_memory(context)?;
+    let tx_id = context.client.submit_new_transaction(account_id, tx_request).await
+        .map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))?;
+
+    Ok(serde_json::json!({
+        "tx_id": tx_id.to_hex(),
+        "account_id": account_id.to_hex(),
+        "notes": notes_json,
+    })
+    .to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Set, replace or remove the split policy of an account (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_id_hex`: Receiving account (C string)
+/// - `splits_json`: JSON array `[{"account_id":"0x..","percent":12.5},...]` with 1-16
+///   distinct beneficiaries, percentages > 0 with at most 2 decimals summing to ≤ 100;
+///   NULL removes the policy
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, or the policy file could not be written
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_set_split_policy(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    splits_json: *const c_char,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let (account_id, _) = match parse_account_id(account_id_hex) {
+        Ok(parsed) => parsed,
+        Err(code) => return code,
+    };
+    let policy = match parse_optional_str(splits_json) {
+        Ok(Some(json)) => match parse_split_policy(json) {
+            Ok(policy) => Some(policy),
+            Err(code) => return code,
+        },
+        Ok(None) => None,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::SetSplitPolicy { account_id, policy, reply }) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
+
+/// Consume a received note and pay out the shares of its account's split policy (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `note_id_hex`: Consumable note of an account with a split policy (C string)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"tx_id","account_id","notes":[{"note_id","account_id","assets":[...]}]}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -4: Invalid note ID or note operation failed
+/// - -5: Note not consumable, or no consuming account has a split policy
+/// - -6: Transaction submission failed
+/// - -7: Output buffer too small
+/// - -101: Blocked by the account's withdrawal whitelist
+/// - -102: Blocked by compliance screening
+///
+/// The share notes are only known once the split ran, so only the rest of the result is
+/// checked up front: a -7 with `*json_out_len` above 137 means the transaction was
+/// submitted. Do not retry it; the notes are in the account's output notes.
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_apply_split(
+    handle: MidenHandle,
+    note_id_hex: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let result = serde_json::json!({
+        "tx_id": hex_placeholder(ID_HEX_LEN),
+        "account_id": hex_placeholder(ACCOUNT_ID_HEX_LEN),
+        "notes": [],
+    });
+    if let Err(code) = check_out_capacity(json_out, json_out_len, output_len(&result)) {
+        return code;
+    }
+    let note_id = match parse_required_str(note_id_hex) {
+        Ok(s) => match NoteId::try_from_hex(s) {
+            Ok(id) => id,
+            Err(_) => return ERR_NOTE_OP,
+        },
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::ApplySplit { note_id, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
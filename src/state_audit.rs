@@ -0,0 +1,156 @@
+//! Local state self-audit
+//!
+//! A store that missed an update (an interrupted sync, a restored backup, a bug) shows
+//! stale balances or offers notes that were already spent, and nothing tells the user
+//! why a transaction then fails. The audit compares one account's local state with the
+//! node's:
+//!
+//! - `stale_account`: the account commitment differs from the node's. Expected while the
+//!   account has uncommitted transactions (the store applies them right away), which the
+//!   report flags with `pending_transactions`
+//! - `missing_consumption`: a note the store considers consumable by the account has
+//!   its nullifier on chain, so it was consumed elsewhere (another device, a recall)
+//!
+//! With `repair`, a sync round applies what the node reports (nullifiers, public account
+//! updates) and the account is audited again. A discrepancy left after that (a private
+//! account the node only knows the commitment of, a store behind a reorg) needs the
+//! account to be restored from a backup or re-imported.
+
+use std::os::raw::c_char;
+
+use miden_client::{rpc::NodeRpcClient, store::TransactionFilter};
+use miden_objects::{account::AccountId, utils::Serializable};
+
+use crate::{
+    block_times, get_handle, last_error, live_tx, parse_account_id, request_blocking,
+    retention, sync_state_impl, widget, write_out_buffer, MidenContext, MidenHandle, Request,
+    ERR_INVALID_HANDLE, ERR_LOOKUP, ERR_NOTE_OP,
+};
+
+/// Compare the account's local state with the node's
+async fn audit(context: &MidenContext, account_id: AccountId) -> Result<serde_json::Value, i32> {
+    let record = context.client.get_account(account_id).await
+        .map_err(|e| last_error::detail(ERR_LOOKUP, e))?
+        .ok_or(ERR_LOOKUP)?;
+    let local_commitment = record.account().commitment();
+
+    let fetched = context.rpc.get_account_details(account_id).await
+        .map_err(|e| last_error::detail(ERR_LOOKUP, e))?;
+    let node_commitment = fetched.commitment();
+
+    let pending_transactions = context.client.get_transactions(TransactionFilter::Uncommitted)
+        .await
+        .map_err(|e| last_error::detail(ERR_LOOKUP, e))?
+        .iter()
+        .filter(|tx| tx.details.account_id == account_id)
+        .count();
+
+    let mut discrepancies = Vec::new();
+    if local_commitment != node_commitment {
+        discrepancies.push(serde_json::json!({
+            "kind": "stale_account",
+            "local_commitment": hex::encode(local_commitment.to_bytes()),
+            "node_commitment": hex::encode(node_commitment.to_bytes()),
+        }));
+    }
+
+    let consumable = context.client.get_consumable_notes(Some(account_id)).await
+        .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?;
+    for (note, _) in &consumable {
+        let nullifier = note.nullifier();
+        let spent_at = context.rpc.get_nullifier_commit_height(&nullifier, 0.into()).await
+            .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?;
+        if spent_at.is_some() {
+            discrepancies.push(serde_json::json!({
+                "kind": "missing_consumption",
+                "note_id": note.id().to_hex(),
+                "nullifier": nullifier.to_hex(),
+            }));
+        }
+    }
+
+    Ok(serde_json::json!({
+        "account_id": account_id.to_hex(),
+        "checked_notes": consumable.len(),
+        "pending_transactions": pending_transactions,
+        "consistent": discrepancies.is_empty(),
+        "discrepancies": discrepancies,
+    }))
+}
+
+pub(crate) async fn audit_state_impl(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    repair: bool,
+) -> Result<String, i32> {
+    let mut report = audit(context, account_id).await?;
+    let needs_repair = repair && report["consistent"] == false;
+    if needs_repair {
+        sync_state_impl(context).await?;
+        // Store-local post-sync work, as in an account sync
+        retention::apply_after_sync(context).await;
+        block_times::backfill_after_sync(context).await;
+        #[cfg(feature = "scripts")]
+        crate::bridge::update_after_sync(context).await;
+        live_tx::update_after_sync(context).await;
+        widget::update_after_sync(context).await;
+
+        let found = report["discrepancies"].take();
+        report = audit(context, account_id).await?;
+        report["repaired_from"] = found;
+    }
+    report["repair_attempted"] = needs_repair.into();
+    Ok(report.to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Compare an account's local state with the node's, optionally repairing it (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Makes one node request per consumable note of the account.
+///
+/// # Parameters
+/// - `account_id_hex`: Account ID (C string)
+/// - `repair`: Sync and audit again when discrepancies are found
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"account_id","checked_notes","pending_transactions","consistent",
+///   "discrepancies":[{"kind":"stale_account","local_commitment","node_commitment"}
+///   |{"kind":"missing_consumption","note_id","nullifier"}],"repair_attempted",
+///   "repaired_from"}` (`repaired_from`: the discrepancies found before the repair, only
+///   when one was attempted)
+///
+/// # Returns
+/// - 0: Success (check `consistent`)
+/// - -1: Invalid parameters
+/// - -2: Invalid handle, worker closed or repair sync failed
+/// - -3: Invalid account ID
+/// - -4: Nullifier query failed
+/// - -5: Account not in the store, or unknown to the node
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_audit_state(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    repair: bool,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let account_id = match parse_account_id(account_id_hex) {
+        Ok((account_id, _)) => account_id,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::AuditState { account_id, repair, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,220 @@
+//! Degraded mode for a full disk or read-only store
+//!
+//! When the device disk fills up, every store write fails and the client used to answer
+//! each call with the generic error of the operation. A handle now goes into degraded mode
+//! when a store write fails for lack of space or write access, or when a small probe file
+//! cannot be written next to the store at start-up:
+//!
+//! - read-only requests (balances, notes, transactions, ...) keep working
+//! - requests that may write to the store (see `Request::is_read_only`) fail with
+//!   `ERR_STORAGE_FULL` before being queued
+//! - `wc_miden_health_check` reports `degraded` with the reason
+//!
+//! The condition clears once the probe file can be written again: the probe is retried
+//! by mutating calls and health checks, at most every [`STORAGE_PROBE_INTERVAL_SECS`]
+//! seconds. Write failures are recognized from the error text at the failure sites that
+//! attach it (see last_error.rs).
+
+use std::{
+    cell::RefCell,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    clock, diagnostics, get_handle, memory_store, write_out_buffer, MidenHandle,
+    ERR_INVALID_HANDLE, ERR_STORAGE_FULL,
+};
+
+/// Least time between two probes while degraded
+pub const STORAGE_PROBE_INTERVAL_SECS: u64 = 10;
+
+/// Size of the probe file
+const STORAGE_PROBE_BYTES: usize = 64 * 1024;
+
+/// Parts of error messages that mean the disk is full
+const FULL_MARKERS: [&str; 2] = ["database or disk is full", "No space left on device"];
+
+/// Parts of error messages that mean the store cannot be written
+const READ_ONLY_MARKERS: [&str; 2] =
+    ["attempt to write a readonly database", "Read-only file system"];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StorageProblem {
+    Full,
+    ReadOnly,
+}
+
+impl StorageProblem {
+    fn as_str(self) -> &'static str {
+        match self {
+            StorageProblem::Full => "full",
+            StorageProblem::ReadOnly => "read_only",
+        }
+    }
+
+    fn of_message(message: &str) -> Option<Self> {
+        if FULL_MARKERS.iter().any(|marker| message.contains(marker)) {
+            Some(StorageProblem::Full)
+        } else if READ_ONLY_MARKERS.iter().any(|marker| message.contains(marker)) {
+            Some(StorageProblem::ReadOnly)
+        } else {
+            None
+        }
+    }
+
+    fn of_io_error(error: &std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::ReadOnlyFilesystem | std::io::ErrorKind::PermissionDenied => {
+                StorageProblem::ReadOnly
+            }
+            _ => StorageProblem::Full,
+        }
+    }
+}
+
+struct Degraded {
+    problem: StorageProblem,
+    reason: String,
+    since: u64,
+    last_probe: Instant,
+}
+
+/// Storage state of a handle, shared between the handle and its worker
+pub(crate) struct StorageHealth {
+    probe_path: PathBuf,
+    degraded: Mutex<Option<Degraded>>,
+}
+
+thread_local! {
+    /// Storage state of the handle whose worker runs on this thread
+    static INSTALLED: RefCell<Option<Arc<StorageHealth>>> = const { RefCell::new(None) };
+}
+
+impl StorageHealth {
+    pub fn new(store_path: &Path) -> Self {
+        Self {
+            probe_path: store_path.with_extension("probe"),
+            degraded: Mutex::new(None),
+        }
+    }
+
+    /// Write and remove the probe file
+    fn probe(&self) -> std::io::Result<()> {
+        if memory_store::is_in_memory(&self.probe_path) {
+            return Ok(());
+        }
+        let mut file = std::fs::File::create(&self.probe_path)?;
+        let written = file.write_all(&[0u8; STORAGE_PROBE_BYTES]).and_then(|_| file.sync_all());
+        let _ = std::fs::remove_file(&self.probe_path);
+        written
+    }
+
+    fn mark(&self, problem: StorageProblem, reason: String) {
+        let mut degraded = self.degraded.lock().unwrap();
+        if degraded.is_none() {
+            diagnostics::log("storage", format!("degraded ({}): {}", problem.as_str(), reason));
+        }
+        let since = degraded.as_ref().map_or_else(clock::now_unix, |d| d.since);
+        *degraded = Some(Degraded { problem, reason, since, last_probe: Instant::now() });
+    }
+
+    /// Probe the store now, entering or leaving degraded mode (worker start-up)
+    pub fn check_now(&self) {
+        match self.probe() {
+            Ok(()) => self.clear(),
+            Err(e) => self.mark(StorageProblem::of_io_error(&e), e.to_string()),
+        }
+    }
+
+    fn clear(&self) {
+        if self.degraded.lock().unwrap().take().is_some() {
+            diagnostics::log("storage", "store writable again");
+        }
+    }
+
+    /// Retry the probe if degraded and the last probe is old enough
+    fn refresh(&self) {
+        let due = self.degraded.lock().unwrap().as_ref().is_some_and(|degraded| {
+            degraded.last_probe.elapsed() >= Duration::from_secs(STORAGE_PROBE_INTERVAL_SECS)
+        });
+        if due {
+            self.check_now();
+        }
+    }
+
+    /// Whether a request that may write to the store can be queued
+    pub fn check_writable(&self) -> Result<(), i32> {
+        self.refresh();
+        match self.degraded.lock().unwrap().as_ref() {
+            Some(_) => Err(ERR_STORAGE_FULL),
+            None => Ok(()),
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let degraded = self.degraded.lock().unwrap();
+        serde_json::json!({
+            "degraded": degraded.is_some(),
+            "storage": degraded.as_ref().map_or("ok", |d| d.problem.as_str()),
+            "reason": degraded.as_ref().map(|d| d.reason.as_str()),
+            "degraded_since": degraded.as_ref().map(|d| d.since),
+        })
+        .to_string()
+    }
+}
+
+/// Install the handle's storage state on the worker thread
+pub(crate) fn install(health: Arc<StorageHealth>) {
+    INSTALLED.with(|installed| *installed.borrow_mut() = Some(health));
+}
+
+/// Code to report for a failure with `message`: `ERR_STORAGE_FULL` (entering degraded
+/// mode) when the store could not be written, else `code`
+pub(crate) fn classify(code: i32, message: &str) -> i32 {
+    let Some(problem) = StorageProblem::of_message(message) else {
+        return code;
+    };
+    INSTALLED.with(|installed| {
+        if let Some(health) = installed.borrow().as_ref() {
+            health.mark(problem, message.to_string());
+        }
+    });
+    ERR_STORAGE_FULL
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Get the health of a handle's storage
+///
+/// Does not queue; safe to call from the main thread, also while the worker is busy.
+/// While degraded, the probe is retried at most every 10 seconds, so a recovered disk is
+/// reported without another write attempt.
+///
+/// # Output JSON
+/// `{"degraded":true,"storage":"full","reason":"database or disk is full",
+///   "degraded_since":1700000000}` (`storage`: `ok`, `full` or `read_only`; `reason` and
+///   `degraded_since` are null when not degraded)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_health_check(
+    handle: MidenHandle,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    worker.storage.refresh();
+    write_out_buffer(worker.storage.to_json().as_bytes(), json_out, json_out_len)
+}
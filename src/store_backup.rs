@@ -0,0 +1,305 @@
+//! Store backups
+//!
+//! `wc_miden_backup_store` writes a consistent snapshot of a client to a new directory, for
+//! app-level backups (an export to Files, a cloud drive):
+//!
+//! - `store.sqlite3`: copy of the SQLite store made with SQLite's online backup API, so it
+//!   is a single checkpointed file (no WAL) even while the store is in use; an encrypted
+//!   store stays encrypted with its key (see encrypted_store.rs)
+//! - `keystore/`: copy of the keystore directory, encrypted keys included as they are
+//! - `manifest.json`: `{"version":1,"created_at","store":{"path","size","sha3_256",
+//!   "encrypted"},"keystore":{"encrypted","files":[{"path","size","sha3_256"}]}}`, written
+//!   last, so a directory without one is an interrupted backup
+//!
+//! `wc_miden_restore_store` checks every file against the manifest and that the backup
+//! opens the way the client was created (same store key, same keystore passphrase), then
+//! restores the keystore (staged like a rekey, see encrypted_keystore.rs) and the store
+//! (through the backup API, in one transaction) and reopens the client. Key files only the
+//! current keystore holds are kept, so accounts created after the backup can be recovered
+//! by importing them again.
+//!
+//! Not included: keys of the memory backend, keys held by external signers (their bindings
+//! are), and the state files next to the store (retention policy, watches, the archive,
+//! ...), which keep their current contents on restore. In-memory clients have no files to
+//! back up, and a tenant's client is refused: the store also holds the other tenants'
+//! rows (see tenants.rs).
+
+use std::{
+    fs,
+    os::raw::c_char,
+    path::{Component, Path, PathBuf},
+};
+
+use sha3::{Digest, Sha3_256};
+
+use crate::{
+    clock, encrypted_keystore, encrypted_store, get_handle, last_error, memory_store,
+    park_client, parse_required_str, reopen_client, reorg, request_blocking, MidenContext,
+    MidenHandle, Request, ERR_ACCOUNT_OP, ERR_INVALID_HANDLE, ERR_INVALID_PARAM,
+    ERR_PERMISSION_DENIED,
+};
+
+/// Version tag of `manifest.json`
+pub const STORE_BACKUP_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+const STORE_FILE: &str = "store.sqlite3";
+
+const KEYSTORE_DIR: &str = "keystore";
+
+/// Files under `dir`, with subdirectories
+fn list_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            list_files(&entry.path(), files)?;
+        } else {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// Manifest entry of a file under `root`
+fn file_entry(root: &Path, path: &Path) -> std::io::Result<serde_json::Value> {
+    let bytes = fs::read(path)?;
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    Ok(serde_json::json!({
+        "path": relative.to_string_lossy(),
+        "size": bytes.len(),
+        "sha3_256": hex::encode(Sha3_256::digest(&bytes)),
+    }))
+}
+
+/// Check a manifest entry against the file under `root`, returning the file's path
+fn verify_entry(root: &Path, entry: &serde_json::Value) -> Result<PathBuf, String> {
+    let relative = entry["path"].as_str().map(Path::new).ok_or("malformed manifest")?;
+    if !relative.components().all(|component| matches!(component, Component::Normal(_))) {
+        return Err(format!("manifest path {} leaves the backup", relative.display()));
+    }
+
+    let path = root.join(relative);
+    let bytes = fs::read(&path).map_err(|e| format!("{}: {}", relative.display(), e))?;
+    let hash = hex::encode(Sha3_256::digest(&bytes));
+    if entry["size"].as_u64() != Some(bytes.len() as u64) || entry["sha3_256"] != hash {
+        return Err(format!("{} does not match the manifest", relative.display()));
+    }
+    Ok(path)
+}
+
+fn refuse_unsupported(context: &MidenContext) -> Result<(), i32> {
+    if memory_store::is_in_memory(&context.store_path) {
+        let detail = "in-memory clients have no store files";
+        return Err(last_error::detail(ERR_INVALID_PARAM, detail));
+    }
+    // The store holds every tenant's rows (see tenants.rs)
+    if context.tenant.is_some() {
+        let detail = "the store is shared by other tenants; back it up without a tenant_id";
+        return Err(last_error::detail(ERR_INVALID_PARAM, detail));
+    }
+    Ok(())
+}
+
+pub(crate) fn backup_store_impl(context: &MidenContext, dest: PathBuf) -> Result<(), i32> {
+    refuse_unsupported(context)?;
+    let is_empty = fs::read_dir(&dest).map_or(true, |mut entries| entries.next().is_none());
+    if !is_empty {
+        return Err(last_error::detail(ERR_INVALID_PARAM, "destination is not empty"));
+    }
+
+    let write = || -> Result<(), String> {
+        fs::create_dir_all(&dest).map_err(|e| e.to_string())?;
+
+        let store_copy = dest.join(STORE_FILE);
+        let conn = rusqlite::Connection::open(&context.database_path).map_err(|e| e.to_string())?;
+        let copy_path = encrypted_store::with_store_key(&context.database_path, &store_copy);
+        conn.backup(rusqlite::MAIN_DB, copy_path, None).map_err(|e| e.to_string())?;
+
+        let keystore_copy = dest.join(KEYSTORE_DIR);
+        encrypted_keystore::copy_dir(&context.keystore_path, &keystore_copy)
+            .map_err(|e| e.to_string())?;
+        let mut keystore_files = Vec::new();
+        list_files(&keystore_copy, &mut keystore_files).map_err(|e| e.to_string())?;
+        keystore_files.sort();
+
+        let mut store = file_entry(&dest, &store_copy).map_err(|e| e.to_string())?;
+        store["encrypted"] = (context.database_path != context.store_path).into();
+        let files = keystore_files
+            .iter()
+            .map(|path| file_entry(&keystore_copy, path))
+            .collect::<std::io::Result<Vec<_>>>()
+            .map_err(|e| e.to_string())?;
+
+        let manifest = serde_json::json!({
+            "version": STORE_BACKUP_VERSION,
+            "created_at": clock::now_unix(),
+            "store": store,
+            "keystore": {
+                "encrypted": encrypted_keystore::is_encrypted(&keystore_copy),
+                "files": files,
+            },
+        });
+        fs::write(dest.join(MANIFEST_FILE), manifest.to_string()).map_err(|e| e.to_string())
+    };
+
+    write().map_err(|e| {
+        let _ = fs::remove_dir_all(&dest);
+        last_error::detail(ERR_ACCOUNT_OP, e)
+    })
+}
+
+/// Check a backup against its manifest and the client (see the module docs)
+fn verify_backup(context: &MidenContext, src: &Path) -> Result<(), i32> {
+    let invalid = |e: String| last_error::detail(ERR_INVALID_PARAM, e);
+
+    let contents = fs::read_to_string(src.join(MANIFEST_FILE))
+        .map_err(|e| invalid(format!("no backup manifest: {}", e)))?;
+    let manifest: serde_json::Value =
+        serde_json::from_str(&contents).map_err(|_| invalid("malformed manifest".into()))?;
+    if manifest["version"].as_u64() != Some(u64::from(STORE_BACKUP_VERSION)) {
+        return Err(invalid("unsupported backup version".into()));
+    }
+
+    let store_copy = verify_entry(src, &manifest["store"]).map_err(invalid)?;
+    if store_copy != src.join(STORE_FILE) {
+        return Err(invalid("malformed manifest".into()));
+    }
+    let keystore_copy = src.join(KEYSTORE_DIR);
+    let mut listed = manifest["keystore"]["files"]
+        .as_array()
+        .ok_or_else(|| invalid("malformed manifest".into()))?
+        .iter()
+        .map(|entry| verify_entry(&keystore_copy, entry))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(invalid)?;
+    let mut present = Vec::new();
+    list_files(&keystore_copy, &mut present).map_err(|e| invalid(e.to_string()))?;
+    listed.sort();
+    present.sort();
+    if listed != present {
+        return Err(invalid("keystore files do not match the manifest".into()));
+    }
+
+    let encrypted = context.database_path != context.store_path;
+    if manifest["store"]["encrypted"].as_bool() != Some(encrypted) {
+        let detail = match encrypted {
+            true => "backup store is not encrypted",
+            false => "backup store is encrypted",
+        };
+        return Err(invalid(detail.into()));
+    }
+    let copy_path = encrypted_store::with_store_key(&context.database_path, &store_copy);
+    let flags = rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_URI;
+    let check = rusqlite::Connection::open_with_flags(copy_path, flags)
+        .and_then(|conn| conn.query_row("PRAGMA quick_check", [], |row| row.get::<_, String>(0)));
+    match check {
+        Ok(result) if result == "ok" => Ok(()),
+        Ok(result) => Err(invalid(format!("backup store is damaged: {}", result))),
+        Err(e) if encrypted => Err(last_error::detail(ERR_PERMISSION_DENIED, e)),
+        Err(e) => Err(invalid(e.to_string())),
+    }
+}
+
+pub(crate) async fn restore_store_impl(
+    context: &mut MidenContext,
+    src: PathBuf,
+) -> Result<(), i32> {
+    refuse_unsupported(context)?;
+    verify_backup(context, &src)?;
+
+    // The keystore goes first: it checks the passphrase before touching anything
+    context.keystore.restore(&src.join(KEYSTORE_DIR))?;
+
+    park_client(context).await.map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    let store_copy = encrypted_store::with_store_key(&context.database_path, &src.join(STORE_FILE));
+    let restored = rusqlite::Connection::open(&context.database_path).and_then(|mut conn| {
+        conn.restore(rusqlite::MAIN_DB, store_copy, None::<fn(rusqlite::backup::Progress)>)
+    });
+    reopen_client(context).await.map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+    restored.map_err(|e| last_error::detail(ERR_ACCOUNT_OP, e))?;
+
+    // Sync state of the replaced store no longer applies
+    context.reorg = reorg::ReorgGuard::default();
+    if let Ok(height) = context.client.get_sync_height().await {
+        context.progress.set_local_height(height.as_u32());
+    }
+    Ok(())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Back up the store and keystore to a directory (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread. The whole store
+/// is copied.
+///
+/// Writes `store.sqlite3`, `keystore/` and `manifest.json` (see the module docs). A failed
+/// backup removes what it wrote.
+///
+/// # Parameters
+/// - `dest_path`: Directory to write the backup to (C string); created if missing, and
+///   must be empty
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, a non-empty destination, or an in-memory client
+/// - -2: Invalid handle or worker closed
+/// - -3: Copying the store or keystore failed
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_backup_store(handle: MidenHandle, dest_path: *const c_char) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let dest = match parse_required_str(dest_path) {
+        Ok(dest) if !dest.is_empty() => PathBuf::from(dest),
+        Ok(_) => return ERR_INVALID_PARAM,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::BackupStore { dest, reply }) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
+
+/// Restore the store and keystore from a backup (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Replaces the client's store with the backup's and restores its keystore files, then
+/// reopens the client (see the module docs). Close other handles on the same store first.
+/// Accounts and notes added after the backup are gone from the store; sync afterwards.
+///
+/// # Parameters
+/// - `src_path`: Backup directory written by `wc_miden_backup_store` (C string)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, a backup that does not match its manifest or is damaged, an
+///   unencrypted backup for an encrypted store (or the reverse), or an in-memory client
+/// - -2: Invalid handle or worker closed
+/// - -3: Restoring the store or keystore failed
+/// - -99: Operation timed out
+/// - -101: The backup's store key or keystore passphrase differs from the client's
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_restore_store(handle: MidenHandle, src_path: *const c_char) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let src = match parse_required_str(src_path) {
+        Ok(src) if !src.is_empty() => PathBuf::from(src),
+        Ok(_) => return ERR_INVALID_PARAM,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::RestoreStore { src, reply }) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,656 @@
+//! Streaming payments (experimental)
+//!
+//! A stream pays a recipient `amount_per_hour` of one asset until it is cancelled. There
+//! is no on-chain channel: the sender's wallet accrues the owed amount locally and, after
+//! each successful sync, pays what accrued since the last payout in a public P2IDE note
+//! once at least `STREAM_PAYOUT_INTERVAL_SECS` have passed. Payout notes carry a reclaim
+//! height `STREAM_RECLAIM_BLOCKS` ahead, after which the sender may take back payouts the
+//! recipient never claimed.
+//!
+//! The stream budget (initial deposit plus top-ups) caps the total paid; funds stay in
+//! the sender's vault until each payout. Accrual continues while the budget is exhausted
+//! and arrears are paid after a top-up. Cancelling pays the amount accrued so far and
+//! stops the stream. Payouts pass the withdrawal whitelist and screening checks; a failed
+//! payout is logged and retried after the next sync.
+//!
+//! On the receiving side a claim schedule consumes the notes a given sender streams to a
+//! local account every `interval_blocks` (again after sync). Streams and claim schedules
+//! are kept in a JSON file next to the SQLite store.
+
+use std::{
+    os::raw::c_char,
+    path::{Path, PathBuf},
+};
+
+use miden_client::transaction::TransactionRequestBuilder;
+use miden_lib::note::create_p2ide_note;
+use miden_objects::{
+    account::AccountId,
+    asset::FungibleAsset,
+    block::BlockNumber,
+    note::{NoteId, NoteType},
+    transaction::{OutputNote, TransactionId},
+    Felt,
+};
+use rand::RngCore;
+
+use crate::{
+    check_out_capacity, clock, diagnostics, get_handle, hex_placeholder, last_error, memory_store,
+    output_len, parse_account_id, parse_required_str, proving, request_blocking, screening,
+    whitelist, write_out_buffer, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM, ERR_LOOKUP, ERR_NOTE_OP, ERR_TX_SUBMIT, ACCOUNT_ID_HEX_LEN, ID_HEX_LEN,
+};
+
+/// Minimum time between two payouts of a stream
+pub const STREAM_PAYOUT_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Blocks after a payout at which the sender can reclaim it if unclaimed
+pub const STREAM_RECLAIM_BLOCKS: u32 = 100_000;
+
+const SECONDS_PER_HOUR: u128 = 60 * 60;
+
+struct Stream {
+    id: String,
+    sender: AccountId,
+    recipient: AccountId,
+    faucet_id: AccountId,
+    amount_per_hour: u64,
+    budget: u64,
+    paid: u64,
+    started_at: u64,
+    last_payout_at: u64,
+    /// Unix time the stream was cancelled (accrual stops there)
+    cancelled_at: Option<u64>,
+}
+
+impl Stream {
+    /// Amount accrued and not yet paid, capped by the remaining budget
+    fn due(&self, now: u64) -> u64 {
+        let until = self.cancelled_at.unwrap_or(now).max(self.started_at);
+        let accrued = u128::from(self.amount_per_hour) * u128::from(until - self.started_at)
+            / SECONDS_PER_HOUR;
+        let accrued = u64::try_from(accrued).unwrap_or(u64::MAX);
+        accrued.min(self.budget).saturating_sub(self.paid)
+    }
+
+    fn to_json(&self, now: u64) -> serde_json::Value {
+        serde_json::json!({
+            "stream_id": self.id,
+            "sender": self.sender.to_hex(),
+            "recipient": self.recipient.to_hex(),
+            "faucet_id": self.faucet_id.to_hex(),
+            "amount_per_hour": self.amount_per_hour,
+            "budget": self.budget,
+            "paid": self.paid,
+            "due": self.due(now),
+            "started_at": self.started_at,
+            "last_payout_at": self.last_payout_at,
+            "cancelled_at": self.cancelled_at,
+        })
+    }
+
+    /// Widest `to_json` of a stream with these amounts, for sizing output buffers up front
+    fn placeholder_json(amount_per_hour: u64, budget: u64, cancelled: bool) -> serde_json::Value {
+        let account = hex_placeholder(ACCOUNT_ID_HEX_LEN);
+        serde_json::json!({
+            "stream_id": "0".repeat(16),
+            "sender": account,
+            "recipient": account,
+            "faucet_id": account,
+            "amount_per_hour": amount_per_hour,
+            "budget": budget,
+            "paid": budget,
+            "due": budget,
+            "started_at": u64::MAX,
+            "last_payout_at": u64::MAX,
+            "cancelled_at": cancelled.then_some(u64::MAX),
+        })
+    }
+
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let account = |name: &str| AccountId::from_hex(value[name].as_str()?).ok();
+        Some(Self {
+            id: value["stream_id"].as_str()?.to_string(),
+            sender: account("sender")?,
+            recipient: account("recipient")?,
+            faucet_id: account("faucet_id")?,
+            amount_per_hour: value["amount_per_hour"].as_u64()?,
+            budget: value["budget"].as_u64()?,
+            paid: value["paid"].as_u64()?,
+            started_at: value["started_at"].as_u64()?,
+            last_payout_at: value["last_payout_at"].as_u64()?,
+            cancelled_at: value["cancelled_at"].as_u64(),
+        })
+    }
+}
+
+/// Receiver-side schedule claiming a sender's stream notes
+struct ClaimSchedule {
+    account_id: AccountId,
+    sender: AccountId,
+    interval_blocks: u32,
+    last_claim_block: u32,
+}
+
+/// Outgoing streams and incoming claim schedules of the store
+pub(crate) struct Streams {
+    path: PathBuf,
+    streams: Vec<Stream>,
+    claims: Vec<ClaimSchedule>,
+}
+
+impl Streams {
+    /// Load the sidecar file for the store at `store_path` (missing or corrupt → none)
+    pub fn load(store_path: &Path) -> Self {
+        let path = store_path.with_extension("streams.json");
+        let value: serde_json::Value = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        let streams = value["streams"]
+            .as_array()
+            .map(|entries| entries.iter().filter_map(Stream::from_json).collect())
+            .unwrap_or_default();
+        let claims = value["claims"]
+            .as_array()
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|entry| {
+                        let account = |name: &str| AccountId::from_hex(entry[name].as_str()?).ok();
+                        let block = |name: &str| u32::try_from(entry[name].as_u64()?).ok();
+                        Some(ClaimSchedule {
+                            account_id: account("account_id")?,
+                            sender: account("sender")?,
+                            interval_blocks: block("interval_blocks")?,
+                            last_claim_block: block("last_claim_block")?,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self { path, streams, claims }
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let now = clock::now_unix();
+        let claims: Vec<serde_json::Value> = self.claims
+            .iter()
+            .map(|claim| {
+                serde_json::json!({
+                    "account_id": claim.account_id.to_hex(),
+                    "sender": claim.sender.to_hex(),
+                    "interval_blocks": claim.interval_blocks,
+                    "last_claim_block": claim.last_claim_block,
+                })
+            })
+            .collect();
+        let value = serde_json::json!({
+            "streams": self.streams.iter().map(|stream| stream.to_json(now)).collect::<Vec<_>>(),
+            "claims": claims,
+        });
+        memory_store::write(&self.path, value.to_string())
+    }
+
+    fn get_mut(&mut self, stream_id: &str) -> Result<&mut Stream, i32> {
+        self.streams.iter_mut().find(|stream| stream.id == stream_id).ok_or(ERR_LOOKUP)
+    }
+}
+
+fn save(context: &MidenContext) {
+    if let Err(e) = context.streams.save() {
+        diagnostics::log("streams", format!("failed to save state: {:?}", e));
+    }
+}
+
+/// Pay the amount due on a stream; returns the payout transaction (None if nothing is due)
+async fn pay_out(
+    context: &mut MidenContext,
+    index: usize,
+    now: u64,
+) -> Result<Option<TransactionId>, i32> {
+    let stream = &context.streams.streams[index];
+    let amount = stream.due(now);
+    if amount == 0 {
+        return Ok(None);
+    }
+    let (sender, recipient, faucet_id, stream_id) =
+        (stream.sender, stream.recipient, stream.faucet_id, stream.id.clone());
+
+    let reclaim_at = clock::current_block(context).await?.saturating_add(STREAM_RECLAIM_BLOCKS);
+    let asset = FungibleAsset::new(faucet_id, amount).map_err(|_| ERR_INVALID_PARAM)?;
+    let note = create_p2ide_note(
+        sender,
+        recipient,
+        vec![asset.into()],
+        Some(BlockNumber::from(reclaim_at)),
+        None,
+        NoteType::Public,
+        Felt::new(0),
+        context.client.rng(),
+    )
+    .map_err(|_| ERR_NOTE_OP)?;
+
+    let output_notes = [note.clone()];
+    whitelist::check(context, sender, &output_notes)?;
+    screening::screen_transaction(context, sender, &output_notes, &[]).await?;
+
+    let tx_request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note)])
+        .build()
+        .map_err(|_| ERR_NOTE_OP)?;
+
+    proving::check_memory(context)?;
+    let tx_id = context.client.submit_new_transaction(sender, tx_request).await
+        .map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))?;
+
+    let stream = &mut context.streams.streams[index];
+    stream.paid += amount;
+    stream.last_payout_at = now;
+    save(context);
+    context.events.emit(serde_json::json!({
+        "type": "stream_payout",
+        "stream_id": stream_id,
+        "tx_id": tx_id.to_hex(),
+        "amount": amount,
+    }));
+    Ok(Some(tx_id))
+}
+
+/// Consume the committed notes a sender streamed to a local account
+async fn claim(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    sender: AccountId,
+) -> Result<(), i32> {
+    let note_ids: Vec<NoteId> = context.client.get_consumable_notes(Some(account_id)).await
+        .map_err(|_| ERR_NOTE_OP)?
+        .iter()
+        .filter(|(record, _)| record.metadata().is_some_and(|meta| meta.sender() == sender))
+        .map(|(record, _)| record.id())
+        .collect();
+    if note_ids.is_empty() {
+        return Ok(());
+    }
+
+    screening::screen_transaction(context, account_id, &[], &note_ids).await?;
+    let tx_request = TransactionRequestBuilder::new()
+        .build_consume_notes(note_ids.clone())
+        .map_err(|_| ERR_NOTE_OP)?;
+
+    proving::check_memory(context)?;
+    let tx_id = context.client.submit_new_transaction(account_id, tx_request).await
+        .map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))?;
+
+    context.events.emit(serde_json::json!({
+        "type": "stream_claimed",
+        "account_id": account_id.to_hex(),
+        "sender": sender.to_hex(),
+        "tx_id": tx_id.to_hex(),
+        "note_count": note_ids.len(),
+    }));
+    Ok(())
+}
+
+/// Run due payouts and claims (after each successful sync)
+pub(crate) async fn run_after_sync(context: &mut MidenContext) {
+    let now = clock::now_unix();
+    for index in 0..context.streams.streams.len() {
+        // Cancelled streams only come here when their final payout failed
+        let stream = &context.streams.streams[index];
+        let interval_passed =
+            now.saturating_sub(stream.last_payout_at) >= STREAM_PAYOUT_INTERVAL_SECS;
+        if stream.due(now) == 0
+            || (stream.cancelled_at.is_none() && !interval_passed)
+            || context.archive.contains(stream.sender)
+        {
+            continue;
+        }
+        if let Err(code) = pay_out(context, index, now).await {
+            let stream_id = &context.streams.streams[index].id;
+            diagnostics::log("streams", format!("payout of stream {} failed: {}", stream_id, code));
+        }
+    }
+
+    if context.streams.claims.is_empty() {
+        return;
+    }
+    let Ok(block) = clock::current_block(context).await else {
+        return;
+    };
+    for index in 0..context.streams.claims.len() {
+        let schedule = &context.streams.claims[index];
+        if block < schedule.last_claim_block.saturating_add(schedule.interval_blocks)
+            || context.archive.contains(schedule.account_id)
+        {
+            continue;
+        }
+        let (account_id, sender) = (schedule.account_id, schedule.sender);
+        match claim(context, account_id, sender).await {
+            Ok(()) => {
+                context.streams.claims[index].last_claim_block = block;
+                save(context);
+            }
+            Err(code) => diagnostics::log("streams", format!("stream claim failed: {}", code)),
+        }
+    }
+}
+
+pub(crate) async fn start_stream_impl(
+    context: &mut MidenContext,
+    sender: AccountId,
+    recipient: AccountId,
+    faucet_id: AccountId,
+    amount_per_hour: u64,
+    budget: u64,
+) -> Result<String, i32> {
+    context.client.get_account(sender).await
+        .map_err(|_| ERR_LOOKUP)?
+        .ok_or(ERR_LOOKUP)?;
+
+    let mut id = [0u8; 8];
+    rand::rng().fill_bytes(&mut id);
+    let now = clock::now_unix();
+    let stream = Stream {
+        id: hex::encode(id),
+        sender,
+        recipient,
+        faucet_id,
+        amount_per_hour,
+        budget,
+        paid: 0,
+        started_at: now,
+        last_payout_at: now,
+        cancelled_at: None,
+    };
+    let json = stream.to_json(now).to_string();
+
+    context.streams.streams.push(stream);
+    context.streams.save().map_err(|_| ERR_INVALID_PARAM)?;
+    Ok(json)
+}
+
+pub(crate) fn top_up_stream_impl(
+    context: &mut MidenContext,
+    stream_id: String,
+    amount: u64,
+) -> Result<String, i32> {
+    let stream = context.streams.get_mut(&stream_id)?;
+    if stream.cancelled_at.is_some() {
+        return Err(ERR_INVALID_PARAM);
+    }
+    stream.budget = stream.budget.checked_add(amount).ok_or(ERR_INVALID_PARAM)?;
+    let json = stream.to_json(clock::now_unix()).to_string();
+
+    context.streams.save().map_err(|_| ERR_INVALID_PARAM)?;
+    Ok(json)
+}
+
+pub(crate) async fn cancel_stream_impl(
+    context: &mut MidenContext,
+    stream_id: String,
+) -> Result<String, i32> {
+    let now = clock::now_unix();
+    let stream = context.streams.get_mut(&stream_id)?;
+    if stream.cancelled_at.is_some() {
+        return Err(ERR_INVALID_PARAM);
+    }
+    stream.cancelled_at = Some(now);
+    save(context);
+
+    let index = context.streams.streams.iter().position(|stream| stream.id == stream_id)
+        .ok_or(ERR_LOOKUP)?;
+    let final_tx_id = pay_out(context, index, now).await?;
+
+    Ok(serde_json::json!({
+        "stream": context.streams.streams[index].to_json(now),
+        "final_tx_id": final_tx_id.map(|id| id.to_hex()),
+    })
+    .to_string())
+}
+
+pub(crate) fn list_streams_impl(context: &MidenContext) -> Result<String, i32> {
+    let now = clock::now_unix();
+    let streams: Vec<serde_json::Value> =
+        context.streams.streams.iter().map(|stream| stream.to_json(now)).collect();
+    Ok(serde_json::Value::from(streams).to_string())
+}
+
+pub(crate) fn schedule_stream_claims_impl(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    sender: AccountId,
+    interval_blocks: u32,
+) -> Result<String, i32> {
+    let claims = &mut context.streams.claims;
+    claims.retain(|claim| claim.account_id != account_id || claim.sender != sender);
+    if interval_blocks > 0 {
+        claims.push(ClaimSchedule { account_id, sender, interval_blocks, last_claim_block: 0 });
+    }
+    context.streams.save().map_err(|_| ERR_INVALID_PARAM)?;
+    Ok(String::new())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Start streaming an asset to a recipient (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `sender_account_id_hex` / `recipient_account_id_hex` / `faucet_id_hex`: Account IDs
+/// - `amount_per_hour`: Streaming rate in base units per hour (must be > 0)
+/// - `budget`: Maximum total amount paid until topped up (must be > 0)
+/// - `json_out` / `json_out_len`: Output buffer for the stream JSON (`{"stream_id",...}`)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or the stream file could not be written
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID
+/// - -5: Sender account not found
+/// - -7: Output buffer too small
+#[allow(clippy::too_many_arguments)]
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_start_stream(
+    handle: MidenHandle,
+    sender_account_id_hex: *const c_char,
+    recipient_account_id_hex: *const c_char,
+    faucet_id_hex: *const c_char,
+    amount_per_hour: u64,
+    budget: u64,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if amount_per_hour == 0 || budget == 0 {
+        return ERR_INVALID_PARAM;
+    }
+    let result = Stream::placeholder_json(amount_per_hour, budget, false);
+    if let Err(code) = check_out_capacity(json_out, json_out_len, output_len(&result)) {
+        return code;
+    }
+    let ids = (
+        parse_account_id(sender_account_id_hex),
+        parse_account_id(recipient_account_id_hex),
+        parse_account_id(faucet_id_hex),
+    );
+    let (sender, recipient, faucet_id) = match ids {
+        (Ok((sender, _)), Ok((recipient, _)), Ok((faucet_id, _))) => (sender, recipient, faucet_id),
+        (Err(code), ..) | (_, Err(code), _) | (.., Err(code)) => return code,
+    };
+
+    let request = |reply| Request::StartStream {
+        sender,
+        recipient,
+        faucet_id,
+        amount_per_hour,
+        budget,
+        reply,
+    };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Raise the budget of an active stream (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, stream cancelled, or the stream file could not be written
+/// - -2: Invalid handle or worker closed
+/// - -5: Stream not found
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_top_up_stream(
+    handle: MidenHandle,
+    stream_id: *const c_char,
+    amount: u64,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if amount == 0 {
+        return ERR_INVALID_PARAM;
+    }
+    let stream_id = match parse_required_str(stream_id) {
+        Ok(id) => id.to_string(),
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::TopUpStream { stream_id, amount, reply }) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
+
+/// Cancel a stream, paying out what accrued so far (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// The stream is cancelled even if the final payout fails; the payout is then retried
+/// after the next sync.
+///
+/// # Parameters
+/// - `stream_id`: Stream ID (C string)
+/// - `json_out` / `json_out_len`: Output buffer for `{"stream":{...},"final_tx_id":"0x.."|null}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or stream already cancelled
+/// - -2: Invalid handle or worker closed
+/// - -5: Stream not found
+/// - -6: Final payout failed
+/// - -7: Output buffer too small
+/// - -101 / -102: Final payout blocked by the whitelist / screening
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_cancel_stream(
+    handle: MidenHandle,
+    stream_id: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let result = serde_json::json!({
+        "stream": Stream::placeholder_json(u64::MAX, u64::MAX, true),
+        "final_tx_id": hex_placeholder(ID_HEX_LEN),
+    });
+    if let Err(code) = check_out_capacity(json_out, json_out_len, output_len(&result)) {
+        return code;
+    }
+    let stream_id = match parse_required_str(stream_id) {
+        Ok(id) => id.to_string(),
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::CancelStream { stream_id, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// List outgoing streams (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `json_out` / `json_out_len`: Output buffer for a JSON array of `{"stream_id","sender",
+///   "recipient","faucet_id","amount_per_hour","budget","paid","due","started_at",
+///   "last_payout_at","cancelled_at"}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_list_streams(
+    handle: MidenHandle,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    match request_blocking(&worker, |reply| Request::ListStreams { reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Claim a sender's stream notes to a local account on a block schedule (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_id_hex`: Receiving local account (C string)
+/// - `sender_account_id_hex`: Streaming sender (C string)
+/// - `interval_blocks`: Claim at most every this many blocks, after sync (0 removes the schedule)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, or the stream file could not be written
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_schedule_stream_claims(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    sender_account_id_hex: *const c_char,
+    interval_blocks: u32,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let (account_id, sender) =
+        match (parse_account_id(account_id_hex), parse_account_id(sender_account_id_hex)) {
+            (Ok((account_id, _)), Ok((sender, _))) => (account_id, sender),
+            (Err(code), _) | (_, Err(code)) => return code,
+        };
+
+    let request = |reply| Request::ScheduleStreamClaims {
+        account_id,
+        sender,
+        interval_blocks,
+        reply,
+    };
+    match request_blocking(&worker, request) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
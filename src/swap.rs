@@ -0,0 +1,146 @@
+//! Asset swaps
+//!
+//! `wc_miden_create_swap` offers one fungible asset for another in a SWAP note (the
+//! standard script of `miden-lib`): whoever consumes it receives the offered asset and
+//! sends the requested one back to the creator in a payback note. The client is told to
+//! expect the payback note, so a sync picks it up like any other received payment.
+//!
+//! A SWAP note is not a P2ID payment, so accounts with a withdrawal whitelist cannot
+//! create one. Apps without trading can leave this out by building without the `swaps`
+//! feature.
+
+use std::os::raw::c_char;
+
+use miden_client::transaction::{OutputNote, TransactionRequestBuilder};
+use miden_lib::note::create_swap_note;
+use miden_objects::{
+    account::AccountId,
+    asset::FungibleAsset,
+    note::{NoteTag, NoteType},
+    Felt,
+};
+
+use crate::{
+    check_out_capacity, get_handle, last_error, parse_account_id, proving, request_blocking,
+    screening,
+    types::WcNoteType,
+    whitelist, write_out_buffer, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM, ERR_NOTE_OP, ERR_TX_SUBMIT, ID_HEX_LEN,
+};
+
+/// Offer `offered` for `requested` from `account_id` in a SWAP note
+pub(crate) async fn create_swap_impl(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    offered: FungibleAsset,
+    requested: FungibleAsset,
+    note_type: NoteType,
+) -> Result<String, i32> {
+    let (note, payback) = create_swap_note(
+        account_id,
+        offered.into(),
+        requested.into(),
+        note_type,
+        Felt::new(0),
+        note_type,
+        Felt::new(0),
+        context.client.rng(),
+    )
+    .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?;
+
+    let output_notes = [note.clone()];
+    whitelist::check(context, account_id, &output_notes)?;
+    screening::screen_transaction(context, account_id, &output_notes, &[]).await?;
+
+    let tx_request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note)])
+        .expected_future_notes(vec![(payback, NoteTag::from_account_id(account_id))])
+        .build()
+        .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?;
+
+    proving::check_memory(context)?;
+    let tx_id = context.client.submit_new_transaction(account_id, tx_request).await
+        .map_err(|e| last_error::detail(ERR_TX_SUBMIT, e))?;
+    Ok(tx_id.to_hex())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Offer one fungible asset for another in a SWAP note (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+/// NOTE: Timeout (-99) only abandons waiting; the transaction may still be submitted.
+///
+/// The offered amount leaves the account with the SWAP note. Whoever consumes the note
+/// pays the requested amount back in a payback note of the same note type, which the
+/// next sync delivers. A private SWAP note must be shared out of band (see
+/// `wc_miden_export_note`) to be found.
+///
+/// # Parameters
+/// - `account_id_hex`: Offering account ID (C string)
+/// - `offered_faucet_id_hex` / `offered_amount`: Asset offered, in base units (C string, > 0)
+/// - `requested_faucet_id_hex` / `requested_amount`: Asset requested in return (C string, > 0)
+/// - `note_type`: `WcNoteType` value of the SWAP and payback notes (0 = public, 1 = private)
+/// - `tx_id_out` / `tx_id_out_len`: Output buffer for the transaction ID (hex, 66 bytes)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters (including an unknown note type)
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account or faucet ID
+/// - -4: SWAP note construction failed
+/// - -6: Transaction submission failed (e.g. insufficient balance)
+/// - -7: Output buffer too small, checked before anything is submitted
+/// - -8: Queue full
+/// - -99: Operation timed out
+/// - -101: The account has a withdrawal whitelist
+/// - -102: Blocked by the screening callback
+#[allow(clippy::too_many_arguments)]
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_create_swap(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    offered_faucet_id_hex: *const c_char,
+    offered_amount: u64,
+    requested_faucet_id_hex: *const c_char,
+    requested_amount: u64,
+    note_type: u32,
+    tx_id_out: *mut u8,
+    tx_id_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    if offered_amount == 0 || requested_amount == 0 {
+        return ERR_INVALID_PARAM;
+    }
+    if let Err(code) = check_out_capacity(tx_id_out, tx_id_out_len, ID_HEX_LEN) {
+        return code;
+    }
+    let note_type: NoteType = match WcNoteType::try_from(note_type) {
+        Ok(note_type) => note_type.into(),
+        Err(code) => return code,
+    };
+
+    let asset = |faucet_id_hex, amount| -> Result<FungibleAsset, i32> {
+        let (faucet_id, _) = parse_account_id(faucet_id_hex)?;
+        FungibleAsset::new(faucet_id, amount).map_err(|_| ERR_INVALID_PARAM)
+    };
+    let (account_id, offered, requested) = match (
+        parse_account_id(account_id_hex),
+        asset(offered_faucet_id_hex, offered_amount),
+        asset(requested_faucet_id_hex, requested_amount),
+    ) {
+        (Ok((account_id, _)), Ok(offered), Ok(requested)) => (account_id, offered, requested),
+        (Err(code), _, _) | (_, Err(code), _) | (_, _, Err(code)) => return code,
+    };
+
+    let request = |reply| Request::CreateSwap { account_id, offered, requested, note_type, reply };
+    match request_blocking(&worker, request) {
+        Ok(tx_id) => write_out_buffer(&tx_id, tx_id_out, tx_id_out_len),
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,1028 @@
+//! Multi-tenant profiles
+//!
+//! White-label apps host several user profiles on one device. `tenant_id` in the client
+//! config (see config.rs) opens one tenant of a shared store: the SQLite store holds the
+//! rows of every tenant, and the client only sees the tenant's own. The store schema
+//! belongs to `miden-client`, so ownership is kept in tables of this crate in the same
+//! database, and the client store is wrapped in a [`TenantStore`] that filters by them:
+//!
+//! - accounts: the ones the tenant created or imported (`wc_tenant_accounts`); an
+//!   account imported by two tenants is shared, any other account reads as unknown
+//! - transactions: the ones executed by the tenant's accounts
+//! - output notes: the ones sent by the tenant's accounts or created in its transactions
+//! - input notes: the ones the tenant imported or used (`wc_tenant_notes`), and the ones
+//!   whose tag the tenant tracks
+//! - note tags: the ones of the tenant's accounts and notes, and the tags the tenant added
+//!   (`wc_tenant_tags`)
+//! - settings: keys prefixed with the tenant
+//!
+//! Chain data (block headers, the partial MMR, the sync height) is shared, and a sync
+//! covers every tenant of the store: the store has a single sync height, so syncing one
+//! tenant's accounts and tags only would skip the other tenants' notes for good. The
+//! worker lifts the filter for the duration of a sync (see `sync_state_impl`).
+//!
+//! Keys and state files are per tenant:
+//!
+//! - keystore:    `<keystore_path>/tenants/<tenant_id>/`
+//! - state files: `<store_dir>/tenants/<tenant_id>/<store_file_name>.*` (retention state,
+//!   watches, the audit log, ...)
+//!
+//! One tenant of a store is open at a time (clients on one store keep separate caches of
+//! its accounts, and a sync writes every tenant's accounts); opening another tenant fails
+//! until the handles of the open one are destroyed, and a tenant is deleted only while no
+//! tenant of its store is open. A client created without `tenant_id` sees the whole store.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    os::raw::c_char,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use async_trait::async_trait;
+use miden_client::{
+    store::{
+        AccountRecord, AccountStatus, BlockRelevance, InputNoteRecord, NoteFilter,
+        OutputNoteRecord, PartialBlockchainFilter, Store, StoreError, TransactionFilter,
+    },
+    sync::{NoteTagRecord, NoteTagSource, StateSyncUpdate},
+    transaction::{TransactionRecord, TransactionStoreUpdate},
+};
+use miden_client_sqlite_store::SqliteStore;
+use miden_objects::{
+    account::{Account, AccountCode, AccountHeader, AccountId, AccountStorage},
+    address::Address,
+    asset::AssetVault,
+    block::{BlockHeader, BlockNumber},
+    crypto::merkle::{InOrderIndex, MmrPeaks},
+    note::{NoteScript, NoteTag},
+    utils::Serializable,
+    Word,
+};
+use once_cell::sync::Lazy;
+
+use crate::{
+    encrypted_store, parse_optional_str, parse_required_str, proof_cache, write_out_buffer,
+    ERR_ACCOUNT_OP, ERR_INVALID_PARAM,
+};
+
+/// Directory holding per-tenant keystores and state files
+const TENANTS_DIR: &str = "tenants";
+
+/// Maximum tenant ID length
+pub const TENANT_ID_MAX_LEN: usize = 64;
+
+const CREATE_TABLES: &str = "
+CREATE TABLE IF NOT EXISTS wc_tenants (
+    tenant_id TEXT NOT NULL,
+    PRIMARY KEY (tenant_id)
+);
+CREATE TABLE IF NOT EXISTS wc_tenant_accounts (
+    tenant_id TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    PRIMARY KEY (tenant_id, account_id)
+) WITHOUT ROWID;
+CREATE TABLE IF NOT EXISTS wc_tenant_notes (
+    tenant_id TEXT NOT NULL,
+    note_id TEXT NOT NULL,
+    PRIMARY KEY (tenant_id, note_id)
+) WITHOUT ROWID;
+CREATE TABLE IF NOT EXISTS wc_tenant_tags (
+    tenant_id TEXT NOT NULL,
+    tag INTEGER NOT NULL,
+    PRIMARY KEY (tenant_id, tag)
+) WITHOUT ROWID;
+";
+
+/// Tenant open on each shared store (by store path), with its handle count
+static OPEN_TENANTS: Lazy<Mutex<HashMap<PathBuf, (String, usize)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Marks a tenant of a store as open for the lifetime of a handle
+pub(crate) struct TenantLease {
+    store_path: PathBuf,
+}
+
+impl TenantLease {
+    /// Mark `tenant_id` as open on `store_path` (fails while another tenant of it is open)
+    pub fn acquire(store_path: &Path, tenant_id: &str) -> Result<Self, i32> {
+        let mut open = OPEN_TENANTS.lock().unwrap();
+        let entry = open.entry(store_path.to_path_buf()).or_insert((tenant_id.to_string(), 0));
+        if entry.0 != tenant_id {
+            return Err(ERR_INVALID_PARAM);
+        }
+        entry.1 += 1;
+        Ok(Self { store_path: store_path.to_path_buf() })
+    }
+}
+
+impl Drop for TenantLease {
+    fn drop(&mut self) {
+        let mut open = OPEN_TENANTS.lock().unwrap();
+        if let Some((_, count)) = open.get_mut(&self.store_path) {
+            *count -= 1;
+            if *count == 0 {
+                open.remove(&self.store_path);
+            }
+        }
+    }
+}
+
+/// Tenant IDs are 1-64 characters of `[A-Za-z0-9_-]` so they are safe as directory names
+pub(crate) fn validate_tenant_id(tenant_id: &str) -> Result<(), i32> {
+    let valid = !tenant_id.is_empty()
+        && tenant_id.len() <= TENANT_ID_MAX_LEN
+        && tenant_id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-');
+    if valid { Ok(()) } else { Err(ERR_INVALID_PARAM) }
+}
+
+/// Keystore directory of a tenant
+pub(crate) fn tenant_keystore_path(keystore_path: &Path, tenant_id: &str) -> PathBuf {
+    keystore_path.join(TENANTS_DIR).join(tenant_id)
+}
+
+/// Path the state files of a tenant are named after (see the module docs)
+pub(crate) fn tenant_state_path(store_path: &Path, tenant_id: &str) -> PathBuf {
+    let store_dir = store_path.parent().unwrap_or(Path::new("."));
+    let file_name = store_path.file_name().unwrap_or_default();
+    store_dir.join(TENANTS_DIR).join(tenant_id).join(file_name)
+}
+
+fn store_error(e: rusqlite::Error) -> StoreError {
+    StoreError::DatabaseError(e.to_string())
+}
+
+/// Tenant a client is scoped to
+pub(crate) struct TenantScope {
+    tenant_id: String,
+    /// Open `store_wide` sections
+    store_wide: AtomicUsize,
+}
+
+impl TenantScope {
+    pub fn new(tenant_id: String) -> Self {
+        Self { tenant_id, store_wide: AtomicUsize::new(0) }
+    }
+
+    pub fn tenant_id(&self) -> &str {
+        &self.tenant_id
+    }
+
+    /// Lift the filter of the scope's client until the guard is dropped (a sync)
+    pub fn store_wide(self: &Arc<Self>) -> StoreWide {
+        self.store_wide.fetch_add(1, Ordering::SeqCst);
+        StoreWide(self.clone())
+    }
+
+    fn is_scoped(&self) -> bool {
+        self.store_wide.load(Ordering::SeqCst) == 0
+    }
+}
+
+/// Section in which a tenant's client sees the whole store (see `TenantScope::store_wide`)
+pub(crate) struct StoreWide(Arc<TenantScope>);
+
+impl Drop for StoreWide {
+    fn drop(&mut self) {
+        self.0.store_wide.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Rows a tenant owns, as recorded in the ownership tables
+struct Owned {
+    accounts: BTreeSet<String>,
+    notes: BTreeSet<String>,
+    tags: BTreeSet<u32>,
+}
+
+impl Owned {
+    fn has_account(&self, account_id: AccountId) -> bool {
+        self.accounts.contains(&account_id.to_hex())
+    }
+
+    fn has_tag(&self, record: &NoteTagRecord) -> bool {
+        match &record.source {
+            NoteTagSource::Account(account_id) => self.has_account(*account_id),
+            NoteTagSource::Note(note_id) => self.notes.contains(&note_id.to_hex()),
+            NoteTagSource::User => self.tags.contains(&record.tag.as_u32()),
+        }
+    }
+}
+
+/// Client store scoped to one tenant (see the module docs)
+pub(crate) struct TenantStore {
+    inner: Arc<dyn Store>,
+    scope: Arc<TenantScope>,
+    /// Connection for the ownership tables
+    conn: Mutex<rusqlite::Connection>,
+}
+
+impl TenantStore {
+    /// Scope `inner`, opened on `database_path`, to the tenant of `scope`
+    pub fn open(
+        inner: Arc<dyn Store>,
+        database_path: &Path,
+        scope: Arc<TenantScope>,
+    ) -> Result<Self, String> {
+        let open = || -> rusqlite::Result<rusqlite::Connection> {
+            let conn = rusqlite::Connection::open(database_path)?;
+            conn.execute_batch(CREATE_TABLES)?;
+            conn.execute(
+                "INSERT OR IGNORE INTO wc_tenants (tenant_id) VALUES (?1)",
+                [scope.tenant_id()],
+            )?;
+            Ok(conn)
+        };
+        let conn = open().map_err(|e| format!("Failed to open tenant tables: {}", e))?;
+        Ok(Self { inner, scope, conn: Mutex::new(conn) })
+    }
+
+    fn owned(&self) -> Result<Owned, StoreError> {
+        let conn = self.conn.lock().unwrap();
+        let column = |sql: &str| -> rusqlite::Result<BTreeSet<String>> {
+            let mut stmt = conn.prepare(sql)?;
+            let rows = stmt.query_map([self.scope.tenant_id()], |row| row.get(0))?;
+            rows.collect()
+        };
+        let accounts =
+            column("SELECT account_id FROM wc_tenant_accounts WHERE tenant_id = ?1")
+                .map_err(store_error)?;
+        let notes = column("SELECT note_id FROM wc_tenant_notes WHERE tenant_id = ?1")
+            .map_err(store_error)?;
+
+        let mut stmt = conn
+            .prepare("SELECT tag FROM wc_tenant_tags WHERE tenant_id = ?1")
+            .map_err(store_error)?;
+        let tags = stmt
+            .query_map([self.scope.tenant_id()], |row| row.get(0))
+            .and_then(|rows| rows.collect::<rusqlite::Result<BTreeSet<u32>>>())
+            .map_err(store_error)?;
+        Ok(Owned { accounts, notes, tags })
+    }
+
+    /// Whether the client sees `account_id` (always outside the scope)
+    fn sees_account(&self, account_id: AccountId) -> Result<bool, StoreError> {
+        if !self.scope.is_scoped() {
+            return Ok(true);
+        }
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT COUNT(*) FROM wc_tenant_accounts WHERE tenant_id = ?1 AND account_id = ?2",
+            [self.scope.tenant_id(), &account_id.to_hex()],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|count| count > 0)
+        .map_err(store_error)
+    }
+
+    /// Record rows as the tenant's (`sql` inserts one row from the tenant and an ID)
+    fn record(&self, sql: &str, ids: impl IntoIterator<Item = String>) -> Result<(), StoreError> {
+        if !self.scope.is_scoped() {
+            return Ok(());
+        }
+        let conn = self.conn.lock().unwrap();
+        for id in ids {
+            conn.execute(sql, [self.scope.tenant_id(), &id]).map_err(store_error)?;
+        }
+        Ok(())
+    }
+
+    fn record_notes(&self, ids: impl IntoIterator<Item = String>) -> Result<(), StoreError> {
+        let sql = "INSERT OR IGNORE INTO wc_tenant_notes (tenant_id, note_id) VALUES (?1, ?2)";
+        self.record(sql, ids)
+    }
+
+    fn setting_key(&self, key: &str) -> String {
+        format!("tenant:{}:{}", self.scope.tenant_id(), key)
+    }
+
+    fn require_account(&self, account_id: AccountId) -> Result<(), StoreError> {
+        if self.sees_account(account_id)? {
+            Ok(())
+        } else {
+            Err(StoreError::AccountDataNotFound(account_id))
+        }
+    }
+}
+
+#[async_trait]
+impl Store for TenantStore {
+    fn get_current_timestamp(&self) -> Option<u64> {
+        self.inner.get_current_timestamp()
+    }
+
+    async fn get_transactions(
+        &self,
+        filter: TransactionFilter,
+    ) -> Result<Vec<TransactionRecord>, StoreError> {
+        let mut transactions = self.inner.get_transactions(filter).await?;
+        if self.scope.is_scoped() {
+            let owned = self.owned()?;
+            transactions.retain(|record| owned.has_account(record.details.account_id));
+        }
+        Ok(transactions)
+    }
+
+    async fn apply_transaction(&self, tx_update: TransactionStoreUpdate) -> Result<(), StoreError> {
+        let note_ids: Vec<String> = tx_update
+            .note_updates()
+            .updated_input_notes()
+            .map(|update| update.id().to_hex())
+            .chain(tx_update.future_notes().iter().map(|(details, _)| details.id().to_hex()))
+            .collect();
+        self.inner.apply_transaction(tx_update).await?;
+        self.record_notes(note_ids)
+    }
+
+    async fn get_input_notes(&self, filter: NoteFilter) -> Result<Vec<InputNoteRecord>, StoreError> {
+        let mut notes = self.inner.get_input_notes(filter).await?;
+        if self.scope.is_scoped() {
+            let owned = self.owned()?;
+            let tags: BTreeSet<NoteTag> = self.inner.get_note_tags().await?
+                .into_iter()
+                .filter(|record| owned.has_tag(record))
+                .map(|record| record.tag)
+                .collect();
+            notes.retain(|note| {
+                owned.notes.contains(&note.id().to_hex())
+                    || note.metadata().is_some_and(|metadata| tags.contains(&metadata.tag()))
+            });
+        }
+        Ok(notes)
+    }
+
+    async fn get_output_notes(&self, filter: NoteFilter) -> Result<Vec<OutputNoteRecord>, StoreError> {
+        let mut notes = self.inner.get_output_notes(filter).await?;
+        if self.scope.is_scoped() {
+            let owned = self.owned()?;
+            notes.retain(|note| {
+                owned.has_account(note.metadata().sender())
+                    || owned.notes.contains(&note.id().to_hex())
+            });
+        }
+        Ok(notes)
+    }
+
+    async fn upsert_input_notes(&self, notes: &[InputNoteRecord]) -> Result<(), StoreError> {
+        self.inner.upsert_input_notes(notes).await?;
+        self.record_notes(notes.iter().map(|note| note.id().to_hex()))
+    }
+
+    async fn get_note_script(&self, script_root: Word) -> Result<NoteScript, StoreError> {
+        self.inner.get_note_script(script_root).await
+    }
+
+    async fn upsert_note_scripts(&self, note_scripts: &[NoteScript]) -> Result<(), StoreError> {
+        self.inner.upsert_note_scripts(note_scripts).await
+    }
+
+    async fn get_block_headers(
+        &self,
+        block_numbers: &BTreeSet<BlockNumber>,
+    ) -> Result<Vec<(BlockHeader, BlockRelevance)>, StoreError> {
+        self.inner.get_block_headers(block_numbers).await
+    }
+
+    async fn get_tracked_block_headers(&self) -> Result<Vec<BlockHeader>, StoreError> {
+        self.inner.get_tracked_block_headers().await
+    }
+
+    async fn get_partial_blockchain_nodes(
+        &self,
+        filter: PartialBlockchainFilter,
+    ) -> Result<std::collections::BTreeMap<InOrderIndex, Word>, StoreError> {
+        self.inner.get_partial_blockchain_nodes(filter).await
+    }
+
+    async fn insert_partial_blockchain_nodes(
+        &self,
+        nodes: &[(InOrderIndex, Word)],
+    ) -> Result<(), StoreError> {
+        self.inner.insert_partial_blockchain_nodes(nodes).await
+    }
+
+    async fn get_partial_blockchain_peaks_by_block_num(
+        &self,
+        block_num: BlockNumber,
+    ) -> Result<MmrPeaks, StoreError> {
+        self.inner.get_partial_blockchain_peaks_by_block_num(block_num).await
+    }
+
+    async fn insert_block_header(
+        &self,
+        block_header: &BlockHeader,
+        partial_blockchain_peaks: MmrPeaks,
+        has_client_notes: bool,
+    ) -> Result<(), StoreError> {
+        self.inner
+            .insert_block_header(block_header, partial_blockchain_peaks, has_client_notes)
+            .await
+    }
+
+    async fn prune_irrelevant_blocks(&self) -> Result<(), StoreError> {
+        self.inner.prune_irrelevant_blocks().await
+    }
+
+    async fn get_account_ids(&self) -> Result<Vec<AccountId>, StoreError> {
+        let mut account_ids = self.inner.get_account_ids().await?;
+        if self.scope.is_scoped() {
+            let owned = self.owned()?;
+            account_ids.retain(|account_id| owned.has_account(*account_id));
+        }
+        Ok(account_ids)
+    }
+
+    async fn get_account_headers(&self) -> Result<Vec<(AccountHeader, AccountStatus)>, StoreError> {
+        let mut headers = self.inner.get_account_headers().await?;
+        if self.scope.is_scoped() {
+            let owned = self.owned()?;
+            headers.retain(|(header, _)| owned.has_account(header.id()));
+        }
+        Ok(headers)
+    }
+
+    async fn get_account_header(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<(AccountHeader, AccountStatus)>, StoreError> {
+        if !self.sees_account(account_id)? {
+            return Ok(None);
+        }
+        self.inner.get_account_header(account_id).await
+    }
+
+    async fn get_account_header_by_commitment(
+        &self,
+        account_commitment: Word,
+    ) -> Result<Option<AccountHeader>, StoreError> {
+        match self.inner.get_account_header_by_commitment(account_commitment).await? {
+            Some(header) if self.sees_account(header.id())? => Ok(Some(header)),
+            _ => Ok(None),
+        }
+    }
+
+    async fn get_account(&self, account_id: AccountId) -> Result<Option<AccountRecord>, StoreError> {
+        if !self.sees_account(account_id)? {
+            return Ok(None);
+        }
+        self.inner.get_account(account_id).await
+    }
+
+    async fn insert_account(&self, account: &Account, initial_address: Address) -> Result<(), StoreError> {
+        // An account another tenant already tracks is shared rather than inserted twice
+        if self.inner.get_account_header(account.id()).await?.is_none() {
+            self.inner.insert_account(account, initial_address).await?;
+        }
+        let sql =
+            "INSERT OR IGNORE INTO wc_tenant_accounts (tenant_id, account_id) VALUES (?1, ?2)";
+        self.record(sql, [account.id().to_hex()])
+    }
+
+    async fn upsert_foreign_account_code(
+        &self,
+        account_id: AccountId,
+        code: AccountCode,
+    ) -> Result<(), StoreError> {
+        self.inner.upsert_foreign_account_code(account_id, code).await
+    }
+
+    async fn get_foreign_account_code(
+        &self,
+        account_ids: Vec<AccountId>,
+    ) -> Result<std::collections::BTreeMap<AccountId, AccountCode>, StoreError> {
+        self.inner.get_foreign_account_code(account_ids).await
+    }
+
+    async fn get_addresses_by_account_id(&self, account_id: AccountId) -> Result<Vec<Address>, StoreError> {
+        if !self.sees_account(account_id)? {
+            return Ok(Vec::new());
+        }
+        self.inner.get_addresses_by_account_id(account_id).await
+    }
+
+    async fn update_account(&self, new_account_state: &Account) -> Result<(), StoreError> {
+        self.require_account(new_account_state.id())?;
+        self.inner.update_account(new_account_state).await
+    }
+
+    async fn insert_address(&self, address: Address, account_id: AccountId) -> Result<(), StoreError> {
+        self.require_account(account_id)?;
+        self.inner.insert_address(address, account_id).await
+    }
+
+    async fn remove_address(&self, address: Address, account_id: AccountId) -> Result<(), StoreError> {
+        self.require_account(account_id)?;
+        self.inner.remove_address(address, account_id).await
+    }
+
+    async fn set_setting(&self, key: String, value: Vec<u8>) -> Result<(), StoreError> {
+        self.inner.set_setting(self.setting_key(&key), value).await
+    }
+
+    async fn get_setting(&self, key: String) -> Result<Option<Vec<u8>>, StoreError> {
+        self.inner.get_setting(self.setting_key(&key)).await
+    }
+
+    async fn remove_setting(&self, key: String) -> Result<(), StoreError> {
+        self.inner.remove_setting(self.setting_key(&key)).await
+    }
+
+    async fn list_setting_keys(&self) -> Result<Vec<String>, StoreError> {
+        let prefix = self.setting_key("");
+        let keys = self.inner.list_setting_keys().await?;
+        Ok(keys.iter().filter_map(|key| key.strip_prefix(&prefix)).map(String::from).collect())
+    }
+
+    async fn get_note_tags(&self) -> Result<Vec<NoteTagRecord>, StoreError> {
+        let mut tags = self.inner.get_note_tags().await?;
+        if self.scope.is_scoped() {
+            let owned = self.owned()?;
+            tags.retain(|record| owned.has_tag(record));
+        }
+        Ok(tags)
+    }
+
+    async fn add_note_tag(&self, tag: NoteTagRecord) -> Result<bool, StoreError> {
+        let user_tag = matches!(tag.source, NoteTagSource::User).then(|| tag.tag.as_u32());
+        let added = self.inner.add_note_tag(tag).await?;
+        if let Some(tag) = user_tag {
+            let sql = "INSERT OR IGNORE INTO wc_tenant_tags (tenant_id, tag) VALUES (?1, ?2)";
+            self.record(sql, [tag.to_string()])?;
+        }
+        Ok(added)
+    }
+
+    async fn remove_note_tag(&self, tag: NoteTagRecord) -> Result<usize, StoreError> {
+        if !self.scope.is_scoped() {
+            return self.inner.remove_note_tag(tag).await;
+        }
+        if !self.owned()?.has_tag(&tag) {
+            return Ok(0);
+        }
+        if !matches!(tag.source, NoteTagSource::User) {
+            return self.inner.remove_note_tag(tag).await;
+        }
+
+        // A user tag stays in the store while another tenant tracks it
+        let others = {
+            let conn = self.conn.lock().unwrap();
+            let value = tag.tag.as_u32();
+            conn.execute(
+                "DELETE FROM wc_tenant_tags WHERE tenant_id = ?1 AND tag = ?2",
+                rusqlite::params![self.scope.tenant_id(), value],
+            )
+            .and_then(|_| {
+                conn.query_row(
+                    "SELECT COUNT(*) FROM wc_tenant_tags WHERE tag = ?1",
+                    [value],
+                    |row| row.get::<_, i64>(0),
+                )
+            })
+            .map_err(store_error)?
+        };
+        if others > 0 {
+            return Ok(1);
+        }
+        self.inner.remove_note_tag(tag).await
+    }
+
+    async fn get_sync_height(&self) -> Result<BlockNumber, StoreError> {
+        self.inner.get_sync_height().await
+    }
+
+    async fn apply_state_sync(&self, state_sync_update: StateSyncUpdate) -> Result<(), StoreError> {
+        self.inner.apply_state_sync(state_sync_update).await
+    }
+
+    async fn get_account_vault(&self, account_id: AccountId) -> Result<AssetVault, StoreError> {
+        self.require_account(account_id)?;
+        self.inner.get_account_vault(account_id).await
+    }
+
+    async fn get_account_storage(&self, account_id: AccountId) -> Result<AccountStorage, StoreError> {
+        self.require_account(account_id)?;
+        self.inner.get_account_storage(account_id).await
+    }
+}
+
+/// Tenants recorded in the store at `database_path` (none before a tenant was opened)
+fn recorded_tenants(conn: &rusqlite::Connection) -> rusqlite::Result<Vec<String>> {
+    conn.execute_batch(CREATE_TABLES)?;
+    let mut stmt = conn.prepare("SELECT tenant_id FROM wc_tenants ORDER BY tenant_id")?;
+    stmt.query_map([], |row| row.get(0))?.collect()
+}
+
+/// Rows only `tenant_id` sees, as hex IDs (tag records serialized)
+#[derive(Default)]
+struct TenantRows {
+    accounts: Vec<String>,
+    transactions: Vec<String>,
+    input_notes: Vec<String>,
+    output_notes: Vec<String>,
+    tags: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Work out which rows of the store go with the tenant (its accounts that no other tenant
+/// imported, their transactions, and the notes and tags no other tenant sees)
+async fn tenant_rows(database_path: &Path, tenant_id: &str) -> Result<TenantRows, String> {
+    let store = SqliteStore::new(database_path.to_path_buf())
+        .await
+        .map_err(|e| format!("Failed to open store: {:?}", e))?;
+    let inner: Arc<dyn Store> = Arc::new(store);
+    let scoped = |tenant_id: &str| {
+        let scope = Arc::new(TenantScope::new(tenant_id.to_string()));
+        TenantStore::open(inner.clone(), database_path, scope)
+    };
+
+    let tenant = scoped(tenant_id)?;
+    let mut others = Vec::new();
+    let tenants = recorded_tenants(&tenant.conn.lock().unwrap()).map_err(|e| e.to_string())?;
+    for other in tenants.iter().filter(|other| *other != tenant_id) {
+        others.push(scoped(other)?);
+    }
+
+    let error = |e: StoreError| e.to_string();
+    let owned = tenant.owned().map_err(error)?;
+    let mut shared =
+        Owned { accounts: BTreeSet::new(), notes: BTreeSet::new(), tags: BTreeSet::new() };
+    let mut seen_inputs = BTreeSet::new();
+    let mut seen_outputs = BTreeSet::new();
+    for other in &others {
+        let other_owned = other.owned().map_err(error)?;
+        shared.accounts.extend(other_owned.accounts);
+        shared.tags.extend(other_owned.tags);
+        let inputs = other.get_input_notes(NoteFilter::All).await.map_err(error)?;
+        seen_inputs.extend(inputs.iter().map(|note| note.id().to_hex()));
+        let outputs = other.get_output_notes(NoteFilter::All).await.map_err(error)?;
+        seen_outputs.extend(outputs.iter().map(|note| note.id().to_hex()));
+    }
+
+    let mut rows = TenantRows {
+        accounts: owned.accounts.difference(&shared.accounts).cloned().collect(),
+        ..TenantRows::default()
+    };
+    let exclusive = |account_id: AccountId| rows.accounts.contains(&account_id.to_hex());
+    rows.transactions = tenant.get_transactions(TransactionFilter::All).await.map_err(error)?
+        .iter()
+        .filter(|record| exclusive(record.details.account_id))
+        .map(|record| record.id.to_hex())
+        .collect();
+    rows.input_notes = tenant.get_input_notes(NoteFilter::All).await.map_err(error)?
+        .iter()
+        .map(|note| note.id().to_hex())
+        .filter(|id| !seen_inputs.contains(id))
+        .collect();
+    rows.output_notes = tenant.get_output_notes(NoteFilter::All).await.map_err(error)?
+        .iter()
+        .map(|note| note.id().to_hex())
+        .filter(|id| !seen_outputs.contains(id))
+        .collect();
+    rows.tags = tenant.get_note_tags().await.map_err(error)?
+        .iter()
+        .filter(|record| match &record.source {
+            NoteTagSource::Account(account_id) => exclusive(*account_id),
+            NoteTagSource::Note(note_id) => {
+                let id = note_id.to_hex();
+                rows.input_notes.contains(&id) || rows.output_notes.contains(&id)
+            }
+            NoteTagSource::User => !shared.tags.contains(&record.tag.as_u32()),
+        })
+        .map(|record| (record.tag.to_bytes(), record.source.to_bytes()))
+        .collect();
+    Ok(rows)
+}
+
+/// Delete the tenant's rows and ownership records from the store, in one transaction
+fn delete_rows(database_path: &Path, tenant_id: &str, rows: &TenantRows) -> rusqlite::Result<()> {
+    let mut conn = rusqlite::Connection::open(database_path)?;
+    let tx = conn.transaction()?;
+
+    // Account states are content-addressed: drop code, storage and vault rows that no
+    // remaining account state refers to
+    let mut states: Vec<(String, String, String)> = Vec::new();
+    for account_id in &rows.accounts {
+        let mut stmt = tx.prepare(
+            "SELECT code_commitment, storage_commitment, vault_root FROM accounts WHERE id = ?1",
+        )?;
+        let found =
+            stmt.query_map([account_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        states.extend(found.collect::<rusqlite::Result<Vec<_>>>()?);
+
+        tx.execute("DELETE FROM accounts WHERE id = ?1", [account_id])?;
+        tx.execute("DELETE FROM tracked_accounts WHERE id = ?1", [account_id])?;
+        tx.execute("DELETE FROM addresses WHERE account_id = ?1", [account_id])?;
+    }
+    for (code, storage, vault) in &states {
+        let mut stmt = tx.prepare(
+            "SELECT slot_value FROM account_storage
+             WHERE commitment = ?1 AND slot_value IS NOT NULL",
+        )?;
+        let slot_values = stmt
+            .query_map([storage], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        tx.execute(
+            "DELETE FROM account_code WHERE commitment = ?1
+             AND commitment NOT IN (SELECT code_commitment FROM accounts)
+             AND commitment NOT IN (SELECT code_commitment FROM foreign_account_code)",
+            [code],
+        )?;
+        tx.execute(
+            "DELETE FROM account_storage WHERE commitment = ?1
+             AND commitment NOT IN (SELECT storage_commitment FROM accounts)",
+            [storage],
+        )?;
+        tx.execute(
+            "DELETE FROM account_assets WHERE root = ?1
+             AND root NOT IN (SELECT vault_root FROM accounts)",
+            [vault],
+        )?;
+        for root in &slot_values {
+            tx.execute(
+                "DELETE FROM storage_map_entries WHERE root = ?1
+                 AND root NOT IN
+                     (SELECT slot_value FROM account_storage WHERE slot_value IS NOT NULL)",
+                [root],
+            )?;
+        }
+    }
+
+    for id in &rows.transactions {
+        tx.execute("DELETE FROM transactions WHERE id = ?1", [id])?;
+    }
+    for id in &rows.input_notes {
+        tx.execute("DELETE FROM input_notes WHERE note_id = ?1", [id])?;
+    }
+    for id in &rows.output_notes {
+        tx.execute("DELETE FROM output_notes WHERE note_id = ?1", [id])?;
+    }
+    for (tag, source) in &rows.tags {
+        let params = rusqlite::params![tag, source];
+        tx.execute("DELETE FROM tags WHERE tag = ?1 AND source = ?2", params)?;
+    }
+    proof_cache::evict(&tx, &rows.input_notes)?;
+
+    for table in ["wc_tenant_accounts", "wc_tenant_notes", "wc_tenant_tags", "wc_tenants"] {
+        tx.execute(&format!("DELETE FROM {} WHERE tenant_id = ?1", table), [tenant_id])?;
+    }
+    tx.execute(
+        "DELETE FROM settings WHERE name LIKE ?1",
+        [format!("tenant:{}:%", tenant_id)],
+    )?;
+    tx.commit()
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// List the tenants of the store at `store_path`
+///
+/// # Parameters
+/// - `store_path`: Store file path, as in the client config
+/// - `store_key`: Key of an encrypted store (can be NULL for a plain store)
+///
+/// # Output JSON
+/// `["alice","bob"]` (sorted; empty for a store that does not exist yet)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -3: The store could not be read (wrong key)
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_list_tenants(
+    store_path: *const c_char,
+    store_key: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let paths = parse_required_str(store_path)
+        .and_then(|store_path| Ok((PathBuf::from(store_path), parse_optional_str(store_key)?)));
+    let (store_path, store_key) = match paths {
+        Ok(paths) => paths,
+        Err(code) => return code,
+    };
+
+    let tenants = if store_path.exists() {
+        let database_path = encrypted_store::database_path(&store_path, store_key);
+        match rusqlite::Connection::open(database_path).and_then(|conn| recorded_tenants(&conn)) {
+            Ok(tenants) => tenants,
+            Err(_) => return ERR_ACCOUNT_OP,
+        }
+    } else {
+        Vec::new()
+    };
+
+    let json = serde_json::Value::from(tenants).to_string();
+    write_out_buffer(json.as_bytes(), json_out, json_out_len)
+}
+
+/// Delete a tenant: its rows in the store (accounts no other tenant imported, their
+/// transactions, and the notes and tags no other tenant sees), its keystore and its
+/// state files
+///
+/// # Parameters
+/// - `keystore_path` / `store_path`: Base keystore directory and store file path, as in
+///   the client config
+/// - `store_key`: Key of an encrypted store (can be NULL for a plain store)
+/// - `tenant_id`: Tenant to delete
+///
+/// # Returns
+/// - 0: Success (also when the tenant does not exist)
+/// - -1: Invalid parameters, or a tenant of the store is open
+/// - -3: The store could not be updated or the files could not be removed
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_delete_tenant(
+    keystore_path: *const c_char,
+    store_path: *const c_char,
+    store_key: *const c_char,
+    tenant_id: *const c_char,
+) -> i32 {
+    let parsed = parse_required_str(keystore_path).and_then(|keystore_path| {
+        let store_path = parse_required_str(store_path)?;
+        let store_key = parse_optional_str(store_key)?;
+        let tenant_id = parse_required_str(tenant_id)?;
+        validate_tenant_id(tenant_id)?;
+        Ok((Path::new(keystore_path), Path::new(store_path), store_key, tenant_id))
+    });
+    let (base_keystore, store_path, store_key, tenant_id) = match parsed {
+        Ok(parsed) => parsed,
+        Err(code) => return code,
+    };
+
+    // Held until the rows are gone, so the store cannot be opened for a tenant meanwhile
+    let open = OPEN_TENANTS.lock().unwrap();
+    if open.contains_key(store_path) {
+        return ERR_INVALID_PARAM;
+    }
+
+    if store_path.exists() {
+        let database_path = encrypted_store::database_path(store_path, store_key);
+        let rt = match tokio::runtime::Builder::new_current_thread().build() {
+            Ok(rt) => rt,
+            Err(_) => return ERR_ACCOUNT_OP,
+        };
+        let deleted = rt
+            .block_on(tenant_rows(&database_path, tenant_id))
+            .and_then(|rows| {
+                delete_rows(&database_path, tenant_id, &rows).map_err(|e| e.to_string())
+            });
+        if deleted.is_err() {
+            return ERR_ACCOUNT_OP;
+        }
+    }
+
+    let state_dir = tenant_state_path(store_path, tenant_id).parent().map(Path::to_path_buf);
+    let dirs = [state_dir, Some(tenant_keystore_path(base_keystore, tenant_id))];
+    for dir in dirs.iter().flatten() {
+        match std::fs::remove_dir_all(dir) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(_) => return ERR_ACCOUNT_OP,
+        }
+    }
+    0
+}
+
+#[cfg(all(test, feature = "mock-node"))]
+mod tests {
+    use miden_client::{
+        auth::AuthSecretKey,
+        builder::ClientBuilder,
+        keystore::FilesystemKeyStore,
+        testing::mock::MockRpcApi,
+        transaction::TransactionRequestBuilder,
+        Client,
+    };
+    use miden_lib::account::{auth::AuthRpoFalcon512, faucets::BasicFungibleFaucet};
+    use miden_objects::{
+        account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType},
+        asset::{FungibleAsset, TokenSymbol},
+        note::NoteType,
+        Felt,
+    };
+    use rand::rngs::StdRng;
+
+    use super::*;
+    use crate::wallet_core;
+
+    type TestClient = Client<FilesystemKeyStore<StdRng>>;
+
+    fn run(test: impl std::future::Future<Output = ()>) {
+        tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(test);
+    }
+
+    /// Client of `tenant_id` on the store of `dir`, connected to `rpc`
+    async fn tenant_client(
+        dir: &Path,
+        rpc: Arc<MockRpcApi>,
+        tenant_id: &str,
+    ) -> (TestClient, FilesystemKeyStore<StdRng>, Arc<TenantScope>) {
+        let keystore_dir = tenant_keystore_path(&dir.join("keystore"), tenant_id);
+        std::fs::create_dir_all(&keystore_dir).unwrap();
+        let keystore = FilesystemKeyStore::new(keystore_dir).unwrap();
+
+        let database_path = dir.join("store.sqlite3");
+        let inner = SqliteStore::new(database_path.clone()).await.unwrap();
+        let scope = Arc::new(TenantScope::new(tenant_id.to_string()));
+        let store = TenantStore::open(Arc::new(inner), &database_path, scope.clone()).unwrap();
+        let client = ClientBuilder::new()
+            .rpc(rpc)
+            .store(Arc::new(store))
+            .authenticator(Arc::new(keystore.clone()))
+            .in_debug_mode(true.into())
+            .build()
+            .await
+            .unwrap();
+        (client, keystore, scope)
+    }
+
+    fn falcon_auth(keystore: &FilesystemKeyStore<StdRng>) -> AccountComponent {
+        let key = AuthSecretKey::new_rpo_falcon512();
+        keystore.add_key(&key).unwrap();
+        AuthRpoFalcon512::new(key.public_key().to_commitment()).into()
+    }
+
+    async fn add_wallet(
+        client: &mut TestClient,
+        keystore: &FilesystemKeyStore<StdRng>,
+        seed: u8,
+    ) -> AccountId {
+        let auth = falcon_auth(keystore);
+        let account =
+            wallet_core::build_wallet_account([seed; 32], auth, AccountStorageMode::Public)
+                .unwrap();
+        wallet_core::add_account(client, &account).await.unwrap();
+        account.id()
+    }
+
+    /// Sync the whole store, as the worker does
+    async fn sync(client: &mut TestClient, scope: &Arc<TenantScope>) {
+        let _store_wide = scope.store_wide();
+        wallet_core::sync(client).await.unwrap();
+    }
+
+    #[test]
+    fn tenants_of_one_store_see_only_their_rows() {
+        run(async {
+            let dir = std::env::temp_dir()
+                .join(format!("miden-tenants-test-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            let rpc = Arc::new(MockRpcApi::default());
+
+            // Alice mints into her wallet and syncs
+            let (mut alice, keystore, scope) = tenant_client(&dir, rpc.clone(), "alice").await;
+            let wallet = add_wallet(&mut alice, &keystore, 1).await;
+            let faucet = AccountBuilder::new([7; 32])
+                .account_type(AccountType::FungibleFaucet)
+                .storage_mode(AccountStorageMode::Public)
+                .with_auth_component(falcon_auth(&keystore))
+                .with_component(
+                    BasicFungibleFaucet::new(TokenSymbol::new("TST").unwrap(), 8, Felt::new(1_000))
+                        .unwrap(),
+                )
+                .build()
+                .unwrap();
+            wallet_core::add_account(&mut alice, &faucet).await.unwrap();
+            sync(&mut alice, &scope).await;
+
+            let asset = FungibleAsset::new(faucet.id(), 100).unwrap();
+            let request = TransactionRequestBuilder::new()
+                .build_mint_fungible_asset(asset, wallet, NoteType::Public, alice.rng())
+                .unwrap();
+            wallet_core::submit(&mut alice, faucet.id(), request).await.unwrap();
+            rpc.prove_block();
+            sync(&mut alice, &scope).await;
+            assert_eq!(alice.get_input_notes(NoteFilter::All).await.unwrap().len(), 1);
+            drop(alice);
+
+            // Bob shares the store but sees none of it
+            let (mut bob, keystore, _) = tenant_client(&dir, rpc.clone(), "bob").await;
+            let bob_wallet = add_wallet(&mut bob, &keystore, 2).await;
+            assert_eq!(wallet_core::account_ids(&bob).await.unwrap(), vec![bob_wallet]);
+            assert!(bob.get_account(wallet).await.unwrap().is_none());
+            assert!(bob.get_input_notes(NoteFilter::All).await.unwrap().is_empty());
+            assert!(bob.get_output_notes(NoteFilter::All).await.unwrap().is_empty());
+            assert!(bob.get_transactions(TransactionFilter::All).await.unwrap().is_empty());
+            drop(bob);
+
+            // Deleting Alice leaves Bob's rows only
+            let database_path = dir.join("store.sqlite3");
+            let rows = tenant_rows(&database_path, "alice").await.unwrap();
+            delete_rows(&database_path, "alice", &rows).unwrap();
+            let store = SqliteStore::new(database_path.clone()).await.unwrap();
+            assert_eq!(store.get_account_ids().await.unwrap(), vec![bob_wallet]);
+            assert!(store.get_input_notes(NoteFilter::All).await.unwrap().is_empty());
+            assert!(store.get_transactions(TransactionFilter::All).await.unwrap().is_empty());
+            let conn = rusqlite::Connection::open(&database_path).unwrap();
+            assert_eq!(recorded_tenants(&conn).unwrap(), vec!["bob".to_string()]);
+
+            let _ = std::fs::remove_dir_all(&dir);
+        });
+    }
+}
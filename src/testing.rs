@@ -0,0 +1,361 @@
+//! Deterministic failure injection for integration tests
+//!
+//! With the `testing` feature enabled, `wc_test_inject_failure` arms a counter per failure
+//! kind; the next `count` operations of that kind fail with their usual error code before
+//! touching the network, the store or the prover. Swift integration tests use this to
+//! exercise error handling and retry UX without network tricks.
+//!
+//! Each kind has one fail point, where every operation of the kind passes:
+//!
+//! - RPC: every node call of [`RateLimitedRpc`](crate::rate_limit::RateLimitedRpc), made by
+//!   this crate or by `miden-client`
+//! - store writes: every write method of the client store, which testing builds wrap in
+//!   [`FailPointStore`] (see `with_fail_points`)
+//! - proofs: the client's transaction prover, wrapped in [`FailPointProver`], so every
+//!   transaction fails before it is proven and submitted
+//!
+//! Without the feature the wrappers are not built and `should_fail` compiles to `false`.
+
+#[cfg(feature = "testing")]
+pub(crate) use fail_points::with_fail_points;
+
+/// Operation class a failure can be injected into
+#[derive(Clone, Copy)]
+#[cfg_attr(not(feature = "testing"), allow(dead_code))]
+pub(crate) enum FailureKind {
+    /// Node RPC calls
+    Rpc = 0,
+    /// Writes to the client store
+    StoreWrite = 1,
+    /// Transaction proving (and so submission)
+    Proof = 2,
+}
+
+#[cfg(feature = "testing")]
+mod counters {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::FailureKind;
+
+    static PENDING: [AtomicU32; 3] = [AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0)];
+
+    pub fn arm(kind: FailureKind, count: u32) {
+        PENDING[kind as usize].store(count, Ordering::SeqCst);
+    }
+
+    pub fn take(kind: FailureKind) -> bool {
+        PENDING[kind as usize]
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+    }
+}
+
+/// Whether the next operation of `kind` must fail (consumes one injected failure)
+#[cfg(feature = "testing")]
+pub(crate) fn should_fail(kind: FailureKind) -> bool {
+    counters::take(kind)
+}
+
+/// Whether the next operation of `kind` must fail (always false without the `testing` feature)
+#[cfg(not(feature = "testing"))]
+#[inline(always)]
+pub(crate) fn should_fail(_kind: FailureKind) -> bool {
+    false
+}
+
+#[cfg(feature = "testing")]
+mod fail_points {
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        sync::Arc,
+    };
+
+    use async_trait::async_trait;
+    use miden_client::{
+        builder::ClientBuilder,
+        store::{
+            AccountRecord, AccountStatus, BlockRelevance, InputNoteRecord, NoteFilter,
+            OutputNoteRecord, PartialBlockchainFilter, Store, StoreError, TransactionFilter,
+        },
+        sync::{NoteTagRecord, StateSyncUpdate},
+        transaction::{
+            LocalTransactionProver, ProvenTransaction, TransactionInputs, TransactionProver,
+            TransactionProverError, TransactionRecord, TransactionStoreUpdate,
+        },
+    };
+    use miden_client_sqlite_store::SqliteStore;
+    use miden_objects::{
+        account::{Account, AccountCode, AccountHeader, AccountId, AccountStorage},
+        address::Address,
+        asset::AssetVault,
+        block::{BlockHeader, BlockNumber},
+        crypto::merkle::{InOrderIndex, MmrPeaks},
+        note::NoteScript,
+        Word,
+    };
+
+    use super::{should_fail, FailureKind};
+    use crate::auth::WalletAuthenticator;
+
+    /// Put the client store and prover of `builder` behind the fail points
+    pub(crate) fn with_fail_points(
+        builder: ClientBuilder<WalletAuthenticator>,
+        store: SqliteStore,
+    ) -> (ClientBuilder<WalletAuthenticator>, Arc<dyn Store>) {
+        let builder = builder.prover(Arc::new(FailPointProver(LocalTransactionProver::default())));
+        (builder, Arc::new(FailPointStore(store)))
+    }
+
+    fn check_write() -> Result<(), StoreError> {
+        if should_fail(FailureKind::StoreWrite) {
+            return Err(StoreError::DatabaseError("injected store write failure".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Client store whose writes pass the `StoreWrite` fail point
+    pub(crate) struct FailPointStore(SqliteStore);
+
+    #[async_trait]
+    impl Store for FailPointStore {
+        fn get_current_timestamp(&self) -> Option<u64> {
+            self.0.get_current_timestamp()
+        }
+
+        async fn get_transactions(
+            &self,
+            filter: TransactionFilter,
+        ) -> Result<Vec<TransactionRecord>, StoreError> {
+            self.0.get_transactions(filter).await
+        }
+
+        async fn apply_transaction(&self, tx_update: TransactionStoreUpdate) -> Result<(), StoreError> {
+            check_write()?;
+            self.0.apply_transaction(tx_update).await
+        }
+
+        async fn get_input_notes(&self, filter: NoteFilter) -> Result<Vec<InputNoteRecord>, StoreError> {
+            self.0.get_input_notes(filter).await
+        }
+
+        async fn get_output_notes(&self, filter: NoteFilter) -> Result<Vec<OutputNoteRecord>, StoreError> {
+            self.0.get_output_notes(filter).await
+        }
+
+        async fn upsert_input_notes(&self, notes: &[InputNoteRecord]) -> Result<(), StoreError> {
+            check_write()?;
+            self.0.upsert_input_notes(notes).await
+        }
+
+        async fn get_note_script(&self, script_root: Word) -> Result<NoteScript, StoreError> {
+            self.0.get_note_script(script_root).await
+        }
+
+        async fn upsert_note_scripts(&self, note_scripts: &[NoteScript]) -> Result<(), StoreError> {
+            check_write()?;
+            self.0.upsert_note_scripts(note_scripts).await
+        }
+
+        async fn get_block_headers(
+            &self,
+            block_numbers: &BTreeSet<BlockNumber>,
+        ) -> Result<Vec<(BlockHeader, BlockRelevance)>, StoreError> {
+            self.0.get_block_headers(block_numbers).await
+        }
+
+        async fn get_tracked_block_headers(&self) -> Result<Vec<BlockHeader>, StoreError> {
+            self.0.get_tracked_block_headers().await
+        }
+
+        async fn get_partial_blockchain_nodes(
+            &self,
+            filter: PartialBlockchainFilter,
+        ) -> Result<BTreeMap<InOrderIndex, Word>, StoreError> {
+            self.0.get_partial_blockchain_nodes(filter).await
+        }
+
+        async fn insert_partial_blockchain_nodes(
+            &self,
+            nodes: &[(InOrderIndex, Word)],
+        ) -> Result<(), StoreError> {
+            check_write()?;
+            self.0.insert_partial_blockchain_nodes(nodes).await
+        }
+
+        async fn get_partial_blockchain_peaks_by_block_num(
+            &self,
+            block_num: BlockNumber,
+        ) -> Result<MmrPeaks, StoreError> {
+            self.0.get_partial_blockchain_peaks_by_block_num(block_num).await
+        }
+
+        async fn insert_block_header(
+            &self,
+            block_header: &BlockHeader,
+            partial_blockchain_peaks: MmrPeaks,
+            has_client_notes: bool,
+        ) -> Result<(), StoreError> {
+            check_write()?;
+            self.0.insert_block_header(block_header, partial_blockchain_peaks, has_client_notes).await
+        }
+
+        async fn prune_irrelevant_blocks(&self) -> Result<(), StoreError> {
+            check_write()?;
+            self.0.prune_irrelevant_blocks().await
+        }
+
+        async fn get_account_ids(&self) -> Result<Vec<AccountId>, StoreError> {
+            self.0.get_account_ids().await
+        }
+
+        async fn get_account_headers(&self) -> Result<Vec<(AccountHeader, AccountStatus)>, StoreError> {
+            self.0.get_account_headers().await
+        }
+
+        async fn get_account_header(
+            &self,
+            account_id: AccountId,
+        ) -> Result<Option<(AccountHeader, AccountStatus)>, StoreError> {
+            self.0.get_account_header(account_id).await
+        }
+
+        async fn get_account_header_by_commitment(
+            &self,
+            account_commitment: Word,
+        ) -> Result<Option<AccountHeader>, StoreError> {
+            self.0.get_account_header_by_commitment(account_commitment).await
+        }
+
+        async fn get_account(&self, account_id: AccountId) -> Result<Option<AccountRecord>, StoreError> {
+            self.0.get_account(account_id).await
+        }
+
+        async fn insert_account(&self, account: &Account, initial_address: Address) -> Result<(), StoreError> {
+            check_write()?;
+            self.0.insert_account(account, initial_address).await
+        }
+
+        async fn upsert_foreign_account_code(
+            &self,
+            account_id: AccountId,
+            code: AccountCode,
+        ) -> Result<(), StoreError> {
+            check_write()?;
+            self.0.upsert_foreign_account_code(account_id, code).await
+        }
+
+        async fn get_foreign_account_code(
+            &self,
+            account_ids: Vec<AccountId>,
+        ) -> Result<BTreeMap<AccountId, AccountCode>, StoreError> {
+            self.0.get_foreign_account_code(account_ids).await
+        }
+
+        async fn get_addresses_by_account_id(&self, account_id: AccountId) -> Result<Vec<Address>, StoreError> {
+            self.0.get_addresses_by_account_id(account_id).await
+        }
+
+        async fn update_account(&self, new_account_state: &Account) -> Result<(), StoreError> {
+            check_write()?;
+            self.0.update_account(new_account_state).await
+        }
+
+        async fn insert_address(&self, address: Address, account_id: AccountId) -> Result<(), StoreError> {
+            check_write()?;
+            self.0.insert_address(address, account_id).await
+        }
+
+        async fn remove_address(&self, address: Address, account_id: AccountId) -> Result<(), StoreError> {
+            check_write()?;
+            self.0.remove_address(address, account_id).await
+        }
+
+        async fn set_setting(&self, key: String, value: Vec<u8>) -> Result<(), StoreError> {
+            check_write()?;
+            self.0.set_setting(key, value).await
+        }
+
+        async fn get_setting(&self, key: String) -> Result<Option<Vec<u8>>, StoreError> {
+            self.0.get_setting(key).await
+        }
+
+        async fn remove_setting(&self, key: String) -> Result<(), StoreError> {
+            check_write()?;
+            self.0.remove_setting(key).await
+        }
+
+        async fn list_setting_keys(&self) -> Result<Vec<String>, StoreError> {
+            self.0.list_setting_keys().await
+        }
+
+        async fn get_note_tags(&self) -> Result<Vec<NoteTagRecord>, StoreError> {
+            self.0.get_note_tags().await
+        }
+
+        async fn add_note_tag(&self, tag: NoteTagRecord) -> Result<bool, StoreError> {
+            check_write()?;
+            self.0.add_note_tag(tag).await
+        }
+
+        async fn remove_note_tag(&self, tag: NoteTagRecord) -> Result<usize, StoreError> {
+            check_write()?;
+            self.0.remove_note_tag(tag).await
+        }
+
+        async fn get_sync_height(&self) -> Result<BlockNumber, StoreError> {
+            self.0.get_sync_height().await
+        }
+
+        async fn apply_state_sync(&self, state_sync_update: StateSyncUpdate) -> Result<(), StoreError> {
+            check_write()?;
+            self.0.apply_state_sync(state_sync_update).await
+        }
+
+        async fn get_account_vault(&self, account_id: AccountId) -> Result<AssetVault, StoreError> {
+            self.0.get_account_vault(account_id).await
+        }
+
+        async fn get_account_storage(&self, account_id: AccountId) -> Result<AccountStorage, StoreError> {
+            self.0.get_account_storage(account_id).await
+        }
+    }
+
+    /// Local prover behind the `Proof` fail point
+    pub(crate) struct FailPointProver(LocalTransactionProver);
+
+    #[async_trait]
+    impl TransactionProver for FailPointProver {
+        async fn prove(&self, inputs: TransactionInputs) -> Result<ProvenTransaction, TransactionProverError> {
+            if should_fail(FailureKind::Proof) {
+                return Err(TransactionProverError::other("injected proof failure"));
+            }
+            TransactionProver::prove(&self.0, inputs).await
+        }
+    }
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Make the next `count` operations of `kind` fail (testing builds only)
+///
+/// # Parameters
+/// - `kind`: 0 = node RPC calls, 1 = client store writes, 2 = transaction proofs
+/// - `count`: Number of operations to fail; 0 clears pending failures of that kind
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Unknown kind
+#[cfg(feature = "testing")]
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_test_inject_failure(kind: u32, count: u32) -> i32 {
+    let kind = match kind {
+        0 => FailureKind::Rpc,
+        1 => FailureKind::StoreWrite,
+        2 => FailureKind::Proof,
+        _ => return crate::ERR_INVALID_PARAM,
+    };
+    counters::arm(kind, count);
+    0
+}
@@ -0,0 +1,292 @@
+//! Travel-rule metadata envelopes
+//!
+//! Institutional transfers must carry originator/beneficiary information that only the
+//! receiving institution can read. Miden account keys are signing keys, so each local
+//! account gets a separate X25519 travel-rule key, stored in the keystore directory and
+//! published to counterparties out of band (e.g. through a VASP directory). In-memory
+//! clients have no keystore directory and cannot hold travel-rule keys.
+//!
+//! A send with a `travel_rule` section seals the metadata to the recipient's travel-rule
+//! key (ephemeral X25519 + ChaCha20-Poly1305, keyed with SHA3-256) and binds it to the
+//! payment note's ID. The P2ID script takes a fixed set of note inputs, so the envelope
+//! cannot travel on chain: it is returned with the send result and delivered alongside
+//! the note. The recipient opens it with `wc_miden_decode_travel_rule`.
+
+use std::{
+    os::raw::c_char,
+    path::{Path, PathBuf},
+};
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use miden_objects::{account::AccountId, note::NoteId};
+use rand::RngCore;
+use sha3::{Digest, Sha3_256};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::{
+    get_handle, last_error, memory_store, parse_account_id, parse_required_str, request_blocking,
+    write_out_buffer, MidenContext, MidenHandle, Request, ERR_ACCOUNT_OP, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM, ERR_LOOKUP,
+};
+
+/// Version tag of the envelope format
+pub const TRAVEL_RULE_ENVELOPE_VERSION: u32 = 1;
+
+/// Key-derivation domain separator
+const KDF_DOMAIN: &[u8] = b"miden-travel-rule-v1";
+
+/// Keystore subdirectory holding travel-rule secrets
+const KEYS_DIR: &str = "travel_rule";
+
+/// Travel-rule section of a send request:
+/// `{"recipient_key":"<X25519 hex>","data":{"originator":{...},"beneficiary":{...}}}`
+pub(crate) struct TravelRuleRequest {
+    recipient_key: PublicKey,
+    data: serde_json::Value,
+}
+
+impl TravelRuleRequest {
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, i32> {
+        let recipient_key: [u8; 32] = value["recipient_key"].as_str()
+            .and_then(|s| hex::decode(s.trim_start_matches("0x")).ok())
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(ERR_INVALID_PARAM)?;
+
+        let data = value["data"].clone();
+        if !data["originator"].is_object() || !data["beneficiary"].is_object() {
+            return Err(ERR_INVALID_PARAM);
+        }
+
+        Ok(Self { recipient_key: PublicKey::from(recipient_key), data })
+    }
+
+    /// Seal the metadata for the recipient, bound to the payment note
+    pub fn seal(&self, note_id: NoteId) -> Result<serde_json::Value, i32> {
+        let mut secret = [0u8; 32];
+        rand::rng().fill_bytes(&mut secret);
+        let ephemeral = StaticSecret::from(secret);
+        let ephemeral_public = PublicKey::from(&ephemeral);
+
+        let shared = ephemeral.diffie_hellman(&self.recipient_key);
+        let cipher = cipher(shared.as_bytes(), &ephemeral_public, &self.recipient_key);
+
+        let mut nonce = [0u8; 12];
+        rand::rng().fill_bytes(&mut nonce);
+        let plaintext = self.data.to_string().into_bytes();
+        let aad = note_id.to_hex();
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: &plaintext, aad: aad.as_bytes() })
+            .map_err(|_| ERR_INVALID_PARAM)?;
+
+        Ok(serde_json::json!({
+            "version": TRAVEL_RULE_ENVELOPE_VERSION,
+            "note_id": note_id.to_hex(),
+            "ephemeral_key": hex::encode(ephemeral_public.as_bytes()),
+            "nonce": hex::encode(nonce),
+            "ciphertext": hex::encode(ciphertext),
+        }))
+    }
+
+    /// Length of the serialized envelope `seal` returns (known before the note exists)
+    pub fn sealed_len(&self) -> usize {
+        // ChaCha20-Poly1305 appends a 16-byte tag; IDs, keys and nonces have fixed lengths
+        let ciphertext_len = self.data.to_string().len() + 16;
+        serde_json::json!({
+            "version": TRAVEL_RULE_ENVELOPE_VERSION,
+            "note_id": format!("0x{}", "0".repeat(64)),
+            "ephemeral_key": "0".repeat(64),
+            "nonce": "0".repeat(24),
+            "ciphertext": "0".repeat(2 * ciphertext_len),
+        })
+        .to_string()
+        .len()
+    }
+}
+
+fn cipher(shared: &[u8; 32], ephemeral: &PublicKey, recipient: &PublicKey) -> ChaCha20Poly1305 {
+    let mut hasher = Sha3_256::new();
+    hasher.update(KDF_DOMAIN);
+    hasher.update(shared);
+    hasher.update(ephemeral.as_bytes());
+    hasher.update(recipient.as_bytes());
+    ChaCha20Poly1305::new(Key::from_slice(&hasher.finalize()))
+}
+
+fn key_path(keystore_path: &Path, account_id: AccountId) -> PathBuf {
+    keystore_path.join(KEYS_DIR).join(format!("{}.key", account_id.to_hex()))
+}
+
+/// Load the account's travel-rule secret, creating it on first use when `create` is set
+fn load_secret(
+    keystore_path: &Path,
+    account_id: AccountId,
+    create: bool,
+) -> Result<StaticSecret, i32> {
+    let path = key_path(keystore_path, account_id);
+
+    if let Ok(contents) = std::fs::read_to_string(&path) {
+        let bytes: [u8; 32] = hex::decode(contents.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(ERR_ACCOUNT_OP)?;
+        return Ok(StaticSecret::from(bytes));
+    }
+    if !create {
+        return Err(ERR_LOOKUP);
+    }
+    if memory_store::is_in_memory(keystore_path) {
+        let message = "in-memory clients have no travel-rule keys";
+        return Err(last_error::detail(ERR_ACCOUNT_OP, message));
+    }
+
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|_| ERR_ACCOUNT_OP)?;
+    }
+    std::fs::write(&path, hex::encode(bytes)).map_err(|_| ERR_ACCOUNT_OP)?;
+    Ok(StaticSecret::from(bytes))
+}
+
+async fn ensure_local_account(context: &MidenContext, account_id: AccountId) -> Result<(), i32> {
+    context.client.get_account(account_id).await
+        .map_err(|_| ERR_LOOKUP)?
+        .map(|_| ())
+        .ok_or(ERR_LOOKUP)
+}
+
+pub(crate) async fn travel_rule_public_key_impl(
+    context: &MidenContext,
+    account_id: AccountId,
+) -> Result<String, i32> {
+    ensure_local_account(context, account_id).await?;
+    let secret = load_secret(&context.keystore_path, account_id, true)?;
+    Ok(hex::encode(PublicKey::from(&secret).as_bytes()))
+}
+
+pub(crate) async fn decode_travel_rule_impl(
+    context: &MidenContext,
+    account_id: AccountId,
+    envelope: serde_json::Value,
+) -> Result<String, i32> {
+    ensure_local_account(context, account_id).await?;
+    if envelope["version"].as_u64() != Some(u64::from(TRAVEL_RULE_ENVELOPE_VERSION)) {
+        return Err(ERR_INVALID_PARAM);
+    }
+
+    let hex_field = |name: &str| -> Result<Vec<u8>, i32> {
+        envelope[name].as_str()
+            .and_then(|s| hex::decode(s).ok())
+            .ok_or(ERR_INVALID_PARAM)
+    };
+    let note_id = envelope["note_id"].as_str().ok_or(ERR_INVALID_PARAM)?;
+    let ephemeral: [u8; 32] =
+        hex_field("ephemeral_key")?.try_into().map_err(|_| ERR_INVALID_PARAM)?;
+    let nonce: [u8; 12] = hex_field("nonce")?.try_into().map_err(|_| ERR_INVALID_PARAM)?;
+    let ciphertext = hex_field("ciphertext")?;
+
+    let secret = load_secret(&context.keystore_path, account_id, false)?;
+    let ephemeral = PublicKey::from(ephemeral);
+    let shared = secret.diffie_hellman(&ephemeral);
+    let cipher = cipher(shared.as_bytes(), &ephemeral, &PublicKey::from(&secret));
+
+    // Fails for a different recipient, a tampered envelope or a mismatched note ID
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: note_id.as_bytes() })
+        .map_err(|_| ERR_ACCOUNT_OP)?;
+    let data: serde_json::Value = serde_json::from_slice(&plaintext).map_err(|_| ERR_ACCOUNT_OP)?;
+
+    Ok(serde_json::json!({ "note_id": note_id, "data": data }).to_string())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Get (creating on first use) the travel-rule public key of a local account (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Counterparties pass this key as `travel_rule.recipient_key` when sending to the account.
+///
+/// # Parameters
+/// - `account_id_hex`: Local account ID (C string)
+/// - `key_out` / `key_out_len`: Output buffer for the X25519 public key (64 hex characters)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID or key file could not be read/written
+/// - -5: Account not found
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_travel_rule_public_key(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    key_out: *mut u8,
+    key_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let (account_id, _) = match parse_account_id(account_id_hex) {
+        Ok(parsed) => parsed,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::TravelRulePublicKey { account_id, reply }) {
+        Ok(key) => write_out_buffer(&key, key_out, key_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Decrypt a travel-rule envelope received with a payment (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_id_hex`: Receiving local account (whose travel-rule key the envelope is sealed to)
+/// - `envelope_json`: Envelope `{"version":1,"note_id","ephemeral_key","nonce","ciphertext"}`
+/// - `json_out` / `json_out_len`: Output buffer for `{"note_id":"0x..","data":{...}}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters or malformed envelope
+/// - -2: Invalid handle or worker closed
+/// - -3: Envelope is not addressed to this account or was tampered with
+/// - -5: Account or travel-rule key not found
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_decode_travel_rule(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    envelope_json: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let (account_id, _) = match parse_account_id(account_id_hex) {
+        Ok(parsed) => parsed,
+        Err(code) => return code,
+    };
+    let envelope = match parse_required_str(envelope_json)
+        .and_then(|json| serde_json::from_str(json).map_err(|_| ERR_INVALID_PARAM))
+    {
+        Ok(envelope) => envelope,
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::DecodeTravelRule { account_id, envelope, reply };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
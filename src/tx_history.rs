@@ -0,0 +1,390 @@
+//! Transaction history
+//!
+//! Lists the transactions in the client's store for a transaction list in the app, newest
+//! first. A transaction is `pending` from submission until the sync that sees it in a
+//! block (`committed`), or until the node or the store gives up on it (`discarded`).
+//! Without an account filter, transactions of archived accounts are left out, as in
+//! `wc_miden_get_input_notes`.
+//!
+//! `wc_miden_wait_for_transaction` answers "did my payment land?" without the app running
+//! its own sync loop: the wait is a worker request that runs one sync round and a status
+//! lookup, and a timer task on the worker's runtime queues it again after a pause, until
+//! the transaction leaves `pending` or the timeout elapses. No thread is started for a
+//! wait, and other requests interleave with it between rounds.
+
+use std::{
+    os::raw::c_char,
+    time::{Duration, Instant},
+};
+
+use miden_client::{
+    store::TransactionFilter,
+    transaction::{TransactionRecord, TransactionStatus},
+};
+use miden_objects::{account::AccountId, transaction::TransactionId};
+
+use crate::{
+    cancel::{self, Operation},
+    get_handle, last_error,
+    live_tx::parse_tx_id,
+    paging::Page,
+    parse_optional_str, parse_required_str, request_blocking, run_after_sync, sync_state_impl,
+    try_send_request, write_out_buffer, BytesCallback, MidenContext, MidenHandle, Reply, Request,
+    ERR_CANCELLED, ERR_INVALID_HANDLE, ERR_INVALID_PARAM, ERR_LOOKUP,
+};
+
+/// Pause between sync rounds while waiting for a transaction (about one block)
+const TX_WAIT_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Longest wait accepted by `wc_miden_wait_for_transaction`
+const TX_WAIT_MAX_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Granularity of cancellation checks during the pause
+const TX_WAIT_CANCEL_CHECK: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TxStatusKind {
+    Pending,
+    Committed,
+    Discarded,
+}
+
+impl TxStatusKind {
+    pub(crate) fn of(status: &TransactionStatus) -> Self {
+        match status {
+            TransactionStatus::Pending => TxStatusKind::Pending,
+            TransactionStatus::Committed { .. } => TxStatusKind::Committed,
+            TransactionStatus::Discarded(_) => TxStatusKind::Discarded,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            TxStatusKind::Pending => "pending",
+            TxStatusKind::Committed => "committed",
+            TxStatusKind::Discarded => "discarded",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(TxStatusKind::Pending),
+            "committed" => Some(TxStatusKind::Committed),
+            "discarded" => Some(TxStatusKind::Discarded),
+            _ => None,
+        }
+    }
+}
+
+/// Filter of a history query (every field optional)
+#[derive(Default)]
+pub(crate) struct TxHistoryFilter {
+    account_id: Option<AccountId>,
+    status: Option<TxStatusKind>,
+    limit: Option<usize>,
+    offset: usize,
+}
+
+impl TxHistoryFilter {
+    /// Parse `{"account_id":"0x..","status":"pending"|"committed"|"discarded","limit":50,
+    /// "offset":100}`
+    fn from_json(json: &str) -> Result<Self, i32> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|_| ERR_INVALID_PARAM)?;
+        if !value.is_object() {
+            return Err(ERR_INVALID_PARAM);
+        }
+
+        let account_id = match &value["account_id"] {
+            serde_json::Value::Null => None,
+            id => Some(
+                id.as_str()
+                    .and_then(|hex| AccountId::from_hex(hex).ok())
+                    .ok_or(ERR_INVALID_PARAM)?,
+            ),
+        };
+        let status = match &value["status"] {
+            serde_json::Value::Null => None,
+            status => Some(status.as_str().and_then(TxStatusKind::parse).ok_or(ERR_INVALID_PARAM)?),
+        };
+        let limit = match &value["limit"] {
+            serde_json::Value::Null => None,
+            limit => Some(
+                limit.as_u64()
+                    .filter(|limit| *limit > 0)
+                    .and_then(|limit| usize::try_from(limit).ok())
+                    .ok_or(ERR_INVALID_PARAM)?,
+            ),
+        };
+
+        let offset = match &value["offset"] {
+            serde_json::Value::Null => 0,
+            offset => offset.as_u64()
+                .and_then(|offset| usize::try_from(offset).ok())
+                .ok_or(ERR_INVALID_PARAM)?,
+        };
+
+        Ok(Self { account_id, status, limit, offset })
+    }
+
+    fn matches(&self, context: &MidenContext, record: &TransactionRecord) -> bool {
+        let account_matches = match self.account_id {
+            Some(account_id) => record.details.account_id == account_id,
+            None => !context.archive.contains(record.details.account_id),
+        };
+        let status = TxStatusKind::of(&record.status);
+        account_matches && self.status.is_none_or(|wanted| wanted == status)
+    }
+}
+
+fn record_json(context: &MidenContext, record: &TransactionRecord) -> serde_json::Value {
+    let block_num = match &record.status {
+        TransactionStatus::Committed { block_number, .. } => Some(block_number.as_u32()),
+        _ => None,
+    };
+    serde_json::json!({
+        "tx_id": record.id.to_hex(),
+        "account_id": record.details.account_id.to_hex(),
+        "status": TxStatusKind::of(&record.status).as_str(),
+        "block_num": block_num,
+        "timestamp": block_num.and_then(|block| context.block_times.timestamp(block)),
+        "input_note_count": record.details.input_note_nullifiers.len(),
+        "output_note_count": record.details.output_notes.num_notes(),
+    })
+}
+
+pub(crate) async fn get_transactions_impl(
+    context: &MidenContext,
+    filter: TxHistoryFilter,
+) -> Result<String, i32> {
+    let mut records: Vec<TransactionRecord> =
+        context.client.get_transactions(TransactionFilter::All).await
+            .map_err(|e| last_error::detail(ERR_LOOKUP, e))?
+            .into_iter()
+            .filter(|record| filter.matches(context, record))
+            .collect();
+
+    // Newest first: pending transactions executed against the latest blocks lead
+    records.sort_by_key(|record| std::cmp::Reverse(record.details.block_num));
+
+    let page = Page { offset: filter.offset, limit: filter.limit.unwrap_or(usize::MAX) };
+    let transactions: Vec<serde_json::Value> =
+        records.iter().map(|record| record_json(context, record)).collect();
+    Ok(page.envelope("transactions", transactions))
+}
+
+pub(crate) async fn get_transaction_status_impl(
+    context: &MidenContext,
+    tx_id: TransactionId,
+) -> Result<String, i32> {
+    let record = context.client.get_transactions(TransactionFilter::Ids(vec![tx_id])).await
+        .map_err(|e| last_error::detail(ERR_LOOKUP, e))?
+        .into_iter()
+        .next()
+        .ok_or(ERR_LOOKUP)?;
+    Ok(record_json(context, &record).to_string())
+}
+
+/// One round of a wait: sync, look up the status, then reply or queue the next round
+pub(crate) async fn wait_round(
+    context: &mut MidenContext,
+    tx_id: TransactionId,
+    operation: Operation,
+    deadline: Instant,
+    reply: Reply,
+) {
+    if operation.is_cancelled() {
+        operation.finish();
+        return reply.send(Err(ERR_CANCELLED));
+    }
+
+    // A failed round (node unreachable) is retried until the deadline
+    if sync_state_impl(context).await.is_ok() {
+        run_after_sync(context).await;
+    }
+
+    let status = get_transaction_status_impl(context, tx_id).await.and_then(|json| {
+        serde_json::from_str::<serde_json::Value>(&json).map_err(|_| ERR_LOOKUP)
+    });
+    let mut status = match status {
+        Ok(status) => status,
+        Err(code) => {
+            operation.finish();
+            return reply.send(Err(code));
+        }
+    };
+    let pending = status["status"] == TxStatusKind::Pending.as_str();
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if !pending || remaining.is_zero() {
+        status["timed_out"] = pending.into();
+        operation.finish();
+        return reply.send(Ok(status.to_string().into_bytes()));
+    }
+
+    let requests = context.requests.clone();
+    let resume = Instant::now() + TX_WAIT_POLL_INTERVAL.min(remaining);
+    tokio::spawn(async move {
+        // A cancelled wait skips the rest of the pause; its next round replies
+        while Instant::now() < resume && !operation.is_cancelled() {
+            tokio::time::sleep(TX_WAIT_CANCEL_CHECK).await;
+        }
+
+        let request = Request::WaitForTransaction { tx_id, operation, deadline, reply };
+        let unsent = match requests.upgrade() {
+            Some(sender) => sender.send(request).await.err().map(|e| e.0),
+            None => Some(request),
+        };
+        // The handle was destroyed during the pause
+        if let Some(Request::WaitForTransaction { operation, reply, .. }) = unsent {
+            operation.finish();
+            reply.send(Err(ERR_INVALID_HANDLE));
+        }
+    });
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// List the transactions in the store, newest first (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `filter_json`: `{"account_id","status":"pending"|"committed"|"discarded","limit",
+///   "offset"}`, every field optional (C string, NULL = all transactions of active accounts)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"transactions":[{"tx_id","account_id","status","block_num","timestamp",
+///   "input_note_count","output_note_count"}],"total_count","offset","has_more"}`
+///   (`block_num` and `timestamp`: the commit block, null until committed; `timestamp` also
+///   null until the block time is known; `total_count`: matching transactions on all pages)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid filter
+/// - -2: Invalid handle or worker closed
+/// - -5: Store query failed
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_transactions(
+    handle: MidenHandle,
+    filter_json: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let filter = match parse_optional_str(filter_json) {
+        Ok(Some(json)) => match TxHistoryFilter::from_json(json) {
+            Ok(filter) => filter,
+            Err(code) => return code,
+        },
+        Ok(None) => TxHistoryFilter::default(),
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::GetTransactions { filter, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Look up the status of one transaction in the store (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Reflects the last sync; `wc_miden_wait_for_transaction` syncs until the status changes.
+///
+/// # Parameters
+/// - `tx_id_hex`: Transaction ID (C string)
+/// - `json_out` / `json_out_len`: Output buffer for one entry as listed by
+///   `wc_miden_get_transactions`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid transaction ID
+/// - -2: Invalid handle or worker closed
+/// - -5: Transaction not in the store, or store query failed
+/// - -7: Output buffer too small
+/// - -99: Operation timed out
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_transaction_status(
+    handle: MidenHandle,
+    tx_id_hex: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let tx_id = match parse_required_str(tx_id_hex).map(parse_tx_id) {
+        Ok(Some(tx_id)) => tx_id,
+        Ok(None) => return ERR_INVALID_PARAM,
+        Err(code) => return code,
+    };
+
+    match request_blocking(&worker, |reply| Request::GetTransactionStatus { tx_id, reply }) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Sync until a transaction is committed or discarded, or the timeout elapses (async)
+///
+/// NOTE: Callback is invoked on the worker thread, NOT main thread.
+///
+/// The callback receives the transaction's entry (as `wc_miden_get_transaction_status`)
+/// with `"timed_out"`: true when it was still pending at the deadline. Sync failures
+/// during the wait are retried; the wait is cancellable with `wc_miden_cancel`. The wait
+/// runs on the worker as one request per sync round (see the module docs).
+///
+/// # Parameters
+/// - `tx_id_hex`: Transaction ID (C string), submitted through this store
+/// - `timeout_ms`: Longest wait in milliseconds (at most 10 minutes, must be > 0)
+///
+/// # Returns
+/// An operation handle (> 0) for `wc_miden_cancel`, or a negative error code:
+/// - -1: Invalid transaction ID or timeout
+/// - -2: Invalid handle or worker closed
+/// - -8: Queue full
+///
+/// Callback error codes: -2 (worker closed during the wait), -5 (transaction not in the
+/// store), -9 (cancelled).
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_wait_for_transaction(
+    handle: MidenHandle,
+    tx_id_hex: *const c_char,
+    timeout_ms: u64,
+    callback: BytesCallback,
+    user_data: *mut std::ffi::c_void,
+) -> i64 {
+    let Some(worker) = get_handle(handle) else {
+        return i64::from(ERR_INVALID_HANDLE);
+    };
+
+    let timeout = Duration::from_millis(timeout_ms);
+    if timeout.is_zero() || timeout > TX_WAIT_MAX_TIMEOUT {
+        return i64::from(ERR_INVALID_PARAM);
+    }
+    let tx_id = match parse_required_str(tx_id_hex).map(parse_tx_id) {
+        Ok(Some(tx_id)) => tx_id,
+        Ok(None) => return i64::from(ERR_INVALID_PARAM),
+        Err(code) => return i64::from(code),
+    };
+
+    let operation = Operation::register();
+    let id = operation.id();
+    let reply = Reply::Callback { callback, user_data: user_data as usize };
+    let deadline = Instant::now() + timeout;
+    let request = Request::WaitForTransaction { tx_id, operation, deadline, reply };
+    match try_send_request(&worker, request) {
+        Ok(()) => id as i64,
+        Err(code) => {
+            cancel::forget(id);
+            i64::from(code)
+        }
+    }
+}
@@ -0,0 +1,370 @@
+//! C enums shared by the FFI surface
+//!
+//! Modes that used to travel as magic integers or strings are exported into the
+//! generated header as `#[repr(C)]` enums, so Swift sees them as typed constants.
+//! JSON payloads keep the lowercase string names (`"public"`, `"private"`, ...), which
+//! these types parse and produce as well.
+
+use miden_client::store::{AccountStatus, NoteFilter};
+use miden_objects::{
+    account::{AccountStorageMode, AccountType},
+    note::NoteType,
+};
+
+use crate::ERR_INVALID_PARAM;
+
+/// Note visibility
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WcNoteType {
+    /// Note details are published on chain
+    Public = 0,
+    /// Only the note commitment is published; details travel off chain
+    Private = 1,
+}
+
+/// Account type
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WcAccountType {
+    FungibleFaucet = 0,
+    NonFungibleFaucet = 1,
+    RegularImmutableCode = 2,
+    RegularUpdatableCode = 3,
+}
+
+/// Account storage mode
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WcStorageMode {
+    /// Full account state is public on chain
+    Public = 0,
+    /// Only the state commitment is on chain
+    Private = 1,
+    /// Public account executed by the network
+    Network = 2,
+}
+
+/// Tracking status of an account in the local store
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WcAccountStatus {
+    /// Created locally, not yet seen on chain
+    New = 0,
+    /// Known on chain and tracked
+    Tracked = 1,
+    /// Local state diverged from the chain; the account must be re-imported
+    Locked = 2,
+}
+
+/// Backend holding an account's signing key
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WcKeyBackend {
+    /// Key file in the keystore directory
+    Filesystem = 0,
+    /// External signer callbacks (Secure Enclave, hardware wallet, MPC)
+    Callback = 1,
+    /// Process memory only; lost when the handle is destroyed
+    Memory = 2,
+}
+
+/// Content of an exported note file
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WcNoteExportType {
+    /// Note ID only; the recipient fetches the note from the node (public notes)
+    Id = 0,
+    /// Full note with its inclusion proof (committed notes)
+    Full = 1,
+    /// Note details with the tag and the block to look from; the recipient finds the
+    /// inclusion proof by syncing
+    Partial = 2,
+}
+
+/// Encoding of amounts in JSON output
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WcAmountEncoding {
+    /// JSON numbers (base units)
+    Number = 0,
+    /// Decimal strings (base units), for bridges limited to 53-bit integers
+    String = 1,
+}
+
+/// Input notes selected by `wc_miden_get_input_notes_filtered`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WcNoteFilter {
+    /// Notes an account can consume now (the `wc_miden_get_input_notes` result)
+    Consumable = 0,
+    /// Every input note in the store
+    All = 1,
+    /// Announced (e.g. imported or pushed) but not yet seen in a block
+    Expected = 2,
+    /// Included in a block and not consumed
+    Committed = 3,
+    /// Consumed by a local or external transaction
+    Consumed = 4,
+    /// Being consumed by a submitted local transaction
+    Processing = 5,
+}
+
+/// Result of checking a user-entered account or note ID
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WcIdValidation {
+    /// Accepted by the FFI as is
+    Valid = 0,
+    /// Empty string
+    Empty = 1,
+    /// Hex digits without the `0x` prefix
+    MissingPrefix = 2,
+    /// `0x` followed by the wrong number of hex digits
+    WrongLength = 3,
+    /// Characters that are neither hex digits nor a bech32 address
+    InvalidCharacter = 4,
+    /// Bech32 address with a checksum that does not match (mistyped or truncated)
+    BadChecksum = 5,
+    /// Well-formed, but not an ID (reserved bits set or values out of field range)
+    InvalidId = 6,
+    /// Intact bech32 address; the FFI takes the `0x` hex form
+    Bech32Address = 7,
+}
+
+impl WcNoteType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WcNoteType::Public => "public",
+            WcNoteType::Private => "private",
+        }
+    }
+
+    /// Parse a JSON `note_type` value: a string name or the numeric enum value
+    /// (absent or null means public)
+    pub fn from_json(value: Option<&serde_json::Value>) -> Result<Self, i32> {
+        match value {
+            None | Some(serde_json::Value::Null) => Ok(WcNoteType::Public),
+            Some(v) => match (v.as_str(), v.as_u64()) {
+                (Some("public"), _) | (_, Some(0)) => Ok(WcNoteType::Public),
+                (Some("private"), _) | (_, Some(1)) => Ok(WcNoteType::Private),
+                _ => Err(ERR_INVALID_PARAM),
+            },
+        }
+    }
+}
+
+impl TryFrom<u32> for WcNoteType {
+    type Error = i32;
+
+    /// Convert a raw FFI argument (an out-of-range value is rejected, not transmuted)
+    fn try_from(value: u32) -> Result<Self, i32> {
+        match value {
+            0 => Ok(WcNoteType::Public),
+            1 => Ok(WcNoteType::Private),
+            _ => Err(ERR_INVALID_PARAM),
+        }
+    }
+}
+
+impl From<WcNoteType> for NoteType {
+    fn from(note_type: WcNoteType) -> Self {
+        match note_type {
+            WcNoteType::Public => NoteType::Public,
+            WcNoteType::Private => NoteType::Private,
+        }
+    }
+}
+
+impl TryFrom<NoteType> for WcNoteType {
+    type Error = i32;
+
+    fn try_from(note_type: NoteType) -> Result<Self, i32> {
+        match note_type {
+            NoteType::Public => Ok(WcNoteType::Public),
+            NoteType::Private => Ok(WcNoteType::Private),
+            #[allow(unreachable_patterns)]
+            _ => Err(ERR_INVALID_PARAM),
+        }
+    }
+}
+
+impl WcAccountType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WcAccountType::FungibleFaucet => "fungible_faucet",
+            WcAccountType::NonFungibleFaucet => "non_fungible_faucet",
+            WcAccountType::RegularImmutableCode => "regular_immutable_code",
+            WcAccountType::RegularUpdatableCode => "regular_updatable_code",
+        }
+    }
+}
+
+impl From<WcAccountType> for AccountType {
+    fn from(account_type: WcAccountType) -> Self {
+        match account_type {
+            WcAccountType::FungibleFaucet => AccountType::FungibleFaucet,
+            WcAccountType::NonFungibleFaucet => AccountType::NonFungibleFaucet,
+            WcAccountType::RegularImmutableCode => AccountType::RegularAccountImmutableCode,
+            WcAccountType::RegularUpdatableCode => AccountType::RegularAccountUpdatableCode,
+        }
+    }
+}
+
+impl From<AccountType> for WcAccountType {
+    fn from(account_type: AccountType) -> Self {
+        match account_type {
+            AccountType::FungibleFaucet => WcAccountType::FungibleFaucet,
+            AccountType::NonFungibleFaucet => WcAccountType::NonFungibleFaucet,
+            AccountType::RegularAccountImmutableCode => WcAccountType::RegularImmutableCode,
+            AccountType::RegularAccountUpdatableCode => WcAccountType::RegularUpdatableCode,
+        }
+    }
+}
+
+impl WcStorageMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WcStorageMode::Public => "public",
+            WcStorageMode::Private => "private",
+            WcStorageMode::Network => "network",
+        }
+    }
+}
+
+impl From<WcStorageMode> for AccountStorageMode {
+    fn from(storage_mode: WcStorageMode) -> Self {
+        match storage_mode {
+            WcStorageMode::Public => AccountStorageMode::Public,
+            WcStorageMode::Private => AccountStorageMode::Private,
+            WcStorageMode::Network => AccountStorageMode::Network,
+        }
+    }
+}
+
+impl From<AccountStorageMode> for WcStorageMode {
+    fn from(storage_mode: AccountStorageMode) -> Self {
+        match storage_mode {
+            AccountStorageMode::Public => WcStorageMode::Public,
+            AccountStorageMode::Private => WcStorageMode::Private,
+            AccountStorageMode::Network => WcStorageMode::Network,
+        }
+    }
+}
+
+impl TryFrom<u32> for WcStorageMode {
+    type Error = i32;
+
+    /// Convert a raw FFI argument (an out-of-range value is rejected, not transmuted)
+    fn try_from(value: u32) -> Result<Self, i32> {
+        match value {
+            0 => Ok(WcStorageMode::Public),
+            1 => Ok(WcStorageMode::Private),
+            2 => Ok(WcStorageMode::Network),
+            _ => Err(ERR_INVALID_PARAM),
+        }
+    }
+}
+
+impl WcAccountStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WcAccountStatus::New => "new",
+            WcAccountStatus::Tracked => "tracked",
+            WcAccountStatus::Locked => "locked",
+        }
+    }
+}
+
+impl From<&AccountStatus> for WcAccountStatus {
+    fn from(status: &AccountStatus) -> Self {
+        match status {
+            AccountStatus::New { .. } => WcAccountStatus::New,
+            AccountStatus::Tracked => WcAccountStatus::Tracked,
+            AccountStatus::Locked => WcAccountStatus::Locked,
+        }
+    }
+}
+
+impl WcKeyBackend {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WcKeyBackend::Filesystem => "filesystem",
+            WcKeyBackend::Callback => "callback",
+            WcKeyBackend::Memory => "memory",
+        }
+    }
+}
+
+impl TryFrom<u32> for WcKeyBackend {
+    type Error = i32;
+
+    /// Convert a raw FFI argument (an out-of-range value is rejected, not transmuted)
+    fn try_from(value: u32) -> Result<Self, i32> {
+        match value {
+            0 => Ok(WcKeyBackend::Filesystem),
+            1 => Ok(WcKeyBackend::Callback),
+            2 => Ok(WcKeyBackend::Memory),
+            _ => Err(ERR_INVALID_PARAM),
+        }
+    }
+}
+
+impl WcNoteFilter {
+    /// Store filter of the selection (None for `Consumable`, which is answered by the
+    /// client's consumability check instead)
+    pub fn store_filter(self) -> Option<NoteFilter> {
+        match self {
+            WcNoteFilter::Consumable => None,
+            WcNoteFilter::All => Some(NoteFilter::All),
+            WcNoteFilter::Expected => Some(NoteFilter::Expected),
+            WcNoteFilter::Committed => Some(NoteFilter::Committed),
+            WcNoteFilter::Consumed => Some(NoteFilter::Consumed),
+            WcNoteFilter::Processing => Some(NoteFilter::Processing),
+        }
+    }
+}
+
+impl TryFrom<u32> for WcNoteFilter {
+    type Error = i32;
+
+    /// Convert a raw FFI argument (an out-of-range value is rejected, not transmuted)
+    fn try_from(value: u32) -> Result<Self, i32> {
+        match value {
+            0 => Ok(WcNoteFilter::Consumable),
+            1 => Ok(WcNoteFilter::All),
+            2 => Ok(WcNoteFilter::Expected),
+            3 => Ok(WcNoteFilter::Committed),
+            4 => Ok(WcNoteFilter::Consumed),
+            5 => Ok(WcNoteFilter::Processing),
+            _ => Err(ERR_INVALID_PARAM),
+        }
+    }
+}
+
+impl TryFrom<u32> for WcAmountEncoding {
+    type Error = i32;
+
+    /// Convert a raw FFI argument (an out-of-range value is rejected, not transmuted)
+    fn try_from(value: u32) -> Result<Self, i32> {
+        match value {
+            0 => Ok(WcAmountEncoding::Number),
+            1 => Ok(WcAmountEncoding::String),
+            _ => Err(ERR_INVALID_PARAM),
+        }
+    }
+}
+
+impl TryFrom<u32> for WcNoteExportType {
+    type Error = i32;
+
+    /// Convert a raw FFI argument (an out-of-range value is rejected, not transmuted)
+    fn try_from(value: u32) -> Result<Self, i32> {
+        match value {
+            0 => Ok(WcNoteExportType::Id),
+            1 => Ok(WcNoteExportType::Full),
+            2 => Ok(WcNoteExportType::Partial),
+            _ => Err(ERR_INVALID_PARAM),
+        }
+    }
+}
@@ -0,0 +1,569 @@
+//! Wallet core, independent of the C FFI
+//!
+//! The wallet flows every binding needs (wallet creation, queries, payments, consuming
+//! notes, auth key rotation, sync) as safe async functions over a `miden-client`
+//! [`Client`] with any authenticator. The C FFI calls them from its request handlers and
+//! adds what only the FFI has around them (a rate-limited RPC client, RPC metrics,
+//! failure injection, screening, key backends, JSON output); a UniFFI layer can call the
+//! same functions directly, so a flow is implemented and tested once.
+//!
+//! Failures are [`CoreError`]s: the FFI error code of the failure (see lib.rs) and the
+//! upstream message. Nothing here touches handles, pointers or thread-local state.
+//!
+//! The unit tests at the bottom run the flows against the upstream mock node and need the
+//! `mock-node` feature: `cargo test --lib --features mock-node`.
+
+use std::fmt;
+
+use miden_client::{
+    account::component::BasicWallet,
+    auth::TransactionAuthenticator,
+    note::NoteConsumability,
+    store::InputNoteRecord,
+    transaction::{TransactionRequest, TransactionRequestBuilder},
+    Client,
+};
+use miden_lib::note::{create_p2id_note, create_p2ide_note};
+use miden_objects::{
+    account::{
+        Account, AccountBuilder, AccountComponent, AccountId, AccountStorageMode, AccountType,
+    },
+    asset::{Asset, FungibleAsset},
+    block::BlockNumber,
+    crypto::rand::FeltRng,
+    note::{Note, NoteId, NoteType},
+    transaction::TransactionId,
+    Felt, Word,
+};
+
+use crate::{
+    key_rotation, last_error, reconnect, ERR_ACCOUNT_OP, ERR_INVALID_HANDLE, ERR_INVALID_PARAM,
+    ERR_LOOKUP, ERR_NOTE_OP, ERR_TX_SUBMIT,
+};
+
+/// Failure of a core flow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoreError {
+    code: i32,
+    message: String,
+    unreachable: bool,
+}
+
+impl CoreError {
+    fn new(code: i32, error: impl fmt::Display) -> Self {
+        Self { code, message: error.to_string(), unreachable: false }
+    }
+
+    /// Failure of a node call; remembers whether the node could not be reached
+    fn node(code: i32, error: impl fmt::Display + fmt::Debug) -> Self {
+        let unreachable = reconnect::is_transport_error(&error);
+        Self { code, message: error.to_string(), unreachable }
+    }
+
+    /// FFI error code (`ERR_*`)
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// Upstream error message
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The node could not be reached (worth retrying, unlike a refusal by the node)
+    pub fn is_unreachable(&self) -> bool {
+        self.unreachable
+    }
+
+    /// Error code for the FFI, recording the message as the last error (worker thread)
+    pub(crate) fn into_code(self) -> i32 {
+        last_error::detail(self.code, self.message)
+    }
+}
+
+impl fmt::Display for CoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (code {})", self.message, self.code)
+    }
+}
+
+impl std::error::Error for CoreError {}
+
+// ================================================================================================
+// Accounts
+// ================================================================================================
+
+/// Build a basic wallet account around the given auth component (not yet in the store)
+pub fn build_wallet_account(
+    init_seed: [u8; 32],
+    auth_component: AccountComponent,
+    storage_mode: AccountStorageMode,
+) -> Result<Account, CoreError> {
+    AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(storage_mode)
+        .with_auth_component(auth_component)
+        .with_component(BasicWallet)
+        .build()
+        .map_err(|e| CoreError::new(ERR_ACCOUNT_OP, e))
+}
+
+/// Build a wallet account whose auth key can be rotated (see key_rotation.rs; not yet in
+/// the store)
+pub fn build_rotatable_wallet_account(
+    init_seed: [u8; 32],
+    pub_key: Word,
+    storage_mode: AccountStorageMode,
+) -> Result<Account, CoreError> {
+    let auth_component = key_rotation::rotatable_auth_component(pub_key)
+        .map_err(|e| CoreError::new(ERR_ACCOUNT_OP, e))?;
+    AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(storage_mode)
+        .with_auth_component(auth_component)
+        .with_component(BasicWallet)
+        .build()
+        .map_err(|e| CoreError::new(ERR_ACCOUNT_OP, e))
+}
+
+/// Track a new account in the store (its key must already be with the authenticator)
+pub async fn add_account<AUTH>(
+    client: &mut Client<AUTH>,
+    account: &Account,
+) -> Result<(), CoreError>
+where
+    AUTH: TransactionAuthenticator + Sync + 'static,
+{
+    client.add_account(account, false).await.map_err(|e| CoreError::new(ERR_ACCOUNT_OP, e))
+}
+
+/// IDs of the accounts in the store, in store order
+pub async fn account_ids<AUTH>(client: &Client<AUTH>) -> Result<Vec<AccountId>, CoreError>
+where
+    AUTH: TransactionAuthenticator + Sync + 'static,
+{
+    let headers = client.get_account_headers().await
+        .map_err(|e| CoreError::new(ERR_ACCOUNT_OP, e))?;
+    Ok(headers.iter().map(|(header, _status)| header.id()).collect())
+}
+
+/// Assets in the vault of a stored account (`ERR_LOOKUP` when it is not in the store)
+pub async fn vault_assets<AUTH>(
+    client: &Client<AUTH>,
+    account_id: AccountId,
+) -> Result<Vec<Asset>, CoreError>
+where
+    AUTH: TransactionAuthenticator + Sync + 'static,
+{
+    let record = client.get_account(account_id).await
+        .map_err(|e| CoreError::new(ERR_LOOKUP, e))?
+        .ok_or_else(|| {
+            let message = format!("account {} is not in the store", account_id.to_hex());
+            CoreError::new(ERR_LOOKUP, message)
+        })?;
+    Ok(record.account().vault().assets().collect())
+}
+
+// ================================================================================================
+// Notes and Transactions
+// ================================================================================================
+
+/// Notes `account_id` can consume (any stored account when None), with their consumers
+pub async fn consumable_notes<AUTH>(
+    client: &Client<AUTH>,
+    account_id: Option<AccountId>,
+) -> Result<Vec<(InputNoteRecord, Vec<NoteConsumability>)>, CoreError>
+where
+    AUTH: TransactionAuthenticator + Sync + 'static,
+{
+    client.get_consumable_notes(account_id).await.map_err(|e| CoreError::new(ERR_NOTE_OP, e))
+}
+
+/// Transaction request consuming the given notes
+pub fn consume_notes_request(note_ids: Vec<NoteId>) -> Result<TransactionRequest, CoreError> {
+    if note_ids.is_empty() {
+        return Err(CoreError::new(ERR_NOTE_OP, "no notes to consume"));
+    }
+    TransactionRequestBuilder::new()
+        .build_consume_notes(note_ids)
+        .map_err(|e| CoreError::new(ERR_NOTE_OP, e))
+}
+
+/// Transaction request moving a rotatable wallet to the auth key `new_pub_key`
+///
+/// The transaction is signed with the account's current key; later transactions are
+/// signed with the new one.
+pub fn rotate_auth_key_request(
+    account: &Account,
+    new_pub_key: Word,
+) -> Result<TransactionRequest, CoreError> {
+    if account.account_type() != AccountType::RegularAccountUpdatableCode {
+        return Err(CoreError::new(ERR_ACCOUNT_OP, "the account code is immutable"));
+    }
+    if !key_rotation::is_rotatable(account.code()) {
+        return Err(CoreError::new(ERR_ACCOUNT_OP, "the account has no rotatable auth key"));
+    }
+    let script = key_rotation::rotation_script(new_pub_key)
+        .map_err(|e| CoreError::new(ERR_ACCOUNT_OP, e))?;
+    TransactionRequestBuilder::new()
+        .custom_script(script)
+        .build()
+        .map_err(|e| CoreError::new(ERR_ACCOUNT_OP, e))
+}
+
+/// Payment note of `amount` of `faucet_id`'s asset from `sender` to `target`
+///
+/// A P2ID note, or with a `reclaim_block` a P2IDE note the sender can recall from that
+/// block. Each call draws a fresh serial number from `rng`.
+pub fn payment_note<R: FeltRng>(
+    sender: AccountId,
+    target: AccountId,
+    faucet_id: AccountId,
+    amount: u64,
+    note_type: NoteType,
+    reclaim_block: Option<u32>,
+    rng: &mut R,
+) -> Result<Note, CoreError> {
+    let asset = FungibleAsset::new(faucet_id, amount)
+        .map_err(|e| CoreError::new(ERR_INVALID_PARAM, e))?;
+    let assets = vec![asset.into()];
+    let note = match reclaim_block {
+        Some(block) => {
+            let reclaim = Some(BlockNumber::from(block));
+            create_p2ide_note(sender, target, assets, reclaim, None, note_type, Felt::new(0), rng)
+        }
+        None => create_p2id_note(sender, target, assets, note_type, Felt::new(0), rng),
+    };
+    note.map_err(|e| CoreError::new(ERR_NOTE_OP, e))
+}
+
+/// Execute, prove and submit a transaction for `account_id`
+pub async fn submit<AUTH>(
+    client: &mut Client<AUTH>,
+    account_id: AccountId,
+    request: TransactionRequest,
+) -> Result<TransactionId, CoreError>
+where
+    AUTH: TransactionAuthenticator + Sync + 'static,
+{
+    client.submit_new_transaction(account_id, request).await
+        .map_err(|e| CoreError::node(ERR_TX_SUBMIT, e))
+}
+
+// ================================================================================================
+// Sync
+// ================================================================================================
+
+/// Sync the store with the node, returning the synced block number
+pub async fn sync<AUTH>(client: &mut Client<AUTH>) -> Result<u32, CoreError>
+where
+    AUTH: TransactionAuthenticator + Sync + 'static,
+{
+    let summary = client.sync_state().await
+        .map_err(|e| CoreError::node(ERR_INVALID_HANDLE, e))?;
+    Ok(summary.block_num.as_u32())
+}
+
+#[cfg(all(test, feature = "mock-node"))]
+mod tests {
+    use std::{
+        path::PathBuf,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use miden_client::{
+        auth::AuthSecretKey, builder::ClientBuilder, keystore::FilesystemKeyStore,
+        testing::mock::MockRpcApi,
+    };
+    use miden_client_sqlite_store::ClientBuilderSqliteExt;
+    use miden_lib::account::{auth::AuthRpoFalcon512, faucets::BasicFungibleFaucet};
+    use miden_objects::{asset::TokenSymbol, transaction::OutputNote};
+    use rand::rngs::StdRng;
+
+    use super::*;
+
+    type TestClient = Client<FilesystemKeyStore<StdRng>>;
+
+    /// Client on a fresh store and keystore, connected to a mock node
+    async fn test_client() -> (TestClient, FilesystemKeyStore<StdRng>, Arc<MockRpcApi>) {
+        static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+        let dir: PathBuf = std::env::temp_dir().join(format!(
+            "miden-core-test-{}-{}",
+            std::process::id(),
+            NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("keystore")).unwrap();
+
+        let rpc = Arc::new(MockRpcApi::default());
+        let keystore = FilesystemKeyStore::new(dir.join("keystore")).unwrap();
+        let client = ClientBuilder::new()
+            .rpc(rpc.clone())
+            .sqlite_store(dir.join("store.sqlite3"))
+            .authenticator(Arc::new(keystore.clone()))
+            .in_debug_mode(true.into())
+            .build()
+            .await
+            .unwrap();
+        (client, keystore, rpc)
+    }
+
+    fn run(test: impl std::future::Future<Output = ()>) {
+        tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(test);
+    }
+
+    /// Auth component of a new Falcon key, saved to the keystore
+    fn falcon_auth(keystore: &FilesystemKeyStore<StdRng>) -> AccountComponent {
+        let key = AuthSecretKey::new_rpo_falcon512();
+        keystore.add_key(&key).unwrap();
+        AuthRpoFalcon512::new(key.public_key().to_commitment()).into()
+    }
+
+    async fn add_wallet(
+        client: &mut TestClient,
+        keystore: &FilesystemKeyStore<StdRng>,
+        seed: u8,
+    ) -> AccountId {
+        let account =
+            build_wallet_account([seed; 32], falcon_auth(keystore), AccountStorageMode::Public)
+                .unwrap();
+        add_account(client, &account).await.unwrap();
+        account.id()
+    }
+
+    async fn add_faucet(
+        client: &mut TestClient,
+        keystore: &FilesystemKeyStore<StdRng>,
+    ) -> AccountId {
+        let symbol = TokenSymbol::new("TST").unwrap();
+        let account = AccountBuilder::new([7; 32])
+            .account_type(AccountType::FungibleFaucet)
+            .storage_mode(AccountStorageMode::Public)
+            .with_auth_component(falcon_auth(keystore))
+            .with_component(BasicFungibleFaucet::new(symbol, 8, Felt::new(1_000_000)).unwrap())
+            .build()
+            .unwrap();
+        add_account(client, &account).await.unwrap();
+        account.id()
+    }
+
+    /// Mint `amount` into a public note for `target` and commit it
+    async fn fund(
+        client: &mut TestClient,
+        rpc: &MockRpcApi,
+        faucet_id: AccountId,
+        target: AccountId,
+        amount: u64,
+    ) {
+        let asset = FungibleAsset::new(faucet_id, amount).unwrap();
+        let request = TransactionRequestBuilder::new()
+            .build_mint_fungible_asset(asset, target, NoteType::Public, client.rng())
+            .unwrap();
+        submit(client, faucet_id, request).await.unwrap();
+        rpc.prove_block();
+        sync(client).await.unwrap();
+    }
+
+    fn fungible_amount(assets: &[Asset], faucet_id: AccountId) -> u64 {
+        assets
+            .iter()
+            .filter_map(|asset| match asset {
+                Asset::Fungible(fungible) if fungible.faucet_id() == faucet_id => {
+                    Some(fungible.amount())
+                }
+                _ => None,
+            })
+            .sum()
+    }
+
+    #[test]
+    fn wallet_accounts_are_deterministic_per_seed_and_key() {
+        let key = AuthSecretKey::new_rpo_falcon512();
+        let auth = || -> AccountComponent {
+            AuthRpoFalcon512::new(key.public_key().to_commitment()).into()
+        };
+
+        let first = build_wallet_account([1; 32], auth(), AccountStorageMode::Public).unwrap();
+        let again = build_wallet_account([1; 32], auth(), AccountStorageMode::Public).unwrap();
+        let other = build_wallet_account([2; 32], auth(), AccountStorageMode::Public).unwrap();
+        let private = build_wallet_account([1; 32], auth(), AccountStorageMode::Private).unwrap();
+
+        assert_eq!(first.id(), again.id());
+        assert_ne!(first.id(), other.id());
+        assert!(first.id().is_public());
+        assert!(!private.id().is_public());
+    }
+
+    #[test]
+    fn created_wallet_is_listed_with_an_empty_vault() {
+        run(async {
+            let (mut client, keystore, _rpc) = test_client().await;
+            let account_id = add_wallet(&mut client, &keystore, 1).await;
+
+            assert_eq!(account_ids(&client).await.unwrap(), vec![account_id]);
+            assert!(vault_assets(&client, account_id).await.unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn unknown_account_is_a_lookup_error() {
+        run(async {
+            let (mut client, keystore, _rpc) = test_client().await;
+            let stored = add_wallet(&mut client, &keystore, 1).await;
+            let auth = falcon_auth(&keystore);
+            let unknown =
+                build_wallet_account([9; 32], auth, AccountStorageMode::Public).unwrap().id();
+            assert_ne!(stored, unknown);
+
+            let error = vault_assets(&client, unknown).await.unwrap_err();
+            assert_eq!(error.code(), ERR_LOOKUP);
+            assert!(!error.is_unreachable());
+        });
+    }
+
+    #[test]
+    fn sync_follows_the_node_tip() {
+        run(async {
+            let (mut client, _keystore, rpc) = test_client().await;
+            let first = sync(&mut client).await.unwrap();
+
+            rpc.prove_block();
+            rpc.prove_block();
+            assert_eq!(sync(&mut client).await.unwrap(), first + 2);
+        });
+    }
+
+    #[test]
+    fn minted_note_is_consumed_into_the_vault() {
+        run(async {
+            let (mut client, keystore, rpc) = test_client().await;
+            let faucet_id = add_faucet(&mut client, &keystore).await;
+            let wallet = add_wallet(&mut client, &keystore, 1).await;
+            sync(&mut client).await.unwrap();
+
+            fund(&mut client, &rpc, faucet_id, wallet, 500).await;
+            let notes = consumable_notes(&client, Some(wallet)).await.unwrap();
+            assert_eq!(notes.len(), 1);
+
+            let request = consume_notes_request(vec![notes[0].0.id()]).unwrap();
+            submit(&mut client, wallet, request).await.unwrap();
+            rpc.prove_block();
+            sync(&mut client).await.unwrap();
+
+            let assets = vault_assets(&client, wallet).await.unwrap();
+            assert_eq!(fungible_amount(&assets, faucet_id), 500);
+            assert!(consumable_notes(&client, Some(wallet)).await.unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn payment_moves_assets_between_wallets() {
+        run(async {
+            let (mut client, keystore, rpc) = test_client().await;
+            let faucet_id = add_faucet(&mut client, &keystore).await;
+            let sender = add_wallet(&mut client, &keystore, 1).await;
+            let recipient = add_wallet(&mut client, &keystore, 2).await;
+            sync(&mut client).await.unwrap();
+
+            fund(&mut client, &rpc, faucet_id, sender, 500).await;
+            let funding = consumable_notes(&client, Some(sender)).await.unwrap();
+            let request = consume_notes_request(vec![funding[0].0.id()]).unwrap();
+            submit(&mut client, sender, request).await.unwrap();
+            rpc.prove_block();
+            sync(&mut client).await.unwrap();
+
+            let note_type = NoteType::Public;
+            let rng = client.rng();
+            let note =
+                payment_note(sender, recipient, faucet_id, 200, note_type, None, rng).unwrap();
+            let request = TransactionRequestBuilder::new()
+                .own_output_notes(vec![OutputNote::Full(note)])
+                .build()
+                .unwrap();
+            submit(&mut client, sender, request).await.unwrap();
+            rpc.prove_block();
+            sync(&mut client).await.unwrap();
+
+            let incoming = consumable_notes(&client, Some(recipient)).await.unwrap();
+            assert_eq!(incoming.len(), 1);
+            let request = consume_notes_request(vec![incoming[0].0.id()]).unwrap();
+            submit(&mut client, recipient, request).await.unwrap();
+            rpc.prove_block();
+            sync(&mut client).await.unwrap();
+
+            let sender_assets = vault_assets(&client, sender).await.unwrap();
+            let recipient_assets = vault_assets(&client, recipient).await.unwrap();
+            assert_eq!(fungible_amount(&sender_assets, faucet_id), 300);
+            assert_eq!(fungible_amount(&recipient_assets, faucet_id), 200);
+        });
+    }
+
+    #[test]
+    fn rotated_auth_key_signs_later_transactions() {
+        run(async {
+            let (mut client, keystore, rpc) = test_client().await;
+            let key = AuthSecretKey::new_rpo_falcon512();
+            keystore.add_key(&key).unwrap();
+            let pub_key: Word = key.public_key().to_commitment().into();
+            let storage_mode = AccountStorageMode::Public;
+            let account = build_rotatable_wallet_account([3; 32], pub_key, storage_mode).unwrap();
+            add_account(&mut client, &account).await.unwrap();
+            sync(&mut client).await.unwrap();
+
+            // Signed with the current key: the new one is not with the authenticator yet
+            let new_key = AuthSecretKey::new_rpo_falcon512();
+            let new_pub_key: Word = new_key.public_key().to_commitment().into();
+            let request = rotate_auth_key_request(&account, new_pub_key).unwrap();
+            submit(&mut client, account.id(), request).await.unwrap();
+            rpc.prove_block();
+            sync(&mut client).await.unwrap();
+
+            let record = client.get_account(account.id()).await.unwrap().unwrap();
+            assert_eq!(record.account().storage().get_item(0).unwrap(), new_pub_key);
+
+            let request = rotate_auth_key_request(record.account(), pub_key).unwrap();
+            let error = submit(&mut client, account.id(), request).await.unwrap_err();
+            assert_eq!(error.code(), ERR_TX_SUBMIT);
+
+            keystore.add_key(&new_key).unwrap();
+            let request = rotate_auth_key_request(record.account(), pub_key).unwrap();
+            submit(&mut client, account.id(), request).await.unwrap();
+        });
+    }
+
+    #[test]
+    fn immutable_wallets_cannot_rotate_their_key() {
+        let key = AuthSecretKey::new_rpo_falcon512();
+        let auth = AuthRpoFalcon512::new(key.public_key().to_commitment()).into();
+        let account = build_wallet_account([1; 32], auth, AccountStorageMode::Public).unwrap();
+
+        let error = rotate_auth_key_request(&account, Word::default()).unwrap_err();
+        assert_eq!(error.code(), ERR_ACCOUNT_OP);
+    }
+
+    #[test]
+    fn invalid_requests_fail_before_reaching_the_node() {
+        run(async {
+            let (mut client, keystore, _rpc) = test_client().await;
+            let faucet_id = add_faucet(&mut client, &keystore).await;
+            let wallet = add_wallet(&mut client, &keystore, 1).await;
+
+            assert_eq!(consume_notes_request(Vec::new()).unwrap_err().code(), ERR_NOTE_OP);
+
+            let note_type = NoteType::Public;
+            // Above the maximum amount of a fungible asset
+            let (amount, rng) = (u64::MAX, client.rng());
+            let error =
+                payment_note(wallet, wallet, faucet_id, amount, note_type, None, rng).unwrap_err();
+            assert_eq!(error.code(), ERR_INVALID_PARAM);
+
+            // A regular account is not a faucet
+            let error = payment_note(wallet, wallet, wallet, 1, note_type, None, client.rng())
+                .unwrap_err();
+            assert_eq!(error.code(), ERR_INVALID_PARAM);
+        });
+    }
+}
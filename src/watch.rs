@@ -0,0 +1,311 @@
+//! Watchtower delegation for sent P2IDE notes
+//!
+//! A P2IDE note (escrows, stream payouts) can be reclaimed by the sender once its reclaim
+//! height is reached, but the wallet only notices when it next syncs. A watch service
+//! follows the chain on the wallet's behalf and alerts when a delegated note becomes
+//! reclaimable or is consumed.
+//!
+//! Delegation posts `{"watches":[{"note_id","nullifier","reclaim_block"}]}` to the
+//! service. That is all it needs: the note ID to recognize the note, the nullifier to see
+//! it consumed and the reclaim height. Assets, recipient and sender are not shared.
+//!
+//! The service delivers alerts out of band (typically as a push). Ingesting an alert
+//! `{"note_id","alert":"reclaimable"|"consumed","block_num"}` emits a `watch_alert`
+//! event: `{"type":"watch_alert","note_id","alert","block_num"}`. Alerts are not
+//! authenticated, so they are only accepted for delegated notes and treated as hints: act
+//! on them after a sync. A `consumed` alert ends the watch. Watches are kept in a JSON
+//! file next to the SQLite store.
+
+use std::{
+    collections::BTreeMap,
+    os::raw::c_char,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use miden_objects::note::NoteId;
+
+use crate::{
+    diagnostics, get_handle, last_error, memory_store, note_summary, parse_required_str,
+    request_blocking, write_out_buffer, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM, ERR_LOOKUP, ERR_NOTE_OP, ERR_RELAY,
+};
+
+/// Timeout of the delegation request
+const WATCH_SERVICE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Upper bound on the notes of one delegation
+pub const WATCH_MAX_NOTES: usize = 256;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WatchAlertKind {
+    Reclaimable,
+    Consumed,
+}
+
+impl WatchAlertKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            WatchAlertKind::Reclaimable => "reclaimable",
+            WatchAlertKind::Consumed => "consumed",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "reclaimable" => Some(WatchAlertKind::Reclaimable),
+            "consumed" => Some(WatchAlertKind::Consumed),
+            _ => None,
+        }
+    }
+}
+
+/// Alert delivered by a watch service
+pub(crate) struct WatchAlert {
+    note_id: NoteId,
+    kind: WatchAlertKind,
+    block_num: u32,
+}
+
+impl WatchAlert {
+    /// Parse `{"note_id":"0x..","alert":"reclaimable","block_num":123}`
+    fn from_json(json: &str) -> Result<Self, i32> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|_| ERR_INVALID_PARAM)?;
+
+        let note_id = value["note_id"].as_str()
+            .and_then(|hex| NoteId::try_from_hex(hex).ok())
+            .ok_or(ERR_INVALID_PARAM)?;
+        let kind = value["alert"].as_str()
+            .and_then(WatchAlertKind::parse)
+            .ok_or(ERR_INVALID_PARAM)?;
+        let block_num = value["block_num"].as_u64()
+            .and_then(|block| u32::try_from(block).ok())
+            .ok_or(ERR_INVALID_PARAM)?;
+
+        Ok(Self { note_id, kind, block_num })
+    }
+}
+
+struct Watch {
+    service_url: String,
+    nullifier: String,
+    reclaim_at: u64,
+}
+
+/// Notes delegated to watch services from this store
+pub(crate) struct Watches {
+    path: PathBuf,
+    watches: BTreeMap<NoteId, Watch>,
+}
+
+impl Watches {
+    /// Load the sidecar file for the store at `store_path` (missing or corrupt → none)
+    pub fn load(store_path: &Path) -> Self {
+        let path = store_path.with_extension("watch.json");
+        let entries: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        let watches = entries
+            .iter()
+            .filter_map(|entry| {
+                let note_id = NoteId::try_from_hex(entry["note_id"].as_str()?).ok()?;
+                let watch = Watch {
+                    service_url: entry["service_url"].as_str()?.to_string(),
+                    nullifier: entry["nullifier"].as_str()?.to_string(),
+                    reclaim_at: entry["reclaim_block"].as_u64()?,
+                };
+                Some((note_id, watch))
+            })
+            .collect();
+
+        Self { path, watches }
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let entries: Vec<serde_json::Value> = self.watches
+            .iter()
+            .map(|(note_id, watch)| {
+                serde_json::json!({
+                    "note_id": note_id.to_hex(),
+                    "service_url": watch.service_url,
+                    "nullifier": watch.nullifier,
+                    "reclaim_block": watch.reclaim_at,
+                })
+            })
+            .collect();
+        memory_store::write(&self.path, serde_json::Value::from(entries).to_string())
+    }
+}
+
+pub(crate) async fn delegate_watch_impl(
+    context: &mut MidenContext,
+    service_url: String,
+    note_ids: Vec<NoteId>,
+) -> Result<String, i32> {
+    // Only notes sent from this store with a reclaim height can be watched
+    let mut watches = Vec::with_capacity(note_ids.len());
+    for note_id in note_ids {
+        let record = context.client.get_output_note(note_id).await
+            .map_err(|e| last_error::detail(ERR_NOTE_OP, e))?
+            .ok_or(ERR_LOOKUP)?;
+        let reclaim_at = record.recipient()
+            .and_then(note_summary::reclaim_height)
+            .ok_or(ERR_INVALID_PARAM)?;
+        let nullifier = record.nullifier().ok_or(ERR_INVALID_PARAM)?.to_hex();
+        watches.push((note_id, Watch { service_url: service_url.clone(), nullifier, reclaim_at }));
+    }
+
+    let body = serde_json::json!({
+        "watches": watches
+            .iter()
+            .map(|(note_id, watch)| serde_json::json!({
+                "note_id": note_id.to_hex(),
+                "nullifier": watch.nullifier,
+                "reclaim_block": watch.reclaim_at,
+            }))
+            .collect::<Vec<_>>(),
+    });
+
+    let client = reqwest::Client::builder()
+        .timeout(WATCH_SERVICE_TIMEOUT)
+        .build()
+        .map_err(|e| last_error::detail(ERR_RELAY, e))?;
+    let response = client.post(&service_url).json(&body).send().await
+        .map_err(|e| last_error::detail(ERR_RELAY, e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        diagnostics::log("watch", format!("watch service refused delegation: {}", status));
+        return Err(last_error::detail(ERR_RELAY, format!("watch service answered {}", status)));
+    }
+
+    let delegated: Vec<String> = watches.iter().map(|(note_id, _)| note_id.to_hex()).collect();
+    context.watches.watches.extend(watches);
+    context.watches.save().map_err(|_| ERR_NOTE_OP)?;
+
+    Ok(serde_json::json!({ "delegated": delegated }).to_string())
+}
+
+pub(crate) fn ingest_watch_alert_impl(
+    context: &mut MidenContext,
+    alert: WatchAlert,
+) -> Result<(), i32> {
+    let watch = context.watches.watches.get(&alert.note_id).ok_or(ERR_LOOKUP)?;
+    // A note cannot become reclaimable before its reclaim height
+    if alert.kind == WatchAlertKind::Reclaimable && u64::from(alert.block_num) < watch.reclaim_at {
+        return Err(ERR_INVALID_PARAM);
+    }
+
+    if alert.kind == WatchAlertKind::Consumed {
+        context.watches.watches.remove(&alert.note_id);
+        context.watches.save().map_err(|_| ERR_NOTE_OP)?;
+    }
+    context.events.emit(serde_json::json!({
+        "type": "watch_alert",
+        "note_id": alert.note_id.to_hex(),
+        "alert": alert.kind.as_str(),
+        "block_num": alert.block_num,
+    }));
+    Ok(())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Delegate monitoring of sent P2IDE notes to a watch service (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Shares only the note ID, nullifier and reclaim height of each note (see the module
+/// docs). Delegating a note again replaces its watch.
+///
+/// # Parameters
+/// - `watch_service_url`: Delegation endpoint of the watch service (C string, http or https)
+/// - `note_ids_json`: JSON array of output note IDs (hex), at most [`WATCH_MAX_NOTES`]
+/// - `json_out` / `json_out_len`: Output buffer for `{"delegated":["0x.."]}`
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, or a note without a reclaim height or known recipient
+/// - -2: Invalid handle or worker closed
+/// - -4: Store error
+/// - -5: Note not found among the notes sent from this store
+/// - -7: Output buffer too small
+/// - -103: Watch service unreachable or delegation refused
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_delegate_watch(
+    handle: MidenHandle,
+    watch_service_url: *const c_char,
+    note_ids_json: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let service_url = match parse_required_str(watch_service_url) {
+        Ok(url) if url.starts_with("https://") || url.starts_with("http://") => url.to_string(),
+        Ok(_) => return ERR_INVALID_PARAM,
+        Err(code) => return code,
+    };
+    let note_ids = match parse_required_str(note_ids_json) {
+        Ok(json) => match serde_json::from_str::<Vec<String>>(json) {
+            Ok(ids) if !ids.is_empty() && ids.len() <= WATCH_MAX_NOTES => ids,
+            _ => return ERR_INVALID_PARAM,
+        },
+        Err(code) => return code,
+    };
+    let note_ids = match note_ids
+        .iter()
+        .map(|id| NoteId::try_from_hex(id))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(note_ids) => note_ids,
+        Err(_) => return ERR_INVALID_PARAM,
+    };
+
+    let request = |reply| Request::DelegateWatch { service_url, note_ids, reply };
+    match request_blocking(&worker, request) {
+        Ok(json) => write_out_buffer(&json, json_out, json_out_len),
+        Err(code) => code,
+    }
+}
+
+/// Ingest an alert from a watch service into the event stream (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// Emits a `watch_alert` event. Alerts are not authenticated: sync before acting on one.
+///
+/// # Parameters
+/// - `alert_json`: `{"note_id","alert":"reclaimable"|"consumed","block_num"}` (C string)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid alert, or `reclaimable` before the note's reclaim height
+/// - -2: Invalid handle or worker closed
+/// - -4: Store error
+/// - -5: Note is not watched
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_ingest_watch_alert(
+    handle: MidenHandle,
+    alert_json: *const c_char,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let alert = match parse_required_str(alert_json).and_then(WatchAlert::from_json) {
+        Ok(alert) => alert,
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::IngestWatchAlert { alert, reply };
+    match request_blocking(&worker, request) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,328 @@
+//! Per-account withdrawal whitelists
+//!
+//! A whitelisted account can only send assets to the listed account IDs; any other send
+//! is rejected before execution. Output notes whose recipient cannot be determined (note
+//! scripts other than P2ID) are rejected too when they carry assets.
+//!
+//! The first whitelist set for an account fixes its lock passphrase; changing or removing
+//! the whitelist later requires the same passphrase. Every change, refused change and
+//! blocked withdrawal is recorded in the audit log. Whitelists and passphrase hashes are
+//! kept in a JSON file next to the SQLite store; the hash is Argon2id with its parameters
+//! stored as `kdf` (see passphrase.rs). Locks set before Argon2id (iterated SHA3-256, no
+//! `kdf`) are still checked, and hashed again with Argon2id on their next change.
+//!
+//! The file fails closed. When it exists but cannot be read or parsed, every send of
+//! assets and every whitelist change is refused until it is repaired; an entry that cannot
+//! be read refuses sends from its account and is written back as found when other entries
+//! change. Only a missing file means no whitelists.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    os::raw::c_char,
+    path::{Path, PathBuf},
+};
+
+use miden_objects::{account::AccountId, note::Note};
+use rand::RngCore;
+
+use crate::{
+    audit_log, diagnostics, get_handle, last_error, memory_store, parse_account_id,
+    parse_required_str,
+    passphrase::{Kdf, KdfParams},
+    request_blocking, screening, MidenContext, MidenHandle, Request, ERR_INVALID_HANDLE,
+    ERR_INVALID_PARAM, ERR_PERMISSION_DENIED,
+};
+
+/// SHA3-256 rounds of locks set before Argon2id
+const LEGACY_PASSPHRASE_ROUNDS: u32 = 100_000;
+
+struct AccountWhitelist {
+    addresses: BTreeSet<AccountId>,
+    salt: [u8; 16],
+    kdf: Kdf,
+    passphrase_hash: [u8; 32],
+}
+
+/// Whitelists of all accounts in the store
+pub(crate) struct Whitelists {
+    path: PathBuf,
+    accounts: HashMap<AccountId, AccountWhitelist>,
+    /// Entries that could not be read, kept as found
+    unreadable: HashMap<AccountId, serde_json::Value>,
+    /// Why the file could not be read; every send and change is refused then
+    error: Option<String>,
+}
+
+fn hash_passphrase(kdf: Kdf, salt: &[u8; 16], passphrase: &str) -> [u8; 32] {
+    // Legacy locks were hashed without a domain
+    let domain = match kdf {
+        Kdf::Argon2id(_) => "miden-whitelist:v2:",
+        Kdf::LegacySha3 { .. } => "",
+    };
+    kdf.derive(domain, salt, passphrase)
+}
+
+/// Compare without an early exit on the first differing byte
+fn hashes_equal(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+impl Whitelists {
+    /// Load the sidecar file for the store at `store_path` (missing → no whitelists)
+    pub fn load(store_path: &Path) -> Self {
+        let path = store_path.with_extension("whitelist.json");
+        let mut whitelists = Self {
+            path,
+            accounts: HashMap::new(),
+            unreadable: HashMap::new(),
+            error: None,
+        };
+
+        let entries = match std::fs::read_to_string(&whitelists.path) {
+            Ok(contents) => serde_json::from_str::<serde_json::Map<_, _>>(&contents)
+                .map_err(|e| format!("whitelist file is not valid: {}", e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return whitelists,
+            Err(e) => Err(format!("whitelist file could not be read: {}", e)),
+        };
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(error) => return whitelists.failed(error),
+        };
+
+        for (account_id, entry) in entries {
+            let Ok(account_id) = AccountId::from_hex(&account_id) else {
+                let error = format!("whitelist entry of invalid account {}", account_id);
+                return whitelists.failed(error);
+            };
+            match parse_entry(&entry) {
+                Some(whitelist) => {
+                    whitelists.accounts.insert(account_id, whitelist);
+                }
+                None => {
+                    diagnostics::log(
+                        "whitelist",
+                        format!("entry of {} could not be read", account_id.to_hex()),
+                    );
+                    whitelists.unreadable.insert(account_id, entry);
+                }
+            }
+        }
+        whitelists
+    }
+
+    /// Refuse everything after a read error (see the module docs)
+    fn failed(mut self, error: String) -> Self {
+        diagnostics::log("whitelist", &error);
+        self.accounts.clear();
+        self.unreadable.clear();
+        self.error = Some(error);
+        self
+    }
+
+    /// Why sends from and changes to `account_id` are refused without checking the lock
+    fn unreadable(&self, account_id: AccountId) -> Option<String> {
+        if let Some(error) = &self.error {
+            return Some(error.clone());
+        }
+        self.unreadable
+            .contains_key(&account_id)
+            .then(|| format!("whitelist entry of {} could not be read", account_id.to_hex()))
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let entries: serde_json::Map<String, serde_json::Value> = self.accounts
+            .iter()
+            .map(|(account_id, whitelist)| {
+                let addresses: Vec<String> =
+                    whitelist.addresses.iter().map(|id| id.to_hex()).collect();
+                let mut entry = serde_json::json!({
+                    "addresses": addresses,
+                    "salt": hex::encode(whitelist.salt),
+                    "passphrase_hash": hex::encode(whitelist.passphrase_hash),
+                });
+                if let Kdf::Argon2id(params) = whitelist.kdf {
+                    entry["kdf"] = params.to_json();
+                }
+                (account_id.to_hex(), entry)
+            })
+            .chain(self.unreadable.iter().map(|(account_id, entry)| {
+                (account_id.to_hex(), entry.clone())
+            }))
+            .collect();
+        memory_store::write(&self.path, serde_json::Value::Object(entries).to_string())
+    }
+}
+
+fn parse_entry(entry: &serde_json::Value) -> Option<AccountWhitelist> {
+    let hex_field = |name: &str| entry[name].as_str().and_then(|s| hex::decode(s).ok());
+    let addresses = entry["addresses"]
+        .as_array()?
+        .iter()
+        .map(|id| id.as_str().and_then(|s| AccountId::from_hex(s).ok()))
+        .collect::<Option<BTreeSet<_>>>()?;
+    Some(AccountWhitelist {
+        addresses,
+        salt: hex_field("salt")?.try_into().ok()?,
+        kdf: match entry.get("kdf") {
+            Some(kdf) => Kdf::Argon2id(KdfParams::from_json(kdf)?),
+            None => Kdf::LegacySha3 { rounds: LEGACY_PASSPHRASE_ROUNDS },
+        },
+        passphrase_hash: hex_field("passphrase_hash")?.try_into().ok()?,
+    })
+}
+
+/// Reject sends from a whitelisted account to recipients outside its whitelist, and all
+/// sends of assets from an account whose whitelist could not be read
+pub(crate) fn check(
+    context: &MidenContext,
+    account_id: AccountId,
+    output_notes: &[Note],
+) -> Result<(), i32> {
+    if let Some(error) = context.whitelists.unreadable(account_id) {
+        if output_notes.iter().all(|note| note.assets().is_empty()) {
+            return Ok(());
+        }
+        audit_log::record(context, "withdrawal_blocked", serde_json::json!({
+            "account_id": account_id.to_hex(),
+            "reason": error,
+        }));
+        return Err(last_error::detail(ERR_PERMISSION_DENIED, error));
+    }
+    let Some(whitelist) = context.whitelists.accounts.get(&account_id) else {
+        return Ok(());
+    };
+
+    for note in output_notes.iter().filter(|note| !note.assets().is_empty()) {
+        let recipient = screening::p2id_target(note);
+        if recipient.is_some_and(|recipient| whitelist.addresses.contains(&recipient)) {
+            continue;
+        }
+
+        audit_log::record(context, "withdrawal_blocked", serde_json::json!({
+            "account_id": account_id.to_hex(),
+            "recipient": recipient.map(|id| id.to_hex()),
+        }));
+        return Err(ERR_PERMISSION_DENIED);
+    }
+    Ok(())
+}
+
+pub(crate) fn set_withdrawal_whitelist_impl(
+    context: &mut MidenContext,
+    account_id: AccountId,
+    addresses: Option<BTreeSet<AccountId>>,
+    passphrase: String,
+) -> Result<String, i32> {
+    if let Some(error) = context.whitelists.unreadable(account_id) {
+        audit_log::record(context, "whitelist_change_denied", serde_json::json!({
+            "account_id": account_id.to_hex(),
+            "reason": error,
+        }));
+        return Err(last_error::detail(ERR_PERMISSION_DENIED, error));
+    }
+    if let Some(existing) = context.whitelists.accounts.get(&account_id) {
+        let hash = hash_passphrase(existing.kdf, &existing.salt, &passphrase);
+        if !hashes_equal(&hash, &existing.passphrase_hash) {
+            audit_log::record(context, "whitelist_change_denied", serde_json::json!({
+                "account_id": account_id.to_hex(),
+            }));
+            return Err(ERR_PERMISSION_DENIED);
+        }
+    }
+
+    let (event, details) = match addresses {
+        Some(addresses) => {
+            let mut salt = [0u8; 16];
+            rand::rng().fill_bytes(&mut salt);
+            let details = serde_json::json!({
+                "account_id": account_id.to_hex(),
+                "addresses": addresses.iter().map(|id| id.to_hex()).collect::<Vec<_>>(),
+            });
+            let kdf = Kdf::Argon2id(KdfParams::default());
+            context.whitelists.accounts.insert(account_id, AccountWhitelist {
+                addresses,
+                salt,
+                kdf,
+                passphrase_hash: hash_passphrase(kdf, &salt, &passphrase),
+            });
+            ("whitelist_updated", details)
+        }
+        None => {
+            context.whitelists.accounts.remove(&account_id);
+            ("whitelist_removed", serde_json::json!({ "account_id": account_id.to_hex() }))
+        }
+    };
+
+    context.whitelists.save().map_err(|_| ERR_INVALID_PARAM)?;
+    audit_log::record(context, event, details);
+    Ok(String::new())
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Set, replace or remove the withdrawal whitelist of an account (blocking)
+///
+/// WARNING: This is a blocking call. Do NOT call from the main/UI thread.
+///
+/// # Parameters
+/// - `account_id_hex`: Account whose sends are restricted (C string)
+/// - `addresses_json`: JSON array of allowed recipient account IDs, or NULL to remove
+///   the whitelist (an empty array blocks all sends)
+/// - `lock_passphrase`: Sets the lock when the account has no whitelist yet; must match
+///   the existing lock otherwise (C string, not empty)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters, or the whitelist file could not be written
+/// - -2: Invalid handle or worker closed
+/// - -3: Invalid account ID
+/// - -101: Wrong lock passphrase, or the whitelist file or the account's entry could not
+///   be read
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_set_withdrawal_whitelist(
+    handle: MidenHandle,
+    account_id_hex: *const c_char,
+    addresses_json: *const c_char,
+    lock_passphrase: *const c_char,
+) -> i32 {
+    let Some(worker) = get_handle(handle) else {
+        return ERR_INVALID_HANDLE;
+    };
+
+    let (account_id, _) = match parse_account_id(account_id_hex) {
+        Ok(parsed) => parsed,
+        Err(code) => return code,
+    };
+    let addresses = if addresses_json.is_null() {
+        None
+    } else {
+        let parsed = parse_required_str(addresses_json).and_then(|json| {
+            let ids: Vec<String> = serde_json::from_str(json).map_err(|_| ERR_INVALID_PARAM)?;
+            ids.iter()
+                .map(|id| AccountId::from_hex(id).map_err(|_| ERR_INVALID_PARAM))
+                .collect::<Result<BTreeSet<_>, _>>()
+        });
+        match parsed {
+            Ok(addresses) => Some(addresses),
+            Err(code) => return code,
+        }
+    };
+    let passphrase = match parse_required_str(lock_passphrase) {
+        Ok(passphrase) if !passphrase.is_empty() => passphrase.to_string(),
+        Ok(_) => return ERR_INVALID_PARAM,
+        Err(code) => return code,
+    };
+
+    let request = |reply| Request::SetWithdrawalWhitelist {
+        account_id,
+        addresses,
+        passphrase,
+        reply,
+    };
+    match request_blocking(&worker, request) {
+        Ok(_) => 0,
+        Err(code) => code,
+    }
+}
@@ -0,0 +1,149 @@
+//! WidgetKit snapshot
+//!
+//! Widget timeline providers run in their own process under tight time and memory
+//! limits, so they cannot open a client (let alone sync). After every successful sync the
+//! worker writes a small summary next to the SQLite store:
+//!
+//! - the balance of each token summed over all accounts of the store (except archived ones)
+//! - the most recent transaction
+//! - the number of notes waiting to be consumed
+//!
+//! `wc_miden_get_widget_snapshot` only reads that file, from the store path (typically in
+//! an App Group container shared with the widget extension), without a handle. The file
+//! is replaced atomically, so a reader never sees a partial write.
+
+use std::{collections::BTreeMap, os::raw::c_char, path::Path};
+
+use miden_client::store::TransactionFilter;
+use miden_objects::{account::AccountId, asset::Asset};
+
+use crate::{
+    clock, diagnostics, memory_store, parse_required_str, write_out_buffer, MidenContext,
+    ERR_INVALID_PARAM, ERR_LOOKUP,
+};
+
+/// Version tag of the snapshot format
+pub const WIDGET_SNAPSHOT_VERSION: u32 = 1;
+
+async fn build_snapshot(context: &MidenContext) -> Result<serde_json::Value, String> {
+    // Archived accounts are left out of the totals
+    let headers: Vec<_> = context.client.get_account_headers().await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|(header, _)| !context.archive.contains(header.id()))
+        .collect();
+
+    let mut balances: BTreeMap<AccountId, u64> = BTreeMap::new();
+    for (header, _) in &headers {
+        let record = context.client.get_account(header.id()).await.map_err(|e| e.to_string())?;
+        let Some(record) = record else {
+            continue;
+        };
+        for asset in record.account().vault().assets() {
+            if let Asset::Fungible(fungible) = asset {
+                let total = balances.entry(fungible.faucet_id()).or_default();
+                *total = total.saturating_add(fungible.amount());
+            }
+        }
+    }
+
+    let last_activity = context.client.get_transactions(TransactionFilter::All).await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .max_by_key(|record| record.details.block_num)
+        .map(|record| {
+            serde_json::json!({
+                "tx_id": record.id.to_hex(),
+                "account_id": record.details.account_id.to_hex(),
+                "block_num": record.details.block_num.as_u32(),
+                "timestamp": context.block_times.timestamp(record.details.block_num.as_u32()),
+            })
+        });
+
+    let mut unclaimed_notes = context.client.get_consumable_notes(None).await
+        .map_err(|e| e.to_string())?;
+    context.archive.retain_active(&mut unclaimed_notes);
+    let unclaimed_note_count = unclaimed_notes.len();
+
+    let balances: Vec<serde_json::Value> = balances
+        .into_iter()
+        .map(|(faucet_id, amount)| {
+            serde_json::json!({ "faucet_id": faucet_id.to_hex(), "amount": amount })
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "version": WIDGET_SNAPSHOT_VERSION,
+        "updated_at": clock::now_unix(),
+        "sync_height": clock::current_block(context).await.ok(),
+        "account_count": headers.len(),
+        "balances": balances,
+        "last_activity": last_activity,
+        "unclaimed_note_count": unclaimed_note_count,
+    }))
+}
+
+/// Refresh the snapshot file (run after each successful sync)
+pub(crate) async fn update_after_sync(context: &MidenContext) {
+    // An in-memory client has no file for an extension to read
+    if memory_store::is_in_memory(&context.store_path) {
+        return;
+    }
+
+    let snapshot = match build_snapshot(context).await {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            diagnostics::log("widget", format!("snapshot failed: {}", e));
+            return;
+        }
+    };
+
+    let path = context.store_path.with_extension("widget.json");
+    let tmp_path = context.store_path.with_extension("widget.json.tmp");
+    let result = std::fs::write(&tmp_path, snapshot.to_string())
+        .and_then(|_| std::fs::rename(&tmp_path, &path));
+    if let Err(e) = result {
+        diagnostics::log("widget", format!("failed to save snapshot: {:?}", e));
+    }
+}
+
+// ================================================================================================
+// FFI Interface
+// ================================================================================================
+
+/// Read the wallet summary written after the last successful sync
+///
+/// Does not need a handle and does not touch the SQLite store or the network: it only
+/// reads a small file, so it is cheap enough for a widget timeline provider.
+///
+/// # Parameters
+/// - `store_path`: SQLite database file path, as passed to `wc_miden_create` (C string)
+/// - `json_out` / `json_out_len`: Output buffer for
+///   `{"version":1,"updated_at","sync_height","account_count",
+///   "balances":[{"faucet_id","amount"}],
+///   "last_activity":{"tx_id","account_id","block_num","timestamp"}|null,
+///   "unclaimed_note_count"}` (`updated_at` in Unix seconds, `timestamp` in ISO-8601;
+///   balances summed over all accounts, in base units)
+///
+/// # Returns
+/// - 0: Success
+/// - -1: Invalid parameters
+/// - -5: No snapshot yet (no sync has completed on this store)
+/// - -7: Output buffer too small
+#[unsafe(no_mangle)]
+pub extern "C" fn wc_miden_get_widget_snapshot(
+    store_path: *const c_char,
+    json_out: *mut u8,
+    json_out_len: *mut usize,
+) -> i32 {
+    let store_path = match parse_required_str(store_path) {
+        Ok(path) if !path.is_empty() => path,
+        Ok(_) => return ERR_INVALID_PARAM,
+        Err(code) => return code,
+    };
+
+    match std::fs::read(Path::new(store_path).with_extension("widget.json")) {
+        Ok(snapshot) => write_out_buffer(&snapshot, json_out, json_out_len),
+        Err(_) => ERR_LOOKUP,
+    }
+}
@@ -0,0 +1,61 @@
+//! SQLCipher store: with `store_key` the store file is encrypted on disk, and only a
+//! connection keyed with it can read the client's tables.
+//!
+//! Run with `cargo test --features sqlcipher`.
+
+#![cfg(feature = "sqlcipher")]
+
+use std::{ffi::CString, os::raw::c_char, path::PathBuf};
+
+use miden_swift_client as ffi;
+
+unsafe extern "C" {
+    fn wc_miden_create_with_config(config_json: *const c_char, handle_out: *mut u64) -> i32;
+}
+
+const STORE_KEY: &str = "correct horse battery staple";
+
+/// Fresh directory for one test's keystore and store
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("miden-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn table_count(conn: &rusqlite::Connection) -> rusqlite::Result<i64> {
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| row.get(0))
+}
+
+#[test]
+fn store_file_needs_the_key() {
+    let dir = test_dir("encrypted-store");
+    let store_path = dir.join("store.sqlite3");
+    let config = serde_json::json!({
+        "keystore_path": dir.join("keystore"),
+        "store_path": store_path,
+        "network_id": "localhost",
+        "store_key": STORE_KEY,
+    });
+    let config = CString::new(config.to_string()).unwrap();
+
+    let mut handle: ffi::MidenHandle = 0;
+    assert_eq!(unsafe { wc_miden_create_with_config(config.as_ptr(), &mut handle) }, 0);
+    ffi::wc_miden_destroy(&mut handle);
+
+    // SQLCipher leaves no plain SQLite header, so an unkeyed connection sees no database
+    let header = std::fs::read(&store_path).unwrap();
+    assert!(!header.starts_with(b"SQLite format 3\0"));
+    let plain = rusqlite::Connection::open(&store_path).unwrap();
+    assert!(table_count(&plain).is_err());
+
+    let wrong = rusqlite::Connection::open(&store_path).unwrap();
+    wrong.pragma_update(None, "key", "wrong key").unwrap();
+    assert!(table_count(&wrong).is_err());
+
+    let keyed = rusqlite::Connection::open(&store_path).unwrap();
+    keyed.pragma_update(None, "key", STORE_KEY).unwrap();
+    assert!(table_count(&keyed).unwrap() > 0);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
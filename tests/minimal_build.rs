@@ -0,0 +1,115 @@
+//! Feature-gated builds: the core wallet flows must link with every optional subsystem
+//! compiled out, and `wc_miden_capabilities` must report what the build contains.
+//!
+//! Run against the minimal profile with `cargo test --no-default-features --features
+//! mock-node`, which also runs the wallet core flows against the mock node (the unit tests
+//! in wallet_core.rs). CI runs it on every push (`.github/workflows/minimal-build.yml`).
+
+use miden_swift_client as ffi;
+
+/// Address of an FFI entry point (non-null once the symbol has been linked)
+macro_rules! symbol {
+    ($f:path) => {
+        $f as *const () as usize
+    };
+}
+
+#[test]
+fn core_wallet_flows_link() {
+    let entry_points = [
+        symbol!(ffi::wc_miden_create),
+        symbol!(ffi::wc_miden_destroy),
+        symbol!(ffi::wc_miden_sync),
+        symbol!(ffi::wc_miden_sync_async),
+        symbol!(ffi::wc_miden_create_wallet),
+        symbol!(ffi::wc_miden_get_accounts),
+        symbol!(ffi::wc_miden_get_balance),
+        symbol!(ffi::wc_miden_get_input_notes),
+        symbol!(ffi::wc_miden_consume_notes),
+        symbol!(ffi::wc_miden_send),
+        symbol!(ffi::wc_miden_send_async),
+        symbol!(ffi::wc_bytes_free),
+    ];
+    assert!(entry_points.iter().all(|address| *address != 0));
+}
+
+#[cfg(feature = "faucets")]
+#[test]
+fn faucet_entry_points_link() {
+    // Declared here because the faucet module is private; the addresses are never called
+    unsafe extern "C" {
+        fn wc_miden_create_faucet();
+        fn wc_miden_mint();
+    }
+    assert_ne!(symbol!(wc_miden_create_faucet), 0);
+    assert_ne!(symbol!(wc_miden_mint), 0);
+}
+
+#[cfg(feature = "swaps")]
+#[test]
+fn swap_entry_points_link() {
+    unsafe extern "C" {
+        fn wc_miden_create_swap();
+    }
+    assert_ne!(symbol!(wc_miden_create_swap), 0);
+}
+
+#[cfg(feature = "mnemonic")]
+#[test]
+fn mnemonic_round_trips_to_a_seed() {
+    use std::{ffi::CString, os::raw::c_char, ptr};
+
+    unsafe extern "C" {
+        fn wc_mnemonic_generate(word_count: u32, out: *mut u8, out_len: *mut usize) -> i32;
+        fn wc_mnemonic_to_seed(
+            phrase: *const c_char,
+            passphrase: *const c_char,
+            out: *mut u8,
+            out_len: *mut usize,
+        ) -> i32;
+    }
+    let to_seed = |phrase: &CString, passphrase: *const c_char| {
+        let mut seed = [0u8; 64];
+        let mut len = seed.len();
+        let result = unsafe {
+            wc_mnemonic_to_seed(phrase.as_ptr(), passphrase, seed.as_mut_ptr(), &mut len)
+        };
+        (result == 0).then_some(seed).filter(|_| len == 64)
+    };
+
+    let mut phrase = vec![0u8; 256];
+    let mut len = phrase.len();
+    assert_eq!(unsafe { wc_mnemonic_generate(24, phrase.as_mut_ptr(), &mut len) }, 0);
+    phrase.truncate(len);
+    assert_eq!(phrase.split(|byte| *byte == b' ').count(), 24);
+    assert_eq!(unsafe { wc_mnemonic_generate(13, phrase.as_mut_ptr(), &mut len) }, -1);
+
+    let phrase = CString::new(phrase).unwrap();
+    let seed = to_seed(&phrase, ptr::null()).unwrap();
+    assert_eq!(to_seed(&phrase, c"".as_ptr()), Some(seed));
+    assert_ne!(to_seed(&phrase, c"extra words".as_ptr()), Some(seed));
+
+    // The last word carries a checksum, so twelve times the first word is not a phrase
+    let mistyped = CString::new(["abandon"; 12].join(" ")).unwrap();
+    assert_eq!(to_seed(&mistyped, ptr::null()), None);
+}
+
+#[test]
+fn capabilities_report_compiled_features() {
+    let mut buffer = vec![0u8; 4096];
+    let mut len = buffer.len();
+    assert_eq!(ffi::wc_miden_capabilities(buffer.as_mut_ptr(), &mut len), 0);
+
+    let json: serde_json::Value = serde_json::from_slice(&buffer[..len]).unwrap();
+    let capabilities = &json["capabilities"];
+    assert_eq!(capabilities["faucets"], cfg!(feature = "faucets"));
+    assert_eq!(capabilities["evm_bridge"], cfg!(feature = "scripts"));
+    assert_eq!(capabilities["name_registry"], cfg!(feature = "scripts"));
+    assert_eq!(capabilities["swaps"], cfg!(feature = "swaps"));
+    assert_eq!(capabilities["mnemonic"], cfg!(feature = "mnemonic"));
+    assert_eq!(capabilities["testing_hooks"], cfg!(feature = "testing"));
+    assert_eq!(capabilities["memory_debug"], cfg!(feature = "memory-debug"));
+    assert_eq!(capabilities["mock_node"], cfg!(feature = "mock-node"));
+    // Always compiled in
+    assert_eq!(capabilities["external_signers"], true);
+}
@@ -0,0 +1,269 @@
+//! Release tasks for the Swift package
+//!
+//! `cargo xtask build-xcframework` builds `miden_swift_client.xcframework` from Rust
+//! alone: the C header is generated with cbindgen as a library (no installed CLI), each
+//! Apple target is built with its deployment target pinned through the environment (so a
+//! local `.cargo/config.toml` cannot change it), the simulator and macOS slices are merged
+//! into universal libraries with `lipo`, and `xcodebuild -create-xcframework` assembles the
+//! slices with the header and module map.
+//!
+//! Requires Xcode command line tools and the Rust targets (`rustup target add ...`, listed
+//! by `cargo xtask targets`).
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+};
+
+const CRATE_NAME: &str = "miden_swift_client";
+const MODULE_NAME: &str = "MidenSwiftClient";
+
+/// Deployment targets used unless overridden (matching `build_ios.sh`)
+const DEFAULT_IOS_DEPLOYMENT_TARGET: &str = "18.5";
+const DEFAULT_MACOS_DEPLOYMENT_TARGET: &str = "15.0";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Platform {
+    Ios,
+    IosSimulator,
+    MacOs,
+}
+
+/// XCFramework slices: each platform's triples are merged into one library
+const TARGETS: &[(&str, Platform)] = &[
+    ("aarch64-apple-ios", Platform::Ios),
+    ("aarch64-apple-ios-sim", Platform::IosSimulator),
+    ("x86_64-apple-ios", Platform::IosSimulator),
+    ("aarch64-apple-darwin", Platform::MacOs),
+    ("x86_64-apple-darwin", Platform::MacOs),
+];
+
+impl Platform {
+    const ALL: [Platform; 3] = [Platform::Ios, Platform::IosSimulator, Platform::MacOs];
+
+    fn dir_name(self) -> &'static str {
+        match self {
+            Platform::Ios => "ios",
+            Platform::IosSimulator => "ios-simulator",
+            Platform::MacOs => "macos",
+        }
+    }
+
+    /// Linker flag setting the minimum OS version
+    fn min_version_arg(self, options: &Options) -> String {
+        match self {
+            Platform::Ios => format!("-miphoneos-version-min={}", options.ios_deployment_target),
+            Platform::IosSimulator => {
+                format!("-mios-simulator-version-min={}", options.ios_deployment_target)
+            }
+            Platform::MacOs => format!("-mmacosx-version-min={}", options.macos_deployment_target),
+        }
+    }
+}
+
+struct Options {
+    ios_deployment_target: String,
+    macos_deployment_target: String,
+    /// Cargo features of the crate (e.g. `testing` for integration-test builds)
+    features: Option<String>,
+    /// Build without the crate's default features (see Cargo.toml)
+    no_default_features: bool,
+    /// Skip the macOS slice
+    no_macos: bool,
+}
+
+impl Options {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options {
+            ios_deployment_target: env::var("IOS_DEPLOYMENT_TARGET")
+                .unwrap_or_else(|_| DEFAULT_IOS_DEPLOYMENT_TARGET.to_string()),
+            macos_deployment_target: env::var("MACOS_DEPLOYMENT_TARGET")
+                .unwrap_or_else(|_| DEFAULT_MACOS_DEPLOYMENT_TARGET.to_string()),
+            features: None,
+            no_default_features: false,
+            no_macos: false,
+        };
+
+        let mut args = args;
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+            match arg.as_str() {
+                "--ios-deployment-target" => options.ios_deployment_target = value(&arg)?,
+                "--macos-deployment-target" => options.macos_deployment_target = value(&arg)?,
+                "--features" => options.features = Some(value(&arg)?),
+                "--no-default-features" => options.no_default_features = true,
+                "--no-macos" => options.no_macos = true,
+                _ => return Err(format!("unknown option: {}", arg)),
+            }
+        }
+        Ok(options)
+    }
+
+    fn platforms(&self) -> Vec<Platform> {
+        Platform::ALL
+            .into_iter()
+            .filter(|platform| !(self.no_macos && *platform == Platform::MacOs))
+            .collect()
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let result = match args.next().as_deref() {
+        Some("build-xcframework") => Options::parse(args).and_then(|o| build_xcframework(&o)),
+        Some("targets") => {
+            TARGETS.iter().for_each(|(triple, _)| println!("{}", triple));
+            Ok(())
+        }
+        _ => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+const USAGE: &str = "usage: cargo xtask <task>
+
+tasks:
+  build-xcframework   Build build/miden_swift_client.xcframework
+      --ios-deployment-target <version>    (default: $IOS_DEPLOYMENT_TARGET or 18.5)
+      --macos-deployment-target <version>  (default: $MACOS_DEPLOYMENT_TARGET or 15.0)
+      --features <features>                Crate features, e.g. testing
+      --no-default-features                Leave out faucets, scripts, swaps and mnemonic (see Cargo.toml)
+      --no-macos                           iOS and simulator slices only
+  targets             List the Rust targets to install";
+
+fn build_xcframework(options: &Options) -> Result<(), String> {
+    let root = project_root();
+    let build_dir = root.join("build");
+    let headers_dir = build_dir.join("headers");
+    let platforms = options.platforms();
+
+    println!("Generating {}.h", CRATE_NAME);
+    let header = root.join(format!("{}.h", CRATE_NAME));
+    generate_header(&root, &header)?;
+    reset_dir(&headers_dir)?;
+    copy(&header, &headers_dir.join(header.file_name().unwrap()))?;
+    let module_map = format!(
+        "module {} {{\n    header \"{}.h\"\n    export *\n}}\n",
+        MODULE_NAME, CRATE_NAME
+    );
+    fs::write(headers_dir.join("module.modulemap"), module_map).map_err(io_error)?;
+
+    let mut xcodebuild = Command::new("xcodebuild");
+    xcodebuild.arg("-create-xcframework");
+    for platform in platforms {
+        let libraries = TARGETS
+            .iter()
+            .filter(|(_, target_platform)| *target_platform == platform)
+            .map(|(triple, _)| build_target(&root, triple, platform, options))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let platform_dir = build_dir.join(platform.dir_name());
+        reset_dir(&platform_dir)?;
+        let library = platform_dir.join(format!("lib{}.a", CRATE_NAME));
+        if let [single] = libraries.as_slice() {
+            copy(single, &library)?;
+        } else {
+            println!("Merging {} slices", platform.dir_name());
+            run(Command::new("lipo").arg("-create").args(&libraries).arg("-output").arg(&library))?;
+        }
+        xcodebuild.arg("-library").arg(&library).arg("-headers").arg(&headers_dir);
+    }
+
+    // xcodebuild refuses to overwrite an existing framework
+    let output = build_dir.join(format!("{}.xcframework", CRATE_NAME));
+    if output.exists() {
+        fs::remove_dir_all(&output).map_err(io_error)?;
+    }
+    println!("Creating {}", output.display());
+    run(xcodebuild.arg("-output").arg(&output))
+}
+
+/// Generate the C header with the crate's cbindgen.toml
+fn generate_header(root: &Path, header: &Path) -> Result<(), String> {
+    let config = cbindgen::Config::from_file(root.join("cbindgen.toml"))?;
+    let bindings = cbindgen::Builder::new()
+        .with_crate(root)
+        .with_config(config)
+        .generate()
+        .map_err(|e| format!("header generation failed: {}", e))?;
+    bindings.write_to_file(header);
+    Ok(())
+}
+
+/// Build the static library for one target, returning its path
+fn build_target(
+    root: &Path,
+    triple: &str,
+    platform: Platform,
+    options: &Options,
+) -> Result<PathBuf, String> {
+    println!("Building {}", triple);
+    let mut cargo = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()));
+    cargo
+        .current_dir(root)
+        .args(["build", "--release", "--lib", "-p", CRATE_NAME, "--target", triple]);
+    if options.no_default_features {
+        cargo.arg("--no-default-features");
+    }
+    if let Some(features) = &options.features {
+        cargo.args(["--features", features]);
+    }
+
+    // Environment config overrides `[target.<triple>] rustflags` of .cargo/config.toml
+    let rustflags_var = format!(
+        "CARGO_TARGET_{}_RUSTFLAGS",
+        triple.to_uppercase().replace('-', "_")
+    );
+    cargo.env(rustflags_var, format!("-C link-arg={}", platform.min_version_arg(options)));
+    match platform {
+        Platform::Ios | Platform::IosSimulator => {
+            cargo.env("IPHONEOS_DEPLOYMENT_TARGET", &options.ios_deployment_target)
+        }
+        Platform::MacOs => cargo.env("MACOSX_DEPLOYMENT_TARGET", &options.macos_deployment_target),
+    };
+    run(&mut cargo)?;
+
+    Ok(root.join("target").join(triple).join("release").join(format!("lib{}.a", CRATE_NAME)))
+}
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let status = command
+        .status()
+        .map_err(|e| format!("failed to run {}: {}", program, e))?;
+    if !status.success() {
+        return Err(format!("{} failed ({})", program, status));
+    }
+    Ok(())
+}
+
+/// The crate root (parent of this xtask crate)
+fn project_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask lives inside the crate")
+        .to_path_buf()
+}
+
+fn reset_dir(dir: &Path) -> Result<(), String> {
+    if dir.exists() {
+        fs::remove_dir_all(dir).map_err(io_error)?;
+    }
+    fs::create_dir_all(dir).map_err(io_error)
+}
+
+fn copy(from: &Path, to: &Path) -> Result<(), String> {
+    fs::copy(from, to).map(|_| ()).map_err(io_error)
+}
+
+fn io_error(e: std::io::Error) -> String {
+    e.to_string()
+}